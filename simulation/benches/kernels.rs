@@ -0,0 +1,129 @@
+//! Benchmarks of the simulation's hot loops, to catch regressions when
+//! refactoring toward larger CDP books.
+//!
+//! ```bash
+//! cargo bench                          # everything
+//! cargo bench -- liquidation_round     # one group
+//! cargo bench -- --save-baseline main  # then compare a branch with --baseline main
+//! ```
+//!
+//! - `liquidation_round`: one round right after a 25% drop, by mechanism
+//!   and CDP count (building the simulation is not timed)
+//! - `price_path`: `generate_price_path` per model, and
+//!   `generate_price_paths` batches per path
+//! - `cascade`: a full run, book construction included, per scenario and
+//!   mechanism, and by CDP count
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use fair_simulation::cascade::{CascadeSimulationBuilder, LiquidationMechanism, PriceScenario, SimulationConfig};
+use fair_simulation::monte_carlo::{generate_price_path, generate_price_paths, PriceModel, PricePathConfig};
+
+const BOOK_SIZES: [usize; 3] = [1_000, 10_000, 100_000];
+const PATH_BATCH: usize = 1024;
+
+fn mechanism_label(mechanism: LiquidationMechanism) -> &'static str {
+    mechanism.info().short_name
+}
+
+fn liquidation_round(c: &mut Criterion) {
+    let mut group = c.benchmark_group("liquidation_round");
+    group.sample_size(20);
+    for mechanism in LiquidationMechanism::all() {
+        for cdps in BOOK_SIZES {
+            let config = SimulationConfig { num_cdps: cdps, seed: Some(1), ..Default::default() };
+            // Drawn at the opening price, then opened 25% lower
+            let book = CascadeSimulationBuilder::new()
+                .config(config.clone())
+                .build(&mut StdRng::seed_from_u64(1))
+                .expect("valid benchmark config")
+                .cdps()
+                .to_vec();
+            let crashed_price = config.initial_eth_price * 0.75;
+            group.bench_with_input(BenchmarkId::new(mechanism_label(mechanism), cdps), &config, |b, config| {
+                b.iter_batched(
+                    || {
+                        let mut rng = StdRng::seed_from_u64(1);
+                        let sim = CascadeSimulationBuilder::new()
+                            .config(config.clone())
+                            .mechanism(mechanism)
+                            .cdps(book.clone())
+                            .eth_price(crashed_price)
+                            .build(&mut rng)
+                            .expect("valid benchmark config");
+                        (sim, rng)
+                    },
+                    |(mut sim, mut rng)| black_box(sim.liquidation_round(&mut rng)),
+                    BatchSize::LargeInput,
+                );
+            });
+        }
+    }
+    group.finish();
+}
+
+fn price_path(c: &mut Criterion) {
+    let mut group = c.benchmark_group("price_path");
+    let mut rng = StdRng::seed_from_u64(2);
+    for model in PriceModel::all() {
+        let config = PricePathConfig { model, ..Default::default() };
+        group.bench_with_input(BenchmarkId::new("single", model.name()), &config, |b, config| {
+            b.iter(|| black_box(generate_price_path(config, &mut rng)));
+        });
+    }
+    group.throughput(Throughput::Elements(PATH_BATCH as u64));
+    for model in [PriceModel::GBM, PriceModel::JumpDiffusion] {
+        let config = PricePathConfig { model, ..Default::default() };
+        group.bench_with_input(BenchmarkId::new("batch", model.name()), &config, |b, config| {
+            b.iter(|| black_box(generate_price_paths(config, PATH_BATCH, &mut rng)));
+        });
+    }
+    group.finish();
+}
+
+fn cascade(c: &mut Criterion) {
+    let mut group = c.benchmark_group("cascade");
+    let mut rng = StdRng::seed_from_u64(3);
+    let config = SimulationConfig { seed: Some(3), ..Default::default() };
+    for scenario in PriceScenario::all() {
+        for mechanism in LiquidationMechanism::all() {
+            let id = BenchmarkId::new(scenario.name(), mechanism_label(mechanism));
+            group.bench_function(id, |b| {
+                b.iter(|| {
+                    let mut sim = CascadeSimulationBuilder::new()
+                        .config(config.clone())
+                        .mechanism(mechanism)
+                        .scenario(scenario)
+                        .build(&mut rng)
+                        .expect("valid benchmark config");
+                    black_box(sim.run(&mut rng))
+                });
+            });
+        }
+    }
+
+    group.sample_size(10);
+    for cdps in BOOK_SIZES {
+        let config = SimulationConfig { num_cdps: cdps, ..config.clone() };
+        group.throughput(Throughput::Elements(cdps as u64));
+        group.bench_with_input(BenchmarkId::new("cdps", cdps), &config, |b, config| {
+            b.iter(|| {
+                let mut sim = CascadeSimulationBuilder::new()
+                    .config(config.clone())
+                    .mechanism(LiquidationMechanism::keeper_pool())
+                    .scenario(PriceScenario::FlashCrash)
+                    .build(&mut rng)
+                    .expect("valid benchmark config");
+                black_box(sim.run(&mut rng))
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, liquidation_round, price_path, cascade);
+criterion_main!(benches);
@@ -0,0 +1,24 @@
+//! Embeds the git commit the binary was built from, for run manifests
+//! (`manifest::RunManifest`). Builds outside a git checkout embed nothing.
+
+use std::process::Command;
+
+fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().ok()?;
+    output.status.success().then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=src");
+    if let Some(git_dir) = git(&["rev-parse", "--absolute-git-dir"]) {
+        println!("cargo:rerun-if-changed={}/HEAD", git_dir);
+        println!("cargo:rerun-if-changed={}/index", git_dir);
+    }
+
+    if let Some(hash) = git(&["rev-parse", "HEAD"]) {
+        println!("cargo:rustc-env=FAIR_SIM_GIT_HASH={}", hash);
+    }
+    if let Some(status) = git(&["status", "--porcelain", "--untracked-files=no", "."]) {
+        println!("cargo:rustc-env=FAIR_SIM_GIT_DIRTY={}", !status.is_empty());
+    }
+}
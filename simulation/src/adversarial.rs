@@ -0,0 +1,391 @@
+//! Adversarial Mechanism Fuzzing
+//!
+//! Randomly composes adversarial keeper populations and searches for mixes
+//! under which Fair's keeper pool ends a cascade with more bad debt than
+//! winner-takes-all liquidation. Each hit is shrunk to a minimal mix that
+//! still reproduces the gap.
+//!
+//! ## Adversaries
+//! - Colluders: honest keepers replaced by a cartel that withholds until the
+//!   penalty clears its profit bar
+//! - Sybils: extra identities of one operator, who executes once per block
+//! - Griefers: join pools for the share but never execute when selected
+//! - Manipulators: dump spot ETH for the first blocks of the cascade
+//!
+//! ## Method
+//! - Both mechanisms see the same seeds per run (common random numbers), so
+//!   the bad debt gap reflects the mechanism rather than sampling noise
+//! - A mix is a finding when mean pool bad debt exceeds Traditional's by
+//!   more than `min_gap`
+//! - Minimization greedily zeroes, halves, or decrements one adversary at a
+//!   time, keeping any step that still clears `min_gap`
+
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::cascade::{
+    CascadeSimulationBuilder, Keeper, KeeperBehavior, LiquidationMechanism, PriceScenario, SimulationConfig,
+};
+use crate::error::{Error, Result};
+
+pub const MANIPULATION_BLOCKS: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct AdversaryMix {
+    pub colluders: usize,       // Honest keepers turned cartel members
+    pub cartel_min_profit: f64, // USD penalty the cartel waits for
+    pub sybils: usize,          // Extra identities of a single operator
+    pub griefers: usize,
+    pub manipulation_eth: f64,  // Spot ETH dumped per block for MANIPULATION_BLOCKS blocks
+}
+
+impl AdversaryMix {
+    pub fn label(&self) -> String {
+        let mut parts = Vec::new();
+        if self.colluders > 0 {
+            parts.push(format!("{} colluders (>${:.0})", self.colluders, self.cartel_min_profit));
+        }
+        if self.sybils > 0 {
+            parts.push(format!("{} sybils", self.sybils));
+        }
+        if self.griefers > 0 {
+            parts.push(format!("{} griefers", self.griefers));
+        }
+        if self.manipulation_eth > 0.0 {
+            parts.push(format!("dump {:.0} ETH/block", self.manipulation_eth));
+        }
+        if parts.is_empty() {
+            "no adversaries".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+
+    fn sample(fuzz: &FuzzConfig, num_keepers: usize, rng: &mut impl Rng) -> Self {
+        // Each adversary type is present half the time to keep mixes sparse
+        let mut present = || rng.gen_bool(0.5);
+        let (c, s, g, m) = (present(), present(), present(), present());
+        Self {
+            colluders: if c { rng.gen_range(1..=num_keepers) } else { 0 },
+            cartel_min_profit: if c { rng.gen_range(0.0..fuzz.max_cartel_min_profit).round() } else { 0.0 },
+            sybils: if s { rng.gen_range(1..=fuzz.max_sybils.max(1)) } else { 0 },
+            griefers: if g { rng.gen_range(1..=fuzz.max_griefers.max(1)) } else { 0 },
+            manipulation_eth: if m { rng.gen_range(0.0..fuzz.max_manipulation_eth).round() } else { 0.0 },
+        }
+    }
+
+    /// Strictly smaller neighbours, most aggressive first.
+    fn shrink_candidates(&self) -> Vec<Self> {
+        let mut out = Vec::new();
+        let mut push = |candidate: Self| {
+            if candidate != *self && !out.contains(&candidate) {
+                out.push(candidate);
+            }
+        };
+        for n in shrink_count(self.colluders) {
+            let cartel_min_profit = if n == 0 { 0.0 } else { self.cartel_min_profit };
+            push(Self { colluders: n, cartel_min_profit, ..*self });
+        }
+        for n in shrink_count(self.sybils) {
+            push(Self { sybils: n, ..*self });
+        }
+        for n in shrink_count(self.griefers) {
+            push(Self { griefers: n, ..*self });
+        }
+        for eth in shrink_amount(self.manipulation_eth, 1.0) {
+            push(Self { manipulation_eth: eth, ..*self });
+        }
+        if self.colluders > 0 {
+            for profit in shrink_amount(self.cartel_min_profit, 10.0) {
+                push(Self { cartel_min_profit: profit, ..*self });
+            }
+        }
+        out
+    }
+
+    /// Honest population drawn from `rng`; extra identities from
+    /// `adversary_rng` so adding them leaves the rest of the run unchanged.
+    pub(crate) fn keepers(&self, config: &SimulationConfig, rng: &mut impl Rng, adversary_rng: &mut impl Rng) -> Vec<Keeper> {
+        let mut keepers: Vec<Keeper> = (0..config.keepers.count)
+            .map(|id| {
+                let keeper = config.keepers.keeper(id, rng);
+                if id < self.colluders {
+                    keeper.with_behavior(KeeperBehavior::Cartel { min_profit: self.cartel_min_profit })
+                } else {
+                    keeper
+                }
+            })
+            .collect();
+        let extra = (0..self.sybils)
+            .map(|_| KeeperBehavior::Sybil)
+            .chain((0..self.griefers).map(|_| KeeperBehavior::Griefer));
+        for behavior in extra {
+            let id = keepers.len();
+            keepers.push(config.keepers.keeper(id, adversary_rng).with_behavior(behavior));
+        }
+        keepers
+    }
+}
+
+fn shrink_count(n: usize) -> Vec<usize> {
+    if n == 0 {
+        Vec::new()
+    } else {
+        vec![0, n / 2, n - 1]
+    }
+}
+
+fn shrink_amount(x: f64, granularity: f64) -> Vec<f64> {
+    if x <= 0.0 {
+        Vec::new()
+    } else if x < 2.0 * granularity {
+        vec![0.0]
+    } else {
+        vec![0.0, (x / 2.0 / granularity).floor() * granularity]
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FuzzConfig {
+    pub iterations: usize,          // Random mixes to try
+    pub runs: usize,                // Paired runs per evaluation
+    pub min_gap: f64,               // USD of extra pool bad debt that counts as a finding
+    pub max_sybils: usize,
+    pub max_griefers: usize,
+    pub max_cartel_min_profit: f64,
+    pub max_manipulation_eth: f64,
+}
+
+impl Default for FuzzConfig {
+    fn default() -> Self {
+        Self {
+            iterations: 100,
+            runs: 20,
+            min_gap: 1000.0,
+            max_sybils: 50,
+            max_griefers: 20,
+            max_cartel_min_profit: 2000.0,
+            max_manipulation_eth: 200.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Evaluation {
+    pub mix: AdversaryMix,
+    pub scenario: PriceScenario,
+    pub pool_bad_debt: f64,        // Mean over paired runs
+    pub traditional_bad_debt: f64,
+}
+
+impl Evaluation {
+    /// Extra bad debt the keeper pool ends with; positive means it underperforms.
+    pub fn gap(&self) -> f64 {
+        self.pool_bad_debt - self.traditional_bad_debt
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Finding {
+    pub original: Evaluation,
+    pub minimized: Evaluation,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FuzzReport {
+    pub iterations: usize,
+    pub runs: usize,
+    pub min_gap: f64,
+    pub evaluations: usize,
+    pub findings: Vec<Finding>, // Largest minimized gap first
+}
+
+impl FuzzReport {
+    pub fn print(&self) {
+        println!("  Mixes tried:             {}", self.iterations);
+        println!("  Evaluations:             {} ({} paired runs each)", self.evaluations, self.runs);
+        println!("  Findings:                {}", self.findings.len());
+        for (i, f) in self.findings.iter().enumerate() {
+            let m = &f.minimized;
+            println!();
+            println!("  #{} {}", i + 1, m.scenario.name());
+            println!("    Minimized mix:         {}", m.mix.label());
+            println!("    Found as:              {}", f.original.mix.label());
+            println!(
+                "    Bad debt (pool/trad):  ${:.0} / ${:.0} (gap ${:.0})",
+                m.pool_bad_debt,
+                m.traditional_bad_debt,
+                m.gap()
+            );
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+struct Fuzzer<'a> {
+    fuzz: &'a FuzzConfig,
+    config: &'a SimulationConfig,
+    seed: u64, // Shared by every evaluation so mixes are compared on the same draws
+    evaluations: usize,
+}
+
+impl Fuzzer<'_> {
+    fn evaluate(&mut self, mix: AdversaryMix, scenario: PriceScenario) -> Result<Evaluation> {
+        self.evaluations += 1;
+        let mut totals = [0.0; 2];
+        let mechanisms = [LiquidationMechanism::keeper_pool(), LiquidationMechanism::Traditional];
+
+        for run in 0..self.fuzz.runs {
+            for (total, &mechanism) in totals.iter_mut().zip(&mechanisms) {
+                let run_seed = self.seed.wrapping_add(run as u64);
+                let mut rng = self.config.run_rng(run_seed);
+                let mut adversary_rng = self.config.run_rng(!run_seed);
+                let keepers = mix.keepers(self.config, &mut rng, &mut adversary_rng);
+                let mut sim = CascadeSimulationBuilder::new()
+                    .config(self.config.clone())
+                    .mechanism(mechanism)
+                    .scenario(scenario)
+                    .keepers(keepers)
+                    .manipulation(mix.manipulation_eth, MANIPULATION_BLOCKS)
+                    .build(&mut rng)?;
+                *total += sim.run(&mut rng).bad_debt;
+            }
+        }
+
+        let n = self.fuzz.runs as f64;
+        Ok(Evaluation {
+            mix,
+            scenario,
+            pool_bad_debt: totals[0] / n,
+            traditional_bad_debt: totals[1] / n,
+        })
+    }
+
+    fn minimize(&mut self, found: &Evaluation) -> Result<Evaluation> {
+        let mut best = found.clone();
+        'shrink: loop {
+            for candidate in best.mix.shrink_candidates() {
+                let evaluation = self.evaluate(candidate, best.scenario)?;
+                if evaluation.gap() > self.fuzz.min_gap {
+                    best = evaluation;
+                    continue 'shrink;
+                }
+            }
+            return Ok(best);
+        }
+    }
+}
+
+/// Searches `fuzz.iterations` random adversary mixes across `scenarios`.
+pub fn fuzz_mechanisms(
+    fuzz: &FuzzConfig,
+    scenarios: &[PriceScenario],
+    config: &SimulationConfig,
+) -> Result<FuzzReport> {
+    config.validate()?;
+    if fuzz.runs == 0 || scenarios.is_empty() {
+        return Err(Error::Invalid("fuzzing needs at least one run and one scenario".to_string()));
+    }
+
+    let mut rng = config.rng();
+    let mut fuzzer = Fuzzer {
+        fuzz,
+        config,
+        seed: rng.gen(),
+        evaluations: 0,
+    };
+    let mut findings: Vec<Finding> = Vec::new();
+
+    for _ in 0..fuzz.iterations {
+        let mix = AdversaryMix::sample(fuzz, config.keepers.count, &mut rng);
+        let scenario = scenarios[rng.gen_range(0..scenarios.len())];
+        let evaluation = fuzzer.evaluate(mix, scenario)?;
+        if evaluation.gap() <= fuzz.min_gap {
+            continue;
+        }
+
+        let minimized = fuzzer.minimize(&evaluation)?;
+        let duplicate = findings
+            .iter()
+            .any(|f| f.minimized.mix == minimized.mix && f.minimized.scenario == minimized.scenario);
+        if !duplicate {
+            findings.push(Finding {
+                original: evaluation,
+                minimized,
+            });
+        }
+    }
+
+    findings.sort_by(|a, b| b.minimized.gap().partial_cmp(&a.minimized.gap()).unwrap());
+    Ok(FuzzReport {
+        iterations: fuzz.iterations,
+        runs: fuzz.runs,
+        min_gap: fuzz.min_gap,
+        evaluations: fuzzer.evaluations,
+        findings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_griefers_only_affect_the_pool() {
+        let config = SimulationConfig::default();
+        let fuzz = FuzzConfig { runs: 3, ..Default::default() };
+        let mut fuzzer = Fuzzer { fuzz: &fuzz, config: &config, seed: 5, evaluations: 0 };
+
+        let clean = fuzzer.evaluate(AdversaryMix::default(), PriceScenario::FlashCrash).unwrap();
+        let griefed = fuzzer
+            .evaluate(AdversaryMix { griefers: 200, ..Default::default() }, PriceScenario::FlashCrash)
+            .unwrap();
+
+        // Stalled rounds delay pool liquidations (and their price impact)
+        assert!((griefed.pool_bad_debt - clean.pool_bad_debt).abs() > 1.0);
+        // Winner-takes-all ignores griefers entirely
+        assert!((griefed.traditional_bad_debt - clean.traditional_bad_debt).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_minimized_findings_still_underperform() {
+        let fuzz = FuzzConfig {
+            iterations: 6,
+            runs: 3,
+            min_gap: 0.0,
+            max_griefers: 200,
+            ..Default::default()
+        };
+        let config = SimulationConfig { seed: Some(11), ..Default::default() };
+        let report = fuzz_mechanisms(&fuzz, &[PriceScenario::FlashCrash], &config).unwrap();
+
+        assert!(!report.findings.is_empty());
+        for finding in &report.findings {
+            assert!(finding.minimized.gap() > 0.0);
+            assert!(finding.minimized.mix.griefers <= finding.original.mix.griefers);
+            assert!(finding.minimized.mix.sybils <= finding.original.mix.sybils);
+        }
+        assert!(report.evaluations >= fuzz.iterations);
+    }
+
+    #[test]
+    fn test_shrink_candidates_are_smaller() {
+        let mix = AdversaryMix {
+            colluders: 4,
+            cartel_min_profit: 500.0,
+            sybils: 0,
+            griefers: 1,
+            manipulation_eth: 1.5,
+        };
+        let candidates = mix.shrink_candidates();
+
+        assert!(candidates.contains(&AdversaryMix { colluders: 0, cartel_min_profit: 0.0, ..mix }));
+        assert!(candidates.contains(&AdversaryMix { griefers: 0, ..mix }));
+        assert!(candidates.contains(&AdversaryMix { manipulation_eth: 0.0, ..mix }));
+        assert!(candidates.iter().all(|c| c.sybils == 0));
+        assert!(!candidates.contains(&mix));
+    }
+}
@@ -0,0 +1,305 @@
+//! Alerting Thresholds for Automated Risk Gates
+//!
+//! Parses assertions such as `insolvency_prob<0.001` and checks them against
+//! simulated results, so a binary can exit nonzero when risk exceeds a
+//! threshold and gate parameter-change proposals in CI.
+//!
+//! ## Syntax
+//! `<metric><op><value>` where `op` is one of `<`, `<=`, `>`, `>=`.
+//! Pass `--assert <expr>` (repeatable) to `fair-sim monte-carlo`, `fair-sim monitor`
+//! or `fair-sim reaggregate` (which also takes `var_<level>`, see `tail_metrics`).
+//!
+//! ## Exit Codes
+//! - `0`: all assertions hold
+//! - `1`: at least one assertion was violated
+//! - `2`: usage or input error
+
+use std::fmt;
+
+use crate::error::{Error, Result};
+use crate::monte_carlo::{MonteCarloResult, TailLevel, TailRisk, TailThresholds};
+use crate::snapshot::SnapshotRiskReport;
+
+pub const EXIT_OK: i32 = 0;
+pub const EXIT_VIOLATION: i32 = 1;
+pub const EXIT_USAGE: i32 = 2;
+
+pub const MONTE_CARLO_METRICS: &[&str] = &[
+    "insolvency_prob",
+    "bad_debt_prob",
+    "mean_bad_debt",
+    "max_bad_debt",
+    "var_95",
+    "var_99",
+    "var_999",
+    "cvar_95",
+    "cvar_99",
+    "fund_exhaustion_prob",
+];
+
+/// Metrics of results re-aggregated at `thresholds`: those of
+/// `MONTE_CARLO_METRICS` without fixed levels, plus `var_<level>` and
+/// `cvar_<level>` per level (`var_975` for 97.5%).
+pub fn tail_metrics(thresholds: &TailThresholds) -> Vec<String> {
+    let mut metrics: Vec<String> =
+        ["insolvency_prob", "bad_debt_prob", "mean_bad_debt", "max_bad_debt"].map(String::from).to_vec();
+    for &level in &thresholds.levels {
+        metrics.push(format!("var_{}", TailLevel::suffix(level)));
+        metrics.push(format!("cvar_{}", TailLevel::suffix(level)));
+    }
+    metrics
+}
+
+pub const SNAPSHOT_METRICS: &[&str] = &["buffer_exhaustion_prob", "mean_uncovered_bad_debt"];
+
+/// Anything that exposes named scalar metrics an assertion can check.
+pub trait MetricSource {
+    fn metric(&self, name: &str) -> Option<f64>;
+}
+
+impl MetricSource for MonteCarloResult {
+    fn metric(&self, name: &str) -> Option<f64> {
+        match name {
+            "insolvency_prob" => Some(self.insolvency_probability),
+            "bad_debt_prob" => Some(self.bad_debt_probability),
+            "mean_bad_debt" => Some(self.mean_bad_debt),
+            "max_bad_debt" => Some(self.max_bad_debt),
+            "var_95" => Some(self.var_95),
+            "var_99" => Some(self.var_99),
+            "var_999" => Some(self.var_999),
+            "cvar_95" => Some(self.cvar_95),
+            "cvar_99" => Some(self.cvar_99),
+            "fund_exhaustion_prob" => self.fund_exhaustion_probability,
+            _ => None,
+        }
+    }
+}
+
+impl MetricSource for TailRisk {
+    fn metric(&self, name: &str) -> Option<f64> {
+        match name {
+            "insolvency_prob" => Some(self.insolvency_probability),
+            "bad_debt_prob" => Some(self.bad_debt_probability),
+            "mean_bad_debt" => Some(self.mean_bad_debt),
+            "max_bad_debt" => Some(self.max_bad_debt),
+            _ => {
+                let (cvar, suffix) = match name.strip_prefix("cvar_") {
+                    Some(suffix) => (true, suffix),
+                    None => (false, name.strip_prefix("var_")?),
+                };
+                let level = self.levels.iter().find(|l| TailLevel::suffix(l.level) == suffix)?;
+                Some(if cvar { level.cvar } else { level.var })
+            }
+        }
+    }
+}
+
+impl MetricSource for SnapshotRiskReport {
+    fn metric(&self, name: &str) -> Option<f64> {
+        match name {
+            "buffer_exhaustion_prob" => Some(self.buffer_exhaustion_probability),
+            "mean_uncovered_bad_debt" => Some(self.mean_uncovered_bad_debt),
+            _ => self.monte_carlo.metric(name),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Comparison {
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+impl Comparison {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Self::Less => "<",
+            Self::LessOrEqual => "<=",
+            Self::Greater => ">",
+            Self::GreaterOrEqual => ">=",
+        }
+    }
+
+    fn holds(&self, lhs: f64, rhs: f64) -> bool {
+        match self {
+            Self::Less => lhs < rhs,
+            Self::LessOrEqual => lhs <= rhs,
+            Self::Greater => lhs > rhs,
+            Self::GreaterOrEqual => lhs >= rhs,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Assertion {
+    pub metric: String,
+    pub comparison: Comparison,
+    pub threshold: f64,
+}
+
+impl Assertion {
+    pub fn parse(expr: &str) -> Result<Self> {
+        let expr = expr.trim();
+        let (idx, comparison, op_len) = expr
+            .find(['<', '>'])
+            .map(|i| {
+                let rest = &expr[i..];
+                match (rest.starts_with('<'), rest[1..].starts_with('=')) {
+                    (true, true) => (i, Comparison::LessOrEqual, 2),
+                    (true, false) => (i, Comparison::Less, 1),
+                    (false, true) => (i, Comparison::GreaterOrEqual, 2),
+                    (false, false) => (i, Comparison::Greater, 1),
+                }
+            })
+            .ok_or_else(|| Error::Invalid(format!("assertion '{}' has no comparison", expr)))?;
+
+        let metric = expr[..idx].trim().to_string();
+        if metric.is_empty() {
+            return Err(Error::Invalid(format!("assertion '{}' has no metric", expr)));
+        }
+        let threshold = expr[idx + op_len..]
+            .trim()
+            .parse::<f64>()
+            .map_err(|_| Error::Invalid(format!("assertion '{}' has a non-numeric threshold", expr)))?;
+
+        Ok(Self {
+            metric,
+            comparison,
+            threshold,
+        })
+    }
+
+    /// Returns `Ok(None)` when the assertion holds, `Ok(Some(value))` with the
+    /// offending value when it is violated.
+    pub fn check(&self, source: &impl MetricSource) -> Result<Option<f64>> {
+        let value = source
+            .metric(&self.metric)
+            .ok_or_else(|| Error::Invalid(format!("unknown metric '{}'", self.metric)))?;
+        if self.comparison.holds(value, self.threshold) {
+            Ok(None)
+        } else {
+            Ok(Some(value))
+        }
+    }
+}
+
+impl fmt::Display for Assertion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}{}", self.metric, self.comparison.symbol(), self.threshold)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Violation {
+    pub context: String,
+    pub assertion: Assertion,
+    pub value: f64,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: expected {} but {} = {}",
+            self.context, self.assertion, self.assertion.metric, self.value
+        )
+    }
+}
+
+/// Checks every assertion against `source`, labelling failures with `context`.
+pub fn check_all(
+    assertions: &[Assertion],
+    source: &impl MetricSource,
+    context: &str,
+) -> Result<Vec<Violation>> {
+    let mut violations = Vec::new();
+    for assertion in assertions {
+        if let Some(value) = assertion.check(source)? {
+            violations.push(Violation {
+                context: context.to_string(),
+                assertion: assertion.clone(),
+                value,
+            });
+        }
+    }
+    Ok(violations)
+}
+
+/// Rejects assertions on metrics not in `known`, so typos fail before a long run.
+pub fn validate_metrics(assertions: &[Assertion], known: &[&[&str]]) -> Result<()> {
+    for assertion in assertions {
+        if !known.iter().any(|set| set.contains(&assertion.metric.as_str())) {
+            return Err(Error::Invalid(format!("unknown metric '{}'", assertion.metric)));
+        }
+    }
+    Ok(())
+}
+
+/// Prints the gate outcome and returns the process exit code.
+pub fn report(assertions: &[Assertion], violations: &[Violation]) -> i32 {
+    if assertions.is_empty() {
+        return EXIT_OK;
+    }
+
+    println!("=======================================================");
+    println!("  Risk Gate");
+    println!("=======================================================");
+    println!();
+    for assertion in assertions {
+        println!("  assert {}", assertion);
+    }
+    println!();
+
+    if violations.is_empty() {
+        println!("  PASS: all assertions hold");
+        EXIT_OK
+    } else {
+        for violation in violations {
+            println!("  FAIL: {}", violation);
+        }
+        EXIT_VIOLATION
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Fixed(f64);
+
+    impl MetricSource for Fixed {
+        fn metric(&self, name: &str) -> Option<f64> {
+            (name == "insolvency_prob").then_some(self.0)
+        }
+    }
+
+    #[test]
+    fn test_parse_assertion() {
+        let a = Assertion::parse("insolvency_prob<0.001").unwrap();
+        assert_eq!(a.metric, "insolvency_prob");
+        assert_eq!(a.comparison, Comparison::Less);
+        assert!((a.threshold - 0.001).abs() < 1e-12);
+
+        let b = Assertion::parse("var_99 >= 5000").unwrap();
+        assert_eq!(b.comparison, Comparison::GreaterOrEqual);
+
+        assert!(Assertion::parse("insolvency_prob").is_err());
+        assert!(Assertion::parse("<0.1").is_err());
+        assert!(Assertion::parse("insolvency_prob<abc").is_err());
+    }
+
+    #[test]
+    fn test_check_assertions() {
+        let assertions = vec![Assertion::parse("insolvency_prob<0.01").unwrap()];
+
+        assert!(check_all(&assertions, &Fixed(0.001), "ok").unwrap().is_empty());
+        assert_eq!(check_all(&assertions, &Fixed(0.05), "bad").unwrap().len(), 1);
+
+        let unknown = vec![Assertion::parse("nope<1").unwrap()];
+        assert!(check_all(&unknown, &Fixed(0.0), "x").is_err());
+        assert!(validate_metrics(&unknown, &[MONTE_CARLO_METRICS]).is_err());
+        assert!(validate_metrics(&assertions, &[MONTE_CARLO_METRICS]).is_ok());
+    }
+}
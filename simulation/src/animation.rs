@@ -0,0 +1,366 @@
+//! Cascade Animation Frames
+//!
+//! Per-block frames of a few cascade runs, compact enough to ship to a web
+//! page that animates the cascade: the oracle price, which CDPs are
+//! liquidatable, which get liquidated and which are left waiting in the
+//! queue, and how the open book's collateral ratios are spread. Every
+//! mechanism replays the same seeds, so the runs can be played side by side.
+//!
+//! For readers without the web page, `to_svg` renders a run as one
+//! self-contained animated SVG (SMIL, no scripts): the price path with a
+//! marker walking along it, next to the book's health histogram emptying
+//! and sliding toward the liquidation line block by block.
+//!
+//! ## Format
+//!
+//! One `Animation` per scenario; CDPs are indices into the book, riskiest
+//! first, and empty sets are left out of a frame:
+//!
+//! ```json
+//! {"scenario":"FlashCrash","cdps":500,"initial_price":2000.0,"runs":[
+//!   {"mechanism":"Traditional (Winner-Takes-All)","seed":42,"bad_debt":0.0,"frames":[
+//!     {"block":0,"price":1400.0,"liquidatable":[17,3,88],"liquidated":[17,3],"queue":[88],"health":[0,3,41,210,201,42]},
+//!     {"block":1,"price":1391.52,"liquidatable":[88],"liquidated":[88]},
+//!     {"block":2,"price":1393.07}]}]}
+//! ```
+//!
+//! `health` counts open CDPs per `HEALTH_BANDS` collateral-ratio band:
+//! underwater, then up to 1.2, 1.5, 2 and 3, then the rest.
+//!
+//! Seeds are drawn from `config.seed` the way a campaign draws its per-run
+//! seeds, so run `i` here is run `i` of `fair-sim cascade` with the same
+//! seed and config.
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::cascade::{replay_observed, LiquidationMechanism, LiveState, PriceScenario, SimulationConfig};
+
+/// Upper collateral-ratio bounds of the health bands; a last band holds
+/// everything above.
+pub const HEALTH_BANDS: [f64; 5] = [1.0, 1.2, 1.5, 2.0, 3.0];
+
+/// Playback speed of `to_svg`.
+pub const SECONDS_PER_FRAME: f64 = 0.25;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Frame {
+    pub block: usize,
+    pub price: f64, // Oracle price at the end of the block, to the cent
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub liquidatable: Vec<usize>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub liquidated: Vec<usize>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub queue: Vec<usize>, // Liquidatable but not liquidated this block
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub health: Vec<usize>, // Open CDPs per `HEALTH_BANDS` band
+}
+
+impl Frame {
+    pub fn from_state(state: &LiveState) -> Self {
+        Self {
+            block: state.event.block,
+            price: (state.event.price_end * 100.0).round() / 100.0,
+            liquidatable: state.liquidatable_cdps.clone(),
+            liquidated: state.liquidated_cdps.clone(),
+            queue: state
+                .liquidatable_cdps
+                .iter()
+                .copied()
+                .filter(|cdp| !state.liquidated_cdps.contains(cdp))
+                .collect(),
+            health: health_histogram(&state.collateral_ratios),
+        }
+    }
+}
+
+/// Counts `ratios` per `HEALTH_BANDS` band.
+pub fn health_histogram(ratios: &[f64]) -> Vec<usize> {
+    let mut counts = vec![0; HEALTH_BANDS.len() + 1];
+    for &ratio in ratios {
+        counts[HEALTH_BANDS.iter().take_while(|&&upper| ratio >= upper).count()] += 1;
+    }
+    counts
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnimatedRun {
+    pub mechanism: String,
+    pub seed: u64,
+    pub bad_debt: f64,
+    pub frames: Vec<Frame>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Animation {
+    pub scenario: PriceScenario,
+    pub cdps: usize,
+    pub initial_price: f64,
+    #[serde(default)]
+    pub min_collateral_ratio: f64,
+    pub runs: Vec<AnimatedRun>, // Each seed under every mechanism, seed-major
+}
+
+/// The first `runs` per-run seeds a campaign on `config` would use.
+pub fn campaign_seeds(runs: usize, config: &SimulationConfig) -> Vec<u64> {
+    let mut rng = config.rng();
+    (0..runs).map(|_| rng.gen()).collect()
+}
+
+/// Replays one run from its seed, recording a frame per block.
+pub fn record(
+    mechanism: LiquidationMechanism,
+    scenario: PriceScenario,
+    config: &SimulationConfig,
+    seed: u64,
+) -> AnimatedRun {
+    let mut frames = Vec::new();
+    let result = replay_observed(mechanism, scenario, config, seed, |state| frames.push(Frame::from_state(state)));
+    AnimatedRun { mechanism: mechanism.name(), seed, bad_debt: result.bad_debt, frames }
+}
+
+/// Records every seed in `seeds` under each of `mechanisms`.
+pub fn animate(
+    mechanisms: &[LiquidationMechanism],
+    scenario: PriceScenario,
+    seeds: &[u64],
+    config: &SimulationConfig,
+) -> Animation {
+    let runs = seeds
+        .iter()
+        .flat_map(|&seed| mechanisms.iter().map(move |&mechanism| record(mechanism, scenario, config, seed)))
+        .collect();
+    Animation {
+        scenario,
+        cdps: config.num_cdps,
+        initial_price: config.initial_eth_price,
+        min_collateral_ratio: config.min_collateral_ratio,
+        runs,
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// Shows its group only during frame `index` of `frames`, looping.
+fn frame_visibility(index: usize, frames: usize) -> String {
+    if frames == 1 {
+        return String::new();
+    }
+    let (start, end) = (index as f64 / frames as f64, (index + 1) as f64 / frames as f64);
+    let (values, key_times) = match index {
+        0 => ("visible;hidden".to_string(), format!("0;{:.6}", end)),
+        _ if index + 1 == frames => ("hidden;visible".to_string(), format!("0;{:.6}", start)),
+        _ => ("hidden;visible;hidden".to_string(), format!("0;{:.6};{:.6}", start, end)),
+    };
+    format!(
+        r#"<animate attributeName="visibility" values="{}" keyTimes="{}" dur="{:.2}s" calcMode="discrete" repeatCount="indefinite"/>"#,
+        values,
+        key_times,
+        frames as f64 * SECONDS_PER_FRAME
+    )
+}
+
+/// Renders `run` of `animation` as a self-contained animated SVG.
+pub fn to_svg(animation: &Animation, run: &AnimatedRun) -> String {
+    const WIDTH: f64 = 960.0;
+    const HEIGHT: f64 = 540.0;
+    let (chart_left, chart_right, top, bottom) = (70.0, 520.0, 90.0, 450.0);
+    let (bars_left, bars_right) = (580.0, 930.0);
+    let frames = run.frames.len().max(1);
+
+    let prices: Vec<f64> = std::iter::once(animation.initial_price).chain(run.frames.iter().map(|f| f.price)).collect();
+    let low = prices.iter().copied().fold(f64::INFINITY, f64::min);
+    let high = prices.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let span = if high > low { high - low } else { 1.0 };
+    let x = |i: usize| chart_left + (chart_right - chart_left) * i as f64 / (prices.len() - 1).max(1) as f64;
+    let y = |price: f64| bottom - (bottom - top) * (price - low) / span;
+
+    let mut svg = format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="sans-serif" font-size="13">
+<rect width="{w}" height="{h}" fill="white"/>
+<text x="{cx}" y="30" text-anchor="middle" font-size="18" font-weight="bold">{title}</text>
+<text x="{cx}" y="52" text-anchor="middle" fill="#555">{mechanism} · seed {seed} · final bad debt ${bad_debt:.0}</text>
+"##,
+        w = WIDTH,
+        h = HEIGHT,
+        cx = WIDTH / 2.0,
+        title = escape(animation.scenario.name()),
+        mechanism = escape(&run.mechanism),
+        seed = run.seed,
+        bad_debt = run.bad_debt,
+    );
+
+    // Price panel: the whole path, drawn once
+    let path: Vec<String> = prices.iter().enumerate().map(|(i, &p)| format!("{:.1},{:.1}", x(i), y(p))).collect();
+    svg.push_str(&format!(
+        r##"<line x1="{l}" y1="{b}" x2="{r}" y2="{b}" stroke="#999"/><line x1="{l}" y1="{t}" x2="{l}" y2="{b}" stroke="#999"/>
+<text x="{lt}" y="{t}" text-anchor="end">${high:.0}</text><text x="{lt}" y="{b}" text-anchor="end">${low:.0}</text>
+<text x="{mid}" y="{lb}" text-anchor="middle">Block</text><text x="{mid}" y="{tt}" text-anchor="middle" font-weight="bold">ETH price</text>
+<polyline points="{points}" fill="none" stroke="#1f77b4" stroke-width="2"/>
+"##,
+        l = chart_left,
+        r = chart_right,
+        t = top,
+        b = bottom,
+        lt = chart_left - 6.0,
+        lb = bottom + 24.0,
+        tt = top - 12.0,
+        mid = (chart_left + chart_right) / 2.0,
+        high = high,
+        low = low,
+        points = path.join(" "),
+    ));
+
+    // Health panel: band labels, drawn once
+    let bands = HEALTH_BANDS.len() + 1;
+    let slot = (bars_right - bars_left) / bands as f64;
+    svg.push_str(&format!(
+        r##"<line x1="{l}" y1="{b}" x2="{r}" y2="{b}" stroke="#999"/>
+<text x="{mid}" y="{tt}" text-anchor="middle" font-weight="bold">Open CDPs by collateral ratio</text>
+"##,
+        l = bars_left,
+        r = bars_right,
+        b = bottom,
+        mid = (bars_left + bars_right) / 2.0,
+        tt = top - 12.0,
+    ));
+    for band in 0..bands {
+        let label = match band {
+            0 => format!("<{}", HEALTH_BANDS[0]),
+            _ if band == bands - 1 => format!("≥{}", HEALTH_BANDS[band - 1]),
+            _ => format!("{}–{}", HEALTH_BANDS[band - 1], HEALTH_BANDS[band]),
+        };
+        svg.push_str(&format!(
+            concat!(r#"<text x="{:.1}" y="{:.1}" text-anchor="middle">{}</text>"#, "\n"),
+            bars_left + slot * (band as f64 + 0.5),
+            bottom + 18.0,
+            escape(&label)
+        ));
+    }
+    let tallest = run.frames.iter().flat_map(|f| f.health.iter().copied()).max().unwrap_or(0).max(1) as f64;
+    let colour = |band: usize| {
+        let upper = HEALTH_BANDS.get(band).copied().unwrap_or(f64::INFINITY);
+        if band == 0 {
+            "#d62728" // Underwater
+        } else if upper <= animation.min_collateral_ratio {
+            "#ff7f0e" // Liquidatable
+        } else {
+            "#2ca02c"
+        }
+    };
+
+    // One group per block, shown in turn
+    let mut liquidated = 0;
+    for (i, frame) in run.frames.iter().enumerate() {
+        liquidated += frame.liquidated.len();
+        svg.push_str(&format!(r#"<g visibility="{}">{}"#, if frames == 1 { "visible" } else { "hidden" }, frame_visibility(i, frames)));
+        svg.push_str(&format!(
+            r##"<line x1="{x:.1}" y1="{t}" x2="{x:.1}" y2="{b}" stroke="#bbb" stroke-dasharray="4 3"/><circle cx="{x:.1}" cy="{y:.1}" r="5" fill="#d62728"/>
+<text x="{cx}" y="{fy}" text-anchor="middle">Block {block} · ETH ${price:.0} · {now} liquidated this block ({total} so far) · {queue} waiting</text>
+"##,
+            x = x(i + 1),
+            y = y(frame.price),
+            t = top,
+            b = bottom,
+            cx = WIDTH / 2.0,
+            fy = HEIGHT - 30.0,
+            block = frame.block,
+            price = frame.price,
+            now = frame.liquidated.len(),
+            total = liquidated,
+            queue = frame.queue.len(),
+        ));
+        for (band, &count) in frame.health.iter().enumerate() {
+            let height = (bottom - top) * count as f64 / tallest;
+            svg.push_str(&format!(
+                concat!(
+                    r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="{}"/>"#,
+                    r#"<text x="{:.1}" y="{:.1}" text-anchor="middle">{}</text>"#,
+                    "\n"
+                ),
+                bars_left + slot * band as f64 + 4.0,
+                bottom - height,
+                slot - 8.0,
+                height,
+                colour(band),
+                bars_left + slot * (band as f64 + 0.5),
+                bottom - height - 4.0,
+                count
+            ));
+        }
+        svg.push_str("</g>
+");
+    }
+    svg.push_str("</svg>
+");
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cascade::run_cascade_simulation;
+
+    #[test]
+    fn test_frames_follow_the_run() {
+        let config = SimulationConfig { seed: Some(4), ..Default::default() };
+        let seed = campaign_seeds(1, &config)[0];
+        let run = record(LiquidationMechanism::Traditional, PriceScenario::FlashCrash, &config, seed);
+        let result = &run_cascade_simulation(LiquidationMechanism::Traditional, PriceScenario::FlashCrash, 1, &config)[0];
+
+        assert_eq!(result.seed, Some(seed));
+        assert_eq!(run.bad_debt, result.bad_debt);
+        assert_eq!(run.frames.iter().map(|f| f.liquidated.len()).sum::<usize>(), result.total_liquidations);
+        assert!((run.frames.last().unwrap().price - result.final_price).abs() < 0.01);
+        for frame in &run.frames {
+            assert_eq!(frame.liquidated.len() + frame.queue.len(), frame.liquidatable.len());
+            assert!(frame.liquidated.iter().all(|cdp| frame.liquidatable.contains(cdp) && *cdp < config.num_cdps));
+        }
+    }
+
+    #[test]
+    fn test_mechanisms_share_seeds() {
+        let config = SimulationConfig { seed: Some(9), ..Default::default() };
+        let mechanisms = LiquidationMechanism::all();
+        let seeds = campaign_seeds(2, &config);
+        let animation = animate(&mechanisms, PriceScenario::VolatileCrash, &seeds, &config);
+
+        assert_eq!(animation.runs.len(), 2 * mechanisms.len());
+        assert_eq!(animation.cdps, config.num_cdps);
+        let run_seeds: Vec<u64> = animation.runs.iter().map(|r| r.seed).collect();
+        let expected: Vec<u64> = seeds.iter().flat_map(|&seed| std::iter::repeat_n(seed, mechanisms.len())).collect();
+        assert_eq!(run_seeds, expected);
+        assert_ne!(animation.runs[0].mechanism, animation.runs[1].mechanism);
+    }
+
+    #[test]
+    fn test_quiet_frames_stay_compact() {
+        let quiet =
+            Frame { block: 3, price: 1834.21, liquidatable: vec![], liquidated: vec![], queue: vec![], health: vec![] };
+        let json = serde_json::to_string(&quiet).unwrap();
+
+        assert_eq!(json, r#"{"block":3,"price":1834.21}"#);
+        assert_eq!(serde_json::from_str::<Frame>(&json).unwrap(), quiet);
+    }
+
+    #[test]
+    fn test_svg_shows_one_frame_per_block() {
+        let config = SimulationConfig { num_cdps: 80, seed: Some(2), ..Default::default() };
+        let seeds = campaign_seeds(1, &config);
+        let animation = animate(&[LiquidationMechanism::Traditional], PriceScenario::FlashCrash, &seeds, &config);
+        let run = &animation.runs[0];
+        let svg = to_svg(&animation, run);
+
+        assert!(svg.starts_with("<svg") && svg.ends_with("</svg>\n"));
+        assert!(svg.contains("&lt;1</text>"));
+        assert_eq!(svg.matches("<animate ").count(), run.frames.len());
+        assert_eq!(svg.matches("<g ").count(), svg.matches("</g>").count());
+        // Health covers the open book: everything not yet liquidated
+        let first = &run.frames[0];
+        assert_eq!(first.health.iter().sum::<usize>(), config.num_cdps - first.liquidated.len());
+        assert_eq!(health_histogram(&[0.5, 1.0, 1.49, 1.5, 9.0, f64::INFINITY]), vec![1, 1, 1, 1, 0, 2]);
+    }
+}
@@ -0,0 +1,154 @@
+//! Price Impact Attribution
+//!
+//! How much of a run's price decline the liquidations themselves caused.
+//! Every price move is either exogenous, the scenario's shock for the
+//! block, or endogenous, the impact of ETH sold into the market, so the
+//! log decline splits exactly into the two. The amplification factor, the
+//! total decline over the exogenous one, measures the spiral: 1 when
+//! liquidations moved nothing, and lower under a mechanism that sells
+//! less into the fall.
+//!
+//! ## Method
+//! - Runs each mechanism on the same scenario; every run sees the same
+//!   seeds when `config.seed` is set
+//! - Declines are tallied in log terms per price move, so they add up
+//!   across blocks and to the run's total; the endogenous part includes
+//!   a neighbor's and a manipulator's sales, when configured
+//! - Amplification is the ratio of the mean total decline to the mean
+//!   exogenous decline, which stays defined when single runs rally
+
+use crate::cascade::{run_cascade_simulation, CascadeResult, LiquidationMechanism, PriceScenario, SimulationConfig};
+use crate::error::Result;
+
+/// A log decline as a percentage fall in price.
+pub fn decline_pct(log_decline: f64) -> f64 {
+    (1.0 - (-log_decline).exp()) * 100.0
+}
+
+#[derive(Debug, Clone)]
+pub struct AttributionPoint {
+    pub mechanism: LiquidationMechanism,
+    pub avg_shock_decline: f64,  // Mean log decline from the scenario's moves
+    pub avg_impact_decline: f64, // Mean log decline from ETH sold
+    pub avg_bad_debt: f64,
+}
+
+impl AttributionPoint {
+    fn from_results(mechanism: LiquidationMechanism, results: &[CascadeResult]) -> Self {
+        let n = results.len().max(1) as f64;
+        let mean = |f: fn(&CascadeResult) -> f64| results.iter().map(f).sum::<f64>() / n;
+        Self {
+            mechanism,
+            avg_shock_decline: mean(|r| r.shock_decline),
+            avg_impact_decline: mean(|r| r.impact_decline),
+            avg_bad_debt: mean(|r| r.bad_debt),
+        }
+    }
+
+    pub fn total_decline(&self) -> f64 {
+        self.avg_shock_decline + self.avg_impact_decline
+    }
+
+    /// Total decline over the exogenous decline; None when the scenario
+    /// did not push the price down on average.
+    pub fn amplification(&self) -> Option<f64> {
+        (self.avg_shock_decline > 0.0).then(|| self.total_decline() / self.avg_shock_decline)
+    }
+
+    /// Share of the total decline caused by selling; None without a decline.
+    pub fn impact_share(&self) -> Option<f64> {
+        (self.total_decline() > 0.0).then(|| self.avg_impact_decline / self.total_decline())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AttributionReport {
+    pub scenario: PriceScenario,
+    pub runs: usize,
+    pub points: Vec<AttributionPoint>, // In the order the mechanisms were given
+}
+
+impl AttributionReport {
+    pub fn print(&self) {
+        println!("| Mechanism                      | Exogenous | Liquidation | Total   | Amplification | From Liquidations | Bad Debt |");
+        println!("|--------------------------------|-----------|-------------|---------|---------------|-------------------|----------|");
+        let ratio = |value: Option<f64>, scale: f64, suffix: &str| match value {
+            Some(value) => format!("{:.2}{}", value * scale, suffix),
+            None => "-".to_string(),
+        };
+        for point in &self.points {
+            println!(
+                "| {:30} | {:8.1}% | {:10.1}% | {:6.1}% | {:>13} | {:>17} | ${:7.0} |",
+                point.mechanism.name(),
+                decline_pct(point.avg_shock_decline),
+                decline_pct(point.avg_impact_decline),
+                decline_pct(point.total_decline()),
+                ratio(point.amplification(), 1.0, "x"),
+                ratio(point.impact_share(), 100.0, "%"),
+                point.avg_bad_debt,
+            );
+        }
+
+        let traditional = self.points.iter().find(|p| p.mechanism == LiquidationMechanism::Traditional);
+        let Some(baseline) = traditional.and_then(AttributionPoint::amplification) else {
+            return;
+        };
+        for point in self.points.iter().filter(|p| p.mechanism.is_keeper_pool()) {
+            let Some(amplification) = point.amplification() else { continue };
+            let verb = if amplification < baseline { "dampens" } else { "does not dampen" };
+            println!(
+                "  {} {} the spiral: the scenario's fall is amplified {:.2}x, against {:.2}x under Traditional",
+                point.mechanism.name(),
+                verb,
+                amplification,
+                baseline,
+            );
+        }
+    }
+}
+
+/// Runs each of `mechanisms` on `scenario` and splits its price decline
+/// into the scenario's and the liquidations'.
+pub fn attribute_decline(
+    mechanisms: &[LiquidationMechanism],
+    scenario: PriceScenario,
+    runs: usize,
+    config: &SimulationConfig,
+) -> Result<AttributionReport> {
+    config.validate()?;
+    let mut points = Vec::with_capacity(mechanisms.len());
+    for &mechanism in mechanisms {
+        mechanism.validate()?;
+        points.push(AttributionPoint::from_results(mechanism, &run_cascade_simulation(mechanism, scenario, runs, config)));
+    }
+    Ok(AttributionReport { scenario, runs, points })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_components_sum_to_the_decline() {
+        let config = SimulationConfig { seed: Some(9), ..Default::default() };
+        for mechanism in LiquidationMechanism::all() {
+            for result in run_cascade_simulation(mechanism, PriceScenario::VolatileCrash, 5, &config) {
+                let decline = (1.0 - result.price_drop_pct / 100.0).ln();
+                assert!((result.shock_decline + result.impact_decline + decline).abs() < 1e-9);
+                assert!(result.impact_decline >= 0.0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_frictionless_market_does_not_amplify() {
+        let config = SimulationConfig { seed: Some(3), price_impact_per_eth: 0.0, ..Default::default() };
+        let report = attribute_decline(&LiquidationMechanism::all(), PriceScenario::FlashCrash, 6, &config).unwrap();
+
+        for point in &report.points {
+            assert_eq!(point.avg_impact_decline, 0.0);
+            assert_eq!(point.amplification(), Some(1.0));
+        }
+        assert!(decline_pct(report.points[0].total_decline()) > 0.0);
+    }
+}
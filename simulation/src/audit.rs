@@ -0,0 +1,220 @@
+//! Conservation Audit
+//!
+//! End-of-run reconciliation of a cascade's books. The simulation tallies
+//! each flow where it happens (collateral seized, debt repaid, penalties
+//! split); the audit checks those tallies against the end state, so every
+//! headline number in a `CascadeResult` is internally consistent.
+//!
+//! ## Identities
+//! - Collateral (ETH): initial + accrued + topped up = seized + remaining
+//! - Debt (USD): repaid + liquidation shortfall = debt closed by
+//!   liquidations + debt converted by soft liquidation's bands
+//! - Penalties (USD): penalties + conversion spread + incentives + emitted
+//!   token value = keeper profit + external keeper revenue + protocol
+//!   revenue + the keeper treasury's net balance
+//!
+//! Accrual counts growth on live CDPs only; top-ups are what owners add
+//! in a grace period. The liquidation shortfall is what a keeper absorbs
+//! repaying an underwater CDP; it is not part of the headline bad debt,
+//! which counts unliquidated CDPs only. English auctions are the
+//! exception: a winning bid short of the debt leaves the protocol holding
+//! the difference, so it is also bad debt.
+
+use serde::{Deserialize, Serialize};
+
+use crate::cascade::CascadeResult;
+
+/// Relative tolerance for floating-point drift across thousands of flows.
+const TOLERANCE: f64 = 1e-9;
+
+/// Flows tallied over a run, plus the end-state totals they reconcile to.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RunLedger {
+    pub initial_collateral: f64,   // ETH backing the book at block 0
+    pub accrued_collateral: f64,   // ETH added to live CDPs by accruing collateral
+    pub seized_collateral: f64,    // ETH taken from liquidated CDPs
+    pub remaining_collateral: f64, // ETH backing unliquidated CDPs at the end
+    pub liquidated_debt: f64,      // Debt closed by liquidations, partial or whole
+    pub debt_repaid: f64,          // Repaid out of seized collateral value
+    pub liquidation_shortfall: f64, // Debt above the collateral value when liquidated
+    pub penalties: f64,
+    pub incentives_paid: f64,
+    pub emission_value: f64,       // Reward tokens paid to keepers, at their value when paid
+    pub keeper_profit: f64,        // Simulated keepers
+    pub external_revenue: f64,     // External strategy, if any
+    pub protocol_revenue: f64,
+    #[serde(default)]
+    pub treasury_net: f64,         // Keeper treasury's end balance less its endowment
+    #[serde(default)]
+    pub topped_up_collateral: f64, // ETH owners added to CDPs in their grace period
+    #[serde(default)]
+    pub converted_debt: f64,       // Debt paid down by soft liquidation's band trades, net of buybacks
+    #[serde(default)]
+    pub conversion_spread: f64,    // Arbitrage spread borrowers paid on band trades
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reconciliation {
+    pub name: &'static str,
+    pub identity: &'static str,
+    pub left: f64,
+    pub right: f64,
+}
+
+impl Reconciliation {
+    pub fn discrepancy(&self) -> f64 {
+        self.left - self.right
+    }
+
+    pub fn balances(&self) -> bool {
+        self.discrepancy().abs() <= TOLERANCE * self.left.abs().max(self.right.abs()).max(1.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConservationReport {
+    pub checks: Vec<Reconciliation>,
+}
+
+impl ConservationReport {
+    pub fn from_ledger(ledger: &RunLedger) -> Self {
+        let checks = vec![
+            Reconciliation {
+                name: "Collateral",
+                identity: "initial + accrued + topped up = seized + remaining (ETH)",
+                left: ledger.initial_collateral + ledger.accrued_collateral + ledger.topped_up_collateral,
+                right: ledger.seized_collateral + ledger.remaining_collateral,
+            },
+            Reconciliation {
+                name: "Debt",
+                identity: "repaid + shortfall = liquidated + converted debt ($)",
+                left: ledger.debt_repaid + ledger.liquidation_shortfall,
+                right: ledger.liquidated_debt + ledger.converted_debt,
+            },
+            Reconciliation {
+                name: "Penalties",
+                identity: "penalties + spread + incentives + emissions = keepers + protocol + treasury ($)",
+                left: ledger.penalties + ledger.conversion_spread + ledger.incentives_paid + ledger.emission_value,
+                right: ledger.keeper_profit + ledger.external_revenue + ledger.protocol_revenue + ledger.treasury_net,
+            },
+        ];
+        Self { checks }
+    }
+
+    pub fn of(result: &CascadeResult) -> Self {
+        Self::from_ledger(&result.ledger)
+    }
+
+    pub fn discrepancies(&self) -> Vec<&Reconciliation> {
+        self.checks.iter().filter(|c| !c.balances()).collect()
+    }
+
+    pub fn is_consistent(&self) -> bool {
+        self.checks.iter().all(Reconciliation::balances)
+    }
+
+    pub fn print(&self) {
+        println!("| Check      | Identity                                                               | Left           | Right          | Discrepancy  |");
+        println!("|------------|------------------------------------------------------------------------|----------------|----------------|--------------|");
+        for check in &self.checks {
+            println!(
+                "| {:10} | {:70} | {:14.4} | {:14.4} | {:>12} |",
+                check.name,
+                check.identity,
+                check.left,
+                check.right,
+                if check.balances() { "-".to_string() } else { format!("{:+.4} !", check.discrepancy()) },
+            );
+        }
+    }
+}
+
+/// Runs whose books do not reconcile, with their reports.
+pub fn flag_discrepancies(results: &[CascadeResult]) -> Vec<(&CascadeResult, ConservationReport)> {
+    results
+        .iter()
+        .map(|result| (result, ConservationReport::of(result)))
+        .filter(|(_, report)| !report.is_consistent())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cascade::{
+        run_cascade_simulation, run_single_with_strategy, AccruingCollateral, IncentiveProgram, LiquidationMechanism, PriceScenario,
+        RewardToken, SimulationConfig, TreasuryDrip,
+    };
+    use crate::strategy::{FnStrategy, KeeperAction, Opportunity};
+
+    #[test]
+    fn test_every_mechanism_reconciles() {
+        let config = SimulationConfig { seed: Some(11), ..Default::default() };
+        for mechanism in LiquidationMechanism::all() {
+            for scenario in PriceScenario::all() {
+                let results = run_cascade_simulation(mechanism, scenario, 5, &config);
+                assert!(flag_discrepancies(&results).is_empty(), "{} {}", mechanism.name(), scenario.name());
+                // An auction winner can lose on a lot whose price fell while its bid stood
+                if !matches!(mechanism, LiquidationMechanism::EnglishAuction(_)) {
+                    assert!(results.iter().all(|r| r.ledger.penalties >= r.protocol_revenue));
+                }
+            }
+
+            // An external keeper's take comes out of the same penalties
+            let mut rng = config.rng();
+            let mut strategy = FnStrategy::new("always", |_: &Opportunity| Some(KeeperAction { gas_priority: 1.0 }));
+            let (result, ledger) =
+                run_single_with_strategy(mechanism, PriceScenario::VolatileCrash, &config, &mut rng, &mut strategy);
+            // Auction lots, band trades and batches go to the simulated keepers only
+            let offered = !matches!(
+                mechanism,
+                LiquidationMechanism::EnglishAuction(_) | LiquidationMechanism::SoftLiquidation(_) | LiquidationMechanism::BatchAuction(_)
+            );
+            assert!(ledger.revenue > 0.0 || !offered);
+            assert!(ConservationReport::of(&result).is_consistent());
+        }
+    }
+
+    #[test]
+    fn test_add_ons_reconcile() {
+        // Accrual, emissions, incentives and the treasury each add a flow to the books
+        let config = SimulationConfig {
+            seed: Some(2),
+            accruing_collateral: Some(AccruingCollateral::default()),
+            reward_token: Some(RewardToken::default()),
+            incentive_program: Some(IncentiveProgram::default()),
+            ..Default::default()
+        };
+        let results = run_cascade_simulation(LiquidationMechanism::keeper_pool(), PriceScenario::VolatileCrash, 5, &config);
+
+        assert!(flag_discrepancies(&results).is_empty());
+        assert!(results.iter().any(|r| r.ledger.emission_value > 0.0 && r.ledger.incentives_paid > 0.0));
+
+        let config = SimulationConfig { treasury_drip: Some(TreasuryDrip { endowment: 50_000.0, ..Default::default() }), ..config };
+        let results = run_cascade_simulation(LiquidationMechanism::keeper_pool(), PriceScenario::VolatileCrash, 5, &config);
+        assert!(flag_discrepancies(&results).is_empty());
+        assert!(results.iter().any(|r| r.ledger.treasury_net != 0.0));
+    }
+
+    #[test]
+    fn test_flags_tampered_books() {
+        let ledger = RunLedger {
+            initial_collateral: 100.0,
+            seized_collateral: 40.0,
+            remaining_collateral: 60.0,
+            liquidated_debt: 50_000.0,
+            debt_repaid: 50_000.0,
+            penalties: 1_000.0,
+            keeper_profit: 700.0,
+            protocol_revenue: 300.0,
+            ..Default::default()
+        };
+        assert!(ConservationReport::from_ledger(&ledger).is_consistent());
+
+        let report = ConservationReport::from_ledger(&RunLedger { protocol_revenue: 310.0, ..ledger });
+        let flagged = report.discrepancies();
+        assert_eq!(flagged.len(), 1);
+        assert_eq!(flagged[0].name, "Penalties");
+        assert!((flagged[0].discrepancy() + 10.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,158 @@
+//! Keeper Strategy Backtesting
+//!
+//! Drops an external `KeeperStrategy` into simulated cascades and reports its
+//! PnL, making the crate useful to keeper operators as well as protocol
+//! designers.
+//!
+//! ## What We Report
+//! - Net PnL distribution (mean, std dev, 5th percentile, loss probability)
+//! - Win rate, attempts, revenue and gas spend per run
+//! - Max intra-run drawdown of cumulative PnL
+
+use crate::cascade::{run_single_with_strategy, LiquidationMechanism, PriceScenario, SimulationConfig};
+use crate::stats;
+use crate::strategy::{KeeperLedger, KeeperStrategy};
+
+#[derive(Debug, Clone)]
+pub struct BacktestReport {
+    pub strategy: String,
+    pub mechanism: LiquidationMechanism,
+    pub scenario: PriceScenario,
+    pub runs: usize,
+    pub mean_pnl: f64,
+    pub std_pnl: f64,
+    pub p5_pnl: f64,
+    pub loss_probability: f64,
+    pub win_rate: f64,
+    pub avg_attempts: f64,
+    pub avg_revenue: f64,
+    pub avg_gas_spent: f64,
+    pub avg_max_drawdown: f64,
+}
+
+impl BacktestReport {
+    pub fn print(&self) {
+        println!("  Runs:                    {}", self.runs);
+        println!("  Mean net PnL:            ${:.0}", self.mean_pnl);
+        println!("  PnL std dev:             ${:.0}", self.std_pnl);
+        println!("  PnL 5th percentile:      ${:.0}", self.p5_pnl);
+        println!("  Loss probability:        {:.1}%", self.loss_probability * 100.0);
+        println!("  Win rate:                {:.1}%", self.win_rate * 100.0);
+        println!("  Avg attempts:            {:.1}", self.avg_attempts);
+        println!("  Avg revenue:             ${:.0}", self.avg_revenue);
+        println!("  Avg gas spent:           ${:.0}", self.avg_gas_spent);
+        println!("  Avg max drawdown:        ${:.0}", self.avg_max_drawdown);
+    }
+}
+
+fn summarize(
+    strategy: &str,
+    mechanism: LiquidationMechanism,
+    scenario: PriceScenario,
+    ledgers: &[KeeperLedger],
+) -> BacktestReport {
+    let n = ledgers.len() as f64;
+    let mut pnls: Vec<f64> = ledgers.iter().map(|l| l.net_pnl()).collect();
+    let attempts: usize = ledgers.iter().map(|l| l.attempts).sum();
+    let wins: usize = ledgers.iter().map(|l| l.wins).sum();
+
+    let mean_pnl = stats::mean(&pnls);
+    let std_pnl = stats::variance(&pnls).sqrt();
+    let loss_probability = pnls.iter().filter(|&&p| p < 0.0).count() as f64 / n;
+    pnls.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let p5_idx = ((pnls.len() as f64 - 1.0) * 0.05).round() as usize;
+
+    BacktestReport {
+        strategy: strategy.to_string(),
+        mechanism,
+        scenario,
+        runs: ledgers.len(),
+        mean_pnl,
+        std_pnl,
+        p5_pnl: pnls.get(p5_idx).copied().unwrap_or(0.0),
+        loss_probability,
+        win_rate: if attempts > 0 { wins as f64 / attempts as f64 } else { 0.0 },
+        avg_attempts: attempts as f64 / n,
+        avg_revenue: ledgers.iter().map(|l| l.revenue).sum::<f64>() / n,
+        avg_gas_spent: ledgers.iter().map(|l| l.gas_spent).sum::<f64>() / n,
+        avg_max_drawdown: ledgers.iter().map(|l| l.max_drawdown()).sum::<f64>() / n,
+    }
+}
+
+pub fn run_backtest(
+    strategy: &mut dyn KeeperStrategy,
+    mechanism: LiquidationMechanism,
+    scenario: PriceScenario,
+    runs: usize,
+    config: &SimulationConfig,
+) -> BacktestReport {
+    let mut rng = config.rng();
+    let ledgers: Vec<KeeperLedger> = (0..runs)
+        .map(|_| run_single_with_strategy(mechanism, scenario, config, &mut rng, strategy).1)
+        .collect();
+    summarize(strategy.name(), mechanism, scenario, &ledgers)
+}
+
+/// Backtests the strategy across every scenario and mechanism.
+pub fn run_backtest_suite(
+    strategy: &mut dyn KeeperStrategy,
+    runs: usize,
+    config: &SimulationConfig,
+) -> Vec<BacktestReport> {
+    let mut reports = Vec::new();
+    for scenario in PriceScenario::all() {
+        for mechanism in LiquidationMechanism::all() {
+            reports.push(run_backtest(strategy, mechanism, scenario, runs, config));
+        }
+    }
+    reports
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategy::{FnStrategy, KeeperAction, ThresholdStrategy};
+
+    #[test]
+    fn test_passive_strategy_has_zero_pnl() {
+        let mut passive = FnStrategy::new("Passive", |_| None);
+        let report = run_backtest(
+            &mut passive,
+            LiquidationMechanism::Traditional,
+            PriceScenario::FlashCrash,
+            5,
+            &SimulationConfig::default(),
+        );
+
+        assert_eq!(report.avg_attempts, 0.0);
+        assert_eq!(report.mean_pnl, 0.0);
+    }
+
+    #[test]
+    fn test_max_priority_wins_traditional_races() {
+        let mut strategy = FnStrategy::new("Always", |_| Some(KeeperAction { gas_priority: 1.0 }));
+        let report = run_backtest(
+            &mut strategy,
+            LiquidationMechanism::Traditional,
+            PriceScenario::FlashCrash,
+            5,
+            &SimulationConfig::default(),
+        );
+
+        assert!(report.avg_attempts > 0.0);
+        assert!(report.win_rate > 0.99);
+    }
+
+    #[test]
+    fn test_suite_covers_all_cells() {
+        let mut strategy = ThresholdStrategy {
+            min_profit: 0.0,
+            gas_priority: 0.5,
+        };
+        let reports = run_backtest_suite(&mut strategy, 2, &SimulationConfig::default());
+        assert_eq!(
+            reports.len(),
+            PriceScenario::all().len() * LiquidationMechanism::all().len()
+        );
+    }
+}
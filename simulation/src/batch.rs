@@ -0,0 +1,213 @@
+//! Batch Campaigns
+//!
+//! A campaign as a list of jobs (a scenario or price model, a mechanism and
+//! a run count) run as one batch, instead of nested scenario × mechanism
+//! loops in each subcommand. The batch runs each distinct job once, spreads
+//! every job's runs over one thread pool, and hands back all results as a
+//! single bundle in job order.
+//!
+//! ## Method
+//! - Jobs are identical when they run the same mechanism through the same
+//!   scenario for the same number of runs. A price model only picks the
+//!   scenario (`scenario_for_model`), so GBM and GARCH jobs share their runs
+//! - Distinct jobs run concurrently and their runs interleave on the pool;
+//!   each job's seeds are drawn the way `run_cascade_simulation` draws them,
+//!   so a job's results match running it alone, whatever the thread count
+//! - `max_threads` caps the pool (the global rayon pool otherwise) and
+//!   `max_runs` refuses a batch whose distinct jobs add up to more runs
+
+use rayon::prelude::*;
+
+use crate::cascade::{
+    aggregate_results, run_cascade_simulation, AggregatedCascadeResult, CascadeResult, LiquidationMechanism,
+    PriceScenario, SimulationConfig,
+};
+use crate::error::{Error, Result};
+use crate::monte_carlo::{scenario_for_model, summarize, MonteCarloResult, PriceModel};
+use crate::portfolio::CdpPortfolio;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Job {
+    pub model: Option<PriceModel>, // Monte Carlo jobs; the scenario follows from it
+    pub mechanism: LiquidationMechanism,
+    pub scenario: PriceScenario,
+    pub runs: usize,
+}
+
+impl Job {
+    pub fn cascade(mechanism: LiquidationMechanism, scenario: PriceScenario, runs: usize) -> Self {
+        Self { model: None, mechanism, scenario, runs }
+    }
+
+    pub fn monte_carlo(model: PriceModel, mechanism: LiquidationMechanism, runs: usize) -> Self {
+        Self { model: Some(model), mechanism, scenario: scenario_for_model(model), runs }
+    }
+
+    /// One cascade job per scenario and mechanism, scenario-major.
+    pub fn matrix(scenarios: &[PriceScenario], mechanisms: &[LiquidationMechanism], runs: usize) -> Vec<Self> {
+        scenarios
+            .iter()
+            .flat_map(|&scenario| mechanisms.iter().map(move |&mechanism| Self::cascade(mechanism, scenario, runs)))
+            .collect()
+    }
+
+    /// One Monte Carlo job per price model and mechanism, model-major.
+    pub fn model_matrix(models: &[PriceModel], mechanisms: &[LiquidationMechanism], runs: usize) -> Vec<Self> {
+        models
+            .iter()
+            .flat_map(|&model| mechanisms.iter().map(move |&mechanism| Self::monte_carlo(model, mechanism, runs)))
+            .collect()
+    }
+
+    /// Whether `self` and `other` run the same cascades.
+    fn same_runs(&self, other: &Self) -> bool {
+        self.mechanism == other.mechanism && self.scenario == other.scenario && self.runs == other.runs
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Batch {
+    pub jobs: Vec<Job>,
+    pub max_threads: Option<usize>, // None = the global rayon pool
+    pub max_runs: Option<usize>,    // Cap on the runs of distinct jobs
+}
+
+impl Batch {
+    pub fn new(jobs: Vec<Job>) -> Self {
+        Self { jobs, ..Default::default() }
+    }
+
+    pub fn max_threads(mut self, threads: usize) -> Self {
+        self.max_threads = Some(threads);
+        self
+    }
+
+    pub fn max_runs(mut self, runs: usize) -> Self {
+        self.max_runs = Some(runs);
+        self
+    }
+
+    /// Runs the batch's distinct jobs on the synthetic book, or on
+    /// `portfolio` when given.
+    pub fn run(&self, config: &SimulationConfig, portfolio: Option<&CdpPortfolio>) -> Result<BatchResults> {
+        config.validate()?;
+        let mut distinct: Vec<Job> = Vec::new();
+        let cells = self
+            .jobs
+            .iter()
+            .map(|job| {
+                job.mechanism.validate()?;
+                Ok(distinct.iter().position(|d| d.same_runs(job)).unwrap_or_else(|| {
+                    distinct.push(*job);
+                    distinct.len() - 1
+                }))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let total: usize = distinct.iter().map(|job| job.runs).sum();
+        if let Some(max_runs) = self.max_runs.filter(|&max| total > max) {
+            return Err(Error::Invalid(format!("batch needs {} runs, over the limit of {}", total, max_runs)));
+        }
+
+        // Jobs run on pool workers, logging to the caller's subscriber
+        let dispatch = tracing::dispatcher::get_default(|dispatch| dispatch.clone());
+        let run_all = || -> Vec<Vec<CascadeResult>> {
+            distinct
+                .par_iter()
+                .map(|job| {
+                    tracing::dispatcher::with_default(&dispatch, || match portfolio {
+                        Some(portfolio) => portfolio.run_cascade(job.mechanism, job.scenario, job.runs, config),
+                        None => run_cascade_simulation(job.mechanism, job.scenario, job.runs, config),
+                    })
+                })
+                .collect()
+        };
+        let results = match self.max_threads {
+            Some(threads) => rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .map_err(|e| Error::Invalid(format!("failed to start {} threads: {}", threads, e)))?
+                .install(run_all),
+            None => run_all(),
+        };
+
+        Ok(BatchResults { jobs: self.jobs.clone(), cells, results })
+    }
+}
+
+/// Every job's results, in job order; identical jobs share one result set.
+#[derive(Debug, Clone)]
+pub struct BatchResults {
+    pub jobs: Vec<Job>,
+    cells: Vec<usize>, // Index into `results` per job
+    results: Vec<Vec<CascadeResult>>,
+}
+
+impl BatchResults {
+    /// The runs of job `index`.
+    pub fn results(&self, index: usize) -> &[CascadeResult] {
+        &self.results[self.cells[index]]
+    }
+
+    /// Each job with its runs, in job order.
+    pub fn iter(&self) -> impl Iterator<Item = (&Job, &[CascadeResult])> {
+        self.jobs.iter().zip(&self.cells).map(|(job, &cell)| (job, self.results[cell].as_slice()))
+    }
+
+    pub fn aggregate(&self, index: usize) -> AggregatedCascadeResult {
+        aggregate_results(self.results(index))
+    }
+
+    /// The tail risk of Monte Carlo job `index`; None for a cascade job.
+    pub fn monte_carlo(&self, index: usize) -> Option<MonteCarloResult> {
+        let job = &self.jobs[index];
+        job.model.map(|model| summarize(model, job.mechanism, self.results(index)))
+    }
+
+    /// The tail risk of every Monte Carlo job, in job order.
+    pub fn monte_carlo_results(&self) -> Vec<MonteCarloResult> {
+        (0..self.jobs.len()).filter_map(|index| self.monte_carlo(index)).collect()
+    }
+
+    /// Jobs actually run after deduplication.
+    pub fn distinct_jobs(&self) -> usize {
+        self.results.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monte_carlo::run_monte_carlo_with_config;
+
+    #[test]
+    fn test_batch_matches_running_each_job_alone() {
+        let config = SimulationConfig { seed: Some(4), num_cdps: 120, ..Default::default() };
+        let scenarios = [PriceScenario::FlashCrash, PriceScenario::VolatileCrash];
+        let jobs = Job::matrix(&scenarios, &LiquidationMechanism::all(), 5);
+        let batch = Batch::new(jobs).max_threads(2).run(&config, None).unwrap();
+
+        assert_eq!(batch.jobs.len(), 2 * LiquidationMechanism::all().len());
+        for (job, results) in batch.iter() {
+            let alone = run_cascade_simulation(job.mechanism, job.scenario, job.runs, &config);
+            assert_eq!(results.iter().map(|r| r.seed).collect::<Vec<_>>(), alone.iter().map(|r| r.seed).collect::<Vec<_>>());
+            assert_eq!(results.iter().map(|r| r.bad_debt).collect::<Vec<_>>(), alone.iter().map(|r| r.bad_debt).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn test_identical_jobs_run_once() {
+        let config = SimulationConfig { seed: Some(9), num_cdps: 120, ..Default::default() };
+        let mechanism = LiquidationMechanism::keeper_pool();
+        // GBM and GARCH both run the volatile crash
+        let mut jobs = Job::model_matrix(&[PriceModel::GBM, PriceModel::GARCH], &[mechanism], 4);
+        jobs.push(Job::cascade(mechanism, PriceScenario::VolatileCrash, 4));
+        let batch = Batch::new(jobs).run(&config, None).unwrap();
+
+        assert_eq!(batch.distinct_jobs(), 1);
+        let garch = batch.monte_carlo(1).unwrap();
+        assert_eq!(garch.model, PriceModel::GARCH);
+        assert_eq!(garch.bad_debts, run_monte_carlo_with_config(PriceModel::GARCH, mechanism, 4, &config).bad_debts);
+        assert!(batch.monte_carlo(2).is_none());
+        assert!(Batch::new(batch.jobs.clone()).max_runs(3).run(&config, None).is_err());
+    }
+}
@@ -0,0 +1,170 @@
+//! Batch Auction Comparison
+//!
+//! A per-block uniform-price batch auction (see `cascade::BatchAuction`)
+//! against the per-CDP mechanisms on the same scenario. Racers pay gas and
+//! priority fees per CDP and mostly lose; the batch takes one bid per
+//! keeper per block and nothing is raced. Winners all pay the marginal
+//! bidder's discount and split the batch pro rata to the capital they
+//! bring, so profit follows capital rather than who won each race; and the
+//! whole queue's collateral reaches the market in one block.
+//!
+//! ## Method
+//! - Runs Traditional, the keeper pool, fixed spread and the batch auction
+//!   at each reservation dispersion; every run sees the same seeds when
+//!   `config.seed` is set
+//! - Gas waste is the fee burn (gas and priority fees, reverted races
+//!   included), price impact the log price decline from ETH sold, and
+//!   fairness the top fifth of keepers' share of profit
+//! - The clearing discount is the collateral value winners took beyond
+//!   the debt, per dollar repaid; CDPs with less equity than the marginal
+//!   reservation pay only what they have
+
+use crate::cascade::{run_cascade_simulation, BatchAuction, CascadeResult, LiquidationMechanism, PriceScenario, SimulationConfig};
+use crate::error::{Error, Result};
+
+/// Reservation dispersions compared by default: near-identical bidders up
+/// to widely scattered ones.
+pub const BATCH_DISPERSIONS: [f64; 3] = [0.1, 0.5, 1.0];
+
+#[derive(Debug, Clone)]
+pub struct BatchPoint {
+    pub mechanism: LiquidationMechanism,
+    pub avg_bad_debt: f64,
+    pub bad_debt_frequency: f64,
+    pub avg_fee_burn: f64,
+    pub avg_impact_decline: f64,   // Log price decline from ETH sold
+    pub profit_concentration: f64, // Top fifth of keepers' share of profit
+    pub clearing_discount: f64,    // Batch discount per dollar repaid; 0 for per-CDP mechanisms
+}
+
+impl BatchPoint {
+    fn from_results(mechanism: LiquidationMechanism, results: &[CascadeResult]) -> Self {
+        let n = results.len().max(1) as f64;
+        let mean = |f: fn(&CascadeResult) -> f64| results.iter().map(f).sum::<f64>() / n;
+        let (discount, debt) = results
+            .iter()
+            .filter_map(|r| r.batches)
+            .fold((0.0, 0.0), |(discount, debt), b| (discount + b.discount, debt + b.debt));
+        Self {
+            mechanism,
+            avg_bad_debt: mean(|r| r.bad_debt),
+            bad_debt_frequency: results.iter().filter(|r| r.bad_debt > 0.0).count() as f64 / n,
+            avg_fee_burn: mean(|r| r.fee_burn),
+            avg_impact_decline: mean(|r| r.impact_decline),
+            profit_concentration: mean(|r| r.profit_concentration),
+            clearing_discount: if debt > 0.0 { discount / debt } else { 0.0 },
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self.mechanism {
+            LiquidationMechanism::BatchAuction(batch) => format!("Batch, sigma {:.2}", batch.dispersion),
+            mechanism => mechanism.info().short_name.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BatchReport {
+    pub scenario: PriceScenario,
+    pub runs: usize,
+    pub per_cdp: Vec<BatchPoint>, // Traditional, the keeper pool and fixed spread
+    pub points: Vec<BatchPoint>,
+}
+
+impl BatchReport {
+    pub fn print(&self) {
+        println!("| Mechanism          | Bad Debt   | P(Bad Debt) | Fee Burn    | Impact Decline | Top 20% Profit | Clearing |");
+        println!("|--------------------|------------|-------------|-------------|----------------|----------------|----------|");
+        for point in self.per_cdp.iter().chain(&self.points) {
+            let clearing = match point.mechanism {
+                LiquidationMechanism::BatchAuction(_) => format!("{:.2}%", point.clearing_discount * 100.0),
+                _ => "-".to_string(),
+            };
+            println!(
+                "| {:18} | ${:9.0} | {:10.1}% | ${:10.0} | {:13.1}% | {:13.1}% | {:>8} |",
+                point.label(),
+                point.avg_bad_debt,
+                point.bad_debt_frequency * 100.0,
+                point.avg_fee_burn,
+                point.avg_impact_decline * 100.0,
+                point.profit_concentration * 100.0,
+                clearing,
+            );
+        }
+        let cheapest = self.per_cdp.iter().min_by(|a, b| a.avg_fee_burn.total_cmp(&b.avg_fee_burn));
+        if let Some(cheapest) = cheapest {
+            for point in &self.points {
+                println!(
+                    "  {} burns ${:.0} against {}'s ${:.0}, paying keepers {:.2}% of the debt repaid",
+                    point.label(),
+                    point.avg_fee_burn,
+                    cheapest.label(),
+                    cheapest.avg_fee_burn,
+                    point.clearing_discount * 100.0,
+                );
+            }
+        }
+    }
+}
+
+/// Runs Traditional, the default keeper pool, fixed spread, and `batch` at
+/// each reservation dispersion in `dispersions`.
+pub fn compare_batch_auction(
+    batch: BatchAuction,
+    scenario: PriceScenario,
+    dispersions: &[f64],
+    runs: usize,
+    config: &SimulationConfig,
+) -> Result<BatchReport> {
+    if dispersions.is_empty() {
+        return Err(Error::Invalid("batch auction comparison needs at least one dispersion".to_string()));
+    }
+    config.validate()?;
+    let run = |mechanism: LiquidationMechanism| -> Result<BatchPoint> {
+        mechanism.validate()?;
+        Ok(BatchPoint::from_results(mechanism, &run_cascade_simulation(mechanism, scenario, runs, config)))
+    };
+    let per_cdp = [LiquidationMechanism::Traditional, LiquidationMechanism::keeper_pool(), LiquidationMechanism::fixed_spread()]
+        .into_iter()
+        .map(run)
+        .collect::<Result<Vec<_>>>()?;
+    let points = dispersions
+        .iter()
+        .map(|&dispersion| run(LiquidationMechanism::BatchAuction(BatchAuction { dispersion, ..batch })))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(BatchReport { scenario, runs, per_cdp, points })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batches_burn_less_than_races() {
+        let config = SimulationConfig { seed: Some(3), ..Default::default() };
+        let report = compare_batch_auction(BatchAuction::default(), PriceScenario::GradualDecline, &[0.5], 10, &config).unwrap();
+
+        let (traditional, batch) = (&report.per_cdp[0], &report.points[0]);
+        assert!(batch.avg_fee_burn < traditional.avg_fee_burn);
+        // Winners split each batch by capital, not by who won the race
+        assert!(batch.profit_concentration < traditional.profit_concentration);
+        assert!(batch.clearing_discount > 0.0 && batch.clearing_discount <= config.liquidation_penalty);
+    }
+
+    #[test]
+    fn test_tight_reservations_clear_near_the_penalty() {
+        let config = SimulationConfig { seed: Some(3), ..Default::default() };
+        let report = compare_batch_auction(BatchAuction::default(), PriceScenario::FlashCrash, &[0.1, 1.0], 10, &config).unwrap();
+
+        let (tight, dispersed) = (&report.points[0], &report.points[1]);
+        assert!(tight.clearing_discount > dispersed.clearing_discount);
+        for result in run_cascade_simulation(LiquidationMechanism::batch_auction(), PriceScenario::FlashCrash, 3, &config) {
+            let batches = result.batches.unwrap();
+            assert_eq!(batches.settled, result.total_liquidations);
+            assert!(crate::audit::ConservationReport::of(&result).is_consistent());
+        }
+        assert!(compare_batch_auction(BatchAuction::default(), PriceScenario::FlashCrash, &[-1.0], 1, &config).is_err());
+    }
+}
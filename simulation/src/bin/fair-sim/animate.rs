@@ -0,0 +1,111 @@
+//! `fair-sim animate`: per-block frames of a few cascade runs (price,
+//! liquidatable set, liquidated set, queue) as compact JSON for the web
+//! animation of the cascade, or as self-contained animated SVGs showing the
+//! price and the book's health block by block. Every mechanism replays the
+//! same seeds.
+//!
+//! ```bash
+//! # The first three runs of `fair-sim cascade --seed 7 --scenario flash-crash`
+//! fair-sim animate --scenario flash-crash --seed 7 --runs 3 --output frames.json
+//!
+//! # An outlier reported by a campaign, under both mechanisms
+//! fair-sim animate --scenario black-swan --run-seed 1234567890 --output outlier.json
+//!
+//! # One animated SVG per run, to open in any browser or drop into a slide
+//! fair-sim animate --scenario flash-crash --seed 7 --runs 1 --svg-dir cascade-svg
+//! ```
+//!
+//! The frames file holds one `animation::Animation` per scenario; SVGs are
+//! named `<scenario>-<seed>-<mechanism>.svg`.
+
+use std::path::{Path, PathBuf};
+
+use clap::Args;
+
+use fair_simulation::alerts::EXIT_OK;
+use fair_simulation::animation::{animate, campaign_seeds, to_svg, Animation};
+
+use crate::{save_results, usage_error, MechanismArgs, RunArgs, ScenarioArgs};
+
+const DEFAULT_RUNS: usize = 3;
+
+#[derive(Args, Debug)]
+pub struct AnimateArgs {
+    #[command(flatten)]
+    run: RunArgs,
+    #[command(flatten)]
+    mechanisms: MechanismArgs,
+    #[command(flatten)]
+    scenarios: ScenarioArgs,
+    /// Record the campaign run with this per-run seed instead (repeatable)
+    #[arg(long = "run-seed", conflicts_with = "runs")]
+    run_seeds: Vec<u64>,
+    /// Frames file to write
+    #[arg(long, required_unless_present = "svg_dir")]
+    output: Option<PathBuf>,
+    /// Write each run as a self-contained animated SVG into this directory
+    #[arg(long)]
+    svg_dir: Option<PathBuf>,
+}
+
+/// Writes every run of every animation as an SVG under `dir`.
+fn save_svgs(dir: &Path, animations: &[Animation]) -> Result<usize, String> {
+    std::fs::create_dir_all(dir).map_err(|e| format!("failed to create {}: {}", dir.display(), e))?;
+    let mut written = 0;
+    for animation in animations {
+        for run in &animation.runs {
+            let mechanism: String = run.mechanism.split(' ').next().unwrap_or_default().to_lowercase();
+            let path = dir.join(format!("{:?}-{}-{}.svg", animation.scenario, run.seed, mechanism));
+            std::fs::write(&path, to_svg(animation, run)).map_err(|e| format!("failed to write {}: {}", path.display(), e))?;
+            written += 1;
+        }
+    }
+    Ok(written)
+}
+
+pub fn run(args: AnimateArgs) -> i32 {
+    let mut setup = match args.run.setup() {
+        Ok(setup) => setup,
+        Err(e) => return usage_error(e),
+    };
+    args.mechanisms.apply(&mut setup);
+    args.scenarios.apply(&mut setup);
+    let runs = if args.run_seeds.is_empty() { setup.runs_or(DEFAULT_RUNS) } else { args.run_seeds.len() };
+    let manifest = match args.run.manifest(&mut setup, runs, args.output.is_some()) {
+        Ok(manifest) => manifest,
+        Err(e) => return usage_error(e),
+    };
+    let seeds = if args.run_seeds.is_empty() { campaign_seeds(runs, &setup.simulation) } else { args.run_seeds.clone() };
+    let mechanisms = setup.mechanisms();
+
+    let animations: Vec<Animation> = setup
+        .scenarios()
+        .into_iter()
+        .map(|scenario| animate(&mechanisms, scenario, &seeds, &setup.simulation))
+        .collect();
+    for animation in &animations {
+        println!("{}", animation.scenario.name());
+        for run in &animation.runs {
+            println!(
+                "  {:32} seed {:>20}  {:>3} frames  bad debt ${:.0}",
+                run.mechanism,
+                run.seed,
+                run.frames.len(),
+                run.bad_debt
+            );
+        }
+    }
+
+    if let Some(path) = &args.output {
+        if let Err(e) = save_results(path, &animations, manifest.as_ref()) {
+            return usage_error(e);
+        }
+    }
+    if let Some(dir) = &args.svg_dir {
+        match save_svgs(dir, &animations) {
+            Ok(written) => println!("Saved {} animated SVGs to {}", written, dir.display()),
+            Err(e) => return usage_error(e),
+        }
+    }
+    EXIT_OK
+}
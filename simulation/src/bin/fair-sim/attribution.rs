@@ -0,0 +1,61 @@
+//! `fair-sim attribution`: each scenario's price decline split into its
+//! exogenous moves and the liquidations' price impact, with the
+//! amplification factor per mechanism.
+//!
+//! ```bash
+//! fair-sim attribution --runs 200 --seed 5
+//!
+//! # Traditional against a 90/10 pool
+//! fair-sim attribution --scenario black-swan --mechanism traditional --mechanism keeper-pool --split 0.9
+//! ```
+
+use clap::Args;
+
+use fair_simulation::alerts::EXIT_OK;
+use fair_simulation::attribution::attribute_decline;
+
+use crate::{usage_error, MechanismArgs, RunArgs, ScenarioArgs};
+
+const DEFAULT_RUNS: usize = 100;
+
+#[derive(Args, Debug)]
+pub struct AttributionArgs {
+    #[command(flatten)]
+    run: RunArgs,
+    #[command(flatten)]
+    mechanisms: MechanismArgs,
+    #[command(flatten)]
+    scenarios: ScenarioArgs,
+}
+
+pub fn run(args: AttributionArgs) -> i32 {
+    let mut setup = match args.run.setup() {
+        Ok(setup) => setup,
+        Err(e) => return usage_error(e),
+    };
+    args.mechanisms.apply(&mut setup);
+    args.scenarios.apply(&mut setup);
+    let runs = setup.runs_or(DEFAULT_RUNS);
+    if let Err(e) = args.run.manifest(&mut setup, runs, false) {
+        return usage_error(e);
+    }
+    let mechanisms = setup.mechanisms();
+
+    println!("=======================================================");
+    println!("  Price Impact Attribution");
+    println!("  Exogenous decline versus liquidation-driven decline");
+    println!("=======================================================");
+
+    for scenario in setup.scenarios() {
+        let report = match attribute_decline(&mechanisms, scenario, runs, &setup.simulation) {
+            Ok(report) => report,
+            Err(e) => return usage_error(e),
+        };
+        println!();
+        println!("{} ({} runs)", scenario.name(), runs);
+        println!("{}", "-".repeat(50));
+        report.print();
+    }
+
+    EXIT_OK
+}
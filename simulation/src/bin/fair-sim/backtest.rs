@@ -0,0 +1,70 @@
+//! `fair-sim backtest`: backtests the reference threshold strategy across
+//! scenarios and mechanisms. Keeper operators can swap in their own
+//! `KeeperStrategy` through the library API.
+
+use clap::Args;
+
+use fair_simulation::alerts::EXIT_OK;
+use fair_simulation::backtest::run_backtest;
+use fair_simulation::strategy::{KeeperStrategy, ThresholdStrategy};
+
+use crate::{usage_error, MechanismArgs, RunArgs, ScenarioArgs};
+
+const SIMULATION_RUNS: usize = 500;
+
+#[derive(Args, Debug)]
+pub struct BacktestArgs {
+    #[command(flatten)]
+    run: RunArgs,
+    #[command(flatten)]
+    mechanisms: MechanismArgs,
+    #[command(flatten)]
+    scenarios: ScenarioArgs,
+    /// Minimum expected profit after gas before the strategy attempts (USD)
+    #[arg(long, default_value_t = 25.0)]
+    min_profit: f64,
+    /// Gas priority the strategy bids, 0-1
+    #[arg(long, default_value_t = 0.9)]
+    gas_priority: f64,
+}
+
+pub fn run(args: BacktestArgs) -> i32 {
+    let mut setup = match args.run.setup() {
+        Ok(setup) => setup,
+        Err(e) => return usage_error(e),
+    };
+    args.mechanisms.apply(&mut setup);
+    args.scenarios.apply(&mut setup);
+    let runs = setup.runs_or(SIMULATION_RUNS);
+    if let Err(e) = args.run.manifest(&mut setup, runs, false) {
+        return usage_error(e);
+    }
+
+    let mut strategy = ThresholdStrategy {
+        min_profit: args.min_profit,
+        gas_priority: args.gas_priority,
+    };
+
+    println!("=======================================================");
+    println!("  Keeper Strategy Backtest");
+    println!("  Strategy: {}", strategy.name());
+    println!("=======================================================");
+    println!();
+    println!("Parameters:");
+    println!("  Min profit after gas: ${:.0}", strategy.min_profit);
+    println!("  Gas priority:         {:.2}", strategy.gas_priority);
+    println!("  Runs per cell:        {}", runs);
+    println!();
+
+    for scenario in setup.scenarios() {
+        for mechanism in setup.mechanisms() {
+            let report = run_backtest(&mut strategy, mechanism, scenario, runs, &setup.simulation);
+            println!("Scenario: {} | Mechanism: {}", report.scenario.name(), report.mechanism.name());
+            println!("{}", "-".repeat(50));
+            report.print();
+            println!();
+        }
+    }
+
+    EXIT_OK
+}
@@ -0,0 +1,116 @@
+//! `fair-sim budget`: the scenario × mechanism matrix under a wall-clock
+//! budget, with runs beyond a pilot allocated to the least precise cells
+//! and the precision each cell reached reported at the end.
+//!
+//! ```bash
+//! # Whatever five minutes buys across every cell
+//! fair-sim budget --time 5m --seed 42
+//!
+//! # A fixed total run count allocates the same way on every machine
+//! fair-sim budget --total-runs 2000 --scenario volatile --scenario black-swan --seed 42
+//!
+//! # Until every cell's 95% CI on mean bad debt is within ±5%
+//! fair-sim budget --precision 5% --max-runs 20000 --seed 42
+//! ```
+
+use std::time::Duration;
+
+use clap::Args;
+
+use fair_simulation::alerts::EXIT_OK;
+use fair_simulation::budget::{run_budgeted, Budget, BATCH_RUNS, PILOT_RUNS};
+
+use crate::{usage_error, MechanismArgs, ScenarioArgs, SetupArgs};
+
+#[derive(Args, Debug)]
+#[group(id = "limit", required = true, multiple = false, args = ["time", "total_runs", "precision"])]
+pub struct BudgetArgs {
+    #[command(flatten)]
+    setup: SetupArgs,
+    #[command(flatten)]
+    mechanisms: MechanismArgs,
+    #[command(flatten)]
+    scenarios: ScenarioArgs,
+    /// Wall-clock budget, e.g. 90s, 5m, 1h (bare numbers are seconds)
+    #[arg(long, value_parser = parse_duration)]
+    time: Option<Duration>,
+    /// Total runs across every cell, instead of a time budget
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    total_runs: Option<u64>,
+    /// Run each cell until its 95% CI on mean bad debt is within ± this
+    /// share of the mean, e.g. 5% or 0.05
+    #[arg(long, value_parser = parse_precision)]
+    precision: Option<f64>,
+    /// Per-cell run cap under --precision, for tails too heavy to converge
+    #[arg(long, requires = "precision", default_value_t = 10_000, value_parser = clap::value_parser!(u64).range(PILOT_RUNS as u64..))]
+    max_runs: u64,
+}
+
+fn parse_precision(value: &str) -> Result<f64, String> {
+    let (number, scale) = match value.strip_suffix('%') {
+        Some(percent) => (percent, 0.01),
+        None => (value, 1.0),
+    };
+    let number: f64 = number.trim().parse().map_err(|e| format!("invalid precision '{}': {}", value, e))?;
+    let precision = number * scale;
+    if !(precision.is_finite() && precision > 0.0) {
+        return Err(format!("precision '{}' must be positive", value));
+    }
+    Ok(precision)
+}
+
+fn parse_duration(value: &str) -> Result<Duration, String> {
+    let (number, unit) = match value.find(|c: char| c.is_ascii_alphabetic()) {
+        Some(i) => value.split_at(i),
+        None => (value, "s"),
+    };
+    let scale = match unit {
+        "s" => 1.0,
+        "m" => 60.0,
+        "h" => 3600.0,
+        _ => return Err(format!("invalid duration '{}': use s, m or h", value)),
+    };
+    let number: f64 = number.parse().map_err(|e| format!("invalid duration '{}': {}", value, e))?;
+    if !(number.is_finite() && number > 0.0) {
+        return Err(format!("duration '{}' must be positive", value));
+    }
+    Ok(Duration::from_secs_f64(number * scale))
+}
+
+pub fn run(args: BudgetArgs) -> i32 {
+    let mut setup = match args.setup.setup() {
+        Ok(setup) => setup,
+        Err(e) => return usage_error(e),
+    };
+    args.mechanisms.apply(&mut setup);
+    args.scenarios.apply(&mut setup);
+    if let Err(e) = args.setup.manifest(&mut setup, false) {
+        return usage_error(e);
+    }
+    let budget = match (args.time, args.total_runs, args.precision) {
+        (Some(time), _, _) => Budget::Time(time),
+        (None, Some(runs), _) => Budget::Runs(runs as usize),
+        (None, None, Some(relative)) => Budget::Precision { relative, max_runs: args.max_runs as usize },
+        (None, None, None) => unreachable!("clap requires a limit"),
+    };
+
+    println!("=======================================================");
+    println!("  Budgeted Cascade Campaign");
+    match budget {
+        Budget::Time(time) => println!("  Budget: {:.0}s of wall-clock time", time.as_secs_f64()),
+        Budget::Runs(runs) => println!("  Budget: {} runs in total", runs),
+        Budget::Precision { relative, max_runs } => {
+            println!("  Target: 95% CI within ±{:.1}% of mean bad debt, at most {} runs per cell", relative * 100.0, max_runs)
+        }
+    }
+    println!("  Pilot: {} runs per cell, then batches of {} to the noisiest", PILOT_RUNS, BATCH_RUNS);
+    println!("=======================================================");
+    println!();
+
+    let report = match run_budgeted(&setup.scenarios(), &setup.mechanisms(), budget, &setup.simulation) {
+        Ok(report) => report,
+        Err(e) => return usage_error(e),
+    };
+    report.print();
+    EXIT_OK
+}
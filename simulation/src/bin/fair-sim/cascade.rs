@@ -0,0 +1,545 @@
+//! `fair-sim cascade`: deleveraging cascades under each mechanism.
+//!
+//! Compares Fair's keeper pool mechanism vs traditional winner-takes-all
+//! under various stress scenarios.
+//!
+//! ```bash
+//! # Keeper pool at an 80/20 split
+//! fair-sim cascade --mechanism keeper-pool --split 0.8
+//!
+//! # Bad debt vs participation across splits 50/50 to 95/5
+//! fair-sim cascade --sweep-split --scenario flash-crash --seed 1
+//!
+//! # Replay the on-chain CDP book instead of the synthetic one
+//! fair-sim cascade --portfolio cdps.csv
+//!
+//! # Named historical presets instead of the built-in scenarios
+//! fair-sim cascade --preset Mar2020 --preset LunaCollapse
+//!
+//! # Reconcile every run's books (exits 1 if any run does not balance)
+//! fair-sim cascade --audit --seed 1
+//!
+//! # Every run and cell aggregate as JSON, with its manifest alongside
+//! fair-sim cascade --seed 1 --output cascade.json
+//!
+//! # The summary as a LaTeX table for the paper (or Markdown for .md)
+//! fair-sim cascade --seed 1 --table cascades.tex
+//!
+//! # Quantiles and histograms of bad debt, cascade depth and participation
+//! fair-sim cascade --seed 1 --distributions distributions.csv --bins 50
+//!
+//! # Append the setup and every run to a SQLite database (`sqlite` feature)
+//! fair-sim cascade --seed 1 --db research.sqlite
+//!
+//! # Very large campaigns: each run as JSON Lines the moment it completes,
+//! # then the cell aggregates, without holding any runs in memory
+//! fair-sim cascade --seed 1 --runs 1000000 --stream cascade.jsonl
+//!
+//! # Every liquidation of every run as JSON Lines, for auditing a cascade
+//! fair-sim cascade --scenario flash-crash --seed 1 --runs 10 --liquidation-log liquidations.jsonl
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use clap::Args;
+
+use fair_simulation::alerts::{EXIT_OK, EXIT_VIOLATION};
+use fair_simulation::audit::flag_discrepancies;
+use fair_simulation::batch::{Batch, BatchResults, Job};
+use fair_simulation::cascade::{
+    run_cascade_simulation_with_liquidations, run_cascade_streaming, aggregate_results,
+    sweep_pool_split, AggregatedCascadeResult, CascadeResult, LiquidationMechanism, PriceScenario,
+    SPLIT_SWEEP,
+};
+use fair_simulation::config::SimulationSetup;
+use fair_simulation::distribution::{self, summarize_cells, DistributionConfig, DEFAULT_BINS, DEFAULT_QUANTILES};
+use fair_simulation::manifest::{sidecar_path, RunManifest};
+use fair_simulation::outliers::{worst_runs, DEFAULT_OUTLIERS};
+use fair_simulation::portfolio::CdpPortfolio;
+use fair_simulation::report::table::cascade_summary;
+use fair_simulation::results::{save_jsonl, CascadeCell, CascadeStream, LoggedLiquidation};
+use fair_simulation::scenarios::ScenarioPreset;
+
+use crate::{
+    parse_preset, parse_table_path, print_outliers, repro_command, save_results, save_table, usage_error, MechanismArgs,
+    RunArgs, ScenarioArgs,
+};
+
+const SIMULATION_RUNS: usize = 1000;
+
+/// Runs per cell of the summary table.
+const COMPARISON_RUNS: usize = 100;
+
+#[derive(Args, Debug)]
+pub struct CascadeArgs {
+    #[command(flatten)]
+    run: RunArgs,
+    #[command(flatten)]
+    mechanisms: MechanismArgs,
+    #[command(flatten)]
+    scenarios: ScenarioArgs,
+    /// Sweep the keeper pool split from 50/50 to 95/5 instead
+    #[arg(long, conflicts_with_all = ["mechanisms", "split", "portfolio"])]
+    sweep_split: bool,
+    /// CDP book (.csv or .json) to replay instead of the synthetic one
+    #[arg(long)]
+    portfolio: Option<PathBuf>,
+    /// Named preset (repeatable), e.g. Mar2020, LunaCollapse, SlowBleed2022, FlashCrashRecovery, LiquidityCrunch
+    #[arg(long = "preset", value_parser = parse_preset, conflicts_with_all = ["scenarios", "sweep_split", "portfolio"])]
+    presets: Vec<ScenarioPreset>,
+    /// Worst runs per cell to list with a reproduction command (0 = none)
+    #[arg(long, default_value_t = DEFAULT_OUTLIERS)]
+    outliers: usize,
+    /// Reconcile collateral, debt and penalty flows of every run
+    #[arg(long, conflicts_with = "sweep_split")]
+    audit: bool,
+    /// Tabulate each cell's wall time, RNG draws and allocations per run
+    #[arg(long)]
+    compute: bool,
+    /// Save every run and cell aggregate as JSON (see `results::CascadeCell`)
+    #[arg(long)]
+    output: Option<PathBuf>,
+    /// Write every cell's summary as a Markdown (.md) or LaTeX (.tex) table
+    #[arg(long, value_parser = parse_table_path)]
+    table: Option<PathBuf>,
+    /// Save quantiles and histograms of every cell's metrics as JSON, or CSV for .csv
+    #[arg(long, conflicts_with_all = ["sweep_split", "stream"])]
+    distributions: Option<PathBuf>,
+    /// Histogram bins for --distributions
+    #[arg(long, default_value_t = DEFAULT_BINS, requires = "distributions")]
+    bins: usize,
+    /// Quantiles for --distributions [default: 0.01,0.05,0.25,0.5,0.75,0.95,0.99]
+    #[arg(long, value_delimiter = ',', requires = "distributions")]
+    quantiles: Vec<f64>,
+    /// Append the setup and every run's metrics to this SQLite database (`sqlite` feature)
+    #[arg(long, env = "FAIR_SIM_DB", conflicts_with = "sweep_split")]
+    db: Option<PathBuf>,
+    /// Log every liquidation as JSON Lines (see `results::LoggedLiquidation`)
+    #[arg(long, conflicts_with_all = ["sweep_split", "presets", "portfolio"])]
+    liquidation_log: Option<PathBuf>,
+    /// Stream every run as JSON Lines as it completes, then the cell aggregates (see `results::StreamedLine`)
+    #[arg(
+        long,
+        conflicts_with_all = ["sweep_split", "presets", "portfolio", "audit", "output", "db", "liquidation_log"]
+    )]
+    stream: Option<PathBuf>,
+}
+
+/// Saves the campaign's cells if `--output` was given, records them if
+/// `--db` was, and saves their distributions and summary if
+/// `--distributions` and `--table` were.
+fn save_cells(args: &CascadeArgs, cells: &[CascadeCell], manifest: Option<&RunManifest>) -> Result<(), String> {
+    if let Some(path) = &args.output {
+        save_results(path, cells, manifest)?;
+    }
+    if let Some(path) = &args.distributions {
+        summarize_cells(cells, &distribution_config(args))
+            .and_then(|summaries| distribution::save(path, &summaries))
+            .map_err(|e| format!("failed to save distributions to {}: {}", path.display(), e))?;
+        println!("Saved distributions to {}", path.display());
+    }
+    #[cfg(feature = "sqlite")]
+    if let (Some(path), Some(manifest)) = (&args.db, manifest) {
+        crate::record_campaign(path, |db| db.record_cascade(manifest, cells))?;
+    }
+    match &args.table {
+        Some(path) => save_table(path, &cascade_summary(cells)),
+        None => Ok(()),
+    }
+}
+
+fn distribution_config(args: &CascadeArgs) -> DistributionConfig {
+    let quantiles = if args.quantiles.is_empty() { DEFAULT_QUANTILES.to_vec() } else { args.quantiles.clone() };
+    DistributionConfig { quantiles, bins: args.bins }
+}
+
+/// Prints the cell's audit summary and each unbalanced run; true if all reconcile.
+fn print_audit(results: &[CascadeResult]) -> bool {
+    let flagged = flag_discrepancies(results);
+    println!("Audit: {}/{} runs reconcile", results.len() - flagged.len(), results.len());
+    for (result, report) in &flagged {
+        match result.seed {
+            Some(seed) => println!("  Run seed {}:", seed),
+            None => println!("  Unseeded run:"),
+        }
+        report.print();
+    }
+    flagged.is_empty()
+}
+
+pub fn run(args: CascadeArgs) -> i32 {
+    let mut setup = match args.run.setup() {
+        Ok(setup) => setup,
+        Err(e) => return usage_error(e),
+    };
+    args.mechanisms.apply(&mut setup);
+    args.scenarios.apply(&mut setup);
+    let portfolio = match args.portfolio.as_deref().map(CdpPortfolio::load).transpose() {
+        Ok(portfolio) => portfolio,
+        Err(e) => return usage_error(format!("failed to load portfolio: {}", e)),
+    };
+
+    if cfg!(not(feature = "sqlite")) && args.db.is_some() {
+        return usage_error("--db needs the `sqlite` feature (--features sqlite)");
+    }
+
+    if let Err(e) = distribution_config(&args).validate() {
+        return usage_error(e);
+    }
+
+    let runs = setup.runs_or(SIMULATION_RUNS);
+    let wants_manifest = args.output.is_some() || args.db.is_some() || args.stream.is_some();
+    let manifest = match args.run.manifest(&mut setup, runs, wants_manifest) {
+        Ok(manifest) => manifest,
+        Err(e) => return usage_error(e),
+    };
+    let config = &setup.simulation;
+
+    println!("=======================================================");
+    println!("  Deleveraging Cascade Simulation");
+    println!("  Comparing Fair vs Traditional Liquidation");
+    println!("=======================================================");
+    println!();
+    println!("Parameters:");
+    match &portfolio {
+        Some(portfolio) => {
+            println!("  Keepers: {}, Runs: {}", config.keepers.count, runs);
+            portfolio.print(config.initial_eth_price);
+        }
+        None => println!(
+            "  CDPs: {}, Keepers: {}, Runs: {}",
+            config.num_cdps, config.keepers.count, runs
+        ),
+    }
+    println!("  Liquidations per block: {}", config.liquidations_per_block);
+    println!("  Price impact: {}% per ETH sold", config.price_impact_per_eth * 100.0);
+    if config.severity != 1.0 {
+        println!("  Scenario severity: {}x", config.severity);
+    }
+    println!();
+
+    if args.sweep_split {
+        return run_split_sweep(&args, &setup, runs, manifest.as_ref());
+    }
+    if !args.presets.is_empty() {
+        return run_presets(&args, &setup, runs, manifest.as_ref());
+    }
+    if let Some(path) = &args.stream {
+        return match run_streamed(path, &args, &setup, runs, manifest.as_ref()) {
+            Ok(()) => EXIT_OK,
+            Err(e) => usage_error(e),
+        };
+    }
+
+    // One batch for the campaign and the summary table's 100-run cells,
+    // which share the campaign's runs at --runs 100
+    let campaign = Job::matrix(&setup.scenarios(), &setup.mechanisms(), runs);
+    let mut jobs = if args.liquidation_log.is_some() { Vec::new() } else { campaign.clone() };
+    let summary_start = jobs.len();
+    jobs.extend(Job::matrix(&setup.scenarios(), &setup.mechanisms(), COMPARISON_RUNS));
+    let batch = match Batch::new(jobs).run(config, portfolio.as_ref()) {
+        Ok(batch) => batch,
+        Err(e) => return usage_error(e),
+    };
+
+    let mut cells = Vec::new();
+    let mut liquidations = Vec::new();
+    let mut balanced = true;
+    let mut previous_scenario = None;
+    for (i, &Job { mechanism, scenario, .. }) in campaign.iter().enumerate() {
+        if previous_scenario != Some(scenario) {
+            println!("=======================================================");
+            println!("Scenario: {}", scenario.name());
+            println!("=======================================================");
+            println!();
+            previous_scenario = Some(scenario);
+        }
+
+        println!("Mechanism: {}", mechanism.name());
+        println!("{}", "-".repeat(50));
+
+        let results = if args.liquidation_log.is_some() {
+            let (results, logs) = run_cascade_simulation_with_liquidations(mechanism, scenario, runs, config);
+            liquidations.extend(LoggedLiquidation::from_runs(scenario, mechanism, &results, &logs));
+            results
+        } else {
+            batch.results(i).to_vec()
+        };
+        let agg = aggregate_results(&results);
+        agg.print();
+        print_outliers(&worst_runs(&results, args.outliers), |seed| {
+            repro_command(args.run.config_path(), args.portfolio.as_deref(), mechanism, scenario, seed)
+        });
+        if args.audit {
+            balanced &= print_audit(&results);
+        }
+        println!();
+        cells.push(CascadeCell { label: scenario.name().to_string(), aggregate: agg, results });
+    }
+
+    println!("=======================================================");
+    println!("  Summary: Fair vs Traditional");
+    println!("=======================================================");
+    println!();
+    
+    print_comparison_table(&batch, summary_start);
+    if args.compute {
+        print_compute(&cells);
+    }
+
+    if let Err(e) = save_cells(&args, &cells, manifest.as_ref()) {
+        return usage_error(e);
+    }
+    if let Some(path) = &args.liquidation_log {
+        if let Err(e) = save_jsonl(path, &liquidations) {
+            return usage_error(format!("failed to save liquidation log to {}: {}", path.display(), e));
+        }
+        println!("Saved {} liquidations to {}", liquidations.len(), path.display());
+    }
+
+    if balanced { EXIT_OK } else { EXIT_VIOLATION }
+}
+
+/// Runs every cell through a `CascadeStream`, printing each cell's
+/// aggregate once its last run is written.
+fn run_streamed(
+    path: &Path,
+    args: &CascadeArgs,
+    setup: &SimulationSetup,
+    runs: usize,
+    manifest: Option<&RunManifest>,
+) -> Result<(), String> {
+    let failed = |e: fair_simulation::error::Error| format!("failed to stream results to {}: {}", path.display(), e);
+    let mut stream = CascadeStream::create(path).map_err(failed)?;
+    for scenario in setup.scenarios() {
+        println!("=======================================================");
+        println!("Scenario: {}", scenario.name());
+        println!("=======================================================");
+        println!();
+
+        for mechanism in setup.mechanisms() {
+            println!("Mechanism: {}", mechanism.name());
+            println!("{}", "-".repeat(50));
+            run_cascade_streaming(mechanism, scenario, runs, &setup.simulation, |result| {
+                stream.write_run(scenario.name(), &result)
+            })
+            .map_err(failed)?;
+            if let Some(aggregate) = stream.aggregate(scenario.name(), mechanism) {
+                aggregate.print();
+            }
+            println!();
+        }
+    }
+
+    let cells = stream.finish().map_err(failed)?;
+    if args.compute {
+        print_compute(&cells);
+    }
+    if let Some(manifest) = manifest {
+        manifest.save(sidecar_path(path)).map_err(failed)?;
+    }
+    println!(
+        "Streamed {} runs of {} cells to {} (manifest: {})",
+        runs * cells.len(),
+        cells.len(),
+        path.display(),
+        sidecar_path(path).display()
+    );
+    match &args.table {
+        Some(table) => save_table(table, &cascade_summary(&cells)),
+        None => Ok(()),
+    }
+}
+
+fn run_presets(args: &CascadeArgs, setup: &SimulationSetup, runs: usize, manifest: Option<&RunManifest>) -> i32 {
+    let mut cells = Vec::new();
+    let mut balanced = true;
+    for &preset in &args.presets {
+        println!("=======================================================");
+        println!("Preset: {}", preset);
+        println!("  {}", preset.description());
+        println!("=======================================================");
+        println!();
+
+        for mechanism in setup.mechanisms() {
+            println!("Mechanism: {}", mechanism.name());
+            println!("{}", "-".repeat(50));
+
+            let results = match preset.run(mechanism, runs, &setup.simulation) {
+                Ok(results) => results,
+                Err(e) => return usage_error(e),
+            };
+            let agg = aggregate_results(&results);
+            agg.print();
+            print_outliers(&worst_runs(&results, args.outliers), |seed| {
+                repro_command(args.run.config_path(), None, mechanism, preset, seed)
+            });
+            if args.audit {
+                balanced &= print_audit(&results);
+            }
+            println!();
+            cells.push(CascadeCell { label: preset.name().to_string(), aggregate: agg, results });
+        }
+    }
+
+    println!("=======================================================");
+    println!("  Summary: Fair vs Traditional");
+    println!("=======================================================");
+    println!();
+    print_band_header("Preset");
+    for cell in &cells {
+        print_band_row(&cell.label, &cell.aggregate);
+    }
+    print_band_legend();
+    if args.compute {
+        print_compute(&cells);
+    }
+
+    if let Err(e) = save_cells(args, &cells, manifest) {
+        return usage_error(e);
+    }
+
+    if balanced { EXIT_OK } else { EXIT_VIOLATION }
+}
+
+fn run_split_sweep(args: &CascadeArgs, setup: &SimulationSetup, runs: usize, manifest: Option<&RunManifest>) -> i32 {
+    let mut cells = Vec::new();
+    for scenario in setup.scenarios() {
+        println!("=======================================================");
+        println!("Split Sweep: {}", scenario.name());
+        println!("=======================================================");
+        println!();
+
+        let sweep = match sweep_pool_split(scenario, &SPLIT_SWEEP, runs, &setup.simulation) {
+            Ok(sweep) => sweep,
+            Err(e) => return usage_error(e),
+        };
+        println!("| Split | Bad Debt (p5 / p50 / p95)    | P(Bad Debt) | Participation (p5 / p50 / p95) | Liquidations    | Protocol Revenue (p5 / p50 / p95) |");
+        println!("|-------|------------------------------|-------------|--------------------------------|-----------------|-----------------------------------|");
+        for agg in &sweep {
+            let split = agg.mechanism.keeper_share();
+            let label = format!("{:.0}/{:.0}", split * 100.0, (1.0 - split) * 100.0);
+            let bands = &agg.bands;
+            println!(
+                "| {:5} | {:28} | {:10.1}% | {:30} | {:15} | {:33} |",
+                label,
+                bands.bad_debt.format(dollars),
+                agg.bad_debt_frequency * 100.0,
+                bands.participation_rate.format(percent),
+                bands.liquidations.format(|v| format!("{:.0}", v)),
+                bands.protocol_revenue.format(dollars),
+            );
+        }
+        print_band_legend();
+        println!();
+        cells.extend(sweep.into_iter().map(|aggregate| CascadeCell {
+            label: format!("{} @ {:.2}", scenario.name(), aggregate.mechanism.keeper_share()),
+            aggregate,
+            results: Vec::new(),
+        }));
+    }
+    if args.compute {
+        print_compute(&cells);
+    }
+
+    if let Err(e) = save_cells(args, &cells, manifest) {
+        return usage_error(e);
+    }
+
+    EXIT_OK
+}
+
+/// Per-cell compute, with each cell's share of the campaign's run time.
+fn print_compute(cells: &[CascadeCell]) {
+    let total: f64 = cells.iter().map(|c| c.aggregate.compute.wall_secs).sum();
+    println!();
+    println!("| Cell                                     | Mechanism   |  Runs | Run Time | Share | ms/run | RNG draws/run | Allocs/run |");
+    println!("|------------------------------------------|-------------|-------|----------|-------|--------|---------------|------------|");
+    for cell in cells {
+        let compute = &cell.aggregate.compute;
+        println!(
+            "| {:40} | {:11} | {:5} | {:7.2}s | {:4.0}% | {:6.2} | {:13.0} | {:>10} |",
+            cell.label,
+            cell.aggregate.mechanism.info().short_name,
+            compute.runs,
+            compute.wall_secs,
+            compute.wall_secs / total.max(f64::MIN_POSITIVE) * 100.0,
+            compute.mean_wall_secs() * 1000.0,
+            compute.mean_rng_draws(),
+            compute.mean_allocations().map_or("-".to_string(), |a| format!("{:.0}", a)),
+        );
+    }
+    println!();
+    println!("Run time is summed over runs: CPU time when runs share a thread pool.");
+}
+
+fn print_comparison_table(batch: &BatchResults, start: usize) {
+    print_band_header("Scenario");
+
+    let mut rows: Vec<(&str, AggregatedCascadeResult)> = Vec::new();
+    for (i, job) in batch.jobs.iter().enumerate().skip(start) {
+        let scenario = job.scenario;
+        let agg = batch.aggregate(i);
+
+        let scenario_name = match scenario {
+            PriceScenario::GradualDecline => "Gradual",
+            PriceScenario::FlashCrash => "Flash",
+            PriceScenario::VolatileCrash => "Volatile",
+            PriceScenario::BlackSwan => "Black Swan",
+        };
+
+        print_band_row(scenario_name, &agg);
+        rows.push((scenario_name, agg));
+    }
+    print_band_legend();
+
+    // A mechanism's mean bad debt only differs from Traditional's when the
+    // bands separate; overlapping bands are within run-to-run noise.
+    let within_noise: Vec<String> = rows
+        .iter()
+        .filter(|(_, agg)| agg.mechanism != LiquidationMechanism::Traditional)
+        .filter_map(|(scenario, agg)| {
+            let traditional = rows.iter().find(|(s, other)| {
+                s == scenario && other.mechanism == LiquidationMechanism::Traditional
+            })?;
+            agg.bands.bad_debt.overlaps(&traditional.1.bands.bad_debt).then(|| {
+                format!("{} ({})", scenario, agg.mechanism.info().short_name)
+            })
+        })
+        .collect();
+    if !within_noise.is_empty() {
+        println!(
+            "Bad debt bands overlap Traditional's (difference within run-to-run spread): {}",
+            within_noise.join(", ")
+        );
+    }
+}
+
+fn print_band_header(label: &str) {
+    println!("| {:19} | Mechanism   | Bad Debt (p5 / p50 / p95)    | Participation (p5 / p50 / p95) | Concentration (p5 / p50 / p95) |", label);
+    println!("|---------------------|-------------|------------------------------|--------------------------------|--------------------------------|");
+}
+
+fn print_band_row(label: &str, agg: &AggregatedCascadeResult) {
+    println!(
+        "| {:19} | {:11} | {:28} | {:30} | {:30} |",
+        label,
+        agg.mechanism.info().short_name,
+        agg.bands.bad_debt.format(dollars),
+        agg.bands.participation_rate.format(percent),
+        agg.bands.profit_concentration.format(percent),
+    );
+}
+
+fn print_band_legend() {
+    println!();
+    println!("Bands are the 5th / 50th / 95th percentiles over runs; differences inside overlapping bands are run-to-run noise.");
+}
+
+fn dollars(value: f64) -> String {
+    format!("${:.0}", value)
+}
+
+fn percent(value: f64) -> String {
+    format!("{:.1}%", value * 100.0)
+}
@@ -0,0 +1,73 @@
+//! `fair-sim compare`: loads two saved result sets, Monte Carlo or cascade,
+//! and reports every metric delta with its significance and a 95% interval
+//! on the relative change, then lists the changes that are significant.
+//!
+//! ```bash
+//! fair-sim cascade --seed 1 --output before.json
+//! fair-sim cascade --seed 1 --config lower-penalty.toml --output after.json
+//! fair-sim compare before.json after.json
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use clap::Args;
+
+use fair_simulation::alerts::{EXIT_OK, EXIT_VIOLATION};
+use fair_simulation::diff::{diff_cascades, diff_results, to_markdown, CellDiff, Significance};
+use fair_simulation::monte_carlo::MonteCarloResult;
+use fair_simulation::results::{load_json, CascadeCell};
+
+use crate::usage_error;
+
+#[derive(Args, Debug)]
+pub struct CompareArgs {
+    /// Baseline result set (`monte-carlo --output` or `cascade --output`)
+    before: PathBuf,
+    /// Result set of the same kind to compare against the baseline
+    after: PathBuf,
+}
+
+enum ResultSet {
+    MonteCarlo(Vec<MonteCarloResult>),
+    Cascade(Vec<CascadeCell>),
+}
+
+fn load(path: &Path) -> Result<ResultSet, String> {
+    if let Ok(results) = load_json(path) {
+        return Ok(ResultSet::MonteCarlo(results));
+    }
+    load_json(path)
+        .map(ResultSet::Cascade)
+        .map_err(|e| format!("failed to load {} as Monte Carlo or cascade results: {}", path.display(), e))
+}
+
+pub fn run(args: CompareArgs) -> i32 {
+    let diffs: Vec<CellDiff> = match (load(&args.before), load(&args.after)) {
+        (Ok(ResultSet::MonteCarlo(before)), Ok(ResultSet::MonteCarlo(after))) => diff_results(&before, &after),
+        (Ok(ResultSet::Cascade(before)), Ok(ResultSet::Cascade(after))) => diff_cascades(&before, &after),
+        (Ok(_), Ok(_)) => return usage_error("cannot compare a Monte Carlo result set with a cascade result set"),
+        (Err(e), _) | (_, Err(e)) => return usage_error(e),
+    };
+    if diffs.is_empty() {
+        eprintln!("no matching cells with per-run results between the two result sets");
+        return EXIT_VIOLATION;
+    }
+
+    println!("## Comparison: `{}` → `{}`", args.before.display(), args.after.display());
+    println!();
+    print!("{}", to_markdown(&diffs));
+    println!();
+    println!("### Significant changes (p < 0.05)");
+    println!();
+    let mut any = false;
+    for cell in &diffs {
+        for delta in cell.deltas.iter().filter(|d| matches!(d.significance, Significance::Strong | Significance::Weak)) {
+            println!("- {} / {}: {}", cell.label, cell.mechanism.name(), delta.describe());
+            any = true;
+        }
+    }
+    if !any {
+        println!("_None._");
+    }
+    EXIT_OK
+}
@@ -0,0 +1,59 @@
+//! `fair-sim contagion`: how much of Fair's bad debt comes from a neighboring
+//! lending protocol deleveraging into the same collateral market. Configure
+//! the neighbor under `[simulation.neighbor]` in `--config`.
+//!
+//! ```bash
+//! fair-sim contagion --runs 200 --scenario black-swan --seed 4
+//! ```
+
+use clap::Args;
+
+use fair_simulation::alerts::EXIT_OK;
+use fair_simulation::contagion::measure_contagion;
+
+use crate::{usage_error, MechanismArgs, RunArgs, ScenarioArgs};
+
+const DEFAULT_RUNS: usize = 100;
+
+#[derive(Args, Debug)]
+pub struct ContagionArgs {
+    #[command(flatten)]
+    run: RunArgs,
+    #[command(flatten)]
+    mechanisms: MechanismArgs,
+    #[command(flatten)]
+    scenarios: ScenarioArgs,
+}
+
+pub fn run(args: ContagionArgs) -> i32 {
+    let mut setup = match args.run.setup() {
+        Ok(setup) => setup,
+        Err(e) => return usage_error(e),
+    };
+    args.mechanisms.apply(&mut setup);
+    args.scenarios.apply(&mut setup);
+    let runs = setup.runs_or(DEFAULT_RUNS);
+    if let Err(e) = args.run.manifest(&mut setup, runs, false) {
+        return usage_error(e);
+    }
+
+    println!("=======================================================");
+    println!("  Cross-Protocol Contagion");
+    println!("  Fair bad debt with and without a neighbor deleveraging");
+    println!("=======================================================");
+
+    for mechanism in setup.mechanisms() {
+        let results = match measure_contagion(mechanism, &setup.scenarios(), runs, &setup.simulation) {
+            Ok(results) => results,
+            Err(e) => return usage_error(e),
+        };
+        for result in results {
+            println!();
+            println!("{} / {} ({} runs)", mechanism.name(), result.scenario.name(), runs);
+            println!("{}", "-".repeat(50));
+            result.print();
+        }
+    }
+
+    EXIT_OK
+}
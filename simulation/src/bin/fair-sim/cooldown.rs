@@ -0,0 +1,80 @@
+//! `fair-sim cooldown`: per-borrower liquidation rate limits against
+//! liquidating whole CDPs, pricing borrower protection in bad debt.
+//!
+//! ```bash
+//! fair-sim cooldown --scenario flash-crash --runs 200 --seed 5
+//!
+//! # Closing a third of the debt at each of the default cooldowns
+//! fair-sim cooldown --mechanism keeper-pool --close-factor 0.33
+//! ```
+//!
+//! A `liquidation_cooldown` table in the config file is evaluated
+//! alongside the default policies.
+
+use clap::Args;
+
+use fair_simulation::alerts::EXIT_OK;
+use fair_simulation::cascade::LiquidationCooldown;
+use fair_simulation::cooldown::{compare_cooldowns, COOLDOWN_POLICIES};
+
+use crate::{usage_error, MechanismArgs, RunArgs, ScenarioArgs};
+
+const DEFAULT_RUNS: usize = 100;
+
+#[derive(Args, Debug)]
+pub struct CooldownArgs {
+    #[command(flatten)]
+    run: RunArgs,
+    #[command(flatten)]
+    mechanisms: MechanismArgs,
+    #[command(flatten)]
+    scenarios: ScenarioArgs,
+    /// Share of the debt each liquidation closes, at 1, 5 and 20 block cooldowns [default: 0.5 and 0.25]
+    #[arg(long)]
+    close_factor: Option<f64>,
+}
+
+pub fn run(args: CooldownArgs) -> i32 {
+    let mut setup = match args.run.setup() {
+        Ok(setup) => setup,
+        Err(e) => return usage_error(e),
+    };
+    args.mechanisms.apply(&mut setup);
+    args.scenarios.apply(&mut setup);
+    let runs = setup.runs_or(DEFAULT_RUNS);
+    if let Err(e) = args.run.manifest(&mut setup, runs, false) {
+        return usage_error(e);
+    }
+    let mut policies = match args.close_factor {
+        Some(close_factor) => [1, 5, 20]
+            .into_iter()
+            .map(|cooldown_blocks| LiquidationCooldown { close_factor, cooldown_blocks })
+            .collect(),
+        None => COOLDOWN_POLICIES.to_vec(),
+    };
+    if let Some(custom) = setup.simulation.liquidation_cooldown.take() {
+        if !policies.contains(&custom) {
+            policies.push(custom);
+        }
+    }
+
+    println!("=======================================================");
+    println!("  Liquidation Cooldowns");
+    println!("  Borrower protection vs bad debt under per-borrower rate limits");
+    println!("=======================================================");
+
+    for scenario in setup.scenarios() {
+        for mechanism in setup.mechanisms() {
+            let report = match compare_cooldowns(mechanism, scenario, &policies, runs, &setup.simulation) {
+                Ok(report) => report,
+                Err(e) => return usage_error(e),
+            };
+            println!();
+            println!("{} / {} ({} runs)", mechanism.name(), scenario.name(), runs);
+            println!("{}", "-".repeat(50));
+            report.print();
+        }
+    }
+
+    EXIT_OK
+}
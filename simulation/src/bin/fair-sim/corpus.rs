@@ -0,0 +1,75 @@
+//! `fair-sim corpus`: generates a price model's paths and run seeds once and
+//! saves them as JSON, for `monte-carlo --corpus` to replay against any
+//! mechanism and configuration.
+//!
+//! ```bash
+//! fair-sim corpus --model jump-diffusion --runs 100000 --seed 42 --output jd.json
+//!
+//! # On the GPU (`gpu` feature)
+//! fair-sim corpus --model gbm --runs 1000000 --seed 42 --paths gpu --output gbm.json
+//! ```
+//!
+//! Paths span the config's `max_blocks`. A corpus generated on a seed holds
+//! the runs `monte-carlo --paths` draws on that seed.
+
+use std::path::PathBuf;
+
+use clap::Args;
+
+use fair_simulation::alerts::EXIT_OK;
+use fair_simulation::monte_carlo::PricePathConfig;
+use fair_simulation::path_backend::PathCorpus;
+
+use crate::monte_carlo::{path_backend, PathSource};
+use crate::{usage_error, Model, RunArgs};
+
+const DEFAULT_RUNS: usize = 10_000;
+
+#[derive(Args, Debug)]
+pub struct CorpusArgs {
+    #[command(flatten)]
+    run: RunArgs,
+    /// Price model the paths follow
+    #[arg(long, value_enum, default_value = "gbm")]
+    model: Model,
+    /// Where the paths are generated
+    #[arg(long, value_enum, default_value = "cpu")]
+    paths: PathSource,
+    /// Corpus file to write
+    #[arg(long)]
+    output: PathBuf,
+}
+
+pub fn run(args: CorpusArgs) -> i32 {
+    let mut setup = match args.run.setup() {
+        Ok(setup) => setup,
+        Err(e) => return usage_error(e),
+    };
+    let runs = setup.runs_or(DEFAULT_RUNS);
+    if let Err(e) = args.run.manifest(&mut setup, runs, false) {
+        return usage_error(e);
+    }
+    let mut backend = match path_backend(args.paths) {
+        Ok(backend) => backend,
+        Err(e) => return usage_error(e),
+    };
+    let path_config = PricePathConfig { model: args.model.into(), blocks: setup.simulation.max_blocks, ..Default::default() };
+
+    let corpus = match PathCorpus::generate(backend.as_mut(), &path_config, runs, &setup.simulation) {
+        Ok(corpus) => corpus,
+        Err(e) => return usage_error(format!("failed to generate paths: {}", e)),
+    };
+    if let Err(e) = corpus.save(&args.output) {
+        return usage_error(format!("failed to save corpus to {}: {}", args.output.display(), e));
+    }
+    println!(
+        "Saved {} {} paths of {} blocks ({}) to {}",
+        corpus.len(),
+        path_config.model.name(),
+        path_config.blocks,
+        corpus.backend,
+        args.output.display()
+    );
+
+    EXIT_OK
+}
@@ -0,0 +1,245 @@
+//! `fair-sim dashboard`: watches a single cascade run block by block: price,
+//! liquidation queue, keeper PnL, and the protocol's net buffer (retained
+//! penalties minus bad debt). Requires the `tui` feature.
+//!
+//! ```bash
+//! cargo run --features tui --release -- dashboard \
+//!     --scenario black-swan --mechanism traditional --delay-ms 100
+//! ```
+//!
+//! Without `--scenario`/`--mechanism`, the first entries of the `--config`
+//! file are used. Press `q` to quit.
+
+use std::time::Duration;
+
+use clap::Args;
+use ratatui::crossterm::event::{self, Event, KeyCode};
+use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::style::{Color, Style};
+use ratatui::symbols;
+use ratatui::widgets::{Axis, Bar, BarChart, BarGroup, Block, Borders, Chart, Dataset, Gauge, Paragraph, Sparkline};
+use ratatui::Frame;
+
+use fair_simulation::alerts::EXIT_OK;
+use fair_simulation::cascade::{run_single_observed, LiquidationMechanism, LiveState, PriceScenario};
+
+use crate::{mechanism_parser, usage_error, Scenario, SetupArgs};
+
+const DEFAULT_DELAY_MS: u64 = 150;
+const TOP_KEEPERS: usize = 10;
+
+#[derive(Args, Debug)]
+pub struct DashboardArgs {
+    #[command(flatten)]
+    setup: SetupArgs,
+    /// Price scenario [default: flash-crash]
+    #[arg(long, value_enum)]
+    scenario: Option<Scenario>,
+    /// Liquidation mechanism [default: keeper-pool]
+    #[arg(long, value_parser = mechanism_parser())]
+    mechanism: Option<LiquidationMechanism>,
+    /// Pause between blocks in milliseconds
+    #[arg(long, default_value_t = DEFAULT_DELAY_MS)]
+    delay_ms: u64,
+}
+
+struct Dashboard {
+    scenario: PriceScenario,
+    mechanism: LiquidationMechanism,
+    prices: Vec<(f64, f64)>,
+    queue: Vec<u64>,
+    latest: Option<LiveState>,
+    finished: bool,
+}
+
+impl Dashboard {
+    fn update(&mut self, state: &LiveState) {
+        if self.prices.is_empty() {
+            self.prices.push((0.0, state.event.price_start));
+        }
+        self.prices.push(((state.event.block + 1) as f64, state.event.price_end));
+        self.queue.push(state.event.queue_after() as u64);
+        self.latest = Some(state.clone());
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(10), Constraint::Length(8)])
+            .split(frame.area());
+        let middle = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+            .split(rows[1]);
+        let bottom = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+            .split(rows[2]);
+
+        let status = if self.finished { "finished - press q to quit" } else { "running - press q to quit" };
+        let block = self.latest.as_ref().map_or(0, |s| s.event.block);
+        frame.render_widget(
+            Paragraph::new(format!(
+                "{} | {} | block {} | {}",
+                self.scenario.name(),
+                self.mechanism.name(),
+                block,
+                status
+            ))
+            .block(Block::default().borders(Borders::ALL).title("Fair Cascade Dashboard")),
+            rows[0],
+        );
+
+        self.render_price(frame, middle[0]);
+        self.render_keepers(frame, middle[1]);
+        self.render_queue(frame, bottom[0]);
+        self.render_buffer(frame, bottom[1]);
+    }
+
+    fn render_price(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let max_x = self.prices.last().map_or(1.0, |p| p.0.max(1.0));
+        let (min_y, max_y) = self
+            .prices
+            .iter()
+            .fold((f64::MAX, f64::MIN), |(lo, hi), p| (lo.min(p.1), hi.max(p.1)));
+        let (min_y, max_y) = if self.prices.is_empty() { (0.0, 1.0) } else { (min_y * 0.95, max_y * 1.05) };
+
+        let dataset = Dataset::default()
+            .name("ETH")
+            .marker(symbols::Marker::Braille)
+            .style(Style::default().fg(Color::Cyan))
+            .data(&self.prices);
+        let chart = Chart::new(vec![dataset])
+            .block(Block::default().borders(Borders::ALL).title("ETH price"))
+            .x_axis(Axis::default().bounds([0.0, max_x]).labels(["0".to_string(), format!("{:.0}", max_x)]))
+            .y_axis(
+                Axis::default()
+                    .bounds([min_y, max_y])
+                    .labels([format!("${:.0}", min_y), format!("${:.0}", max_y)]),
+            );
+        frame.render_widget(chart, area);
+    }
+
+    fn render_keepers(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let mut profits: Vec<(usize, f64)> = self
+            .latest
+            .as_ref()
+            .map(|s| s.keeper_profits.iter().copied().enumerate().collect())
+            .unwrap_or_default();
+        profits.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+
+        let bars: Vec<Bar> = profits
+            .iter()
+            .take(TOP_KEEPERS)
+            .map(|(id, profit)| {
+                Bar::default()
+                    .label(format!("k{}", id))
+                    .value(*profit as u64)
+                    .text_value(format!("${:.0}", profit))
+            })
+            .collect();
+        let chart = BarChart::default()
+            .block(Block::default().borders(Borders::ALL).title("Keeper PnL (top 10)"))
+            .direction(Direction::Horizontal)
+            .bar_width(1)
+            .bar_gap(0)
+            .data(BarGroup::default().bars(&bars));
+        frame.render_widget(chart, area);
+    }
+
+    fn render_queue(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let title = match &self.latest {
+            Some(s) => format!(
+                "Liquidation queue: {} pending ({} liquidatable, {} cleared this block)",
+                s.event.queue_after(),
+                s.event.liquidatable,
+                s.event.liquidated
+            ),
+            None => "Liquidation queue".to_string(),
+        };
+        let sparkline = Sparkline::default()
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .style(Style::default().fg(Color::Yellow))
+            .data(&self.queue);
+        frame.render_widget(sparkline, area);
+    }
+
+    fn render_buffer(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let (revenue, bad_debt) = self
+            .latest
+            .as_ref()
+            .map_or((0.0, 0.0), |s| (s.protocol_revenue, s.event.bad_debt));
+        let net = revenue - bad_debt;
+        let ratio = if revenue > 0.0 { (net / revenue).clamp(0.0, 1.0) } else { 0.0 };
+        let color = if net >= 0.0 { Color::Green } else { Color::Red };
+
+        let gauge = Gauge::default()
+            .block(Block::default().borders(Borders::ALL).title(format!(
+                "Buffer: revenue ${:.0} - bad debt ${:.0}",
+                revenue, bad_debt
+            )))
+            .gauge_style(Style::default().fg(color))
+            .ratio(ratio)
+            .label(format!("net ${:.0}", net));
+        frame.render_widget(gauge, area);
+    }
+}
+
+fn quit_requested(timeout: Duration) -> bool {
+    if let Ok(true) = event::poll(timeout) {
+        if let Ok(Event::Key(key)) = event::read() {
+            return key.code == KeyCode::Char('q');
+        }
+    }
+    false
+}
+
+pub fn run(args: DashboardArgs) -> i32 {
+    let mut setup = match args.setup.setup() {
+        Ok(setup) => setup,
+        Err(e) => return usage_error(e),
+    };
+    let scenario = args
+        .scenario
+        .map(PriceScenario::from)
+        .or_else(|| setup.scenarios.first().copied())
+        .unwrap_or(PriceScenario::FlashCrash);
+    let mechanism = args
+        .mechanism
+        .or_else(|| setup.mechanisms.first().copied())
+        .unwrap_or(LiquidationMechanism::keeper_pool());
+    let delay = Duration::from_millis(args.delay_ms);
+    if let Err(e) = args.setup.manifest(&mut setup, false) {
+        return usage_error(e);
+    }
+
+    let mut terminal = ratatui::init();
+    let mut dashboard = Dashboard {
+        scenario,
+        mechanism,
+        prices: Vec::new(),
+        queue: Vec::new(),
+        latest: None,
+        finished: false,
+    };
+    let mut quit = false;
+    let mut rng = setup.simulation.rng();
+
+    run_single_observed(mechanism, scenario, &setup.simulation, &mut rng, |state| {
+        dashboard.update(state);
+        if quit {
+            return;
+        }
+        terminal.draw(|frame| dashboard.render(frame)).ok();
+        quit = quit_requested(delay);
+    });
+
+    dashboard.finished = true;
+    while !quit {
+        terminal.draw(|frame| dashboard.render(frame)).ok();
+        quit = quit_requested(Duration::from_millis(250));
+    }
+
+    ratatui::restore();
+    EXIT_OK
+}
@@ -0,0 +1,49 @@
+//! `fair-sim diff`: loads two saved Monte Carlo result sets and prints metric
+//! deltas with significance flags as Markdown, ready to paste into a
+//! governance forum post.
+//!
+//! ```bash
+//! fair-sim monte-carlo --output before.json
+//! # ... change parameters ...
+//! fair-sim monte-carlo --output after.json
+//! fair-sim diff before.json after.json
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use clap::Args;
+
+use fair_simulation::alerts::{EXIT_OK, EXIT_VIOLATION};
+use fair_simulation::diff::{diff_results, to_markdown};
+use fair_simulation::results::load_monte_carlo;
+
+use crate::usage_error;
+
+#[derive(Args, Debug)]
+pub struct DiffArgs {
+    /// Baseline result set
+    before: PathBuf,
+    /// Result set to compare against the baseline
+    after: PathBuf,
+}
+
+pub fn run(args: DiffArgs) -> i32 {
+    let load = |path: &Path| {
+        load_monte_carlo(path).map_err(|e| format!("failed to load {}: {}", path.display(), e))
+    };
+    let (before, after) = match (load(&args.before), load(&args.after)) {
+        (Ok(before), Ok(after)) => (before, after),
+        (Err(e), _) | (_, Err(e)) => return usage_error(e),
+    };
+
+    let diffs = diff_results(&before, &after);
+    if diffs.is_empty() {
+        eprintln!("no matching (model, mechanism) cells between the two result sets");
+        return EXIT_VIOLATION;
+    }
+
+    println!("## Simulation diff: `{}` → `{}`", args.before.display(), args.after.display());
+    println!();
+    print!("{}", to_markdown(&diffs));
+    EXIT_OK
+}
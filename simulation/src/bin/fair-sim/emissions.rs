@@ -0,0 +1,65 @@
+//! `fair-sim emissions`: whether paying pool keepers partly in a protocol
+//! token holds up participation when the token collapses. The token model
+//! comes from `[simulation.reward_token]` in `--config`.
+//!
+//! ```bash
+//! fair-sim emissions --scenario black-swan --runs 200 --seed 5
+//! ```
+
+use clap::Args;
+
+use fair_simulation::alerts::EXIT_OK;
+use fair_simulation::cascade::LiquidationMechanism;
+use fair_simulation::emissions::{sweep_emission_share, EMISSION_SWEEP};
+
+use crate::{parse_split, usage_error, RunArgs, ScenarioArgs};
+
+const DEFAULT_RUNS: usize = 100;
+
+#[derive(Args, Debug)]
+pub struct EmissionsArgs {
+    #[command(flatten)]
+    run: RunArgs,
+    #[command(flatten)]
+    scenarios: ScenarioArgs,
+    /// Keeper share of each penalty under the keeper pool [default: 0.7]
+    #[arg(long, value_parser = parse_split)]
+    split: Option<f64>,
+}
+
+pub fn run(args: EmissionsArgs) -> i32 {
+    let mut setup = match args.run.setup() {
+        Ok(setup) => setup,
+        Err(e) => return usage_error(e),
+    };
+    args.scenarios.apply(&mut setup);
+    let runs = setup.runs_or(DEFAULT_RUNS);
+    if let Err(e) = args.run.manifest(&mut setup, runs, false) {
+        return usage_error(e);
+    }
+    let mechanism = match args.split {
+        Some(split) => LiquidationMechanism::KeeperPool { split },
+        None => LiquidationMechanism::keeper_pool(),
+    };
+    let token = setup.simulation.reward_token.clone().unwrap_or_default();
+
+    println!("=======================================================");
+    println!("  Reward Token Emissions");
+    println!("  Keeper pool paid partly in a health-linked token");
+    println!("=======================================================");
+    println!();
+    println!("  Token price:             health^{}", token.health_beta);
+
+    for scenario in setup.scenarios() {
+        let report = match sweep_emission_share(mechanism, scenario, &EMISSION_SWEEP, runs, &setup.simulation) {
+            Ok(report) => report,
+            Err(e) => return usage_error(e),
+        };
+        println!();
+        println!("{} / {} ({} runs)", mechanism.name(), scenario.name(), runs);
+        println!("{}", "-".repeat(50));
+        report.print();
+    }
+
+    EXIT_OK
+}
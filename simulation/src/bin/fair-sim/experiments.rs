@@ -0,0 +1,40 @@
+//! `fair-sim experiments`: lists registered simulation campaigns, optionally
+//! filtered by tag.
+
+use std::path::PathBuf;
+
+use clap::Args;
+
+use fair_simulation::alerts::EXIT_OK;
+use fair_simulation::experiments::{ExperimentRegistry, DEFAULT_REGISTRY_DIR};
+
+use crate::usage_error;
+
+#[derive(Args, Debug)]
+pub struct ExperimentsArgs {
+    /// Experiment registry directory
+    #[arg(long, default_value = DEFAULT_REGISTRY_DIR)]
+    registry: PathBuf,
+    /// Only list experiments with this tag
+    #[arg(long)]
+    tag: Option<String>,
+}
+
+pub fn run(args: ExperimentsArgs) -> i32 {
+    let registry = match ExperimentRegistry::open(&args.registry) {
+        Ok(registry) => registry,
+        Err(e) => return usage_error(format!("failed to open registry {}: {}", args.registry.display(), e)),
+    };
+
+    let experiments: Vec<_> = match &args.tag {
+        Some(tag) => registry.find_by_tag(tag),
+        None => registry.list().iter().collect(),
+    };
+
+    println!("| Name                 | Tags                 | Description");
+    println!("|----------------------|----------------------|------------");
+    for e in experiments {
+        println!("| {:20} | {:20} | {}", e.name, e.tags.join(", "), e.description);
+    }
+    EXIT_OK
+}
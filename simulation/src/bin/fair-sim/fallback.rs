@@ -0,0 +1,86 @@
+//! `fair-sim fallback`: coverage cost of each fallback policy when the
+//! keeper pool's selected executor fails to land its transaction. The
+//! failure rate comes from `--failure-rate`, else
+//! `[simulation.execution_failure]` in `--config`.
+//!
+//! ```bash
+//! fair-sim fallback --failure-rate 0.2 --scenario volatile --runs 200 --seed 5
+//! ```
+
+use clap::Args;
+
+use fair_simulation::alerts::EXIT_OK;
+use fair_simulation::cascade::LiquidationMechanism;
+use fair_simulation::fallback::{compare_fallback_policies, FALLBACK_POLICIES};
+
+use crate::{parse_split, usage_error, RunArgs, ScenarioArgs};
+
+const DEFAULT_RUNS: usize = 100;
+
+fn parse_rate(value: &str) -> Result<f64, String> {
+    let rate: f64 = value.parse().map_err(|e| format!("invalid failure rate '{}': {}", value, e))?;
+    if !(0.0..=1.0).contains(&rate) {
+        return Err(format!("failure rate {} must be within [0, 1]", rate));
+    }
+    Ok(rate)
+}
+
+#[derive(Args, Debug)]
+pub struct FallbackArgs {
+    #[command(flatten)]
+    run: RunArgs,
+    #[command(flatten)]
+    scenarios: ScenarioArgs,
+    /// Keeper share of each penalty under the keeper pool [default: 0.7]
+    #[arg(long, value_parser = parse_split)]
+    split: Option<f64>,
+    /// Chance each selected executor fails [default: 0.05]
+    #[arg(long, value_parser = parse_rate)]
+    failure_rate: Option<f64>,
+}
+
+pub fn run(args: FallbackArgs) -> i32 {
+    let mut setup = match args.run.setup() {
+        Ok(setup) => setup,
+        Err(e) => return usage_error(e),
+    };
+    args.scenarios.apply(&mut setup);
+    let runs = setup.runs_or(DEFAULT_RUNS);
+    if let Err(e) = args.run.manifest(&mut setup, runs, false) {
+        return usage_error(e);
+    }
+    let mechanism = match args.split {
+        Some(split) => LiquidationMechanism::KeeperPool { split },
+        None => LiquidationMechanism::keeper_pool(),
+    };
+    let failure_rate = args
+        .failure_rate
+        .unwrap_or_else(|| setup.simulation.execution_failure.unwrap_or_default().failure_rate);
+
+    println!("=======================================================");
+    println!("  Execution Failure Fallbacks");
+    println!("  Keeper pool executor failing to land");
+    println!("=======================================================");
+    println!();
+    println!("  Failure rate:            {:.1}%", failure_rate * 100.0);
+
+    for scenario in setup.scenarios() {
+        let report = match compare_fallback_policies(
+            mechanism,
+            scenario,
+            failure_rate,
+            &FALLBACK_POLICIES,
+            runs,
+            &setup.simulation,
+        ) {
+            Ok(report) => report,
+            Err(e) => return usage_error(e),
+        };
+        println!();
+        println!("{} / {} ({} runs)", mechanism.name(), scenario.name(), runs);
+        println!("{}", "-".repeat(50));
+        report.print();
+    }
+
+    EXIT_OK
+}
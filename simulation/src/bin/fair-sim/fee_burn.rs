@@ -0,0 +1,59 @@
+//! `fair-sim fee-burn`: priority fees burned racing for execution, with
+//! pool members racing for an executor premium against Traditional races
+//! for the whole penalty.
+//!
+//! ```bash
+//! fair-sim fee-burn --scenario flash-crash --runs 200 --seed 5
+//! ```
+
+use clap::Args;
+
+use fair_simulation::alerts::EXIT_OK;
+use fair_simulation::cascade::DEFAULT_POOL_SPLIT;
+use fair_simulation::fee_burn::{compare_fee_burn, EXECUTOR_SHARE_SWEEP};
+
+use crate::{parse_split, usage_error, RunArgs, ScenarioArgs};
+
+const DEFAULT_RUNS: usize = 100;
+
+#[derive(Args, Debug)]
+pub struct FeeBurnArgs {
+    #[command(flatten)]
+    run: RunArgs,
+    #[command(flatten)]
+    scenarios: ScenarioArgs,
+    /// Keeper share of each penalty under the keeper pool [default: 0.7]
+    #[arg(long, value_parser = parse_split)]
+    split: Option<f64>,
+}
+
+pub fn run(args: FeeBurnArgs) -> i32 {
+    let mut setup = match args.run.setup() {
+        Ok(setup) => setup,
+        Err(e) => return usage_error(e),
+    };
+    args.scenarios.apply(&mut setup);
+    let runs = setup.runs_or(DEFAULT_RUNS);
+    if let Err(e) = args.run.manifest(&mut setup, runs, false) {
+        return usage_error(e);
+    }
+    let split = args.split.unwrap_or(DEFAULT_POOL_SPLIT);
+
+    println!("=======================================================");
+    println!("  Priority Fee Burn");
+    println!("  Intra-pool execution races vs Traditional");
+    println!("=======================================================");
+
+    for scenario in setup.scenarios() {
+        let report = match compare_fee_burn(split, scenario, &EXECUTOR_SHARE_SWEEP, runs, &setup.simulation) {
+            Ok(report) => report,
+            Err(e) => return usage_error(e),
+        };
+        println!();
+        println!("Keeper pool {:.0}/{:.0} / {} ({} runs)", split * 100.0, (1.0 - split) * 100.0, scenario.name(), runs);
+        println!("{}", "-".repeat(50));
+        report.print();
+    }
+
+    EXIT_OK
+}
@@ -0,0 +1,96 @@
+//! `fair-sim flip`: Maker flip (English) auctions at each keeper apathy
+//! level, with recovery, zero-bid auctions and the keeper pool for
+//! reference.
+//!
+//! ```bash
+//! fair-sim flip --scenario flash-crash --runs 200 --seed 5
+//!
+//! # Longer bids and auctions, closer to Black Thursday's
+//! fair-sim flip --scenario black-swan --bid-blocks 10 --auction-blocks 60 --apathy 0.99
+//! ```
+//!
+//! An `EnglishAuction` entry among the config file's mechanisms sets the
+//! bid and auction lengths and increment; its apathy joins the compared
+//! levels.
+
+use clap::Args;
+
+use fair_simulation::alerts::EXIT_OK;
+use fair_simulation::cascade::{FlipAuction, LiquidationMechanism};
+use fair_simulation::flip::{compare_flip_apathy, FLIP_APATHY};
+
+use crate::{usage_error, RunArgs, ScenarioArgs};
+
+const DEFAULT_RUNS: usize = 100;
+
+#[derive(Args, Debug)]
+pub struct FlipArgs {
+    #[command(flatten)]
+    run: RunArgs,
+    #[command(flatten)]
+    scenarios: ScenarioArgs,
+    /// Keeper apathy levels to compare, in [0, 1) (repeatable) [default: 0, 0.9, 0.98 and 0.995]
+    #[arg(long = "apathy")]
+    apathy: Vec<f64>,
+    /// Blocks a bid stands before it wins [default: 5]
+    #[arg(long)]
+    bid_blocks: Option<usize>,
+    /// Blocks after which an auction ends regardless [default: 25]
+    #[arg(long)]
+    auction_blocks: Option<usize>,
+}
+
+pub fn run(args: FlipArgs) -> i32 {
+    let mut setup = match args.run.setup() {
+        Ok(setup) => setup,
+        Err(e) => return usage_error(e),
+    };
+    args.scenarios.apply(&mut setup);
+    let runs = setup.runs_or(DEFAULT_RUNS);
+    if let Err(e) = args.run.manifest(&mut setup, runs, false) {
+        return usage_error(e);
+    }
+    let configured = setup.mechanisms().into_iter().find_map(|mechanism| match mechanism {
+        LiquidationMechanism::EnglishAuction(flip) => Some(flip),
+        _ => None,
+    });
+    let mut auction = configured.unwrap_or_default();
+    if let Some(bid_blocks) = args.bid_blocks {
+        auction.bid_blocks = bid_blocks;
+    }
+    if let Some(auction_blocks) = args.auction_blocks {
+        auction.auction_blocks = auction_blocks;
+    }
+    let mut levels = if args.apathy.is_empty() { FLIP_APATHY.to_vec() } else { args.apathy };
+    if let Some(FlipAuction { apathy, .. }) = configured {
+        if !levels.contains(&apathy) {
+            levels.push(apathy);
+        }
+    }
+    levels.sort_unstable_by(f64::total_cmp);
+
+    println!("=======================================================");
+    println!("  English Auction Apathy");
+    println!("  Maker flip auctions with keepers looking away");
+    println!("=======================================================");
+    println!();
+    println!(
+        "Bids stand {} blocks, auctions end after {}, raises of at least {:.0}%",
+        auction.bid_blocks,
+        auction.auction_blocks,
+        auction.min_increment * 100.0,
+    );
+
+    for scenario in setup.scenarios() {
+        let report = match compare_flip_apathy(auction, scenario, &levels, runs, &setup.simulation) {
+            Ok(report) => report,
+            Err(e) => return usage_error(e),
+        };
+        println!();
+        println!("{} ({} runs)", scenario.name(), runs);
+        println!("{}", "-".repeat(50));
+        report.print();
+    }
+
+    EXIT_OK
+}
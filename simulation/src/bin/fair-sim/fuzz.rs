@@ -0,0 +1,79 @@
+//! `fair-sim fuzz`: searches adversarial keeper mixes (colluders, sybils,
+//! griefers, manipulators) for cases where the keeper pool ends with more bad
+//! debt than winner-takes-all, and reports minimized mixes. Exits 1 when any
+//! are found.
+//!
+//! ```bash
+//! fair-sim fuzz --iterations 200 --runs 20 --scenario flash-crash --seed 3 --output findings.json
+//! ```
+
+use std::path::PathBuf;
+
+use clap::Args;
+
+use fair_simulation::adversarial::{fuzz_mechanisms, FuzzConfig};
+use fair_simulation::alerts::{EXIT_OK, EXIT_VIOLATION};
+
+use crate::{usage_error, RunArgs, ScenarioArgs};
+
+#[derive(Args, Debug)]
+pub struct FuzzArgs {
+    #[command(flatten)]
+    run: RunArgs,
+    #[command(flatten)]
+    scenarios: ScenarioArgs,
+    /// Random adversary mixes to try
+    #[arg(long, default_value_t = FuzzConfig::default().iterations)]
+    iterations: usize,
+    /// Extra mean pool bad debt (USD) that counts as underperforming
+    #[arg(long, default_value_t = FuzzConfig::default().min_gap)]
+    min_gap: f64,
+    /// Save the findings as JSON
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+pub fn run(args: FuzzArgs) -> i32 {
+    let mut setup = match args.run.setup() {
+        Ok(setup) => setup,
+        Err(e) => return usage_error(e),
+    };
+    args.scenarios.apply(&mut setup);
+    let defaults = FuzzConfig::default();
+    let fuzz = FuzzConfig {
+        iterations: args.iterations,
+        runs: setup.runs_or(defaults.runs),
+        min_gap: args.min_gap,
+        ..defaults
+    };
+    if let Err(e) = args.run.manifest(&mut setup, fuzz.runs, false) {
+        return usage_error(e);
+    }
+
+    println!("=======================================================");
+    println!("  Adversarial Mechanism Fuzzing");
+    println!("  Keeper Pool vs Traditional under hostile keepers");
+    println!("=======================================================");
+    println!();
+
+    let report = match fuzz_mechanisms(&fuzz, &setup.scenarios(), &setup.simulation) {
+        Ok(report) => report,
+        Err(e) => return usage_error(e),
+    };
+    report.print();
+    println!();
+
+    if let Some(path) = &args.output {
+        let saved = report.to_json().and_then(|json| Ok(std::fs::write(path, json)?));
+        match saved {
+            Ok(()) => println!("Saved {} findings to {}", report.findings.len(), path.display()),
+            Err(e) => return usage_error(format!("failed to save findings to {}: {}", path.display(), e)),
+        }
+    }
+
+    if report.findings.is_empty() {
+        EXIT_OK
+    } else {
+        EXIT_VIOLATION
+    }
+}
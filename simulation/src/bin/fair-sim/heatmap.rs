@@ -0,0 +1,110 @@
+//! `fair-sim heatmap`: the bad-debt heatmap over shock severity and keeper
+//! count, one grid per mechanism and scenario, printed or saved as tidy CSV
+//! for plotting.
+//!
+//! ```bash
+//! fair-sim heatmap --seed 3 --output heatmap.csv
+//! fair-sim heatmap --scenario flash-crash --severities 0.5:3:0.5 --keepers 5,20,100 --runs 200
+//! ```
+//!
+//! With `--output` the run manifest is written alongside (`heatmap.manifest.json`).
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::PathBuf;
+
+use clap::Args;
+
+use fair_simulation::alerts::EXIT_OK;
+use fair_simulation::cascade::PriceScenario;
+use fair_simulation::manifest::sidecar_path;
+use fair_simulation::sweep::{bad_debt_heatmaps, write_heatmap_csv, SweepAxis, HEATMAP_KEEPER_COUNTS, HEATMAP_SEVERITIES};
+
+use crate::{usage_error, MechanismArgs, RunArgs, Scenario};
+
+const DEFAULT_RUNS: usize = 50;
+
+#[derive(Args, Debug)]
+pub struct HeatmapArgs {
+    #[command(flatten)]
+    run: RunArgs,
+    #[command(flatten)]
+    mechanisms: MechanismArgs,
+    /// Price scenario (repeatable) [default: volatile]
+    #[arg(long = "scenario", value_enum)]
+    scenarios: Vec<Scenario>,
+    /// Shock severities down the rows, as "start:end:step" or "a,b,c" [default: 0.25:2:0.25]
+    #[arg(long, value_parser = parse_values)]
+    severities: Option<Vec<f64>>,
+    /// Keeper counts across the columns [default: 5,10,20,50,100,200]
+    #[arg(long, value_parser = parse_values)]
+    keepers: Option<Vec<f64>>,
+    /// Save the heatmaps as tidy CSV instead of printing them
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+fn parse_values(spec: &str) -> Result<Vec<f64>, String> {
+    SweepAxis::parse(&format!("values={}", spec)).map(|axis| axis.values).map_err(|e| e.to_string())
+}
+
+pub fn run(args: HeatmapArgs) -> i32 {
+    let mut setup = match args.run.setup() {
+        Ok(setup) => setup,
+        Err(e) => return usage_error(e),
+    };
+    args.mechanisms.apply(&mut setup);
+    if !args.scenarios.is_empty() {
+        setup.scenarios = args.scenarios.iter().map(|&s| s.into()).collect();
+    } else if setup.scenarios.is_empty() {
+        setup.scenarios = vec![PriceScenario::VolatileCrash];
+    }
+    let runs = setup.runs_or(DEFAULT_RUNS);
+    let manifest = match args.run.manifest(&mut setup, runs, args.output.is_some()) {
+        Ok(manifest) => manifest,
+        Err(e) => return usage_error(e),
+    };
+    let severities = args.severities.unwrap_or_else(|| HEATMAP_SEVERITIES.to_vec());
+    let keepers = args.keepers.unwrap_or_else(|| HEATMAP_KEEPER_COUNTS.to_vec());
+    let (mechanisms, scenarios) = (setup.mechanisms(), setup.scenarios());
+
+    println!("=======================================================");
+    println!("  Bad-Debt Heatmap: Shock Severity x Keeper Count");
+    println!("=======================================================");
+    println!();
+    println!(
+        "  Grid: {} severities x {} keeper counts x {} mechanisms x {} scenarios, {} runs each",
+        severities.len(),
+        keepers.len(),
+        mechanisms.len(),
+        scenarios.len(),
+        runs
+    );
+    println!();
+
+    let heatmaps = match bad_debt_heatmaps(&severities, &keepers, &mechanisms, &scenarios, runs, &setup.simulation) {
+        Ok(heatmaps) => heatmaps,
+        Err(e) => return usage_error(e),
+    };
+
+    match &args.output {
+        Some(path) => {
+            let written = File::create(path)
+                .map_err(Into::into)
+                .and_then(|f| write_heatmap_csv(&heatmaps, BufWriter::new(f)))
+                .and_then(|()| manifest.as_ref().map_or(Ok(()), |m| m.save(sidecar_path(path))));
+            match written {
+                Ok(()) => println!(
+                    "Saved {} heatmaps to {} (manifest: {})",
+                    heatmaps.len(),
+                    path.display(),
+                    sidecar_path(path).display()
+                ),
+                Err(e) => return usage_error(format!("failed to save heatmap to {}: {}", path.display(), e)),
+            }
+        }
+        None => heatmaps.iter().for_each(|map| map.print()),
+    }
+
+    EXIT_OK
+}
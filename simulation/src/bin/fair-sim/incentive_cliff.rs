@@ -0,0 +1,64 @@
+//! `fair-sim incentive-cliff`: participation and bad debt when a keeper
+//! incentive program ends, or a vesting cliff hits, at each block of a
+//! stress scenario. The program comes from `[simulation.incentive_program]`
+//! in `--config`.
+//!
+//! ```bash
+//! fair-sim incentive-cliff --scenario volatile --runs 200 --seed 5
+//! ```
+
+use clap::Args;
+
+use fair_simulation::alerts::EXIT_OK;
+use fair_simulation::incentive_cliff::{sweep_cliff_timing, CLIFF_SWEEP};
+
+use crate::{usage_error, MechanismArgs, RunArgs, ScenarioArgs};
+
+const DEFAULT_RUNS: usize = 100;
+
+#[derive(Args, Debug)]
+pub struct IncentiveCliffArgs {
+    #[command(flatten)]
+    run: RunArgs,
+    #[command(flatten)]
+    mechanisms: MechanismArgs,
+    #[command(flatten)]
+    scenarios: ScenarioArgs,
+}
+
+pub fn run(args: IncentiveCliffArgs) -> i32 {
+    let mut setup = match args.run.setup() {
+        Ok(setup) => setup,
+        Err(e) => return usage_error(e),
+    };
+    args.mechanisms.apply(&mut setup);
+    args.scenarios.apply(&mut setup);
+    let runs = setup.runs_or(DEFAULT_RUNS);
+    if let Err(e) = args.run.manifest(&mut setup, runs, false) {
+        return usage_error(e);
+    }
+    let program = setup.simulation.incentive_program.clone().unwrap_or_default();
+
+    println!("=======================================================");
+    println!("  Incentive Cliff");
+    println!("  Keeper bonus ending mid-crisis");
+    println!("=======================================================");
+    println!();
+    println!("  Bonus per liquidation:   ${:.0}", program.bonus_per_liquidation);
+    println!("  Mercenary keepers:       {:.0}%", program.mercenary_share * 100.0);
+
+    for scenario in setup.scenarios() {
+        for mechanism in setup.mechanisms() {
+            let report = match sweep_cliff_timing(mechanism, scenario, &CLIFF_SWEEP, runs, &setup.simulation) {
+                Ok(report) => report,
+                Err(e) => return usage_error(e),
+            };
+            println!();
+            println!("{} / {} ({} runs)", mechanism.name(), scenario.name(), runs);
+            println!("{}", "-".repeat(50));
+            report.print();
+        }
+    }
+
+    EXIT_OK
+}
@@ -0,0 +1,65 @@
+//! `fair-sim invariants`: mines properties that held in every simulated
+//! keeper pool block and emits them as candidate Foundry invariant tests.
+//!
+//! ```bash
+//! fair-sim invariants --runs 500 --seed 1 --output ../test/FairSimulatedInvariants.t.sol
+//! ```
+
+use std::path::PathBuf;
+
+use clap::Args;
+
+use fair_simulation::alerts::EXIT_OK;
+use fair_simulation::invariants::mine_invariants;
+
+use crate::{usage_error, RunArgs, ScenarioArgs};
+
+const DEFAULT_RUNS: usize = 200;
+
+#[derive(Args, Debug)]
+pub struct InvariantsArgs {
+    #[command(flatten)]
+    run: RunArgs,
+    #[command(flatten)]
+    scenarios: ScenarioArgs,
+    /// Write the Solidity test contract to this path
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+pub fn run(args: InvariantsArgs) -> i32 {
+    let mut setup = match args.run.setup() {
+        Ok(setup) => setup,
+        Err(e) => return usage_error(e),
+    };
+    args.scenarios.apply(&mut setup);
+    let runs = setup.runs_or(DEFAULT_RUNS);
+    if let Err(e) = args.run.manifest(&mut setup, runs, false) {
+        return usage_error(e);
+    }
+
+    println!("=======================================================");
+    println!("  Invariant Mining (Keeper Pool)");
+    println!("=======================================================");
+    println!();
+
+    let report = match mine_invariants(&setup.scenarios(), runs, &setup.simulation) {
+        Ok(report) => report,
+        Err(e) => return usage_error(e),
+    };
+    report.print();
+    println!();
+
+    let solidity = report.to_solidity();
+    match &args.output {
+        Some(path) => {
+            if let Err(e) = std::fs::write(path, &solidity) {
+                return usage_error(format!("failed to write invariants to {}: {}", path.display(), e));
+            }
+            println!("Saved candidate invariants to {}", path.display());
+        }
+        None => println!("{}", solidity),
+    }
+
+    EXIT_OK
+}
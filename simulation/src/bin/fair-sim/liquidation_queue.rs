@@ -0,0 +1,72 @@
+//! `fair-sim liquidation-queue`: liquidations in the protocol's published
+//! queue, by collateral ratio or IPFE score, against keepers taking the
+//! most profitable CDPs first, by price impact and bad debt.
+//!
+//! ```bash
+//! fair-sim liquidation-queue --scenario flash-crash --runs 200 --seed 5
+//!
+//! # A tight per-block limit, where the order decides most
+//! fair-sim liquidation-queue --mechanism traditional --per-block 2
+//! ```
+//!
+//! The config file's `queue_order` is overridden; every order is compared.
+
+use clap::Args;
+
+use fair_simulation::alerts::EXIT_OK;
+use fair_simulation::liquidation_queue::compare_queue_orders;
+
+use crate::{usage_error, MechanismArgs, RunArgs, ScenarioArgs};
+
+const DEFAULT_RUNS: usize = 100;
+
+#[derive(Args, Debug)]
+pub struct LiquidationQueueArgs {
+    #[command(flatten)]
+    run: RunArgs,
+    #[command(flatten)]
+    mechanisms: MechanismArgs,
+    #[command(flatten)]
+    scenarios: ScenarioArgs,
+    /// Liquidations allowed per block [default: the config's]
+    #[arg(long)]
+    per_block: Option<usize>,
+}
+
+pub fn run(args: LiquidationQueueArgs) -> i32 {
+    let mut setup = match args.run.setup() {
+        Ok(setup) => setup,
+        Err(e) => return usage_error(e),
+    };
+    args.mechanisms.apply(&mut setup);
+    args.scenarios.apply(&mut setup);
+    if let Some(per_block) = args.per_block {
+        setup.simulation.liquidations_per_block = per_block;
+    }
+    let runs = setup.runs_or(DEFAULT_RUNS);
+    if let Err(e) = args.run.manifest(&mut setup, runs, false) {
+        return usage_error(e);
+    }
+
+    println!("=======================================================");
+    println!("  Liquidation Queues");
+    println!("  Protocol-ordered versus keeper-ordered execution");
+    println!("=======================================================");
+    println!();
+    println!("{} liquidations per block", setup.simulation.liquidations_per_block);
+
+    for scenario in setup.scenarios() {
+        for mechanism in setup.mechanisms() {
+            let report = match compare_queue_orders(mechanism, scenario, runs, &setup.simulation) {
+                Ok(report) => report,
+                Err(e) => return usage_error(e),
+            };
+            println!();
+            println!("{} / {} ({} runs)", mechanism.name(), scenario.name(), runs);
+            println!("{}", "-".repeat(50));
+            report.print();
+        }
+    }
+
+    EXIT_OK
+}
@@ -0,0 +1,87 @@
+//! `fair-sim live`: streams a campaign block by block as newline-delimited
+//! JSON (see `live` for the format), for a notebook to explore while it
+//! runs.
+//!
+//! ```bash
+//! # Pipe into anything that reads JSON Lines
+//! fair-sim live --scenario flash-crash --seed 7 --runs 5 | jq -c 'select(.kind == "run")'
+//!
+//! # Serve one notebook connection on a local port
+//! fair-sim live --listen 127.0.0.1:7878 --seed 7 --runs 100
+//! ```
+//!
+//! From Python:
+//!
+//! ```python
+//! import json, socket
+//! for line in socket.create_connection(("127.0.0.1", 7878)).makefile():
+//!     event = json.loads(line)
+//! ```
+//!
+//! Status messages go to stderr, so stdout carries only the stream.
+
+use std::io::BufWriter;
+use std::net::{SocketAddr, TcpListener};
+
+use clap::Args;
+
+use fair_simulation::alerts::EXIT_OK;
+use fair_simulation::live::stream_campaign;
+
+use crate::{usage_error, MechanismArgs, RunArgs, ScenarioArgs};
+
+const DEFAULT_RUNS: usize = 10;
+
+#[derive(Args, Debug)]
+pub struct LiveArgs {
+    #[command(flatten)]
+    run: RunArgs,
+    #[command(flatten)]
+    mechanisms: MechanismArgs,
+    #[command(flatten)]
+    scenarios: ScenarioArgs,
+    /// Stream to the first client connecting to this address instead of stdout
+    #[arg(long)]
+    listen: Option<SocketAddr>,
+}
+
+pub fn run(args: LiveArgs) -> i32 {
+    let mut setup = match args.run.setup() {
+        Ok(setup) => setup,
+        Err(e) => return usage_error(e),
+    };
+    args.mechanisms.apply(&mut setup);
+    args.scenarios.apply(&mut setup);
+    let runs = setup.runs_or(DEFAULT_RUNS);
+    if args.listen.is_none() && args.run.setup.manifest.is_some() {
+        return usage_error("--manifest reports to stdout, which carries the stream; use it with --listen");
+    }
+    if let Err(e) = args.run.manifest(&mut setup, runs, false) {
+        return usage_error(e);
+    }
+
+    let (mechanisms, scenarios) = (setup.mechanisms(), setup.scenarios());
+    let streamed = match args.listen {
+        Some(addr) => {
+            let listener = match TcpListener::bind(addr) {
+                Ok(listener) => listener,
+                Err(e) => return usage_error(format!("failed to listen on {}: {}", addr, e)),
+            };
+            eprintln!("Waiting for a connection on {}", addr);
+            let (stream, peer) = match listener.accept() {
+                Ok(connection) => connection,
+                Err(e) => return usage_error(format!("failed to accept a connection on {}: {}", addr, e)),
+            };
+            eprintln!("Streaming to {}", peer);
+            stream_campaign(&mechanisms, &scenarios, runs, &setup.simulation, BufWriter::new(stream))
+        }
+        None => stream_campaign(&mechanisms, &scenarios, runs, &setup.simulation, std::io::stdout().lock()),
+    };
+    match streamed {
+        Ok(lines) => {
+            eprintln!("Streamed {} lines", lines);
+            EXIT_OK
+        }
+        Err(e) => usage_error(format!("stream ended: {}", e)),
+    }
+}
@@ -0,0 +1,93 @@
+//! `fair-sim llamma`: crvUSD soft liquidation at each band count against
+//! Fair's keeper pool, by bad debt and borrower loss.
+//!
+//! ```bash
+//! fair-sim llamma --scenario volatile --runs 200 --seed 5
+//!
+//! # Wide, cheap bands
+//! fair-sim llamma --bands 50 --band-width 0.02 --spread 0.001
+//! ```
+//!
+//! A `SoftLiquidation` entry among the config file's mechanisms sets the
+//! band width and spread; its band count joins the compared ones.
+
+use clap::Args;
+
+use fair_simulation::alerts::EXIT_OK;
+use fair_simulation::cascade::{LiquidationMechanism, Llamma};
+use fair_simulation::llamma::{compare_soft_liquidation, LLAMMA_BANDS};
+
+use crate::{usage_error, RunArgs, ScenarioArgs};
+
+const DEFAULT_RUNS: usize = 100;
+
+#[derive(Args, Debug)]
+pub struct LlammaArgs {
+    #[command(flatten)]
+    run: RunArgs,
+    #[command(flatten)]
+    scenarios: ScenarioArgs,
+    /// Band counts to compare (repeatable) [default: 4, 10 and 30]
+    #[arg(long = "bands")]
+    bands: Vec<usize>,
+    /// Price fall across one band, as a fraction [default: 0.01]
+    #[arg(long)]
+    band_width: Option<f64>,
+    /// Arbitrageurs' discount to the market on each conversion [default: 0.005]
+    #[arg(long)]
+    spread: Option<f64>,
+}
+
+pub fn run(args: LlammaArgs) -> i32 {
+    let mut setup = match args.run.setup() {
+        Ok(setup) => setup,
+        Err(e) => return usage_error(e),
+    };
+    args.scenarios.apply(&mut setup);
+    let runs = setup.runs_or(DEFAULT_RUNS);
+    if let Err(e) = args.run.manifest(&mut setup, runs, false) {
+        return usage_error(e);
+    }
+    let configured = setup.mechanisms().into_iter().find_map(|mechanism| match mechanism {
+        LiquidationMechanism::SoftLiquidation(llamma) => Some(llamma),
+        _ => None,
+    });
+    let mut llamma = configured.unwrap_or_default();
+    if let Some(band_width) = args.band_width {
+        llamma.band_width = band_width;
+    }
+    if let Some(spread) = args.spread {
+        llamma.spread = spread;
+    }
+    let mut bands = if args.bands.is_empty() { LLAMMA_BANDS.to_vec() } else { args.bands };
+    if let Some(Llamma { bands: configured, .. }) = configured {
+        if !bands.contains(&configured) {
+            bands.push(configured);
+        }
+    }
+    bands.sort_unstable();
+
+    println!("=======================================================");
+    println!("  Soft Liquidation (LLAMMA)");
+    println!("  crvUSD bands versus Fair's keeper pool");
+    println!("=======================================================");
+    println!();
+    println!(
+        "Bands {:.1}% wide, arbitrageurs take {:.2}% per conversion",
+        llamma.band_width * 100.0,
+        llamma.spread * 100.0,
+    );
+
+    for scenario in setup.scenarios() {
+        let report = match compare_soft_liquidation(llamma, scenario, &bands, runs, &setup.simulation) {
+            Ok(report) => report,
+            Err(e) => return usage_error(e),
+        };
+        println!();
+        println!("{} ({} runs)", scenario.name(), runs);
+        println!("{}", "-".repeat(50));
+        report.print();
+    }
+
+    EXIT_OK
+}
@@ -0,0 +1,722 @@
+//! Fair Simulation CLI
+//!
+//! Single entry point for every simulation, analysis, and reporting tool.
+//!
+//! ## Usage
+//! ```bash
+//! cargo run --release -- poa --runs 10000
+//! cargo run --release -- cascade --scenario flash-crash --mechanism keeper-pool --seed 7
+//! cargo run --release -- cascade --preset Mar2020 --preset LunaCollapse
+//! cargo run --release -- monte-carlo --model mar2020 --assert "insolvency_prob<0.001" --output before.json
+//! cargo run --release -- corpus --model jump-diffusion --runs 10000 --seed 42 --output jd.json
+//! cargo run --release -- monte-carlo --corpus jd.json
+//! cargo run --release -- monitor snapshot.json --runs 500
+//! cargo run --release -- reaggregate before.json --insolvency 250000 --level 0.975
+//! cargo run --release -- diff before.json after.json
+//! cargo run --release -- compare before.json after.json
+//! cargo run --release -- mechanisms
+//! cargo run --release -- recommend --target "insolvency_prob<0.001"
+//! cargo run --release -- sweep --param liquidation_penalty=0.05:0.20:0.05 --param keepers.count=10,50,200
+//! cargo run --release -- sweep --param severity=0.25:3:0.25 --scenario flash-crash --output severity.csv
+//! cargo run --release -- heatmap --seed 3 --output heatmap.csv
+//! cargo run --release -- invariants --output ../test/FairSimulatedInvariants.t.sol
+//! cargo run --release -- animate --scenario flash-crash --runs 3 --output frames.json
+//! cargo run --release -- budget --time 5m --seed 42
+//! cargo run --release -- budget --precision 5% --seed 42
+//! cargo run --release -- ruin --scenario volatile --runs 50 --seed 5
+//! cargo run --release -- liquidity --scenario flash-crash --impact-multiplier 20
+//! cargo run --release -- attribution --scenario flash-crash --runs 200
+//! cargo run --release -- timelock --scenario black-swan --delay 10 --delay 50
+//! cargo run --release -- treasury --scenario volatile --smoothing 0.5 --smoothing 0.1
+//! cargo run --release -- flip --scenario flash-crash --apathy 0.95 --apathy 0.99
+//! cargo run --release -- llamma --scenario black-swan --bands 4 --bands 50
+//! cargo run --release -- staking --scenario flash-crash --griefers 20
+//! cargo run --release -- commit-reveal --scenario volatile --reveal-blocks 2 --reveal-blocks 10
+//! cargo run --release -- grace --scenario flash-crash --window 5 --top-up-rate 0.5
+//! cargo run --release -- batch-auction --scenario volatile --dispersion 0.1 --dispersion 2.0
+//! cargo run --release -- penalty-curve --scenario flash-crash --mechanism keeper-pool --slope 0.5 --slope 2.0
+//! cargo run --release -- reputation --scenario flash-crash --griefers 20 --exponent 2 --exponent 16
+//! cargo run --release -- pool-split --scenario gradual --pareto-alpha 1.1
+//! cargo run --release -- liquidation-queue --scenario flash-crash --mechanism traditional --per-block 2
+//! cargo run --release -- live --listen 127.0.0.1:7878 --seed 7
+//! cargo run --release -- --help
+//! ```
+//!
+//! Every simulating subcommand accepts `--runs`, `--seed`, and `--config`
+//! (a TOML/YAML scenario file). Settings layer as defaults < config file <
+//! environment < flags, so CI can pin a campaign without editing scenario
+//! files:
+//!
+//! | Flag        | Environment        |
+//! |-------------|--------------------|
+//! | `--config`  | `FAIR_SIM_CONFIG`  |
+//! | `--runs`    | `FAIR_SIM_RUNS`    |
+//! | `--seed`    | `FAIR_SIM_SEED`    |
+//! | `--rng`     | `FAIR_SIM_RNG`     |
+//! | `--db`      | `FAIR_SIM_DB`      |
+//! | `--threads` | `FAIR_SIM_THREADS` |
+//!
+//! ```bash
+//! FAIR_SIM_RUNS=50 FAIR_SIM_SEED=7 fair-sim monte-carlo --config stress.toml
+//! ```
+//!
+//! Subcommands that run a matrix take `--scenario`, `--mechanism` and (for
+//! price models) `--model`, each repeatable, in place of the config's
+//! lists, so one cell can be iterated on without running the rest; the
+//! `figures` binary takes the same flags to narrow its pinned campaigns.
+//!
+//! `--manifest run.json` records the crate version, git commit, command
+//! line, seed and fully resolved config behind a run, so a chart can be
+//! traced back to the invocation that replays it. Saved results
+//! (`monte-carlo`, `cascade` and `poa --output`, `--experiment`,
+//! `sweep --output`, `animate`) carry one
+//! automatically. Unseeded runs are given a seed when a manifest is written.
+//!
+//! `--db research.sqlite` (`cascade` and `monte-carlo`, `sqlite` feature)
+//! appends the manifest and every run's metrics to a SQLite database
+//! instead; see `database` for the schema.
+//!
+//! Independent runs of a campaign are spread over a rayon pool, one worker
+//! per core unless `--threads` says otherwise. Every run's seed is drawn
+//! from the campaign seed before any run starts, so results do not depend
+//! on the thread count. `--rng small` runs each cascade on Xoshiro256++
+//! instead of ChaCha12: the same seeds and different draws, each several
+//! times cheaper (`cascade --compute` shows how many a cell makes).
+//! Replaying a run needs the same `--rng`; manifests record it with the
+//! config.
+//!
+//! Every run records its wall time, RNG draws and heap allocations (see
+//! `compute`); `cascade --compute` tabulates them per cell, and `-v`
+//! campaign summaries include the totals.
+//!
+//! ## Logging
+//! The library emits `tracing` spans per campaign (scenario, mechanism,
+//! runs) and per run (seed). `-v` logs campaign summaries to stderr, `-vv`
+//! each run's outcome and `-vvv` every block; `--log-format json` writes
+//! one JSON object per line for a log pipeline. `FAIR_SIM_LOG` takes
+//! `tracing` filter directives when no `-v` is given:
+//!
+//! ```bash
+//! fair-sim -vv --log-format json cascade --runs 20 --seed 7 2> runs.log
+//! FAIR_SIM_LOG=fair_simulation::budget=debug fair-sim budget --total-runs 400
+//! ```
+//!
+//! ## Exit Codes
+//! - `0`: success
+//! - `1`: a risk assertion or target was not met, or an audited run did not reconcile
+//! - `2`: usage or input error
+
+mod animate;
+mod attribution;
+mod backtest;
+mod batch_auction;
+mod budget;
+mod cascade;
+mod commit_reveal;
+mod compare;
+mod contagion;
+mod cooldown;
+mod corpus;
+#[cfg(feature = "tui")]
+mod dashboard;
+mod diff;
+mod emissions;
+mod experiments;
+mod fallback;
+mod fee_burn;
+mod flip;
+mod fuzz;
+mod grace;
+mod heatmap;
+mod incentive_cliff;
+mod invariants;
+mod liquidation_queue;
+mod liquidity;
+mod llamma;
+mod live;
+mod mechanisms;
+mod mempool;
+mod switching;
+mod monitor;
+mod monte_carlo;
+mod narrative;
+mod penalty_curve;
+mod poa;
+mod pool_split;
+mod reaggregate;
+mod reputation;
+mod recommend;
+mod rotation;
+mod ruin;
+mod shared_liquidity;
+mod staking;
+mod sweep;
+mod timelock;
+mod treasury;
+
+use std::path::{Path, PathBuf};
+
+use clap::builder::{PossibleValue, PossibleValuesParser, TypedValueParser};
+use clap::{ArgAction, Args, Parser, Subcommand, ValueEnum};
+use tracing_subscriber::EnvFilter;
+
+use fair_simulation::alerts::{Assertion, EXIT_USAGE};
+use fair_simulation::cascade::{LiquidationMechanism, PriceScenario, RngKind};
+use fair_simulation::compute::CountingAllocator;
+use fair_simulation::config::SimulationSetup;
+use fair_simulation::manifest::{sidecar_path, RunManifest};
+use fair_simulation::monte_carlo::PriceModel;
+use fair_simulation::outliers::Outlier;
+use fair_simulation::report::table::{Table, TableFormat};
+use fair_simulation::scenarios::ScenarioPreset;
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[derive(Parser)]
+#[command(name = "fair-sim", version, about = "Fair stablecoin liquidation simulations")]
+struct Cli {
+    /// Log to stderr: -v campaign summaries, -vv runs, -vvv blocks
+    #[arg(short, long, global = true, action = ArgAction::Count)]
+    verbose: u8,
+    #[arg(long, global = true, value_enum, default_value_t = LogFormat::Text)]
+    log_format: LogFormat,
+    /// Worker threads for independent runs [default: one per core]
+    #[arg(long, global = true, env = "FAIR_SIM_THREADS", value_parser = clap::value_parser!(u64).range(1..))]
+    threads: Option<u64>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum LogFormat {
+    Text,
+    Json,
+}
+
+/// Installs the stderr log subscriber. `-v` flags win over `FAIR_SIM_LOG`,
+/// which wins over warnings only.
+fn init_tracing(verbose: u8, format: LogFormat) {
+    let filter = match verbose {
+        0 => EnvFilter::try_from_env("FAIR_SIM_LOG").unwrap_or_else(|_| EnvFilter::new("warn")),
+        1 => EnvFilter::new("info"),
+        2 => EnvFilter::new("debug"),
+        _ => EnvFilter::new("trace"),
+    };
+    let subscriber = tracing_subscriber::fmt().with_env_filter(filter).with_writer(std::io::stderr);
+    match format {
+        LogFormat::Text => subscriber.init(),
+        LogFormat::Json => subscriber.json().init(),
+    }
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Price of Anarchy across obfuscation strategies
+    Poa(poa::PoaArgs),
+    /// Deleveraging cascades: keeper pool vs winner-takes-all
+    Cascade(cascade::CascadeArgs),
+    /// Monte Carlo tail risk (VaR/CVaR, insolvency) per price model
+    MonteCarlo(monte_carlo::MonteCarloArgs),
+    /// A price model's paths and run seeds saved once, for `monte-carlo --corpus` to replay
+    Corpus(corpus::CorpusArgs),
+    /// Monte Carlo forward from an indexer snapshot of on-chain state
+    Monitor(monitor::MonitorArgs),
+    /// Tail metrics of a saved Monte Carlo campaign at new thresholds, without re-simulating
+    Reaggregate(reaggregate::ReaggregateArgs),
+    /// Metric deltas with significance flags between two saved campaigns
+    Diff(diff::DiffArgs),
+    /// Significant changes, with intervals, between two saved result sets
+    Compare(compare::CompareArgs),
+    /// List registered experiments
+    Experiments(experiments::ExperimentsArgs),
+    /// List liquidation mechanisms and their capabilities
+    Mechanisms(mechanisms::MechanismsArgs),
+    /// Block-by-block narrative of single runs
+    Narrative(narrative::NarrativeArgs),
+    /// Per-block frames of a few cascades as compact JSON for a web animation
+    Animate(animate::AnimateArgs),
+    /// PnL backtest of the reference keeper strategy
+    Backtest(backtest::BacktestArgs),
+    /// Propose penalty / MCR / split values meeting risk targets
+    Recommend(recommend::RecommendArgs),
+    /// Candidate Foundry invariants mined from simulated cascades
+    Invariants(invariants::InvariantsArgs),
+    /// Search adversarial keeper mixes where the keeper pool underperforms
+    Fuzz(fuzz::FuzzArgs),
+    /// Fair bad debt driven by a neighboring protocol's liquidations
+    Contagion(contagion::ContagionArgs),
+    /// Keeper capital won by rival protocols across a bonus sweep
+    SharedLiquidity(shared_liquidity::SharedLiquidityArgs),
+    /// Keeper pool participation when paid in a collapsing protocol token
+    Emissions(emissions::EmissionsArgs),
+    /// Participation and bad debt when a keeper incentive program hits its cliff
+    IncentiveCliff(incentive_cliff::IncentiveCliffArgs),
+    /// Priority fees burned racing for execution, pool executor premium vs Traditional
+    FeeBurn(fee_burn::FeeBurnArgs),
+    /// Coverage cost of fallback policies when the pool's executor fails
+    Fallback(fallback::FallbackArgs),
+    /// Coverage and concentration versus the share of keepers watching the mempool
+    Mempool(mempool::MempoolArgs),
+    /// Keeper pool with a backstop under stress versus the pure mechanisms
+    Switching(switching::SwitchingArgs),
+    /// Borrower protection versus bad debt under per-borrower liquidation cooldowns
+    Cooldown(cooldown::CooldownArgs),
+    /// Borrower top-ups in a grace period before liquidation versus the bad debt it costs
+    Grace(grace::GraceArgs),
+    /// Price decline split into the scenario's and the liquidations', with the amplification per mechanism
+    Attribution(attribution::AttributionArgs),
+    /// Bad debt accruing while a governance fix waits out its timelock, per timelock length
+    Timelock(timelock::TimelockArgs),
+    /// Keeper pool payouts dripped from a treasury against per-event splits
+    Treasury(treasury::TreasuryArgs),
+    /// Maker flip auctions under keeper apathy: recovery and zero-bid auctions
+    Flip(flip::FlipArgs),
+    /// crvUSD soft liquidation bands against the keeper pool: bad debt and borrower loss
+    Llamma(llamma::LlammaArgs),
+    /// Keeper pool stakes and slashing against the flat split, with griefers free riding
+    Staking(staking::StakingArgs),
+    /// Keeper pool commit-reveal: latency cost per reveal delay against the MEV it saves
+    CommitReveal(commit_reveal::CommitRevealArgs),
+    /// Per-block uniform-price batch auctions against per-CDP races: gas, price impact and fairness
+    BatchAuction(batch_auction::BatchAuctionArgs),
+    /// Penalty curves rising below the minimum ratio: least bad debt under a borrower-loss cap
+    PenaltyCurve(penalty_curve::PenaltyCurveArgs),
+    /// Keeper pool executors drawn by reputation: latency against execution concentration, with griefers
+    Reputation(reputation::ReputationArgs),
+    /// Keeper pool shares split alike against pro rata to capital: inequality and small-keeper viability
+    PoolSplit(pool_split::PoolSplitArgs),
+    /// Liquidations in the protocol's published queue against keepers' profit order: price impact and bad debt
+    LiquidationQueue(liquidation_queue::LiquidationQueueArgs),
+    /// Bad debt when market makers withdraw mid-crash and price impact jumps, per mechanism
+    Liquidity(liquidity::LiquidityArgs),
+    /// Stream a campaign block by block as JSON Lines to stdout or a socket, for live notebooks
+    Live(live::LiveArgs),
+    /// Keeper pool active sets rotated per epoch, and their liveness risk
+    Rotation(rotation::RotationArgs),
+    /// How often small keepers are wiped out under each mechanism, net of gas and inventory drawdowns
+    Ruin(ruin::RuinArgs),
+    /// Cascade metrics over the cross-product of config field ranges
+    Sweep(sweep::SweepArgs),
+    /// Bad debt over shock severity x keeper count, per mechanism
+    Heatmap(heatmap::HeatmapArgs),
+    /// Scenario × mechanism cascades under a time, run or precision budget, runs allocated by variance
+    Budget(budget::BudgetArgs),
+    /// Watch a single cascade live in the terminal
+    #[cfg(feature = "tui")]
+    Dashboard(dashboard::DashboardArgs),
+}
+
+/// `--mechanism` values: every mechanism's `MechanismInfo::key`, at its
+/// default parameters.
+pub fn mechanism_parser() -> impl TypedValueParser<Value = LiquidationMechanism> {
+    let keys = LiquidationMechanism::all().into_iter().map(|mechanism| {
+        let info = mechanism.info();
+        PossibleValue::new(info.key).help(info.description)
+    });
+    PossibleValuesParser::new(keys)
+        .map(|key| LiquidationMechanism::from_key(&key).expect("possible values are mechanism keys"))
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum Scenario {
+    Gradual,
+    FlashCrash,
+    Volatile,
+    BlackSwan,
+}
+
+impl From<Scenario> for PriceScenario {
+    fn from(s: Scenario) -> Self {
+        match s {
+            Scenario::Gradual => Self::GradualDecline,
+            Scenario::FlashCrash => Self::FlashCrash,
+            Scenario::Volatile => Self::VolatileCrash,
+            Scenario::BlackSwan => Self::BlackSwan,
+        }
+    }
+}
+
+impl From<PriceScenario> for Scenario {
+    fn from(s: PriceScenario) -> Self {
+        match s {
+            PriceScenario::GradualDecline => Self::Gradual,
+            PriceScenario::FlashCrash => Self::FlashCrash,
+            PriceScenario::VolatileCrash => Self::Volatile,
+            PriceScenario::BlackSwan => Self::BlackSwan,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum Model {
+    Gbm,
+    JumpDiffusion,
+    Garch,
+    Mar2020,
+    May2021,
+    Nov2022,
+}
+
+impl From<Model> for PriceModel {
+    fn from(m: Model) -> Self {
+        match m {
+            Model::Gbm => Self::GBM,
+            Model::JumpDiffusion => Self::JumpDiffusion,
+            Model::Garch => Self::GARCH,
+            Model::Mar2020 => Self::HistoricalMar2020,
+            Model::May2021 => Self::HistoricalMay2021,
+            Model::Nov2022 => Self::HistoricalNov2022,
+        }
+    }
+}
+
+impl From<PriceModel> for Model {
+    fn from(m: PriceModel) -> Self {
+        match m {
+            PriceModel::GBM => Self::Gbm,
+            PriceModel::JumpDiffusion => Self::JumpDiffusion,
+            PriceModel::GARCH => Self::Garch,
+            PriceModel::HistoricalMar2020 => Self::Mar2020,
+            PriceModel::HistoricalMay2021 => Self::May2021,
+            PriceModel::HistoricalNov2022 => Self::Nov2022,
+        }
+    }
+}
+
+/// Seed and scenario file, shared by every simulating subcommand. Each
+/// falls back to its environment variable when the flag is absent.
+#[derive(Args, Debug)]
+pub struct SetupArgs {
+    /// RNG seed for reproducible runs
+    #[arg(long, env = "FAIR_SIM_SEED")]
+    seed: Option<u64>,
+    /// Per-run generator: std (ChaCha12) or small (Xoshiro256++, faster) [default: std]
+    #[arg(long, env = "FAIR_SIM_RNG")]
+    rng: Option<RngKind>,
+    /// TOML/YAML scenario file
+    #[arg(long, env = "FAIR_SIM_CONFIG")]
+    config: Option<PathBuf>,
+    /// Write a JSON manifest of the run (version, commit, seed, resolved config)
+    #[arg(long)]
+    manifest: Option<PathBuf>,
+}
+
+impl SetupArgs {
+    /// Loads the scenario file (if any) and applies flag (or environment)
+    /// overrides on top of it.
+    pub fn setup(&self) -> Result<SimulationSetup, String> {
+        let mut setup = match &self.config {
+            Some(path) => SimulationSetup::load(path)
+                .map_err(|e| format!("failed to load config {}: {}", path.display(), e))?,
+            None => SimulationSetup::default(),
+        };
+        if self.seed.is_some() {
+            setup.simulation.seed = self.seed;
+        }
+        if let Some(kind) = self.rng {
+            setup.simulation.rng_kind = kind;
+        }
+        Ok(setup)
+    }
+
+    pub fn config_path(&self) -> Option<&Path> {
+        self.config.as_deref()
+    }
+
+    /// Captures the run manifest when `--manifest` is given or `attach` is
+    /// set (results are being saved), writing it to `--manifest`. Unseeded
+    /// runs are given a drawn seed first so the manifest replays them.
+    pub fn manifest(&self, setup: &mut SimulationSetup, attach: bool) -> Result<Option<RunManifest>, String> {
+        if self.manifest.is_none() && !attach {
+            return Ok(None);
+        }
+        setup.simulation.seed.get_or_insert_with(rand::random);
+        let manifest = RunManifest::capture(setup, std::env::args().collect());
+        if let Some(path) = &self.manifest {
+            manifest
+                .save(path)
+                .map_err(|e| format!("failed to write manifest to {}: {}", path.display(), e))?;
+            println!("Wrote run manifest to {}", path.display());
+        }
+        Ok(Some(manifest))
+    }
+}
+
+/// `SetupArgs` plus a run count, for subcommands that run campaigns.
+#[derive(Args, Debug)]
+pub struct RunArgs {
+    /// Simulation runs per cell [default: per subcommand]
+    #[arg(long, env = "FAIR_SIM_RUNS", value_parser = clap::value_parser!(u64).range(1..))]
+    runs: Option<u64>,
+    #[command(flatten)]
+    setup: SetupArgs,
+}
+
+impl RunArgs {
+    pub fn setup(&self) -> Result<SimulationSetup, String> {
+        let mut setup = self.setup.setup()?;
+        if let Some(runs) = self.runs {
+            setup.runs = Some(runs as usize);
+        }
+        Ok(setup)
+    }
+
+    pub fn config_path(&self) -> Option<&Path> {
+        self.setup.config_path()
+    }
+
+    /// `SetupArgs::manifest` with the resolved run count recorded.
+    pub fn manifest(&self, setup: &mut SimulationSetup, runs: usize, attach: bool) -> Result<Option<RunManifest>, String> {
+        setup.runs = Some(runs);
+        self.setup.manifest(setup, attach)
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct MechanismArgs {
+    /// Liquidation mechanism (repeatable) [default: all]
+    #[arg(long = "mechanism", value_parser = mechanism_parser())]
+    mechanisms: Vec<LiquidationMechanism>,
+    /// Keeper share of each penalty under the keeper pool [default: 0.7]
+    #[arg(long, value_parser = parse_split)]
+    split: Option<f64>,
+}
+
+impl MechanismArgs {
+    pub fn apply(&self, setup: &mut SimulationSetup) {
+        if !self.mechanisms.is_empty() {
+            setup.mechanisms = self.mechanisms.clone();
+        }
+        if let Some(split) = self.split {
+            setup.mechanisms = setup.mechanisms().into_iter().map(|m| m.with_split(split)).collect();
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct ScenarioArgs {
+    /// Price scenario (repeatable) [default: all]
+    #[arg(long = "scenario", value_enum)]
+    scenarios: Vec<Scenario>,
+    /// Exponent on every price move: 0 = flat, 1 = as defined, 2 = twice the log drop
+    #[arg(long, value_parser = parse_severity)]
+    severity: Option<f64>,
+}
+
+impl ScenarioArgs {
+    pub fn apply(&self, setup: &mut SimulationSetup) {
+        if !self.scenarios.is_empty() {
+            setup.scenarios = self.scenarios.iter().map(|&s| s.into()).collect();
+        }
+        if let Some(severity) = self.severity {
+            setup.simulation.severity = severity;
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct ModelArgs {
+    /// Price model (repeatable) [default: all]
+    #[arg(long = "model", value_enum)]
+    models: Vec<Model>,
+}
+
+impl ModelArgs {
+    pub fn apply(&self, setup: &mut SimulationSetup) {
+        if !self.models.is_empty() {
+            setup.models = self.models.iter().map(|&m| m.into()).collect();
+        }
+    }
+}
+
+/// What a reproduced run was driven by: a built-in scenario or a preset.
+#[derive(Clone, Copy, Debug)]
+pub enum ReproScenario {
+    Scenario(PriceScenario),
+    Preset(ScenarioPreset),
+}
+
+impl From<PriceScenario> for ReproScenario {
+    fn from(s: PriceScenario) -> Self {
+        Self::Scenario(s)
+    }
+}
+
+impl From<ScenarioPreset> for ReproScenario {
+    fn from(p: ScenarioPreset) -> Self {
+        Self::Preset(p)
+    }
+}
+
+/// `fair-sim narrative` invocation replaying one campaign run from its seed.
+pub fn repro_command(
+    config: Option<&Path>,
+    portfolio: Option<&Path>,
+    mechanism: LiquidationMechanism,
+    scenario: impl Into<ReproScenario>,
+    seed: u64,
+) -> String {
+    let quote = |path: &Path| {
+        let path = path.display().to_string();
+        if path.contains(|c: char| c.is_whitespace() || c == '\'') {
+            format!("'{}'", path.replace('\'', "'\\''"))
+        } else {
+            path
+        }
+    };
+    let mut command = String::from("fair-sim narrative");
+    if let Some(path) = config {
+        command.push_str(&format!(" --config {}", quote(path)));
+    }
+    if let Some(path) = portfolio {
+        command.push_str(&format!(" --portfolio {}", quote(path)));
+    }
+    command.push_str(&format!(" --mechanism {}", mechanism.info().key));
+    if mechanism.info().configurable_split {
+        command.push_str(&format!(" --split {}", mechanism.keeper_share()));
+    }
+    match scenario.into() {
+        ReproScenario::Scenario(scenario) => {
+            let scenario = Scenario::from(scenario);
+            let name = scenario.to_possible_value().expect("scenarios are never skipped");
+            command.push_str(&format!(" --scenario {}", name.get_name()));
+        }
+        ReproScenario::Preset(preset) => command.push_str(&format!(" --preset {}", preset)),
+    }
+    command.push_str(&format!(" --run-seed {}", seed));
+    command
+}
+
+pub fn print_outliers(outliers: &[Outlier], command: impl Fn(u64) -> String) {
+    if outliers.is_empty() {
+        return;
+    }
+    println!("  Worst runs (reproduce with):");
+    for outlier in outliers {
+        println!("    run {:>5}  ${:>10.0}  {}", outlier.run, outlier.bad_debt, command(outlier.seed));
+    }
+}
+
+/// Saves `results` as JSON to `path`, with the run manifest alongside.
+pub fn save_results<T: serde::Serialize + ?Sized>(path: &Path, results: &T, manifest: Option<&RunManifest>) -> Result<(), String> {
+    fair_simulation::results::save_json(path, results)
+        .and_then(|()| manifest.map_or(Ok(()), |m| m.save(sidecar_path(path))))
+        .map_err(|e| format!("failed to save results to {}: {}", path.display(), e))?;
+    println!("Saved results to {} (manifest: {})", path.display(), sidecar_path(path).display());
+    Ok(())
+}
+
+/// `--table` path, checked for a `.md` or `.tex` extension before anything runs.
+pub fn parse_table_path(value: &str) -> Result<PathBuf, String> {
+    TableFormat::from_path(value).map_err(|e| e.to_string())?;
+    Ok(PathBuf::from(value))
+}
+
+pub fn save_table(path: &Path, table: &Table) -> Result<(), String> {
+    table.save(path).map_err(|e| format!("failed to save table to {}: {}", path.display(), e))?;
+    println!("Saved table to {}", path.display());
+    Ok(())
+}
+
+/// Appends a campaign to the `--db` results database.
+#[cfg(feature = "sqlite")]
+pub fn record_campaign(
+    path: &Path,
+    record: impl FnOnce(&mut fair_simulation::database::ResultsDb) -> fair_simulation::error::Result<i64>,
+) -> Result<(), String> {
+    let campaign = fair_simulation::database::ResultsDb::open(path)
+        .and_then(|mut db| record(&mut db))
+        .map_err(|e| format!("failed to record campaign in {}: {}", path.display(), e))?;
+    println!("Recorded campaign {} in {}", campaign, path.display());
+    Ok(())
+}
+
+pub fn parse_split(value: &str) -> Result<f64, String> {
+    let split: f64 = value.parse().map_err(|e| format!("invalid split '{}': {}", value, e))?;
+    LiquidationMechanism::KeeperPool { split }.validate().map_err(|e| e.to_string())?;
+    Ok(split)
+}
+
+pub fn parse_severity(value: &str) -> Result<f64, String> {
+    let severity: f64 = value.parse().map_err(|e| format!("invalid severity '{}': {}", value, e))?;
+    if !(severity.is_finite() && severity >= 0.0) {
+        return Err(format!("severity {} must be non-negative", severity));
+    }
+    Ok(severity)
+}
+
+pub fn parse_preset(name: &str) -> Result<ScenarioPreset, String> {
+    name.parse().map_err(|e: fair_simulation::error::Error| e.to_string())
+}
+
+pub fn parse_assertion(expr: &str) -> Result<Assertion, String> {
+    Assertion::parse(expr).map_err(|e| e.to_string())
+}
+
+/// Prints a usage/input error and returns the matching exit code.
+pub fn usage_error(message: impl std::fmt::Display) -> i32 {
+    eprintln!("{}", message);
+    EXIT_USAGE
+}
+
+fn main() {
+    let cli = Cli::parse();
+    init_tracing(cli.verbose, cli.log_format);
+    if let Some(threads) = cli.threads {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads as usize)
+            .build_global()
+            .expect("the global pool is built once, before any run");
+    }
+    let code = match cli.command {
+        Command::Poa(args) => poa::run(args),
+        Command::Cascade(args) => cascade::run(args),
+        Command::MonteCarlo(args) => monte_carlo::run(args),
+        Command::Corpus(args) => corpus::run(args),
+        Command::Monitor(args) => monitor::run(args),
+        Command::Reaggregate(args) => reaggregate::run(args),
+        Command::Diff(args) => diff::run(args),
+        Command::Compare(args) => compare::run(args),
+        Command::Experiments(args) => experiments::run(args),
+        Command::Mechanisms(args) => mechanisms::run(args),
+        Command::Narrative(args) => narrative::run(args),
+        Command::Animate(args) => animate::run(args),
+        Command::Backtest(args) => backtest::run(args),
+        Command::Recommend(args) => recommend::run(args),
+        Command::Invariants(args) => invariants::run(args),
+        Command::Fuzz(args) => fuzz::run(args),
+        Command::Contagion(args) => contagion::run(args),
+        Command::SharedLiquidity(args) => shared_liquidity::run(args),
+        Command::Emissions(args) => emissions::run(args),
+        Command::IncentiveCliff(args) => incentive_cliff::run(args),
+        Command::FeeBurn(args) => fee_burn::run(args),
+        Command::Fallback(args) => fallback::run(args),
+        Command::Mempool(args) => mempool::run(args),
+        Command::Switching(args) => switching::run(args),
+        Command::Cooldown(args) => cooldown::run(args),
+        Command::Grace(args) => grace::run(args),
+        Command::Attribution(args) => attribution::run(args),
+        Command::Timelock(args) => timelock::run(args),
+        Command::Treasury(args) => treasury::run(args),
+        Command::Flip(args) => flip::run(args),
+        Command::Llamma(args) => llamma::run(args),
+        Command::Staking(args) => staking::run(args),
+        Command::CommitReveal(args) => commit_reveal::run(args),
+        Command::BatchAuction(args) => batch_auction::run(args),
+        Command::PenaltyCurve(args) => penalty_curve::run(args),
+        Command::Reputation(args) => reputation::run(args),
+        Command::PoolSplit(args) => pool_split::run(args),
+        Command::LiquidationQueue(args) => liquidation_queue::run(args),
+        Command::Liquidity(args) => liquidity::run(args),
+        Command::Live(args) => live::run(args),
+        Command::Rotation(args) => rotation::run(args),
+        Command::Ruin(args) => ruin::run(args),
+        Command::Sweep(args) => sweep::run(args),
+        Command::Heatmap(args) => heatmap::run(args),
+        Command::Budget(args) => budget::run(args),
+        #[cfg(feature = "tui")]
+        Command::Dashboard(args) => dashboard::run(args),
+    };
+    std::process::exit(code);
+}
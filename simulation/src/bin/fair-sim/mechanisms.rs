@@ -0,0 +1,38 @@
+//! `fair-sim mechanisms`: lists every liquidation mechanism the engine
+//! implements with its capabilities, as `--mechanism` accepts them.
+
+use clap::Args;
+
+use fair_simulation::alerts::EXIT_OK;
+use fair_simulation::cascade::LiquidationMechanism;
+
+#[derive(Args, Debug)]
+pub struct MechanismsArgs {
+    /// Print the descriptors as JSON instead of a table
+    #[arg(long)]
+    json: bool,
+}
+
+pub fn run(args: MechanismsArgs) -> i32 {
+    let infos: Vec<_> = LiquidationMechanism::all().iter().map(LiquidationMechanism::info).collect();
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&infos).expect("descriptors serialize"));
+        return EXIT_OK;
+    }
+
+    let yes_no = |flag: bool| if flag { "yes" } else { "no" };
+    println!("| Key          | Partial | Commit-reveal | Stake | Split | Description");
+    println!("|--------------|---------|---------------|-------|-------|------------");
+    for info in infos {
+        println!(
+            "| {:12} | {:7} | {:13} | {:5} | {:5} | {}",
+            info.key,
+            yes_no(info.partial_liquidation),
+            yes_no(info.commit_reveal),
+            yes_no(info.requires_stake),
+            yes_no(info.configurable_split),
+            info.description,
+        );
+    }
+    EXIT_OK
+}
@@ -0,0 +1,71 @@
+//! `fair-sim mempool`: coverage and profit concentration as the share of
+//! keepers watching the public mempool goes from 0% (private) to 100%
+//! (fully public). The lead comes from `--lead-blocks`, else
+//! `[simulation.mempool]` in `--config`.
+//!
+//! ```bash
+//! fair-sim mempool --scenario flash-crash --lead-blocks 2 --runs 200 --seed 5
+//! ```
+
+use clap::Args;
+
+use fair_simulation::alerts::EXIT_OK;
+use fair_simulation::mempool::{sweep_visibility, VISIBILITY_SWEEP};
+
+use crate::{usage_error, MechanismArgs, RunArgs, ScenarioArgs};
+
+const DEFAULT_RUNS: usize = 100;
+
+#[derive(Args, Debug)]
+pub struct MempoolArgs {
+    #[command(flatten)]
+    run: RunArgs,
+    #[command(flatten)]
+    mechanisms: MechanismArgs,
+    #[command(flatten)]
+    scenarios: ScenarioArgs,
+    /// Blocks non-watching keepers lag behind watchers [default: 1]
+    #[arg(long)]
+    lead_blocks: Option<usize>,
+}
+
+pub fn run(args: MempoolArgs) -> i32 {
+    let mut setup = match args.run.setup() {
+        Ok(setup) => setup,
+        Err(e) => return usage_error(e),
+    };
+    args.mechanisms.apply(&mut setup);
+    args.scenarios.apply(&mut setup);
+    let runs = setup.runs_or(DEFAULT_RUNS);
+    if let Some(lead_blocks) = args.lead_blocks {
+        let mut mempool = setup.simulation.mempool.clone().unwrap_or_default();
+        mempool.lead_blocks = lead_blocks;
+        setup.simulation.mempool = Some(mempool);
+    }
+    if let Err(e) = args.run.manifest(&mut setup, runs, false) {
+        return usage_error(e);
+    }
+    let lead_blocks = setup.simulation.mempool.clone().unwrap_or_default().lead_blocks;
+
+    println!("=======================================================");
+    println!("  Mempool Visibility");
+    println!("  Coverage and concentration vs information asymmetry");
+    println!("=======================================================");
+    println!();
+    println!("  Watcher lead:            {} block(s)", lead_blocks);
+
+    for scenario in setup.scenarios() {
+        for mechanism in setup.mechanisms() {
+            let report = match sweep_visibility(mechanism, scenario, &VISIBILITY_SWEEP, runs, &setup.simulation) {
+                Ok(report) => report,
+                Err(e) => return usage_error(e),
+            };
+            println!();
+            println!("{} / {} ({} runs)", mechanism.name(), scenario.name(), runs);
+            println!("{}", "-".repeat(50));
+            report.print();
+        }
+    }
+
+    EXIT_OK
+}
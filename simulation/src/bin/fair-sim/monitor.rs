@@ -0,0 +1,106 @@
+//! `fair-sim monitor`: Monte Carlo forward from an indexer snapshot of
+//! current CDPs, oracle price, and surplus buffer.
+//!
+//! ```bash
+//! # Gate on the keeper pool's risk from current state
+//! fair-sim monitor snapshot.json --assert "buffer_exhaustion_prob<0.01"
+//! ```
+
+use std::path::PathBuf;
+
+use clap::Args;
+
+use fair_simulation::alerts::{self, Assertion, MONTE_CARLO_METRICS, SNAPSHOT_METRICS};
+use fair_simulation::snapshot::{run_monte_carlo_from_snapshot, ChainSnapshot};
+
+use crate::{parse_assertion, usage_error, MechanismArgs, ModelArgs, RunArgs};
+
+const DEFAULT_RUNS: usize = 1000;
+
+#[derive(Args, Debug)]
+pub struct MonitorArgs {
+    /// Indexer snapshot JSON
+    snapshot: PathBuf,
+    #[command(flatten)]
+    run: RunArgs,
+    #[command(flatten)]
+    models: ModelArgs,
+    #[command(flatten)]
+    mechanisms: MechanismArgs,
+    /// Risk assertion on the keeper pool results (repeatable)
+    #[arg(long = "assert", value_parser = parse_assertion)]
+    assertions: Vec<Assertion>,
+}
+
+pub fn run(args: MonitorArgs) -> i32 {
+    let mut setup = match args.run.setup() {
+        Ok(setup) => setup,
+        Err(e) => return usage_error(e),
+    };
+    args.models.apply(&mut setup);
+    args.mechanisms.apply(&mut setup);
+
+    if let Err(e) = alerts::validate_metrics(&args.assertions, &[MONTE_CARLO_METRICS, SNAPSHOT_METRICS]) {
+        return usage_error(e);
+    }
+    if !args.assertions.is_empty() && !setup.mechanisms().iter().any(|m| m.is_keeper_pool()) {
+        return usage_error("--assert checks the keeper pool; include --mechanism keeper-pool");
+    }
+    let runs = setup.runs_or(DEFAULT_RUNS);
+    if let Err(e) = args.run.manifest(&mut setup, runs, false) {
+        return usage_error(e);
+    }
+    if setup.simulation.insurance_fund.is_none() && args.assertions.iter().any(|a| a.metric == "fund_exhaustion_prob") {
+        return usage_error("fund_exhaustion_prob needs an insurance_fund in the config");
+    }
+
+    let snapshot = match ChainSnapshot::load(&args.snapshot) {
+        Ok(snapshot) => snapshot,
+        Err(e) => return usage_error(format!("failed to load snapshot {}: {}", args.snapshot.display(), e)),
+    };
+
+    println!("=======================================================");
+    println!("  Live Risk Monitor");
+    println!("  Monte Carlo from on-chain state");
+    println!("=======================================================");
+    println!();
+    println!("Snapshot:");
+    if let Some(block) = snapshot.block_number {
+        println!("  Block:                   {}", block);
+    }
+    println!("  Oracle price:            ${:.2}", snapshot.oracle_price);
+    println!("  CDPs:                    {}", snapshot.cdps.len());
+    println!("  Total collateral:        {:.1} ETH", snapshot.total_collateral());
+    println!("  Total debt:              ${:.0}", snapshot.total_debt());
+    println!("  System collateral ratio: {:.1}%", snapshot.system_collateral_ratio() * 100.0);
+    println!("  Liquidatable now:        {}", snapshot.currently_liquidatable(setup.simulation.min_collateral_ratio));
+    println!("  Surplus buffer:          ${:.0}", snapshot.surplus_buffer);
+    println!("  Runs per model:          {}", runs);
+    println!();
+
+    let mut violations = Vec::new();
+
+    for model in setup.models() {
+        println!("=======================================================");
+        println!("Price Model: {}", model.name());
+        println!("=======================================================");
+        println!();
+
+        for mechanism in setup.mechanisms() {
+            println!("Mechanism: {}", mechanism.name());
+            println!("{}", "-".repeat(50));
+            let report = run_monte_carlo_from_snapshot(&snapshot, model, mechanism, runs, &setup.simulation);
+            report.print();
+            println!();
+
+            if mechanism.is_keeper_pool() {
+                match alerts::check_all(&args.assertions, &report, model.name()) {
+                    Ok(found) => violations.extend(found),
+                    Err(e) => return usage_error(e),
+                }
+            }
+        }
+    }
+
+    alerts::report(&args.assertions, &violations)
+}
@@ -0,0 +1,554 @@
+//! `fair-sim monte-carlo`: tail risk of Fair vs Traditional per price model.
+//!
+//! ```bash
+//! # Gate on Fair's risk metrics (exits 1 if any assertion fails)
+//! fair-sim monte-carlo --assert "insolvency_prob<0.001"
+//!
+//! # Save the full result set for later comparison with `diff`, with its
+//! # run manifest alongside (before.manifest.json)
+//! fair-sim monte-carlo --output before.json
+//!
+//! # Every path as a Parquet row for Polars/DuckDB (`arrow` feature)
+//! fair-sim monte-carlo --runs 100000 --output paths.parquet
+//!
+//! # Price fans, bad-debt histograms and keeper profit Lorenz curves per
+//! # model (`plots` feature)
+//! fair-sim monte-carlo --runs 2000 --plot-dir plots --plot-format png
+//!
+//! # One self-contained HTML page for a forum post
+//! fair-sim monte-carlo --runs 2000 --seed 42 --html report.html
+//!
+//! # The summary table for the paper (LaTeX) or a forum post (.md)
+//! fair-sim monte-carlo --runs 2000 --seed 42 --table tail-risk.tex
+//!
+//! # Append the setup and every path to a SQLite database (`sqlite` feature)
+//! fair-sim monte-carlo --runs 2000 --db research.sqlite
+//!
+//! # Stress the on-chain CDP book instead of the synthetic one
+//! fair-sim monte-carlo --portfolio cdps.json
+//!
+//! # A multi-hour campaign that can be killed and rerun to resume where it
+//! # stopped, finishing with the same results as an uninterrupted run
+//! fair-sim monte-carlo --runs 100000 --seed 42 --checkpoint campaign.ckpt.jsonl
+//!
+//! # Ten million runs per cell in constant memory, VaR/CVaR from a t-digest
+//! fair-sim monte-carlo --runs 10000000 --seed 42 --streaming
+//!
+//! # Drive every run with a GBM or jump-diffusion path generated on the GPU
+//! # instead of the model's cascade scenario (`gpu` feature)
+//! fair-sim monte-carlo --model gbm --model jump-diffusion --runs 10000000 --paths gpu
+//!
+//! # Replay one saved corpus of paths against each mechanism, then again
+//! # under another config: every replay runs the same paths and seeds
+//! fair-sim corpus --model jump-diffusion --runs 100000 --seed 42 --output jd.json
+//! fair-sim monte-carlo --corpus jd.json
+//! fair-sim monte-carlo --corpus jd.json --config penalty-20.toml
+//!
+//! # Gate on the insurance fund lasting the tail (config with an
+//! # [simulation.insurance_fund] table)
+//! fair-sim monte-carlo --config insured.toml --assert "fund_exhaustion_prob<0.01"
+//!
+//! # Register the campaign in the experiment registry
+//! fair-sim monte-carlo --experiment penalty-13 \
+//!     --description "Baseline 13% penalty" --tag baseline --tag penalty
+//! ```
+
+use std::path::PathBuf;
+
+use clap::{Args, ValueEnum};
+
+use fair_simulation::alerts::{self, Assertion, MONTE_CARLO_METRICS};
+use fair_simulation::batch::{Batch, Job};
+use fair_simulation::checkpoint::{Checkpoint, DEFAULT_CHECKPOINT_EVERY};
+use fair_simulation::experiments::{ExperimentRegistry, DEFAULT_REGISTRY_DIR};
+use fair_simulation::manifest::sidecar_path;
+use fair_simulation::monte_carlo::{run_monte_carlo_streaming, scenario_for_model, MonteCarloResult, PriceModel};
+use fair_simulation::monte_carlo::PricePathConfig;
+use fair_simulation::outliers::{worst_monte_carlo_runs, DEFAULT_OUTLIERS};
+use fair_simulation::path_backend::{replay_corpus, run_on_paths, CpuBackend, PathBackend, PathCorpus};
+use fair_simulation::portfolio::CdpPortfolio;
+use fair_simulation::report::table::monte_carlo_summary;
+use fair_simulation::report::{monte_carlo_html, save_html};
+use fair_simulation::results::save_monte_carlo_as;
+
+use crate::{
+    parse_assertion, parse_table_path, print_outliers, repro_command, save_table, usage_error, MechanismArgs, ModelArgs,
+    RunArgs,
+};
+
+const SIMULATION_RUNS: usize = 10_000;
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum PlotFormat {
+    Svg,
+    Png,
+}
+
+/// Where `--paths` generates price paths.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum PathSource {
+    Cpu,
+    Gpu,
+}
+
+#[derive(Args, Debug)]
+pub struct MonteCarloArgs {
+    #[command(flatten)]
+    run: RunArgs,
+    #[command(flatten)]
+    models: ModelArgs,
+    #[command(flatten)]
+    mechanisms: MechanismArgs,
+    /// Risk assertion on the keeper pool results, e.g. "insolvency_prob<0.001" (repeatable)
+    #[arg(long = "assert", value_parser = parse_assertion)]
+    assertions: Vec<Assertion>,
+    /// Save the full result set as JSON, or as per-path Parquet rows for .parquet (`arrow` feature)
+    #[arg(long)]
+    output: Option<PathBuf>,
+    /// Write a self-contained HTML report (summary, Fair vs Traditional deltas, charts)
+    #[arg(long)]
+    html: Option<PathBuf>,
+    /// Write the summary as a Markdown (.md) or LaTeX (.tex) table
+    #[arg(long, value_parser = parse_table_path)]
+    table: Option<PathBuf>,
+    /// Append the setup and every run's metrics to this SQLite database (`sqlite` feature)
+    #[arg(long, env = "FAIR_SIM_DB")]
+    db: Option<PathBuf>,
+    /// CDP book (.csv or .json) to stress instead of the synthetic one
+    #[arg(long)]
+    portfolio: Option<PathBuf>,
+    /// Append completed runs to this file, resuming from it if it exists
+    #[arg(long)]
+    checkpoint: Option<PathBuf>,
+    /// Runs per cell between checkpoints
+    #[arg(long, default_value_t = DEFAULT_CHECKPOINT_EVERY, requires = "checkpoint")]
+    checkpoint_every: usize,
+    /// Estimate VaR/CVaR in memory independent of --runs, keeping no per-run metrics or seeds
+    #[arg(long, conflicts_with_all = ["checkpoint", "plot_dir"])]
+    streaming: bool,
+    /// Drive each run with a generated path of its price model rather than the model's scenario,
+    /// streamed as with --streaming (gpu: GBM and jump-diffusion, `gpu` feature)
+    #[arg(long, value_enum, conflicts_with_all = ["checkpoint", "plot_dir", "portfolio"])]
+    paths: Option<PathSource>,
+    /// Replay the paths and run seeds of a `fair-sim corpus` file instead of drawing runs,
+    /// overriding --model and --runs
+    #[arg(long, conflicts_with_all = ["checkpoint", "plot_dir", "portfolio", "paths", "streaming"])]
+    corpus: Option<PathBuf>,
+    /// Write price fans, bad-debt histograms and Lorenz curves per model here (`plots` feature)
+    #[arg(long)]
+    plot_dir: Option<PathBuf>,
+    /// Image format of the plots
+    #[arg(long, value_enum, default_value_t = PlotFormat::Svg, requires = "plot_dir")]
+    plot_format: PlotFormat,
+    /// Worst runs per result set to list with a reproduction command (0 = none)
+    #[arg(long, default_value_t = DEFAULT_OUTLIERS)]
+    outliers: usize,
+    /// Register the campaign under this name
+    #[arg(long)]
+    experiment: Option<String>,
+    /// Experiment description
+    #[arg(long, default_value = "", requires = "experiment")]
+    description: String,
+    /// Experiment tag (repeatable)
+    #[arg(long = "tag", requires = "experiment")]
+    tags: Vec<String>,
+    /// Experiment registry directory
+    #[arg(long, default_value = DEFAULT_REGISTRY_DIR)]
+    registry: PathBuf,
+}
+
+/// Charts written by `--plot-dir`.
+#[cfg(feature = "plots")]
+mod plots {
+    use std::path::{Path, PathBuf};
+
+    use clap::ValueEnum;
+
+    use fair_simulation::cascade::{replay_observed, LiveState, SimulationConfig};
+    use fair_simulation::monte_carlo::{scenario_for_model, MonteCarloResult};
+    use fair_simulation::plots::{bad_debt_histogram, lorenz_curves, price_fan};
+    use fair_simulation::portfolio::CdpPortfolio;
+
+    use super::PlotFormat;
+    use crate::Model;
+
+    /// Runs per result set replayed for price fans and Lorenz curves.
+    const PLOT_PATHS: usize = 200;
+
+    /// Price paths and final keeper profits of the first `PLOT_PATHS` runs
+    /// of `result`, replayed from their seeds.
+    fn replay_sample(
+        result: &MonteCarloResult,
+        portfolio: Option<&CdpPortfolio>,
+        config: &SimulationConfig,
+    ) -> (Vec<Vec<f64>>, Vec<f64>) {
+        let scenario = scenario_for_model(result.model);
+        let mut paths = Vec::new();
+        let mut profits = Vec::new();
+        for &seed in result.run_seeds.iter().take(PLOT_PATHS) {
+            let mut path = Vec::new();
+            let mut keeper_profits = Vec::new();
+            let observer = |state: &LiveState| {
+                if path.is_empty() {
+                    path.push(state.event.price_start);
+                }
+                path.push(state.event.price_end);
+                keeper_profits.clone_from(&state.keeper_profits);
+            };
+            match portfolio {
+                Some(portfolio) => portfolio.replay_observed(result.mechanism, scenario, config, seed, observer),
+                None => replay_observed(result.mechanism, scenario, config, seed, observer),
+            };
+            paths.push(path);
+            profits.extend(keeper_profits);
+        }
+        (paths, profits)
+    }
+
+    /// Writes one model's price fan, bad-debt histogram and Lorenz curves to
+    /// `dir`, returning the files written.
+    pub fn write(
+        dir: &Path,
+        format: PlotFormat,
+        results: &[MonteCarloResult],
+        portfolio: Option<&CdpPortfolio>,
+        config: &SimulationConfig,
+    ) -> Result<Vec<PathBuf>, String> {
+        let Some(model) = results.first().map(|r| r.model) else {
+            return Ok(Vec::new());
+        };
+        std::fs::create_dir_all(dir).map_err(|e| format!("failed to create {}: {}", dir.display(), e))?;
+        let name = Model::from(model).to_possible_value().expect("models are never skipped");
+        let extension = match format {
+            PlotFormat::Svg => "svg",
+            PlotFormat::Png => "png",
+        };
+        let file = |chart: &str| dir.join(format!("{}-{}.{}", name.get_name(), chart, extension));
+
+        let labels: Vec<String> = results.iter().map(|r| r.mechanism.name()).collect();
+        let samples: Vec<_> = results.iter().map(|r| replay_sample(r, portfolio, config)).collect();
+        let fans: Vec<_> = labels.iter().zip(&samples).map(|(l, (paths, _))| (l.as_str(), paths.as_slice())).collect();
+        let debts: Vec<_> = labels.iter().zip(results).map(|(l, r)| (l.as_str(), r.bad_debts.as_slice())).collect();
+        let profits: Vec<_> = labels.iter().zip(&samples).map(|(l, (_, p))| (l.as_str(), p.as_slice())).collect();
+
+        let files = [file("price-fan"), file("bad-debt"), file("lorenz")];
+        price_fan(&files[0], &format!("{}: ETH price", model.name()), &fans)
+            .and_then(|()| bad_debt_histogram(&files[1], &format!("{}: bad debt", model.name()), &debts))
+            .and_then(|()| lorenz_curves(&files[2], &format!("{}: keeper profits", model.name()), &profits))
+            .map_err(|e| format!("failed to write plots to {}: {}", dir.display(), e))?;
+        Ok(files.to_vec())
+    }
+}
+
+/// The generator `--paths` picks.
+pub fn path_backend(source: PathSource) -> Result<Box<dyn PathBackend>, String> {
+    match source {
+        PathSource::Cpu => Ok(Box::new(CpuBackend)),
+        #[cfg(feature = "gpu")]
+        PathSource::Gpu => fair_simulation::path_backend::GpuBackend::new()
+            .map(|gpu| Box::new(gpu) as Box<dyn PathBackend>)
+            .map_err(|e| e.to_string()),
+        #[cfg(not(feature = "gpu"))]
+        PathSource::Gpu => Err("--paths gpu needs the `gpu` feature (--features gpu)".to_string()),
+    }
+}
+
+fn print_comparison(trad: &MonteCarloResult, fair: &MonteCarloResult) {
+    let improvement = if trad.mean_bad_debt > 0.0 {
+        (1.0 - fair.mean_bad_debt / trad.mean_bad_debt) * 100.0
+    } else if fair.mean_bad_debt > 0.0 {
+        -100.0
+    } else {
+        0.0
+    };
+
+    println!("Comparison:");
+    println!("  Bad debt improvement:    {:.1}%", improvement);
+    println!(
+        "  VaR 99% ratio:           {:.2}x",
+        if trad.var_99 > 0.0 { fair.var_99 / trad.var_99 } else { 0.0 }
+    );
+    println!(
+        "  Insolvency prob ratio:   {:.2}x",
+        if trad.insolvency_probability > 0.0 {
+            fair.insolvency_probability / trad.insolvency_probability
+        } else {
+            0.0
+        }
+    );
+    println!();
+}
+
+pub fn run(args: MonteCarloArgs) -> i32 {
+    let mut setup = match args.run.setup() {
+        Ok(setup) => setup,
+        Err(e) => return usage_error(e),
+    };
+    args.models.apply(&mut setup);
+    args.mechanisms.apply(&mut setup);
+
+    if let Err(e) = alerts::validate_metrics(&args.assertions, &[MONTE_CARLO_METRICS]) {
+        return usage_error(e);
+    }
+    let mechanisms = setup.mechanisms();
+    if !args.assertions.is_empty() && !mechanisms.iter().any(|m| m.is_keeper_pool()) {
+        return usage_error("--assert checks the keeper pool; include --mechanism keeper-pool");
+    }
+
+    let portfolio = match args.portfolio.as_deref().map(CdpPortfolio::load).transpose() {
+        Ok(portfolio) => portfolio,
+        Err(e) => return usage_error(format!("failed to load portfolio: {}", e)),
+    };
+
+    if cfg!(not(feature = "plots")) && args.plot_dir.is_some() {
+        return usage_error("--plot-dir needs the `plots` feature (--features plots)");
+    }
+    if cfg!(not(feature = "sqlite")) && args.db.is_some() {
+        return usage_error("--db needs the `sqlite` feature (--features sqlite)");
+    }
+    let mut backend = match args.paths.map(path_backend).transpose() {
+        Ok(backend) => backend,
+        Err(e) => return usage_error(e),
+    };
+    let corpus = match args.corpus.as_deref().map(PathCorpus::load).transpose() {
+        Ok(corpus) => corpus,
+        Err(e) => return usage_error(format!("failed to load corpus: {}", e)),
+    };
+    if let Some(corpus) = &corpus {
+        // The corpus fixes the model and the runs
+        setup.models = vec![corpus.path_config.model];
+        setup.runs = Some(corpus.len());
+    }
+
+    let runs = setup.runs_or(SIMULATION_RUNS);
+    if let Some(path) = &args.checkpoint {
+        // An unseeded resume continues the checkpointed campaign's seed
+        match Checkpoint::config_at(path) {
+            Ok(recorded) => {
+                if setup.simulation.seed.is_none() {
+                    setup.simulation.seed = recorded.and_then(|config| config.seed);
+                }
+            }
+            Err(e) => return usage_error(format!("failed to read checkpoint {}: {}", path.display(), e)),
+        }
+    }
+    let attach = args.output.is_some() || args.experiment.is_some() || args.html.is_some() || args.db.is_some();
+    let manifest = match args.run.manifest(&mut setup, runs, attach || args.checkpoint.is_some()) {
+        Ok(manifest) => manifest,
+        Err(e) => return usage_error(e),
+    };
+    let config = &setup.simulation;
+    if config.insurance_fund.is_none() && args.assertions.iter().any(|a| a.metric == "fund_exhaustion_prob") {
+        return usage_error("fund_exhaustion_prob needs an insurance_fund in the config");
+    }
+    let mut checkpoint = match args.checkpoint.as_ref().map(|path| {
+        Checkpoint::open(path, config, runs, args.checkpoint_every)
+            .map_err(|e| format!("failed to open checkpoint {}: {}", path.display(), e))
+    }) {
+        Some(Ok(checkpoint)) => Some(checkpoint),
+        Some(Err(e)) => return usage_error(e),
+        None => None,
+    };
+    let mut violations = Vec::new();
+    let mut all_results = Vec::new();
+
+    println!("=======================================================");
+    println!("  Monte Carlo Stress Testing");
+    println!("  Statistical Analysis of Fair Stablecoin");
+    println!("=======================================================");
+    println!();
+    println!("Parameters:");
+    println!("  Runs per scenario: {}", runs);
+    match &portfolio {
+        Some(portfolio) => {
+            println!("  Keepers: {}", config.keepers.count);
+            portfolio.print(config.initial_eth_price);
+        }
+        None => println!("  CDPs: {}, Keepers: {}", config.num_cdps, config.keepers.count),
+    }
+    if let Some(seed) = config.seed {
+        println!("  Seed: {}", seed);
+    }
+    if let Some(backend) = &backend {
+        println!("  Paths: {}", backend.name());
+    }
+    if let (Some(corpus), Some(path)) = (&corpus, &args.corpus) {
+        println!("  Paths: {} ({}, {} paths)", path.display(), corpus.backend, corpus.len());
+    }
+    println!();
+
+    // Unless checkpointing or streaming, every cell runs as one batch, in
+    // which models sharing a scenario share their runs
+    let models = setup.models();
+    let jobs = if checkpoint.is_some() || args.streaming || backend.is_some() || corpus.is_some() {
+        Vec::new()
+    } else {
+        Job::model_matrix(&models, &mechanisms, runs)
+    };
+    let mut batched = match Batch::new(jobs).run(config, portfolio.as_ref()) {
+        Ok(batch) => batch.monte_carlo_results().into_iter(),
+        Err(e) => return usage_error(e),
+    };
+
+    for model in models {
+        println!("=======================================================");
+        println!("Price Model: {}", model.name());
+        println!("=======================================================");
+        println!();
+
+        let results = match &mut checkpoint {
+            None if corpus.is_some() => {
+                let corpus = corpus.as_ref().expect("checked above");
+                match mechanisms.iter().map(|&m| replay_corpus(corpus, m, config)).collect() {
+                    Ok(results) => Ok(results),
+                    Err(e) => return usage_error(format!("corpus replay failed: {}", e)),
+                }
+            }
+            Some(checkpoint) => mechanisms
+                .iter()
+                .map(|&mechanism| checkpoint.run_monte_carlo(model, mechanism, portfolio.as_ref()))
+                .collect::<Result<Vec<MonteCarloResult>, _>>(),
+            None if backend.is_some() => {
+                let path_config = PricePathConfig { model, blocks: config.max_blocks, ..Default::default() };
+                let backend = backend.as_deref_mut().expect("checked above");
+                match mechanisms.iter().map(|&m| run_on_paths(backend, &path_config, m, runs, config)).collect() {
+                    Ok(results) => Ok(results),
+                    Err(e) => return usage_error(format!("{} paths failed: {}", model.name(), e)),
+                }
+            }
+            None if args.streaming => Ok(mechanisms
+                .iter()
+                .map(|&mechanism| run_monte_carlo_streaming(model, mechanism, runs, config, portfolio.as_ref()))
+                .collect()),
+            None => Ok(batched.by_ref().take(mechanisms.len()).collect()),
+        };
+        let results = match results {
+            Ok(results) => results,
+            Err(e) => return usage_error(format!("checkpointed campaign failed: {}", e)),
+        };
+
+        for result in &results {
+            println!("Mechanism: {}", result.mechanism.name());
+            println!("{}", "-".repeat(50));
+            result.print();
+            print_outliers(&worst_monte_carlo_runs(result, args.outliers), |seed| {
+                let scenario = scenario_for_model(model);
+                repro_command(args.run.config_path(), args.portfolio.as_deref(), result.mechanism, scenario, seed)
+            });
+            println!();
+
+            if result.mechanism.is_keeper_pool() {
+                violations.extend(
+                    alerts::check_all(&args.assertions, result, model.name())
+                        .expect("assertion metrics validated above"),
+                );
+            }
+        }
+
+        let find = |keeper_pool: bool| results.iter().find(|r| r.mechanism.is_keeper_pool() == keeper_pool);
+        if let (Some(trad), Some(fair)) = (find(false), find(true)) {
+            print_comparison(trad, fair);
+        }
+
+        #[cfg(feature = "plots")]
+        if let Some(dir) = &args.plot_dir {
+            match plots::write(dir, args.plot_format, &results, portfolio.as_ref(), config) {
+                Ok(files) => {
+                    for file in files {
+                        println!("Saved plot to {}", file.display());
+                    }
+                    println!();
+                }
+                Err(e) => return usage_error(e),
+            }
+        }
+
+        all_results.extend(results);
+    }
+
+    if let Some(path) = &args.output {
+        let saved = save_monte_carlo_as(path, &all_results)
+            .and_then(|()| manifest.as_ref().map_or(Ok(()), |m| m.save(sidecar_path(path))));
+        match saved {
+            Ok(()) => println!(
+                "Saved {} result sets to {} (manifest: {})",
+                all_results.len(),
+                path.display(),
+                sidecar_path(path).display()
+            ),
+            Err(e) => return usage_error(format!("failed to save results to {}: {}", path.display(), e)),
+        }
+        println!();
+    }
+
+    if let Some(path) = &args.html {
+        let html = monte_carlo_html("Fair Monte Carlo stress test", &all_results, manifest.as_ref());
+        match save_html(path, &html) {
+            Ok(()) => println!("Saved HTML report to {}", path.display()),
+            Err(e) => return usage_error(format!("failed to save report to {}: {}", path.display(), e)),
+        }
+        println!();
+    }
+
+    if let Some(path) = &args.table {
+        if let Err(e) = save_table(path, &monte_carlo_summary(&all_results)) {
+            return usage_error(e);
+        }
+        println!();
+    }
+
+    #[cfg(feature = "sqlite")]
+    if let (Some(path), Some(manifest)) = (&args.db, &manifest) {
+        if let Err(e) = crate::record_campaign(path, |db| db.record_monte_carlo(manifest, &all_results)) {
+            return usage_error(e);
+        }
+        println!();
+    }
+
+    if let Some(name) = &args.experiment {
+        let registered = ExperimentRegistry::open(&args.registry).and_then(|mut registry| {
+            registry
+                .register(name, &args.description, &args.tags, &all_results, manifest.as_ref())
+                .map(|_| ())
+        });
+        match registered {
+            Ok(()) => println!("Registered experiment '{}' in {}", name, args.registry.display()),
+            Err(e) => return usage_error(format!("failed to register experiment '{}': {}", name, e)),
+        }
+        println!();
+    }
+
+    println!("=======================================================");
+    println!("  Summary Table");
+    println!("=======================================================");
+    println!();
+    print_summary_table(&all_results);
+    println!();
+
+    alerts::report(&args.assertions, &violations)
+}
+
+fn print_summary_table(results: &[MonteCarloResult]) {
+    println!("| Model            | Mechanism   | Mean Debt | VaR 99% | P(Insolvency) |");
+    println!("|------------------|-------------|-----------|---------|---------------|");
+
+    let mut previous_model = None;
+    for result in results {
+        let model_name = match result.model {
+            _ if previous_model == Some(result.model) => "",
+            PriceModel::GBM => "GBM",
+            PriceModel::JumpDiffusion => "Jump-Diff",
+            PriceModel::GARCH => "GARCH",
+            PriceModel::HistoricalMar2020 => "Mar 2020",
+            PriceModel::HistoricalMay2021 => "May 2021",
+            PriceModel::HistoricalNov2022 => "Nov 2022",
+        };
+        let mech_name = result.mechanism.info().short_name;
+        previous_model = Some(result.model);
+
+        println!(
+            "| {:16} | {:11} | ${:7.0} | ${:6.0} | {:12.1}% |",
+            model_name, mech_name, result.mean_bad_debt, result.var_99, result.insolvency_probability * 100.0
+        );
+    }
+}
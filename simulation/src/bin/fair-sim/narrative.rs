@@ -0,0 +1,183 @@
+//! `fair-sim narrative`: runs one cascade per scenario and mechanism and
+//! prints a human-readable timeline of what happened, block by block.
+//!
+//! ```bash
+//! # Replay an outlier reported by a campaign
+//! fair-sim narrative --config stress.toml --mechanism keeper-pool --split 0.7 \
+//!     --scenario black-swan --run-seed 1234567890
+//!
+//! # Walk through a named preset
+//! fair-sim narrative --preset LunaCollapse --mechanism keeper-pool
+//!
+//! # Check the run's books balance
+//! fair-sim narrative --scenario flash-crash --seed 7 --audit
+//!
+//! # Save the shocks a volatile run drew, then replay them under every mechanism
+//! fair-sim narrative --scenario volatile --mechanism traditional --run-seed 99 \
+//!     --record-shocks shocks.json
+//! fair-sim narrative --shocks shocks.json
+//! ```
+
+use std::path::PathBuf;
+
+use clap::Args;
+
+use fair_simulation::alerts::{EXIT_OK, EXIT_VIOLATION};
+use fair_simulation::audit::ConservationReport;
+use fair_simulation::cascade::{
+    replay_on_shocks, replay_recording_shocks, replay_with_events, run_single_with_events, BlockEvent, CascadeResult,
+};
+use fair_simulation::narrative::{narrate, summarize};
+use fair_simulation::portfolio::CdpPortfolio;
+use fair_simulation::price_path::ShockSequence;
+use fair_simulation::scenarios::ScenarioPreset;
+
+use crate::{parse_preset, usage_error, MechanismArgs, ScenarioArgs, SetupArgs};
+
+#[derive(Args, Debug)]
+pub struct NarrativeArgs {
+    #[command(flatten)]
+    setup: SetupArgs,
+    #[command(flatten)]
+    mechanisms: MechanismArgs,
+    #[command(flatten)]
+    scenarios: ScenarioArgs,
+    /// Replay the campaign run with this per-run seed
+    #[arg(long, conflicts_with = "seed")]
+    run_seed: Option<u64>,
+    /// CDP book (.csv or .json) the campaign ran on
+    #[arg(long, requires = "run_seed")]
+    portfolio: Option<PathBuf>,
+    /// Named preset to run instead of the built-in scenarios
+    #[arg(long, value_parser = parse_preset, conflicts_with_all = ["scenarios", "portfolio"])]
+    preset: Option<ScenarioPreset>,
+    /// Reconcile the run's collateral, debt and penalty flows
+    #[arg(long)]
+    audit: bool,
+    /// Save the shocks the run's scenario drew as JSON (one scenario and mechanism)
+    #[arg(long, requires = "run_seed", conflicts_with_all = ["preset", "portfolio"])]
+    record_shocks: Option<PathBuf>,
+    /// Drive every mechanism with shocks saved by --record-shocks instead of a scenario
+    #[arg(long, conflicts_with_all = ["scenarios", "preset", "portfolio", "record_shocks"])]
+    shocks: Option<PathBuf>,
+}
+
+/// Prints the timeline (and audit, if asked); false if the books do not reconcile.
+fn print_run(result: &CascadeResult, events: &[BlockEvent], audit: bool) -> bool {
+    for line in narrate(events) {
+        println!("  {}", line);
+    }
+    println!();
+    println!("  {}", summarize(result, events));
+    println!();
+    if !audit {
+        return true;
+    }
+    let report = ConservationReport::of(result);
+    report.print();
+    println!();
+    report.is_consistent()
+}
+
+pub fn run(args: NarrativeArgs) -> i32 {
+    let mut setup = match args.setup.setup() {
+        Ok(setup) => setup,
+        Err(e) => return usage_error(e),
+    };
+    args.mechanisms.apply(&mut setup);
+    args.scenarios.apply(&mut setup);
+    let portfolio = match args.portfolio.as_deref().map(CdpPortfolio::load).transpose() {
+        Ok(portfolio) => portfolio,
+        Err(e) => return usage_error(format!("failed to load portfolio: {}", e)),
+    };
+    if let Err(e) = args.setup.manifest(&mut setup, false) {
+        return usage_error(e);
+    }
+    let mut rng = setup.simulation.rng();
+    let mut balanced = true;
+
+    if let Some(path) = &args.shocks {
+        let shocks = match ShockSequence::load(path) {
+            Ok(shocks) => shocks,
+            Err(e) => return usage_error(format!("failed to load shocks from {}: {}", path.display(), e)),
+        };
+        let seed = args.run_seed.unwrap_or(shocks.seed);
+        println!("=======================================================");
+        println!("Recorded shocks: {} (run seed {}, {} blocks)", shocks.scenario.name(), shocks.seed, shocks.shocks.len());
+        println!("=======================================================");
+        println!();
+
+        for mechanism in setup.mechanisms() {
+            println!("Mechanism: {}", mechanism.name());
+            println!("{}", "-".repeat(50));
+
+            let (result, events) = replay_on_shocks(mechanism, &shocks, &setup.simulation, seed);
+            balanced &= print_run(&result, &events, args.audit);
+        }
+        return if balanced { EXIT_OK } else { EXIT_VIOLATION };
+    }
+    if let (Some(path), Some(seed)) = (&args.record_shocks, args.run_seed) {
+        let (&[scenario], &[mechanism]) = (setup.scenarios().as_slice(), setup.mechanisms().as_slice()) else {
+            return usage_error("--record-shocks needs a single --scenario and --mechanism");
+        };
+        println!("=======================================================");
+        println!("Scenario: {}", scenario.name());
+        println!("=======================================================");
+        println!();
+        println!("Mechanism: {}", mechanism.name());
+        println!("{}", "-".repeat(50));
+
+        let (result, events, shocks) = replay_recording_shocks(mechanism, scenario, &setup.simulation, seed);
+        balanced &= print_run(&result, &events, args.audit);
+        if let Err(e) = shocks.save(path) {
+            return usage_error(format!("failed to save shocks to {}: {}", path.display(), e));
+        }
+        println!("Saved {} shocks to {}", shocks.shocks.len(), path.display());
+        return if balanced { EXIT_OK } else { EXIT_VIOLATION };
+    }
+
+    if let Some(preset) = args.preset {
+        println!("=======================================================");
+        println!("Preset: {}", preset);
+        println!("=======================================================");
+        println!();
+
+        for mechanism in setup.mechanisms() {
+            println!("Mechanism: {}", mechanism.name());
+            println!("{}", "-".repeat(50));
+
+            let run = match args.run_seed {
+                Some(seed) => preset.replay(mechanism, &setup.simulation, seed),
+                None => preset
+                    .simulation(mechanism, &setup.simulation, &mut rng)
+                    .map(|mut sim| sim.run_with_events(&mut rng)),
+            };
+            match run {
+                Ok((result, events)) => balanced &= print_run(&result, &events, args.audit),
+                Err(e) => return usage_error(e),
+            }
+        }
+        return if balanced { EXIT_OK } else { EXIT_VIOLATION };
+    }
+
+    for scenario in setup.scenarios() {
+        println!("=======================================================");
+        println!("Scenario: {}", scenario.name());
+        println!("=======================================================");
+        println!();
+
+        for mechanism in setup.mechanisms() {
+            println!("Mechanism: {}", mechanism.name());
+            println!("{}", "-".repeat(50));
+
+            let (result, events) = match (args.run_seed, &portfolio) {
+                (Some(seed), Some(portfolio)) => portfolio.replay(mechanism, scenario, &setup.simulation, seed),
+                (Some(seed), None) => replay_with_events(mechanism, scenario, &setup.simulation, seed),
+                (None, _) => run_single_with_events(mechanism, scenario, &setup.simulation, &mut rng),
+            };
+            balanced &= print_run(&result, &events, args.audit);
+        }
+    }
+
+    if balanced { EXIT_OK } else { EXIT_VIOLATION }
+}
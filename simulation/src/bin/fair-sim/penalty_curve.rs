@@ -0,0 +1,108 @@
+//! `fair-sim penalty-curve`: sweeps penalty curves rising below the
+//! minimum ratio for the one with the least bad debt under a
+//! borrower-loss cap, against the flat penalty.
+//!
+//! ```bash
+//! fair-sim penalty-curve --scenario flash-crash --runs 200 --seed 5
+//!
+//! # A finer grid, borrowers losing at most $2M on average
+//! fair-sim penalty-curve --min-penalty 0.03 --min-penalty 0.06 --slope 0.1 --slope 2.0 --max-borrower-loss 2000000
+//! ```
+//!
+//! A `penalty_curve` table in the config file sets the max penalty; its
+//! starting penalty and slope join the swept ones.
+
+use clap::Args;
+
+use fair_simulation::alerts::EXIT_OK;
+use fair_simulation::penalty_curve::{sweep_penalty_curves, MIN_PENALTIES, PENALTY_SLOPES};
+
+use crate::{usage_error, MechanismArgs, RunArgs, ScenarioArgs};
+
+const DEFAULT_RUNS: usize = 50;
+
+#[derive(Args, Debug)]
+pub struct PenaltyCurveArgs {
+    #[command(flatten)]
+    run: RunArgs,
+    #[command(flatten)]
+    mechanisms: MechanismArgs,
+    #[command(flatten)]
+    scenarios: ScenarioArgs,
+    /// Penalties at the minimum ratio to sweep (repeatable) [default: 0.02, 0.05 and 0.10]
+    #[arg(long = "min-penalty")]
+    min_penalties: Vec<f64>,
+    /// Penalty added per unit of ratio below the minimum, to sweep (repeatable) [default: 0, 0.25, 0.5 and 1]
+    #[arg(long = "slope")]
+    slopes: Vec<f64>,
+    /// Cap on every curve's penalty [default: 0.20]
+    #[arg(long)]
+    max_penalty: Option<f64>,
+    /// Average borrower loss, USD, the best curve may not exceed [default: the flat penalty's]
+    #[arg(long)]
+    max_borrower_loss: Option<f64>,
+}
+
+pub fn run(args: PenaltyCurveArgs) -> i32 {
+    let mut setup = match args.run.setup() {
+        Ok(setup) => setup,
+        Err(e) => return usage_error(e),
+    };
+    args.mechanisms.apply(&mut setup);
+    args.scenarios.apply(&mut setup);
+    let runs = setup.runs_or(DEFAULT_RUNS);
+    if let Err(e) = args.run.manifest(&mut setup, runs, false) {
+        return usage_error(e);
+    }
+    // The sweep sets the curve per point; a configured table joins the grid
+    let configured = setup.simulation.penalty_curve.take();
+    let max_penalty = args.max_penalty.unwrap_or(configured.unwrap_or_default().max_penalty);
+    let mut min_penalties = if args.min_penalties.is_empty() { MIN_PENALTIES.to_vec() } else { args.min_penalties };
+    let mut slopes = if args.slopes.is_empty() { PENALTY_SLOPES.to_vec() } else { args.slopes };
+    if let Some(curve) = configured {
+        if !min_penalties.contains(&curve.min_penalty) {
+            min_penalties.push(curve.min_penalty);
+        }
+        if !slopes.contains(&curve.slope) {
+            slopes.push(curve.slope);
+        }
+    }
+    min_penalties.sort_by(f64::total_cmp);
+    slopes.sort_by(f64::total_cmp);
+
+    println!("=======================================================");
+    println!("  Penalty Curves");
+    println!("  Bad debt under a borrower-loss cap vs the flat penalty");
+    println!("=======================================================");
+    println!();
+    println!(
+        "{} starting penalties x {} slopes, capped at {:.0}%",
+        min_penalties.len(),
+        slopes.len(),
+        max_penalty * 100.0,
+    );
+
+    for scenario in setup.scenarios() {
+        for mechanism in setup.mechanisms() {
+            let report = match sweep_penalty_curves(
+                mechanism,
+                scenario,
+                &min_penalties,
+                &slopes,
+                max_penalty,
+                args.max_borrower_loss,
+                runs,
+                &setup.simulation,
+            ) {
+                Ok(report) => report,
+                Err(e) => return usage_error(e),
+            };
+            println!();
+            println!("{} / {} ({} runs)", mechanism.name(), scenario.name(), runs);
+            println!("{}", "-".repeat(50));
+            report.print();
+        }
+    }
+
+    EXIT_OK
+}
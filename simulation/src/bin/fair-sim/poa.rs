@@ -0,0 +1,122 @@
+//! `fair-sim poa`: Price of Anarchy across obfuscation strategies.
+//!
+//! The game has its own fixed setup; only runs, seed, the keeper pool
+//! split and the config's `[perception]` calibration apply.
+//! `--output games.json` saves every game as JSON; `--table poa.tex` the
+//! per-strategy summary as a LaTeX (or, for `.md`, Markdown) table.
+
+use std::path::PathBuf;
+
+use clap::Args;
+
+use fair_simulation::alerts::EXIT_OK;
+use fair_simulation::poa::{compute_poa, run_poa_simulation_with_calibration, ObfuscationStrategy};
+use fair_simulation::report::table::poa_summary;
+
+use crate::{parse_split, parse_table_path, save_results, save_table, usage_error, RunArgs};
+
+const SIMULATION_RUNS: usize = 10_000;
+
+#[derive(Args, Debug)]
+pub struct PoaArgs {
+    #[command(flatten)]
+    run: RunArgs,
+    /// Keeper share of each penalty under the keeper pool [default: 0.7]
+    #[arg(long, value_parser = parse_split)]
+    split: Option<f64>,
+    /// Save every game of every strategy as JSON (`[GameResult]`)
+    #[arg(long)]
+    output: Option<PathBuf>,
+    /// Write the per-strategy summary as a Markdown (.md) or LaTeX (.tex) table
+    #[arg(long, value_parser = parse_table_path)]
+    table: Option<PathBuf>,
+}
+
+pub fn run(args: PoaArgs) -> i32 {
+    let mut setup = match args.run.setup() {
+        Ok(setup) => setup,
+        Err(e) => return usage_error(e),
+    };
+    let runs = setup.runs_or(SIMULATION_RUNS);
+    let manifest = match args.run.manifest(&mut setup, runs, args.output.is_some()) {
+        Ok(manifest) => manifest,
+        Err(e) => return usage_error(e),
+    };
+
+    println!("=======================================================");
+    println!("  IPFE Price of Anarchy Simulation");
+    println!("  Comparing obfuscation strategies for liquidation");
+    println!("=======================================================\n");
+
+    let mut campaigns = Vec::new();
+    for strategy in ObfuscationStrategy::all() {
+        let strategy = match (strategy, args.split) {
+            (ObfuscationStrategy::KeeperPool { .. }, Some(split)) => ObfuscationStrategy::KeeperPool { split },
+            _ => strategy,
+        };
+        println!("Strategy: {}", strategy.name());
+        println!("{}", "-".repeat(50));
+
+        let results = run_poa_simulation_with_calibration(strategy, runs, setup.simulation.seed, &setup.perception);
+        let poa = compute_poa(&results);
+
+        let avg_successful: f64 = results
+            .iter()
+            .map(|r| r.successful_liquidations as f64)
+            .sum::<f64>()
+            / runs as f64;
+
+        let avg_failed: f64 = results.iter().map(|r| r.failed_attempts as f64).sum::<f64>()
+            / runs as f64;
+
+        let avg_missed: f64 = results
+            .iter()
+            .map(|r| r.missed_liquidations as f64)
+            .sum::<f64>()
+            / runs as f64;
+
+        let avg_concentration: f64 = results.iter().map(|r| r.profit_concentration).sum::<f64>()
+            / runs as f64;
+
+        let front_runner_share: f64 = results
+            .iter()
+            .map(|r| {
+                if r.total_profit > 0.0 {
+                    r.front_runner_profit / r.total_profit
+                } else {
+                    0.0
+                }
+            })
+            .sum::<f64>()
+            / runs as f64;
+
+        println!("  Successful liquidations: {:.1}", avg_successful);
+        println!("  Failed attempts:         {:.1}", avg_failed);
+        println!("  Missed (bad debt risk):  {:.1}", avg_missed);
+        println!("  Profit concentration:    {:.1}%", avg_concentration * 100.0);
+        println!("  Front-runner share:      {:.1}%", front_runner_share * 100.0);
+        println!("  Price of Anarchy:        {:.2}", poa);
+        println!();
+        campaigns.push(results);
+    }
+
+    println!("=======================================================");
+    println!("  Interpretation:");
+    println!("  - PoA = 1.0 means fair, efficient market");
+    println!("  - PoA > 1.0 means value extraction by sophisticated actors");
+    println!("  - Lower PoA = better for protocol health");
+    println!("=======================================================");
+
+    if let Some(path) = &args.output {
+        if let Err(e) = save_results(path, &campaigns.concat(), manifest.as_ref()) {
+            return usage_error(e);
+        }
+    }
+    if let Some(path) = &args.table {
+        if let Err(e) = save_table(path, &poa_summary(&campaigns)) {
+            return usage_error(e);
+        }
+    }
+
+    EXIT_OK
+}
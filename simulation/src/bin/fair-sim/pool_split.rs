@@ -0,0 +1,69 @@
+//! `fair-sim pool-split`: the keeper pool's members' part split alike
+//! against pro rata to capital, by profit inequality and small-keeper
+//! viability.
+//!
+//! ```bash
+//! fair-sim pool-split --scenario flash-crash --runs 200 --seed 5
+//!
+//! # A few whales among many small keepers
+//! fair-sim pool-split --pareto-alpha 1.1
+//! ```
+//!
+//! The keepers' capital distribution comes from the config file unless
+//! `--pareto-alpha` replaces it; its `pool_split` is compared either way.
+
+use clap::Args;
+
+use fair_simulation::alerts::EXIT_OK;
+use fair_simulation::cascade::CapitalDistribution;
+use fair_simulation::pool_split::compare_pool_splits;
+
+use crate::{usage_error, RunArgs, ScenarioArgs};
+
+const DEFAULT_RUNS: usize = 100;
+
+#[derive(Args, Debug)]
+pub struct PoolSplitArgs {
+    #[command(flatten)]
+    run: RunArgs,
+    #[command(flatten)]
+    scenarios: ScenarioArgs,
+    /// Draw keeper capital from a Pareto tail from $10k with this alpha
+    #[arg(long)]
+    pareto_alpha: Option<f64>,
+}
+
+pub fn run(args: PoolSplitArgs) -> i32 {
+    let mut setup = match args.run.setup() {
+        Ok(setup) => setup,
+        Err(e) => return usage_error(e),
+    };
+    args.scenarios.apply(&mut setup);
+    if let Some(alpha) = args.pareto_alpha {
+        setup.simulation.keepers.capital = CapitalDistribution::PowerLaw { min: 10_000.0, alpha };
+    }
+    let runs = setup.runs_or(DEFAULT_RUNS);
+    if let Err(e) = args.run.manifest(&mut setup, runs, false) {
+        return usage_error(e);
+    }
+
+    println!("=======================================================");
+    println!("  Pool Splits");
+    println!("  Equal shares versus pro rata to capital");
+    println!("=======================================================");
+    println!();
+    println!("{} keepers, capital {:?}", setup.simulation.keepers.count, setup.simulation.keepers.capital);
+
+    for scenario in setup.scenarios() {
+        let report = match compare_pool_splits(scenario, runs, &setup.simulation) {
+            Ok(report) => report,
+            Err(e) => return usage_error(e),
+        };
+        println!();
+        println!("{} ({} runs)", scenario.name(), runs);
+        println!("{}", "-".repeat(50));
+        report.print();
+    }
+
+    EXIT_OK
+}
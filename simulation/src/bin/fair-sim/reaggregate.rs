@@ -0,0 +1,119 @@
+//! `fair-sim reaggregate`: recomputes tail metrics of a saved Monte Carlo
+//! result set at another insolvency threshold or VaR/CVaR levels, from the
+//! per-run bad debts, without re-simulating.
+//!
+//! ```bash
+//! fair-sim monte-carlo --runs 10000 --seed 42 --output runs.json
+//! fair-sim reaggregate runs.json --insolvency 250000 --level 0.975 --level 0.995
+//!
+//! # Gate on a level the campaign was not summarized at
+//! fair-sim reaggregate runs.json --level 0.975 --assert "var_975<500000"
+//! ```
+//!
+//! Assertions name levels by their digits (`var_975`, `cvar_995`) and check
+//! the keeper pool results, as `monte-carlo --assert` does.
+
+use std::path::PathBuf;
+
+use clap::Args;
+
+use fair_simulation::alerts::{self, Assertion};
+use fair_simulation::monte_carlo::{TailRisk, TailThresholds};
+use fair_simulation::results::{load_monte_carlo, save_json};
+
+use crate::{parse_assertion, usage_error};
+
+#[derive(Args, Debug)]
+pub struct ReaggregateArgs {
+    /// Result set saved by `monte-carlo --output` (JSON)
+    results: PathBuf,
+    /// Bad debt above which a run counts as insolvent [default: 100000]
+    #[arg(long)]
+    insolvency: Option<f64>,
+    /// VaR/CVaR confidence level (repeatable) [default: 0.95, 0.99 and 0.999]
+    #[arg(long = "level")]
+    levels: Vec<f64>,
+    /// Risk assertion on the keeper pool results, e.g. "var_975<500000" (repeatable)
+    #[arg(long = "assert", value_parser = parse_assertion)]
+    assertions: Vec<Assertion>,
+    /// Save the re-aggregated metrics as JSON
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+pub fn run(args: ReaggregateArgs) -> i32 {
+    let mut thresholds = TailThresholds::default();
+    if let Some(insolvency) = args.insolvency {
+        thresholds.insolvency = insolvency;
+    }
+    if !args.levels.is_empty() {
+        thresholds.levels = args.levels;
+    }
+    let metrics = alerts::tail_metrics(&thresholds);
+    let known: Vec<&str> = metrics.iter().map(String::as_str).collect();
+    if let Err(e) = alerts::validate_metrics(&args.assertions, &[&known]) {
+        return usage_error(e);
+    }
+    let results = match load_monte_carlo(&args.results) {
+        Ok(results) => results,
+        Err(e) => return usage_error(format!("failed to load {}: {}", args.results.display(), e)),
+    };
+    let tails = match results.iter().map(|result| result.reaggregate(&thresholds)).collect::<Result<Vec<_>, _>>() {
+        Ok(tails) => tails,
+        Err(e) => return usage_error(e),
+    };
+
+    println!("=======================================================");
+    println!("  Re-aggregated Tail Risk");
+    println!("  {} ({} result sets)", args.results.display(), tails.len());
+    println!("=======================================================");
+
+    let mut violations = Vec::new();
+    for tail in &tails {
+        println!();
+        println!("{} / {}", tail.model.name(), tail.mechanism.name());
+        println!("{}", "-".repeat(50));
+        tail.print();
+        if tail.mechanism.is_keeper_pool() {
+            violations.extend(
+                alerts::check_all(&args.assertions, tail, tail.model.name()).expect("assertion metrics validated above"),
+            );
+        }
+    }
+    println!();
+    print_summary_table(&tails);
+    println!();
+
+    if let Some(path) = &args.output {
+        if let Err(e) = save_json(path, &tails) {
+            return usage_error(format!("failed to save {}: {}", path.display(), e));
+        }
+        println!("Saved re-aggregated metrics to {}", path.display());
+        println!();
+    }
+
+    alerts::report(&args.assertions, &violations)
+}
+
+fn print_summary_table(tails: &[TailRisk]) {
+    let mut header = "| Model                | Mechanism                      | P(Insolvency) |".to_string();
+    let mut rule = "|----------------------|--------------------------------|---------------|".to_string();
+    for level in tails.first().map_or(&[][..], |tail| &tail.levels) {
+        header.push_str(&format!(" {:>11} |", format!("VaR {}%", level.percent())));
+        rule.push_str("-------------|");
+    }
+    println!("{}", header);
+    println!("{}", rule);
+    for tail in tails {
+        let mut row = format!(
+            "| {:20} | {:30} | {:12.1}% |",
+            tail.model.name(),
+            tail.mechanism.name(),
+            tail.insolvency_probability * 100.0
+        );
+        for level in &tail.levels {
+            row.push_str(&format!(" ${:10.0} |", level.var));
+        }
+        println!("{}", row);
+    }
+}
@@ -0,0 +1,77 @@
+//! `fair-sim recommend`: proposes penalty / MCR / keeper pool split values
+//! that meet governance risk targets and prints the supporting evidence as
+//! Markdown. Exits 1 when no candidate meets every target.
+//!
+//! ```bash
+//! fair-sim recommend --target "insolvency_prob<0.001" --target "var_99<250000" \
+//!     --runs 500 --output recommendation.md
+//! ```
+
+use std::path::PathBuf;
+
+use clap::Args;
+
+use fair_simulation::alerts::{Assertion, EXIT_OK, EXIT_VIOLATION};
+use fair_simulation::recommend::{recommend, ParameterGrid};
+
+use crate::{parse_assertion, usage_error, ModelArgs, RunArgs};
+
+const DEFAULT_RUNS: usize = 200;
+
+#[derive(Args, Debug)]
+pub struct RecommendArgs {
+    #[command(flatten)]
+    run: RunArgs,
+    #[command(flatten)]
+    models: ModelArgs,
+    /// Risk target every model must meet (repeatable)
+    #[arg(
+        long = "target",
+        value_parser = parse_assertion,
+        default_value = "insolvency_prob<0.01"
+    )]
+    targets: Vec<Assertion>,
+    /// Write the Markdown report to this path
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+pub fn run(args: RecommendArgs) -> i32 {
+    let mut setup = match args.run.setup() {
+        Ok(setup) => setup,
+        Err(e) => return usage_error(e),
+    };
+    args.models.apply(&mut setup);
+    let runs = setup.runs_or(DEFAULT_RUNS);
+    if let Err(e) = args.run.manifest(&mut setup, runs, false) {
+        return usage_error(e);
+    }
+    let grid = ParameterGrid::default();
+
+    println!("=======================================================");
+    println!("  Protocol Parameter Recommendation");
+    println!("  {} candidates x {} models x {} runs", grid.len(), setup.models().len(), runs);
+    println!("=======================================================");
+    println!();
+
+    let recommendation = match recommend(&args.targets, &grid, &setup.models(), runs, &setup.simulation) {
+        Ok(recommendation) => recommendation,
+        Err(e) => return usage_error(e),
+    };
+
+    let markdown = recommendation.to_markdown();
+    println!("{}", markdown);
+
+    if let Some(path) = &args.output {
+        if let Err(e) = std::fs::write(path, &markdown) {
+            return usage_error(format!("failed to write report to {}: {}", path.display(), e));
+        }
+        println!("Saved report to {}", path.display());
+    }
+
+    if recommendation.recommended().is_none() {
+        EXIT_VIOLATION
+    } else {
+        EXIT_OK
+    }
+}
@@ -0,0 +1,84 @@
+//! `fair-sim rotation`: keeper pool active sets rotated per epoch, swept
+//! over set size and epoch length, with the liveness risk of a set that is
+//! entirely offline during a crash. The offline rate comes from
+//! `--offline-rate`, else `[simulation.keeper_rotation]` in `--config`.
+//!
+//! ```bash
+//! fair-sim rotation --scenario flash-crash --offline-rate 0.3 --runs 200 --seed 5
+//!
+//! # Specific set sizes and epochs
+//! fair-sim rotation --set-size 4 --set-size 8 --epoch-blocks 5 --epoch-blocks 100
+//! ```
+
+use clap::Args;
+
+use fair_simulation::alerts::EXIT_OK;
+use fair_simulation::cascade::DEFAULT_POOL_SPLIT;
+use fair_simulation::rotation::{sweep_rotation, EPOCH_LENGTHS, SET_SIZES};
+
+use crate::{parse_split, usage_error, RunArgs, ScenarioArgs};
+
+const DEFAULT_RUNS: usize = 100;
+
+#[derive(Args, Debug)]
+pub struct RotationArgs {
+    #[command(flatten)]
+    run: RunArgs,
+    #[command(flatten)]
+    scenarios: ScenarioArgs,
+    /// Keeper share of each penalty under the keeper pool [default: 0.7]
+    #[arg(long, value_parser = parse_split)]
+    split: Option<f64>,
+    /// Active set size (repeatable) [default: 3, 5, 10, 20]
+    #[arg(long = "set-size")]
+    set_sizes: Vec<usize>,
+    /// Blocks per epoch (repeatable) [default: 10, 50]
+    #[arg(long = "epoch-blocks")]
+    epochs: Vec<usize>,
+    /// Chance each active member is offline for its epoch [default: 0.2]
+    #[arg(long)]
+    offline_rate: Option<f64>,
+}
+
+pub fn run(args: RotationArgs) -> i32 {
+    let mut setup = match args.run.setup() {
+        Ok(setup) => setup,
+        Err(e) => return usage_error(e),
+    };
+    args.scenarios.apply(&mut setup);
+    let runs = setup.runs_or(DEFAULT_RUNS);
+    if let Some(offline_rate) = args.offline_rate {
+        let mut rotation = setup.simulation.keeper_rotation.unwrap_or_default();
+        rotation.offline_rate = offline_rate;
+        setup.simulation.keeper_rotation = Some(rotation);
+    }
+    if let Err(e) = args.run.manifest(&mut setup, runs, false) {
+        return usage_error(e);
+    }
+    let split = args.split.unwrap_or(DEFAULT_POOL_SPLIT);
+    let set_sizes = if args.set_sizes.is_empty() { SET_SIZES.to_vec() } else { args.set_sizes.clone() };
+    let epochs = if args.epochs.is_empty() { EPOCH_LENGTHS.to_vec() } else { args.epochs.clone() };
+
+    println!("=======================================================");
+    println!("  Keeper Rotation");
+    println!("  Active set size and epoch length vs liveness");
+    println!("=======================================================");
+    println!();
+    println!(
+        "  Offline rate:            {:.0}% per member per epoch",
+        setup.simulation.keeper_rotation.unwrap_or_default().offline_rate * 100.0
+    );
+
+    for scenario in setup.scenarios() {
+        let report = match sweep_rotation(split, scenario, &set_sizes, &epochs, runs, &setup.simulation) {
+            Ok(report) => report,
+            Err(e) => return usage_error(e),
+        };
+        println!();
+        println!("Keeper pool {:.0}/{:.0} / {} ({} runs)", split * 100.0, (1.0 - split) * 100.0, scenario.name(), runs);
+        println!("{}", "-".repeat(50));
+        report.print();
+    }
+
+    EXIT_OK
+}
@@ -0,0 +1,82 @@
+//! `fair-sim ruin`: how often small keepers are wiped out under each
+//! mechanism, net of gas (reverted races included) and inventory drawdowns.
+//!
+//! ```bash
+//! fair-sim ruin --scenario volatile --runs 50 --seed 5
+//!
+//! # Longer careers, ruin at a 25% drawdown, the bottom decile as small
+//! fair-sim ruin --campaigns 50 --ruin-level 0.75 --small-quantile 0.1
+//! ```
+//!
+//! `--runs` counts careers; each career carries one keeper population's
+//! capital through `--campaigns` consecutive cascades.
+
+use clap::Args;
+
+use fair_simulation::alerts::EXIT_OK;
+use fair_simulation::ruin::{analyze_ruin, RuinConfig};
+
+use crate::{usage_error, MechanismArgs, RunArgs, ScenarioArgs};
+
+const DEFAULT_CAREERS: usize = 20;
+
+#[derive(Args, Debug)]
+pub struct RuinArgs {
+    #[command(flatten)]
+    run: RunArgs,
+    #[command(flatten)]
+    mechanisms: MechanismArgs,
+    #[command(flatten)]
+    scenarios: ScenarioArgs,
+    /// Consecutive cascades per career
+    #[arg(long, default_value_t = RuinConfig::default().campaigns)]
+    campaigns: usize,
+    /// Share of starting capital at which a keeper counts as wiped out
+    #[arg(long, default_value_t = RuinConfig::default().ruin_level)]
+    ruin_level: f64,
+    /// Keepers at or below this starting capital quantile count as small
+    #[arg(long, default_value_t = RuinConfig::default().small_quantile)]
+    small_quantile: f64,
+}
+
+pub fn run(args: RuinArgs) -> i32 {
+    let mut setup = match args.run.setup() {
+        Ok(setup) => setup,
+        Err(e) => return usage_error(e),
+    };
+    args.mechanisms.apply(&mut setup);
+    args.scenarios.apply(&mut setup);
+    let careers = setup.runs_or(DEFAULT_CAREERS);
+    if let Err(e) = args.run.manifest(&mut setup, careers, false) {
+        return usage_error(e);
+    }
+    let ruin = RuinConfig {
+        campaigns: args.campaigns,
+        ruin_level: args.ruin_level,
+        small_quantile: args.small_quantile,
+    };
+
+    println!("=======================================================");
+    println!("  Keeper Risk of Ruin");
+    println!("  Small keeper viability net of gas and inventory drawdowns");
+    println!("=======================================================");
+
+    for scenario in setup.scenarios() {
+        let report = match analyze_ruin(&setup.mechanisms(), scenario, careers, &ruin, &setup.simulation) {
+            Ok(report) => report,
+            Err(e) => return usage_error(e),
+        };
+        println!();
+        println!(
+            "{} ({} careers of {} cascades, ruin at {:.0}% of starting capital)",
+            scenario.name(),
+            careers,
+            ruin.campaigns,
+            ruin.ruin_level * 100.0
+        );
+        println!("{}", "-".repeat(50));
+        report.print();
+    }
+
+    EXIT_OK
+}
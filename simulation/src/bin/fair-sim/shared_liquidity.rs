@@ -0,0 +1,59 @@
+//! `fair-sim shared-liquidity`: whether Fair's rewards still win keeper
+//! capital when rival stablecoins liquidate in the same market-wide event.
+//! Rival volume comes from `[simulation.shared_liquidity]` in `--config`.
+//!
+//! ```bash
+//! fair-sim shared-liquidity --scenario black-swan --runs 200 --seed 5
+//! ```
+
+use clap::Args;
+
+use fair_simulation::alerts::EXIT_OK;
+use fair_simulation::shared_liquidity::{sweep_competitor_bonus, BONUS_SWEEP};
+
+use crate::{usage_error, MechanismArgs, RunArgs, ScenarioArgs};
+
+const DEFAULT_RUNS: usize = 100;
+
+#[derive(Args, Debug)]
+pub struct SharedLiquidityArgs {
+    #[command(flatten)]
+    run: RunArgs,
+    #[command(flatten)]
+    mechanisms: MechanismArgs,
+    #[command(flatten)]
+    scenarios: ScenarioArgs,
+}
+
+pub fn run(args: SharedLiquidityArgs) -> i32 {
+    let mut setup = match args.run.setup() {
+        Ok(setup) => setup,
+        Err(e) => return usage_error(e),
+    };
+    args.mechanisms.apply(&mut setup);
+    args.scenarios.apply(&mut setup);
+    let runs = setup.runs_or(DEFAULT_RUNS);
+    if let Err(e) = args.run.manifest(&mut setup, runs, false) {
+        return usage_error(e);
+    }
+
+    println!("=======================================================");
+    println!("  Shared-Liquidity Stress");
+    println!("  Rival protocols bidding for the same keeper capital");
+    println!("=======================================================");
+
+    for scenario in setup.scenarios() {
+        for mechanism in setup.mechanisms() {
+            let report = match sweep_competitor_bonus(mechanism, scenario, &BONUS_SWEEP, runs, &setup.simulation) {
+                Ok(report) => report,
+                Err(e) => return usage_error(e),
+            };
+            println!();
+            println!("{} / {} ({} runs)", mechanism.name(), scenario.name(), runs);
+            println!("{}", "-".repeat(50));
+            report.print();
+        }
+    }
+
+    EXIT_OK
+}
@@ -0,0 +1,130 @@
+//! `fair-sim sweep`: cascade metrics over the cross-product of config field
+//! ranges, printed (or saved as CSV) in long format.
+//!
+//! ```bash
+//! fair-sim sweep --param liquidation_penalty=0.05:0.20:0.05 \
+//!     --param min_collateral_ratio=1.1:2.0:0.1 --param keepers.count=10,50,100,200 \
+//!     --scenario flash-crash --runs 100 --seed 3 --output sweep.csv
+//! ```
+//!
+//! With `--output` the run manifest is written alongside (`sweep.manifest.json`).
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::PathBuf;
+
+use clap::Args;
+
+use fair_simulation::alerts::EXIT_OK;
+use fair_simulation::manifest::sidecar_path;
+use fair_simulation::sweep::{grid, run_sweep, write_csv, SweepAxis, SweepRow};
+
+use crate::{usage_error, MechanismArgs, RunArgs, ScenarioArgs};
+
+const DEFAULT_RUNS: usize = 100;
+
+#[derive(Args, Debug)]
+pub struct SweepArgs {
+    #[command(flatten)]
+    run: RunArgs,
+    #[command(flatten)]
+    mechanisms: MechanismArgs,
+    #[command(flatten)]
+    scenarios: ScenarioArgs,
+    /// Swept field, e.g. "liquidation_penalty=0.05:0.20:0.05" or "keepers.count=10,50" (repeatable)
+    #[arg(long = "param", required = true, value_parser = parse_axis)]
+    axes: Vec<SweepAxis>,
+    /// Save the long-format table as CSV instead of printing it
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+fn parse_axis(spec: &str) -> Result<SweepAxis, String> {
+    SweepAxis::parse(spec).map_err(|e| e.to_string())
+}
+
+pub fn run(args: SweepArgs) -> i32 {
+    let mut setup = match args.run.setup() {
+        Ok(setup) => setup,
+        Err(e) => return usage_error(e),
+    };
+    args.mechanisms.apply(&mut setup);
+    args.scenarios.apply(&mut setup);
+    let runs = setup.runs_or(DEFAULT_RUNS);
+    let manifest = match args.run.manifest(&mut setup, runs, args.output.is_some()) {
+        Ok(manifest) => manifest,
+        Err(e) => return usage_error(e),
+    };
+    let (mechanisms, scenarios) = (setup.mechanisms(), setup.scenarios());
+
+    println!("=======================================================");
+    println!("  Parameter Sweep");
+    println!("=======================================================");
+    println!();
+    for axis in &args.axes {
+        println!("  {:<24} {} values", axis.field, axis.values.len());
+    }
+    println!(
+        "  Grid points:             {} x {} mechanisms x {} scenarios, {} runs each",
+        grid(&args.axes).len(),
+        mechanisms.len(),
+        scenarios.len(),
+        runs
+    );
+    println!();
+
+    let rows = match run_sweep(&args.axes, &mechanisms, &scenarios, runs, &setup.simulation) {
+        Ok(rows) => rows,
+        Err(e) => return usage_error(e),
+    };
+
+    match &args.output {
+        Some(path) => {
+            let written = File::create(path)
+                .map_err(Into::into)
+                .and_then(|f| write_csv(&rows, BufWriter::new(f)))
+                .and_then(|()| manifest.as_ref().map_or(Ok(()), |m| m.save(sidecar_path(path))));
+            match written {
+                Ok(()) => println!(
+                    "Saved {} rows to {} (manifest: {})",
+                    rows.len(),
+                    path.display(),
+                    sidecar_path(path).display()
+                ),
+                Err(e) => return usage_error(format!("failed to save sweep to {}: {}", path.display(), e)),
+            }
+        }
+        None => print_table(&args.axes, &rows),
+    }
+
+    EXIT_OK
+}
+
+fn print_table(axes: &[SweepAxis], rows: &[SweepRow]) {
+    let header: String = axes.iter().map(|a| format!(" {} |", a.field)).collect();
+    let rule: String = axes.iter().map(|a| format!("{}|", "-".repeat(a.field.len() + 2))).collect();
+    println!(
+        "|{} Mechanism                      | Scenario                              | Metric                 | Value        |",
+        header
+    );
+    println!(
+        "|{}--------------------------------|---------------------------------------|------------------------|--------------|",
+        rule
+    );
+    for row in rows {
+        let point: String = row
+            .point
+            .iter()
+            .zip(axes)
+            .map(|((_, value), axis)| format!(" {:>width$} |", value, width = axis.field.len()))
+            .collect();
+        println!(
+            "|{} {:30} | {:37} | {:22} | {:12.4} |",
+            point,
+            row.mechanism.name(),
+            row.scenario.name(),
+            row.metric,
+            row.value
+        );
+    }
+}
@@ -0,0 +1,79 @@
+//! `fair-sim switching`: hybrid policies that hand the keeper pool's queue
+//! to a backstop under stress, against pure Traditional and the pure pool.
+//!
+//! ```bash
+//! fair-sim switching --scenario black-swan --runs 200 --seed 5
+//!
+//! # Both backstops engaging at a single queue depth
+//! fair-sim switching --queue-threshold 30
+//! ```
+//!
+//! A `mechanism_switch` table in the config file is evaluated alongside
+//! the default policies.
+
+use clap::Args;
+
+use fair_simulation::alerts::EXIT_OK;
+use fair_simulation::cascade::{Backstop, MechanismSwitch, DEFAULT_POOL_SPLIT};
+use fair_simulation::switching::{compare_switching, SWITCH_POLICIES};
+
+use crate::{parse_split, usage_error, RunArgs, ScenarioArgs};
+
+const DEFAULT_RUNS: usize = 100;
+
+#[derive(Args, Debug)]
+pub struct SwitchingArgs {
+    #[command(flatten)]
+    run: RunArgs,
+    #[command(flatten)]
+    scenarios: ScenarioArgs,
+    /// Keeper share of each penalty under the keeper pool [default: 0.7]
+    #[arg(long, value_parser = parse_split)]
+    split: Option<f64>,
+    /// Liquidatable CDPs above which each backstop engages [default: 20 and 50]
+    #[arg(long)]
+    queue_threshold: Option<usize>,
+}
+
+pub fn run(args: SwitchingArgs) -> i32 {
+    let mut setup = match args.run.setup() {
+        Ok(setup) => setup,
+        Err(e) => return usage_error(e),
+    };
+    args.scenarios.apply(&mut setup);
+    let runs = setup.runs_or(DEFAULT_RUNS);
+    if let Err(e) = args.run.manifest(&mut setup, runs, false) {
+        return usage_error(e);
+    }
+    let split = args.split.unwrap_or(DEFAULT_POOL_SPLIT);
+    let mut policies = match args.queue_threshold {
+        Some(queue_threshold) => vec![
+            MechanismSwitch { queue_threshold, backstop: Backstop::Traditional },
+            MechanismSwitch { queue_threshold, ..MechanismSwitch::default() },
+        ],
+        None => SWITCH_POLICIES.to_vec(),
+    };
+    if let Some(custom) = setup.simulation.mechanism_switch {
+        if !policies.contains(&custom) {
+            policies.push(custom);
+        }
+    }
+
+    println!("=======================================================");
+    println!("  Mechanism Switching");
+    println!("  Keeper pool with a backstop under stress vs pure mechanisms");
+    println!("=======================================================");
+
+    for scenario in setup.scenarios() {
+        let report = match compare_switching(split, scenario, &policies, runs, &setup.simulation) {
+            Ok(report) => report,
+            Err(e) => return usage_error(e),
+        };
+        println!();
+        println!("Keeper pool {:.0}/{:.0} / {} ({} runs)", split * 100.0, (1.0 - split) * 100.0, scenario.name(), runs);
+        println!("{}", "-".repeat(50));
+        report.print();
+    }
+
+    EXIT_OK
+}
@@ -0,0 +1,112 @@
+//! `fair-sim timelock`: bad debt accruing while a governance fix for a
+//! flagged configuration waits out its timelock, per timelock length.
+//!
+//! ```bash
+//! fair-sim timelock --scenario black-swan --runs 200 --seed 5
+//!
+//! # Flag at 30 queued CDPs and move the pool to a 90/10 split
+//! fair-sim timelock --mechanism keeper-pool --queue-threshold 30 --split 0.9 --delay 10 --delay 50
+//! ```
+//!
+//! A `governance_timelock` table in the config file sets the detection
+//! threshold and the change; its delay joins the compared lengths.
+
+use clap::Args;
+
+use fair_simulation::alerts::EXIT_OK;
+use fair_simulation::cascade::GovernanceTimelock;
+use fair_simulation::timelock::{compare_timelocks, TIMELOCK_DELAYS};
+
+use crate::{parse_split, usage_error, MechanismArgs, RunArgs, ScenarioArgs};
+
+const DEFAULT_RUNS: usize = 100;
+
+#[derive(Args, Debug)]
+pub struct TimelockArgs {
+    #[command(flatten)]
+    run: RunArgs,
+    #[command(flatten)]
+    mechanisms: MechanismArgs,
+    #[command(flatten)]
+    scenarios: ScenarioArgs,
+    /// Timelock lengths to compare, in blocks (repeatable) [default: 5, 10, 25, 50 and 100]
+    #[arg(long = "delay")]
+    delays: Vec<usize>,
+    /// Liquidatable CDPs at the start of a block above which the configuration is flagged [default: 20]
+    #[arg(long)]
+    queue_threshold: Option<usize>,
+    /// Liquidation penalty the change sets [default: 0.20]
+    #[arg(long)]
+    penalty: Option<f64>,
+    /// Keeper pool share the change sets [default: unchanged]
+    #[arg(long, value_parser = parse_split)]
+    split: Option<f64>,
+}
+
+pub fn run(args: TimelockArgs) -> i32 {
+    let mut setup = match args.run.setup() {
+        Ok(setup) => setup,
+        Err(e) => return usage_error(e),
+    };
+    args.mechanisms.apply(&mut setup);
+    args.scenarios.apply(&mut setup);
+    let runs = setup.runs_or(DEFAULT_RUNS);
+    if let Err(e) = args.run.manifest(&mut setup, runs, false) {
+        return usage_error(e);
+    }
+    let configured = setup.simulation.governance_timelock.take();
+    let mut change = configured.unwrap_or_default();
+    if let Some(queue_threshold) = args.queue_threshold {
+        change.queue_threshold = queue_threshold;
+    }
+    if args.penalty.is_some() {
+        change.liquidation_penalty = args.penalty;
+    }
+    if args.split.is_some() {
+        change.split = args.split;
+    }
+    let mut delays = if args.delays.is_empty() { TIMELOCK_DELAYS.to_vec() } else { args.delays };
+    if let Some(GovernanceTimelock { delay_blocks, .. }) = configured {
+        if !delays.contains(&delay_blocks) {
+            delays.push(delay_blocks);
+        }
+    }
+    delays.sort_unstable();
+
+    println!("=======================================================");
+    println!("  Governance Timelocks");
+    println!("  Bad debt accruing between detection and the change executing");
+    println!("=======================================================");
+    println!();
+    println!("Flagged above {} queued CDPs; the change sets {}", change.queue_threshold, describe(&change));
+
+    for scenario in setup.scenarios() {
+        for mechanism in setup.mechanisms() {
+            let report = match compare_timelocks(mechanism, scenario, change, &delays, runs, &setup.simulation) {
+                Ok(report) => report,
+                Err(e) => return usage_error(e),
+            };
+            println!();
+            println!("{} / {} ({} runs)", mechanism.name(), scenario.name(), runs);
+            println!("{}", "-".repeat(50));
+            report.print();
+        }
+    }
+
+    EXIT_OK
+}
+
+fn describe(change: &GovernanceTimelock) -> String {
+    let mut parts = Vec::new();
+    if let Some(penalty) = change.liquidation_penalty {
+        parts.push(format!("a {:.0}% penalty", penalty * 100.0));
+    }
+    if let Some(split) = change.split {
+        parts.push(format!("a {:.0}/{:.0} pool split", split * 100.0, (1.0 - split) * 100.0));
+    }
+    if parts.is_empty() {
+        "nothing".to_string()
+    } else {
+        parts.join(" and ")
+    }
+}
@@ -0,0 +1,93 @@
+//! `fair-sim treasury`: the keeper pool's keeper share paid per liquidation
+//! versus dripped from a treasury over epochs, per smoothing weight.
+//!
+//! ```bash
+//! fair-sim treasury --scenario volatile --runs 200 --seed 5
+//!
+//! # Hourly epochs, heavy smoothing, and a $100k starting balance
+//! fair-sim treasury --epoch-blocks 300 --smoothing 0.1 --smoothing 0.05 --endowment 100000
+//! ```
+//!
+//! A `treasury_drip` table in the config file sets the epoch length and
+//! endowment; its smoothing joins the compared weights. Mechanisms other
+//! than the keeper pool are skipped.
+
+use clap::Args;
+
+use fair_simulation::alerts::EXIT_OK;
+use fair_simulation::cascade::TreasuryDrip;
+use fair_simulation::treasury::{compare_treasury_drips, TREASURY_SMOOTHING};
+
+use crate::{usage_error, MechanismArgs, RunArgs, ScenarioArgs};
+
+const DEFAULT_RUNS: usize = 100;
+
+#[derive(Args, Debug)]
+pub struct TreasuryArgs {
+    #[command(flatten)]
+    run: RunArgs,
+    #[command(flatten)]
+    mechanisms: MechanismArgs,
+    #[command(flatten)]
+    scenarios: ScenarioArgs,
+    /// Drip smoothing weights to compare, in (0, 1] (repeatable) [default: 1.0, 0.5 and 0.2]
+    #[arg(long = "smoothing")]
+    smoothings: Vec<f64>,
+    /// Blocks per payout epoch [default: 10]
+    #[arg(long)]
+    epoch_blocks: Option<usize>,
+    /// USD the treasury holds at the start [default: 0]
+    #[arg(long)]
+    endowment: Option<f64>,
+}
+
+pub fn run(args: TreasuryArgs) -> i32 {
+    let mut setup = match args.run.setup() {
+        Ok(setup) => setup,
+        Err(e) => return usage_error(e),
+    };
+    args.mechanisms.apply(&mut setup);
+    args.scenarios.apply(&mut setup);
+    let runs = setup.runs_or(DEFAULT_RUNS);
+    if let Err(e) = args.run.manifest(&mut setup, runs, false) {
+        return usage_error(e);
+    }
+    let configured = setup.simulation.treasury_drip.take();
+    let mut base = configured.unwrap_or_default();
+    if let Some(epoch_blocks) = args.epoch_blocks {
+        base.epoch_blocks = epoch_blocks;
+    }
+    if let Some(endowment) = args.endowment {
+        base.endowment = endowment;
+    }
+    let mut smoothings = if args.smoothings.is_empty() { TREASURY_SMOOTHING.to_vec() } else { args.smoothings };
+    if let Some(TreasuryDrip { smoothing, .. }) = configured {
+        if !smoothings.contains(&smoothing) {
+            smoothings.push(smoothing);
+        }
+    }
+    smoothings.sort_unstable_by(|a, b| b.total_cmp(a));
+    let drips: Vec<TreasuryDrip> = smoothings.into_iter().map(|smoothing| TreasuryDrip { smoothing, ..base }).collect();
+
+    println!("=======================================================");
+    println!("  Keeper Treasury");
+    println!("  Per-event keeper payouts versus a treasury drip");
+    println!("=======================================================");
+    println!();
+    println!("Epochs of {} blocks; the treasury starts with ${:.0}", base.epoch_blocks, base.endowment);
+
+    for scenario in setup.scenarios() {
+        for mechanism in setup.mechanisms().into_iter().filter(|m| m.is_keeper_pool()) {
+            let report = match compare_treasury_drips(mechanism, scenario, &drips, runs, &setup.simulation) {
+                Ok(report) => report,
+                Err(e) => return usage_error(e),
+            };
+            println!();
+            println!("{} / {} ({} runs)", mechanism.name(), scenario.name(), runs);
+            println!("{}", "-".repeat(50));
+            report.print();
+        }
+    }
+
+    EXIT_OK
+}
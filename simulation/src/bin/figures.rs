@@ -0,0 +1,167 @@
+//! `figures`: regenerates every whitepaper chart and table from the
+//! checked-in configs in `simulation/figures/`, each on its fixed seed, into
+//! a directory versioned by crate version and commit.
+//!
+//! ```bash
+//! cargo run --release --bin figures                       # figures-out/v0.1.0-<commit>/
+//! cargo run --release --bin figures -- --only split-sweep --out-dir paper/figures
+//! cargo run --release --bin figures -- --list
+//!
+//! # Iterate on one cell of the pinned campaigns
+//! cargo run --release --bin figures -- --scenario flash-crash --mechanism keeper-pool --model garch
+//! ```
+//!
+//! Each figure is written as `<name>.md` and as a LaTeX table `<name>.tex`,
+//! with its run manifest alongside (`<name>.manifest.json`); `figures.md`
+//! collects the Markdown in order. `--scenario`, `--mechanism` and `--model`
+//! narrow each figure to the configured cells they name (skipping figures
+//! with none), so the output is a subset of the published figures.
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::builder::PossibleValuesParser;
+use clap::{Parser, ValueEnum};
+
+use fair_simulation::cascade::{LiquidationMechanism, PriceScenario};
+use fair_simulation::figures::{markdown, render_table, versioned_dir, Figure, CONFIG_DIR, FIGURES};
+use fair_simulation::manifest::{sidecar_path, RunManifest};
+use fair_simulation::monte_carlo::PriceModel;
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Scenario {
+    Gradual,
+    FlashCrash,
+    Volatile,
+    BlackSwan,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Model {
+    Gbm,
+    JumpDiffusion,
+    Garch,
+    Mar2020,
+    May2021,
+    Nov2022,
+}
+
+#[derive(Parser)]
+#[command(name = "figures", version, about = "Regenerate the whitepaper figures from fixed seeds")]
+struct Cli {
+    /// Directory holding the figure configs
+    #[arg(long, default_value = CONFIG_DIR)]
+    config_dir: PathBuf,
+    /// Root of the versioned output directories
+    #[arg(long, default_value = "figures-out")]
+    out_dir: PathBuf,
+    /// Only regenerate this figure (repeatable)
+    #[arg(long = "only")]
+    only: Vec<String>,
+    /// Only run this scenario (repeatable)
+    #[arg(long = "scenario", value_enum)]
+    scenarios: Vec<Scenario>,
+    /// Only run this mechanism, at the figure's configured parameters (repeatable)
+    #[arg(long = "mechanism", value_parser = mechanism_keys())]
+    mechanisms: Vec<String>,
+    /// Only run this price model (repeatable)
+    #[arg(long = "model", value_enum)]
+    models: Vec<Model>,
+    /// List the figures and exit
+    #[arg(long)]
+    list: bool,
+}
+
+fn mechanism_keys() -> PossibleValuesParser {
+    PossibleValuesParser::new(LiquidationMechanism::all().into_iter().map(|m| m.info().key))
+}
+
+impl Cli {
+    /// The cells named by `--scenario`, `--mechanism` and `--model`.
+    fn filters(&self) -> (Vec<PriceScenario>, Vec<LiquidationMechanism>, Vec<PriceModel>) {
+        let scenarios = self.scenarios.iter().map(|scenario| match scenario {
+            Scenario::Gradual => PriceScenario::GradualDecline,
+            Scenario::FlashCrash => PriceScenario::FlashCrash,
+            Scenario::Volatile => PriceScenario::VolatileCrash,
+            Scenario::BlackSwan => PriceScenario::BlackSwan,
+        });
+        let mechanisms =
+            self.mechanisms.iter().map(|key| LiquidationMechanism::from_key(key).expect("validated by clap"));
+        let models = self.models.iter().map(|model| match model {
+            Model::Gbm => PriceModel::GBM,
+            Model::JumpDiffusion => PriceModel::JumpDiffusion,
+            Model::Garch => PriceModel::GARCH,
+            Model::Mar2020 => PriceModel::HistoricalMar2020,
+            Model::May2021 => PriceModel::HistoricalMay2021,
+            Model::Nov2022 => PriceModel::HistoricalNov2022,
+        });
+        (scenarios.collect(), mechanisms.collect(), models.collect())
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    if cli.list {
+        for figure in FIGURES {
+            println!("{:20} {}", figure.name, figure.caption);
+        }
+        return ExitCode::SUCCESS;
+    }
+    let figures = match cli.only.iter().map(|name| Figure::find(name).ok_or(name)).collect::<Result<Vec<_>, _>>() {
+        Ok(only) if !only.is_empty() => only,
+        Ok(_) => FIGURES.to_vec(),
+        Err(name) => {
+            eprintln!("error: unknown figure '{}' (see --list)", name);
+            return ExitCode::from(2);
+        }
+    };
+
+    let dir = versioned_dir(&cli.out_dir);
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        eprintln!("error: failed to create {}: {}", dir.display(), e);
+        return ExitCode::from(2);
+    }
+    let (scenarios, mechanisms, models) = cli.filters();
+    let mut collected = String::new();
+    let mut rendered = 0;
+    for figure in &figures {
+        let written = figure.setup(&cli.config_dir).and_then(|mut setup| {
+            if !setup.narrow(&scenarios, &mechanisms, &models) {
+                return Ok(None);
+            }
+            println!("Rendering {} ...", figure.name);
+            let (table, charts) = render_table(figure, &setup, &dir)?;
+            let markdown = markdown(figure, &table);
+            let path = dir.join(format!("{}.md", figure.name));
+            std::fs::write(&path, &markdown)?;
+            table.save(path.with_extension("tex"))?;
+            RunManifest::capture(&setup, std::env::args().collect()).save(sidecar_path(&path))?;
+            Ok(Some((path, markdown, charts)))
+        });
+        match written {
+            Ok(None) => println!("Skipping {}: runs none of the selected cells", figure.name),
+            Ok(Some((path, markdown, charts))) => {
+                rendered += 1;
+                println!("  {}", path.display());
+                println!("  {}", path.with_extension("tex").display());
+                for chart in charts {
+                    println!("  {}", chart.display());
+                }
+                collected.push_str(&markdown);
+                collected.push('\n');
+            }
+            Err(e) => {
+                eprintln!("error: figure '{}': {}", figure.name, e);
+                return ExitCode::from(2);
+            }
+        }
+    }
+
+    let index = dir.join("figures.md");
+    if let Err(e) = std::fs::write(&index, collected) {
+        eprintln!("error: failed to write {}: {}", index.display(), e);
+        return ExitCode::from(2);
+    }
+    println!("Wrote {} figures to {}", rendered, dir.display());
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,116 @@
+//! `keeper-sim-client`: the reference off-chain keeper. It consumes a feed of
+//! simulated blocks, runs the threshold strategy on every opportunity
+//! through the public `KeeperStrategy` trait, and writes the liquidations it
+//! would submit to stdout as JSON Lines. An integration swaps
+//! `ThresholdStrategy` for its own strategy and keeps the rest of `main`.
+//!
+//! ```bash
+//! keeper-sim-client --scenario flash-crash --seed 7                  # feed recorded live from the engine
+//! keeper-sim-client --mechanism traditional --record feed.jsonl      # ... and saved for replay
+//! keeper-sim-client --feed feed.jsonl --min-profit 50 --gas-priority 0.8
+//! cat feed.jsonl | keeper-sim-client --feed -
+//! ```
+//!
+//! A run summary goes to stderr, so stdout stays a clean intent stream.
+
+use std::io::BufReader;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::builder::PossibleValuesParser;
+use clap::{Parser, ValueEnum};
+
+use fair_simulation::cascade::{LiquidationMechanism, PriceScenario};
+use fair_simulation::config::SimulationSetup;
+use fair_simulation::error::Result;
+use fair_simulation::keeper_client::{read_feed, record_feed, save_feed, ClientSummary, KeeperClient};
+use fair_simulation::strategy::{KeeperStrategy, ThresholdStrategy};
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Scenario {
+    Gradual,
+    FlashCrash,
+    Volatile,
+    BlackSwan,
+}
+
+#[derive(Parser)]
+#[command(name = "keeper-sim-client", version, about = "Reference keeper consuming a simulated block feed")]
+struct Cli {
+    /// JSON Lines block feed to consume ('-' for stdin) instead of running the engine
+    #[arg(long, conflicts_with_all = ["mechanism", "scenario", "seed", "config", "record"])]
+    feed: Option<PathBuf>,
+    /// Mechanism of the engine run
+    #[arg(long, value_parser = mechanism_keys(), default_value = "keeper-pool")]
+    mechanism: String,
+    /// Price scenario of the engine run
+    #[arg(long, value_enum, default_value = "flash-crash")]
+    scenario: Scenario,
+    /// Seed of the engine run
+    #[arg(long, default_value_t = 1)]
+    seed: u64,
+    /// TOML/YAML setup supplying the engine's simulation config
+    #[arg(long)]
+    config: Option<PathBuf>,
+    /// Also save the engine's feed as JSON Lines
+    #[arg(long)]
+    record: Option<PathBuf>,
+    /// Minimum expected profit after gas before the strategy attempts (USD)
+    #[arg(long, default_value_t = 25.0)]
+    min_profit: f64,
+    /// Gas priority the strategy bids, 0-1
+    #[arg(long, default_value_t = 0.9)]
+    gas_priority: f64,
+}
+
+fn mechanism_keys() -> PossibleValuesParser {
+    PossibleValuesParser::new(LiquidationMechanism::all().into_iter().map(|m| m.info().key))
+}
+
+impl Cli {
+    fn run(&self, strategy: impl KeeperStrategy) -> Result<ClientSummary> {
+        let mut client = KeeperClient::new(strategy);
+        let stdout = std::io::stdout().lock();
+        match self.feed.as_deref() {
+            Some(path) if path.as_os_str() == "-" => client.run(read_feed(std::io::stdin().lock()), stdout),
+            Some(path) => client.run(read_feed(BufReader::new(std::fs::File::open(path)?)), stdout),
+            None => {
+                let setup = self.config.as_ref().map(SimulationSetup::load).transpose()?.unwrap_or_default();
+                let mechanism = LiquidationMechanism::from_key(&self.mechanism).expect("validated by clap");
+                let scenario = match self.scenario {
+                    Scenario::Gradual => PriceScenario::GradualDecline,
+                    Scenario::FlashCrash => PriceScenario::FlashCrash,
+                    Scenario::Volatile => PriceScenario::VolatileCrash,
+                    Scenario::BlackSwan => PriceScenario::BlackSwan,
+                };
+                let blocks = record_feed(mechanism, scenario, &setup.simulation, self.seed)?;
+                if let Some(path) = &self.record {
+                    save_feed(path, &blocks)?;
+                }
+                client.run(blocks.into_iter().map(Ok), stdout)
+            }
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    if !(0.0..=1.0).contains(&cli.gas_priority) {
+        eprintln!("error: --gas-priority must be within [0, 1]");
+        return ExitCode::from(2);
+    }
+    let strategy = ThresholdStrategy { min_profit: cli.min_profit, gas_priority: cli.gas_priority };
+    match cli.run(strategy) {
+        Ok(summary) => {
+            eprintln!(
+                "{} blocks, {} opportunities, {} intents: ${:.0} expected penalties against ${:.0} max gas",
+                summary.blocks, summary.opportunities, summary.intents, summary.expected_profit, summary.max_gas_cost,
+            );
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::from(2)
+        }
+    }
+}
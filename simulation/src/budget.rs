@@ -0,0 +1,308 @@
+//! Budgeted Campaigns
+//!
+//! When the full scenario × mechanism matrix is too expensive at a fixed
+//! run count, spend a wall-clock budget where it buys the most precision
+//! instead, and report how precise each cell ended up.
+//!
+//! ## Method
+//! - Every cell gets `PILOT_RUNS` runs first; the pilot always completes,
+//!   even past the budget, so every cell has a variance estimate
+//! - Then batches of `BATCH_RUNS` go to the cell whose mean bad debt has
+//!   the largest standard error, until the budget is spent
+//! - Each cell draws per-run seeds from `config.seed` the way
+//!   `run_cascade_simulation` does, so a cell's `n` runs are the first `n`
+//!   runs of `fair-sim cascade` with the same seed and config
+//! - Precision is the 95% confidence half-width of the mean bad debt
+//!   (normal approximation). `Budget::Runs` caps the total run count
+//!   instead of the time, for an allocation that reproduces exactly
+//! - `Budget::Precision` stops on precision instead: batches go to the
+//!   least precise cell (relative to its mean) until every cell's interval
+//!   is within the target or has hit the per-cell run cap. Easy scenarios
+//!   stop after the pilot; heavy-tailed ones run until they are powered
+
+use std::time::{Duration, Instant};
+
+use rand::rngs::StdRng;
+use rand::Rng;
+use tracing::{debug, info, info_span};
+
+use crate::cascade::{campaign_span, replay, CascadeResult, LiquidationMechanism, PriceScenario, SimulationConfig};
+use crate::error::{Error, Result};
+use crate::stats;
+
+/// Runs every cell gets before any adaptive allocation.
+pub const PILOT_RUNS: usize = 20;
+
+/// Runs handed to the least precise cell per allocation step.
+pub const BATCH_RUNS: usize = 10;
+
+const Z_95: f64 = 1.96;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Budget {
+    Time(Duration),
+    Runs(usize), // Total across cells
+    /// Until every cell's 95% half-width is within `relative` of its mean,
+    /// at most `max_runs` runs per cell
+    Precision { relative: f64, max_runs: usize },
+}
+
+#[derive(Debug, Clone)]
+pub struct BudgetCell {
+    pub scenario: PriceScenario,
+    pub mechanism: LiquidationMechanism,
+    pub results: Vec<CascadeResult>,
+}
+
+impl BudgetCell {
+    fn bad_debts(&self) -> Vec<f64> {
+        self.results.iter().map(|r| r.bad_debt).collect()
+    }
+
+    pub fn runs(&self) -> usize {
+        self.results.len()
+    }
+
+    pub fn mean_bad_debt(&self) -> f64 {
+        stats::mean(&self.bad_debts())
+    }
+
+    pub fn std_error(&self) -> f64 {
+        stats::std_error(&self.bad_debts())
+    }
+
+    /// 95% confidence half-width of the mean bad debt.
+    pub fn half_width(&self) -> f64 {
+        Z_95 * self.std_error()
+    }
+
+    /// Half-width relative to the mean; None when the mean is zero.
+    pub fn relative_precision(&self) -> Option<f64> {
+        let mean = self.mean_bad_debt();
+        (mean > 0.0).then(|| self.half_width() / mean)
+    }
+
+    /// Whether the interval is within `relative` of the mean. A cell without
+    /// bad debt in any run is exact.
+    pub fn is_precise(&self, relative: f64) -> bool {
+        self.relative_precision().is_none_or(|p| p <= relative)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BudgetReport {
+    pub budget: Budget,
+    pub elapsed: Duration,
+    pub cells: Vec<BudgetCell>,
+}
+
+impl BudgetReport {
+    pub fn total_runs(&self) -> usize {
+        self.cells.iter().map(|c| c.runs()).sum()
+    }
+
+    pub fn print(&self) {
+        println!("| {:37} | {:30} |  Runs | Mean Bad Debt |   ± 95% CI | Relative |", "Scenario", "Mechanism");
+        println!("|{}|{}|-------|---------------|------------|----------|", "-".repeat(39), "-".repeat(32));
+        for cell in &self.cells {
+            println!(
+                "| {:37} | {:30} | {:5} | ${:12.0} | ${:9.0} | {:>8} |",
+                cell.scenario.name(),
+                cell.mechanism.name(),
+                cell.runs(),
+                cell.mean_bad_debt(),
+                cell.half_width(),
+                cell.relative_precision().map_or("-".to_string(), |p| format!("{:.1}%", p * 100.0)),
+            );
+        }
+        println!();
+        println!("{} runs in {:.1}s", self.total_runs(), self.elapsed.as_secs_f64());
+        if let Budget::Precision { relative, max_runs } = self.budget {
+            let precise = self.cells.iter().filter(|c| c.is_precise(relative)).count();
+            print!("{} of {} cells within ±{:.1}%", precise, self.cells.len(), relative * 100.0);
+            if precise < self.cells.len() {
+                print!("; the rest stopped at {} runs", max_runs);
+            }
+            println!();
+        }
+    }
+}
+
+struct CellRunner {
+    cell: BudgetCell,
+    seeds: StdRng,
+}
+
+impl CellRunner {
+    fn run(&mut self, runs: usize, config: &SimulationConfig) {
+        let _campaign = campaign_span(self.cell.mechanism, self.cell.scenario, runs).entered();
+        for _ in 0..runs {
+            let seed = self.seeds.gen();
+            self.cell.results.push(replay(self.cell.mechanism, self.cell.scenario, config, seed));
+        }
+    }
+}
+
+/// Runs every scenario × mechanism cell within `budget`, allocating runs
+/// beyond the pilot to the least precise cell.
+pub fn run_budgeted(
+    scenarios: &[PriceScenario],
+    mechanisms: &[LiquidationMechanism],
+    budget: Budget,
+    config: &SimulationConfig,
+) -> Result<BudgetReport> {
+    config.validate()?;
+    for mechanism in mechanisms {
+        mechanism.validate()?;
+    }
+    if scenarios.is_empty() || mechanisms.is_empty() {
+        return Err(Error::Invalid("a budgeted campaign needs at least one scenario and mechanism".to_string()));
+    }
+    if let Budget::Precision { relative, max_runs } = budget {
+        if !(relative.is_finite() && relative > 0.0) {
+            return Err(Error::Invalid(format!("target precision must be positive, got {}", relative)));
+        }
+        if max_runs < PILOT_RUNS {
+            return Err(Error::Invalid(format!("max runs per cell must cover the {}-run pilot", PILOT_RUNS)));
+        }
+    }
+
+    let _budget = info_span!("budget", budget = ?budget).entered();
+    let start = Instant::now();
+    let mut runners: Vec<CellRunner> = scenarios
+        .iter()
+        .flat_map(|&scenario| {
+            mechanisms.iter().map(move |&mechanism| CellRunner {
+                cell: BudgetCell { scenario, mechanism, results: Vec::new() },
+                seeds: config.rng(),
+            })
+        })
+        .collect();
+    for runner in &mut runners {
+        runner.run(PILOT_RUNS, config);
+    }
+
+    let spent = |runners: &[CellRunner]| match budget {
+        Budget::Time(limit) => start.elapsed() >= limit,
+        Budget::Runs(limit) => runners.iter().map(|r| r.cell.runs()).sum::<usize>() >= limit,
+        Budget::Precision { .. } => runners.iter().all(|r| !open(budget, &r.cell)),
+    };
+    while !spent(&runners) {
+        // Least precise open cell first; among equals, the cell with fewest runs
+        let mut next = runners.iter().position(|r| open(budget, &r.cell)).expect("a budget is spent without open cells");
+        for (i, runner) in runners.iter().enumerate().skip(next + 1) {
+            let (best, this) = (&runners[next].cell, &runner.cell);
+            if open(budget, this)
+                && (imprecision(budget, this) > imprecision(budget, best)
+                    || (imprecision(budget, this) == imprecision(budget, best) && this.runs() < best.runs()))
+            {
+                next = i;
+            }
+        }
+        let batch = match budget {
+            Budget::Runs(limit) => BATCH_RUNS.min(limit - runners.iter().map(|r| r.cell.runs()).sum::<usize>()),
+            Budget::Time(_) => BATCH_RUNS,
+            Budget::Precision { max_runs, .. } => BATCH_RUNS.min(max_runs - runners[next].cell.runs()),
+        };
+        debug!(
+            scenario = runners[next].cell.scenario.name(),
+            mechanism = %runners[next].cell.mechanism.name(),
+            std_error = runners[next].cell.std_error(),
+            batch,
+            "allocating batch"
+        );
+        runners[next].run(batch, config);
+    }
+
+    let report = BudgetReport { budget, elapsed: start.elapsed(), cells: runners.into_iter().map(|r| r.cell).collect() };
+    info!(runs = report.total_runs(), elapsed_secs = report.elapsed.as_secs_f64(), "budget spent");
+    Ok(report)
+}
+
+/// Whether `cell` can still take runs under `budget`.
+fn open(budget: Budget, cell: &BudgetCell) -> bool {
+    match budget {
+        Budget::Precision { relative, max_runs } => cell.runs() < max_runs && !cell.is_precise(relative),
+        Budget::Time(_) | Budget::Runs(_) => true,
+    }
+}
+
+/// What allocation minimizes: the absolute standard error under a time or
+/// run budget, the error relative to the mean under a precision target.
+fn imprecision(budget: Budget, cell: &BudgetCell) -> f64 {
+    match budget {
+        Budget::Precision { .. } => cell.relative_precision().unwrap_or(0.0),
+        Budget::Time(_) | Budget::Runs(_) => cell.std_error(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cascade::run_cascade_simulation;
+
+    #[test]
+    fn test_runs_go_to_the_noisiest_cell() {
+        let config = SimulationConfig { seed: Some(3), ..Default::default() };
+        let scenarios = [PriceScenario::GradualDecline, PriceScenario::VolatileCrash];
+        let report = run_budgeted(&scenarios, &[LiquidationMechanism::Traditional], Budget::Runs(100), &config).unwrap();
+
+        assert_eq!(report.total_runs(), 100);
+        let (calm, volatile) = (&report.cells[0], &report.cells[1]);
+        assert!(volatile.std_error() > 0.0);
+        assert!(volatile.runs() > calm.runs(), "{} vs {}", volatile.runs(), calm.runs());
+    }
+
+    #[test]
+    fn test_cells_are_campaign_prefixes() {
+        let config = SimulationConfig { seed: Some(11), ..Default::default() };
+        let mechanism = LiquidationMechanism::keeper_pool();
+        let report = run_budgeted(&[PriceScenario::FlashCrash], &[mechanism], Budget::Runs(35), &config).unwrap();
+        let cell = &report.cells[0];
+        let campaign = run_cascade_simulation(mechanism, PriceScenario::FlashCrash, cell.runs(), &config);
+
+        assert_eq!(cell.runs(), 35);
+        let seeds: Vec<_> = cell.results.iter().map(|r| r.seed).collect();
+        assert_eq!(seeds, campaign.iter().map(|r| r.seed).collect::<Vec<_>>());
+        assert_eq!(cell.mean_bad_debt(), stats::mean(&campaign.iter().map(|r| r.bad_debt).collect::<Vec<_>>()));
+    }
+
+    #[test]
+    fn test_pilot_completes_past_the_budget() {
+        let config = SimulationConfig { seed: Some(5), ..Default::default() };
+        let report = run_budgeted(
+            &[PriceScenario::FlashCrash],
+            &LiquidationMechanism::all(),
+            Budget::Time(Duration::ZERO),
+            &config,
+        )
+        .unwrap();
+
+        assert!(report.cells.iter().all(|c| c.runs() == PILOT_RUNS));
+        assert!(run_budgeted(&[], &LiquidationMechanism::all(), Budget::Runs(10), &config).is_err());
+    }
+
+    #[test]
+    fn test_precision_target_stops_each_cell_when_powered() {
+        let config = SimulationConfig { seed: Some(3), ..Default::default() };
+        let scenarios = [PriceScenario::GradualDecline, PriceScenario::VolatileCrash];
+        let budget = Budget::Precision { relative: 0.25, max_runs: 400 };
+        let report = run_budgeted(&scenarios, &[LiquidationMechanism::Traditional], budget, &config).unwrap();
+
+        for cell in &report.cells {
+            assert!(cell.runs() >= PILOT_RUNS && cell.runs() <= 400);
+            assert!(cell.is_precise(0.25) || cell.runs() == 400, "{:?}", cell.relative_precision());
+            // Stopped at the first batch boundary that met the target
+            let before = BudgetCell { results: cell.results[..cell.runs() - BATCH_RUNS].to_vec(), ..cell.clone() };
+            assert!(cell.runs() == PILOT_RUNS || !before.is_precise(0.25));
+        }
+        let tight = Budget::Precision { relative: 0.25, max_runs: PILOT_RUNS };
+        assert!(run_budgeted(&scenarios, &[LiquidationMechanism::Traditional], tight, &config)
+            .unwrap()
+            .cells
+            .iter()
+            .all(|c| c.runs() == PILOT_RUNS));
+        let unbounded = Budget::Precision { relative: 0.0, max_runs: 100 };
+        assert!(run_budgeted(&scenarios, &[LiquidationMechanism::Traditional], unbounded, &config).is_err());
+    }
+}
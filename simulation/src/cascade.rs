@@ -15,9 +15,389 @@
 //! - Bad debt (unliquidated underwater positions)
 //! - Time to stability (blocks until no more liquidations)
 //! - Price impact (how much liquidations move the price)
+//! - Weighted coverage (the share of liquidatable exposure cleared, each
+//!   CDP weighted by its dollar shortfall below the minimum ratio for every
+//!   block it waits; see `CascadeResult::weighted_coverage`)
+//!
+//! ## Interest-Bearing Collateral
+//! `SimulationConfig::accruing_collateral` backs part of the book with a
+//! yield-bearing token (e.g. a liquid staking token) whose ETH value grows
+//! every block, lifting collateral ratios over long horizons. Rebasing
+//! tokens grow the CDP's balance; exchange-rate tokens grow the value per
+//! unit. Both lift the ETH backing identically; they differ in what the CDP
+//! records.
+//!
+//! ## Neighboring Protocols
+//! `SimulationConfig::neighbor` adds a second lending protocol holding the
+//! same collateral. Its liquidations clear at its own threshold and
+//! throughput and sell into the shared market, so they move the price Fair
+//! liquidates against. See `contagion` for attributing bad debt to them.
+//!
+//! ## Shared Keeper Liquidity
+//! Keepers are normally assumed to flash-borrow, so capital never binds.
+//! `SimulationConfig::shared_liquidity` instead has rival protocols
+//! liquidating in the same market-wide event: each block keepers commit
+//! their capital to rival liquidations whenever the rival bonus beats the
+//! best return Fair offers, and only keepers with capital left for a CDP's
+//! debt take part in Fair's round.
+//!
+//! ## Reward Token Emissions
+//! `SimulationConfig::reward_token` pays part of the keeper pool's share in
+//! a protocol token instead of stablecoin. A fixed number of tokens is
+//! emitted per USD of keeper share, valued at par when the system is
+//! healthy; the token trades at `health^health_beta`, where health is the
+//! ETH price relative to its start times the share of debt still backed.
+//! Keepers value their payout at the current token price, so token
+//! incentives weaken exactly as the system degrades. See `emissions`.
+//!
+//! ## Keeper Incentive Programs
+//! `SimulationConfig::incentive_program` pays keepers a flat bonus per
+//! liquidation on top of the penalty until a cliff block, when the program
+//! ends (or a token vesting cliff hits) and the mercenary keepers it
+//! attracted leave. See `incentive_cliff` for timing the cliff against
+//! market stress.
+//!
+//! ## Priority Fees
+//! Every liquidation attempt lands a transaction. Traditional keepers race
+//! for the whole penalty: each willing keeper bids `gas_priority` of the
+//! prize as a priority fee on top of base gas, and losers revert but still
+//! pay. The keeper pool picks its executor at random, so members pay only
+//! the commit and the executor base gas. `SimulationConfig::execution_race`
+//! pays the executor an extra `executor_share` of the keeper share, which
+//! turns the slot into a race of its own: members bid for it the same way
+//! and the highest bid executes. `CascadeResult::fee_burn` totals the gas
+//! the simulated keepers spend; see `fee_burn` for comparing it against
+//! Traditional races.
+//!
+//! ## Execution Failures
+//! `SimulationConfig::execution_failure` has the selected pool executor
+//! fail to land its transaction (out of gas, nonce clashes, stale state)
+//! at a fixed rate. Under `FallbackPolicy::Backup` another member is
+//! selected in the same block; under `Reselect` the CDP waits out a delay
+//! before a fresh round selects again. Failures draw from their own RNG
+//! stream, so policies compare on the same paths. Traditional races need
+//! no fallback, since the runner-up's transaction lands instead. See
+//! `fallback` for the coverage cost of each policy.
+//!
+//! ## Mempool Visibility
+//! `SimulationConfig::mempool` splits keepers into mempool watchers, who
+//! see pending oracle updates and liquidations and act on a CDP the block
+//! it becomes liquidatable, and the rest, who react to confirmed state
+//! `lead_blocks` later. A visible share of zero is a fully private
+//! mempool; one is fully public. `CascadeResult::avg_liquidation_delay`
+//! records how long liquidated CDPs waited. See `mempool` for sweeping the
+//! visible share.
+//!
+//! ## Mechanism Switching
+//! `SimulationConfig::mechanism_switch` models a protocol policy that
+//! hands liquidations to a backstop whenever more than `queue_threshold`
+//! CDPs are liquidatable at the start of a block, e.g. the keeper pool
+//! normally and a Dutch auction under stress. A `Backstop::DutchAuction`
+//! races like Traditional, but the penalty on each CDP starts low and
+//! rises every block the CDP has waited, so thin-equity CDPs no keeper
+//! would touch at the configured penalty clear once it is deep enough. The
+//! switch reverts as soon as the queue drains. See `switching` for
+//! evaluating hybrid policies against the pure mechanisms.
+//!
+//! ## Auction Discounts
+//! `SimulationConfig::auction_discount` makes an auction's realized
+//! discount a random variable instead of its offered penalty. Each bidder
+//! on a CDP holds a reservation discount, the offered penalty scaled by a
+//! mean-one lognormal draw of `dispersion`, and the lowest reservation
+//! clears (never above the offer), so more competition means thinner
+//! discounts. The auction sells only collateral worth the debt plus the
+//! realized discount and returns the rest to the borrower, so
+//! `CascadeResult::borrower_loss` follows the discount. The protocol keeps
+//! `protocol_share` of it as `protocol_revenue` and the winning bidder the
+//! rest. Applies to `Backstop::DutchAuction` rounds; draws come from their
+//! own stream.
+//!
+//! ## Liquidation Cooldowns
+//! `SimulationConfig::liquidation_cooldown` liquidates CDPs partially: each
+//! liquidation closes `close_factor` of the debt, seizing collateral worth
+//! the closed debt plus its penalty, and the CDP then waits out
+//! `cooldown_blocks` before it can be liquidated again. Borrowers keep the
+//! equity a whole-CDP liquidation seizes (`CascadeResult::borrower_loss`),
+//! while a CDP still underwater keeps falling through its cooldown. See
+//! `cooldown` for the borrower-protection versus bad-debt tradeoff.
+//!
+//! ## Grace Periods
+//! `SimulationConfig::grace_period` holds a CDP that falls below the
+//! minimum ratio out of the queue for `grace_blocks` blocks. Each block of
+//! grace its owner tops it back up to `top_up_ratio` with `top_up_rate`,
+//! and a CDP topped up leaves the queue without a liquidation. Owners of
+//! underwater CDPs have no equity to save and walk away; a CDP still
+//! below the minimum when grace ends is liquidated as usual, at whatever
+//! price it has fallen to. Top-ups draw from their own RNG stream and are
+//! tallied in the audit's collateral identity. Soft liquidation has no
+//! liquidation to hold back, so grace does not apply to it. See `grace`
+//! for bad debt against borrower loss by window length.
+//!
+//! ## Liquidation Queues
+//! `SimulationConfig::queue_order` sets the order liquidatable CDPs are
+//! taken in when more fall below the minimum than `liquidations_per_block`
+//! allows. The protocol publishes the queue and keepers execute it in
+//! order: `QueueOrder::Ratio` (the default) by lowest collateral ratio,
+//! `QueueOrder::RiskScore` by lowest IPFE score, the sum of the features
+//! `Fair.sol` computes (see `CDP::risk_score`), which also weighs size and
+//! so puts large CDPs behind small ones at the same ratio.
+//! `QueueOrder::Profit` leaves the order to keepers, who take the most
+//! profitable CDPs, the ones with the most equity at the penalty, first.
+//! Auctions kick and batches fill in the same order. See
+//! `liquidation_queue` for price impact under each.
+//!
+//! ## Penalty Curves
+//! `SimulationConfig::penalty_curve` replaces the flat `liquidation_penalty`
+//! with one that rises with how far below the minimum a CDP has fallen:
+//! `min_penalty` at the minimum ratio plus `slope` per unit of ratio
+//! below it, capped at `max_penalty`. A liquidation under a curve seizes
+//! only the debt plus the penalty, as a partial liquidation or an auction
+//! does, and returns the rest of the collateral to the borrower, where a
+//! whole-CDP liquidation at the flat penalty takes all of it. CDPs just
+//! past the line pay little, sparing their owners, while deep ones offer
+//! keepers more to clear them before they go underwater. The curve prices
+//! each CDP at its ratio when it is liquidated or kicked into an auction;
+//! batch auctions clear one discount across the batch and the bands
+//! charge no penalty, so it does not reach them. See `penalty_curve` for
+//! the curve minimizing bad debt under a borrower-loss cap.
+//!
+//! ## Fixed-Spread Liquidations
+//! `LiquidationMechanism::FixedSpread` is the Aave/Compound design: any
+//! keeper repays up to `close_factor` of a CDP's debt and receives
+//! collateral worth the repaid debt plus `bonus` of it, capped by the
+//! collateral there is. Nothing is auctioned or pooled; keepers race on
+//! gas priority as under Traditional, and the winner keeps the whole
+//! bonus. A CDP still below the minimum ratio after a repay can be
+//! liquidated again the next block (after `liquidation_cooldown`, when
+//! set); a repay that would leave dust closes the whole debt.
+//!
+//! ## English Auctions
+//! `LiquidationMechanism::EnglishAuction` is Maker's original flip
+//! auction. A liquidatable CDP is bitten into an auction of its whole
+//! collateral (the lot) for its debt plus `liquidation_penalty` of it (the
+//! tab). Keepers bid DAI over several blocks, each raise at least
+//! `min_increment` over the standing bid; once the tab is covered they bid
+//! instead on taking less collateral for it, and the rest goes back to
+//! the borrower. An auction settles `bid_blocks` after its last bid or
+//! `auction_blocks` after it started, and one nobody bid on restarts. The
+//! CDP stays on the book until it settles.
+//!
+//! A lone bidder opens at zero and, if nobody else turns up before the
+//! bid expires, wins the lot for nothing: Black Thursday's zero-bid
+//! auctions. Competing bidders push the bid to where the runner-up drops
+//! out, at its free capital under `shared_liquidity` or the lot's value
+//! less the keepers' threshold. `apathy` is the chance each keeper
+//! ignores an auction in a given block, drawn from its own stream. Debt a
+//! settled bid leaves uncovered is bad debt, since no keeper absorbs it.
+//! See `flip` for the apathy sweep.
+//!
+//! ## Soft Liquidations
+//! `LiquidationMechanism::SoftLiquidation` is crvUSD's LLAMMA. Each CDP's
+//! collateral sits in `bands` price bands of `band_width` each, the top
+//! one at the price where the CDP reaches the minimum ratio. As the price
+//! falls through a band, arbitrageurs buy its collateral for stablecoin
+//! at `spread` below the market, and the stablecoin pays down the debt;
+//! as it climbs back, they sell the collateral back at `spread` above. No
+//! CDP is ever liquidated outright: band trades are tallied in
+//! `CascadeResult::soft` and the ledger's converted debt and spread, not
+//! as liquidations, and a CDP counts as liquidated once, the block its
+//! bands finish converting it, whether its debt is repaid or its
+//! collateral runs out first. The spread is the borrower's loss and the
+//! arbitrageurs' profit, and a price that gaps below a CDP's bands
+//! converts it at the gapped price. Arbitrageurs race like Traditional keepers and the
+//! fastest takes the block's trades; under `shared_liquidity` each fills
+//! what its free capital allows, and trades beyond that wait a block. A CDP left below water has nobody to close it, so its
+//! deficit is bad debt. The bands and their trades live in `llamma`, with
+//! the comparison against the pool.
+//!
+//! ## Batch Auctions
+//! `LiquidationMechanism::BatchAuction` settles a block's whole queue, up
+//! to `max_batch` CDPs, in one uniform-price auction instead of a race per
+//! CDP. Each keeper bids its capital (its free capital under
+//! `shared_liquidity`) at a reservation discount, `liquidation_penalty`
+//! scaled by a mean-one lognormal draw of `dispersion` from its own stream;
+//! reservations above the penalty are not bid. The lowest reservations
+//! fill the batch's debt, the marginal one sets the discount every winner
+//! pays, and winners take the discount and the collateral pro rata to
+//! their fills. CDPs close whole, in queue order as far as the fills
+//! reach; the borrower keeps collateral beyond the debt and the clearing
+//! discount. Every bid is one transaction at base gas, filled or not, and
+//! nothing is raced. Underwater CDPs pay no discount and are not batched.
+//! See `batch_auction` for gas, price impact and fairness against the
+//! per-CDP mechanisms.
+//!
+//! ## Governance Timelocks
+//! `SimulationConfig::governance_timelock` models governance fixing a
+//! dangerous configuration mid-crisis. The configuration counts as
+//! detected the first block more than `queue_threshold` CDPs are
+//! liquidatable at its start (the queue the early warning watches), and
+//! the change (a new penalty and/or keeper pool split) executes
+//! `delay_blocks` later, holding for the rest of the run.
+//! `CascadeResult::governance_window_bad_debt` is the bad debt that accrued
+//! between detection and execution. See `timelock` for the cost per delay.
+//!
+//! ## Treasury Drip
+//! `SimulationConfig::treasury_drip` pays the keeper pool's keeper share
+//! into a treasury instead of splitting it per liquidation. Each member's
+//! share is recorded as a claim, and every `epoch_blocks` the treasury
+//! pays out its drip, an exponential average (weight `smoothing`) of the
+//! epoch inflows, pro rata to the epoch's claims. Bursts build a surplus
+//! that lulls pay out of; a drip the balance cannot cover pays what is
+//! left and counts as a short epoch. Keepers value their share at the last
+//! epoch's payout per dollar claimed, so thin payouts thin participation.
+//! The incentive bonus, executor premium and an external keeper stay paid
+//! per event. See `treasury` for the comparison with per-event splits.
+//!
+//! ## Insurance Fund
+//! `SimulationConfig::insurance_fund` sets `share` of the protocol's take
+//! of each block's penalties aside in a fund that starts at `endowment`.
+//! Mechanisms that retain nothing, Traditional among them, fund it with
+//! the endowment alone. The fund is exhausted the first block the book's
+//! bad debt exceeds its balance; at the end it absorbs what it can of the
+//! bad debt, and `CascadeResult::bad_debt` is what reaches the system.
+//! `BlockEvent::insurance_fund` tracks the balance block by block.
+//!
+//! ## Debt Auctions
+//! `SimulationConfig::debt_auction` recapitalizes what bad debt the
+//! insurance fund leaves (all of it without one) as Maker's flop auctions
+//! do: at the end of the run, governance tokens are minted and sold a
+//! `lot` at a time at a `discount` to the market, each sale pushing the
+//! token price down by `impact` times the share of supply it minted.
+//! Auctions stop once `max_dilution` of the supply has been minted, and
+//! what they could not raise is the bad debt left in
+//! `CascadeResult::bad_debt`. With `reward_token` set the governance token
+//! is the reward token, so auctions open at its crash-depressed price.
+//!
+//! ## Keeper Staking
+//! `SimulationConfig::keeper_staking` has every keeper bond `stake_share`
+//! of its capital to join keeper pool rounds, and splits the keeper share
+//! among a round's members pro rata to their stakes rather than equally.
+//! A selected executor that fails to act, whether a griefer or a failed
+//! transaction, is slashed `slash_rate` of its stake, paid to the
+//! protocol; a keeper whose stake falls below `min_stake` is ejected from
+//! the pool. Stakes are bonded afresh after a warm-up. See `staking` for
+//! the free-rider comparison with the flat split.
+//!
+//! ## Pool Splits
+//! `SimulationConfig::pool_split` sets how the keeper pool divides the
+//! members' part of the keeper share. `PoolSplit::Equal` pays every member
+//! of a round alike; `PoolSplit::Capital` pays them pro rata to capital,
+//! the stake each would bond, without bonding or slashing anything. Under
+//! `keeper_staking` members are paid pro rata to their bonded stake either
+//! way. Keepers decide whether to join on the round's whole payout, so the
+//! split moves profit between members without changing who joins. See
+//! `pool_split` for inequality and small-keeper viability.
+//!
+//! ## Keeper Reputation
+//! `SimulationConfig::keeper_reputation` draws the keeper pool's executor
+//! weighted by reputation rather than uniformly. Each keeper's score is
+//! its reliability (executions landed, against failed executions and
+//! missed reveals) times its uptime (pool rounds joined, of those it was
+//! active and willing to take), each a moving average updated with weight
+//! `memory` and starting at 1. Members are drawn with weight `floor + score^exponent`,
+//! so griefers and absent keepers are passed over while newcomers keep a
+//! chance; the backup after a failure is still drawn uniformly. Draws come
+//! from their own RNG stream. See `reputation` for latency against
+//! execution concentration.
+//!
+//! ## Keeper Rotation
+//! `SimulationConfig::keeper_rotation` restricts keeper pool rounds to an
+//! active set of `set_size` keepers, redrawn every `epoch_blocks`. Only
+//! active members commit, execute and share the keeper share. Each member
+//! is offline for its epoch with `offline_rate`, so a small set can be
+//! entirely offline while a crash unfolds; `CascadeResult::rotation_outages`
+//! counts those blocks. Rotation draws from its own RNG stream. See
+//! `rotation` for sweeping set size and epoch length.
+//!
+//! ## Commit-Reveal Timing
+//! Without `SimulationConfig::commit_reveal`, a keeper pool round commits,
+//! draws its executor and liquidates within one block. With it, members
+//! commit to a CDP in one block and reveal `reveal_blocks` later at that
+//! block's price; only committed members still willing to liquidate at
+//! the new price reveal, and each misses its reveal with `miss_rate`. A
+//! reveal nobody makes lapses, and the CDP is committed to afresh. A run
+//! stops early only after `reveal_blocks` more quiet blocks than usual, so
+//! pending reveals land. An external strategy is offered the reveal.
+//! Missed reveals draw from their own RNG stream. See `commit_reveal` for
+//! the latency cost against the priority gas winner-takes-all races burn.
+//!
+//! ## Liquidity Crises
+//! `SimulationConfig::liquidity_crisis` multiplies the price impact of every
+//! ETH sold by `impact_multiplier` from block `block` on: market makers pull
+//! their quotes partway through the crash, so the same liquidations move the
+//! price further. Mechanisms that were robust to the price path can still
+//! break on the liquidity regime; see `liquidity` for the paired comparison.
+//!
+//! ## Warm-Up
+//! The synthetic book starts every CDP at a ratio drawn at time zero, some
+//! just above the threshold, so the first blocks of a scenario liquidate
+//! positions no borrower would have left there. `SimulationConfig::warmup`
+//! first runs calm blocks (a driftless random walk) in which borrowers top
+//! up positions drifting toward liquidation and keepers clear the rest,
+//! then starts the scenario and every metric from the settled state.
+//!
+//! ## Reproducing Runs
+//! Campaigns draw one seed per run from the campaign RNG and run each
+//! cascade on `SimulationConfig::run_rng(seed)`, recording the seed in
+//! `CascadeResult::seed`. Any single run, such as a tail outlier, replays on
+//! its own from that seed. `SimulationConfig::rng_kind` picks the per-run
+//! generator: ChaCha12 by default, or Xoshiro256++ for long campaigns where
+//! drawing dominates; a run replays only under the kind it ran with.
+//!
+//! ## Run Storage
+//! A finished run leaves its book, keepers and working lists to the next
+//! run on the same thread, and the block loop works in buffers kept across
+//! rounds, so a campaign allocates per worker rather than per run or block.
+//! `--compute` on `fair-sim cascade` shows the allocations left per run.
+//!
+//! ## Tracing
+//! Runs emit `tracing` spans and events for hosts embedding the library: an
+//! `info` span per campaign (scenario, mechanism, runs) closed by a summary
+//! event, a `debug` span per run (index, seed) closed by its outcome, and a
+//! `trace` event per block. Nothing is recorded until the host installs a
+//! subscriber; `fair-sim -v` installs one on stderr.
+//!
+//! ## Price Paths
+//! Each block opens with the scenario's exogenous move. `price_path`
+//! defines the `PricePath` trait the scenarios implement; the builder
+//! accepts any implementation in their place.
+//!
+//! ## Severity
+//! `SimulationConfig::severity` scales every exogenous move in log space:
+//! a shock `s` becomes `s^severity`, so 2.0 turns the flash crash's 30%
+//! drop into 51% and doubles the volatile crash's drift and volatility,
+//! while 0.0 holds the price flat. One scenario definition thereby spans
+//! mild to catastrophic stress; sweep it with `sweep` (`severity=0.5:3:0.5`).
+//!
+//! ## Custom Setups
+//! `CascadeSimulationBuilder` runs the engine on a hand-built CDP book,
+//! keeper set, or exogenous price path instead of the random initial state.
+
+use std::cell::Cell;
+use std::mem;
+use std::str::FromStr;
 
 use rand::prelude::*;
-use rand_distr::{Distribution, Normal};
+use rand::rngs::SmallRng;
+use rand_distr::{Beta, LogNormal, Pareto};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, debug_span, info, info_span, trace, Span};
+
+use crate::audit::RunLedger;
+use crate::compute::{measure, ComputeSummary, CountingRng, RunCompute};
+use crate::core::{price_after_sale, profit_concentration, shocked_price, LiquidationIndex};
+pub use crate::core::{Keeper, KeeperBehavior, CDP};
+use crate::llamma::{BandMarket, SoftBook};
+pub use crate::llamma::{Llamma, SoftOutcome};
+use crate::error::{Error, Result};
+use crate::price_path::{PricePath, ScriptedPath, ShockSequence};
+use crate::quantiles::TDigest;
+use crate::stats::Band;
+use crate::strategy::{
+    attempt_gas_cost, AttemptOutcome, AttemptResult, KeeperLedger, KeeperStrategy, Opportunity, BASE_GAS_COST,
+    POOL_COMMIT_COST,
+};
 
 const NUM_CDPS: usize = 500;
 const NUM_KEEPERS: usize = 50;
@@ -28,347 +408,3933 @@ const MIN_COLLATERAL_RATIO: f64 = 1.5; // 150% minimum
 const LIQUIDATIONS_PER_BLOCK: usize = 10;
 const MAX_BLOCKS: usize = 100;
 const PRICE_IMPACT_PER_ETH: f64 = 0.0001; // 0.01% per ETH sold
+pub const BLOCKS_PER_YEAR: f64 = 365.0 * 24.0 * 60.0 * 5.0; // 12s blocks
 
-#[derive(Clone, Copy, Debug, PartialEq)]
-pub enum LiquidationMechanism {
-    Traditional,  // Winner-takes-all, gas priority
-    KeeperPool,   // Fair: 70/30 split, commit-reveal
+pub const DEFAULT_POOL_SPLIT: f64 = 0.7; // Keeper pool: 70% to keepers, 30% to protocol
+pub const DEFAULT_SPREAD_BONUS: f64 = 0.05; // Fixed spread: 5% of the repaid debt, Aave's ETH bonus
+pub const DEFAULT_CLOSE_FACTOR: f64 = 0.5; // Fixed spread: half the debt per liquidation
+
+/// Keeper shares swept by `sweep_pool_split`: 50/50 through 95/5.
+pub const SPLIT_SWEEP: [f64; 10] = [0.50, 0.55, 0.60, 0.65, 0.70, 0.75, 0.80, 0.85, 0.90, 0.95];
+
+/// Uniform ranges the synthetic CDP book is drawn from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CdpDistribution {
+    pub min_collateral: f64, // ETH
+    pub max_collateral: f64,
+    pub min_ratio: f64,      // Initial collateral ratio
+    pub max_ratio: f64,
 }
 
-impl LiquidationMechanism {
-    pub fn all() -> Vec<Self> {
-        vec![Self::Traditional, Self::KeeperPool]
+impl Default for CdpDistribution {
+    fn default() -> Self {
+        Self {
+            min_collateral: 1.0,
+            max_collateral: 20.0,
+            min_ratio: 1.5,
+            max_ratio: 2.5,
+        }
     }
+}
 
-    pub fn name(&self) -> &'static str {
-        match self {
-            Self::Traditional => "Traditional (Winner-Takes-All)",
-            Self::KeeperPool => "Fair (Keeper Pool 70/30)",
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum CollateralAccounting {
+    Rebasing,     // Balance grows (stETH-style)
+    ExchangeRate, // Balance fixed, ETH per unit grows (wstETH/rETH-style)
+}
+
+/// A yield-bearing collateral token backing part of the CDP book; the rest
+/// holds plain ETH.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AccruingCollateral {
+    pub accounting: CollateralAccounting,
+    pub annual_yield: f64, // e.g. 0.035 for 3.5% staking yield
+    pub book_share: f64,   // Fraction of CDPs holding the token
+}
+
+impl Default for AccruingCollateral {
+    fn default() -> Self {
+        Self {
+            accounting: CollateralAccounting::ExchangeRate,
+            annual_yield: 0.035,
+            book_share: 1.0,
         }
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
-pub enum PriceScenario {
-    GradualDecline,    // 2% per block for 10 blocks
-    FlashCrash,        // 30% instant drop
-    VolatileCrash,     // Jump-diffusion with high volatility
-    BlackSwan,         // 50% crash + continued decline
+impl AccruingCollateral {
+    /// Growth in ETH value of one unit held for `years`.
+    pub fn growth(&self, years: f64) -> f64 {
+        (1.0 + self.annual_yield).powf(years)
+    }
 }
 
-impl PriceScenario {
-    pub fn all() -> Vec<Self> {
-        vec![
-            Self::GradualDecline,
-            Self::FlashCrash,
-            Self::VolatileCrash,
-            Self::BlackSwan,
-        ]
-    }
+/// A second lending protocol sharing Fair's collateral market. Its keepers
+/// are assumed efficient: every liquidatable CDP clears, up to
+/// `liquidations_per_block`, riskiest first.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NeighborProtocol {
+    pub num_cdps: usize,
+    pub min_collateral_ratio: f64,
+    pub liquidations_per_block: usize,
+    pub cdp_distribution: CdpDistribution,
+}
 
-    pub fn name(&self) -> &'static str {
-        match self {
-            Self::GradualDecline => "Gradual Decline (-20% over 10 blocks)",
-            Self::FlashCrash => "Flash Crash (-30% instant)",
-            Self::VolatileCrash => "Volatile Crash (jump-diffusion)",
-            Self::BlackSwan => "Black Swan (-50% + continued decline)",
+impl Default for NeighborProtocol {
+    fn default() -> Self {
+        // Aave-style: lower threshold, thinner buffers, higher throughput
+        Self {
+            num_cdps: NUM_CDPS,
+            min_collateral_ratio: 1.25,
+            liquidations_per_block: 20,
+            cdp_distribution: CdpDistribution {
+                min_ratio: 1.3,
+                max_ratio: 2.2,
+                ..Default::default()
+            },
         }
     }
 }
 
-#[derive(Clone)]
-struct CDP {
-    id: usize,
-    collateral: f64,      // ETH
-    debt: f64,            // USD
-    is_liquidated: bool,
+impl NeighborProtocol {
+    /// Synthetic book for the neighbor, opened at `eth_price`.
+    pub fn book(&self, eth_price: f64, rng: &mut impl Rng) -> Vec<CDP> {
+        (0..self.num_cdps)
+            .map(|i| CDP::new(i, eth_price, &self.cdp_distribution, rng))
+            .collect()
+    }
 }
 
-impl CDP {
-    fn new(id: usize, rng: &mut impl Rng) -> Self {
-        let collateral = 1.0 + rng.gen::<f64>() * 19.0; // 1-20 ETH
-        let ratio = 1.5 + rng.gen::<f64>() * 1.0; // 150-250% initial ratio
-        let debt = (collateral * INITIAL_ETH_PRICE) / ratio;
-        
+/// Rival protocols bidding for the same keepers' capital during a
+/// market-wide event.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SharedLiquidity {
+    pub competitor_bonus: f64,          // Keeper return per USD of rival debt repaid
+    pub competitor_debt_per_block: f64, // Rival debt on offer per block while the price is below its start
+}
+
+impl Default for SharedLiquidity {
+    fn default() -> Self {
         Self {
-            id,
-            collateral,
-            debt,
-            is_liquidated: false,
+            competitor_bonus: 0.05,
+            competitor_debt_per_block: 1_000_000.0,
         }
     }
+}
 
-    fn collateral_ratio(&self, eth_price: f64) -> f64 {
-        if self.debt == 0.0 {
-            return f64::INFINITY;
+/// Keeper pool payouts made partly in a protocol token whose price tracks
+/// system health. Has no effect on winner-takes-all liquidations.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RewardToken {
+    pub emission_share: f64, // Fraction of the keeper share paid in tokens
+    pub health_beta: f64,    // Token price elasticity to system health
+}
+
+impl Default for RewardToken {
+    fn default() -> Self {
+        Self {
+            emission_share: 0.5,
+            health_beta: 3.0, // Governance tokens fall harder than the collateral
         }
-        (self.collateral * eth_price) / self.debt
     }
+}
 
-    fn is_underwater(&self, eth_price: f64) -> bool {
-        self.collateral_ratio(eth_price) < 1.0
+impl RewardToken {
+    /// Token price relative to par at `health` (1.0 = opening state).
+    pub fn price(&self, health: f64) -> f64 {
+        health.clamp(0.0, 1.0).powf(self.health_beta)
     }
 
-    fn is_liquidatable(&self, eth_price: f64) -> bool {
-        !self.is_liquidated && self.collateral_ratio(eth_price) < MIN_COLLATERAL_RATIO
+    /// Value to a keeper of one USD of keeper share at `token_price`.
+    pub fn payout_value(&self, token_price: f64) -> f64 {
+        1.0 - self.emission_share + self.emission_share * token_price
     }
+}
+
+/// A keeper incentive program ending at a cliff. Mercenaries are the last
+/// `mercenary_share` of the keeper set (keepers are i.i.d.).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IncentiveProgram {
+    pub bonus_per_liquidation: f64, // USD to keepers, split across the pool like the penalty
+    pub cliff_block: Option<usize>, // None = the program outlasts the run
+    pub mercenary_share: f64,       // Keepers that leave at the cliff
+}
 
-    fn liquidation_profit(&self, eth_price: f64) -> f64 {
-        let collateral_value = self.collateral * eth_price;
-        let profit = (collateral_value - self.debt) * LIQUIDATION_PENALTY;
-        profit.max(0.0)
+impl Default for IncentiveProgram {
+    fn default() -> Self {
+        Self {
+            bonus_per_liquidation: 20.0,
+            cliff_block: Some(5),
+            mercenary_share: 0.5,
+        }
     }
+}
 
-    fn bad_debt(&self, eth_price: f64) -> f64 {
-        if self.is_underwater(eth_price) && !self.is_liquidated {
-            (self.debt - self.collateral * eth_price).max(0.0)
-        } else {
-            0.0
+impl IncentiveProgram {
+    pub fn is_live(&self, block: usize) -> bool {
+        self.cliff_block.is_none_or(|cliff| block < cliff)
+    }
+}
+
+/// An executor premium inside the keeper pool. Members race for the
+/// execution slot with priority fees instead of being picked at random.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExecutionRace {
+    pub executor_share: f64, // Part of the keeper share paid to the executor alone
+}
+
+impl Default for ExecutionRace {
+    fn default() -> Self {
+        Self { executor_share: 0.1 }
+    }
+}
+
+/// What the keeper pool does when its selected executor fails.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum FallbackPolicy {
+    Backup,                   // Another member executes in the same block
+    Reselect { delay: usize }, // The CDP is re-selected `delay` blocks later
+}
+
+/// The selected pool executor failing to land its transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExecutionFailure {
+    pub failure_rate: f64, // Chance each selected executor fails
+    pub fallback: FallbackPolicy,
+}
+
+impl Default for ExecutionFailure {
+    fn default() -> Self {
+        Self {
+            failure_rate: 0.05,
+            fallback: FallbackPolicy::Reselect { delay: 1 },
         }
     }
 }
 
-#[derive(Clone)]
-struct Keeper {
-    id: usize,
-    capital: f64,         // Available capital for liquidations
-    gas_priority: f64,    // 0-1, higher = faster execution
-    total_profit: f64,
-    liquidations: usize,
+/// Which keepers watch the public mempool, and how far ahead that puts
+/// them. Watchers are the first `visible_share` of the keeper set (keepers
+/// are i.i.d.).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MempoolVisibility {
+    pub visible_share: f64, // 0 = private mempool, 1 = fully public
+    pub lead_blocks: usize, // Blocks the rest lag behind watchers
 }
 
-impl Keeper {
-    fn new(id: usize, rng: &mut impl Rng) -> Self {
+impl Default for MempoolVisibility {
+    fn default() -> Self {
         Self {
-            id,
-            capital: 10000.0 + rng.gen::<f64>() * 90000.0, // $10k-$100k
-            gas_priority: rng.gen::<f64>(),
-            total_profit: 0.0,
-            liquidations: 0,
+            visible_share: 0.5,
+            lead_blocks: 1,
         }
     }
+}
 
-    fn willing_to_liquidate(&self, profit: f64, mechanism: LiquidationMechanism) -> bool {
-        match mechanism {
-            LiquidationMechanism::Traditional => {
-                profit > 50.0 // Only if profit > gas cost
-            }
-            LiquidationMechanism::KeeperPool => {
-                profit > 10.0 // Lower threshold because of shared profit
+/// What the protocol liquidates with while its queue is deep.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Backstop {
+    Traditional, // Winner-takes-all race at the configured penalty
+    DutchAuction { start_penalty: f64, step: f64, max_penalty: f64 }, // Penalty rises per block a CDP waits
+}
+
+impl Backstop {
+    /// Penalty offered on a CDP liquidatable for `blocks_waiting` blocks.
+    pub fn penalty(&self, base_penalty: f64, blocks_waiting: usize) -> f64 {
+        match *self {
+            Self::Traditional => base_penalty,
+            Self::DutchAuction { start_penalty, step, max_penalty } => {
+                (start_penalty + step * blocks_waiting as f64).min(max_penalty)
             }
         }
     }
+
+    /// Backstops that sell collateral by auction rather than a fixed-penalty race.
+    pub fn is_auction(&self) -> bool {
+        matches!(self, Self::DutchAuction { .. })
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            Self::Traditional => "Traditional backstop".to_string(),
+            Self::DutchAuction { start_penalty, step, max_penalty } => format!(
+                "Dutch auction {:.0}%+{:.0}%/block to {:.0}%",
+                start_penalty * 100.0,
+                step * 100.0,
+                max_penalty * 100.0
+            ),
+        }
+    }
 }
 
-struct CascadeSimulation {
-    cdps: Vec<CDP>,
-    keepers: Vec<Keeper>,
-    eth_price: f64,
-    mechanism: LiquidationMechanism,
-    scenario: PriceScenario,
-    
-    block: usize,
-    cascade_depth: usize,
-    current_wave_liquidations: usize,
-    total_liquidations: usize,
-    total_bad_debt: f64,
-    price_history: Vec<f64>,
-    liquidations_per_block: Vec<usize>,
+/// Competition-driven auction clearing: the realized discount on each
+/// auctioned CDP is the lowest of its bidders' reservation discounts.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuctionDiscount {
+    pub dispersion: f64,     // Lognormal sigma of reservations around the offered penalty
+    pub protocol_share: f64, // Share of the realized discount the protocol keeps
 }
 
-impl CascadeSimulation {
-    fn new(mechanism: LiquidationMechanism, scenario: PriceScenario, rng: &mut impl Rng) -> Self {
-        let cdps: Vec<CDP> = (0..NUM_CDPS).map(|i| CDP::new(i, rng)).collect();
-        let keepers: Vec<Keeper> = (0..NUM_KEEPERS).map(|i| Keeper::new(i, rng)).collect();
-        
+impl Default for AuctionDiscount {
+    fn default() -> Self {
         Self {
-            cdps,
-            keepers,
-            eth_price: INITIAL_ETH_PRICE,
-            mechanism,
-            scenario,
-            block: 0,
-            cascade_depth: 0,
-            current_wave_liquidations: 0,
-            total_liquidations: 0,
-            total_bad_debt: 0.0,
-            price_history: vec![INITIAL_ETH_PRICE],
-            liquidations_per_block: Vec::new(),
+            dispersion: 0.5,
+            protocol_share: 0.0,
         }
     }
+}
 
-    fn apply_price_shock(&mut self, rng: &mut impl Rng) {
-        match self.scenario {
-            PriceScenario::GradualDecline => {
-                if self.block < 10 {
-                    self.eth_price *= 0.98; // 2% drop per block
-                }
-            }
-            PriceScenario::FlashCrash => {
-                if self.block == 0 {
-                    self.eth_price *= 0.70; // 30% instant drop
-                }
-            }
-            PriceScenario::VolatileCrash => {
-                let normal = Normal::new(-0.02, 0.05).unwrap();
-                let return_pct: f64 = normal.sample(rng);
-                self.eth_price *= 1.0 + return_pct;
-                
-                if rng.gen::<f64>() < 0.1 {
-                    self.eth_price *= 0.9; // 10% chance of 10% jump down
-                }
-            }
-            PriceScenario::BlackSwan => {
-                if self.block == 0 {
-                    self.eth_price *= 0.50; // 50% instant drop
-                } else if self.block < 20 {
-                    self.eth_price *= 0.99; // Continued 1% decline
-                }
-            }
+impl AuctionDiscount {
+    /// Discount an auction offering `offered` clears at with `bidders`
+    /// competing for the CDP.
+    pub fn clearing_discount(&self, offered: f64, bidders: usize, rng: &mut impl Rng) -> f64 {
+        let reservation = LogNormal::new(-self.dispersion.powi(2) / 2.0, self.dispersion).expect("validated dispersion");
+        let lowest = (0..bidders).map(|_| reservation.sample(rng)).fold(f64::INFINITY, f64::min);
+        offered * lowest.min(1.0)
+    }
+}
+
+/// A policy switching the configured mechanism to a backstop whenever more
+/// than `queue_threshold` CDPs are liquidatable at the start of a block,
+/// and back once the queue drains.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MechanismSwitch {
+    pub queue_threshold: usize,
+    pub backstop: Backstop,
+}
+
+impl Default for MechanismSwitch {
+    fn default() -> Self {
+        Self {
+            queue_threshold: 2 * LIQUIDATIONS_PER_BLOCK,
+            backstop: Backstop::DutchAuction { start_penalty: 0.05, step: 0.02, max_penalty: 0.30 },
         }
-        
-        self.eth_price = self.eth_price.max(100.0);
-        self.price_history.push(self.eth_price);
     }
+}
 
-    fn apply_liquidation_price_impact(&mut self, eth_sold: f64) {
-        let impact = eth_sold * PRICE_IMPACT_PER_ETH;
-        self.eth_price *= 1.0 - impact;
-        self.eth_price = self.eth_price.max(100.0);
+/// A governance change queued behind a timelock: detected the first block
+/// more than `queue_threshold` CDPs are liquidatable at its start, executed
+/// `delay_blocks` later. Unset parameters keep their configured values.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GovernanceTimelock {
+    pub queue_threshold: usize,
+    pub delay_blocks: usize,
+    pub liquidation_penalty: Option<f64>,
+    pub split: Option<f64>, // Keeper pool share; Traditional ignores it
+}
+
+impl Default for GovernanceTimelock {
+    fn default() -> Self {
+        Self {
+            queue_threshold: 2 * LIQUIDATIONS_PER_BLOCK,
+            delay_blocks: 10,
+            liquidation_penalty: Some(0.20),
+            split: None,
+        }
     }
+}
 
-    fn run_liquidation_round(&mut self, rng: &mut impl Rng) -> usize {
-        let mut liquidatable: Vec<usize> = self.cdps.iter()
-            .enumerate()
-            .filter(|(_, cdp)| cdp.is_liquidatable(self.eth_price))
-            .map(|(i, _)| i)
-            .collect();
-        
-        liquidatable.sort_by(|&a, &b| {
-            let ratio_a = self.cdps[a].collateral_ratio(self.eth_price);
-            let ratio_b = self.cdps[b].collateral_ratio(self.eth_price);
-            ratio_a.partial_cmp(&ratio_b).unwrap()
-        });
-        
-        let mut liquidations_this_block = 0;
-        let mut eth_sold_this_block = 0.0;
-        
-        for cdp_idx in liquidatable.iter().take(LIQUIDATIONS_PER_BLOCK) {
-            let cdp = &self.cdps[*cdp_idx];
-            let profit = cdp.liquidation_profit(self.eth_price);
-            
-            let participating_keepers: Vec<usize> = self.keepers.iter()
-                .enumerate()
-                .filter(|(_, k)| k.willing_to_liquidate(profit, self.mechanism))
-                .map(|(i, _)| i)
-                .collect();
-            
-            if participating_keepers.is_empty() {
-                continue;
-            }
-            
-            match self.mechanism {
-                LiquidationMechanism::Traditional => {
-                    let winner_idx = participating_keepers.iter()
-                        .max_by(|&&a, &&b| {
-                            self.keepers[a].gas_priority
-                                .partial_cmp(&self.keepers[b].gas_priority)
-                                .unwrap()
-                        })
-                        .unwrap();
-                    
-                    self.keepers[*winner_idx].total_profit += profit;
-                    self.keepers[*winner_idx].liquidations += 1;
-                }
-                LiquidationMechanism::KeeperPool => {
-                    let keeper_share = profit * 0.7;
-                    let per_keeper = keeper_share / participating_keepers.len() as f64;
-                    
-                    for &k_idx in &participating_keepers {
-                        self.keepers[k_idx].total_profit += per_keeper;
-                    }
-                    
-                    let winner_idx = participating_keepers[rng.gen_range(0..participating_keepers.len())];
-                    self.keepers[winner_idx].liquidations += 1;
-                }
-            }
-            
-            eth_sold_this_block += self.cdps[*cdp_idx].collateral;
-            self.cdps[*cdp_idx].is_liquidated = true;
-            liquidations_this_block += 1;
+/// The keeper pool's keeper share paid from a treasury: claims accrue per
+/// liquidation and the treasury drips an exponential average of its
+/// inflows back out every `epoch_blocks`, pro rata to the epoch's claims.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TreasuryDrip {
+    pub epoch_blocks: usize,
+    pub smoothing: f64, // Weight of the latest epoch's inflow in the drip; 1 = pay each epoch's inflow
+    pub endowment: f64, // USD the treasury holds at block 0
+}
+
+impl Default for TreasuryDrip {
+    fn default() -> Self {
+        Self {
+            epoch_blocks: 10,
+            smoothing: 0.3,
+            endowment: 0.0,
         }
-        
-        self.apply_liquidation_price_impact(eth_sold_this_block);
-        
-        liquidations_this_block
     }
+}
 
-    fn calculate_bad_debt(&self) -> f64 {
-        self.cdps.iter()
-            .map(|cdp| cdp.bad_debt(self.eth_price))
-            .sum()
+/// A protocol backstop funded from its penalty take, which absorbs bad
+/// debt before it reaches the system.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InsuranceFund {
+    pub share: f64,     // Of the protocol's penalty take paid into the fund
+    pub endowment: f64, // USD the fund holds at block 0
+}
+
+impl Default for InsuranceFund {
+    fn default() -> Self {
+        Self { share: 0.5, endowment: 0.0 }
     }
+}
 
-    fn run(&mut self, rng: &mut impl Rng) -> CascadeResult {
-        let mut consecutive_empty_blocks = 0;
-        let mut max_wave_liquidations = 0;
-        
-        while self.block < MAX_BLOCKS {
-            self.apply_price_shock(rng);
-            
-            let liquidations = self.run_liquidation_round(rng);
-            self.liquidations_per_block.push(liquidations);
-            self.total_liquidations += liquidations;
-            
-            if liquidations > 0 {
-                self.current_wave_liquidations += liquidations;
-                max_wave_liquidations = max_wave_liquidations.max(liquidations);
-                consecutive_empty_blocks = 0;
-            } else {
-                if self.current_wave_liquidations > 0 {
-                    self.cascade_depth += 1;
-                }
-                self.current_wave_liquidations = 0;
-                consecutive_empty_blocks += 1;
-                
-                if consecutive_empty_blocks >= 5 && self.block > 10 {
-                    break;
-                }
+/// Governance-token debt auctions covering bad debt past the insurance
+/// fund, Maker's flop auctions.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DebtAuction {
+    pub lot: f64,          // USD raised per auction (Maker's sump)
+    pub market_cap: f64,   // USD value of the token supply at par
+    pub discount: f64,     // Below the market bidders buy minted tokens at
+    pub impact: f64,       // Log price fall per unit share of supply minted
+    pub max_dilution: f64, // Share of supply governance will mint before giving up
+}
+
+impl Default for DebtAuction {
+    fn default() -> Self {
+        Self {
+            lot: 50_000.0,
+            market_cap: 20_000_000.0,
+            discount: 0.05,
+            impact: 3.0,
+            max_dilution: 0.25,
+        }
+    }
+}
+
+impl DebtAuction {
+    /// Covers `hole` USD of bad debt with auctions opening at `price`
+    /// relative to par.
+    pub fn recapitalize(&self, hole: f64, price: f64) -> DebtAuctionOutcome {
+        let mut outcome = DebtAuctionOutcome { auctions: 0, raised: 0.0, dilution: 0.0, final_price: price, uncovered: hole };
+        while outcome.uncovered > 1e-6 && outcome.dilution < self.max_dilution {
+            let bid = outcome.final_price * (1.0 - self.discount) * self.market_cap; // USD per unit of supply
+            if bid <= 0.0 {
+                break;
             }
-            
-            self.block += 1;
+            let minted = (self.lot.min(outcome.uncovered) / bid).min(self.max_dilution - outcome.dilution);
+            outcome.auctions += 1;
+            outcome.raised += minted * bid;
+            outcome.uncovered -= minted * bid;
+            outcome.dilution += minted;
+            outcome.final_price *= (-self.impact * minted).exp();
         }
-        
-        self.total_bad_debt = self.calculate_bad_debt();
-        
-        let keeper_profits: Vec<f64> = self.keepers.iter().map(|k| k.total_profit).collect();
-        let total_profit: f64 = keeper_profits.iter().sum();
-        
-        let profit_concentration = if total_profit > 0.0 {
-            let mut sorted_profits = keeper_profits.clone();
-            sorted_profits.sort_by(|a, b| b.partial_cmp(a).unwrap());
-            let top_20_pct: f64 = sorted_profits.iter().take(NUM_KEEPERS / 5).sum();
-            top_20_pct / total_profit
-        } else {
-            0.0
-        };
-        
-        let participation_rate = self.keepers.iter()
-            .filter(|k| k.liquidations > 0)
-            .count() as f64 / NUM_KEEPERS as f64;
-        
-        let price_drop = 1.0 - (self.eth_price / INITIAL_ETH_PRICE);
-        
-        let unliquidated_underwater: usize = self.cdps.iter()
-            .filter(|cdp| cdp.is_underwater(self.eth_price) && !cdp.is_liquidated)
-            .count();
-        
+        if outcome.uncovered <= 1e-6 {
+            outcome.uncovered = 0.0;
+        }
+        outcome
+    }
+}
+
+/// Keeper pool membership bonded with stake: rewards pro rata to stake and
+/// slashing for assigned liquidations left unexecuted.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeeperStaking {
+    pub stake_share: f64, // Of its capital each keeper bonds
+    pub min_stake: f64,   // USD below which a keeper is ejected from the pool
+    pub slash_rate: f64,  // Of its stake an executor loses per liquidation it fails to execute
+}
+
+impl Default for KeeperStaking {
+    fn default() -> Self {
+        Self {
+            stake_share: 0.1,
+            min_stake: 1_000.0,
+            slash_rate: 0.3,
+        }
+    }
+}
+
+/// How the keeper pool divides the members' part of the keeper share.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PoolSplit {
+    #[default]
+    Equal,   // Alike per member of the round
+    Capital, // Pro rata to members' capital
+}
+
+/// The order in which liquidatable CDPs are taken each block.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QueueOrder {
+    #[default]
+    Ratio,     // Protocol queue, lowest collateral ratio first
+    RiskScore, // Protocol queue, lowest IPFE risk score first
+    Profit,    // Keepers' choice, most profitable first
+}
+
+/// Keeper pool executors drawn weighted by their track record.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeeperReputation {
+    pub memory: f64,   // Weight of each new outcome in a keeper's moving averages
+    pub exponent: f64, // Applied to the score; higher favors the best record more
+    pub floor: f64,    // Added to every weight, so no member is shut out
+}
+
+impl Default for KeeperReputation {
+    fn default() -> Self {
+        Self {
+            memory: 0.1,
+            exponent: 2.0,
+            floor: 0.05,
+        }
+    }
+}
+
+/// Keeper pool rounds split into a commit and a reveal `reveal_blocks`
+/// later, at which committed members can miss or drop out.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CommitReveal {
+    pub reveal_blocks: usize, // Blocks from commit to reveal (k)
+    pub miss_rate: f64,       // Chance each committed member misses its reveal
+}
+
+impl Default for CommitReveal {
+    fn default() -> Self {
+        Self { reveal_blocks: 2, miss_rate: 0.05 }
+    }
+}
+
+/// A window in which a CDP below the minimum ratio cannot be liquidated
+/// and its owner may top it back up.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GracePeriod {
+    pub grace_blocks: usize, // Blocks from falling below the minimum to becoming liquidatable
+    pub top_up_rate: f64,    // Chance per block of grace the owner tops up
+    pub top_up_ratio: f64,   // Collateral ratio owners top back up to
+}
+
+impl Default for GracePeriod {
+    fn default() -> Self {
+        Self {
+            grace_blocks: 5,
+            top_up_rate: 0.2,
+            top_up_ratio: 1.7,
+        }
+    }
+}
+
+/// A liquidation penalty rising linearly with how far a CDP's collateral
+/// ratio is below the minimum.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PenaltyCurve {
+    pub min_penalty: f64, // At the minimum ratio
+    pub slope: f64,       // Added per unit of collateral ratio below the minimum
+    pub max_penalty: f64,
+}
+
+impl Default for PenaltyCurve {
+    fn default() -> Self {
+        Self {
+            min_penalty: 0.05,
+            slope: 0.5,
+            max_penalty: 0.20,
+        }
+    }
+}
+
+impl PenaltyCurve {
+    /// Penalty on a CDP at `collateral_ratio` under `min_collateral_ratio`.
+    pub fn penalty(&self, collateral_ratio: f64, min_collateral_ratio: f64) -> f64 {
+        let depth = (min_collateral_ratio - collateral_ratio).max(0.0);
+        (self.min_penalty + self.slope * depth).min(self.max_penalty)
+    }
+}
+
+/// Partial liquidations rate-limited per borrower: each liquidation closes
+/// `close_factor` of the CDP's debt, after which the CDP cannot be
+/// liquidated again for `cooldown_blocks` blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LiquidationCooldown {
+    pub close_factor: f64,      // Share of the debt each liquidation closes
+    pub cooldown_blocks: usize, // Blocks before the same CDP can be liquidated again
+}
+
+impl Default for LiquidationCooldown {
+    fn default() -> Self {
+        Self {
+            close_factor: 0.5,
+            cooldown_blocks: 5,
+        }
+    }
+}
+
+/// A partial liquidation that would leave less debt than this closes the CDP.
+pub(crate) const DUST_DEBT: f64 = 100.0;
+
+/// An active keeper set rotated every `epoch_blocks`: only the `set_size`
+/// keepers drawn for the epoch join keeper pool rounds and share their
+/// rewards. Each member drawn is offline for the whole epoch with
+/// probability `offline_rate`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeeperRotation {
+    pub set_size: usize,
+    pub epoch_blocks: usize,
+    pub offline_rate: f64, // Chance each member misses its epoch (downtime, outages)
+}
+
+impl Default for KeeperRotation {
+    fn default() -> Self {
+        Self {
+            set_size: 10,
+            epoch_blocks: 25,
+            offline_rate: 0.2,
+        }
+    }
+}
+
+/// Market makers withdrawing partway through a crash: from `block` on, the
+/// price impact per ETH sold is `impact_multiplier` times the configured one.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LiquidityCrisis {
+    pub block: usize,            // First block under the thin market
+    pub impact_multiplier: f64,  // Applied to `price_impact_per_eth`
+}
+
+impl Default for LiquidityCrisis {
+    fn default() -> Self {
+        Self {
+            block: 5,
+            impact_multiplier: 10.0,
+        }
+    }
+}
+
+/// Calm blocks run before the scenario starts: the price follows a driftless
+/// random walk, collateral accrues, attentive borrowers top up positions
+/// drifting toward liquidation and keepers clear the ones that cross the
+/// line. The run then restarts from the evolved book, keepers and price,
+/// so neither the stress nor the metrics see the random time-zero state.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Warmup {
+    pub blocks: usize,
+    pub volatility: f64,   // Per-block log-price volatility
+    pub top_up_ratio: f64, // Collateral ratio borrowers top back up to
+    pub top_up_rate: f64,  // Chance per block a borrower below it tops up
+}
+
+impl Default for Warmup {
+    fn default() -> Self {
+        Self {
+            blocks: 50,
+            volatility: 0.002,
+            top_up_ratio: 1.7,
+            top_up_rate: 0.1,
+        }
+    }
+}
+
+/// Distribution each keeper's capital (USD) is drawn from.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum CapitalDistribution {
+    Uniform { min: f64, max: f64 },
+    LogNormal { median: f64, sigma: f64 }, // sigma of ln(capital)
+    PowerLaw { min: f64, alpha: f64 },     // Pareto tail: a few whales, many small keepers
+}
+
+impl Default for CapitalDistribution {
+    fn default() -> Self {
+        Self::Uniform { min: 10_000.0, max: 100_000.0 }
+    }
+}
+
+impl CapitalDistribution {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        match *self {
+            Self::Uniform { min, max } => min + rng.gen::<f64>() * (max - min),
+            Self::LogNormal { median, sigma } => LogNormal::new(median.ln(), sigma).unwrap().sample(rng),
+            Self::PowerLaw { min, alpha } => Pareto::new(min, alpha).unwrap().sample(rng),
+        }
+    }
+}
+
+/// Distribution of keeper gas priority on [0, 1].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum GasPriorityDistribution {
+    #[default]
+    Uniform,
+    Beta { alpha: f64, beta: f64 }, // e.g. alpha > beta skews the field toward fast keepers
+}
+
+impl GasPriorityDistribution {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        match *self {
+            Self::Uniform => rng.gen::<f64>(),
+            Self::Beta { alpha, beta } => Beta::new(alpha, beta).unwrap().sample(rng),
+        }
+    }
+}
+
+/// Gas the simulated keepers pay, in USD, and the margin they want on top
+/// of it. Participation thresholds derive from it unless overridden.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GasModel {
+    pub base_gas: f64,     // Per liquidation transaction
+    pub pool_commit: f64,  // To join a pool liquidation
+    pub race_premium: f64, // Priority fee a racer expects to need under Traditional
+    pub min_margin: f64,   // Net profit below which a keeper doesn't bother
+}
+
+impl Default for GasModel {
+    fn default() -> Self {
+        Self { base_gas: BASE_GAS_COST, pool_commit: POOL_COMMIT_COST, race_premium: 28.0, min_margin: 2.0 }
+    }
+}
+
+impl GasModel {
+    /// Gas a keeper risks to take part: a whole race under Traditional and
+    /// fixed spread, a bid under an English or batch auction, only the
+    /// commit under the pool (the executor's gas is paid from the penalty).
+    pub fn overhead(&self, mechanism: LiquidationMechanism) -> f64 {
+        match mechanism {
+            LiquidationMechanism::Traditional | LiquidationMechanism::FixedSpread { .. } => {
+                self.base_gas + self.race_premium
+            }
+            // One transaction per auction bid or band trade
+            LiquidationMechanism::EnglishAuction(_)
+            | LiquidationMechanism::SoftLiquidation(_)
+            | LiquidationMechanism::BatchAuction(_) => self.base_gas,
+            LiquidationMechanism::KeeperPool { .. } => self.pool_commit,
+        }
+    }
+
+    /// Keeper-side payout at which taking part clears the margin.
+    pub fn break_even(&self, mechanism: LiquidationMechanism) -> f64 {
+        self.overhead(mechanism) + self.min_margin
+    }
+
+    fn validate(&self) -> Result<()> {
+        if [self.base_gas, self.pool_commit, self.race_premium, self.min_margin].iter().all(|c| c.is_finite() && *c >= 0.0) {
+            Ok(())
+        } else {
+            Err(Error::Invalid("keepers gas costs and margin must be non-negative".to_string()))
+        }
+    }
+}
+
+/// Minimum keeper-side payout (penalty times keeper share, USD) a simulated
+/// keeper will act on, per mechanism, where it overrides the gas model's
+/// break-even (`KeeperConfig::threshold`).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ParticipationThresholds {
+    pub traditional: Option<f64>,
+    pub keeper_pool: Option<f64>,
+    pub fixed_spread: Option<f64>,
+    pub english_auction: Option<f64>,
+    pub soft_liquidation: Option<f64>,
+    pub batch_auction: Option<f64>,
+}
+
+impl ParticipationThresholds {
+    pub fn for_mechanism(&self, mechanism: LiquidationMechanism) -> Option<f64> {
+        match mechanism {
+            LiquidationMechanism::Traditional => self.traditional,
+            LiquidationMechanism::KeeperPool { .. } => self.keeper_pool,
+            LiquidationMechanism::FixedSpread { .. } => self.fixed_spread,
+            LiquidationMechanism::EnglishAuction(_) => self.english_auction,
+            LiquidationMechanism::SoftLiquidation(_) => self.soft_liquidation,
+            LiquidationMechanism::BatchAuction(_) => self.batch_auction,
+        }
+    }
+}
+
+/// The simulated keeper population.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeeperConfig {
+    pub count: usize,
+    pub capital: CapitalDistribution,
+    pub gas_priority: GasPriorityDistribution,
+    pub gas: GasModel,
+    pub thresholds: ParticipationThresholds,
+}
+
+impl Default for KeeperConfig {
+    fn default() -> Self {
+        Self {
+            count: NUM_KEEPERS,
+            capital: CapitalDistribution::default(),
+            gas_priority: GasPriorityDistribution::default(),
+            gas: GasModel::default(),
+            thresholds: ParticipationThresholds::default(),
+        }
+    }
+}
+
+impl KeeperConfig {
+    /// Minimum keeper-side payout the keepers act on under `mechanism`: the
+    /// override if set, else the gas model's break-even ($50 Traditional,
+    /// $7 pool by default).
+    pub fn threshold(&self, mechanism: LiquidationMechanism) -> f64 {
+        self.thresholds.for_mechanism(mechanism).unwrap_or_else(|| self.gas.break_even(mechanism))
+    }
+
+    /// One honest keeper drawn from the configured distributions.
+    pub fn keeper<R: Rng + ?Sized>(&self, id: usize, rng: &mut R) -> Keeper {
+        let capital = self.capital.sample(rng);
+        Keeper::new(id, capital, self.gas_priority.sample(rng))
+    }
+
+    /// `count` honest keepers.
+    pub fn population<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<Keeper> {
+        let mut keepers = Vec::new();
+        self.fill_population(rng, &mut keepers);
+        keepers
+    }
+
+    /// `population` into `keepers`, replacing its contents.
+    pub fn fill_population<R: Rng + ?Sized>(&self, rng: &mut R, keepers: &mut Vec<Keeper>) {
+        keepers.clear();
+        keepers.extend((0..self.count).map(|id| self.keeper(id, rng)));
+    }
+
+    fn validate(&self) -> Result<()> {
+        let check = |ok: bool, msg: &str| if ok { Ok(()) } else { Err(Error::Invalid(msg.to_string())) };
+
+        check(self.count > 0, "keepers count must be positive")?;
+        match self.capital {
+            CapitalDistribution::Uniform { min, max } => {
+                check(min >= 0.0 && min <= max, "keepers uniform capital range must be non-negative and ordered")?
+            }
+            CapitalDistribution::LogNormal { median, sigma } => check(
+                median > 0.0 && sigma.is_finite() && sigma >= 0.0,
+                "keepers lognormal capital needs a positive median and non-negative sigma",
+            )?,
+            CapitalDistribution::PowerLaw { min, alpha } => check(
+                min > 0.0 && alpha > 0.0 && alpha.is_finite(),
+                "keepers power-law capital needs a positive min and alpha",
+            )?,
+        }
+        if let GasPriorityDistribution::Beta { alpha, beta } = self.gas_priority {
+            check(
+                alpha > 0.0 && beta > 0.0 && alpha.is_finite() && beta.is_finite(),
+                "keepers beta gas priority needs positive alpha and beta",
+            )?;
+        }
+        let thresholds = &self.thresholds;
+        check(
+            [
+                thresholds.traditional,
+                thresholds.keeper_pool,
+                thresholds.fixed_spread,
+                thresholds.english_auction,
+                thresholds.soft_liquidation,
+            ]
+            .iter().flatten().all(|t| t.is_finite() && *t >= 0.0),
+            "keepers thresholds must be non-negative",
+        )?;
+        self.gas.validate()
+    }
+}
+
+/// Generator each run of a campaign draws from. The campaign RNG that deals
+/// out per-run seeds is always `StdRng`, so switching kinds keeps the seeds a
+/// campaign records and changes only what each run draws from them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RngKind {
+    #[default]
+    Std, // ChaCha12, rand's `StdRng`
+    Small, // Xoshiro256++, rand's `SmallRng`: far cheaper per draw, not cryptographic
+}
+
+impl RngKind {
+    /// A generator of this kind seeded from `seed`.
+    pub fn seeded(self, seed: u64) -> RunRng {
+        match self {
+            Self::Std => RunRng::Std(StdRng::seed_from_u64(seed)),
+            Self::Small => RunRng::Small(SmallRng::seed_from_u64(seed)),
+        }
+    }
+}
+
+impl FromStr for RngKind {
+    type Err = Error;
+
+    /// Case-insensitive; "chacha" and "xoshiro" name the algorithms.
+    fn from_str(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "std" | "chacha" => Ok(Self::Std),
+            "small" | "xoshiro" => Ok(Self::Small),
+            _ => Err(Error::Invalid(format!("unknown rng '{}' (expected std or small)", name))),
+        }
+    }
+}
+
+/// A run's RNG, of the `RngKind` its config selects.
+#[allow(clippy::large_enum_variant)] // Boxing ChaCha's buffer would add a hop to every default draw
+#[derive(Debug, Clone)]
+pub enum RunRng {
+    Std(StdRng),
+    Small(SmallRng),
+}
+
+impl RngCore for RunRng {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        match self {
+            Self::Std(rng) => rng.next_u32(),
+            Self::Small(rng) => rng.next_u32(),
+        }
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        match self {
+            Self::Std(rng) => rng.next_u64(),
+            Self::Small(rng) => rng.next_u64(),
+        }
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        match self {
+            Self::Std(rng) => rng.fill_bytes(dest),
+            Self::Small(rng) => rng.fill_bytes(dest),
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> std::result::Result<(), rand::Error> {
+        match self {
+            Self::Std(rng) => rng.try_fill_bytes(dest),
+            Self::Small(rng) => rng.try_fill_bytes(dest),
+        }
+    }
+}
+
+/// Runtime parameters of the cascade engine. Defaults reproduce the original
+/// hardcoded setup, so sweeps only need to override the fields they vary.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SimulationConfig {
+    pub num_cdps: usize,
+    pub keepers: KeeperConfig,
+    pub initial_eth_price: f64,
+    pub liquidation_penalty: f64,
+    pub penalty_curve: Option<PenaltyCurve>, // None = every CDP pays `liquidation_penalty`
+    pub min_collateral_ratio: f64,
+    pub liquidations_per_block: usize,
+    pub max_blocks: usize,
+    pub price_impact_per_eth: f64,
+    pub severity: f64,     // Exponent on every exogenous price move; 1 = as defined
+    pub cdp_distribution: CdpDistribution,
+    pub accruing_collateral: Option<AccruingCollateral>, // None = plain ETH book
+    pub neighbor: Option<NeighborProtocol>, // None = Fair is the only seller
+    pub shared_liquidity: Option<SharedLiquidity>, // None = keeper capital never binds
+    pub reward_token: Option<RewardToken>, // None = keeper pool pays in stablecoin only
+    pub incentive_program: Option<IncentiveProgram>, // None = keepers earn the penalty alone
+    pub execution_race: Option<ExecutionRace>, // None = the pool picks its executor at random
+    pub execution_failure: Option<ExecutionFailure>, // None = selected executors always land
+    pub mempool: Option<MempoolVisibility>, // None = every keeper sees opportunities at once
+    pub mechanism_switch: Option<MechanismSwitch>, // None = one mechanism throughout
+    pub governance_timelock: Option<GovernanceTimelock>, // None = parameters fixed all run
+    pub treasury_drip: Option<TreasuryDrip>, // None = the keeper share is split per liquidation
+    pub insurance_fund: Option<InsuranceFund>, // None = bad debt falls on the system directly
+    pub debt_auction: Option<DebtAuction>, // None = nothing recapitalizes bad debt
+    pub keeper_staking: Option<KeeperStaking>, // None = pool members split the keeper share per `pool_split`, unbonded
+    pub pool_split: PoolSplit, // Members' part of the keeper share, without staking
+    pub keeper_reputation: Option<KeeperReputation>, // None = the pool draws its executor uniformly
+    pub commit_reveal: Option<CommitReveal>, // None = pool rounds commit and execute in one block
+    pub auction_discount: Option<AuctionDiscount>, // None = auctions clear at their offered penalty
+    pub liquidation_cooldown: Option<LiquidationCooldown>, // None = each liquidation closes the whole CDP
+    pub grace_period: Option<GracePeriod>, // None = CDPs are liquidatable as soon as they fall below the minimum
+    pub queue_order: QueueOrder, // Order liquidatable CDPs are taken in under the per-block limit
+    pub keeper_rotation: Option<KeeperRotation>, // None = every keeper joins every pool round
+    pub liquidity_crisis: Option<LiquidityCrisis>, // None = price impact holds all run
+    pub warmup: Option<Warmup>, // None = the stress starts from the random initial state
+    pub seed: Option<u64>, // Fixed RNG seed for reproducible campaigns
+    pub rng_kind: RngKind, // Generator behind each run; the campaign's seeds are the same either way
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        Self {
+            num_cdps: NUM_CDPS,
+            keepers: KeeperConfig::default(),
+            initial_eth_price: INITIAL_ETH_PRICE,
+            liquidation_penalty: LIQUIDATION_PENALTY,
+            penalty_curve: None,
+            min_collateral_ratio: MIN_COLLATERAL_RATIO,
+            liquidations_per_block: LIQUIDATIONS_PER_BLOCK,
+            max_blocks: MAX_BLOCKS,
+            price_impact_per_eth: PRICE_IMPACT_PER_ETH,
+            severity: 1.0,
+            cdp_distribution: CdpDistribution::default(),
+            accruing_collateral: None,
+            neighbor: None,
+            shared_liquidity: None,
+            reward_token: None,
+            incentive_program: None,
+            execution_race: None,
+            execution_failure: None,
+            mempool: None,
+            mechanism_switch: None,
+            governance_timelock: None,
+            treasury_drip: None,
+            insurance_fund: None,
+            debt_auction: None,
+            keeper_staking: None,
+            pool_split: PoolSplit::Equal,
+            keeper_reputation: None,
+            commit_reveal: None,
+            auction_discount: None,
+            liquidation_cooldown: None,
+            grace_period: None,
+            queue_order: QueueOrder::Ratio,
+            keeper_rotation: None,
+            liquidity_crisis: None,
+            warmup: None,
+            seed: None,
+            rng_kind: RngKind::Std,
+        }
+    }
+}
+
+impl SimulationConfig {
+    /// Seeded RNG when `seed` is set, otherwise seeded from OS entropy.
+    pub fn rng(&self) -> StdRng {
+        match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        }
+    }
+
+    /// RNG for a single run of a campaign; `CascadeResult::seed` replays it.
+    pub fn run_rng(&self, seed: u64) -> RunRng {
+        self.rng_kind.seeded(seed)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        let check = |ok: bool, msg: &str| if ok { Ok(()) } else { Err(Error::Invalid(msg.to_string())) };
+
+        check(self.num_cdps > 0, "num_cdps must be positive")?;
+        self.keepers.validate()?;
+        check(self.initial_eth_price > 0.0, "initial_eth_price must be positive")?;
+        check(
+            (0.0..=1.0).contains(&self.liquidation_penalty),
+            "liquidation_penalty must be within [0, 1]",
+        )?;
+        if let Some(curve) = &self.penalty_curve {
+            check(
+                (0.0..=1.0).contains(&curve.min_penalty) && (0.0..=1.0).contains(&curve.max_penalty),
+                "penalty_curve penalties must be within [0, 1]",
+            )?;
+            check(curve.slope.is_finite() && curve.slope >= 0.0, "penalty_curve slope must be non-negative")?;
+            check(curve.min_penalty <= curve.max_penalty, "penalty_curve must start at or below its max penalty")?;
+        }
+        check(self.min_collateral_ratio >= 1.0, "min_collateral_ratio must be at least 1.0")?;
+        check(self.liquidations_per_block > 0, "liquidations_per_block must be positive")?;
+        check(self.max_blocks > 0, "max_blocks must be positive")?;
+        check(self.price_impact_per_eth >= 0.0, "price_impact_per_eth must be non-negative")?;
+        check(self.severity.is_finite() && self.severity >= 0.0, "severity must be non-negative")?;
+
+        let dist = &self.cdp_distribution;
+        check(
+            dist.min_collateral > 0.0 && dist.min_collateral <= dist.max_collateral,
+            "cdp_distribution collateral range must be positive and ordered",
+        )?;
+        check(
+            dist.min_ratio > 0.0 && dist.min_ratio <= dist.max_ratio,
+            "cdp_distribution ratio range must be positive and ordered",
+        )?;
+
+        if let Some(token) = &self.accruing_collateral {
+            check(token.annual_yield > -1.0, "accruing_collateral annual_yield must exceed -100%")?;
+            check(
+                (0.0..=1.0).contains(&token.book_share),
+                "accruing_collateral book_share must be within [0, 1]",
+            )?;
+        }
+
+        if let Some(shared) = &self.shared_liquidity {
+            check(
+                shared.competitor_bonus.is_finite() && shared.competitor_bonus >= 0.0,
+                "shared_liquidity competitor_bonus must be non-negative",
+            )?;
+            check(
+                shared.competitor_debt_per_block.is_finite() && shared.competitor_debt_per_block >= 0.0,
+                "shared_liquidity competitor_debt_per_block must be non-negative",
+            )?;
+        }
+
+        if let Some(token) = &self.reward_token {
+            check(
+                (0.0..=1.0).contains(&token.emission_share),
+                "reward_token emission_share must be within [0, 1]",
+            )?;
+            check(
+                token.health_beta.is_finite() && token.health_beta >= 0.0,
+                "reward_token health_beta must be non-negative",
+            )?;
+        }
+
+        if let Some(program) = &self.incentive_program {
+            check(
+                program.bonus_per_liquidation.is_finite() && program.bonus_per_liquidation >= 0.0,
+                "incentive_program bonus_per_liquidation must be non-negative",
+            )?;
+            check(
+                (0.0..=1.0).contains(&program.mercenary_share),
+                "incentive_program mercenary_share must be within [0, 1]",
+            )?;
+        }
+
+        if let Some(race) = &self.execution_race {
+            check(
+                (0.0..=1.0).contains(&race.executor_share),
+                "execution_race executor_share must be within [0, 1]",
+            )?;
+        }
+
+        if let Some(failure) = &self.execution_failure {
+            check(
+                (0.0..=1.0).contains(&failure.failure_rate),
+                "execution_failure failure_rate must be within [0, 1]",
+            )?;
+            check(
+                failure.fallback != FallbackPolicy::Reselect { delay: 0 },
+                "execution_failure reselect delay must be at least one block",
+            )?;
+        }
+
+        if let Some(mempool) = &self.mempool {
+            check(
+                (0.0..=1.0).contains(&mempool.visible_share),
+                "mempool visible_share must be within [0, 1]",
+            )?;
+        }
+
+        if let Some(switch) = &self.mechanism_switch {
+            if let Backstop::DutchAuction { start_penalty, step, max_penalty } = switch.backstop {
+                check(
+                    (0.0..=1.0).contains(&start_penalty) && (0.0..=1.0).contains(&max_penalty),
+                    "mechanism_switch auction penalties must be within [0, 1]",
+                )?;
+                check(step.is_finite() && step >= 0.0, "mechanism_switch auction step must be non-negative")?;
+                check(start_penalty <= max_penalty, "mechanism_switch auction must start at or below its max penalty")?;
+            }
+        }
+
+        if let Some(timelock) = &self.governance_timelock {
+            if let Some(penalty) = timelock.liquidation_penalty {
+                check((0.0..=1.0).contains(&penalty), "governance_timelock liquidation_penalty must be within [0, 1]")?;
+                check(self.penalty_curve.is_none(), "governance_timelock cannot change the flat penalty a penalty_curve replaces")?;
+            }
+            if let Some(split) = timelock.split {
+                LiquidationMechanism::KeeperPool { split }.validate()?;
+            }
+        }
+
+        if let Some(drip) = &self.treasury_drip {
+            check(drip.epoch_blocks > 0, "treasury_drip epoch_blocks must be positive")?;
+            check(
+                drip.smoothing > 0.0 && drip.smoothing <= 1.0,
+                "treasury_drip smoothing must be within (0, 1]",
+            )?;
+            check(
+                drip.endowment.is_finite() && drip.endowment >= 0.0,
+                "treasury_drip endowment must be non-negative",
+            )?;
+        }
+
+        if let Some(fund) = &self.insurance_fund {
+            check((0.0..=1.0).contains(&fund.share), "insurance_fund share must be within [0, 1]")?;
+            check(
+                fund.endowment.is_finite() && fund.endowment >= 0.0,
+                "insurance_fund endowment must be non-negative",
+            )?;
+        }
+
+        if let Some(auction) = &self.debt_auction {
+            check(auction.lot > 0.0 && auction.lot.is_finite(), "debt_auction lot must be positive")?;
+            check(
+                auction.market_cap > 0.0 && auction.market_cap.is_finite(),
+                "debt_auction market_cap must be positive",
+            )?;
+            check((0.0..1.0).contains(&auction.discount), "debt_auction discount must be within [0, 1)")?;
+            check(auction.impact.is_finite() && auction.impact >= 0.0, "debt_auction impact must be non-negative")?;
+            check(
+                auction.max_dilution.is_finite() && auction.max_dilution >= 0.0,
+                "debt_auction max_dilution must be non-negative",
+            )?;
+        }
+
+        if let Some(reputation) = &self.keeper_reputation {
+            check(
+                reputation.memory > 0.0 && reputation.memory <= 1.0,
+                "keeper_reputation memory must be within (0, 1]",
+            )?;
+            check(
+                reputation.exponent.is_finite() && reputation.exponent >= 0.0,
+                "keeper_reputation exponent must be non-negative",
+            )?;
+            check(
+                reputation.floor.is_finite() && reputation.floor >= 0.0,
+                "keeper_reputation floor must be non-negative",
+            )?;
+        }
+
+        if let Some(staking) = &self.keeper_staking {
+            check(
+                staking.stake_share > 0.0 && staking.stake_share <= 1.0,
+                "keeper_staking stake_share must be within (0, 1]",
+            )?;
+            check(
+                staking.min_stake.is_finite() && staking.min_stake >= 0.0,
+                "keeper_staking min_stake must be non-negative",
+            )?;
+            check((0.0..=1.0).contains(&staking.slash_rate), "keeper_staking slash_rate must be within [0, 1]")?;
+        }
+
+        if let Some(reveal) = &self.commit_reveal {
+            check(reveal.reveal_blocks > 0, "commit_reveal reveal_blocks must be positive")?;
+            check((0.0..=1.0).contains(&reveal.miss_rate), "commit_reveal miss_rate must be within [0, 1]")?;
+        }
+
+        if let Some(auction) = &self.auction_discount {
+            check(
+                auction.dispersion.is_finite() && auction.dispersion >= 0.0,
+                "auction_discount dispersion must be non-negative",
+            )?;
+            check(
+                (0.0..=1.0).contains(&auction.protocol_share),
+                "auction_discount protocol_share must be within [0, 1]",
+            )?;
+        }
+
+        if let Some(cooldown) = &self.liquidation_cooldown {
+            check(
+                cooldown.close_factor > 0.0 && cooldown.close_factor <= 1.0,
+                "liquidation_cooldown close_factor must be within (0, 1]",
+            )?;
+            check(cooldown.cooldown_blocks > 0, "liquidation_cooldown cooldown_blocks must be positive")?;
+        }
+
+        if let Some(grace) = &self.grace_period {
+            check(grace.grace_blocks > 0, "grace_period grace_blocks must be positive")?;
+            check((0.0..=1.0).contains(&grace.top_up_rate), "grace_period top_up_rate must be within [0, 1]")?;
+            check(
+                grace.top_up_ratio.is_finite() && grace.top_up_ratio >= self.min_collateral_ratio,
+                "grace_period top_up_ratio must be at least min_collateral_ratio",
+            )?;
+        }
+
+        if let Some(rotation) = &self.keeper_rotation {
+            check(rotation.set_size > 0, "keeper_rotation set_size must be positive")?;
+            check(rotation.epoch_blocks > 0, "keeper_rotation epoch_blocks must be positive")?;
+            check(
+                (0.0..=1.0).contains(&rotation.offline_rate),
+                "keeper_rotation offline_rate must be within [0, 1]",
+            )?;
+        }
+
+        if let Some(crisis) = &self.liquidity_crisis {
+            check(
+                crisis.impact_multiplier.is_finite() && crisis.impact_multiplier >= 0.0,
+                "liquidity_crisis impact_multiplier must be non-negative",
+            )?;
+        }
+
+        if let Some(warmup) = &self.warmup {
+            check(
+                warmup.volatility.is_finite() && warmup.volatility >= 0.0,
+                "warmup volatility must be non-negative",
+            )?;
+            check(
+                warmup.top_up_ratio.is_finite() && warmup.top_up_ratio > 0.0,
+                "warmup top_up_ratio must be positive",
+            )?;
+            check((0.0..=1.0).contains(&warmup.top_up_rate), "warmup top_up_rate must be within [0, 1]")?;
+        }
+
+        match &self.neighbor {
+            Some(neighbor) => {
+                check(
+                    neighbor.min_collateral_ratio > 1.0,
+                    "neighbor min_collateral_ratio must exceed 100%",
+                )?;
+                check(
+                    neighbor.liquidations_per_block > 0,
+                    "neighbor liquidations_per_block must be positive",
+                )?;
+                check(
+                    neighbor.cdp_distribution.min_collateral > 0.0
+                        && neighbor.cdp_distribution.min_collateral <= neighbor.cdp_distribution.max_collateral,
+                    "neighbor cdp_distribution collateral range must be positive and ordered",
+                )?;
+                check(
+                    neighbor.cdp_distribution.min_ratio > 0.0
+                        && neighbor.cdp_distribution.min_ratio <= neighbor.cdp_distribution.max_ratio,
+                    "neighbor cdp_distribution ratio range must be positive and ordered",
+                )
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Synthetic CDP book; the first `book_share` of it holds the accruing
+    /// token (CDPs are i.i.d., so this is as good as a random draw).
+    fn synthetic_book(&self, eth_price: f64, rng: &mut impl Rng) -> Vec<CDP> {
+        let mut cdps = Vec::new();
+        self.fill_book(eth_price, rng, &mut cdps);
+        cdps
+    }
+
+    /// `synthetic_book` into `cdps`, replacing its contents.
+    fn fill_book(&self, eth_price: f64, rng: &mut impl Rng, cdps: &mut Vec<CDP>) {
+        let accruing = self
+            .accruing_collateral
+            .as_ref()
+            .map_or(0, |token| (token.book_share * self.num_cdps as f64).round() as usize);
+        cdps.clear();
+        cdps.extend((0..self.num_cdps).map(|i| {
+            let cdp = CDP::new(i, eth_price, &self.cdp_distribution, rng);
+            if i < accruing { cdp.accruing() } else { cdp }
+        }));
+    }
+}
+
+/// What a mechanism supports and requires, so runners, reports and the CLI
+/// can enumerate and configure mechanisms without matching on them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct MechanismInfo {
+    pub key: &'static str,         // CLI value, e.g. `keeper-pool`
+    pub variant: &'static str,     // Serialized variant name
+    pub short_name: &'static str,  // Table label
+    pub description: &'static str,
+    pub partial_liquidation: bool, // Honors `liquidation_cooldown` close factors
+    pub commit_reveal: bool,       // Keepers commit before the executor is drawn
+    pub requires_stake: bool,      // Keepers bond capital to take part
+    pub configurable_split: bool,  // Takes a keeper share of the penalty (`--split`)
+}
+
+/// Parameters of Maker's flip auction (`LiquidationMechanism::EnglishAuction`).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FlipAuction {
+    pub bid_blocks: usize,     // A bid wins once this many blocks pass without a higher one (ttl)
+    pub auction_blocks: usize, // Blocks after which an auction ends regardless (tau)
+    pub min_increment: f64,    // Minimum raise over the standing bid, or cut in the lot (beg)
+    pub apathy: f64,           // Chance each keeper ignores an auction in a given block
+}
+
+impl Default for FlipAuction {
+    fn default() -> Self {
+        Self {
+            bid_blocks: 5,
+            auction_blocks: 25,
+            min_increment: 0.03,
+            apathy: 0.0,
+        }
+    }
+}
+
+/// Parameters of a per-block uniform-price batch auction
+/// (`LiquidationMechanism::BatchAuction`).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BatchAuction {
+    pub dispersion: f64,  // Lognormal sigma of bidders' reservation discounts around the penalty
+    pub max_batch: usize, // CDPs one batch settles, in queue order
+}
+
+impl Default for BatchAuction {
+    fn default() -> Self {
+        Self {
+            dispersion: 0.5,
+            max_batch: 100,
+        }
+    }
+}
+
+impl BatchAuction {
+    fn validate(&self) -> Result<()> {
+        if !(self.dispersion.is_finite() && self.dispersion >= 0.0) {
+            return Err(Error::Invalid("batch auction dispersion must be non-negative".to_string()));
+        }
+        if self.max_batch == 0 {
+            return Err(Error::Invalid("batch auction max_batch must be positive".to_string()));
+        }
+        Ok(())
+    }
+}
+
+impl FlipAuction {
+    fn validate(&self) -> Result<()> {
+        if self.bid_blocks == 0 || self.auction_blocks < self.bid_blocks {
+            return Err(Error::Invalid("english auction needs 0 < bid_blocks <= auction_blocks".to_string()));
+        }
+        if !(self.min_increment.is_finite() && self.min_increment > 0.0) {
+            return Err(Error::Invalid("english auction min_increment must be positive".to_string()));
+        }
+        if !(0.0..1.0).contains(&self.apathy) {
+            return Err(Error::Invalid("english auction apathy must be within [0, 1)".to_string()));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(from = "MechanismRepr")]
+pub enum LiquidationMechanism {
+    Traditional,               // Winner-takes-all, gas priority
+    KeeperPool { split: f64 }, // Fair: keeper share of each penalty, commit-reveal
+    FixedSpread { bonus: f64, close_factor: f64 }, // Aave/Compound: fixed bonus per partial repay, gas priority
+    EnglishAuction(FlipAuction), // Maker flip: multi-block ascending bids for collateral lots
+    SoftLiquidation(Llamma),     // crvUSD LLAMMA: collateral converted through price bands by arbitrage
+    BatchAuction(BatchAuction),  // The block's queue cleared in one uniform-price auction
+}
+
+impl LiquidationMechanism {
+    pub fn all() -> Vec<Self> {
+        vec![
+            Self::Traditional,
+            Self::keeper_pool(),
+            Self::fixed_spread(),
+            Self::english_auction(),
+            Self::soft_liquidation(),
+            Self::batch_auction(),
+        ]
+    }
+
+    /// The keeper pool at Fair's deployed 70/30 split.
+    pub const fn keeper_pool() -> Self {
+        Self::KeeperPool { split: DEFAULT_POOL_SPLIT }
+    }
+
+    /// Fixed spread at Aave's 5% bonus and 50% close factor.
+    pub const fn fixed_spread() -> Self {
+        Self::FixedSpread { bonus: DEFAULT_SPREAD_BONUS, close_factor: DEFAULT_CLOSE_FACTOR }
+    }
+
+    /// Maker's flip auction at its default parameters, without apathy.
+    pub fn english_auction() -> Self {
+        Self::EnglishAuction(FlipAuction::default())
+    }
+
+    /// crvUSD's LLAMMA at 10 bands of 1%.
+    pub fn soft_liquidation() -> Self {
+        Self::SoftLiquidation(Llamma::default())
+    }
+
+    /// A uniform-price batch auction at its default parameters.
+    pub fn batch_auction() -> Self {
+        Self::BatchAuction(BatchAuction::default())
+    }
+
+    pub fn is_keeper_pool(&self) -> bool {
+        matches!(self, Self::KeeperPool { .. })
+    }
+
+    pub fn info(&self) -> MechanismInfo {
+        match self {
+            Self::Traditional => MechanismInfo {
+                key: "traditional",
+                variant: "Traditional",
+                short_name: "Traditional",
+                description: "Winner-takes-all gas auction; losing races revert but still pay gas",
+                partial_liquidation: true,
+                commit_reveal: false,
+                requires_stake: false,
+                configurable_split: false,
+            },
+            Self::KeeperPool { .. } => MechanismInfo {
+                key: "keeper-pool",
+                variant: "KeeperPool",
+                short_name: "Fair",
+                description: "Fair's commit-reveal keeper pool; the penalty is split among committed keepers",
+                partial_liquidation: true,
+                commit_reveal: true,
+                requires_stake: false,
+                configurable_split: true,
+            },
+            Self::FixedSpread { .. } => MechanismInfo {
+                key: "fixed-spread",
+                variant: "FixedSpread",
+                short_name: "Spread",
+                description: "Aave/Compound fixed-bonus repay of part of the debt; keepers race on gas priority",
+                partial_liquidation: true,
+                commit_reveal: false,
+                requires_stake: false,
+                configurable_split: false,
+            },
+            Self::EnglishAuction(_) => MechanismInfo {
+                key: "english-auction",
+                variant: "EnglishAuction",
+                short_name: "Flip",
+                description: "Maker's flip: keepers bid DAI for the collateral over several blocks, then on the lot",
+                partial_liquidation: false,
+                commit_reveal: false,
+                requires_stake: false,
+                configurable_split: false,
+            },
+            Self::SoftLiquidation(_) => MechanismInfo {
+                key: "soft-liquidation",
+                variant: "SoftLiquidation",
+                short_name: "LLAMMA",
+                description: "crvUSD's LLAMMA: arbitrageurs convert collateral band by band as the price falls, and back",
+                partial_liquidation: false,
+                commit_reveal: false,
+                requires_stake: false,
+                configurable_split: false,
+            },
+            Self::BatchAuction(_) => MechanismInfo {
+                key: "batch-auction",
+                variant: "BatchAuction",
+                short_name: "Batch",
+                description: "Each block's queue settled in one uniform-price auction; bidders split it pro rata, nobody races",
+                partial_liquidation: false,
+                commit_reveal: false,
+                requires_stake: false,
+                configurable_split: false,
+            },
+        }
+    }
+
+    /// The mechanism `info().key` names, at its default parameters.
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::all().into_iter().find(|m| m.info().key == key)
+    }
+
+    /// With keeper share `split`, for mechanisms that take one.
+    pub fn with_split(self, split: f64) -> Self {
+        match self {
+            Self::KeeperPool { .. } => Self::KeeperPool { split },
+            other => other,
+        }
+    }
+
+    /// Share of each liquidation penalty paid out to keepers.
+    pub fn keeper_share(&self) -> f64 {
+        match self {
+            Self::Traditional
+            | Self::FixedSpread { .. }
+            | Self::EnglishAuction(_)
+            | Self::SoftLiquidation(_)
+            | Self::BatchAuction(_) => 1.0,
+            Self::KeeperPool { split } => *split,
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            Self::Traditional => "Traditional (Winner-Takes-All)".to_string(),
+            Self::KeeperPool { split } => format!(
+                "Fair (Keeper Pool {:.0}/{:.0})",
+                split * 100.0,
+                (1.0 - split) * 100.0
+            ),
+            Self::FixedSpread { bonus, close_factor } => format!(
+                "Fixed Spread ({:.0}% bonus, {:.0}% close)",
+                bonus * 100.0,
+                close_factor * 100.0
+            ),
+            Self::EnglishAuction(flip) if flip.apathy > 0.0 => {
+                format!("English Auction (Maker flip, {:.0}% apathy)", flip.apathy * 100.0)
+            }
+            Self::EnglishAuction(_) => "English Auction (Maker flip)".to_string(),
+            Self::SoftLiquidation(llamma) => format!("Soft Liquidation (LLAMMA, {} bands)", llamma.bands),
+            Self::BatchAuction(_) => "Batch Auction (uniform price)".to_string(),
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            Self::KeeperPool { split } if !(0.0..=1.0).contains(split) => {
+                Err(Error::Invalid("keeper pool split must be within [0, 1]".to_string()))
+            }
+            Self::FixedSpread { bonus, .. } if !(bonus.is_finite() && *bonus >= 0.0) => {
+                Err(Error::Invalid("fixed spread bonus must be non-negative".to_string()))
+            }
+            Self::FixedSpread { close_factor, .. } if !(*close_factor > 0.0 && *close_factor <= 1.0) => {
+                Err(Error::Invalid("fixed spread close factor must be within (0, 1]".to_string()))
+            }
+            Self::EnglishAuction(flip) => flip.validate(),
+            Self::SoftLiquidation(llamma) => llamma.validate(),
+            Self::BatchAuction(batch) => batch.validate(),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Also accepts the bare `"KeeperPool"` of older configs and result files,
+/// and a bare `"FixedSpread"`, `"EnglishAuction"`, `"SoftLiquidation"` or
+/// `"BatchAuction"` at its defaults.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum MechanismRepr {
+    Bare(BareMechanism),
+    Split(SplitMechanism),
+}
+
+#[derive(Deserialize)]
+enum BareMechanism {
+    Traditional,
+    KeeperPool,
+    FixedSpread,
+    EnglishAuction,
+    SoftLiquidation,
+    BatchAuction,
+}
+
+#[derive(Deserialize)]
+enum SplitMechanism {
+    KeeperPool { split: f64 },
+    FixedSpread {
+        #[serde(default = "default_spread_bonus")]
+        bonus: f64,
+        #[serde(default = "default_close_factor")]
+        close_factor: f64,
+    },
+    EnglishAuction(FlipAuction),
+    SoftLiquidation(Llamma),
+    BatchAuction(BatchAuction),
+}
+
+fn default_spread_bonus() -> f64 {
+    DEFAULT_SPREAD_BONUS
+}
+
+fn default_close_factor() -> f64 {
+    DEFAULT_CLOSE_FACTOR
+}
+
+impl From<MechanismRepr> for LiquidationMechanism {
+    fn from(repr: MechanismRepr) -> Self {
+        match repr {
+            MechanismRepr::Bare(BareMechanism::Traditional) => Self::Traditional,
+            MechanismRepr::Bare(BareMechanism::KeeperPool) => Self::keeper_pool(),
+            MechanismRepr::Bare(BareMechanism::FixedSpread) => Self::fixed_spread(),
+            MechanismRepr::Split(SplitMechanism::KeeperPool { split }) => Self::KeeperPool { split },
+            MechanismRepr::Bare(BareMechanism::EnglishAuction) => Self::english_auction(),
+            MechanismRepr::Split(SplitMechanism::FixedSpread { bonus, close_factor }) => {
+                Self::FixedSpread { bonus, close_factor }
+            }
+            MechanismRepr::Split(SplitMechanism::EnglishAuction(flip)) => Self::EnglishAuction(flip),
+            MechanismRepr::Bare(BareMechanism::SoftLiquidation) => Self::soft_liquidation(),
+            MechanismRepr::Split(SplitMechanism::SoftLiquidation(llamma)) => Self::SoftLiquidation(llamma),
+            MechanismRepr::Bare(BareMechanism::BatchAuction) => Self::batch_auction(),
+            MechanismRepr::Split(SplitMechanism::BatchAuction(batch)) => Self::BatchAuction(batch),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum PriceScenario {
+    GradualDecline,    // 2% per block for 10 blocks
+    FlashCrash,        // 30% instant drop
+    VolatileCrash,     // Jump-diffusion with high volatility
+    BlackSwan,         // 50% crash + continued decline
+}
+
+impl PriceScenario {
+    pub fn all() -> Vec<Self> {
+        vec![
+            Self::GradualDecline,
+            Self::FlashCrash,
+            Self::VolatileCrash,
+            Self::BlackSwan,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::GradualDecline => "Gradual Decline (-20% over 10 blocks)",
+            Self::FlashCrash => "Flash Crash (-30% instant)",
+            Self::VolatileCrash => "Volatile Crash (jump-diffusion)",
+            Self::BlackSwan => "Black Swan (-50% + continued decline)",
+        }
+    }
+}
+
+impl CDP {
+    pub(crate) fn new(id: usize, eth_price: f64, dist: &CdpDistribution, rng: &mut impl Rng) -> Self {
+        let collateral = dist.min_collateral + rng.gen::<f64>() * (dist.max_collateral - dist.min_collateral);
+        let ratio = dist.min_ratio + rng.gen::<f64>() * (dist.max_ratio - dist.min_ratio);
+        let debt = (collateral * eth_price) / ratio;
+        
+        Self::from_position(id, collateral, debt)
+    }
+
+    pub(crate) fn accrue(&mut self, growth: f64, accounting: CollateralAccounting) {
+        if !self.accrues {
+            return;
+        }
+        match accounting {
+            CollateralAccounting::Rebasing => self.collateral *= growth,
+            CollateralAccounting::ExchangeRate => self.token_rate *= growth,
+        }
+    }
+
+    /// Adds collateral until the CDP is back at `ratio`; no-op above it.
+    fn top_up(&mut self, ratio: f64, eth_price: f64) {
+        let shortfall = ratio * self.debt - self.collateral_value(eth_price);
+        if shortfall > 0.0 {
+            self.collateral += shortfall / (eth_price * self.token_rate);
+        }
+    }
+}
+
+/// Whether the keepers' share of `profit` clears their threshold.
+pub(crate) fn keepers_will_act(profit: f64, mechanism: LiquidationMechanism, keepers: &KeeperConfig) -> bool {
+    profit * mechanism.keeper_share() > keepers.threshold(mechanism)
+}
+
+const SYBIL_EXECUTIONS_PER_BLOCK: usize = 1;
+
+impl Keeper {
+    pub(crate) fn willing_to_liquidate(
+        &self,
+        profit: f64,
+        mechanism: LiquidationMechanism,
+        keepers: &KeeperConfig,
+    ) -> bool {
+        match self.behavior {
+            KeeperBehavior::Honest | KeeperBehavior::Sybil => keepers_will_act(profit, mechanism, keepers),
+            KeeperBehavior::Cartel { min_profit } => {
+                keepers_will_act(profit, mechanism, keepers) && profit > min_profit
+            }
+            // A failed race costs nothing to the rest of a winner-takes-all market
+            KeeperBehavior::Griefer => mechanism.is_keeper_pool() && profit > 0.0,
+        }
+    }
+
+    pub(crate) fn can_execute(&self, sybil_executions: usize) -> bool {
+        match self.behavior {
+            KeeperBehavior::Griefer => false,
+            KeeperBehavior::Sybil => sybil_executions < SYBIL_EXECUTIONS_PER_BLOCK,
+            _ => true,
+        }
+    }
+}
+
+/// Per-block state recorded when event logging is enabled.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockEvent {
+    pub block: usize,
+    pub price_start: f64,
+    pub price_after_shock: f64,
+    pub price_end: f64,        // After liquidation price impact
+    pub liquidatable: usize,
+    pub liquidated: usize,
+    pub eth_sold: f64,
+    pub bad_debt: f64,
+    #[serde(default)]
+    pub penalties: f64,        // Liquidation penalty paid out this block
+    #[serde(default)]
+    pub protocol_fees: f64,    // Part of `penalties` retained by the protocol
+    #[serde(default)]
+    pub max_payout_share: f64, // Largest fraction of one penalty paid to a single keeper
+    #[serde(default)]
+    pub neighbor_eth_sold: f64, // Collateral sold by a neighboring protocol's liquidations
+    #[serde(default)]
+    pub token_price: Option<f64>, // Reward token price relative to par, when emitted
+    #[serde(default)]
+    pub insurance_fund: Option<f64>, // Insurance fund balance, when configured
+}
+
+/// One liquidation, recorded when liquidation logging is enabled.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LiquidationEvent {
+    pub block: usize,
+    pub cdp_id: usize,
+    pub liquidator: Option<usize>, // Executing keeper's id; None for an external strategy
+    pub profit: f64,               // Penalty earned on the liquidation
+    pub eth_sold: f64,             // Collateral seized and sold
+    pub price: f64,                // Oracle price the liquidation cleared at
+}
+
+impl BlockEvent {
+    /// Liquidatable CDPs left waiting. Soft liquidation's bands can close
+    /// CDPs above the line, so its liquidations can outnumber the queue.
+    pub fn queue_after(&self) -> usize {
+        self.liquidatable.saturating_sub(self.liquidated)
+    }
+}
+
+/// Live view of a running simulation, handed to observers after each block.
+#[derive(Debug, Clone)]
+pub struct LiveState {
+    pub event: BlockEvent,
+    pub keeper_profits: Vec<f64>,
+    pub keeper_capital: Vec<f64>, // `Keeper::net_worth` per keeper
+    pub liquidatable_cdps: Vec<usize>, // CDP indices in the book, riskiest first
+    pub liquidated_cdps: Vec<usize>,   // The ones liquidated this block
+    pub collateral_ratios: Vec<f64>,   // Every open CDP's, at the end-of-block price
+    pub total_liquidations: usize,
+    pub protocol_revenue: f64,  // Penalty share retained by the protocol
+}
+
+#[derive(Default)]
+struct RunHooks<'a> {
+    observer: Option<&'a mut dyn FnMut(&LiveState)>,
+    strategy: Option<&'a mut dyn KeeperStrategy>,
+}
+
+/// Totals of one liquidation round; the CDPs themselves are left in
+/// `RoundBuffers::liquidatable` and `liquidated`.
+struct RoundOutcome {
+    liquidatable: usize,
+    liquidated: usize,
+    conversions: usize, // Soft liquidation's band sales, which are not liquidations
+    eth_sold: f64,
+    penalties: f64,
+    protocol_fees: f64,
+    max_payout_share: f64,
+    neighbor_eth_sold: f64,
+}
+
+/// Balance and open claims of `config.treasury_drip`'s treasury.
+#[derive(Debug)]
+struct Treasury {
+    drip: TreasuryDrip,
+    balance: f64,
+    claims: Vec<f64>,       // Per keeper: keeper share claimed this epoch
+    inflow: f64,            // Claimed this epoch
+    payout: Option<f64>,    // Smoothed drip per epoch, from the first epoch with inflow on
+    rate: f64,              // Paid per dollar claimed in the last epoch that paid out
+    paid: f64,
+    min_balance: f64,
+    short_epochs: usize,
+}
+
+impl Treasury {
+    fn new(drip: TreasuryDrip, keepers: usize) -> Self {
+        Self {
+            drip,
+            balance: drip.endowment,
+            claims: vec![0.0; keepers],
+            inflow: 0.0,
+            payout: None,
+            rate: 1.0,
+            paid: 0.0,
+            min_balance: drip.endowment,
+            short_epochs: 0,
+        }
+    }
+
+    fn claim(&mut self, keeper: usize, amount: f64) {
+        self.claims[keeper] += amount;
+        self.inflow += amount;
+        self.balance += amount;
+    }
+
+    /// Updates the drip and pays it to the epoch's claimants pro rata;
+    /// nothing is paid in an epoch without claims.
+    fn close_epoch(&mut self, keepers: &mut [Keeper]) {
+        let inflow = mem::take(&mut self.inflow);
+        let s = self.drip.smoothing;
+        let drip = *self.payout.insert(self.payout.map_or(inflow, |drip| s * inflow + (1.0 - s) * drip));
+        if inflow > 0.0 {
+            if drip > self.balance {
+                self.short_epochs += 1;
+            }
+            let paid = drip.min(self.balance);
+            for (keeper, claim) in keepers.iter_mut().zip(&mut self.claims) {
+                keeper.total_profit += paid * mem::take(claim) / inflow;
+            }
+            self.balance -= paid;
+            self.paid += paid;
+            self.rate = paid / inflow;
+        }
+        self.min_balance = self.min_balance.min(self.balance);
+    }
+
+    fn outcome(&self) -> TreasuryOutcome {
+        TreasuryOutcome {
+            paid: self.paid,
+            balance: self.balance,
+            min_balance: self.min_balance,
+            short_epochs: self.short_epochs,
+            final_rate: self.rate,
+        }
+    }
+}
+
+/// Balance and watermarks of `config.insurance_fund`.
+#[derive(Debug)]
+struct Insurance {
+    fund: InsuranceFund,
+    balance: f64,
+    inflow: f64,
+    min_headroom: f64, // Lowest balance net of the book's bad debt
+    exhausted_at: Option<usize>,
+}
+
+impl Insurance {
+    fn new(fund: InsuranceFund) -> Self {
+        Self {
+            fund,
+            balance: fund.endowment,
+            inflow: 0.0,
+            min_headroom: fund.endowment,
+            exhausted_at: None,
+        }
+    }
+
+    /// Pays the fund its share of `protocol_fees` and checks it against the
+    /// book's `bad_debt` at the end of `block`.
+    fn close_block(&mut self, block: usize, protocol_fees: f64, bad_debt: f64) {
+        let inflow = protocol_fees * self.fund.share;
+        self.balance += inflow;
+        self.inflow += inflow;
+        self.min_headroom = self.min_headroom.min(self.balance - bad_debt);
+        if bad_debt > self.balance && self.exhausted_at.is_none() {
+            self.exhausted_at = Some(block);
+        }
+    }
+
+    fn outcome(&self, gross_bad_debt: f64) -> InsuranceOutcome {
+        let absorbed = gross_bad_debt.min(self.balance);
+        InsuranceOutcome {
+            inflow: self.inflow,
+            absorbed,
+            balance: self.balance - absorbed,
+            gross_bad_debt,
+            min_headroom: self.min_headroom,
+            exhausted_at: self.exhausted_at,
+        }
+    }
+}
+
+/// An open English auction on a bitten CDP, which stays on the book until
+/// the auction settles.
+#[derive(Debug)]
+struct Flip {
+    cdp: usize,
+    lot: f64,               // ETH the winner receives
+    tab: f64,               // DAI to raise: the debt plus the penalty
+    debt: f64,
+    bid: f64,               // Standing bid, DAI
+    winner: Option<usize>,  // Standing high bidder
+    bids: usize,            // Blocks in which someone bid
+    kicked: usize,          // Block the auction last (re)started
+    last_bid: usize,
+}
+
+/// What a block of English auctions adds to its round.
+#[derive(Debug, Default)]
+struct FlipRound {
+    eth_sold: f64,
+    penalties: f64,
+    protocol_fees: f64,
+    covered: f64,
+    attempts: usize,
+    joined: usize,
+}
+
+/// A keeper's track record under `config.keeper_reputation`.
+#[derive(Debug, Clone, Copy)]
+struct Standing {
+    reliability: f64, // Moving average of executions landed when counted on
+    uptime: f64,      // Moving average of pool rounds joined
+}
+
+impl Default for Standing {
+    fn default() -> Self {
+        Self { reliability: 1.0, uptime: 1.0 }
+    }
+}
+
+impl Standing {
+    fn score(&self) -> f64 {
+        self.reliability * self.uptime
+    }
+}
+
+/// What a block's batch auction adds to its round.
+#[derive(Debug, Default)]
+struct BatchRound {
+    eth_sold: f64,
+    penalties: f64,
+    covered: f64,
+    max_payout: f64, // Largest winner's take
+    attempts: usize,
+    joined: usize,
+}
+
+/// Keepers joining each attempted liquidation, averaged per block.
+#[derive(Debug, Default)]
+struct ParticipationTally {
+    attempts: usize,
+    joined: usize,
+    blocks: usize,
+    sum: f64,    // Of per-block means
+    sum_sq: f64,
+}
+
+impl ParticipationTally {
+    fn add_block(&mut self, attempts: usize, joined: usize) {
+        if attempts == 0 {
+            return;
+        }
+        let mean = joined as f64 / attempts as f64;
+        self.attempts += attempts;
+        self.joined += joined;
+        self.blocks += 1;
+        self.sum += mean;
+        self.sum_sq += mean * mean;
+    }
+
+    fn mean(&self) -> f64 {
+        self.joined as f64 / self.attempts.max(1) as f64
+    }
+
+    /// Coefficient of variation of the per-block means; 0 without
+    /// participation.
+    fn cv(&self) -> f64 {
+        let blocks = self.blocks.max(1) as f64;
+        let mean = self.sum / blocks;
+        if mean > 0.0 {
+            (self.sum_sq / blocks - mean * mean).max(0.0).sqrt() / mean
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Working lists of a liquidation round, kept across rounds and runs so
+/// the block loop doesn't allocate once they have grown.
+#[derive(Debug, Default)]
+struct RoundBuffers {
+    liquidatable: Vec<usize>,     // CDP indices, riskiest first
+    liquidated: Vec<usize>,
+    sorted: Vec<usize>,           // `liquidatable` in book order
+    selectable: Vec<usize>,
+    participants: Vec<usize>,     // Keeper indices taking part in a liquidation
+    candidates: Vec<usize>,       // Pool executor candidates, as positions in `participants`
+    gas: Vec<f64>,                // Per participant
+    inventory: Vec<(usize, f64)>, // (executor, ETH seized) held until the block closes
+    keeper_order: Vec<usize>,     // Keepers fastest first, for `shared_liquidity`
+    neighbor: Vec<usize>,         // The neighbor's liquidatable CDPs
+}
+
+/// Storage a dropped `CascadeSimulation` leaves for the next one on its
+/// thread: campaign runs reuse one book, keeper set and set of buffers per
+/// worker instead of allocating their own.
+#[derive(Debug, Default)]
+struct RunArena {
+    cdps: Vec<CDP>,
+    keepers: Vec<Keeper>,
+    index: LiquidationIndex,
+    queued: Vec<usize>,
+    retry_at: Vec<usize>,
+    liquidatable_since: Vec<Option<usize>>,
+    on_duty: Vec<bool>,
+    price_history: Vec<f64>,
+    liquidations_per_block: Vec<usize>,
+    round: RoundBuffers,
+}
+
+thread_local! {
+    static ARENA: Cell<RunArena> = Cell::new(RunArena::default());
+}
+
+impl RunArena {
+    /// This thread's arena, or an empty one if a live run holds it.
+    fn take() -> Self {
+        ARENA.with(Cell::take)
+    }
+
+    fn recycle(self) {
+        // Thread teardown may drop a run after the slot is gone
+        let _ = ARENA.try_with(|arena| arena.set(self));
+    }
+}
+
+/// A single cascade run. Build one with `CascadeSimulationBuilder` to
+/// supply a bespoke CDP book, keeper set, or price path.
+pub struct CascadeSimulation {
+    config: SimulationConfig,
+    cdps: Vec<CDP>,
+    index: LiquidationIndex,      // `cdps` by cover; update after changing a CDP
+    queued: Vec<usize>,           // CDPs liquidatable last round
+    keepers: Vec<Keeper>,
+    eth_price: f64,
+    initial_price: f64,
+    mechanism: LiquidationMechanism,
+    scenario: PriceScenario,
+    shocks: Box<dyn PricePath>,   // The scenario unless the builder supplied a path
+    shock_log: Option<Vec<f64>>,  // Raw shocks drawn, when recording
+    manipulation_eth: f64,        // Spot ETH dumped per block by a price manipulator
+    manipulation_blocks: usize,
+    neighbor_cdps: Vec<CDP>,      // Book of `config.neighbor`, if any
+    
+    block: usize,
+    cascade_depth: usize,
+    current_wave_liquidations: usize,
+    total_liquidations: usize,
+    total_bad_debt: f64,
+    protocol_revenue: f64,
+    neighbor_eth_sold: f64,
+    capital_diverted: f64,        // Sum over blocks of the keeper capital share committed to rivals
+    initial_debt: f64,
+    token_price: f64,             // Reward token price relative to par
+    tokens_emitted: f64,
+    incentives_paid: f64,
+    fee_burn: f64,                // Gas and priority fees spent by the simulated keepers
+    failure_rng: Option<RunRng>,  // Own stream for `config.execution_failure`
+    retry_at: Vec<usize>,         // Per CDP: first block it may be selected again after a failure
+    execution_failures: usize,
+    liquidatable_since: Vec<Option<usize>>, // Per CDP: block it last became liquidatable
+    rotation_rng: Option<RunRng>, // Own stream for `config.keeper_rotation`
+    auction_rng: Option<RunRng>,  // Own stream for `config.auction_discount`
+    on_duty: Vec<bool>,           // Per keeper: in this epoch's active set and online
+    rotation_outages: usize,
+    liquidation_delay: f64,       // Sum over liquidations of blocks spent liquidatable
+    exposure: f64,                // Sum over blocks of liquidatable CDPs' shortfalls
+    missed_exposure: f64,         // The part of `exposure` on CDPs not liquidated that block
+    ledger: RunLedger,            // Flows tallied for the conservation audit
+    backstop_blocks: usize,       // Blocks run under `config.mechanism_switch`'s backstop
+    backstop_liquidations: usize,
+    governance_detected_at: Option<usize>, // Block `config.governance_timelock` flagged the configuration
+    detection_bad_debt: f64,      // Bad debt outstanding at detection
+    governance_window_bad_debt: Option<f64>, // Accrued from detection to execution, once executed
+    treasury: Option<Treasury>,   // Of `config.treasury_drip`
+    insurance: Option<Insurance>, // Of `config.insurance_fund`
+    stakes: Vec<f64>,             // Per keeper under `config.keeper_staking`, else empty
+    staking: StakingOutcome,
+    standings: Vec<Standing>,     // Per keeper under `config.keeper_reputation`, else empty
+    reputation_rng: Option<RunRng>, // Own stream for reputation-weighted executor draws
+    reputation: ReputationOutcome,
+    auctions: Vec<Flip>,          // Open English auctions
+    flip_rng: Option<RunRng>,     // Own stream for English auction apathy
+    commits: Vec<Vec<usize>>,     // Per CDP under `config.commit_reveal`: members awaiting their reveal
+    reveal_rng: Option<RunRng>,   // Own stream for missed reveals
+    reveals: CommitRevealOutcome,
+    grace_rng: Option<RunRng>,    // Own stream for top-ups in a grace period
+    grace: GraceOutcome,
+    flips: FlipOutcome,
+    flip_bad_debt: f64,           // Debt settled English auctions left uncovered
+    soft: SoftBook,               // Bands per CDP under soft liquidation
+    batch_rng: Option<RunRng>,    // Own stream for batch auction reservations
+    batches: BatchOutcome,
+    participation: ParticipationTally,
+    borrower_loss: f64,           // Collateral value seized beyond the debt repaid
+    shock_decline: f64,           // Log price decline from exogenous moves
+    impact_decline: f64,          // Log price decline from ETH sold into the market
+    price_history: Vec<f64>,
+    liquidations_per_block: Vec<usize>,
+    round: RoundBuffers,
+    events: Option<Vec<BlockEvent>>,
+    liquidation_log: Option<Vec<LiquidationEvent>>,
+    external: KeeperLedger,
+}
+
+impl Drop for CascadeSimulation {
+    fn drop(&mut self) {
+        RunArena {
+            cdps: mem::take(&mut self.cdps),
+            keepers: mem::take(&mut self.keepers),
+            index: mem::take(&mut self.index),
+            queued: mem::take(&mut self.queued),
+            retry_at: mem::take(&mut self.retry_at),
+            liquidatable_since: mem::take(&mut self.liquidatable_since),
+            on_duty: mem::take(&mut self.on_duty),
+            price_history: mem::take(&mut self.price_history),
+            liquidations_per_block: mem::take(&mut self.liquidations_per_block),
+            round: mem::take(&mut self.round),
+        }
+        .recycle();
+    }
+}
+
+impl CascadeSimulation {
+    pub(crate) fn new(
+        mechanism: LiquidationMechanism,
+        scenario: PriceScenario,
+        config: &SimulationConfig,
+        rng: &mut impl Rng,
+    ) -> Self {
+        let mut arena = RunArena::take();
+        config.fill_book(config.initial_eth_price, rng, &mut arena.cdps);
+        Self::from_arena(arena, config.initial_eth_price, mechanism, scenario, config, rng)
+    }
+
+    pub(crate) fn with_cdps(
+        cdps: &[CDP],
+        eth_price: f64,
+        mechanism: LiquidationMechanism,
+        scenario: PriceScenario,
+        config: &SimulationConfig,
+        rng: &mut impl Rng,
+    ) -> Self {
+        let mut arena = RunArena::take();
+        arena.cdps.clear();
+        arena.cdps.extend_from_slice(cdps);
+        Self::from_arena(arena, eth_price, mechanism, scenario, config, rng)
+    }
+
+    /// A run on the book in `arena.cdps`, drawing its keepers.
+    fn from_arena(
+        mut arena: RunArena,
+        eth_price: f64,
+        mechanism: LiquidationMechanism,
+        scenario: PriceScenario,
+        config: &SimulationConfig,
+        rng: &mut impl Rng,
+    ) -> Self {
+        config.keepers.fill_population(rng, &mut arena.keepers);
+        let mut sim = Self::assemble(arena, eth_price, mechanism, scenario, config);
+        if let Some(neighbor) = &config.neighbor {
+            sim.neighbor_cdps = neighbor.book(eth_price, rng);
+        }
+        sim.seed_streams(rng);
+        sim
+    }
+
+    /// Splits off the failure, rotation, auction, apathy, reveal, top-up,
+    /// batch and reputation streams last, so runs without
+    /// `execution_failure`, `keeper_rotation`, `auction_discount`, English
+    /// auction apathy, `commit_reveal`, `grace_period`, a batch auction or
+    /// `keeper_reputation` draw exactly as before.
+    fn seed_streams(&mut self, rng: &mut impl Rng) {
+        if self.config.execution_failure.is_some() {
+            self.failure_rng = Some(self.config.run_rng(rng.gen()));
+        }
+        if self.config.keeper_rotation.is_some() {
+            self.rotation_rng = Some(self.config.run_rng(rng.gen()));
+        }
+        if self.config.auction_discount.is_some() {
+            self.auction_rng = Some(self.config.run_rng(rng.gen()));
+        }
+        if matches!(self.mechanism, LiquidationMechanism::EnglishAuction(flip) if flip.apathy > 0.0) {
+            self.flip_rng = Some(self.config.run_rng(rng.gen()));
+        }
+        if self.config.commit_reveal.is_some() {
+            self.reveal_rng = Some(self.config.run_rng(rng.gen()));
+        }
+        if self.config.grace_period.is_some() {
+            self.grace_rng = Some(self.config.run_rng(rng.gen()));
+        }
+        if let LiquidationMechanism::BatchAuction(_) = self.mechanism {
+            self.batch_rng = Some(self.config.run_rng(rng.gen()));
+        }
+        if self.config.keeper_reputation.is_some() {
+            self.reputation_rng = Some(self.config.run_rng(rng.gen()));
+        }
+    }
+
+    /// A run on `arena`'s book and keepers, resetting its other storage.
+    fn assemble(
+        arena: RunArena,
+        eth_price: f64,
+        mechanism: LiquidationMechanism,
+        scenario: PriceScenario,
+        config: &SimulationConfig,
+    ) -> Self {
+        let RunArena {
+            cdps,
+            keepers,
+            mut index,
+            mut queued,
+            mut retry_at,
+            mut liquidatable_since,
+            mut on_duty,
+            mut price_history,
+            mut liquidations_per_block,
+            round,
+        } = arena;
+        index.rebuild(&cdps);
+        queued.clear();
+        retry_at.clear();
+        retry_at.resize(cdps.len(), 0);
+        liquidatable_since.clear();
+        liquidatable_since.resize(cdps.len(), None);
+        on_duty.clear();
+        price_history.clear();
+        price_history.push(eth_price);
+        liquidations_per_block.clear();
+        let treasury = config.treasury_drip.map(|drip| Treasury::new(drip, keepers.len()));
+        let insurance = config.insurance_fund.map(Insurance::new);
+        let commits = config.commit_reveal.map_or_else(Vec::new, |_| vec![Vec::new(); cdps.len()]);
+        let stakes: Vec<f64> = match config.keeper_staking {
+            Some(staking) => keepers.iter().map(|k| k.capital * staking.stake_share).collect(),
+            None => Vec::new(),
+        };
+        let staking = StakingOutcome { bonded: stakes.iter().sum(), ..Default::default() };
+        let standings = config.keeper_reputation.map_or_else(Vec::new, |_| vec![Standing::default(); keepers.len()]);
+        Self {
+            config: config.clone(),
+            index,
+            queued,
+            cdps,
+            keepers,
+            eth_price,
+            initial_price: eth_price,
+            mechanism,
+            scenario,
+            shocks: Box::new(scenario),
+            shock_log: None,
+            manipulation_eth: 0.0,
+            manipulation_blocks: 0,
+            neighbor_cdps: Vec::new(),
+            block: 0,
+            cascade_depth: 0,
+            current_wave_liquidations: 0,
+            total_liquidations: 0,
+            total_bad_debt: 0.0,
+            protocol_revenue: 0.0,
+            neighbor_eth_sold: 0.0,
+            capital_diverted: 0.0,
+            initial_debt: 0.0,
+            token_price: 1.0,
+            tokens_emitted: 0.0,
+            incentives_paid: 0.0,
+            fee_burn: 0.0,
+            failure_rng: None,
+            retry_at,
+            execution_failures: 0,
+            liquidatable_since,
+            rotation_rng: None,
+            auction_rng: None,
+            on_duty,
+            rotation_outages: 0,
+            liquidation_delay: 0.0,
+            exposure: 0.0,
+            missed_exposure: 0.0,
+            ledger: RunLedger::default(),
+            backstop_blocks: 0,
+            backstop_liquidations: 0,
+            governance_detected_at: None,
+            detection_bad_debt: 0.0,
+            governance_window_bad_debt: None,
+            treasury,
+            insurance,
+            stakes,
+            standings,
+            reputation_rng: None,
+            reputation: ReputationOutcome::default(),
+            staking,
+            auctions: Vec::new(),
+            flip_rng: None,
+            commits,
+            reveal_rng: None,
+            reveals: CommitRevealOutcome::default(),
+            grace_rng: None,
+            grace: GraceOutcome::default(),
+            flips: FlipOutcome::default(),
+            flip_bad_debt: 0.0,
+            soft: SoftBook::default(),
+            batch_rng: None,
+            batches: BatchOutcome::default(),
+            participation: ParticipationTally::default(),
+            borrower_loss: 0.0,
+            shock_decline: 0.0,
+            impact_decline: 0.0,
+            price_history,
+            liquidations_per_block,
+            round,
+            events: None,
+            liquidation_log: None,
+            external: KeeperLedger::default(),
+        }
+    }
+
+    /// Runs `config.warmup`'s calm blocks, then restarts the run from the
+    /// evolved book, keepers and price. Closed CDPs leave the book and
+    /// keeper tallies reset, so every metric covers the stress alone. The
+    /// manipulator, incentive program, liquidity crisis, governance
+    /// timelock, treasury and insurance fund belong to the stress period
+    /// and sit the warm-up out.
+    fn warm_up(&mut self, rng: &mut impl Rng) {
+        let Some(warmup) = self.config.warmup else {
+            return;
+        };
+        let calm = LogNormal::new(-warmup.volatility.powi(2) / 2.0, warmup.volatility).expect("validated volatility");
+        let accrual = self
+            .config
+            .accruing_collateral
+            .as_ref()
+            .map(|token| (token.growth(1.0 / BLOCKS_PER_YEAR), token.accounting));
+        let manipulation_blocks = std::mem::take(&mut self.manipulation_blocks);
+        let program = self.config.incentive_program.take();
+        let crisis = self.config.liquidity_crisis.take();
+        let timelock = self.config.governance_timelock.take();
+        self.treasury = None; // `assemble` opens a fresh one for the stress
+        self.insurance = None;
+        let (events, liquidation_log) = (self.events.take(), self.liquidation_log.take());
+
+        let mut liquidations = 0;
+        for _ in 0..warmup.blocks {
+            self.eth_price = shocked_price(self.eth_price, calm.sample(rng));
+            for (i, cdp) in self.cdps.iter_mut().enumerate().filter(|(_, cdp)| !cdp.is_liquidated) {
+                if let Some((growth, accounting)) = accrual {
+                    cdp.accrue(growth, accounting);
+                }
+                if cdp.collateral_ratio(self.eth_price) < warmup.top_up_ratio && rng.gen::<f64>() < warmup.top_up_rate {
+                    cdp.top_up(warmup.top_up_ratio, self.eth_price);
+                }
+                self.index.update(i, cdp);
+            }
+            liquidations += self.run_liquidation_round(rng, None).liquidated;
+            self.block += 1;
+        }
+        debug!(blocks = warmup.blocks, price = self.eth_price, liquidations, "warm-up finished");
+
+        let mut arena = RunArena::take();
+        arena.cdps.clear();
+        arena.cdps.extend(self.cdps.drain(..).filter(|cdp| !cdp.is_liquidated));
+        arena.keepers.clear();
+        arena.keepers.extend(
+            self.keepers
+                .drain(..)
+                .map(|keeper| Keeper::new(keeper.id, keeper.capital, keeper.gas_priority).with_behavior(keeper.behavior)),
+        );
+        self.config.incentive_program = program;
+        self.config.liquidity_crisis = crisis;
+        self.config.governance_timelock = timelock;
+        let mut warmed = Self::assemble(arena, self.eth_price, self.mechanism, self.scenario, &self.config);
+        warmed.shocks = std::mem::replace(&mut self.shocks, Box::new(self.scenario));
+        warmed.shock_log = self.shock_log.take();
+        warmed.manipulation_eth = self.manipulation_eth;
+        warmed.manipulation_blocks = manipulation_blocks;
+        warmed.neighbor_cdps = std::mem::take(&mut self.neighbor_cdps);
+        warmed.failure_rng = self.failure_rng.take();
+        warmed.rotation_rng = self.rotation_rng.take();
+        warmed.auction_rng = self.auction_rng.take();
+        warmed.flip_rng = self.flip_rng.take();
+        warmed.reveal_rng = self.reveal_rng.take();
+        warmed.grace_rng = self.grace_rng.take();
+        warmed.batch_rng = self.batch_rng.take();
+        warmed.reputation_rng = self.reputation_rng.take();
+        warmed.events = events;
+        warmed.liquidation_log = liquidation_log;
+        *self = warmed;
+    }
+
+    fn apply_price_shock(&mut self, rng: &mut impl Rng) {
+        let before = self.eth_price;
+        let mut shock = self.shocks.shock(self.block, self.eth_price, rng);
+        if let Some(log) = &mut self.shock_log {
+            log.push(shock);
+        }
+        if self.config.severity != 1.0 {
+            shock = shock.powf(self.config.severity);
+        }
+        self.eth_price = shocked_price(self.eth_price, shock);
+        self.price_history.push(self.eth_price);
+        self.shock_decline += (before / self.eth_price).ln();
+    }
+
+    fn apply_liquidation_price_impact(&mut self, eth_sold: f64) {
+        let before = self.eth_price;
+        self.eth_price = price_after_sale(self.eth_price, eth_sold, self.price_impact_per_eth());
+        self.impact_decline += (before / self.eth_price).ln();
+    }
+
+    /// The impact coefficient this block, thinned by a liquidity crisis.
+    fn price_impact_per_eth(&self) -> f64 {
+        match self.config.liquidity_crisis {
+            Some(crisis) if self.block >= crisis.block => self.config.price_impact_per_eth * crisis.impact_multiplier,
+            _ => self.config.price_impact_per_eth,
+        }
+    }
+
+    /// Clears the neighbor's liquidatable CDPs, riskiest first, returning the
+    /// ETH they put on the market.
+    fn run_neighbor_round(&mut self, liquidatable: &mut Vec<usize>) -> f64 {
+        let Some(neighbor) = &self.config.neighbor else {
+            return 0.0;
+        };
+        liquidatable.clear();
+        liquidatable.extend(
+            self.neighbor_cdps.iter()
+                .enumerate()
+                .filter(|(_, cdp)| cdp.is_liquidatable(self.eth_price, neighbor.min_collateral_ratio))
+                .map(|(i, _)| i),
+        );
+        liquidatable.sort_unstable_by(|&a, &b| {
+            let ratio_a = self.neighbor_cdps[a].collateral_ratio(self.eth_price);
+            let ratio_b = self.neighbor_cdps[b].collateral_ratio(self.eth_price);
+            ratio_a.partial_cmp(&ratio_b).unwrap().then(a.cmp(&b))
+        });
+
+        let mut eth_sold = 0.0;
+        for &idx in liquidatable.iter().take(neighbor.liquidations_per_block) {
+            eth_sold += self.neighbor_cdps[idx].collateral_eth();
+            self.neighbor_cdps[idx].is_liquidated = true;
+        }
+        self.neighbor_eth_sold += eth_sold;
+        eth_sold
+    }
+
+    /// Flags the configuration the first block the queue runs deeper than
+    /// `config.governance_timelock` tolerates, and closes the window once
+    /// the change executes.
+    fn watch_governance(&mut self, queue: usize) {
+        let Some(timelock) = self.config.governance_timelock else {
+            return;
+        };
+        match self.governance_detected_at {
+            None if queue > timelock.queue_threshold => {
+                self.governance_detected_at = Some(self.block);
+                self.detection_bad_debt = self.calculate_bad_debt();
+                if timelock.delay_blocks == 0 {
+                    self.governance_window_bad_debt = Some(0.0);
+                }
+            }
+            Some(detected) if self.governance_window_bad_debt.is_none() && self.block >= detected + timelock.delay_blocks => {
+                self.governance_window_bad_debt = Some(self.calculate_bad_debt() - self.detection_bad_debt);
+            }
+            _ => {}
+        }
+    }
+
+    /// `config.governance_timelock`, once its change has executed.
+    fn executed_change(&self) -> Option<&GovernanceTimelock> {
+        self.config.governance_timelock.as_ref().filter(|_| self.governance_window_bad_debt.is_some())
+    }
+
+    fn liquidation_penalty(&self) -> f64 {
+        self.executed_change()
+            .and_then(|timelock| timelock.liquidation_penalty)
+            .unwrap_or(self.config.liquidation_penalty)
+    }
+
+    /// Penalty on the CDP at `idx`: the curve's at its ratio, or `flat`.
+    fn cdp_penalty(&self, idx: usize, flat: f64) -> f64 {
+        match self.config.penalty_curve {
+            Some(curve) => curve.penalty(self.cdps[idx].collateral_ratio(self.eth_price), self.config.min_collateral_ratio),
+            None => flat,
+        }
+    }
+
+    /// Reorders `queue`, lowest collateral ratio first, per `queue_order`;
+    /// ties keep their ratio order.
+    fn order_queue(&self, queue: &mut [usize]) {
+        match self.config.queue_order {
+            QueueOrder::Ratio => {}
+            QueueOrder::RiskScore => {
+                let score = |i: usize| self.cdps[i].risk_score(self.eth_price);
+                queue.sort_by(|&a, &b| score(a).total_cmp(&score(b)));
+            }
+            QueueOrder::Profit => {
+                let flat = self.liquidation_penalty();
+                let profit = |i: usize| self.cdps[i].liquidation_profit(self.eth_price, self.cdp_penalty(i, flat));
+                queue.sort_by(|&a, &b| profit(b).total_cmp(&profit(a)));
+            }
+        }
+    }
+
+    /// The configured mechanism, at the governed split once changed.
+    fn governed_mechanism(&self) -> LiquidationMechanism {
+        match (self.mechanism, self.executed_change().and_then(|timelock| timelock.split)) {
+            (LiquidationMechanism::KeeperPool { .. }, Some(split)) => LiquidationMechanism::KeeperPool { split },
+            (mechanism, _) => mechanism,
+        }
+    }
+
+    /// Resets keeper budgets and commits capital to rival liquidations,
+    /// fastest keepers first, when the rival bonus beats the best return on
+    /// capital Fair offers this block.
+    fn allocate_keeper_capital(&mut self, liquidatable: &[usize], mechanism: LiquidationMechanism, order: &mut Vec<usize>) {
+        let Some(shared) = &self.config.shared_liquidity else {
+            return;
+        };
+        for keeper in &mut self.keepers {
+            keeper.free_capital = keeper.capital;
+        }
+        if self.eth_price >= self.initial_price || liquidatable.is_empty() {
+            return; // No market-wide event, or nothing at Fair to contest
+        }
+        let payout_value = self.keeper_payout_value(mechanism);
+
+        let fair_return = liquidatable
+            .iter()
+            .filter(|&&i| self.cdps[i].debt > 0.0)
+            .map(|&i| {
+                let cdp = &self.cdps[i];
+                cdp.liquidation_profit(self.eth_price, self.cdp_penalty(i, self.liquidation_penalty())) * mechanism.keeper_share()
+                    * payout_value
+                    / cdp.debt
+            })
+            .fold(0.0, f64::max);
+        if shared.competitor_bonus <= fair_return {
+            return;
+        }
+
+        order.clear();
+        order.extend(0..self.keepers.len());
+        order.sort_unstable_by(|&a, &b| {
+            self.keepers[b].gas_priority.partial_cmp(&self.keepers[a].gas_priority).unwrap().then(a.cmp(&b))
+        });
+        let mut remaining = shared.competitor_debt_per_block;
+        let mut diverted = 0.0;
+        for &k in order.iter() {
+            let taken = self.keepers[k].capital.min(remaining);
+            self.keepers[k].free_capital -= taken;
+            remaining -= taken;
+            diverted += taken;
+        }
+        let total: f64 = self.keepers.iter().map(|k| k.capital).sum();
+        if total > 0.0 {
+            self.capital_diverted += diverted / total;
+        }
+    }
+
+    /// Reprices the reward token from the ETH price and the share of opening
+    /// debt that has turned into bad debt.
+    fn update_token_price(&mut self) {
+        let Some(token) = &self.config.reward_token else {
+            return;
+        };
+        let backed = if self.initial_debt > 0.0 {
+            1.0 - self.calculate_bad_debt() / self.initial_debt
+        } else {
+            1.0
+        };
+        self.token_price = token.price(self.eth_price / self.initial_price * backed);
+    }
+
+    /// What one USD of keeper share is worth to keepers right now.
+    fn keeper_payout_value(&self, mechanism: LiquidationMechanism) -> f64 {
+        match (&self.config.reward_token, mechanism) {
+            (Some(token), LiquidationMechanism::KeeperPool { .. }) => token.payout_value(self.token_price),
+            _ => 1.0,
+        }
+    }
+
+    /// Program bonus per liquidation this block, zero once past the cliff.
+    fn incentive_bonus(&self) -> f64 {
+        match &self.config.incentive_program {
+            Some(program) if program.is_live(self.block) => program.bonus_per_liquidation,
+            _ => 0.0,
+        }
+    }
+
+    /// Keepers still active: mercenaries leave once the program hits its cliff.
+    fn active_keepers(&self) -> usize {
+        match &self.config.incentive_program {
+            Some(program) if !program.is_live(self.block) => {
+                let mercenaries = (program.mercenary_share * self.keepers.len() as f64).round() as usize;
+                self.keepers.len() - mercenaries
+            }
+            _ => self.keepers.len(),
+        }
+    }
+
+    /// Draws the epoch's active set at each epoch boundary. Every member
+    /// draws its availability, so every offline rate sees the same sets.
+    fn rotate_active_set(&mut self) {
+        let (Some(rotation), Some(rng)) = (self.config.keeper_rotation, self.rotation_rng.as_mut()) else {
+            return;
+        };
+        if !self.on_duty.is_empty() && !self.block.is_multiple_of(rotation.epoch_blocks) {
+            return;
+        }
+        let members = rand::seq::index::sample(rng, self.keepers.len(), rotation.set_size.min(self.keepers.len()));
+        self.on_duty.clear();
+        self.on_duty.resize(self.keepers.len(), false);
+        for keeper in members {
+            self.on_duty[keeper] = rng.gen::<f64>() >= rotation.offline_rate;
+        }
+    }
+
+    /// Whether keeper `keeper` takes part in a round under `mechanism`;
+    /// the active set gates keeper pool rounds only.
+    fn is_on_duty(&self, keeper: usize, mechanism: LiquidationMechanism) -> bool {
+        !mechanism.is_keeper_pool() || self.on_duty.get(keeper).copied().unwrap_or(true)
+    }
+
+    /// Whether keeper `keeper` may join a round under `mechanism`: pool
+    /// rounds under `config.keeper_staking` need a stake of `min_stake`.
+    fn is_bonded(&self, keeper: usize, mechanism: LiquidationMechanism) -> bool {
+        match (self.config.keeper_staking, self.stakes.get(keeper)) {
+            (Some(staking), Some(&stake)) if mechanism.is_keeper_pool() => stake >= staking.min_stake,
+            _ => true,
+        }
+    }
+
+    /// Slashes keeper `keeper` for an assigned liquidation it did not
+    /// execute; the slashed stake goes to the protocol.
+    fn slash(&mut self, keeper: usize) {
+        let (Some(staking), Some(stake)) = (self.config.keeper_staking, self.stakes.get_mut(keeper)) else {
+            return;
+        };
+        let slashed = *stake * staking.slash_rate;
+        let was_bonded = *stake >= staking.min_stake;
+        *stake -= slashed;
+        self.keepers[keeper].total_profit -= slashed;
+        self.protocol_revenue += slashed;
+        self.staking.slashed += slashed;
+        self.staking.slashings += 1;
+        if was_bonded && *stake < staking.min_stake {
+            self.staking.ejected += 1;
+        }
+    }
+
+    /// Folds whether `keeper` acted when the pool counted on it into its
+    /// reliability under `config.keeper_reputation`.
+    fn record_execution(&mut self, keeper: usize, landed: bool) {
+        if let (Some(reputation), Some(standing)) = (self.config.keeper_reputation, self.standings.get_mut(keeper)) {
+            standing.reliability += reputation.memory * (f64::from(u8::from(landed)) - standing.reliability);
+        }
+    }
+
+    fn reputation_outcome(&self) -> ReputationOutcome {
+        let scores = self.standings.iter().map(Standing::score);
+        ReputationOutcome {
+            avg_score: scores.clone().sum::<f64>() / self.standings.len().max(1) as f64,
+            discredited: scores.filter(|&score| score < 0.5).count(),
+            ..self.reputation
+        }
+    }
+
+    /// Folds whether each of the first `active` keepers willing to take a
+    /// pool round at `incentive` joined it into its uptime under
+    /// `config.keeper_reputation`.
+    fn record_uptime(&mut self, active: usize, incentive: f64, mechanism: LiquidationMechanism, members: &[usize]) {
+        let Some(reputation) = self.config.keeper_reputation else {
+            return;
+        };
+        for (i, (keeper, standing)) in self.keepers.iter().zip(&mut self.standings).take(active).enumerate() {
+            // A keeper the payout does not interest is not down
+            if keeper.willing_to_liquidate(incentive, mechanism, &self.config.keepers) {
+                standing.uptime += reputation.memory * (f64::from(u8::from(members.contains(&i))) - standing.uptime);
+            }
+        }
+    }
+
+    /// Position in a round's `participants` of its executor drawn by
+    /// reputation; the external keeper, last, has a clean record. None
+    /// without `config.keeper_reputation`.
+    fn reputable_draw(&mut self, members: &[usize], participants: usize) -> Option<usize> {
+        let reputation = self.config.keeper_reputation?;
+        let weights: Vec<f64> = (0..participants)
+            .map(|i| {
+                let score = members.get(i).map_or(1.0, |&k| self.standings[k].score());
+                reputation.floor + score.powf(reputation.exponent)
+            })
+            .collect();
+        let rng = self.reputation_rng.as_mut().expect("reputation draws from its own stream");
+        let mut target = rng.gen::<f64>() * weights.iter().sum::<f64>();
+        self.reputation.draws += 1;
+        for (i, weight) in weights.into_iter().enumerate() {
+            target -= weight;
+            if target < 0.0 {
+                return Some(i);
+            }
+        }
+        Some(participants - 1) // Rounding left the draw past the last weight
+    }
+
+    fn charge_commits(&mut self, members: &[usize]) {
+        let commit = self.config.keepers.gas.pool_commit;
+        self.fee_burn += members.len() as f64 * commit;
+        for &k in members {
+            self.keepers[k].gas_spent += commit;
+        }
+    }
+
+    /// The commit-reveal phase of a pool round on CDP `cdp`, given the
+    /// keepers willing to liquidate it now. Without an open commit they
+    /// commit, to reveal `reveal_blocks` on; at the reveal `members` is cut
+    /// to the committed keepers that reveal. Returns false for a commit,
+    /// which ends the round for this block.
+    fn reveal_commit(&mut self, cdp: usize, members: &mut Vec<usize>, reveal: CommitReveal) -> bool {
+        let committed = mem::take(&mut self.commits[cdp]);
+        if committed.is_empty() {
+            if members.is_empty() {
+                return true; // Nobody to commit; the round finds no participants
+            }
+            self.charge_commits(members);
+            self.commits[cdp].clone_from(members);
+            self.retry_at[cdp] = self.block + reveal.reveal_blocks;
+            self.reveals.commits += 1;
+            return false;
+        }
+        let mut revealed = Vec::with_capacity(committed.len());
+        for k in committed {
+            // Every committed member draws, so every miss rate sees the same stream
+            let rng = self.reveal_rng.as_mut().expect("reveals draw from their own stream");
+            let missed = rng.gen::<f64>() < reveal.miss_rate;
+            match (members.contains(&k), missed) {
+                (false, _) => self.reveals.dropped += 1,
+                (true, true) => {
+                    self.reveals.missed += 1;
+                    self.record_execution(k, false);
+                }
+                (true, false) => revealed.push(k),
+            }
+        }
+        if revealed.is_empty() {
+            self.reveals.lapsed += 1;
+        } else {
+            self.reveals.revealed += 1;
+        }
+        *members = revealed;
+        true
+    }
+
+    /// Holds CDPs in `liquidatable` that fell below the minimum within the
+    /// last `grace_blocks` out of the queue, each owner drawing whether to
+    /// top up this block.
+    fn grant_grace(&mut self, liquidatable: &[usize], grace: GracePeriod) {
+        let rng = self.grace_rng.as_mut().expect("top-ups draw from their own stream");
+        for &i in liquidatable {
+            let since = self.liquidatable_since[i].expect("set for every liquidatable CDP");
+            let ends = since + grace.grace_blocks;
+            if since == self.block {
+                self.grace.entered += 1;
+                self.retry_at[i] = self.retry_at[i].max(ends);
+            }
+            if self.block >= ends {
+                self.grace.expired += usize::from(self.block == ends);
+                continue;
+            }
+            // Every owner in grace draws, so every top-up rate sees the same stream
+            let tops_up = rng.gen::<f64>() < grace.top_up_rate;
+            let cdp = &mut self.cdps[i];
+            // An owner whose CDP is underwater has no equity left to save
+            if tops_up && cdp.collateral_value(self.eth_price) > cdp.debt {
+                let before = cdp.collateral_eth();
+                cdp.top_up(grace.top_up_ratio, self.eth_price);
+                let added = cdp.collateral_eth() - before;
+                self.ledger.topped_up_collateral += added;
+                self.grace.topped_up += added * self.eth_price;
+                self.grace.rescued += 1;
+                self.index.update(i, cdp);
+            }
+        }
+    }
+
+    /// Whether the selected executor fails. Always draws, so every failure
+    /// rate sees the same stream.
+    fn execution_fails(&mut self) -> bool {
+        match (&self.config.execution_failure, self.failure_rng.as_mut()) {
+            (Some(failure), Some(rng)) => rng.gen::<f64>() < failure.failure_rate,
+            _ => false,
+        }
+    }
+
+    /// Whether keeper `keeper` has seen CDP `cdp` become liquidatable by now.
+    fn has_seen(&self, keeper: usize, cdp: usize) -> bool {
+        let Some(mempool) = &self.config.mempool else {
+            return true;
+        };
+        let watchers = (mempool.visible_share * self.keepers.len() as f64).round() as usize;
+        let waited = self.liquidatable_since[cdp].map_or(0, |since| self.block - since);
+        keeper < watchers || waited >= mempool.lead_blocks
+    }
+
+    /// Share of the CDP's debt the next liquidation closes: all of it unless
+    /// a fixed spread's or `config.liquidation_cooldown`'s close factor limits
+    /// it and the rest is not dust.
+    fn close_share(&self, cdp: &CDP, mechanism: LiquidationMechanism) -> f64 {
+        let close_factor = match mechanism {
+            LiquidationMechanism::FixedSpread { close_factor, .. } => Some(close_factor),
+            _ => self.config.liquidation_cooldown.map(|cooldown| cooldown.close_factor),
+        };
+        match close_factor {
+            Some(close_factor) if cdp.debt * (1.0 - close_factor) >= DUST_DEBT => close_factor,
+            _ => 1.0,
+        }
+    }
+
+    fn has_capital_for(&self, keeper: &Keeper, debt: f64) -> bool {
+        self.config.shared_liquidity.is_none() || keeper.free_capital >= debt
+    }
+
+    fn run_liquidation_round(
+        &mut self,
+        rng: &mut impl Rng,
+        mut strategy: Option<&mut (dyn KeeperStrategy + '_)>,
+    ) -> RoundOutcome {
+        // Taken for the round so its lists can be walked while `self` changes
+        let mut buffers = mem::take(&mut self.round);
+        let RoundBuffers {
+            liquidatable,
+            liquidated,
+            sorted,
+            selectable,
+            participants: participating_keepers,
+            candidates,
+            gas,
+            inventory,
+            keeper_order,
+            neighbor,
+        } = &mut buffers;
+        self.index.liquidatable_into(&self.cdps, self.eth_price, self.config.min_collateral_ratio, liquidatable);
+        self.order_queue(liquidatable);
+        sorted.clone_from(liquidatable);
+        sorted.sort_unstable();
+        for &i in &self.queued {
+            if sorted.binary_search(&i).is_err() {
+                self.liquidatable_since[i] = None;
+                if let Some(commit) = self.commits.get_mut(i) {
+                    commit.clear(); // Recovered before the reveal
+                }
+            }
+        }
+        for &i in liquidatable.iter() {
+            self.liquidatable_since[i].get_or_insert(self.block);
+        }
+        if let Some(grace) = self.config.grace_period {
+            if !matches!(self.mechanism, LiquidationMechanism::SoftLiquidation(_)) {
+                self.grant_grace(liquidatable, grace);
+            }
+        }
+        self.queued.clone_from(liquidatable);
+        self.watch_governance(liquidatable.len());
+        // A deep queue hands the round to the backstop, which races like Traditional
+        let backstop = self.config.mechanism_switch.as_ref()
+            .filter(|switch| liquidatable.len() > switch.queue_threshold)
+            .map(|switch| switch.backstop);
+        let mechanism = if backstop.is_some() { LiquidationMechanism::Traditional } else { self.governed_mechanism() };
+        let liquidation_penalty = self.liquidation_penalty();
+        let auction = self.config.auction_discount.filter(|_| backstop.is_some_and(|b| b.is_auction()));
+        if backstop.is_some() {
+            self.backstop_blocks += 1;
+        }
+        self.update_token_price();
+        self.allocate_keeper_capital(liquidatable, mechanism, keeper_order);
+        let payout_value = self.keeper_payout_value(mechanism);
+        // Members expect the treasury to keep paying what it paid last epoch
+        let treasury_rate = self.treasury.as_ref().filter(|_| mechanism.is_keeper_pool()).map_or(1.0, |t| t.rate);
+        let emission_share = self.config.reward_token.as_ref().map_or(0.0, |t| t.emission_share);
+        let bonus = self.incentive_bonus();
+        let active_keepers = self.active_keepers();
+        self.rotate_active_set();
+        if mechanism.is_keeper_pool() && !liquidatable.is_empty() && !self.on_duty.is_empty()
+            && !self.on_duty.iter().any(|&online| online)
+        {
+            self.rotation_outages += 1;
+        }
+        
+        liquidated.clear();
+        let mut eth_sold_this_block = 0.0;
+        let mut penalties = 0.0;
+        let mut protocol_fees = 0.0;
+        let mut max_payout_share: f64 = 0.0;
+        let mut sybil_executions = 0;
+        inventory.clear();
+        
+        // CDPs waiting out a failed execution or a cooldown keep their place in the queue
+        selectable.clear();
+        selectable.extend(liquidatable.iter().copied().filter(|&i| self.retry_at[i] <= self.block));
+        
+        let mut covered = 0.0;
+        let (mut attempts, mut joined, mut conversions) = (0, 0, 0);
+        match mechanism {
+            LiquidationMechanism::SoftLiquidation(_) => selectable.clear(), // Nothing is liquidated outright; the bands trade below
+            LiquidationMechanism::BatchAuction(batch) => {
+                let settled =
+                    self.settle_batch(batch, liquidation_penalty, active_keepers, selectable, participating_keepers, liquidated, inventory);
+                eth_sold_this_block += settled.eth_sold;
+                penalties += settled.penalties;
+                covered += settled.covered;
+                attempts += settled.attempts;
+                joined += settled.joined;
+                if settled.penalties > 0.0 {
+                    max_payout_share = max_payout_share.max(settled.max_payout / settled.penalties);
+                }
+                selectable.clear(); // Settled as one batch, not CDP by CDP
+            }
+            _ => {}
+        }
+        // Commits are keepers' transactions, not liquidations, so they take no slot
+        let mut slots = self.config.liquidations_per_block;
+        for cdp_idx in selectable.iter() {
+            if slots == 0 {
+                break;
+            }
+            slots -= 1;
+            if let LiquidationMechanism::EnglishAuction(_) = mechanism {
+                self.kick(*cdp_idx, self.cdp_penalty(*cdp_idx, liquidation_penalty));
+                continue;
+            }
+            let cdp = &self.cdps[*cdp_idx];
+            let shortfall = cdp.shortfall(self.eth_price, self.config.min_collateral_ratio);
+            let penalty = match backstop {
+                Some(backstop) => {
+                    let waiting = self.liquidatable_since[*cdp_idx].map_or(0, |since| self.block - since);
+                    backstop.penalty(liquidation_penalty, waiting)
+                }
+                None => self.cdp_penalty(*cdp_idx, liquidation_penalty),
+            };
+            let close = self.close_share(cdp, mechanism);
+            let profit = match mechanism {
+                LiquidationMechanism::FixedSpread { bonus, .. } => cdp.spread_profit(self.eth_price, cdp.debt * close, bonus),
+                _ => cdp.liquidation_profit(self.eth_price, penalty) * close,
+            };
+            
+            // The bonus counts toward the keeper-side payout thresholds apply to
+            let incentive = profit * payout_value * treasury_rate + bonus / mechanism.keeper_share();
+            // A plain loop: extending from the filter chain compiles to far slower code
+            participating_keepers.clear();
+            for (i, k) in self.keepers.iter().take(active_keepers).enumerate() {
+                if k.willing_to_liquidate(incentive, mechanism, &self.config.keepers)
+                    && self.has_capital_for(k, cdp.debt * close)
+                    && self.has_seen(i, *cdp_idx)
+                    && self.is_on_duty(i, mechanism)
+                    && self.is_bonded(i, mechanism)
+                {
+                    participating_keepers.push(i);
+                }
+            }
+            if mechanism.is_keeper_pool() {
+                self.record_uptime(active_keepers, incentive, mechanism, participating_keepers);
+            }
+            if let Some(reveal) = self.config.commit_reveal.filter(|_| mechanism.is_keeper_pool()) {
+                if !self.reveal_commit(*cdp_idx, participating_keepers, reveal) {
+                    slots += 1;
+                    continue;
+                }
+            }
+            let cdp = &self.cdps[*cdp_idx];
+            
+            let external_action = strategy.as_mut().and_then(|s| {
+                s.decide(&Opportunity {
+                    block: self.block,
+                    cdp_id: cdp.id,
+                    collateral: cdp.collateral_eth(),
+                    debt: cdp.debt,
+                    collateral_ratio: cdp.collateral_ratio(self.eth_price),
+                    eth_price: self.eth_price,
+                    expected_profit: profit,
+                    mechanism,
+                    competitors: participating_keepers.len(),
+                })
+            });
+            let participants = participating_keepers.len() + usize::from(external_action.is_some());
+            attempts += 1;
+            joined += participants;
+            
+            if participants == 0 {
+                continue;
+            }
+            
+            // Bidders compete the offered discount down; the protocol's cut comes off the top
+            let (profit, auction_fee) = match (auction, self.auction_rng.as_mut()) {
+                (Some(auction), Some(auction_rng)) => {
+                    let discount = auction.clearing_discount(penalty, participants, auction_rng);
+                    let cleared = cdp.liquidation_profit(self.eth_price, discount) * close;
+                    (cleared * (1.0 - auction.protocol_share), cleared * auction.protocol_share)
+                }
+                _ => (profit, 0.0),
+            };
+            
+            let mut external_result = None;
+            let mut executor_idx = None;
+            let max_payout = match mechanism {
+                LiquidationMechanism::Traditional | LiquidationMechanism::FixedSpread { .. } => {
+                    // Every racer bids for the whole prize; losing transactions revert but still pay
+                    gas.clear();
+                    gas.extend(
+                        participating_keepers.iter()
+                            .map(|&k| self.config.keepers.gas.base_gas + self.keepers[k].gas_priority * (profit + bonus)),
+                    );
+                    self.fee_burn += gas.iter().sum::<f64>();
+                    for (&k, &cost) in participating_keepers.iter().zip(gas.iter()) {
+                        self.keepers[k].gas_spent += cost;
+                    }
+                    let winner_idx = participating_keepers.iter()
+                        .filter(|&&k| self.keepers[k].can_execute(sybil_executions))
+                        .max_by(|&&a, &&b| {
+                            self.keepers[a].gas_priority
+                                .partial_cmp(&self.keepers[b].gas_priority)
+                                .unwrap()
+                        });
+                    let external_wins = match (external_action, winner_idx) {
+                        (Some(action), Some(&w)) => action.gas_priority > self.keepers[w].gas_priority,
+                        (Some(_), None) => true,
+                        (None, _) => false,
+                    };
+                    
+                    if external_wins {
+                        external_result = Some((AttemptResult::Won, profit + bonus));
+                        self.incentives_paid += bonus;
+                        Some(profit)
+                    } else if let Some(&winner_idx) = winner_idx {
+                        self.keepers[winner_idx].total_profit += profit + bonus;
+                        self.incentives_paid += bonus;
+                        self.keepers[winner_idx].liquidations += 1;
+                        executor_idx = Some(winner_idx);
+                        if self.keepers[winner_idx].behavior == KeeperBehavior::Sybil {
+                            sybil_executions += 1;
+                        }
+                        if external_action.is_some() {
+                            external_result = Some((AttemptResult::Lost, 0.0));
+                        }
+                        Some(profit)
+                    } else {
+                        None
+                    }
+                }
+                LiquidationMechanism::EnglishAuction(_) => unreachable!("English auctions are kicked, not raced"),
+                LiquidationMechanism::SoftLiquidation(_) => unreachable!("soft liquidations trade bands, not CDPs"),
+                LiquidationMechanism::BatchAuction(_) => unreachable!("batch auctions settle the queue at once"),
+                LiquidationMechanism::KeeperPool { split } => {
+                    // The uniform draw is made regardless, so reputation leaves the main stream alone
+                    let drawn = rng.gen_range(0..participants);
+                    let drawn = self.reputable_draw(participating_keepers, participants).unwrap_or(drawn);
+                    let keeper_share = profit * split;
+                    // The executor premium, valued as keepers value their payout
+                    let slot = self.config.execution_race.as_ref()
+                        .map_or(0.0, |race| race.executor_share * keeper_share * payout_value);
+                    // Members race for the slot; the highest priority fee lands first
+                    let priority = |keepers: &[Keeper], i: usize| match participating_keepers.get(i) {
+                        Some(&k) => keepers[k].gas_priority,
+                        None => external_action.map_or(0.0, |action| action.gas_priority),
+                    };
+                    candidates.clear();
+                    candidates.extend(0..participants);
+                    let mut winner = if slot > 0.0 {
+                        candidates.iter()
+                            .copied()
+                            .max_by(|&a, &b| priority(&self.keepers, a).total_cmp(&priority(&self.keepers, b)))
+                            .unwrap_or(drawn)
+                    } else {
+                        drawn
+                    };
+                    if self.config.commit_reveal.is_none() {
+                        self.charge_commits(participating_keepers);
+                    }
+                    if slot > 0.0 {
+                        gas.clear();
+                        gas.extend(
+                            participating_keepers.iter()
+                                .map(|&k| self.config.keepers.gas.base_gas + self.keepers[k].gas_priority * slot),
+                        );
+                        self.fee_burn += gas.iter().sum::<f64>();
+                        for (&k, &cost) in participating_keepers.iter().zip(gas.iter()) {
+                            self.keepers[k].gas_spent += cost;
+                        }
+                    }
+                    
+                    // A failed executor falls back per the policy; the external keeper always lands
+                    let mut failed_over = false;
+                    while winner < participating_keepers.len() && self.execution_fails() {
+                        self.execution_failures += 1;
+                        self.slash(participating_keepers[winner]);
+                        self.record_execution(participating_keepers[winner], false);
+                        if slot == 0.0 {
+                            let base_gas = self.config.keepers.gas.base_gas; // Racers already paid for their transactions
+                            self.fee_burn += base_gas;
+                            self.keepers[participating_keepers[winner]].gas_spent += base_gas;
+                        }
+                        let fallback = self.config.execution_failure.map(|f| f.fallback);
+                        if let Some(FallbackPolicy::Reselect { delay }) = fallback {
+                            self.retry_at[*cdp_idx] = self.block + delay;
+                            failed_over = true;
+                            break;
+                        }
+                        candidates.retain(|&i| i != winner);
+                        let backup = if slot > 0.0 {
+                            candidates.iter()
+                                .copied()
+                                .max_by(|&a, &b| priority(&self.keepers, a).total_cmp(&priority(&self.keepers, b)))
+                        } else {
+                            let rng = self.failure_rng.as_mut().expect("failures draw from their own stream");
+                            candidates.choose(rng).copied()
+                        };
+                        match backup {
+                            Some(next) => winner = next,
+                            None => {
+                                failed_over = true;
+                                break;
+                            }
+                        }
+                    }
+                    let executor = participating_keepers.get(winner).copied();
+                    
+                    if failed_over || executor.is_some_and(|k| !self.keepers[k].can_execute(sybil_executions)) {
+                        // The selected executor never acts, or failed with no
+                        // backup this block: the round lapses unpaid
+                        if let (false, Some(k)) = (failed_over, executor) {
+                            self.slash(k);
+                            self.record_execution(k, false);
+                        }
+                        if external_action.is_some() {
+                            external_result = Some((AttemptResult::Lost, 0.0));
+                        }
+                        None
+                    } else {
+                        // The token part of the keeper share is minted, not paid from the penalty
+                        let retained = profit - keeper_share * (1.0 - emission_share);
+                        let per_keeper = (keeper_share * payout_value - slot) / participants as f64;
+                        let bonus_share = bonus / participants as f64;
+                        // Staked members split the members' part pro rata to stake, others per the pool split
+                        let bonded: f64 = participating_keepers.iter().filter_map(|&k| self.stakes.get(k)).sum();
+                        let pooled: f64 = match self.config.pool_split {
+                            PoolSplit::Capital => participating_keepers.iter().map(|&k| self.keepers[k].capital).sum(),
+                            PoolSplit::Equal => 0.0,
+                        };
+                        // The external keeper, when it executes, takes an equal share and the slot
+                        let mut max_payout: f64 = if executor.is_none() { per_keeper + slot } else { 0.0 };
+                        
+                        for &k_idx in participating_keepers.iter() {
+                            let weight = match self.stakes.get(k_idx) {
+                                Some(&stake) if bonded > 0.0 => stake / bonded * participating_keepers.len() as f64,
+                                Some(_) => 1.0,
+                                None if pooled > 0.0 => self.keepers[k_idx].capital / pooled * participating_keepers.len() as f64,
+                                None => 1.0,
+                            };
+                            let premium = if executor == Some(k_idx) { slot } else { 0.0 };
+                            max_payout = max_payout.max(per_keeper * weight + premium);
+                            match &mut self.treasury {
+                                Some(treasury) => {
+                                    treasury.claim(k_idx, per_keeper * weight);
+                                    self.keepers[k_idx].total_profit += bonus_share * weight;
+                                }
+                                None => self.keepers[k_idx].total_profit += (per_keeper + bonus_share) * weight,
+                            }
+                        }
+                        self.incentives_paid += bonus;
+                        self.protocol_revenue += retained;
+                        self.tokens_emitted += keeper_share * emission_share;
+                        self.ledger.emission_value += keeper_share * (payout_value - 1.0 + emission_share);
+                        
+                        match executor {
+                            Some(k_idx) => {
+                                self.record_execution(k_idx, true);
+                                self.keepers[k_idx].total_profit += slot;
+                                self.keepers[k_idx].liquidations += 1;
+                                executor_idx = Some(k_idx);
+                                if slot == 0.0 {
+                                    let base_gas = self.config.keepers.gas.base_gas;
+                                    self.fee_burn += base_gas;
+                                    self.keepers[k_idx].gas_spent += base_gas;
+                                }
+                                if self.keepers[k_idx].behavior == KeeperBehavior::Sybil {
+                                    sybil_executions += 1;
+                                }
+                                if external_action.is_some() {
+                                    external_result = Some((AttemptResult::PoolShare, per_keeper + bonus_share));
+                                }
+                            }
+                            None => external_result = Some((AttemptResult::Won, per_keeper + bonus_share + slot)),
+                        }
+                        protocol_fees += retained;
+                        Some(max_payout)
+                    }
+                }
+            };
+            
+            if let (Some(action), Some((result, revenue))) = (external_action, external_result) {
+                let outcome = AttemptOutcome {
+                    block: self.block,
+                    cdp_id: self.cdps[*cdp_idx].id,
+                    result,
+                    revenue,
+                    gas_cost: attempt_gas_cost(action, result == AttemptResult::Won, mechanism),
+                };
+                self.external.record(&outcome);
+                if let Some(s) = strategy.as_mut() {
+                    s.on_outcome(&outcome);
+                }
+            }
+            
+            let Some(max_payout) = max_payout else {
+                continue; // Stalled: the CDP stays in the queue
+            };
+            self.protocol_revenue += auction_fee;
+            protocol_fees += auction_fee;
+            let profit = profit + auction_fee;
+            penalties += profit;
+            if profit > 0.0 {
+                max_payout_share = max_payout_share.max(max_payout / profit);
+            }
+            
+            let debt = self.cdps[*cdp_idx].debt * close;
+            let repays = matches!(mechanism, LiquidationMechanism::FixedSpread { .. });
+            let curved = self.config.penalty_curve.is_some();
+            let seized = if close < 1.0 || auction.is_some() || repays || curved {
+                // A partial liquidation, auction, repay or penalty curve seizes the closed debt plus its penalty
+                ((debt + profit) / self.eth_price).min(self.cdps[*cdp_idx].collateral_eth())
+            } else {
+                self.cdps[*cdp_idx].collateral_eth()
+            };
+            if let Some(k_idx) = executor_idx {
+                self.keepers[k_idx].free_capital -= debt;
+                inventory.push((k_idx, seized));
+            }
+            self.borrower_loss += (seized * self.eth_price - debt).max(0.0);
+            self.ledger.seized_collateral += seized;
+            self.ledger.debt_repaid += debt.min(seized * self.eth_price);
+            self.ledger.liquidation_shortfall += (debt - seized * self.eth_price).max(0.0);
+            self.ledger.penalties += profit;
+            eth_sold_this_block += seized;
+            if let Some(log) = &mut self.liquidation_log {
+                log.push(LiquidationEvent {
+                    block: self.block,
+                    cdp_id: self.cdps[*cdp_idx].id,
+                    liquidator: executor_idx.map(|k| self.keepers[k].id),
+                    profit,
+                    eth_sold: seized,
+                    price: self.eth_price,
+                });
+            }
+            self.liquidation_delay += self.liquidatable_since[*cdp_idx].map_or(0, |since| self.block - since) as f64;
+            match self.config.liquidation_cooldown.filter(|_| close < 1.0) {
+                Some(cooldown) => {
+                    self.cdps[*cdp_idx].seize(seized, debt);
+                    self.index.update(*cdp_idx, &self.cdps[*cdp_idx]);
+                    self.ledger.liquidated_debt += debt;
+                    self.retry_at[*cdp_idx] = self.block + cooldown.cooldown_blocks;
+                }
+                // The borrower keeps the collateral the auction, repay or curve did not take
+                None if auction.is_some() || repays || curved => {
+                    self.cdps[*cdp_idx].seize(seized, debt);
+                    self.index.update(*cdp_idx, &self.cdps[*cdp_idx]);
+                    self.ledger.liquidated_debt += debt;
+                }
+                None => {
+                    self.cdps[*cdp_idx].is_liquidated = true;
+                    self.index.update(*cdp_idx, &self.cdps[*cdp_idx]);
+                }
+            }
+            liquidated.push(*cdp_idx);
+            covered += shortfall;
+            if backstop.is_some() {
+                self.backstop_liquidations += 1;
+            }
+        }
+        
+        if let LiquidationMechanism::EnglishAuction(flip) = self.mechanism {
+            let auctions = self.run_flip_auctions(flip, active_keepers, participating_keepers, liquidated, inventory);
+            eth_sold_this_block += auctions.eth_sold;
+            penalties += auctions.penalties;
+            protocol_fees += auctions.protocol_fees;
+            covered += auctions.covered;
+            attempts += auctions.attempts;
+            joined += auctions.joined;
+        }
+        if let LiquidationMechanism::SoftLiquidation(llamma) = self.mechanism {
+            let market = BandMarket {
+                config: &self.config,
+                mechanism: self.mechanism,
+                block: self.block,
+                price: self.eth_price,
+                cdps: &mut self.cdps,
+                index: &mut self.index,
+                keepers: &mut self.keepers,
+                active_keepers,
+                liquidatable_since: &self.liquidatable_since,
+                ledger: &mut self.ledger,
+                fee_burn: &mut self.fee_burn,
+                borrower_loss: &mut self.borrower_loss,
+                liquidation_delay: &mut self.liquidation_delay,
+                liquidation_log: self.liquidation_log.as_mut(),
+            };
+            let trades = self.soft.trade(llamma, market, participating_keepers, liquidated, inventory);
+            eth_sold_this_block += trades.eth_sold;
+            conversions += trades.conversions;
+            attempts += trades.attempts;
+            joined += trades.joined;
+        }
+
+        // CDPs left in the queue are untouched, so still at their round-start shortfall
+        let missed: f64 = liquidatable.iter()
+            .filter(|i| !liquidated.contains(i))
+            .map(|&i| self.cdps[i].shortfall(self.eth_price, self.config.min_collateral_ratio))
+            .sum();
+        self.exposure += covered + missed;
+        self.missed_exposure += missed;
+        self.participation.add_block(attempts, joined);
+
+        // The neighbor clears against the same oracle price in the same block
+        let neighbor_eth_sold = self.run_neighbor_round(neighbor);
+        let dumped = if self.block < self.manipulation_blocks { self.manipulation_eth } else { 0.0 };
+        let oracle_price = self.eth_price;
+        self.apply_liquidation_price_impact(eth_sold_this_block + dumped + neighbor_eth_sold);
+        for &(k_idx, eth) in inventory.iter() {
+            self.keepers[k_idx].inventory_pnl += eth * (self.eth_price - oracle_price);
+        }
+        
+        let outcome = RoundOutcome {
+            liquidatable: liquidatable.len(),
+            liquidated: liquidated.len(),
+            conversions,
+            eth_sold: eth_sold_this_block,
+            penalties,
+            protocol_fees,
+            max_payout_share,
+            neighbor_eth_sold,
+        };
+        self.round = buffers;
+        outcome
+    }
+
+    fn calculate_bad_debt(&self) -> f64 {
+        self.cdps.iter()
+            .map(|cdp| cdp.bad_debt(self.eth_price))
+            .sum::<f64>()
+            + self.flip_bad_debt
+    }
+
+    /// Bites a CDP into an English auction of its whole collateral.
+    fn kick(&mut self, cdp: usize, penalty: f64) {
+        let debt = self.cdps[cdp].debt;
+        self.auctions.push(Flip {
+            cdp,
+            lot: self.cdps[cdp].collateral_eth(),
+            tab: debt * (1.0 + penalty),
+            debt,
+            bid: 0.0,
+            winner: None,
+            bids: 0,
+            kicked: self.block,
+            last_bid: self.block,
+        });
+        self.retry_at[cdp] = usize::MAX; // Out of the queue until the auction settles
+        self.flips.kicked += 1;
+    }
+
+    /// Advances every open English auction a block: keepers bid on those
+    /// kicked in earlier blocks, then auctions that ran out settle.
+    fn run_flip_auctions(
+        &mut self,
+        flip: FlipAuction,
+        active_keepers: usize,
+        bidders: &mut Vec<usize>,
+        liquidated: &mut Vec<usize>,
+        inventory: &mut Vec<(usize, f64)>,
+    ) -> FlipRound {
+        let mut round = FlipRound::default();
+        let mut auctions = mem::take(&mut self.auctions);
+        auctions.retain_mut(|auction| {
+            if auction.kicked < self.block {
+                self.bid_on(auction, flip, active_keepers, bidders, &mut round);
+            }
+            !self.settle(auction, flip, liquidated, inventory, &mut round)
+        });
+        self.auctions = auctions;
+        round
+    }
+
+    /// Settles up to `max_batch` CDPs of `queue` in one uniform-price
+    /// auction: bidders offer their capital at their reservation discounts,
+    /// the lowest fill the batch's debt, and every winner pays the marginal
+    /// bidder's discount.
+    #[allow(clippy::too_many_arguments)]
+    fn settle_batch(
+        &mut self,
+        batch: BatchAuction,
+        penalty: f64,
+        active_keepers: usize,
+        queue: &[usize],
+        bidders: &mut Vec<usize>,
+        liquidated: &mut Vec<usize>,
+        inventory: &mut Vec<(usize, f64)>,
+    ) -> BatchRound {
+        let price = self.eth_price;
+        let mut round = BatchRound::default();
+        // Underwater CDPs pay no discount, so nobody bids for them
+        let lots: Vec<usize> = queue
+            .iter()
+            .copied()
+            .filter(|&i| self.cdps[i].liquidation_profit(price, penalty) > 0.0)
+            .take(batch.max_batch)
+            .collect();
+        if lots.is_empty() {
+            return round;
+        }
+        let debt: f64 = lots.iter().map(|&i| self.cdps[i].debt).sum();
+        let offered: f64 = lots.iter().map(|&i| self.cdps[i].liquidation_profit(price, penalty)).sum();
+
+        let reservation = LogNormal::new(-batch.dispersion.powi(2) / 2.0, batch.dispersion).expect("validated dispersion");
+        let rng = self.batch_rng.as_mut().expect("reservations draw from their own stream");
+        // Every active keeper draws, so every batch sees the same stream
+        let reservations: Vec<f64> = (0..active_keepers).map(|_| penalty * reservation.sample(rng)).collect();
+        let shared = self.config.shared_liquidity.is_some();
+        let capacity = |keeper: &Keeper| if shared { keeper.free_capital.max(0.0) } else { keeper.capital };
+        let mechanism = LiquidationMechanism::BatchAuction(batch);
+        bidders.clear();
+        bidders.extend((0..active_keepers).filter(|&i| {
+            let keeper = &self.keepers[i];
+            // A bidder counts on the offered discount on the share of the batch its capital buys
+            let share = (capacity(keeper) / debt).min(1.0);
+            reservations[i] <= penalty
+                && keeper.willing_to_liquidate(offered * share, mechanism, &self.config.keepers)
+                && keeper.can_execute(0)
+        }));
+        bidders.sort_by(|&a, &b| reservations[a].total_cmp(&reservations[b]));
+        round.attempts = 1;
+        round.joined = bidders.len();
+        let base_gas = self.config.keepers.gas.base_gas;
+        for &k in bidders.iter() {
+            self.keepers[k].gas_spent += base_gas;
+            self.fee_burn += base_gas;
+        }
+
+        // The lowest reservations fill the debt; the marginal one prices the batch
+        let mut fills = Vec::new();
+        let (mut filled, mut clearing) = (0.0, 0.0);
+        for &k in bidders.iter() {
+            if filled >= debt {
+                break;
+            }
+            let take = capacity(&self.keepers[k]).min(debt - filled);
+            if take > 0.0 {
+                fills.push((k, take));
+                filled += take;
+                clearing = reservations[k];
+            }
+        }
+        if fills.is_empty() {
+            return round;
+        }
+
+        // Whole CDPs, in queue order, as far as the fills reach
+        let (mut repaid, mut profit, mut seized) = (0.0, 0.0, 0.0);
+        let liquidator = fills.first().map(|&(k, _)| self.keepers[k].id);
+        for &i in &lots {
+            let cdp = &self.cdps[i];
+            if repaid + cdp.debt > filled * (1.0 + 1e-9) {
+                self.batches.unfilled += 1;
+                continue;
+            }
+            let cdp_debt = cdp.debt;
+            let cdp_profit = cdp.liquidation_profit(price, clearing);
+            let eth = ((cdp_debt + cdp_profit) / price).min(cdp.collateral_eth());
+            round.covered += cdp.shortfall(price, self.config.min_collateral_ratio);
+            self.cdps[i].seize(eth, cdp_debt);
+            self.index.update(i, &self.cdps[i]);
+            self.borrower_loss += (eth * price - cdp_debt).max(0.0);
+            self.ledger.seized_collateral += eth;
+            self.ledger.debt_repaid += cdp_debt.min(eth * price);
+            self.ledger.liquidation_shortfall += (cdp_debt - eth * price).max(0.0);
+            self.ledger.liquidated_debt += cdp_debt;
+            self.ledger.penalties += cdp_profit;
+            if let Some(log) = &mut self.liquidation_log {
+                log.push(LiquidationEvent {
+                    block: self.block,
+                    cdp_id: self.cdps[i].id,
+                    liquidator,
+                    profit: cdp_profit,
+                    eth_sold: eth,
+                    price,
+                });
+            }
+            self.liquidation_delay += self.liquidatable_since[i].map_or(0, |since| self.block - since) as f64;
+            liquidated.push(i);
+            repaid += cdp_debt;
+            profit += cdp_profit;
+            seized += eth;
+        }
+        if repaid == 0.0 {
+            return round;
+        }
+
+        // Winners split the discount and the collateral pro rata to their fills
+        for (k, take) in fills {
+            let share = take / filled;
+            let keeper = &mut self.keepers[k];
+            keeper.total_profit += profit * share;
+            keeper.liquidations += 1;
+            keeper.free_capital -= repaid * share;
+            inventory.push((k, seized * share));
+            round.max_payout = round.max_payout.max(profit * share);
+        }
+        self.batches.batches += 1;
+        self.batches.settled += liquidated.len();
+        self.batches.bids += round.joined;
+        self.batches.debt += repaid;
+        self.batches.discount += profit;
+        round.eth_sold = seized;
+        round.penalties = profit;
+        round
+    }
+
+    /// One block of bidding on `auction` by the keepers who turn up for it.
+    fn bid_on(&mut self, auction: &mut Flip, flip: FlipAuction, active_keepers: usize, bidders: &mut Vec<usize>, round: &mut FlipRound) {
+        let mechanism = self.mechanism;
+        let threshold = self.config.keepers.threshold(mechanism);
+        // Once the tab is covered, bids offer to take less collateral for it
+        let dent = auction.bid >= auction.tab;
+        let (next_bid, next_lot) = match auction.winner {
+            _ if dent => (auction.tab, auction.lot / (1.0 + flip.min_increment)),
+            None => (0.0, auction.lot),
+            Some(_) => ((auction.bid * (1.0 + flip.min_increment)).min(auction.tab), auction.lot),
+        };
+        let profit = next_lot * self.eth_price - next_bid;
+        bidders.clear();
+        for i in 0..active_keepers {
+            let keeper = &self.keepers[i];
+            let willing = Some(i) != auction.winner
+                && keeper.willing_to_liquidate(profit, mechanism, &self.config.keepers)
+                && keeper.can_execute(0)
+                && self.has_capital_for(keeper, next_bid)
+                && self.has_seen(i, auction.cdp);
+            if willing && self.flip_rng.as_mut().is_none_or(|rng| rng.gen::<f64>() >= flip.apathy) {
+                bidders.push(i);
+            }
+        }
+        round.attempts += 1;
+        round.joined += bidders.len();
+        if bidders.is_empty() {
+            return;
+        }
+
+        // Most capital wins, then the fastest transaction; ties keep the standing bidder
+        let rank = |keepers: &[Keeper], k: usize| {
+            let capacity = if self.config.shared_liquidity.is_some() { keepers[k].free_capital } else { f64::INFINITY };
+            (capacity, keepers[k].gas_priority)
+        };
+        let (mut best, mut runner_up) = (None, None);
+        for &k in auction.winner.iter().chain(bidders.iter()) {
+            match best {
+                Some(b) if rank(&self.keepers, k) > rank(&self.keepers, b) => (best, runner_up) = (Some(k), best),
+                Some(_) if runner_up.is_none_or(|r| rank(&self.keepers, k) > rank(&self.keepers, r)) => runner_up = Some(k),
+                Some(_) => {}
+                None => best = Some(k),
+            }
+        }
+        // Only the two left bidding send transactions; the rest drop out first
+        let base_gas = self.config.keepers.gas.base_gas;
+        for k in best.into_iter().chain(runner_up) {
+            self.fee_burn += base_gas;
+            self.keepers[k].gas_spent += base_gas;
+        }
+        let contested = auction.winner.is_some() || bidders.len() > 1;
+        if dent {
+            // Lots shrink to what still clears the keepers' threshold
+            auction.lot = ((auction.tab + threshold) / self.eth_price).min(next_lot);
+        } else if contested {
+            // Bidding climbs until the runner-up drops out
+            let runner_up_capital = runner_up.map_or(f64::INFINITY, |k| rank(&self.keepers, k).0);
+            let limit = (auction.lot * self.eth_price - threshold).min(runner_up_capital).min(auction.tab);
+            auction.bid = limit.max(next_bid);
+        } else {
+            auction.bid = next_bid;
+        }
+        auction.winner = best;
+        auction.bids += 1;
+        auction.last_bid = self.block;
+    }
+
+    /// Settles `auction` once its bid expired or its time ran out, or
+    /// restarts it when nobody bid. Returns whether it settled.
+    fn settle(
+        &mut self,
+        auction: &mut Flip,
+        flip: FlipAuction,
+        liquidated: &mut Vec<usize>,
+        inventory: &mut Vec<(usize, f64)>,
+        round: &mut FlipRound,
+    ) -> bool {
+        let expired = self.block >= auction.last_bid + flip.bid_blocks;
+        let ended = self.block >= auction.kicked + flip.auction_blocks;
+        let Some(winner) = auction.winner else {
+            if ended {
+                auction.kicked = self.block;
+                self.flips.restarts += 1;
+            }
+            return false;
+        };
+        if !(auction.bids > 0 && expired || ended) {
+            return false;
+        }
+
+        let idx = auction.cdp;
+        let debt = auction.debt;
+        let lot = auction.lot.min(self.cdps[idx].collateral_eth());
+        let shortfall = self.cdps[idx].shortfall(self.eth_price, self.config.min_collateral_ratio);
+        // Whatever a bid raises beyond the debt is the protocol's penalty
+        let keeper_profit = lot * self.eth_price - auction.bid;
+        let surplus = (auction.bid - debt).max(0.0);
+        let deficit = (debt - auction.bid).max(0.0);
+        let keeper = &mut self.keepers[winner];
+        keeper.total_profit += keeper_profit;
+        keeper.liquidations += 1;
+        keeper.free_capital -= auction.bid;
+        inventory.push((winner, lot));
+        self.protocol_revenue += surplus;
+        self.flip_bad_debt += deficit;
+        self.borrower_loss += (lot * self.eth_price - debt).max(0.0);
+        self.ledger.seized_collateral += lot;
+        self.ledger.debt_repaid += auction.bid.min(debt);
+        self.ledger.liquidation_shortfall += deficit;
+        self.ledger.liquidated_debt += debt;
+        self.ledger.penalties += keeper_profit + surplus;
+        if let Some(log) = &mut self.liquidation_log {
+            log.push(LiquidationEvent {
+                block: self.block,
+                cdp_id: self.cdps[idx].id,
+                liquidator: Some(self.keepers[winner].id),
+                profit: keeper_profit + surplus,
+                eth_sold: lot,
+                price: self.eth_price,
+            });
+        }
+        self.liquidation_delay += self.liquidatable_since[idx].map_or(0, |since| self.block - since) as f64;
+        // The borrower keeps the collateral the lot bids handed back, debt-free
+        self.cdps[idx].seize(lot, debt);
+        self.index.update(idx, &self.cdps[idx]);
+        self.retry_at[idx] = self.block;
+        liquidated.push(idx);
+
+        self.flips.settled += 1;
+        self.flips.zero_bids += usize::from(auction.bids == 1 && auction.bid == 0.0);
+        self.flips.raised += auction.bid;
+        self.flips.tab += auction.tab;
+        round.eth_sold += lot;
+        round.penalties += keeper_profit + surplus;
+        round.protocol_fees += surplus;
+        round.covered += shortfall;
+        true
+    }
+
+    pub fn run(&mut self, rng: &mut impl Rng) -> CascadeResult {
+        self.run_with(rng, RunHooks::default())
+    }
+
+    /// The book as it stands, liquidated CDPs included.
+    pub fn cdps(&self) -> &[CDP] {
+        &self.cdps
+    }
+
+    /// The keepers, with their tallies so far.
+    pub fn keepers(&self) -> &[Keeper] {
+        &self.keepers
+    }
+
+    /// One liquidation round at the current price, without a price shock or
+    /// advancing the block, returning the CDPs liquidated. The hot loop of
+    /// every run, exposed for the benchmarks.
+    #[doc(hidden)]
+    pub fn liquidation_round(&mut self, rng: &mut impl Rng) -> usize {
+        self.run_liquidation_round(rng, None).liquidated
+    }
+
+    /// Runs to completion with per-block event recording enabled.
+    pub fn run_with_events(&mut self, rng: &mut impl Rng) -> (CascadeResult, Vec<BlockEvent>) {
+        self.events = Some(Vec::new());
+        let result = self.run(rng);
+        (result, self.events.take().unwrap_or_default())
+    }
+
+    /// Runs to completion, recording every liquidation.
+    pub fn run_with_liquidation_log(&mut self, rng: &mut impl Rng) -> (CascadeResult, Vec<LiquidationEvent>) {
+        self.liquidation_log = Some(Vec::new());
+        let result = self.run(rng);
+        (result, self.liquidation_log.take().unwrap_or_default())
+    }
+
+    /// Runs to completion, calling `observer` after every block.
+    pub fn run_observed(&mut self, rng: &mut impl Rng, mut observer: impl FnMut(&LiveState)) -> CascadeResult {
+        self.run_with(
+            rng,
+            RunHooks {
+                observer: Some(&mut observer),
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Runs to completion with an external keeper strategy competing against
+    /// the simulated keepers, returning the strategy's ledger.
+    pub fn run_with_strategy(
+        &mut self,
+        rng: &mut impl Rng,
+        strategy: &mut dyn KeeperStrategy,
+    ) -> (CascadeResult, KeeperLedger) {
+        let result = self.run_with(
+            rng,
+            RunHooks {
+                strategy: Some(strategy),
+                ..Default::default()
+            },
+        );
+        (result, std::mem::take(&mut self.external))
+    }
+
+    /// `run_with_strategy`, also calling `observer` after every block.
+    pub fn run_with_strategy_observed(
+        &mut self,
+        rng: &mut impl Rng,
+        strategy: &mut dyn KeeperStrategy,
+        mut observer: impl FnMut(&LiveState),
+    ) -> (CascadeResult, KeeperLedger) {
+        let result = self.run_with(
+            rng,
+            RunHooks {
+                observer: Some(&mut observer),
+                strategy: Some(strategy),
+            },
+        );
+        (result, std::mem::take(&mut self.external))
+    }
+
+    fn run_with(&mut self, rng: &mut impl Rng, mut hooks: RunHooks<'_>) -> CascadeResult {
+        let mut consecutive_empty_blocks = 0;
+        let mut max_wave_liquidations = 0;
+        if self.block == 0 {
+            self.warm_up(rng);
+            self.initial_debt = self.cdps.iter().map(|cdp| cdp.debt).sum();
+            self.ledger.initial_collateral = self.cdps.iter().map(|cdp| cdp.collateral_eth()).sum();
+        }
+        
+        let accrual = self
+            .config
+            .accruing_collateral
+            .as_ref()
+            .map(|token| (token.growth(1.0 / BLOCKS_PER_YEAR), token.accounting));
+        
+        while self.block < self.config.max_blocks {
+            if let Some((growth, accounting)) = accrual {
+                for (i, cdp) in self.cdps.iter_mut().enumerate() {
+                    let before = cdp.collateral_eth();
+                    cdp.accrue(growth, accounting);
+                    if !cdp.is_liquidated {
+                        self.ledger.accrued_collateral += cdp.collateral_eth() - before;
+                    }
+                    self.index.update(i, cdp);
+                }
+            }
+            let price_start = self.eth_price;
+            self.apply_price_shock(rng);
+            let price_after_shock = self.eth_price;
+            
+            let round = self.run_liquidation_round(rng, hooks.strategy.as_deref_mut());
+            let liquidations = round.liquidated;
+            if self.insurance.is_some() {
+                let bad_debt = self.calculate_bad_debt();
+                if let Some(insurance) = &mut self.insurance {
+                    insurance.close_block(self.block, round.protocol_fees, bad_debt);
+                }
+            }
+            if hooks.strategy.is_some() {
+                let pnl = self.external.net_pnl();
+                self.external.pnl_by_block.push(pnl);
+            }
+            if self.events.is_some() || hooks.observer.is_some() {
+                let event = BlockEvent {
+                    block: self.block,
+                    price_start,
+                    price_after_shock,
+                    price_end: self.eth_price,
+                    liquidatable: round.liquidatable,
+                    liquidated: liquidations,
+                    eth_sold: round.eth_sold,
+                    bad_debt: self.calculate_bad_debt(),
+                    penalties: round.penalties,
+                    protocol_fees: round.protocol_fees,
+                    max_payout_share: round.max_payout_share,
+                    neighbor_eth_sold: round.neighbor_eth_sold,
+                    token_price: self.config.reward_token.as_ref().map(|_| self.token_price),
+                    insurance_fund: self.insurance.as_ref().map(|insurance| insurance.balance),
+                };
+                if let Some(observer) = hooks.observer.as_mut() {
+                    observer(&LiveState {
+                        event: event.clone(),
+                        keeper_profits: self.keepers.iter().map(|k| k.total_profit).collect(),
+                        keeper_capital: self.keepers.iter().map(Keeper::net_worth).collect(),
+                        liquidatable_cdps: self.round.liquidatable.clone(),
+                        liquidated_cdps: self.round.liquidated.clone(),
+                        collateral_ratios: self
+                            .cdps
+                            .iter()
+                            .filter(|cdp| !cdp.is_liquidated)
+                            .map(|cdp| cdp.collateral_ratio(self.eth_price))
+                            .collect(),
+                        total_liquidations: self.total_liquidations + liquidations,
+                        protocol_revenue: self.protocol_revenue,
+                    });
+                }
+                if let Some(events) = &mut self.events {
+                    events.push(event);
+                }
+            }
+            trace!(
+                block = self.block,
+                price = self.eth_price,
+                liquidatable = round.liquidatable,
+                liquidated = liquidations,
+                "block"
+            );
+            self.liquidations_per_block.push(liquidations);
+            self.total_liquidations += liquidations;
+            
+            if liquidations > 0 {
+                self.current_wave_liquidations += liquidations;
+                max_wave_liquidations = max_wave_liquidations.max(liquidations);
+                consecutive_empty_blocks = 0;
+            } else {
+                if self.current_wave_liquidations > 0 {
+                    self.cascade_depth += 1;
+                }
+                self.current_wave_liquidations = 0;
+                // Bands still converting keep the run going without liquidating anything
+                consecutive_empty_blocks = if round.conversions > 0 { 0 } else { consecutive_empty_blocks + 1 };
+                
+                // Commits and grace periods begun before a quiet spell run out before it ends the run
+                let patience = 5
+                    + self.config.commit_reveal.map_or(0, |reveal| reveal.reveal_blocks)
+                    + self.config.grace_period.map_or(0, |grace| grace.grace_blocks);
+                if consecutive_empty_blocks >= patience && self.block > 10 {
+                    break;
+                }
+            }
+            if let Some(treasury) = &mut self.treasury {
+                if (self.block + 1).is_multiple_of(treasury.drip.epoch_blocks) {
+                    treasury.close_epoch(&mut self.keepers);
+                }
+            }
+            
+            self.block += 1;
+        }
+        
+        self.total_bad_debt = self.calculate_bad_debt();
+        debug!(
+            bad_debt = self.total_bad_debt,
+            liquidations = self.total_liquidations,
+            cascade_depth = self.cascade_depth,
+            blocks = self.block,
+            "run finished"
+        );
+        
+        let keeper_profits: Vec<f64> = self.keepers.iter().map(|k| k.total_profit).collect();
+        let total_profit: f64 = keeper_profits.iter().sum();
+        
+        let profit_concentration = profit_concentration(&keeper_profits, total_profit);
+        
+        let participation_rate = self.keepers.iter()
+            .filter(|k| k.liquidations > 0)
+            .count() as f64 / self.keepers.len() as f64;
+        
+        let price_drop = 1.0 - (self.eth_price / self.initial_price);
+        
+        let unliquidated_underwater: usize = self.cdps.iter()
+            .filter(|cdp| cdp.is_underwater(self.eth_price) && !cdp.is_liquidated)
+            .count();
+        
+        let insurance = self.insurance.as_ref().map(|insurance| insurance.outcome(self.total_bad_debt));
+        let hole = self.total_bad_debt - insurance.map_or(0.0, |insurance| insurance.absorbed);
+        let opening_price = self.config.reward_token.as_ref().map_or(1.0, |_| self.token_price);
+        let debt_auction = self.config.debt_auction.map(|auction| auction.recapitalize(hole, opening_price));
+
+        let ledger = RunLedger {
+            remaining_collateral: self.cdps.iter().filter(|cdp| !cdp.is_liquidated).map(|cdp| cdp.collateral_eth()).sum(),
+            liquidated_debt: self.ledger.liquidated_debt
+                + self.cdps.iter().filter(|cdp| cdp.is_liquidated).map(|cdp| cdp.debt).sum::<f64>(),
+            incentives_paid: self.incentives_paid,
+            keeper_profit: total_profit,
+            external_revenue: self.external.revenue,
+            protocol_revenue: self.protocol_revenue,
+            treasury_net: self.treasury.as_ref().map_or(0.0, |t| t.balance - t.drip.endowment),
+            ..self.ledger.clone()
+        };
+        
         CascadeResult {
             mechanism: self.mechanism,
             scenario: self.scenario,
             cascade_depth: self.cascade_depth,
             total_liquidations: self.total_liquidations,
-            bad_debt: self.total_bad_debt,
+            bad_debt: debt_auction.map_or(hole, |auction| auction.uncovered),
             blocks_to_stability: self.block,
             final_price: self.eth_price,
             price_drop_pct: price_drop * 100.0,
@@ -376,62 +4342,916 @@ impl CascadeSimulation {
             participation_rate,
             unliquidated_underwater,
             max_liquidations_per_block: *self.liquidations_per_block.iter().max().unwrap_or(&0),
+            protocol_revenue: self.protocol_revenue,
+            neighbor_eth_sold: self.neighbor_eth_sold,
+            capital_diverted: self.capital_diverted / self.liquidations_per_block.len().max(1) as f64,
+            tokens_emitted: self.tokens_emitted,
+            incentives_paid: self.incentives_paid,
+            fee_burn: self.fee_burn,
+            execution_failures: self.execution_failures,
+            avg_liquidation_delay: self.liquidation_delay / self.total_liquidations.max(1) as f64,
+            exposure: self.exposure,
+            missed_exposure: self.missed_exposure,
+            ledger,
+            backstop_blocks: self.backstop_blocks,
+            backstop_liquidations: self.backstop_liquidations,
+            borrower_loss: self.borrower_loss,
+            governance_detected_at: self.governance_detected_at,
+            // A change still pending at the end leaves the whole rest of the run exposed
+            governance_window_bad_debt: match self.governance_detected_at {
+                Some(_) => self.governance_window_bad_debt.unwrap_or(self.total_bad_debt - self.detection_bad_debt),
+                None => 0.0,
+            },
+            rotation_outages: self.rotation_outages,
+            final_token_price: self.config.reward_token.as_ref().map(|_| self.token_price),
+            seed: None,
+            compute: RunCompute::default(),
+            participation_threshold: self.config.keepers.threshold(self.mechanism),
+            avg_participants: self.participation.mean(),
+            participants_cv: self.participation.cv(),
+            treasury: self.treasury.as_ref().map(Treasury::outcome),
+            insurance,
+            debt_auction,
+            staking: self.config.keeper_staking.map(|_| self.staking),
+            reputation: self.config.keeper_reputation.map(|_| self.reputation_outcome()),
+            commit_reveal: self.config.commit_reveal.map(|_| self.reveals),
+            grace: self.config.grace_period.map(|_| self.grace),
+            shock_decline: self.shock_decline,
+            impact_decline: self.impact_decline,
+            flips: matches!(self.mechanism, LiquidationMechanism::EnglishAuction(_))
+                .then_some(FlipOutcome { open: self.auctions.len(), ..self.flips }),
+            soft: matches!(self.mechanism, LiquidationMechanism::SoftLiquidation(_)).then_some(self.soft.outcome),
+            batches: matches!(self.mechanism, LiquidationMechanism::BatchAuction(_)).then_some(self.batches),
+        }
+    }
+}
+
+/// Assembles a `CascadeSimulation` from caller-supplied parts. Anything left
+/// unset is drawn from the config exactly as the stock runners do.
+///
+/// ```
+/// use fair_simulation::cascade::{CascadeSimulationBuilder, Keeper, LiquidationMechanism, CDP};
+///
+/// let mut rng = rand::thread_rng();
+/// let mut sim = CascadeSimulationBuilder::new()
+///     .mechanism(LiquidationMechanism::keeper_pool())
+///     .cdps(vec![CDP::from_position(0, 10.0, 12_000.0)])
+///     .keepers(vec![Keeper::new(0, 50_000.0, 0.5)])
+///     .price_path(vec![2000.0, 1700.0, 1600.0])
+///     .build(&mut rng)
+///     .unwrap();
+/// let result = sim.run(&mut rng);
+/// assert_eq!(result.total_liquidations, 1);
+/// ```
+#[derive(Default)]
+pub struct CascadeSimulationBuilder {
+    config: SimulationConfig,
+    mechanism: Option<LiquidationMechanism>,
+    scenario: Option<PriceScenario>,
+    cdps: Option<Vec<CDP>>,
+    keepers: Option<Vec<Keeper>>,
+    eth_price: Option<f64>,
+    price_path: Option<Vec<f64>>,
+    shocks: Option<Box<dyn PricePath>>,
+    manipulation: Option<(f64, usize)>,
+    neighbor_cdps: Option<Vec<CDP>>,
+}
+
+impl CascadeSimulationBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn config(mut self, config: SimulationConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Defaults to the keeper pool at the 70/30 split.
+    pub fn mechanism(mut self, mechanism: LiquidationMechanism) -> Self {
+        self.mechanism = Some(mechanism);
+        self
+    }
+
+    /// Defaults to `FlashCrash`. Only labels the result when a price path or
+    /// custom shocks are set.
+    pub fn scenario(mut self, scenario: PriceScenario) -> Self {
+        self.scenario = Some(scenario);
+        self
+    }
+
+    pub fn cdps(mut self, cdps: Vec<CDP>) -> Self {
+        self.cdps = Some(cdps);
+        self
+    }
+
+    pub fn keepers(mut self, keepers: Vec<Keeper>) -> Self {
+        self.keepers = Some(keepers);
+        self
+    }
+
+    /// Opening oracle price; defaults to `config.initial_eth_price`.
+    pub fn eth_price(mut self, eth_price: f64) -> Self {
+        self.eth_price = Some(eth_price);
+        self
+    }
+
+    /// Exogenous price per block, replacing the scenario's shocks. The first
+    /// entry is the opening price; moves apply as returns on top of
+    /// liquidation impact, and the price holds once the path runs out.
+    pub fn price_path(mut self, price_path: Vec<f64>) -> Self {
+        self.price_path = Some(price_path);
+        self
+    }
+
+    /// Custom per-block shocks (see `price_path`), replacing the scenario's.
+    /// The run opens at `eth_price` or the config's initial price.
+    pub fn shocks(mut self, shocks: impl PricePath + 'static) -> Self {
+        self.shocks = Some(Box::new(shocks));
+        self
+    }
+
+    /// A manipulator dumping `eth_per_block` on spot for the first `blocks`
+    /// blocks, moving the price through the same impact model as liquidations.
+    pub fn manipulation(mut self, eth_per_block: f64, blocks: usize) -> Self {
+        self.manipulation = Some((eth_per_block, blocks));
+        self
+    }
+
+    /// Book of the neighboring protocol in `config.neighbor`; drawn from the
+    /// config after the keepers when unset.
+    pub fn neighbor_cdps(mut self, cdps: Vec<CDP>) -> Self {
+        self.neighbor_cdps = Some(cdps);
+        self
+    }
+
+    pub fn build(self, rng: &mut impl Rng) -> Result<CascadeSimulation> {
+        let config = self.config;
+        config.validate()?;
+        let mechanism = self.mechanism.unwrap_or(LiquidationMechanism::keeper_pool());
+        mechanism.validate()?;
+
+        let scripted = self.price_path.map(ScriptedPath::new).transpose()?;
+        if scripted.is_some() {
+            if self.eth_price.is_some() {
+                return Err(Error::Invalid(
+                    "set either eth_price or price_path; the path opens at its first price".to_string(),
+                ));
+            }
+            if self.shocks.is_some() {
+                return Err(Error::Invalid("set either shocks or price_path".to_string()));
+            }
+        }
+        let eth_price = match (&scripted, self.eth_price) {
+            (Some(path), _) => path.opening_price(),
+            (None, Some(price)) if price > 0.0 => price,
+            (None, Some(_)) => return Err(Error::Invalid("eth_price must be positive".to_string())),
+            (None, None) => config.initial_eth_price,
+        };
+
+        let cdps = match self.cdps {
+            Some(cdps) if cdps.is_empty() => return Err(Error::Invalid("cdps must not be empty".to_string())),
+            Some(cdps) => cdps,
+            None => config.synthetic_book(eth_price, rng),
+        };
+        let keepers = match self.keepers {
+            Some(keepers) if keepers.is_empty() => {
+                return Err(Error::Invalid("keepers must not be empty".to_string()))
+            }
+            Some(keepers) => keepers,
+            None => config.keepers.population(rng),
+        };
+        let neighbor_cdps = match (&config.neighbor, self.neighbor_cdps) {
+            (Some(_), Some(cdps)) => cdps,
+            (Some(neighbor), None) => neighbor.book(eth_price, rng),
+            (None, Some(_)) => {
+                return Err(Error::Invalid("neighbor_cdps needs a neighbor in the config".to_string()))
+            }
+            (None, None) => Vec::new(),
+        };
+
+        let mut sim = CascadeSimulation::assemble(
+            RunArena { cdps, keepers, ..RunArena::take() },
+            eth_price,
+            mechanism,
+            self.scenario.unwrap_or(PriceScenario::FlashCrash),
+            &config,
+        );
+        match (scripted, self.shocks) {
+            (Some(path), _) => sim.shocks = Box::new(path),
+            (None, Some(shocks)) => sim.shocks = shocks,
+            (None, None) => {}
+        }
+        sim.neighbor_cdps = neighbor_cdps;
+        if let Some((eth_per_block, blocks)) = self.manipulation {
+            if !(eth_per_block.is_finite() && eth_per_block >= 0.0) {
+                return Err(Error::Invalid("manipulation must dump a non-negative amount".to_string()));
+            }
+            sim.manipulation_eth = eth_per_block;
+            sim.manipulation_blocks = blocks;
+        }
+        sim.seed_streams(rng);
+        Ok(sim)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CascadeResult {
+    pub mechanism: LiquidationMechanism,
+    pub scenario: PriceScenario,
+    pub cascade_depth: usize,
+    pub total_liquidations: usize,
+    pub bad_debt: f64,
+    pub blocks_to_stability: usize,
+    pub final_price: f64,
+    pub price_drop_pct: f64,
+    pub profit_concentration: f64,
+    pub participation_rate: f64,
+    pub unliquidated_underwater: usize,
+    pub max_liquidations_per_block: usize,
+    pub protocol_revenue: f64, // Penalty share retained by the protocol
+    pub neighbor_eth_sold: f64, // Collateral dumped by a neighboring protocol
+    pub capital_diverted: f64,  // Mean per-block share of keeper capital committed to rivals
+    pub tokens_emitted: f64,    // Reward tokens paid to keepers, in USD at par
+    pub incentives_paid: f64,   // Incentive program bonuses paid to keepers
+    pub fee_burn: f64,          // Gas and priority fees spent by the simulated keepers
+    pub execution_failures: usize, // Selected pool executors that failed to land
+    pub avg_liquidation_delay: f64, // Blocks liquidated CDPs spent liquidatable first
+    pub ledger: RunLedger,          // Tallied flows; see `audit::ConservationReport`
+    pub backstop_blocks: usize,     // Blocks run under the switch policy's backstop
+    pub backstop_liquidations: usize,
+    pub borrower_loss: f64,         // Collateral value seized beyond the debt repaid
+    pub rotation_outages: usize,    // Blocks with CDPs liquidatable and the whole active set offline
+    pub final_token_price: Option<f64>, // Reward token price relative to par at the end
+    pub seed: Option<u64>,      // Per-run seed when run by a campaign
+    #[serde(default)]
+    pub compute: RunCompute, // Measured when run by a campaign
+    #[serde(default)]
+    pub participation_threshold: f64, // Keeper-side payout the keepers acted on, `KeeperConfig::threshold`
+    #[serde(default)]
+    pub exposure: f64, // Dollar-blocks: liquidatable CDPs' shortfalls below the minimum ratio, summed over blocks
+    #[serde(default)]
+    pub missed_exposure: f64, // The part of `exposure` on CDPs left unliquidated that block
+    #[serde(default)]
+    pub governance_detected_at: Option<usize>, // Block the governance timelock flagged the configuration
+    #[serde(default)]
+    pub governance_window_bad_debt: f64, // Bad debt accrued between detection and the change executing
+    #[serde(default)]
+    pub avg_participants: f64, // Keepers joining each attempted liquidation
+    #[serde(default)]
+    pub participants_cv: f64, // Coefficient of variation of the per-block mean of `avg_participants`
+    #[serde(default)]
+    pub treasury: Option<TreasuryOutcome>, // With `config.treasury_drip`
+    #[serde(default)]
+    pub insurance: Option<InsuranceOutcome>, // With `config.insurance_fund`
+    #[serde(default)]
+    pub debt_auction: Option<DebtAuctionOutcome>, // With `config.debt_auction`
+    #[serde(default)]
+    pub staking: Option<StakingOutcome>, // With `config.keeper_staking`
+    #[serde(default)]
+    pub reputation: Option<ReputationOutcome>, // With `config.keeper_reputation`
+    #[serde(default)]
+    pub commit_reveal: Option<CommitRevealOutcome>, // With `config.commit_reveal`
+    #[serde(default)]
+    pub grace: Option<GraceOutcome>, // With `config.grace_period`
+    #[serde(default)]
+    pub shock_decline: f64, // Log price decline from the scenario's moves
+    #[serde(default)]
+    pub impact_decline: f64, // Log price decline from ETH sold: liquidations, a neighbor's and a manipulator's
+    #[serde(default)]
+    pub flips: Option<FlipOutcome>, // Under `LiquidationMechanism::EnglishAuction`
+    #[serde(default)]
+    pub soft: Option<SoftOutcome>,  // Under `LiquidationMechanism::SoftLiquidation`
+    #[serde(default)]
+    pub batches: Option<BatchOutcome>, // Under `LiquidationMechanism::BatchAuction`
+}
+
+/// End state of a run's keeper treasury.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TreasuryOutcome {
+    pub paid: f64,           // Dripped to keepers
+    pub balance: f64,        // Held at the end, unpaid claims included
+    pub min_balance: f64,    // Lowest balance after an epoch closed
+    pub short_epochs: usize, // Epochs whose drip exceeded the balance
+    pub final_rate: f64,     // Paid per dollar claimed in the last epoch that paid out
+}
+
+/// End state of a run's insurance fund.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct InsuranceOutcome {
+    pub inflow: f64,                 // Paid in from penalties
+    pub absorbed: f64,               // Bad debt covered at the end
+    pub balance: f64,                // Left after absorbing
+    pub gross_bad_debt: f64,         // Before absorption; `CascadeResult::bad_debt` is what the backstops left
+    pub min_headroom: f64,           // Lowest balance net of the book's bad debt; negative once exhausted
+    pub exhausted_at: Option<usize>, // First block bad debt exceeded the balance
+}
+
+/// Keeper stake bonded and slashed over a run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct StakingOutcome {
+    pub bonded: f64,      // USD staked at the start
+    pub slashed: f64,     // USD slashed to the protocol
+    pub slashings: usize, // Assigned liquidations left unexecuted
+    pub ejected: usize,   // Keepers slashed below `min_stake`
+}
+
+/// Reputation-weighted executor draws and where keepers' scores ended a run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReputationOutcome {
+    pub draws: usize,       // Executors drawn by reputation
+    pub avg_score: f64,     // Over keepers, at the end of the run
+    pub discredited: usize, // Keepers ending with a score below one half
+}
+
+/// Keeper pool commits and what became of their reveals over a run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct CommitRevealOutcome {
+    pub commits: usize,  // Commits opened on a CDP
+    pub revealed: usize, // Reveals at least one member made
+    pub lapsed: usize,   // Reveals no member made; the CDP is committed to afresh
+    pub missed: usize,   // Members that missed their reveal
+    pub dropped: usize,  // Members no longer willing to liquidate at the reveal's price
+}
+
+/// CDPs that entered a grace period and how their owners responded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct GraceOutcome {
+    pub entered: usize,  // Grace periods begun
+    pub rescued: usize,  // Topped up back above the minimum
+    pub expired: usize,  // Still below the minimum when grace ended
+    pub topped_up: f64,  // USD of collateral owners added
+}
+
+/// Debt auctions held at the end of a run.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DebtAuctionOutcome {
+    pub auctions: usize,
+    pub raised: f64,      // USD of bad debt recapitalized
+    pub dilution: f64,    // Tokens minted as a share of the supply
+    pub final_price: f64, // Token price relative to par after the last auction
+    pub uncovered: f64,   // Bad debt left once `max_dilution` was reached
+}
+
+/// Batch auction tallies over a run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct BatchOutcome {
+    pub batches: usize,  // Blocks whose batch settled
+    pub settled: usize,  // CDPs closed in them
+    pub bids: usize,     // Bid transactions sent to settled batches
+    pub unfilled: usize, // CDPs batched but beyond what the fills reached, summed over blocks
+    pub debt: f64,       // USD of debt repaid
+    pub discount: f64,   // USD of collateral winners took beyond it
+}
+
+/// English auction tallies over a run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct FlipOutcome {
+    pub kicked: usize,    // CDPs bitten into an auction
+    pub settled: usize,
+    pub zero_bids: usize, // Settled on a lone opening bid of zero
+    pub restarts: usize,  // Auctions that ended without a bid and started over
+    pub open: usize,      // Still running at the end
+    pub raised: f64,      // DAI bid by winners of settled auctions
+    pub tab: f64,         // DAI those auctions set out to raise
+}
+
+impl FlipOutcome {
+    /// Share of the settled auctions' tab their winning bids raised.
+    pub fn recovery(&self) -> f64 {
+        if self.tab > 0.0 {
+            self.raised / self.tab
+        } else {
+            1.0
+        }
+    }
+
+    /// Share of settled auctions won with a zero bid.
+    pub fn zero_bid_share(&self) -> f64 {
+        self.zero_bids as f64 / self.settled.max(1) as f64
+    }
+}
+
+impl CascadeResult {
+    /// Coverage weighted by how far below the minimum ratio each CDP was
+    /// and for how long: one large vault missed for 50 blocks costs more
+    /// than a few dust positions missed briefly. 1 when nothing was
+    /// liquidatable.
+    pub fn weighted_coverage(&self) -> f64 {
+        if self.exposure > 0.0 {
+            1.0 - self.missed_exposure / self.exposure
+        } else {
+            1.0
+        }
+    }
+
+    /// The run's log price decline over the scenario's alone: 1 when
+    /// selling moved nothing, above 1 when liquidations amplified the fall.
+    /// None when the scenario did not push the price down.
+    pub fn amplification(&self) -> Option<f64> {
+        (self.shock_decline > 0.0).then(|| (self.shock_decline + self.impact_decline) / self.shock_decline)
+    }
+}
+
+/// Span covering a campaign of `runs` cascades; `run_seeded` opens a run
+/// span per cascade inside whichever span is current.
+pub fn campaign_span(mechanism: LiquidationMechanism, scenario: PriceScenario, runs: usize) -> Span {
+    info_span!("campaign", scenario = scenario.name(), mechanism = %mechanism.name(), runs)
+}
+
+/// Lazily runs `runs` cascades, each on its own seed drawn from the
+/// campaign RNG.
+fn seeded_runs<'a>(
+    runs: usize,
+    config: &SimulationConfig,
+    mut run: impl FnMut(&mut CountingRng<RunRng>) -> CascadeResult + 'a,
+) -> impl Iterator<Item = CascadeResult> + 'a {
+    let mut rng = config.rng();
+    let kind = config.rng_kind;
+    (0..runs).map(move |index| {
+        let seed = rng.gen();
+        let _run = debug_span!("run", index, seed).entered();
+        let (mut result, compute) = measure(kind.seeded(seed), &mut run);
+        result.seed = Some(seed);
+        result.compute = compute;
+        result
+    })
+}
+
+/// Runs `runs` cascades in parallel, each on its own seed drawn from the
+/// campaign RNG. Seeds are drawn up front and results kept in seed order,
+/// so a campaign is identical whatever the thread count.
+fn par_seeded<T: Send>(
+    runs: usize,
+    config: &SimulationConfig,
+    run: impl Fn(&mut CountingRng<RunRng>) -> (CascadeResult, T) + Sync,
+) -> Vec<(CascadeResult, T)> {
+    par_runs(&campaign_seeds(runs, config), 0, config, run)
+}
+
+/// The per-run seeds of a campaign of `runs`, in run order.
+fn campaign_seeds(runs: usize, config: &SimulationConfig) -> Vec<u64> {
+    let mut rng = config.rng();
+    (0..runs).map(|_| rng.gen()).collect()
+}
+
+/// Runs the cascades seeded by `seeds` in parallel, the first being run
+/// `first` of its campaign.
+fn par_runs<T: Send>(
+    seeds: &[u64],
+    first: usize,
+    config: &SimulationConfig,
+    run: impl Fn(&mut CountingRng<RunRng>) -> (CascadeResult, T) + Sync,
+) -> Vec<(CascadeResult, T)> {
+    // Workers log to the caller's subscriber, inside its campaign span
+    let dispatch = tracing::dispatcher::get_default(|dispatch| dispatch.clone());
+    let campaign = Span::current();
+    seeds
+        .par_iter()
+        .enumerate()
+        .map(|(offset, &seed)| {
+            let index = first + offset;
+            tracing::dispatcher::with_default(&dispatch, || {
+                let _run = debug_span!(parent: &campaign, "run", index, seed).entered();
+                let ((mut result, extra), compute) = measure(config.run_rng(seed), &run);
+                result.seed = Some(seed);
+                result.compute = compute;
+                (result, extra)
+            })
+        })
+        .collect()
+}
+
+fn log_campaign(results: &[CascadeResult]) {
+    let bad_debts: Vec<f64> = results.iter().map(|r| r.bad_debt).collect();
+    let compute = ComputeSummary::from_runs(results.iter().map(|r| &r.compute));
+    info!(
+        runs = results.len(),
+        mean_bad_debt = crate::stats::mean(&bad_debts),
+        max_bad_debt = bad_debts.iter().copied().fold(0.0, f64::max),
+        run_secs = compute.wall_secs,
+        max_run_secs = compute.max_wall_secs,
+        rng_draws = compute.rng_draws,
+        allocations = compute.allocations,
+        "campaign finished"
+    );
+}
+
+/// Runs `runs` cascades across the rayon pool, each on its own seed drawn
+/// from the campaign RNG.
+pub(crate) fn run_seeded(
+    runs: usize,
+    config: &SimulationConfig,
+    run: impl Fn(&mut CountingRng<RunRng>) -> CascadeResult + Sync,
+) -> Vec<CascadeResult> {
+    let results: Vec<CascadeResult> = par_seeded(runs, config, |rng| (run(rng), ())).into_iter().map(|(r, _)| r).collect();
+    log_campaign(&results);
+    results
+}
+
+/// Runs per parallel chunk of `stream_seeded`.
+const STREAM_CHUNK: usize = 4096;
+
+/// `run_seeded` handing each run to `each`, in run order, instead of
+/// keeping them: seeds are drawn a chunk at a time and each chunk runs
+/// across the rayon pool, so memory is bounded by the chunk however many
+/// runs there are.
+pub(crate) fn stream_seeded(
+    runs: usize,
+    config: &SimulationConfig,
+    mut each: impl FnMut(CascadeResult),
+    run: impl Fn(&mut CountingRng<RunRng>) -> CascadeResult + Sync,
+) {
+    let mut rng = config.rng();
+    let mut first = 0;
+    while first < runs {
+        let seeds: Vec<u64> = (0..STREAM_CHUNK.min(runs - first)).map(|_| rng.gen()).collect();
+        for (result, ()) in par_runs(&seeds, first, config, |rng| (run(rng), ())) {
+            each(result);
         }
+        first += seeds.len();
+    }
+}
+
+/// `run_seeded` picking up after `done`, the completed first runs of the
+/// same campaign, and handing each further batch of `every` runs to
+/// `checkpoint` as it completes. The campaign RNG is rebuilt from `config.seed` rather than
+/// restored, so the results match an uninterrupted campaign's.
+pub(crate) fn resume_seeded(
+    runs: usize,
+    config: &SimulationConfig,
+    mut done: Vec<CascadeResult>,
+    every: usize,
+    mut checkpoint: impl FnMut(&[CascadeResult]) -> Result<()>,
+    run: impl Fn(&mut CountingRng<RunRng>) -> CascadeResult + Sync,
+) -> Result<Vec<CascadeResult>> {
+    if config.seed.is_none() {
+        return Err(Error::Invalid("resuming a campaign needs a fixed seed".to_string()));
+    }
+    let seeds = campaign_seeds(runs, config);
+    if done.len() > runs || done.iter().zip(&seeds).any(|(result, &seed)| result.seed != Some(seed)) {
+        return Err(Error::Invalid("completed runs are not the start of this campaign".to_string()));
+    }
+    while done.len() < runs {
+        let first = done.len();
+        let batch = &seeds[first..(first + every.max(1)).min(runs)];
+        done.extend(par_runs(batch, first, config, |rng| (run(rng), ())).into_iter().map(|(r, _)| r));
+        checkpoint(&done[first..])?;
+    }
+    log_campaign(&done);
+    Ok(done)
+}
+
+pub fn run_cascade_simulation(
+    mechanism: LiquidationMechanism,
+    scenario: PriceScenario,
+    runs: usize,
+    config: &SimulationConfig,
+) -> Vec<CascadeResult> {
+    let _campaign = campaign_span(mechanism, scenario, runs).entered();
+    run_seeded(runs, config, |rng| CascadeSimulation::new(mechanism, scenario, config, rng).run(rng))
+}
+
+/// `run_cascade_simulation` without keeping the results: each run is handed
+/// to `each` as soon as it completes, so memory stays flat however many
+/// runs there are. Stops at the first error `each` returns.
+pub fn run_cascade_streaming(
+    mechanism: LiquidationMechanism,
+    scenario: PriceScenario,
+    runs: usize,
+    config: &SimulationConfig,
+    mut each: impl FnMut(CascadeResult) -> Result<()>,
+) -> Result<()> {
+    let _campaign = campaign_span(mechanism, scenario, runs).entered();
+    for result in seeded_runs(runs, config, |rng| CascadeSimulation::new(mechanism, scenario, config, rng).run(rng)) {
+        each(result)?;
     }
+    info!(runs, "campaign streamed");
+    Ok(())
+}
+
+/// `run_cascade_simulation` with every run's liquidations, in run order.
+pub fn run_cascade_simulation_with_liquidations(
+    mechanism: LiquidationMechanism,
+    scenario: PriceScenario,
+    runs: usize,
+    config: &SimulationConfig,
+) -> (Vec<CascadeResult>, Vec<Vec<LiquidationEvent>>) {
+    let _campaign = campaign_span(mechanism, scenario, runs).entered();
+    let (results, logs): (Vec<_>, Vec<_>) = par_seeded(runs, config, |rng| {
+        CascadeSimulation::new(mechanism, scenario, config, rng).run_with_liquidation_log(rng)
+    })
+    .into_iter()
+    .unzip();
+    log_campaign(&results);
+    (results, logs)
+}
+
+/// Replays one run of `run_cascade_simulation` from its seed.
+pub fn replay(
+    mechanism: LiquidationMechanism,
+    scenario: PriceScenario,
+    config: &SimulationConfig,
+    seed: u64,
+) -> CascadeResult {
+    let _run = debug_span!("run", seed).entered();
+    let (mut result, compute) =
+        measure(config.run_rng(seed), |rng| CascadeSimulation::new(mechanism, scenario, config, rng).run(rng));
+    result.seed = Some(seed);
+    result.compute = compute;
+    result
+}
+
+/// Replays one run of `run_cascade_simulation` from its seed, with events.
+pub fn replay_with_events(
+    mechanism: LiquidationMechanism,
+    scenario: PriceScenario,
+    config: &SimulationConfig,
+    seed: u64,
+) -> (CascadeResult, Vec<BlockEvent>) {
+    let _run = debug_span!("run", seed).entered();
+    let (mut result, events) = run_single_with_events(mechanism, scenario, config, &mut config.run_rng(seed));
+    result.seed = Some(seed);
+    (result, events)
+}
+
+/// Replays one run of `run_cascade_simulation` from its seed, with events,
+/// recording the shocks the scenario drew.
+pub fn replay_recording_shocks(
+    mechanism: LiquidationMechanism,
+    scenario: PriceScenario,
+    config: &SimulationConfig,
+    seed: u64,
+) -> (CascadeResult, Vec<BlockEvent>, ShockSequence) {
+    let _run = debug_span!("run", seed).entered();
+    let mut rng = config.run_rng(seed);
+    let mut sim = CascadeSimulation::new(mechanism, scenario, config, &mut rng);
+    sim.shock_log = Some(Vec::new());
+    let (mut result, events) = sim.run_with_events(&mut rng);
+    result.seed = Some(seed);
+    let shocks = ShockSequence { scenario, seed, shocks: sim.shock_log.take().unwrap_or_default() };
+    (result, events, shocks)
+}
+
+/// Runs `mechanism` on a recorded shock sequence instead of drawing the
+/// scenario, with the CDP book and keepers drawn from `seed` (the recorded
+/// run's seed reproduces its book). Mechanism draws such as keeper pool
+/// executor selection still come from `seed`'s stream, which no longer
+/// interleaves with shock draws.
+pub fn replay_on_shocks(
+    mechanism: LiquidationMechanism,
+    shocks: &ShockSequence,
+    config: &SimulationConfig,
+    seed: u64,
+) -> (CascadeResult, Vec<BlockEvent>) {
+    let _run = debug_span!("run", seed).entered();
+    let mut rng = config.run_rng(seed);
+    let mut sim = CascadeSimulation::new(mechanism, shocks.scenario, config, &mut rng);
+    sim.shocks = Box::new(shocks.clone());
+    let (mut result, events) = sim.run_with_events(&mut rng);
+    result.seed = Some(seed);
+    (result, events)
+}
+
+/// Replays one run of `run_cascade_simulation` from its seed, calling
+/// `observer` after every block.
+pub fn replay_observed(
+    mechanism: LiquidationMechanism,
+    scenario: PriceScenario,
+    config: &SimulationConfig,
+    seed: u64,
+    observer: impl FnMut(&LiveState),
+) -> CascadeResult {
+    let _run = debug_span!("run", seed).entered();
+    let mut result = run_single_observed(mechanism, scenario, config, &mut config.run_rng(seed), observer);
+    result.seed = Some(seed);
+    result
 }
 
-#[derive(Debug, Clone)]
-pub struct CascadeResult {
-    pub mechanism: LiquidationMechanism,
-    pub scenario: PriceScenario,
-    pub cascade_depth: usize,
-    pub total_liquidations: usize,
-    pub bad_debt: f64,
-    pub blocks_to_stability: usize,
-    pub final_price: f64,
-    pub price_drop_pct: f64,
-    pub profit_concentration: f64,
-    pub participation_rate: f64,
-    pub unliquidated_underwater: usize,
-    pub max_liquidations_per_block: usize,
+/// Runs a single cascade with per-block event recording enabled.
+pub fn run_single_with_events(
+    mechanism: LiquidationMechanism,
+    scenario: PriceScenario,
+    config: &SimulationConfig,
+    rng: &mut impl Rng,
+) -> (CascadeResult, Vec<BlockEvent>) {
+    CascadeSimulation::new(mechanism, scenario, config, rng).run_with_events(rng)
 }
 
-pub fn run_cascade_simulation(
+/// Runs a single cascade, calling `observer` after every block. Used by
+/// live front-ends such as the TUI dashboard.
+pub fn run_single_observed(
+    mechanism: LiquidationMechanism,
+    scenario: PriceScenario,
+    config: &SimulationConfig,
+    rng: &mut impl Rng,
+    observer: impl FnMut(&LiveState),
+) -> CascadeResult {
+    CascadeSimulation::new(mechanism, scenario, config, rng).run_observed(rng, observer)
+}
+
+/// Runs a single cascade with an external keeper strategy competing against
+/// the simulated keeper population.
+pub fn run_single_with_strategy(
+    mechanism: LiquidationMechanism,
+    scenario: PriceScenario,
+    config: &SimulationConfig,
+    rng: &mut impl Rng,
+    strategy: &mut dyn KeeperStrategy,
+) -> (CascadeResult, KeeperLedger) {
+    CascadeSimulation::new(mechanism, scenario, config, rng).run_with_strategy(rng, strategy)
+}
+
+/// Runs the cascade starting from a given CDP book and oracle price instead of
+/// the synthetic initial state. Keepers are still drawn randomly per run.
+pub(crate) fn run_cascade_from_state(
     mechanism: LiquidationMechanism,
     scenario: PriceScenario,
+    cdps: &[CDP],
+    eth_price: f64,
     runs: usize,
+    config: &SimulationConfig,
 ) -> Vec<CascadeResult> {
-    let mut rng = rand::thread_rng();
-    
-    (0..runs)
-        .map(|_| {
-            let mut sim = CascadeSimulation::new(mechanism, scenario, &mut rng);
-            sim.run(&mut rng)
-        })
-        .collect()
+    let _campaign = campaign_span(mechanism, scenario, runs).entered();
+    run_seeded(runs, config, |rng| {
+        let mut sim = CascadeSimulation::with_cdps(cdps, eth_price, mechanism, scenario, config, rng);
+        sim.run(rng)
+    })
+}
+
+/// Replays one run of `run_cascade_from_state` from its seed, with events.
+pub(crate) fn replay_from_state(
+    mechanism: LiquidationMechanism,
+    scenario: PriceScenario,
+    cdps: &[CDP],
+    eth_price: f64,
+    config: &SimulationConfig,
+    seed: u64,
+) -> (CascadeResult, Vec<BlockEvent>) {
+    let _run = debug_span!("run", seed).entered();
+    let mut rng = config.run_rng(seed);
+    let mut sim = CascadeSimulation::with_cdps(cdps, eth_price, mechanism, scenario, config, &mut rng);
+    let (mut result, events) = sim.run_with_events(&mut rng);
+    result.seed = Some(seed);
+    (result, events)
+}
+
+/// Replays one run of `run_cascade_from_state` from its seed, calling
+/// `observer` after every block.
+pub(crate) fn replay_from_state_observed(
+    mechanism: LiquidationMechanism,
+    scenario: PriceScenario,
+    cdps: &[CDP],
+    eth_price: f64,
+    config: &SimulationConfig,
+    seed: u64,
+    observer: impl FnMut(&LiveState),
+) -> CascadeResult {
+    let _run = debug_span!("run", seed).entered();
+    let mut rng = config.run_rng(seed);
+    let mut sim = CascadeSimulation::with_cdps(cdps, eth_price, mechanism, scenario, config, &mut rng);
+    let mut result = sim.run_observed(&mut rng, observer);
+    result.seed = Some(seed);
+    result
 }
 
 pub fn aggregate_results(results: &[CascadeResult]) -> AggregatedCascadeResult {
-    let n = results.len() as f64;
-    
-    AggregatedCascadeResult {
-        mechanism: results[0].mechanism,
-        scenario: results[0].scenario,
-        runs: results.len(),
-        avg_cascade_depth: results.iter().map(|r| r.cascade_depth as f64).sum::<f64>() / n,
-        avg_liquidations: results.iter().map(|r| r.total_liquidations as f64).sum::<f64>() / n,
-        avg_bad_debt: results.iter().map(|r| r.bad_debt).sum::<f64>() / n,
-        max_bad_debt: results.iter().map(|r| r.bad_debt).fold(0.0, f64::max),
-        avg_blocks_to_stability: results.iter().map(|r| r.blocks_to_stability as f64).sum::<f64>() / n,
-        avg_price_drop_pct: results.iter().map(|r| r.price_drop_pct).sum::<f64>() / n,
-        avg_profit_concentration: results.iter().map(|r| r.profit_concentration).sum::<f64>() / n,
-        avg_participation_rate: results.iter().map(|r| r.participation_rate).sum::<f64>() / n,
-        avg_unliquidated: results.iter().map(|r| r.unliquidated_underwater as f64).sum::<f64>() / n,
-        bad_debt_frequency: results.iter().filter(|r| r.bad_debt > 0.0).count() as f64 / n,
+    let mut running = RunningAggregate::default();
+    results.iter().for_each(|result| running.push(result));
+    running.finish().expect("aggregate of at least one run")
+}
+
+/// Running sums behind `AggregatedCascadeResult`, for campaigns whose
+/// results are streamed rather than kept.
+#[derive(Debug, Clone, Default)]
+pub struct RunningAggregate {
+    cell: Option<(LiquidationMechanism, PriceScenario)>, // Of the first run
+    participation_threshold: f64,                          // Of the first run
+    runs: usize,
+    cascade_depth: f64,
+    liquidations: f64,
+    bad_debt: f64,
+    max_bad_debt: f64,
+    blocks_to_stability: f64,
+    price_drop_pct: f64,
+    profit_concentration: f64,
+    participation_rate: f64,
+    unliquidated: f64,
+    bad_debt_runs: usize,
+    protocol_revenue: f64,
+    fee_burn: f64,
+    weighted_coverage: f64,
+    compute: ComputeSummary,
+    bands: BandDigests,
+}
+
+/// Per-run values behind `CascadeBands`.
+#[derive(Debug, Clone, Default)]
+struct BandDigests {
+    bad_debt: TDigest,
+    liquidations: TDigest,
+    participation_rate: TDigest,
+    profit_concentration: TDigest,
+    protocol_revenue: TDigest,
+}
+
+impl BandDigests {
+    fn push(&mut self, result: &CascadeResult) {
+        self.bad_debt.add(result.bad_debt);
+        self.liquidations.add(result.total_liquidations as f64);
+        self.participation_rate.add(result.participation_rate);
+        self.profit_concentration.add(result.profit_concentration);
+        self.protocol_revenue.add(result.protocol_revenue);
+    }
+
+    fn finish(&self) -> CascadeBands {
+        let mut digests = self.clone();
+        CascadeBands {
+            bad_debt: Band::of(&mut digests.bad_debt),
+            liquidations: Band::of(&mut digests.liquidations),
+            participation_rate: Band::of(&mut digests.participation_rate),
+            profit_concentration: Band::of(&mut digests.profit_concentration),
+            protocol_revenue: Band::of(&mut digests.protocol_revenue),
+        }
     }
 }
 
-#[derive(Debug)]
+impl RunningAggregate {
+    pub fn push(&mut self, result: &CascadeResult) {
+        if self.cell.is_none() {
+            self.cell = Some((result.mechanism, result.scenario));
+            self.participation_threshold = result.participation_threshold;
+        }
+        self.runs += 1;
+        self.cascade_depth += result.cascade_depth as f64;
+        self.liquidations += result.total_liquidations as f64;
+        self.bad_debt += result.bad_debt;
+        self.max_bad_debt = self.max_bad_debt.max(result.bad_debt);
+        self.blocks_to_stability += result.blocks_to_stability as f64;
+        self.price_drop_pct += result.price_drop_pct;
+        self.profit_concentration += result.profit_concentration;
+        self.participation_rate += result.participation_rate;
+        self.unliquidated += result.unliquidated_underwater as f64;
+        self.bad_debt_runs += usize::from(result.bad_debt > 0.0);
+        self.protocol_revenue += result.protocol_revenue;
+        self.fee_burn += result.fee_burn;
+        self.weighted_coverage += result.weighted_coverage();
+        self.compute.push(&result.compute);
+        self.bands.push(result);
+    }
+
+    pub fn runs(&self) -> usize {
+        self.runs
+    }
+
+    /// The aggregate so far; None before the first run.
+    pub fn finish(&self) -> Option<AggregatedCascadeResult> {
+        let (mechanism, scenario) = self.cell?;
+        let n = self.runs as f64;
+        Some(AggregatedCascadeResult {
+            mechanism,
+            scenario,
+            runs: self.runs,
+            avg_cascade_depth: self.cascade_depth / n,
+            avg_liquidations: self.liquidations / n,
+            avg_bad_debt: self.bad_debt / n,
+            max_bad_debt: self.max_bad_debt,
+            avg_blocks_to_stability: self.blocks_to_stability / n,
+            avg_price_drop_pct: self.price_drop_pct / n,
+            avg_profit_concentration: self.profit_concentration / n,
+            avg_participation_rate: self.participation_rate / n,
+            avg_unliquidated: self.unliquidated / n,
+            bad_debt_frequency: self.bad_debt_runs as f64 / n,
+            avg_protocol_revenue: self.protocol_revenue / n,
+            avg_fee_burn: self.fee_burn / n,
+            compute: self.compute,
+            participation_threshold: self.participation_threshold,
+            avg_weighted_coverage: self.weighted_coverage / n,
+            bands: self.bands.finish(),
+        })
+    }
+}
+
+/// Runs the keeper pool at each keeper share in `splits` (e.g.
+/// `SPLIT_SWEEP`), trading protocol revenue against keeper participation.
+/// Every split sees the same seeds when `config.seed` is set.
+pub fn sweep_pool_split(
+    scenario: PriceScenario,
+    splits: &[f64],
+    runs: usize,
+    config: &SimulationConfig,
+) -> Result<Vec<AggregatedCascadeResult>> {
+    splits
+        .iter()
+        .map(|&split| {
+            let mechanism = LiquidationMechanism::KeeperPool { split };
+            mechanism.validate()?;
+            Ok(aggregate_results(&run_cascade_simulation(mechanism, scenario, runs, config)))
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AggregatedCascadeResult {
     pub mechanism: LiquidationMechanism,
     pub scenario: PriceScenario,
@@ -446,6 +5266,28 @@ pub struct AggregatedCascadeResult {
     pub avg_participation_rate: f64,
     pub avg_unliquidated: f64,
     pub bad_debt_frequency: f64,
+    pub avg_protocol_revenue: f64,
+    pub avg_fee_burn: f64,
+    #[serde(default)]
+    pub compute: ComputeSummary,
+    #[serde(default)]
+    pub participation_threshold: f64,
+    #[serde(default)]
+    pub avg_weighted_coverage: f64, // Mean `CascadeResult::weighted_coverage`
+    #[serde(default)]
+    pub bands: CascadeBands,
+}
+
+/// p5/p50/p95 bands of a cell's headline metrics across its runs, so
+/// summaries show whether a difference between mechanisms exceeds the
+/// run-to-run spread.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct CascadeBands {
+    pub bad_debt: Band,
+    pub liquidations: Band,
+    pub participation_rate: Band,
+    pub profit_concentration: Band,
+    pub protocol_revenue: Band,
 }
 
 impl AggregatedCascadeResult {
@@ -460,6 +5302,10 @@ impl AggregatedCascadeResult {
         println!("  Profit concentration:    {:.1}%", self.avg_profit_concentration * 100.0);
         println!("  Keeper participation:    {:.1}%", self.avg_participation_rate * 100.0);
         println!("  Avg unliquidated:        {:.1} CDPs", self.avg_unliquidated);
+        println!("  Avg protocol revenue:    ${:.0}", self.avg_protocol_revenue);
+        println!("  Avg fee burn:            ${:.0}", self.avg_fee_burn);
+        println!("  Keeper threshold:        ${:.0}", self.participation_threshold);
+        println!("  Weighted coverage:       {:.1}%", self.avg_weighted_coverage * 100.0);
     }
 }
 
@@ -474,6 +5320,8 @@ mod tests {
             collateral: 10.0,
             debt: 10000.0,
             is_liquidated: false,
+            accrues: false,
+            token_rate: 1.0,
         };
         
         assert!((cdp.collateral_ratio(2000.0) - 2.0).abs() < 0.001);
@@ -483,9 +5331,10 @@ mod tests {
     #[test]
     fn test_cascade_simulation_runs() {
         let results = run_cascade_simulation(
-            LiquidationMechanism::KeeperPool,
+            LiquidationMechanism::keeper_pool(),
             PriceScenario::FlashCrash,
             10,
+            &SimulationConfig::default(),
         );
         
         assert_eq!(results.len(), 10);
@@ -500,11 +5349,13 @@ mod tests {
             LiquidationMechanism::Traditional,
             PriceScenario::FlashCrash,
             100,
+            &SimulationConfig::default(),
         );
         let keeper_pool = run_cascade_simulation(
-            LiquidationMechanism::KeeperPool,
+            LiquidationMechanism::keeper_pool(),
             PriceScenario::FlashCrash,
             100,
+            &SimulationConfig::default(),
         );
         
         let trad_agg = aggregate_results(&traditional);
@@ -515,4 +5366,665 @@ mod tests {
         
         assert!(pool_agg.avg_participation_rate >= trad_agg.avg_participation_rate);
     }
+
+    #[test]
+    fn test_custom_config_limits_liquidations() {
+        let config = SimulationConfig {
+            num_cdps: 50,
+            keepers: KeeperConfig { count: 5, ..Default::default() },
+            liquidations_per_block: 2,
+            max_blocks: 10,
+            ..Default::default()
+        };
+        let results = run_cascade_simulation(
+            LiquidationMechanism::Traditional,
+            PriceScenario::BlackSwan,
+            10,
+            &config,
+        );
+
+        for r in &results {
+            assert!(r.total_liquidations <= 20);
+            assert!(r.blocks_to_stability <= 10);
+        }
+    }
+
+    #[test]
+    fn test_keeper_config_distributions() {
+        let mut rng = StdRng::seed_from_u64(6);
+        let draw = |keepers: KeeperConfig, rng: &mut StdRng| {
+            let population = KeeperConfig { count: 2000, ..keepers }.population(rng);
+            let mut capital: Vec<f64> = population.iter().map(|k| k.capital).collect();
+            capital.sort_by(|a, b| b.partial_cmp(a).unwrap());
+            let gas = population.iter().map(|k| k.gas_priority).sum::<f64>() / population.len() as f64;
+            (capital, gas)
+        };
+
+        let (uniform, gas) = draw(KeeperConfig::default(), &mut rng);
+        assert!(uniform.iter().all(|&c| (10_000.0..=100_000.0).contains(&c)));
+        assert!((gas - 0.5).abs() < 0.05);
+
+        // A power-law population concentrates capital in a few whales
+        let power_law = KeeperConfig {
+            capital: CapitalDistribution::PowerLaw { min: 10_000.0, alpha: 1.2 },
+            gas_priority: GasPriorityDistribution::Beta { alpha: 8.0, beta: 2.0 },
+            ..Default::default()
+        };
+        let (whales, gas) = draw(power_law, &mut rng);
+        let top_share = |c: &[f64]| c[..c.len() / 20].iter().sum::<f64>() / c.iter().sum::<f64>();
+        assert!(whales.iter().all(|&c| c >= 10_000.0));
+        assert!(top_share(&whales) > 2.0 * top_share(&uniform));
+        assert!(gas > 0.7);
+    }
+
+    #[test]
+    fn test_thresholds_gate_participation() {
+        let config = |traditional: f64| SimulationConfig {
+            seed: Some(5),
+            keepers: KeeperConfig {
+                thresholds: ParticipationThresholds { traditional: Some(traditional), ..Default::default() },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let run = |traditional| {
+            run_cascade_simulation(LiquidationMechanism::Traditional, PriceScenario::FlashCrash, 3, &config(traditional))
+        };
+
+        assert!(run(0.0).iter().all(|r| r.total_liquidations > 0));
+        assert!(run(1e12).iter().all(|r| r.total_liquidations == 0));
+    }
+
+    #[test]
+    fn test_thresholds_derive_from_gas_model() {
+        let pool = LiquidationMechanism::keeper_pool();
+        let mut keepers = KeeperConfig::default();
+        assert_eq!(keepers.threshold(LiquidationMechanism::Traditional), 50.0);
+        assert_eq!(keepers.threshold(pool), 7.0);
+
+        keepers.gas.base_gas = 40.0;
+        assert_eq!(keepers.threshold(LiquidationMechanism::Traditional), 70.0);
+        assert_eq!(keepers.threshold(pool), 7.0);
+
+        keepers.thresholds.keeper_pool = Some(20.0);
+        assert_eq!(keepers.threshold(pool), 20.0);
+
+        let config = SimulationConfig { seed: Some(5), keepers, ..Default::default() };
+        let results = run_cascade_simulation(pool, PriceScenario::FlashCrash, 2, &config);
+        assert!(results.iter().all(|r| r.participation_threshold == 20.0));
+        assert_eq!(aggregate_results(&results).participation_threshold, 20.0);
+    }
+
+    #[test]
+    fn test_config_validation() {
+        assert!(SimulationConfig::default().validate().is_ok());
+
+        let bad = |f: fn(&mut SimulationConfig)| {
+            let mut config = SimulationConfig::default();
+            f(&mut config);
+            config.validate().is_err()
+        };
+        assert!(bad(|c| c.keepers.count = 0));
+        assert!(bad(|c| c.keepers.capital = CapitalDistribution::PowerLaw { min: 0.0, alpha: 1.5 }));
+        assert!(bad(|c| c.keepers.thresholds.keeper_pool = Some(-1.0)));
+        assert!(bad(|c| c.keepers.gas.race_premium = -1.0));
+        assert!(bad(|c| c.liquidation_penalty = 1.5));
+        assert!(bad(|c| c.min_collateral_ratio = 0.9));
+        assert!(bad(|c| c.warmup = Some(Warmup { volatility: -0.1, ..Default::default() })));
+        assert!(bad(|c| c.warmup = Some(Warmup { top_up_rate: 1.5, ..Default::default() })));
+        assert!(bad(|c| c.auction_discount = Some(AuctionDiscount { dispersion: -0.2, ..Default::default() })));
+        assert!(bad(|c| c.auction_discount = Some(AuctionDiscount { protocol_share: 1.2, ..Default::default() })));
+    }
+
+    #[test]
+    fn test_auction_discount_falls_with_competition() {
+        let mut rng = StdRng::seed_from_u64(12);
+        let auction = AuctionDiscount::default();
+        let mut mean = |bidders: usize| {
+            let draws: Vec<f64> = (0..2_000).map(|_| auction.clearing_discount(0.2, bidders, &mut rng)).collect();
+            assert!(draws.iter().all(|&d| (0.0..=0.2).contains(&d)));
+            draws.iter().sum::<f64>() / draws.len() as f64
+        };
+        let (one, five, twenty) = (mean(1), mean(5), mean(20));
+
+        assert!(one < 0.2 && five < one && twenty < five, "{} {} {}", one, five, twenty);
+        let exact = AuctionDiscount { dispersion: 0.0, ..Default::default() };
+        assert_eq!(exact.clearing_discount(0.2, 10, &mut rng), 0.2);
+    }
+
+    #[test]
+    fn test_seed_reproduces_runs() {
+        let config = SimulationConfig {
+            seed: Some(7),
+            ..Default::default()
+        };
+        let run = || run_cascade_simulation(LiquidationMechanism::keeper_pool(), PriceScenario::VolatileCrash, 5, &config);
+        let (a, b) = (run(), run());
+
+        for (x, y) in a.iter().zip(&b) {
+            assert_eq!(x.bad_debt, y.bad_debt);
+            assert_eq!(x.total_liquidations, y.total_liquidations);
+        }
+    }
+
+    #[test]
+    fn test_mechanism_info_round_trips_keys() {
+        for mechanism in LiquidationMechanism::all() {
+            let info = mechanism.info();
+            assert_eq!(LiquidationMechanism::from_key(info.key), Some(mechanism));
+            let json = serde_json::to_string(&mechanism).unwrap();
+            assert!(json.contains(info.variant), "{} lacks {}", json, info.variant);
+            let split = mechanism.with_split(0.5);
+            assert_eq!(split.keeper_share() == 0.5, info.configurable_split);
+        }
+        assert_eq!(LiquidationMechanism::from_key("dutch"), None);
+    }
+
+    #[test]
+    fn test_campaign_runs_record_compute() {
+        let config = SimulationConfig { seed: Some(6), ..Default::default() };
+        let results = run_cascade_simulation(LiquidationMechanism::keeper_pool(), PriceScenario::FlashCrash, 8, &config);
+        let aggregate = aggregate_results(&results);
+
+        assert!(results.iter().all(|r| r.compute.rng_draws > 0 && r.compute.wall_secs > 0.0));
+        assert_eq!(aggregate.compute.runs, 8);
+        assert_eq!(aggregate.compute.rng_draws, results.iter().map(|r| r.compute.rng_draws).sum::<u64>());
+        // Draws are a property of the seed, wall time is not
+        let replayed = replay(LiquidationMechanism::keeper_pool(), PriceScenario::FlashCrash, &config, results[0].seed.unwrap());
+        assert_eq!(replayed.bad_debt, results[0].bad_debt);
+        assert_eq!(replayed.compute.rng_draws, results[0].compute.rng_draws);
+    }
+
+    #[test]
+    fn test_aggregate_bands_bracket_the_runs() {
+        let config = SimulationConfig { seed: Some(8), ..Default::default() };
+        let results = run_cascade_simulation(LiquidationMechanism::keeper_pool(), PriceScenario::VolatileCrash, 40, &config);
+        let bands = aggregate_results(&results).bands;
+
+        let (low, high) = results.iter().fold((f64::MAX, f64::MIN), |(lo, hi), r| (lo.min(r.bad_debt), hi.max(r.bad_debt)));
+        let bad_debt = bands.bad_debt;
+        assert!(low <= bad_debt.p5 && bad_debt.p5 <= bad_debt.p50 && bad_debt.p50 <= bad_debt.p95 && bad_debt.p95 <= high);
+        assert!(bands.participation_rate.p95 <= 1.0);
+    }
+
+    #[test]
+    fn test_recycled_arena_matches_a_fresh_run() {
+        let small = SimulationConfig { seed: Some(4), num_cdps: 40, ..Default::default() };
+        let large = SimulationConfig {
+            num_cdps: 400,
+            neighbor: Some(NeighborProtocol::default()),
+            keeper_rotation: Some(KeeperRotation::default()),
+            ..small.clone()
+        };
+        let run = |config: &SimulationConfig| {
+            let result = replay(LiquidationMechanism::keeper_pool(), PriceScenario::VolatileCrash, config, 21);
+            (result.bad_debt, result.total_liquidations, result.fee_burn, result.blocks_to_stability)
+        };
+
+        // The first run on this thread allocates; the others inherit a larger run's storage
+        let fresh = run(&small);
+        run(&large);
+        assert_eq!(run(&small), fresh);
+        assert!(RunArena::take().cdps.capacity() >= 400);
+    }
+
+    #[test]
+    fn test_weighted_coverage_counts_what_waits() {
+        // One liquidation a block, lowest ratio first: either the large
+        // vault or the five small ones wait while the other side clears
+        let run = |large_ratio: f64, small_ratio: f64| {
+            let mut cdps = vec![CDP::from_position(0, 100.0, 200_000.0 / large_ratio)];
+            cdps.extend((1..6).map(|id| CDP::from_position(id, 10.0, 20_000.0 / small_ratio)));
+            let config = SimulationConfig { liquidations_per_block: 1, max_blocks: 10, ..Default::default() };
+            let mut rng = StdRng::seed_from_u64(0);
+            CascadeSimulationBuilder::new()
+                .config(config)
+                .mechanism(LiquidationMechanism::Traditional)
+                .cdps(cdps)
+                .keepers(vec![Keeper::new(0, 1e7, 0.5)])
+                .price_path(vec![2000.0; 11])
+                .build(&mut rng)
+                .unwrap()
+                .run(&mut rng)
+        };
+        let large_waits = run(1.4, 1.2);
+        let small_wait = run(1.2, 1.4);
+
+        for result in [&large_waits, &small_wait] {
+            assert_eq!((result.total_liquidations, result.unliquidated_underwater), (6, 0));
+        }
+        assert!(large_waits.weighted_coverage() < small_wait.weighted_coverage());
+        assert!(large_waits.missed_exposure > small_wait.missed_exposure);
+        assert!(small_wait.weighted_coverage() < 1.0);
+    }
+
+    #[test]
+    fn test_small_rng_keeps_seeds_and_replays() {
+        let std =
+            SimulationConfig { seed: Some(9), execution_failure: Some(ExecutionFailure::default()), ..Default::default() };
+        let small = SimulationConfig { rng_kind: "xoshiro".parse().unwrap(), ..std.clone() };
+        let mechanism = LiquidationMechanism::keeper_pool();
+        let a = run_cascade_simulation(mechanism, PriceScenario::VolatileCrash, 6, &std);
+        let b = run_cascade_simulation(mechanism, PriceScenario::VolatileCrash, 6, &small);
+
+        assert_eq!(small.rng_kind, RngKind::Small);
+        assert!("mt19937".parse::<RngKind>().is_err());
+        assert_eq!(a.iter().map(|r| r.seed).collect::<Vec<_>>(), b.iter().map(|r| r.seed).collect::<Vec<_>>());
+        assert_ne!(a.iter().map(|r| r.bad_debt).collect::<Vec<_>>(), b.iter().map(|r| r.bad_debt).collect::<Vec<_>>());
+        let replayed = replay(mechanism, PriceScenario::VolatileCrash, &small, b[2].seed.unwrap());
+        assert_eq!(replayed.bad_debt, b[2].bad_debt);
+        assert_eq!(replayed.execution_failures, b[2].execution_failures);
+    }
+
+    #[test]
+    fn test_thread_count_does_not_change_results() {
+        let config = SimulationConfig { seed: Some(11), ..Default::default() };
+        let run = || run_cascade_simulation(LiquidationMechanism::Traditional, PriceScenario::FlashCrash, 16, &config);
+        let serial = rayon::ThreadPoolBuilder::new().num_threads(1).build().unwrap().install(run);
+        let parallel = rayon::ThreadPoolBuilder::new().num_threads(4).build().unwrap().install(run);
+
+        assert_eq!(
+            serial.iter().map(|r| (r.seed, r.bad_debt, r.total_liquidations)).collect::<Vec<_>>(),
+            parallel.iter().map(|r| (r.seed, r.bad_debt, r.total_liquidations)).collect::<Vec<_>>(),
+        );
+        // The streamed campaign still runs serially, on the same seeds
+        let mut streamed = Vec::new();
+        run_cascade_streaming(LiquidationMechanism::Traditional, PriceScenario::FlashCrash, 16, &config, |r| {
+            streamed.push(r.seed);
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(parallel.iter().map(|r| r.seed).collect::<Vec<_>>(), streamed);
+    }
+
+    #[test]
+    fn test_builder_follows_price_path() {
+        let cdps = vec![
+            CDP::from_position(0, 10.0, 12_000.0), // Liquidatable below $1800
+            CDP::from_position(1, 10.0, 6_000.0),  // Safe down to $900
+        ];
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut sim = CascadeSimulationBuilder::new()
+            .mechanism(LiquidationMechanism::Traditional)
+            .cdps(cdps)
+            .keepers(vec![Keeper::new(0, 50_000.0, 0.9), Keeper::new(1, 50_000.0, 0.1)])
+            .config(SimulationConfig { price_impact_per_eth: 0.0, ..Default::default() })
+            .price_path(vec![2000.0, 1900.0, 1700.0, 1500.0])
+            .build(&mut rng)
+            .unwrap();
+        let (result, events) = sim.run_with_events(&mut rng);
+
+        assert_eq!(result.total_liquidations, 1);
+        assert_eq!(events[1].liquidated, 1);
+        assert!((result.final_price - 1500.0).abs() < 1e-9);
+        assert!((result.participation_rate - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_builder_defaults_match_config() {
+        let config = SimulationConfig {
+            num_cdps: 40,
+            keepers: KeeperConfig { count: 7, ..Default::default() },
+            ..Default::default()
+        };
+        let mut rng = StdRng::seed_from_u64(2);
+        let sim = CascadeSimulationBuilder::new().config(config).build(&mut rng).unwrap();
+
+        assert_eq!(sim.cdps.len(), 40);
+        assert_eq!(sim.keepers.len(), 7);
+        assert_eq!(sim.mechanism, LiquidationMechanism::keeper_pool());
+    }
+
+    #[test]
+    fn test_builder_rejects_invalid_parts() {
+        let mut rng = StdRng::seed_from_u64(3);
+        assert!(CascadeSimulationBuilder::new().cdps(Vec::new()).build(&mut rng).is_err());
+        assert!(CascadeSimulationBuilder::new().price_path(vec![2000.0, 0.0]).build(&mut rng).is_err());
+        assert!(CascadeSimulationBuilder::new()
+            .eth_price(2000.0)
+            .price_path(vec![2000.0])
+            .build(&mut rng)
+            .is_err());
+    }
+
+    #[test]
+    fn test_split_sweep_trades_revenue_for_payout() {
+        let config = SimulationConfig { seed: Some(4), ..Default::default() };
+        let sweep = sweep_pool_split(PriceScenario::FlashCrash, &[0.5, 0.95], 10, &config).unwrap();
+
+        assert_eq!(sweep[0].mechanism, LiquidationMechanism::KeeperPool { split: 0.5 });
+        assert!(sweep[0].avg_protocol_revenue > sweep[1].avg_protocol_revenue);
+        assert!(sweep_pool_split(PriceScenario::FlashCrash, &[1.2], 1, &config).is_err());
+    }
+
+    #[test]
+    fn test_mechanism_reads_bare_and_split_forms() {
+        let bare: LiquidationMechanism = serde_json::from_str(r#""KeeperPool""#).unwrap();
+        let split: LiquidationMechanism = serde_json::from_str(r#"{"KeeperPool":{"split":0.8}}"#).unwrap();
+        let round_trip: LiquidationMechanism = serde_json::from_str(&serde_json::to_string(&split).unwrap()).unwrap();
+
+        assert_eq!(bare, LiquidationMechanism::keeper_pool());
+        assert_eq!(round_trip, LiquidationMechanism::KeeperPool { split: 0.8 });
+        assert_eq!(split.name(), "Fair (Keeper Pool 80/20)");
+    }
+
+    #[test]
+    fn test_accrual_accountings_agree() {
+        for accounting in [CollateralAccounting::Rebasing, CollateralAccounting::ExchangeRate] {
+            let mut cdp = CDP::from_position(0, 10.0, 10_000.0).accruing();
+            cdp.accrue(1.1, accounting);
+            assert!((cdp.collateral_ratio(2000.0) - 2.2).abs() < 1e-9);
+        }
+
+        let run = |accounting| {
+            let config = SimulationConfig {
+                seed: Some(9),
+                accruing_collateral: Some(AccruingCollateral { accounting, annual_yield: 0.05, book_share: 0.5 }),
+                ..Default::default()
+            };
+            run_cascade_simulation(LiquidationMechanism::keeper_pool(), PriceScenario::FlashCrash, 3, &config)
+        };
+        for (a, b) in run(CollateralAccounting::Rebasing).iter().zip(&run(CollateralAccounting::ExchangeRate)) {
+            assert_eq!(a.total_liquidations, b.total_liquidations);
+            assert!((a.bad_debt - b.bad_debt).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_neighbor_liquidations_add_sell_pressure() {
+        let run = |neighbor| {
+            let config = SimulationConfig { neighbor, ..Default::default() };
+            let mut rng = StdRng::seed_from_u64(4);
+            run_single_with_events(LiquidationMechanism::keeper_pool(), PriceScenario::FlashCrash, &config, &mut rng)
+        };
+        let (_, isolated) = run(None);
+        let (result, exposed) = run(Some(NeighborProtocol::default()));
+
+        // Same book and first round; only the neighbor's sales differ
+        assert_eq!(exposed[0].liquidated, isolated[0].liquidated);
+        assert!(exposed[0].neighbor_eth_sold > 0.0);
+        assert!(exposed[0].price_end < isolated[0].price_end);
+        assert!(result.neighbor_eth_sold >= exposed[0].neighbor_eth_sold);
+
+        let bad = SimulationConfig {
+            neighbor: Some(NeighborProtocol { min_collateral_ratio: 0.9, ..Default::default() }),
+            ..Default::default()
+        };
+        assert!(bad.validate().is_err());
+        let orphan = CascadeSimulationBuilder::new().neighbor_cdps(vec![CDP::from_position(0, 1.0, 1000.0)]);
+        assert!(orphan.build(&mut StdRng::seed_from_u64(0)).is_err());
+    }
+
+    #[test]
+    fn test_warmup_settles_the_initial_book() {
+        // Part of the book opens below the threshold; the scenario holds the price flat
+        let run = |warmup| {
+            let config = SimulationConfig {
+                cdp_distribution: CdpDistribution { min_ratio: 1.2, max_ratio: 2.0, ..Default::default() },
+                liquidations_per_block: 500,
+                severity: 0.0,
+                warmup,
+                ..Default::default()
+            };
+            let mut rng = StdRng::seed_from_u64(3);
+            run_single_with_events(LiquidationMechanism::keeper_pool(), PriceScenario::GradualDecline, &config, &mut rng)
+        };
+        let (cold, _) = run(None);
+        let (cleared, events) = run(Some(Warmup { volatility: 0.0, top_up_rate: 0.0, ..Default::default() }));
+        let (topped_up, _) = run(Some(Warmup { volatility: 0.0, top_up_ratio: 1.6, top_up_rate: 1.0, ..Default::default() }));
+
+        assert!(cold.total_liquidations > 0);
+        assert_eq!(cleared.total_liquidations, 0);
+        assert_eq!(events[0].liquidatable, 0);
+        assert!(cleared.ledger.initial_collateral < cold.ledger.initial_collateral); // Closed CDPs left the book
+        assert_eq!(topped_up.total_liquidations, 0);
+        assert!(topped_up.ledger.initial_collateral > cold.ledger.initial_collateral);
+    }
+
+    #[test]
+    fn test_warmup_restarts_metrics_from_the_calm_price() {
+        let config = SimulationConfig {
+            severity: 0.0,
+            warmup: Some(Warmup { volatility: 0.01, ..Default::default() }),
+            ..Default::default()
+        };
+        let (result, events) = run_single_with_events(
+            LiquidationMechanism::Traditional,
+            PriceScenario::FlashCrash,
+            &config,
+            &mut StdRng::seed_from_u64(8),
+        );
+
+        assert_ne!(events[0].price_start, config.initial_eth_price);
+        assert_eq!(events[0].block, 0);
+        assert!(result.price_drop_pct.abs() < 1e-9);
+        assert!(result.blocks_to_stability <= config.max_blocks);
+    }
+
+    #[test]
+    fn test_run_seed_replays_a_single_run() {
+        let config = SimulationConfig { seed: Some(21), ..Default::default() };
+        let results = run_cascade_simulation(LiquidationMechanism::keeper_pool(), PriceScenario::VolatileCrash, 4, &config);
+        let third = &results[2];
+
+        let (replayed, events) = replay_with_events(
+            LiquidationMechanism::keeper_pool(),
+            PriceScenario::VolatileCrash,
+            &config,
+            third.seed.unwrap(),
+        );
+        assert_eq!(replayed.bad_debt, third.bad_debt);
+        assert_eq!(replayed.total_liquidations, third.total_liquidations);
+        assert!(!events.is_empty());
+    }
+
+    #[test]
+    fn test_liquidation_log_matches_the_run() {
+        let config = SimulationConfig { seed: Some(13), ..Default::default() };
+        let plain = run_cascade_simulation(LiquidationMechanism::Traditional, PriceScenario::FlashCrash, 2, &config);
+        let (results, logs) =
+            run_cascade_simulation_with_liquidations(LiquidationMechanism::Traditional, PriceScenario::FlashCrash, 2, &config);
+
+        for ((plain, result), log) in plain.iter().zip(&results).zip(&logs) {
+            assert_eq!(plain.bad_debt, result.bad_debt);
+            assert_eq!(log.len(), result.total_liquidations);
+            assert!(!log.is_empty());
+            assert!(log.windows(2).all(|pair| pair[0].block <= pair[1].block));
+            assert!(log.iter().all(|event| event.liquidator.is_some() && event.eth_sold > 0.0));
+            let penalties: f64 = log.iter().map(|event| event.profit).sum();
+            assert!((penalties - result.ledger.penalties).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_fixed_spread_repays_part_of_the_debt() {
+        let config = SimulationConfig { seed: Some(5), ..Default::default() };
+        let (results, logs) =
+            run_cascade_simulation_with_liquidations(LiquidationMechanism::fixed_spread(), PriceScenario::FlashCrash, 2, &config);
+
+        // The winner seizes the repaid debt plus at most 5% of it
+        let cap = DEFAULT_SPREAD_BONUS / (1.0 + DEFAULT_SPREAD_BONUS);
+        for (result, log) in results.iter().zip(&logs) {
+            assert!(!log.is_empty());
+            assert!(log.iter().all(|event| event.profit <= event.eth_sold * event.price * cap + 1e-6));
+            assert!(result.ledger.liquidated_debt > 0.0);
+            let mut ids: Vec<usize> = log.iter().map(|event| event.cdp_id).collect();
+            ids.sort_unstable();
+            ids.dedup();
+            assert!(ids.len() < log.len(), "some CDP needs more than one repay");
+        }
+
+        let bare: LiquidationMechanism = serde_json::from_str(r#""FixedSpread""#).unwrap();
+        assert_eq!(bare, LiquidationMechanism::fixed_spread());
+        let partial: LiquidationMechanism = serde_json::from_str(r#"{"FixedSpread":{"bonus":0.08}}"#).unwrap();
+        assert_eq!(partial, LiquidationMechanism::FixedSpread { bonus: 0.08, close_factor: DEFAULT_CLOSE_FACTOR });
+        assert!(LiquidationMechanism::FixedSpread { bonus: 0.05, close_factor: 0.0 }.validate().is_err());
+    }
+
+    #[test]
+    fn test_english_auction_settles_and_reconciles() {
+        let config = SimulationConfig { seed: Some(3), ..Default::default() };
+        let results = run_cascade_simulation(LiquidationMechanism::english_auction(), PriceScenario::FlashCrash, 3, &config);
+
+        for result in &results {
+            let flips = result.flips.expect("an english auction run");
+            assert!(flips.settled > 0 && flips.settled <= flips.kicked);
+            assert!(flips.raised <= flips.tab + 1e-6);
+            assert!(crate::audit::ConservationReport::of(result).is_consistent());
+        }
+        let traditional = run_cascade_simulation(LiquidationMechanism::Traditional, PriceScenario::FlashCrash, 1, &config);
+        assert!(traditional[0].flips.is_none());
+
+        let bare: LiquidationMechanism = serde_json::from_str(r#""EnglishAuction""#).unwrap();
+        assert_eq!(bare, LiquidationMechanism::english_auction());
+        let apathetic: LiquidationMechanism = serde_json::from_str(r#"{"EnglishAuction":{"apathy":0.9}}"#).unwrap();
+        assert_eq!(apathetic, LiquidationMechanism::EnglishAuction(FlipAuction { apathy: 0.9, ..Default::default() }));
+        assert!(LiquidationMechanism::EnglishAuction(FlipAuction { bid_blocks: 0, ..Default::default() }).validate().is_err());
+    }
+
+    #[test]
+    fn test_soft_liquidation_converts_through_the_bands() {
+        let llamma = Llamma::default();
+        assert_eq!(llamma.converted_share(2000.0, 2100.0), 0.0);
+        assert!((llamma.converted_share(2000.0, 2000.0 * 0.99f64.powf(2.5)) - 0.25).abs() < 1e-9);
+        assert_eq!(llamma.converted_share(2000.0, 1000.0), 1.0);
+
+        let config = SimulationConfig { seed: Some(3), ..Default::default() };
+        let (results, logs) = run_cascade_simulation_with_liquidations(
+            LiquidationMechanism::soft_liquidation(),
+            PriceScenario::VolatileCrash,
+            3,
+            &config,
+        );
+        for (result, log) in results.iter().zip(&logs) {
+            let soft = result.soft.expect("a soft liquidation run");
+            assert!(soft.entered > 0 && soft.converted > 0.0);
+            // Borrowers lose the spread on each trade and nothing more
+            assert!(log.iter().all(|event| event.profit <= event.eth_sold * event.price * llamma.spread + 1e-6));
+            assert!((result.borrower_loss - result.ledger.conversion_spread).abs() < 1e-6);
+            assert!((soft.spread - result.ledger.conversion_spread).abs() < 1e-6);
+            assert!(crate::audit::ConservationReport::of(result).is_consistent());
+        }
+
+        let bare: LiquidationMechanism = serde_json::from_str(r#""SoftLiquidation""#).unwrap();
+        assert_eq!(bare, LiquidationMechanism::soft_liquidation());
+        let wide: LiquidationMechanism = serde_json::from_str(r#"{"SoftLiquidation":{"bands":50}}"#).unwrap();
+        assert_eq!(wide, LiquidationMechanism::SoftLiquidation(Llamma { bands: 50, ..Default::default() }));
+        assert!(LiquidationMechanism::SoftLiquidation(Llamma { spread: 1.0, ..Default::default() }).validate().is_err());
+    }
+
+    #[test]
+    fn test_insurance_fund_absorbs_bad_debt() {
+        let bare = SimulationConfig { seed: Some(3), ..Default::default() };
+        let insured = SimulationConfig { insurance_fund: Some(InsuranceFund::default()), ..bare.clone() };
+        let uninsured = run_cascade_simulation(LiquidationMechanism::keeper_pool(), PriceScenario::FlashCrash, 3, &bare);
+        let results = run_cascade_simulation(LiquidationMechanism::keeper_pool(), PriceScenario::FlashCrash, 3, &insured);
+        for (result, bare) in results.iter().zip(&uninsured) {
+            let fund = result.insurance.expect("an insured run");
+            // Paying the fund moves revenue around without changing the run
+            assert_eq!(fund.gross_bad_debt, bare.bad_debt);
+            assert!(fund.inflow > 0.0 && fund.balance >= 0.0);
+            assert!((result.bad_debt + fund.absorbed - fund.gross_bad_debt).abs() < 1e-6);
+            assert_eq!(fund.exhausted_at.is_some(), fund.min_headroom < 0.0);
+            assert!(crate::audit::ConservationReport::of(result).is_consistent());
+        }
+
+        // Traditional retains nothing, so only the endowment covers it
+        let endowed = SimulationConfig {
+            insurance_fund: Some(InsuranceFund { endowment: 1e9, ..Default::default() }),
+            ..bare.clone()
+        };
+        let result = run_cascade_simulation(LiquidationMechanism::Traditional, PriceScenario::BlackSwan, 1, &endowed).remove(0);
+        let fund = result.insurance.unwrap();
+        assert_eq!((fund.inflow, result.bad_debt, fund.exhausted_at), (0.0, 0.0, None));
+        assert!(SimulationConfig { insurance_fund: Some(InsuranceFund { share: 1.5, ..Default::default() }), ..bare }
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn test_debt_auctions_recapitalize_what_the_fund_leaves() {
+        let auction = DebtAuction::default();
+        let covered = auction.recapitalize(120_000.0, 1.0);
+        assert_eq!((covered.auctions, covered.uncovered), (3, 0.0));
+        assert!((covered.raised - 120_000.0).abs() < 1e-6);
+        assert!(covered.final_price < 1.0 && covered.dilution > 0.0);
+        // Dilution caps what a collapsed token can raise
+        let capped = auction.recapitalize(1e9, 1.0);
+        assert!((capped.dilution - auction.max_dilution).abs() < 1e-12);
+        assert!(capped.uncovered > 0.0 && capped.raised < auction.max_dilution * auction.market_cap);
+
+        let bare = SimulationConfig { seed: Some(3), ..Default::default() };
+        let backstopped = SimulationConfig {
+            insurance_fund: Some(InsuranceFund::default()),
+            debt_auction: Some(DebtAuction { max_dilution: 0.001, ..Default::default() }),
+            ..bare.clone()
+        };
+        let uninsured = run_cascade_simulation(LiquidationMechanism::Traditional, PriceScenario::BlackSwan, 3, &bare);
+        let results = run_cascade_simulation(LiquidationMechanism::Traditional, PriceScenario::BlackSwan, 3, &backstopped);
+        for (result, bare) in results.iter().zip(&uninsured) {
+            let (fund, auction) = (result.insurance.unwrap(), result.debt_auction.unwrap());
+            assert!(auction.raised > 0.0);
+            assert!((fund.absorbed + auction.raised + result.bad_debt - bare.bad_debt).abs() < 1e-6);
+            assert_eq!(result.bad_debt, auction.uncovered);
+        }
+        assert!(SimulationConfig { debt_auction: Some(DebtAuction { discount: 1.0, ..Default::default() }), ..bare }
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn test_failed_executions_are_slashed_to_the_protocol() {
+        let config = SimulationConfig {
+            seed: Some(3),
+            execution_failure: Some(ExecutionFailure { failure_rate: 0.3, ..Default::default() }),
+            keeper_staking: Some(KeeperStaking::default()),
+            ..Default::default()
+        };
+        for result in run_cascade_simulation(LiquidationMechanism::keeper_pool(), PriceScenario::FlashCrash, 3, &config) {
+            let staking = result.staking.expect("a staked run");
+            assert!(staking.slashings > 0 && staking.slashed > 0.0);
+            assert!(staking.slashed < staking.bonded);
+            // Slashed stake moves from keepers to the protocol, so the books still close
+            assert!(result.protocol_revenue > result.ledger.penalties * (1.0 - DEFAULT_POOL_SPLIT));
+            assert!(crate::audit::ConservationReport::of(&result).is_consistent());
+        }
+    }
+
+    #[test]
+    fn test_runs_are_traced_with_their_seed() {
+        use std::sync::{Arc, Mutex};
+
+        #[derive(Clone, Default)]
+        struct Capture(Arc<Mutex<Vec<u8>>>);
+        impl std::io::Write for Capture {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().write(buf)
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let capture = Capture::default();
+        let writer = capture.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::DEBUG)
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+        let config = SimulationConfig { seed: Some(2), ..Default::default() };
+        let results = tracing::subscriber::with_default(subscriber, || {
+            run_cascade_simulation(LiquidationMechanism::Traditional, PriceScenario::FlashCrash, 2, &config)
+        });
+        let log = String::from_utf8(capture.0.lock().unwrap().clone()).unwrap();
+
+        assert_eq!(log.matches("run finished").count(), 2);
+        assert_eq!(log.matches("campaign finished").count(), 1);
+        for result in &results {
+            assert!(log.contains(&format!("seed={}", result.seed.unwrap())));
+        }
+        assert!(log.contains("mechanism=Traditional"));
+        assert!(!log.contains("liquidatable="), "block events are trace-level");
+    }
 }
@@ -0,0 +1,237 @@
+//! Campaign Checkpoints
+//!
+//! Long Monte Carlo campaigns append their completed runs to a checkpoint
+//! file as they go, so a multi-hour campaign interrupted at 80% resumes from
+//! its last batch instead of restarting, and finishes with exactly the
+//! results an uninterrupted campaign would have.
+//!
+//! ## Format
+//! JSON Lines: a header pinning the resolved config and run count, then one
+//! line per completed run, tagged with its cell:
+//! ```text
+//! {"kind":"header","config":{...},"runs":10000}
+//! {"kind":"run","model":"GBM","mechanism":"Traditional","result":{...}}
+//! ```
+//! Runs are appended and synced to disk a batch at a time; a line cut off by
+//! the interruption is dropped on resume. A `--portfolio` book is not
+//! recorded, so resume with the same one.
+//!
+//! ## RNG State
+//! Every run's seed comes from the campaign seed, so nothing beyond the
+//! config needs saving: resuming redraws the cell's seed sequence, checks
+//! the recorded runs are its start, and runs the rest. Checkpointed
+//! campaigns therefore need `config.seed`.
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tracing::{info, info_span};
+
+use crate::cascade::{
+    campaign_span, resume_seeded, CascadeResult, CascadeSimulation, LiquidationMechanism, SimulationConfig,
+};
+use crate::error::{Error, Result};
+use crate::monte_carlo::{scenario_for_model, summarize, MonteCarloResult, PriceModel};
+use crate::portfolio::CdpPortfolio;
+
+/// Runs per cell between checkpoints.
+pub const DEFAULT_CHECKPOINT_EVERY: usize = 1000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum CheckpointLine {
+    Header { config: Box<SimulationConfig>, runs: usize },
+    Run { model: PriceModel, mechanism: LiquidationMechanism, result: Box<CascadeResult> },
+}
+
+/// A Monte Carlo campaign's checkpoint file, open for appending.
+pub struct Checkpoint {
+    writer: BufWriter<File>,
+    config: SimulationConfig,
+    runs: usize,
+    every: usize,
+    cells: Vec<(PriceModel, LiquidationMechanism, Vec<CascadeResult>)>, // Recorded runs, in run order
+}
+
+impl Checkpoint {
+    /// The config of the campaign checkpointed at `path`, if there is one;
+    /// an unseeded resume takes its seed from here.
+    pub fn config_at(path: impl AsRef<Path>) -> Result<Option<SimulationConfig>> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(None);
+        }
+        match read(path)?.0.first() {
+            Some(CheckpointLine::Header { config, .. }) => Ok(Some(config.as_ref().clone())),
+            _ => Err(Error::Invalid(format!("{} is not a campaign checkpoint", path.display()))),
+        }
+    }
+
+    /// Opens the checkpoint at `path` for a campaign of `runs` per cell,
+    /// resuming the runs it records or starting it if there is none.
+    pub fn open(path: impl AsRef<Path>, config: &SimulationConfig, runs: usize, every: usize) -> Result<Self> {
+        let path = path.as_ref();
+        if config.seed.is_none() {
+            return Err(Error::Invalid("checkpointing a campaign needs a fixed seed".to_string()));
+        }
+        if !path.exists() {
+            let writer = BufWriter::new(File::create(path)?);
+            let mut checkpoint = Self { writer, config: config.clone(), runs, every, cells: Vec::new() };
+            checkpoint.append(&[CheckpointLine::Header { config: Box::new(config.clone()), runs }])?;
+            return Ok(checkpoint);
+        }
+
+        let (lines, valid_len) = read(path)?;
+        let mut lines = lines.into_iter();
+        match lines.next() {
+            Some(CheckpointLine::Header { config: recorded, runs: recorded_runs })
+                if *recorded == *config && recorded_runs == runs => {}
+            Some(CheckpointLine::Header { .. }) => {
+                return Err(Error::Invalid(format!(
+                    "{} checkpoints a different campaign (config or run count changed)",
+                    path.display()
+                )))
+            }
+            _ => return Err(Error::Invalid(format!("{} is not a campaign checkpoint", path.display()))),
+        }
+        let file = OpenOptions::new().append(true).open(path)?;
+        file.set_len(valid_len)?; // Drop a line cut off mid-write
+        let mut checkpoint = Self { writer: BufWriter::new(file), config: config.clone(), runs, every, cells: Vec::new() };
+        for line in lines {
+            if let CheckpointLine::Run { model, mechanism, result } = line {
+                checkpoint.cell(model, mechanism).push(*result);
+            }
+        }
+        Ok(checkpoint)
+    }
+
+    /// Runs recorded so far for `model` under `mechanism`.
+    pub fn completed(&self, model: PriceModel, mechanism: LiquidationMechanism) -> usize {
+        self.cells.iter().find(|(m, mech, _)| *m == model && *mech == mechanism).map_or(0, |(_, _, runs)| runs.len())
+    }
+
+    /// `run_monte_carlo_with_config` (or `CdpPortfolio::run_monte_carlo`)
+    /// resumed from the recorded runs of the cell, checkpointing the rest.
+    pub fn run_monte_carlo(
+        &mut self,
+        model: PriceModel,
+        mechanism: LiquidationMechanism,
+        portfolio: Option<&CdpPortfolio>,
+    ) -> Result<MonteCarloResult> {
+        let _model = info_span!("monte_carlo", model = model.name()).entered();
+        let scenario = scenario_for_model(model);
+        let _campaign = campaign_span(mechanism, scenario, self.runs).entered();
+        let done = std::mem::take(self.cell(model, mechanism));
+        if !done.is_empty() {
+            info!(completed = done.len(), "resuming from checkpoint");
+        }
+
+        let config = self.config.clone();
+        let (runs, every) = (self.runs, self.every);
+        let book = portfolio.map(|p| p.to_cdps());
+        let save = |batch: &[CascadeResult]| {
+            let lines: Vec<_> = batch
+                .iter()
+                .map(|result| CheckpointLine::Run { model, mechanism, result: Box::new(result.clone()) })
+                .collect();
+            self.append(&lines)
+        };
+        let results = resume_seeded(runs, &config, done, every, save, |rng| match &book {
+            Some(cdps) => CascadeSimulation::with_cdps(cdps, config.initial_eth_price, mechanism, scenario, &config, rng)
+                .run(rng),
+            None => CascadeSimulation::new(mechanism, scenario, &config, rng).run(rng),
+        })?;
+
+        let result = summarize(model, mechanism, &results);
+        *self.cell(model, mechanism) = results;
+        Ok(result)
+    }
+
+    fn cell(&mut self, model: PriceModel, mechanism: LiquidationMechanism) -> &mut Vec<CascadeResult> {
+        let position = self.cells.iter().position(|(m, mech, _)| *m == model && *mech == mechanism);
+        let i = position.unwrap_or_else(|| {
+            self.cells.push((model, mechanism, Vec::new()));
+            self.cells.len() - 1
+        });
+        &mut self.cells[i].2
+    }
+
+    /// Writes `lines` and syncs them to disk.
+    fn append(&mut self, lines: &[CheckpointLine]) -> Result<()> {
+        for line in lines {
+            serde_json::to_writer(&mut self.writer, line)?;
+            self.writer.write_all(b"\n")?;
+        }
+        self.writer.flush()?;
+        self.writer.get_ref().sync_data()?;
+        Ok(())
+    }
+}
+
+/// The complete lines of the checkpoint at `path` and the byte length they
+/// span; only a final unterminated line may fail to parse.
+fn read(path: &Path) -> Result<(Vec<CheckpointLine>, u64)> {
+    let text = std::fs::read_to_string(path)?;
+    let mut lines = Vec::new();
+    let mut valid_len = 0;
+    for line in text.split_inclusive('\n') {
+        if !line.ends_with('\n') {
+            break;
+        }
+        lines.push(serde_json::from_str(line)?);
+        valid_len += line.len() as u64;
+    }
+    Ok((lines, valid_len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monte_carlo::run_monte_carlo_with_config;
+
+    fn path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("fair-sim-checkpoint-{}-{}.jsonl", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_interrupted_campaign_resumes_to_the_same_result() {
+        let config = SimulationConfig { seed: Some(5), num_cdps: 120, ..Default::default() };
+        let mechanism = LiquidationMechanism::keeper_pool();
+        let path = path("resume");
+        let _ = std::fs::remove_file(&path);
+        let uninterrupted = run_monte_carlo_with_config(PriceModel::JumpDiffusion, mechanism, 10, &config);
+
+        let mut checkpoint = Checkpoint::open(&path, &config, 10, 3).unwrap();
+        checkpoint.run_monte_carlo(PriceModel::JumpDiffusion, mechanism, None).unwrap();
+        drop(checkpoint);
+        // Interrupted in the third batch, partway through writing a run
+        let text = std::fs::read_to_string(&path).unwrap();
+        let kept: Vec<&str> = text.lines().take(7).collect();
+        std::fs::write(&path, format!("{}\n{{\"kind\":\"run\",\"mod", kept.join("\n"))).unwrap();
+
+        let mut resumed = Checkpoint::open(&path, &config, 10, 3).unwrap();
+        assert_eq!(resumed.completed(PriceModel::JumpDiffusion, mechanism), 6);
+        let result = resumed.run_monte_carlo(PriceModel::JumpDiffusion, mechanism, None).unwrap();
+        assert_eq!(result.run_seeds, uninterrupted.run_seeds);
+        assert_eq!(result.bad_debts, uninterrupted.bad_debts);
+        assert_eq!(result.var_99, uninterrupted.var_99);
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 11);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_rejects_a_different_campaign() {
+        let config = SimulationConfig { seed: Some(5), ..Default::default() };
+        let path = path("mismatch");
+        let _ = std::fs::remove_file(&path);
+        drop(Checkpoint::open(&path, &config, 10, 3).unwrap());
+
+        assert_eq!(Checkpoint::config_at(&path).unwrap(), Some(config.clone()));
+        assert!(Checkpoint::open(&path, &config, 20, 3).is_err());
+        assert!(Checkpoint::open(&path, &SimulationConfig { seed: Some(6), ..config.clone() }, 10, 3).is_err());
+        assert!(Checkpoint::open(&path, &SimulationConfig { seed: None, ..config }, 10, 3).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
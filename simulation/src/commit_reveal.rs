@@ -0,0 +1,179 @@
+//! Commit-Reveal Latency
+//!
+//! What the keeper pool's two-phase commit-reveal (see
+//! `cascade::CommitReveal`) costs in latency against what it saves in MEV.
+//! Committing before the executor is known keeps keepers from bidding
+//! priority gas for the liquidation, as winner-takes-all racers do; but the
+//! liquidation lands `reveal_blocks` after the commit, the price moves in
+//! between, and reveals can be missed or stop paying. The instant pool
+//! (commit, draw and execute in one block) is the latency-free reference.
+//!
+//! ## Method
+//! - Runs Traditional, the instant pool and the pool at each reveal delay
+//!   with the same miss rate; every run sees the same seeds when
+//!   `config.seed` is set
+//! - Latency cost is the bad debt a delay adds over the instant pool
+//! - MEV saved is the fee burn (gas and priority fees, reverted races
+//!   included) Traditional pays above the delayed pool
+//! - Liquidation delay is blocks from a CDP becoming liquidatable to its
+//!   liquidation, averaged over liquidations
+
+use crate::cascade::{
+    run_cascade_simulation, CascadeResult, CommitReveal, LiquidationMechanism, PriceScenario, SimulationConfig,
+};
+use crate::error::{Error, Result};
+
+/// Reveal delays compared by default, in blocks.
+pub const REVEAL_DELAYS: [usize; 4] = [1, 2, 5, 10];
+
+#[derive(Debug, Clone)]
+pub struct RevealPoint {
+    pub mechanism: LiquidationMechanism,
+    pub reveal_blocks: Option<usize>, // None = no commit-reveal phase
+    pub avg_bad_debt: f64,
+    pub bad_debt_frequency: f64,
+    pub avg_delay: f64,
+    pub avg_fee_burn: f64,
+    pub lapse_rate: f64, // Reveals nobody made, of reveals due
+    pub avg_missed: f64,
+    pub avg_dropped: f64,
+}
+
+impl RevealPoint {
+    fn from_results(mechanism: LiquidationMechanism, reveal_blocks: Option<usize>, results: &[CascadeResult]) -> Self {
+        let n = results.len().max(1) as f64;
+        let reveals: Vec<_> = results.iter().filter_map(|r| r.commit_reveal).collect();
+        let (lapsed, due) = reveals.iter().fold((0, 0), |(lapsed, due), r| (lapsed + r.lapsed, due + r.lapsed + r.revealed));
+        Self {
+            mechanism,
+            reveal_blocks,
+            avg_bad_debt: results.iter().map(|r| r.bad_debt).sum::<f64>() / n,
+            bad_debt_frequency: results.iter().filter(|r| r.bad_debt > 0.0).count() as f64 / n,
+            avg_delay: results.iter().map(|r| r.avg_liquidation_delay).sum::<f64>() / n,
+            avg_fee_burn: results.iter().map(|r| r.fee_burn).sum::<f64>() / n,
+            lapse_rate: lapsed as f64 / due.max(1) as f64,
+            avg_missed: reveals.iter().map(|r| r.missed).sum::<usize>() as f64 / n,
+            avg_dropped: reveals.iter().map(|r| r.dropped).sum::<usize>() as f64 / n,
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self.reveal_blocks {
+            Some(blocks) => format!("Pool, reveal +{}", blocks),
+            None if self.mechanism.is_keeper_pool() => "Pool, instant".to_string(),
+            None => self.mechanism.info().short_name.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RevealReport {
+    pub scenario: PriceScenario,
+    pub runs: usize,
+    pub reveal: CommitReveal, // Reveal delay varies per point
+    pub traditional: RevealPoint,
+    pub instant: RevealPoint,
+    pub points: Vec<RevealPoint>,
+}
+
+impl RevealReport {
+    pub fn print(&self) {
+        println!("| Mechanism        | Bad Debt   | P(Bad Debt) | Delay  | Fee Burn    | Lapsed | Missed | Dropped |");
+        println!("|------------------|------------|-------------|--------|-------------|--------|--------|---------|");
+        for point in [&self.traditional, &self.instant].into_iter().chain(&self.points) {
+            let (lapsed, missed, dropped) = match point.reveal_blocks {
+                Some(_) => (
+                    format!("{:.1}%", point.lapse_rate * 100.0),
+                    format!("{:.1}", point.avg_missed),
+                    format!("{:.1}", point.avg_dropped),
+                ),
+                None => ("-".to_string(), "-".to_string(), "-".to_string()),
+            };
+            println!(
+                "| {:16} | ${:9.0} | {:10.1}% | {:6.2} | ${:10.0} | {:>6} | {:>6} | {:>7} |",
+                point.label(),
+                point.avg_bad_debt,
+                point.bad_debt_frequency * 100.0,
+                point.avg_delay,
+                point.avg_fee_burn,
+                lapsed,
+                missed,
+                dropped,
+            );
+        }
+        for point in &self.points {
+            println!(
+                "  {}: ${:.0} latency cost over the instant pool, ${:.0} MEV saved against Traditional",
+                point.label(),
+                point.avg_bad_debt - self.instant.avg_bad_debt,
+                self.traditional.avg_fee_burn - point.avg_fee_burn,
+            );
+        }
+    }
+}
+
+/// Runs Traditional, the default keeper pool without commit-reveal, and
+/// the pool under `reveal` at each delay in `delays`.
+pub fn compare_reveal_delays(
+    reveal: CommitReveal,
+    scenario: PriceScenario,
+    delays: &[usize],
+    runs: usize,
+    config: &SimulationConfig,
+) -> Result<RevealReport> {
+    if delays.is_empty() {
+        return Err(Error::Invalid("commit-reveal comparison needs at least one reveal delay".to_string()));
+    }
+    let instant = SimulationConfig { commit_reveal: None, ..config.clone() };
+    instant.validate()?;
+    let run = |mechanism: LiquidationMechanism, config: &SimulationConfig| {
+        run_cascade_simulation(mechanism, scenario, runs, config)
+    };
+    let traditional = RevealPoint::from_results(LiquidationMechanism::Traditional, None, &run(LiquidationMechanism::Traditional, &instant));
+    let pool = LiquidationMechanism::keeper_pool();
+    let points = delays
+        .iter()
+        .map(|&reveal_blocks| {
+            let config = SimulationConfig { commit_reveal: Some(CommitReveal { reveal_blocks, ..reveal }), ..config.clone() };
+            config.validate()?;
+            Ok(RevealPoint::from_results(pool, Some(reveal_blocks), &run(pool, &config)))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let instant = RevealPoint::from_results(pool, None, &run(pool, &instant));
+
+    Ok(RevealReport { scenario, runs, reveal, traditional, instant, points })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reveal_delay_costs_latency() {
+        let config = SimulationConfig { seed: Some(3), ..Default::default() };
+        let report =
+            compare_reveal_delays(CommitReveal::default(), PriceScenario::FlashCrash, &[1, 10], 10, &config).unwrap();
+
+        let (short, long) = (&report.points[0], &report.points[1]);
+        assert!(report.instant.avg_delay < short.avg_delay);
+        assert!(short.avg_delay < long.avg_delay);
+        // The price moves between commit and reveal, and some members walk away
+        assert!(long.avg_dropped > 0.0);
+        // The pool never races for the liquidation, however long the reveal
+        assert!(long.avg_fee_burn < report.traditional.avg_fee_burn);
+    }
+
+    #[test]
+    fn test_missed_reveals_lapse() {
+        let config = SimulationConfig { seed: Some(3), ..Default::default() };
+        let attentive = CommitReveal { miss_rate: 0.0, ..Default::default() };
+        let absent = CommitReveal { miss_rate: 1.0, ..Default::default() };
+        let kept = compare_reveal_delays(attentive, PriceScenario::GradualDecline, &[2], 5, &config).unwrap();
+        let missed = compare_reveal_delays(absent, PriceScenario::GradualDecline, &[2], 5, &config).unwrap();
+
+        assert_eq!(kept.points[0].avg_missed, 0.0);
+        assert_eq!(missed.points[0].lapse_rate, 1.0);
+        assert!(missed.points[0].avg_bad_debt >= kept.points[0].avg_bad_debt);
+        assert!(compare_reveal_delays(attentive, PriceScenario::GradualDecline, &[0], 1, &config).is_err());
+    }
+}
@@ -0,0 +1,217 @@
+//! Compute Accounting
+//!
+//! What each run of a campaign costs: wall time, draws from its RNG and heap
+//! allocations. Campaign runners record a `RunCompute` on every
+//! `CascadeResult`, and aggregates sum them into a `ComputeSummary`, so a
+//! campaign shows which scenarios dominate its runtime.
+//!
+//! ## Allocations
+//! Counting needs the binary to install the counting allocator:
+//!
+//! ```
+//! use fair_simulation::compute::{thread_allocations, CountingAllocator};
+//!
+//! #[global_allocator]
+//! static ALLOCATOR: CountingAllocator = CountingAllocator;
+//!
+//! fn main() {
+//!     let _ = vec![1u8; 8];
+//!     assert!(thread_allocations().is_some());
+//! }
+//! ```
+//!
+//! Without it `RunCompute::allocations` is None. Counts are per thread, and
+//! a run stays on the worker that started it, so parallel runs don't see
+//! each other's allocations.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+/// Wraps an RNG, counting the calls made into it. Every value is the inner
+/// RNG's, so a wrapped run is identical to an unwrapped one.
+#[derive(Debug, Clone)]
+pub struct CountingRng<R> {
+    inner: R,
+    draws: u64,
+}
+
+impl<R: RngCore> CountingRng<R> {
+    pub fn new(inner: R) -> Self {
+        Self { inner, draws: 0 }
+    }
+
+    pub fn draws(&self) -> u64 {
+        self.draws
+    }
+}
+
+impl<R: RngCore> RngCore for CountingRng<R> {
+    fn next_u32(&mut self) -> u32 {
+        self.draws += 1;
+        self.inner.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.draws += 1;
+        self.inner.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.draws += 1;
+        self.inner.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.draws += 1;
+        self.inner.try_fill_bytes(dest)
+    }
+}
+
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    static ALLOCATIONS: Cell<u64> = const { Cell::new(0) };
+}
+
+/// The system allocator, counting allocations per thread.
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        count_allocation();
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        count_allocation();
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        count_allocation();
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+fn count_allocation() {
+    if !INSTALLED.load(Ordering::Relaxed) {
+        INSTALLED.store(true, Ordering::Relaxed);
+    }
+    // Thread teardown may allocate after the counter is gone
+    let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+}
+
+/// Allocations made on this thread so far; None without `CountingAllocator`.
+pub fn thread_allocations() -> Option<u64> {
+    INSTALLED.load(Ordering::Relaxed).then(|| ALLOCATIONS.with(Cell::get))
+}
+
+/// Compute one run took.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct RunCompute {
+    pub wall_secs: f64,
+    pub rng_draws: u64,
+    pub allocations: Option<u64>, // None when the binary doesn't count them
+}
+
+/// Runs `run` on a counting wrapper of `rng`, timing it.
+pub fn measure<R: RngCore, T>(rng: R, run: impl FnOnce(&mut CountingRng<R>) -> T) -> (T, RunCompute) {
+    let mut rng = CountingRng::new(rng);
+    let allocations = thread_allocations();
+    let start = Instant::now();
+    let value = run(&mut rng);
+    let wall_secs = start.elapsed().as_secs_f64();
+    let compute = RunCompute {
+        wall_secs,
+        rng_draws: rng.draws(),
+        allocations: allocations.zip(thread_allocations()).map(|(before, after)| after - before),
+    };
+    (value, compute)
+}
+
+/// Compute of a campaign's runs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ComputeSummary {
+    pub runs: usize,
+    pub wall_secs: f64, // Summed over runs, so CPU time under a thread pool
+    pub max_wall_secs: f64,
+    pub rng_draws: u64,
+    pub allocations: Option<u64>,
+}
+
+impl ComputeSummary {
+    pub fn push(&mut self, run: &RunCompute) {
+        self.allocations = match (self.runs, self.allocations, run.allocations) {
+            (0, _, allocations) => allocations,
+            (_, Some(total), Some(allocations)) => Some(total + allocations),
+            _ => None,
+        };
+        self.runs += 1;
+        self.wall_secs += run.wall_secs;
+        self.max_wall_secs = self.max_wall_secs.max(run.wall_secs);
+        self.rng_draws += run.rng_draws;
+    }
+
+    pub fn from_runs<'a>(runs: impl IntoIterator<Item = &'a RunCompute>) -> Self {
+        let mut summary = Self::default();
+        runs.into_iter().for_each(|run| summary.push(run));
+        summary
+    }
+
+    pub fn mean_wall_secs(&self) -> f64 {
+        self.wall_secs / self.runs.max(1) as f64
+    }
+
+    pub fn mean_rng_draws(&self) -> f64 {
+        self.rng_draws as f64 / self.runs.max(1) as f64
+    }
+
+    pub fn mean_allocations(&self) -> Option<f64> {
+        self.allocations.map(|total| total as f64 / self.runs.max(1) as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn test_counting_rng_is_transparent() {
+        let mut plain = StdRng::seed_from_u64(9);
+        let expected: Vec<f64> = (0..50).map(|_| plain.gen()).collect();
+        let (drawn, compute) = measure(StdRng::seed_from_u64(9), |rng| (0..50).map(|_| rng.gen::<f64>()).collect::<Vec<_>>());
+
+        assert_eq!(drawn, expected);
+        assert_eq!(compute.rng_draws, 50);
+        assert!(compute.wall_secs >= 0.0);
+        // The test harness doesn't install the counting allocator
+        assert_eq!(compute.allocations, None);
+    }
+
+    #[test]
+    fn test_summary_sums_runs() {
+        let runs = [
+            RunCompute { wall_secs: 0.5, rng_draws: 10, allocations: Some(4) },
+            RunCompute { wall_secs: 1.5, rng_draws: 30, allocations: Some(6) },
+        ];
+        let summary = ComputeSummary::from_runs(&runs);
+
+        assert_eq!((summary.runs, summary.rng_draws, summary.allocations), (2, 40, Some(10)));
+        assert_eq!((summary.wall_secs, summary.max_wall_secs, summary.mean_wall_secs()), (2.0, 1.5, 1.0));
+        assert_eq!(summary.mean_allocations(), Some(5.0));
+        let uncounted = RunCompute { allocations: None, ..runs[0] };
+        assert_eq!(ComputeSummary::from_runs(&[runs[0], uncounted]).allocations, None);
+        assert_eq!(ComputeSummary::default().mean_wall_secs(), 0.0);
+    }
+}
@@ -0,0 +1,336 @@
+//! Scenario Config Files
+//!
+//! Full simulation setups loaded from TOML or YAML so scenario definitions
+//! can be version-controlled. Every field is optional; anything omitted
+//! falls back to the subcommand's built-in defaults. In `fair-sim`, the
+//! `FAIR_SIM_RUNS`/`FAIR_SIM_SEED`/`FAIR_SIM_RNG` environment variables
+//! override the file and explicit flags override both.
+//!
+//! ## Example (TOML)
+//! ```toml
+//! mechanisms = [{ KeeperPool = { split = 0.8 } }] # Or "KeeperPool" for 70/30
+//! scenarios = ["FlashCrash", "BlackSwan"]
+//! runs = 2000
+//!
+//! [simulation]
+//! liquidation_penalty = 0.10
+//! severity = 1.5 # Scales every scenario's price moves (1 = as defined)
+//! rng_kind = "Small" # Xoshiro256++ per run instead of ChaCha12 ("Std")
+//! pool_split = "Capital" # Pool members paid pro rata to capital instead of alike ("Equal")
+//! queue_order = "RiskScore" # Queue by IPFE score instead of lowest ratio ("Ratio"); "Profit" lets keepers choose
+//!
+//! [simulation.keepers]
+//! count = 20
+//! capital = { LogNormal = { median = 30000.0, sigma = 1.2 } }
+//! gas = { base_gas = 20.0, pool_commit = 5.0, race_premium = 28.0, min_margin = 2.0 }
+//! thresholds = { keeper_pool = 7.0 } # Overrides the gas model's break-even
+//!
+//! [simulation.cdp_distribution]
+//! min_ratio = 1.6
+//! max_ratio = 3.0
+//!
+//! [simulation.neighbor] # Optional second protocol selling the same collateral
+//! min_collateral_ratio = 1.2
+//!
+//! [simulation.reward_token] # Optional: pay pool keepers partly in a protocol token
+//! emission_share = 0.5
+//!
+//! [simulation.incentive_program] # Optional: keeper bonus ending at a cliff
+//! bonus_per_liquidation = 20.0
+//! cliff_block = 5
+//!
+//! [simulation.execution_race] # Optional: executor premium pool members race for
+//! executor_share = 0.1
+//!
+//! [simulation.execution_failure] # Optional: selected pool executors failing to land
+//! failure_rate = 0.05
+//! fallback = { Reselect = { delay = 2 } } # Or "Backup" for a same-block backup
+//!
+//! [simulation.mempool] # Optional: only some keepers watch pending transactions
+//! visible_share = 0.25
+//! lead_blocks = 1
+//!
+//! [simulation.mechanism_switch] # Optional: hand a deep queue to a backstop
+//! queue_threshold = 30
+//! backstop = { DutchAuction = { start_penalty = 0.05, step = 0.02, max_penalty = 0.3 } } # Or "Traditional"
+//!
+//! [simulation.auction_discount] # Optional: auctions clear at the lowest bidder's discount
+//! dispersion = 0.5     # Lognormal sigma of reservations around the offered penalty
+//! protocol_share = 0.0 # Share of the realized discount the protocol keeps
+//!
+//! [simulation.liquidation_cooldown] # Optional: partial liquidations rate-limited per borrower
+//! close_factor = 0.5   # Share of the debt each liquidation closes
+//! cooldown_blocks = 5  # Blocks before the same CDP can be liquidated again
+//!
+//! [simulation.penalty_curve] # Optional: replaces the flat penalty
+//! min_penalty = 0.05 # At the minimum ratio
+//! slope = 0.5        # Added per unit of collateral ratio below it
+//! max_penalty = 0.20
+//!
+//! [simulation.grace_period] # Optional: owners get blocks to top up before liquidation
+//! grace_blocks = 5   # Blocks from falling below the minimum to liquidatable
+//! top_up_rate = 0.2  # Chance per block of grace the owner tops up
+//! top_up_ratio = 1.7 # Collateral ratio they top back up to
+//!
+//! [simulation.insurance_fund] # Optional: a backstop absorbing bad debt
+//! share = 0.5     # Of the protocol's penalty take paid into the fund
+//! endowment = 0.0 # USD it holds at block 0
+//!
+//! [simulation.debt_auction] # Optional: mint governance tokens to cover what is left
+//! lot = 50000.0           # USD raised per auction
+//! market_cap = 20000000.0 # USD value of the token supply at par
+//! max_dilution = 0.25     # Share of supply minted before giving up
+//!
+//! [simulation.keeper_staking] # Optional: pool membership bonded, rewards pro rata to stake
+//! stake_share = 0.1  # Of its capital each keeper bonds
+//! min_stake = 1000.0 # USD below which a keeper is ejected
+//! slash_rate = 0.3   # Of its stake lost per assigned liquidation left unexecuted
+//!
+//! [simulation.keeper_reputation] # Optional: pool executors drawn by track record
+//! memory = 0.1   # Weight of each new outcome in a keeper's moving averages
+//! exponent = 2.0 # On the score; higher favors the best record more
+//! floor = 0.05   # Added to every weight, so no member is shut out
+//!
+//! [simulation.commit_reveal] # Optional: pool rounds reveal blocks after they commit
+//! reveal_blocks = 2 # Blocks from commit to reveal
+//! miss_rate = 0.05  # Chance each committed member misses its reveal
+//!
+//! [simulation.keeper_rotation] # Optional: keeper pool active set rotated per epoch
+//! set_size = 10
+//! epoch_blocks = 25
+//! offline_rate = 0.2 # Chance each member misses its epoch
+//!
+//! [simulation.liquidity_crisis] # Optional: market makers withdraw mid-crash
+//! block = 5                # First block under the thin market
+//! impact_multiplier = 10.0 # Times price_impact_per_eth from then on
+//!
+//! [simulation.warmup] # Optional: calm blocks settling the book before the scenario
+//! blocks = 50
+//! volatility = 0.002 # Per-block log-price volatility
+//! top_up_ratio = 1.7 # Borrowers below it top back up ...
+//! top_up_rate = 0.1  # ... with this chance per block
+//!
+//! [perception.ipfe] # `fair-sim poa` only: what keepers see under each strategy
+//! signal = "collateral_ratio"
+//! cutoff = 1.6
+//! confidence = [0.2, 0.6]
+//! ```
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::cascade::{LiquidationMechanism, PriceScenario, SimulationConfig};
+use crate::error::{Error, Result};
+use crate::monte_carlo::PriceModel;
+use crate::poa::PerceptionCalibration;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SimulationSetup {
+    pub mechanisms: Vec<LiquidationMechanism>, // Empty = all
+    pub scenarios: Vec<PriceScenario>,         // Empty = all
+    pub models: Vec<PriceModel>,               // Empty = all
+    pub runs: Option<usize>,
+    pub simulation: SimulationConfig,
+    pub perception: PerceptionCalibration, // Price of Anarchy game only
+}
+
+impl SimulationSetup {
+    pub fn from_toml(text: &str) -> Result<Self> {
+        let setup: Self = toml::from_str(text)?;
+        setup.validate()?;
+        Ok(setup)
+    }
+
+    pub fn from_yaml(text: &str) -> Result<Self> {
+        let setup: Self = serde_yaml::from_str(text)?;
+        setup.validate()?;
+        Ok(setup)
+    }
+
+    /// Picks the format from the file extension (`.toml`, `.yaml`, `.yml`).
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => Self::from_toml(&text),
+            Some("yaml") | Some("yml") => Self::from_yaml(&text),
+            _ => Err(Error::Invalid(format!(
+                "config {} must have a .toml, .yaml or .yml extension",
+                path.display()
+            ))),
+        }
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.runs == Some(0) {
+            return Err(Error::Invalid("runs must be positive".to_string()));
+        }
+        for mechanism in &self.mechanisms {
+            mechanism.validate()?;
+        }
+        self.perception.validate()?;
+        self.simulation.validate()
+    }
+
+    pub fn mechanisms(&self) -> Vec<LiquidationMechanism> {
+        if self.mechanisms.is_empty() {
+            LiquidationMechanism::all()
+        } else {
+            self.mechanisms.clone()
+        }
+    }
+
+    pub fn scenarios(&self) -> Vec<PriceScenario> {
+        if self.scenarios.is_empty() {
+            PriceScenario::all()
+        } else {
+            self.scenarios.clone()
+        }
+    }
+
+    pub fn models(&self) -> Vec<PriceModel> {
+        if self.models.is_empty() {
+            PriceModel::all()
+        } else {
+            self.models.clone()
+        }
+    }
+
+    /// Restricts the campaign matrix to the cells named by `scenarios`,
+    /// `mechanisms` and `models`, keeping the configured entries so a pinned
+    /// split survives (mechanisms match by key); an empty filter keeps its
+    /// dimension whole. Returns false, changing nothing, when a filter names
+    /// nothing the setup runs.
+    pub fn narrow(
+        &mut self,
+        scenarios: &[PriceScenario],
+        mechanisms: &[LiquidationMechanism],
+        models: &[PriceModel],
+    ) -> bool {
+        fn keep<T: Copy>(listed: &[T], all: Vec<T>, only: &[T], same: impl Fn(&T, &T) -> bool) -> Option<Vec<T>> {
+            if only.is_empty() {
+                return Some(listed.to_vec());
+            }
+            let configured = if listed.is_empty() { all } else { listed.to_vec() };
+            let kept: Vec<T> = configured.into_iter().filter(|c| only.iter().any(|o| same(c, o))).collect();
+            (!kept.is_empty()).then_some(kept)
+        }
+        let narrowed = (
+            keep(&self.scenarios, PriceScenario::all(), scenarios, |a, b| a == b),
+            keep(&self.mechanisms, LiquidationMechanism::all(), mechanisms, |a, b| a.info().key == b.info().key),
+            keep(&self.models, PriceModel::all(), models, |a, b| a == b),
+        );
+        let (Some(scenarios), Some(mechanisms), Some(models)) = narrowed else {
+            return false;
+        };
+        (self.scenarios, self.mechanisms, self.models) = (scenarios, mechanisms, models);
+        true
+    }
+
+    pub fn runs_or(&self, default: usize) -> usize {
+        self.runs.unwrap_or(default)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cascade::{CapitalDistribution, GasPriorityDistribution};
+
+    #[test]
+    fn test_toml_and_yaml_agree() {
+        let toml = r#"
+            mechanisms = ["KeeperPool"]
+            scenarios = ["FlashCrash"]
+            runs = 250
+
+            [simulation.keepers]
+            count = 20
+
+            [simulation.cdp_distribution]
+            min_ratio = 1.6
+        "#;
+        let yaml = "
+mechanisms: [KeeperPool]
+scenarios: [FlashCrash]
+runs: 250
+simulation:
+  keepers:
+    count: 20
+  cdp_distribution:
+    min_ratio: 1.6
+";
+        let a = SimulationSetup::from_toml(toml).unwrap();
+        let b = SimulationSetup::from_yaml(yaml).unwrap();
+
+        assert_eq!(a.simulation, b.simulation);
+        assert_eq!(a.mechanisms(), vec![LiquidationMechanism::keeper_pool()]);
+        assert_eq!(b.runs_or(1000), 250);
+        assert_eq!(a.simulation.keepers.count, 20);
+        assert_eq!(a.simulation.num_cdps, SimulationConfig::default().num_cdps);
+        assert!((a.simulation.cdp_distribution.max_ratio - 2.5).abs() < 1e-9);
+        assert_eq!(a.models(), PriceModel::all());
+    }
+
+    #[test]
+    fn test_rejects_unknown_and_invalid_fields() {
+        assert!(SimulationSetup::from_toml("runz = 5").is_err());
+        assert!(SimulationSetup::from_toml("runs = 0").is_err());
+        assert!(SimulationSetup::from_toml("[simulation.keepers]\ncount = 0").is_err());
+    }
+
+    #[test]
+    fn test_keeper_pool_split_forms() {
+        let bare = SimulationSetup::from_toml(r#"mechanisms = ["Traditional", "KeeperPool", "FixedSpread", "EnglishAuction", "SoftLiquidation", "BatchAuction"]"#).unwrap();
+        assert_eq!(bare.mechanisms(), LiquidationMechanism::all());
+
+        let split = SimulationSetup::from_yaml("mechanisms: [{KeeperPool: {split: 0.8}}]").unwrap();
+        assert_eq!(split.mechanisms(), vec![LiquidationMechanism::KeeperPool { split: 0.8 }]);
+
+        assert!(SimulationSetup::from_toml("mechanisms = [{ KeeperPool = { split = 1.5 } }]").is_err());
+    }
+
+    #[test]
+    fn test_keeper_distributions_parse() {
+        let setup = SimulationSetup::from_toml(
+            r#"
+            [simulation.keepers]
+            capital = { PowerLaw = { min = 5000.0, alpha = 1.5 } }
+            gas_priority = { Beta = { alpha = 5.0, beta = 2.0 } }
+            thresholds = { keeper_pool = 3.0 }
+        "#,
+        )
+        .unwrap();
+        let keepers = &setup.simulation.keepers;
+
+        assert_eq!(keepers.capital, CapitalDistribution::PowerLaw { min: 5000.0, alpha: 1.5 });
+        assert_eq!(keepers.gas_priority, GasPriorityDistribution::Beta { alpha: 5.0, beta: 2.0 });
+        assert_eq!(keepers.threshold(LiquidationMechanism::keeper_pool()), 3.0);
+        assert_eq!(keepers.threshold(LiquidationMechanism::Traditional), 50.0);
+        assert!(SimulationSetup::from_toml("[simulation.keepers]\ncapital = { LogNormal = { median = 0.0, sigma = 1.0 } }")
+            .is_err());
+    }
+
+    #[test]
+    fn test_narrow_keeps_configured_cells() {
+        let mut setup = SimulationSetup::from_toml(
+            r#"
+            mechanisms = ["Traditional", { KeeperPool = { split = 0.8 } }]
+            scenarios = ["FlashCrash", "BlackSwan"]
+        "#,
+        )
+        .unwrap();
+
+        assert!(setup.narrow(&[PriceScenario::BlackSwan], &[LiquidationMechanism::keeper_pool()], &[PriceModel::GARCH]));
+        assert_eq!(setup.scenarios(), vec![PriceScenario::BlackSwan]);
+        assert_eq!(setup.mechanisms(), vec![LiquidationMechanism::KeeperPool { split: 0.8 }]);
+        assert_eq!(setup.models(), vec![PriceModel::GARCH]);
+        // Nothing configured runs the volatile crash
+        assert!(!setup.narrow(&[PriceScenario::VolatileCrash], &[], &[]));
+        assert_eq!(setup.scenarios(), vec![PriceScenario::BlackSwan]);
+        assert!(setup.narrow(&[], &[], &[]));
+        assert_eq!(setup.mechanisms().len(), 1);
+    }
+}
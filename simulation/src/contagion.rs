@@ -0,0 +1,159 @@
+//! Cross-Protocol Contagion
+//!
+//! Attributes Fair's bad debt to a neighboring lending protocol that holds
+//! the same collateral. Each cascade runs twice, once with Fair as the only
+//! seller and once with the neighbor's liquidations hitting the same market;
+//! the difference is bad debt driven by the neighbor's deleveraging rather
+//! than Fair's own.
+//!
+//! ## Method
+//! - Both runs share every seed (common random numbers); the neighbor's book
+//!   is drawn from a separate stream so adding it leaves Fair's draws intact
+//! - Uses `config.neighbor`, or `NeighborProtocol::default()` when unset
+
+use rand::prelude::*;
+
+use crate::cascade::{
+    CascadeResult, CascadeSimulationBuilder, LiquidationMechanism, NeighborProtocol, PriceScenario, SimulationConfig,
+};
+use crate::error::Result;
+
+#[derive(Debug, Clone)]
+pub struct ContagionResult {
+    pub mechanism: LiquidationMechanism,
+    pub scenario: PriceScenario,
+    pub runs: usize,
+    pub isolated_bad_debt: f64,  // Mean with Fair as the only seller
+    pub exposed_bad_debt: f64,   // Mean with the neighbor selling alongside
+    pub neighbor_eth_sold: f64,  // Mean collateral the neighbor dumped
+    pub isolated_price_drop_pct: f64,
+    pub exposed_price_drop_pct: f64,
+}
+
+impl ContagionResult {
+    /// Mean bad debt attributable to the neighbor.
+    pub fn contagion_bad_debt(&self) -> f64 {
+        self.exposed_bad_debt - self.isolated_bad_debt
+    }
+
+    /// Share of exposed bad debt the neighbor accounts for.
+    pub fn contagion_share(&self) -> f64 {
+        if self.exposed_bad_debt > 0.0 {
+            self.contagion_bad_debt() / self.exposed_bad_debt
+        } else {
+            0.0
+        }
+    }
+
+    pub fn print(&self) {
+        println!("  Neighbor ETH sold:       {:.0} ETH", self.neighbor_eth_sold);
+        println!(
+            "  Avg price drop:          {:.1}% -> {:.1}%",
+            self.isolated_price_drop_pct, self.exposed_price_drop_pct
+        );
+        println!("  Bad debt (isolated):     ${:.0}", self.isolated_bad_debt);
+        println!("  Bad debt (exposed):      ${:.0}", self.exposed_bad_debt);
+        println!(
+            "  From neighbor:           ${:.0} ({:.1}%)",
+            self.contagion_bad_debt(),
+            self.contagion_share() * 100.0
+        );
+    }
+}
+
+/// Runs `runs` paired cascades per scenario and splits Fair's bad debt into
+/// its own and the neighbor-driven part.
+pub fn measure_contagion(
+    mechanism: LiquidationMechanism,
+    scenarios: &[PriceScenario],
+    runs: usize,
+    config: &SimulationConfig,
+) -> Result<Vec<ContagionResult>> {
+    let neighbor = config.neighbor.clone().unwrap_or_default();
+    let exposed = SimulationConfig { neighbor: Some(neighbor.clone()), ..config.clone() };
+    let isolated = SimulationConfig { neighbor: None, ..config.clone() };
+    exposed.validate()?;
+    mechanism.validate()?;
+
+    let mut rng = config.rng();
+    let mut results = Vec::with_capacity(scenarios.len());
+    for &scenario in scenarios {
+        let mut sums = [0.0; 5];
+        for _ in 0..runs {
+            let run_seed: u64 = rng.gen();
+            let (alone, shared) = run_pair(mechanism, scenario, &neighbor, &isolated, &exposed, run_seed)?;
+            sums[0] += alone.bad_debt;
+            sums[1] += shared.bad_debt;
+            sums[2] += shared.neighbor_eth_sold;
+            sums[3] += alone.price_drop_pct;
+            sums[4] += shared.price_drop_pct;
+        }
+        let n = runs.max(1) as f64;
+        results.push(ContagionResult {
+            mechanism,
+            scenario,
+            runs,
+            isolated_bad_debt: sums[0] / n,
+            exposed_bad_debt: sums[1] / n,
+            neighbor_eth_sold: sums[2] / n,
+            isolated_price_drop_pct: sums[3] / n,
+            exposed_price_drop_pct: sums[4] / n,
+        });
+    }
+    Ok(results)
+}
+
+fn run_pair(
+    mechanism: LiquidationMechanism,
+    scenario: PriceScenario,
+    neighbor: &NeighborProtocol,
+    isolated: &SimulationConfig,
+    exposed: &SimulationConfig,
+    run_seed: u64,
+) -> Result<(CascadeResult, CascadeResult)> {
+    let run = |config: &SimulationConfig, builder: CascadeSimulationBuilder| -> Result<CascadeResult> {
+        let mut rng = config.run_rng(run_seed);
+        let mut sim = builder.config(config.clone()).mechanism(mechanism).scenario(scenario).build(&mut rng)?;
+        Ok(sim.run(&mut rng))
+    };
+    let neighbor_cdps = neighbor.book(exposed.initial_eth_price, &mut exposed.run_rng(!run_seed));
+
+    Ok((
+        run(isolated, CascadeSimulationBuilder::new())?,
+        run(exposed, CascadeSimulationBuilder::new().neighbor_cdps(neighbor_cdps))?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cascade::CdpDistribution;
+
+    #[test]
+    fn test_neighbor_deleveraging_adds_bad_debt() {
+        let config = SimulationConfig { seed: Some(2), ..Default::default() };
+        let results =
+            measure_contagion(LiquidationMechanism::keeper_pool(), &[PriceScenario::BlackSwan], 10, &config).unwrap();
+        let result = &results[0];
+
+        assert!(result.neighbor_eth_sold > 0.0);
+        assert!(result.contagion_bad_debt() > 0.0);
+        assert!(result.contagion_share() <= 1.0);
+    }
+
+    #[test]
+    fn test_idle_neighbor_leaves_runs_unchanged() {
+        // A neighbor with nothing to liquidate must not perturb Fair's draws
+        let idle = NeighborProtocol {
+            min_collateral_ratio: 1.01,
+            cdp_distribution: CdpDistribution { min_ratio: 5.0, max_ratio: 6.0, ..Default::default() },
+            ..Default::default()
+        };
+        let config = SimulationConfig { seed: Some(6), neighbor: Some(idle), ..Default::default() };
+        let results =
+            measure_contagion(LiquidationMechanism::Traditional, &[PriceScenario::GradualDecline], 5, &config).unwrap();
+
+        assert_eq!(results[0].neighbor_eth_sold, 0.0);
+        assert_eq!(results[0].contagion_bad_debt(), 0.0);
+    }
+}
@@ -0,0 +1,217 @@
+//! Liquidation Cooldowns
+//!
+//! Per-borrower rate limits on liquidation (see `cascade::LiquidationCooldown`),
+//! evaluated against liquidating whole CDPs. Partial liquidations leave
+//! borrowers the equity a whole-CDP liquidation seizes; the cooldown between
+//! them protects borrowers from being wiped out by a single wick. The price
+//! is paid in bad debt when a CDP keeps falling while it waits.
+//!
+//! ## Method
+//! - Runs the mechanism liquidating whole CDPs, then under each policy;
+//!   every run sees the same seeds when `config.seed` is set, and cooldowns
+//!   draw nothing from the RNG
+//! - Borrower loss is collateral value seized beyond the debt repaid
+//! - The tradeoff prices each policy in extra bad debt per dollar of
+//!   borrower loss it saves
+
+use crate::cascade::{
+    run_cascade_simulation, CascadeResult, LiquidationCooldown, LiquidationMechanism, PriceScenario, SimulationConfig,
+};
+use crate::error::Result;
+
+/// Policies compared by default: halving the debt once per block, per five
+/// blocks and per twenty, and closing a quarter per five blocks.
+pub const COOLDOWN_POLICIES: [LiquidationCooldown; 4] = [
+    LiquidationCooldown { close_factor: 0.5, cooldown_blocks: 1 },
+    LiquidationCooldown { close_factor: 0.5, cooldown_blocks: 5 },
+    LiquidationCooldown { close_factor: 0.5, cooldown_blocks: 20 },
+    LiquidationCooldown { close_factor: 0.25, cooldown_blocks: 5 },
+];
+
+#[derive(Debug, Clone)]
+pub struct CooldownPoint {
+    pub policy: Option<LiquidationCooldown>, // None = whole-CDP liquidation
+    pub avg_bad_debt: f64,
+    pub bad_debt_frequency: f64,
+    pub avg_borrower_loss: f64,
+    pub avg_liquidations: f64,
+    pub avg_liquidation_delay: f64,
+}
+
+impl CooldownPoint {
+    fn from_results(policy: Option<LiquidationCooldown>, results: &[CascadeResult]) -> Self {
+        let n = results.len().max(1) as f64;
+        let mean = |f: fn(&CascadeResult) -> f64| results.iter().map(f).sum::<f64>() / n;
+        Self {
+            policy,
+            avg_bad_debt: mean(|r| r.bad_debt),
+            bad_debt_frequency: results.iter().filter(|r| r.bad_debt > 0.0).count() as f64 / n,
+            avg_borrower_loss: mean(|r| r.borrower_loss),
+            avg_liquidations: mean(|r| r.total_liquidations as f64),
+            avg_liquidation_delay: mean(|r| r.avg_liquidation_delay),
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match &self.policy {
+            None => "Whole CDP".to_string(),
+            Some(policy) => format!("{:.0}% per {} blocks", policy.close_factor * 100.0, policy.cooldown_blocks),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CooldownReport {
+    pub mechanism: LiquidationMechanism,
+    pub scenario: PriceScenario,
+    pub runs: usize,
+    pub whole: CooldownPoint,
+    pub policies: Vec<CooldownPoint>,
+}
+
+impl CooldownReport {
+    /// Extra bad debt per dollar of borrower loss `point` saves over
+    /// whole-CDP liquidation; None if it saves nothing.
+    pub fn tradeoff(&self, point: &CooldownPoint) -> Option<f64> {
+        let saved = self.whole.avg_borrower_loss - point.avg_borrower_loss;
+        (saved > 0.0).then(|| (point.avg_bad_debt - self.whole.avg_bad_debt) / saved)
+    }
+
+    /// The policy saving borrowers the most per dollar of extra bad debt.
+    pub fn cheapest_protection(&self) -> Option<&CooldownPoint> {
+        self.policies
+            .iter()
+            .filter_map(|point| self.tradeoff(point).map(|cost| (point, cost)))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(point, _)| point)
+    }
+
+    pub fn print(&self) {
+        println!("| Policy                  | Bad Debt | P(Bad Debt) | Borrower Loss | Liquidations | Delay | Bad Debt per $ Saved |");
+        println!("|-------------------------|----------|-------------|---------------|--------------|-------|----------------------|");
+        for point in std::iter::once(&self.whole).chain(&self.policies) {
+            let tradeoff = match (&point.policy, self.tradeoff(point)) {
+                (None, _) => "-".to_string(),
+                (Some(_), Some(cost)) => format!("{:+.3}", cost),
+                (Some(_), None) => "no saving".to_string(),
+            };
+            println!(
+                "| {:23} | ${:7.0} | {:10.1}% | ${:12.0} | {:12.1} | {:5.1} | {:>20} |",
+                point.label(),
+                point.avg_bad_debt,
+                point.bad_debt_frequency * 100.0,
+                point.avg_borrower_loss,
+                point.avg_liquidations,
+                point.avg_liquidation_delay,
+                tradeoff,
+            );
+        }
+        match self.cheapest_protection() {
+            Some(best) => {
+                let saved = self.whole.avg_borrower_loss - best.avg_borrower_loss;
+                let extra = best.avg_bad_debt - self.whole.avg_bad_debt;
+                if extra > 0.0 {
+                    println!("  Cheapest protection: {} saves borrowers ${:.0} for ${:.0} of bad debt", best.label(), saved, extra);
+                } else {
+                    // Smaller liquidations sell less collateral into the cascade
+                    println!("  {} saves borrowers ${:.0} and cuts bad debt by ${:.0}", best.label(), saved, -extra);
+                }
+            }
+            None => println!("  No policy reduces borrower loss"),
+        }
+    }
+}
+
+/// Runs `mechanism` liquidating whole CDPs and under each policy in
+/// `policies`.
+pub fn compare_cooldowns(
+    mechanism: LiquidationMechanism,
+    scenario: PriceScenario,
+    policies: &[LiquidationCooldown],
+    runs: usize,
+    config: &SimulationConfig,
+) -> Result<CooldownReport> {
+    mechanism.validate()?;
+    let run = |policy: Option<LiquidationCooldown>| -> Result<CooldownPoint> {
+        let config = SimulationConfig { liquidation_cooldown: policy, ..config.clone() };
+        config.validate()?;
+        Ok(CooldownPoint::from_results(policy, &run_cascade_simulation(mechanism, scenario, runs, &config)))
+    };
+    let whole = run(None)?;
+    let policies = policies.iter().map(|&policy| run(Some(policy))).collect::<Result<Vec<_>>>()?;
+
+    Ok(CooldownReport { mechanism, scenario, runs, whole, policies })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::ConservationReport;
+    use crate::cascade::{CascadeSimulationBuilder, Keeper, CDP};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_full_close_factor_matches_whole_liquidation() {
+        let plain = SimulationConfig { seed: Some(6), ..Default::default() };
+        let full = SimulationConfig {
+            liquidation_cooldown: Some(LiquidationCooldown { close_factor: 1.0, cooldown_blocks: 10 }),
+            ..plain.clone()
+        };
+        let mechanism = LiquidationMechanism::keeper_pool();
+        let a = run_cascade_simulation(mechanism, PriceScenario::VolatileCrash, 4, &plain);
+        let b = run_cascade_simulation(mechanism, PriceScenario::VolatileCrash, 4, &full);
+
+        for (a, b) in a.iter().zip(&b) {
+            assert_eq!(a.total_liquidations, b.total_liquidations);
+            assert_eq!(a.bad_debt, b.bad_debt);
+            assert_eq!(a.borrower_loss, b.borrower_loss);
+        }
+    }
+
+    #[test]
+    fn test_partial_liquidation_restores_health() {
+        // 10 ETH at $2,000 against $14,000: closing half seizes $7,000 plus
+        // 13% of the $6,000 equity on that half, leaving a healthy CDP
+        let run = |liquidation_cooldown: Option<LiquidationCooldown>| {
+            let config = SimulationConfig { liquidation_cooldown, max_blocks: 10, ..Default::default() };
+            let mut rng = StdRng::seed_from_u64(0);
+            CascadeSimulationBuilder::new()
+                .config(config)
+                .mechanism(LiquidationMechanism::Traditional)
+                .cdps(vec![CDP::from_position(0, 10.0, 14_000.0)])
+                .keepers(vec![Keeper::new(0, 50_000.0, 0.5)])
+                .price_path(vec![2000.0; 11])
+                .build(&mut rng)
+                .unwrap()
+                .run(&mut rng)
+        };
+        let whole = run(None);
+        let partial = run(Some(LiquidationCooldown::default()));
+
+        assert_eq!(whole.total_liquidations, 1);
+        assert!((whole.borrower_loss - 6_000.0).abs() < 1e-6);
+        assert_eq!(partial.total_liquidations, 1);
+        assert!((partial.borrower_loss - 390.0).abs() < 1e-6);
+        assert!((partial.ledger.liquidated_debt - 7_000.0).abs() < 1e-6);
+        assert!((partial.ledger.remaining_collateral - (10.0 - 7_390.0 / 2000.0)).abs() < 1e-9);
+        assert!(ConservationReport::of(&partial).is_consistent());
+    }
+
+    #[test]
+    fn test_report_prices_borrower_protection() {
+        let config = SimulationConfig { seed: Some(3), ..Default::default() };
+        let report =
+            compare_cooldowns(LiquidationMechanism::keeper_pool(), PriceScenario::FlashCrash, &COOLDOWN_POLICIES, 6, &config)
+                .unwrap();
+        let (fast, slow) = (&report.policies[0], &report.policies[2]);
+
+        assert_eq!(report.policies.len(), COOLDOWN_POLICIES.len());
+        assert!(report.policies.iter().all(|p| p.avg_borrower_loss < report.whole.avg_borrower_loss));
+        // A longer cooldown spaces out repeat liquidations of the same CDP
+        assert!(slow.avg_liquidations < fast.avg_liquidations);
+        assert!(report.cheapest_protection().is_some());
+        let bad = [LiquidationCooldown { close_factor: 0.0, cooldown_blocks: 5 }];
+        assert!(compare_cooldowns(LiquidationMechanism::Traditional, PriceScenario::FlashCrash, &bad, 1, &config).is_err());
+    }
+}
@@ -0,0 +1,413 @@
+//! Shared Simulation Core
+//!
+//! The pieces the Price of Anarchy game (`poa`) and the cascade simulation
+//! (`cascade`) both build on, so a change to how a position, a keeper or
+//! the market behaves lands in one place:
+//!
+//! - Positions: `CDP`, collateral and debt with the ratio, penalty and bad
+//!   debt math, and `LiquidationIndex` ordering a book by how close each
+//!   position is to liquidation
+//! - Agents: `Keeper` and its `KeeperBehavior`
+//! - Market: the oracle price under shocks and collateral sales, with a floor
+//! - Accounting: penalty profit, winner/pool splits and profit concentration
+//!
+//! Each simulation layers its own rules on top: `cascade` samples books
+//! from a `CdpDistribution`, accrues yield-bearing collateral and decides
+//! which keepers act; `poa` scores CDPs from hidden features.
+
+use std::cmp::Ordering;
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+/// Lowest oracle price the market model allows, in USD.
+pub const PRICE_FLOOR: f64 = 100.0;
+
+// ---------------------------------------------------------------------------
+// Positions
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone)]
+pub struct CDP {
+    pub(crate) id: usize,
+    pub(crate) collateral: f64,   // Token units (ETH unless accruing)
+    pub(crate) debt: f64,         // USD
+    pub(crate) is_liquidated: bool,
+    pub(crate) accrues: bool,     // Holds the accruing collateral token
+    pub(crate) token_rate: f64,   // ETH per collateral unit
+}
+
+impl CDP {
+    pub fn from_position(id: usize, collateral: f64, debt: f64) -> Self {
+        Self {
+            id,
+            collateral,
+            debt,
+            is_liquidated: false,
+            accrues: false,
+            token_rate: 1.0,
+        }
+    }
+
+    /// Marks the CDP as holding the config's accruing collateral token.
+    pub fn accruing(mut self) -> Self {
+        self.accrues = true;
+        self
+    }
+
+    /// ETH backing the CDP.
+    pub fn collateral_eth(&self) -> f64 {
+        self.collateral * self.token_rate
+    }
+
+    /// USD value of the collateral at `eth_price`.
+    pub fn collateral_value(&self, eth_price: f64) -> f64 {
+        self.collateral_eth() * eth_price
+    }
+
+    /// Removes `eth` of collateral and `debt` of debt in a partial liquidation.
+    pub(crate) fn seize(&mut self, eth: f64, debt: f64) {
+        self.collateral -= eth / self.token_rate;
+        self.debt -= debt;
+    }
+
+    pub fn collateral_ratio(&self, eth_price: f64) -> f64 {
+        if self.debt == 0.0 {
+            return f64::INFINITY;
+        }
+        self.collateral_value(eth_price) / self.debt
+    }
+
+    /// USD of collateral short of `min_collateral_ratio` at `eth_price`:
+    /// how far below the line a CDP sits, scaled by its size.
+    pub(crate) fn shortfall(&self, eth_price: f64, min_collateral_ratio: f64) -> f64 {
+        (self.debt * min_collateral_ratio - self.collateral_value(eth_price)).max(0.0)
+    }
+
+    pub(crate) fn is_underwater(&self, eth_price: f64) -> bool {
+        self.collateral_ratio(eth_price) < 1.0
+    }
+
+    pub(crate) fn is_liquidatable(&self, eth_price: f64, min_collateral_ratio: f64) -> bool {
+        !self.is_liquidated && self.collateral_ratio(eth_price) < min_collateral_ratio
+    }
+
+    /// The CDP's IPFE risk score at `eth_price`, lower being riskier: the
+    /// sum of the features `Fair.sol` computes under unit weights. Ratio is
+    /// capped at 3, utilization at 1 and size, collateral over 10 ETH, at 2;
+    /// volatility is its 0.5 placeholder and every CDP is past the 30-day
+    /// age cap.
+    pub(crate) fn risk_score(&self, eth_price: f64) -> f64 {
+        let ratio = self.collateral_ratio(eth_price);
+        ratio.min(3.0) + 0.5 + (1.0 / ratio).min(1.0) + 1.0 + (self.collateral_eth() / 10.0).min(2.0)
+    }
+
+    /// Penalty earned on the CDP's equity, before gas.
+    pub(crate) fn liquidation_profit(&self, eth_price: f64, penalty: f64) -> f64 {
+        penalty_profit(self.collateral_value(eth_price), self.debt, 0.0, penalty)
+    }
+
+    /// Bonus earned repaying `repaid` of the debt at a fixed spread, capped
+    /// by the collateral left once the repaid debt is covered.
+    pub(crate) fn spread_profit(&self, eth_price: f64, repaid: f64, bonus: f64) -> f64 {
+        (repaid * bonus).min(self.collateral_value(eth_price) - repaid).max(0.0)
+    }
+
+    pub(crate) fn bad_debt(&self, eth_price: f64) -> f64 {
+        if self.is_underwater(eth_price) && !self.is_liquidated {
+            (self.debt - self.collateral_value(eth_price)).max(0.0)
+        } else {
+            0.0
+        }
+    }
+
+    /// ETH per USD of debt: the collateral ratio divided by the price, so
+    /// the ratio's order at any price. None once closed or debt-free.
+    fn cover(&self) -> Option<Cover> {
+        (!self.is_liquidated && self.debt > 0.0).then(|| Cover(self.collateral_eth() / self.debt))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Cover(f64);
+
+impl Eq for Cover {}
+
+impl PartialOrd for Cover {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Cover {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+/// Open CDPs of a book ordered by cover, kept up to date as positions
+/// change, so the liquidatable set at a price is a range of the index
+/// instead of a scan and sort of the whole book. Callers `update` a CDP
+/// after every change to its collateral, debt or status.
+#[derive(Debug, Clone, Default)]
+pub struct LiquidationIndex {
+    by_cover: BTreeSet<(Cover, usize)>, // (cover, book index)
+    covers: Vec<Option<Cover>>,         // Per CDP: its key in `by_cover`
+}
+
+impl LiquidationIndex {
+    pub fn new(cdps: &[CDP]) -> Self {
+        let mut index = Self::default();
+        index.rebuild(cdps);
+        index
+    }
+
+    /// Re-indexes `cdps` from scratch, keeping the per-CDP storage.
+    pub fn rebuild(&mut self, cdps: &[CDP]) {
+        self.covers.clear();
+        self.covers.extend(cdps.iter().map(CDP::cover));
+        // Collecting bulk-builds the tree, far faster than inserting one by one
+        self.by_cover = self.covers.iter().enumerate().filter_map(|(i, cover)| Some(((*cover)?, i))).collect();
+    }
+
+    /// Re-keys CDP `index` after a change to it.
+    pub fn update(&mut self, index: usize, cdp: &CDP) {
+        let cover = cdp.cover();
+        if self.covers[index] == cover {
+            return;
+        }
+        if let Some(old) = self.covers[index] {
+            self.by_cover.remove(&(old, index));
+        }
+        if let Some(new) = cover {
+            self.by_cover.insert((new, index));
+        }
+        self.covers[index] = cover;
+    }
+
+    /// Indices of the CDPs liquidatable at `eth_price`, lowest collateral
+    /// ratio first and ties in book order: exactly what filtering and
+    /// stably sorting the whole book gives.
+    pub fn liquidatable(&self, cdps: &[CDP], eth_price: f64, min_collateral_ratio: f64) -> Vec<usize> {
+        let mut liquidatable = Vec::new();
+        self.liquidatable_into(cdps, eth_price, min_collateral_ratio, &mut liquidatable);
+        liquidatable
+    }
+
+    /// `liquidatable` into a caller's buffer, replacing its contents.
+    pub fn liquidatable_into(&self, cdps: &[CDP], eth_price: f64, min_collateral_ratio: f64, out: &mut Vec<usize>) {
+        // Cover times price rounds differently from the ratio, so take a
+        // sliver more and let the exact test decide
+        let bound = Cover(min_collateral_ratio / eth_price * (1.0 + 1e-9));
+        out.clear();
+        out.extend(
+            self.by_cover
+                .range(..(bound, usize::MAX))
+                .map(|&(_, i)| i)
+                .filter(|&i| cdps[i].is_liquidatable(eth_price, min_collateral_ratio)),
+        );
+        // Breaking ties on the index matches a stable sort without its buffer
+        out.sort_unstable_by(|&a, &b| {
+            cdps[a].collateral_ratio(eth_price).partial_cmp(&cdps[b].collateral_ratio(eth_price)).unwrap().then(a.cmp(&b))
+        });
+    }
+
+    pub fn len(&self) -> usize {
+        self.by_cover.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_cover.is_empty()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Agents
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone)]
+pub struct Keeper {
+    pub(crate) id: usize,
+    pub(crate) capital: f64,      // Available capital for liquidations
+    pub(crate) free_capital: f64, // Capital left this block under shared liquidity
+    pub(crate) gas_priority: f64, // 0-1, higher = faster execution
+    pub(crate) total_profit: f64,
+    pub(crate) gas_spent: f64,     // Gas and priority fees, reverted races included
+    pub(crate) inventory_pnl: f64, // Seized collateral marked from the oracle to the block's closing price
+    pub(crate) liquidations: usize,
+    pub(crate) behavior: KeeperBehavior,
+}
+
+/// How a keeper decides to act. Everything but `Honest` models an adversary.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum KeeperBehavior {
+    Honest,
+    Cartel { min_profit: f64 }, // Colluder withholding until the penalty clears the cartel's bar
+    Sybil,                      // Identity of a single operator that executes once per block
+    Griefer,                    // Joins pools for the share but never executes when selected
+}
+
+impl Keeper {
+    pub fn new(id: usize, capital: f64, gas_priority: f64) -> Self {
+        Self {
+            id,
+            capital,
+            free_capital: capital,
+            gas_priority,
+            total_profit: 0.0,
+            gas_spent: 0.0,
+            inventory_pnl: 0.0,
+            liquidations: 0,
+            behavior: KeeperBehavior::Honest,
+        }
+    }
+
+    /// Capital plus earnings, net of gas and inventory drawdowns.
+    pub fn net_worth(&self) -> f64 {
+        self.capital + self.total_profit - self.gas_spent + self.inventory_pnl
+    }
+
+    pub fn with_behavior(mut self, behavior: KeeperBehavior) -> Self {
+        self.behavior = behavior;
+        self
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Market
+// ---------------------------------------------------------------------------
+
+/// Price after a multiplicative shock (`0.7` = -30%), held at the floor.
+pub fn shocked_price(eth_price: f64, shock: f64) -> f64 {
+    (eth_price * shock).max(PRICE_FLOOR)
+}
+
+/// Price after selling `eth_sold` into a market moving `impact_per_eth` per
+/// ETH, held at the floor.
+pub fn price_after_sale(eth_price: f64, eth_sold: f64, impact_per_eth: f64) -> f64 {
+    let impact = eth_sold * impact_per_eth;
+    (eth_price * (1.0 - impact)).max(PRICE_FLOOR)
+}
+
+// ---------------------------------------------------------------------------
+// Accounting
+// ---------------------------------------------------------------------------
+
+/// Penalty on a position's equity net of `gas_cost`; zero once underwater.
+pub fn penalty_profit(collateral_value: f64, debt: f64, gas_cost: f64, penalty: f64) -> f64 {
+    (collateral_value - debt - gas_cost).max(0.0) * penalty
+}
+
+/// Splits `profit` between the winner and `others` losing participants.
+/// Returns the winner's cut and each loser's.
+pub fn split_with_losers(profit: f64, winner_share: f64, others: usize) -> (f64, f64) {
+    let winner = profit * winner_share;
+    let per_other = profit * (1.0 - winner_share) / others.max(1) as f64;
+    (winner, per_other)
+}
+
+/// Share of `total` earned by the top 20% of keepers (at least one).
+/// `total` is usually the sum of `profits`, but may include profit that
+/// went to the protocol rather than a keeper.
+pub fn profit_concentration(profits: &[f64], total: f64) -> f64 {
+    if total <= 0.0 {
+        return 0.0;
+    }
+    let mut sorted = profits.to_vec();
+    sorted.sort_by(|a, b| b.partial_cmp(a).unwrap());
+    sorted.iter().take((profits.len() / 5).max(1)).sum::<f64>() / total
+}
+
+/// Lorenz curve of `values`: (share of holders, share of the total), from
+/// the smallest holder up. Empty if the total is not positive.
+pub fn lorenz(values: &[f64]) -> Vec<(f64, f64)> {
+    let total: f64 = values.iter().sum();
+    if total <= 0.0 {
+        return Vec::new();
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let n = sorted.len() as f64;
+    let mut cumulative = 0.0;
+    std::iter::once((0.0, 0.0))
+        .chain(sorted.iter().enumerate().map(|(i, value)| {
+            cumulative += value;
+            ((i + 1) as f64 / n, cumulative / total)
+        }))
+        .collect()
+}
+
+/// Gini coefficient of `values`: 0 when everyone holds the same, 1 when
+/// one holder has everything.
+pub fn gini(values: &[f64]) -> f64 {
+    let curve = lorenz(values);
+    let area: f64 = curve.windows(2).map(|w| (w[1].0 - w[0].0) * (w[0].1 + w[1].1) / 2.0).sum();
+    if curve.is_empty() {
+        0.0
+    } else {
+        (1.0 - 2.0 * area).max(0.0) // Rounding can leave perfect equality just below zero
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_position_math() {
+        let mut cdp = CDP::from_position(0, 5.0, 8_000.0);
+        assert_eq!(cdp.collateral_ratio(2000.0), 1.25);
+        assert_eq!(cdp.liquidation_profit(2000.0, 0.1), 200.0);
+        assert_eq!(cdp.bad_debt(1000.0), 3_000.0);
+
+        cdp.seize(2.5, 4_000.0);
+        assert_eq!(cdp.collateral_ratio(2000.0), 1.25);
+        assert_eq!(CDP::from_position(1, 1.0, 0.0).collateral_ratio(2000.0), f64::INFINITY);
+    }
+
+    #[test]
+    fn test_liquidation_index_matches_a_full_scan() {
+        let mut cdps: Vec<CDP> = (0..200)
+            .map(|i| CDP::from_position(i, 1.0 + (i % 17) as f64 * 0.25, 1_000.0 + (i % 11) as f64 * 400.0))
+            .collect();
+        cdps[3].debt = 0.0;
+        let scan = |cdps: &[CDP], price: f64| {
+            let mut all: Vec<usize> = (0..cdps.len()).filter(|&i| cdps[i].is_liquidatable(price, 1.5)).collect();
+            all.sort_by(|&a, &b| cdps[a].collateral_ratio(price).partial_cmp(&cdps[b].collateral_ratio(price)).unwrap());
+            all
+        };
+        let mut index = LiquidationIndex::new(&cdps);
+        assert_eq!(index.len(), 199);
+
+        for (step, price) in [3000.0, 2200.0, 1800.0, 1500.0, 1100.0].into_iter().enumerate() {
+            assert_eq!(index.liquidatable(&cdps, price, 1.5), scan(&cdps, price), "price {}", price);
+            // Partially seize one position and close another, as a round does
+            let picked = index.liquidatable(&cdps, price, 1.5);
+            if let [first, second, ..] = picked[..] {
+                cdps[first].seize(0.1, 200.0 + step as f64);
+                cdps[second].is_liquidated = true;
+                index.update(first, &cdps[first]);
+                index.update(second, &cdps[second]);
+            }
+        }
+        assert!(index.len() < 199);
+    }
+
+    #[test]
+    fn test_market_floor() {
+        assert_eq!(shocked_price(2000.0, 0.7), 1400.0);
+        assert_eq!(shocked_price(120.0, 0.5), PRICE_FLOOR);
+        assert_eq!(price_after_sale(2000.0, 100.0, 0.001), 1800.0);
+        assert_eq!(price_after_sale(150.0, 1e6, 0.001), PRICE_FLOOR);
+    }
+
+    #[test]
+    fn test_accounting() {
+        assert_eq!(penalty_profit(10_000.0, 8_000.0, 50.0, 0.1), 195.0);
+        assert_eq!(penalty_profit(7_000.0, 8_000.0, 0.0, 0.1), 0.0);
+        assert_eq!(split_with_losers(100.0, 0.6, 4), (60.0, 10.0));
+        assert_eq!(profit_concentration(&[0.0; 5], 0.0), 0.0);
+        assert_eq!(profit_concentration(&[50.0, 30.0, 10.0, 10.0, 0.0], 100.0), 0.5);
+        assert_eq!(profit_concentration(&[50.0, 30.0, 10.0, 10.0, 0.0], 200.0), 0.25);
+    }
+}
@@ -0,0 +1,256 @@
+//! Results Database
+//!
+//! An append-only SQLite sink (`sqlite` feature). Each campaign recorded
+//! with `--db` adds one row to `campaigns`: its command, version, commit,
+//! seed and resolved setup. It also adds one row per run to `runs`.
+//! Parameter experiments then compare across weeks of research with SQL
+//! instead of a directory of loose JSON files.
+//!
+//! ```bash
+//! cargo run --release --features sqlite -- cascade --seed 1 --db research.sqlite
+//! FAIR_SIM_DB=research.sqlite cargo run --release --features sqlite -- monte-carlo --runs 2000
+//!
+//! sqlite3 research.sqlite "
+//!   SELECT json_extract(c.setup, '$.simulation.liquidation_penalty') AS penalty, r.mechanism, avg(r.bad_debt)
+//!   FROM runs r JOIN campaigns c ON c.id = r.campaign_id
+//!   GROUP BY penalty, r.mechanism"
+//! ```
+//!
+//! ## Schema
+//! - `campaigns`: `id`, `recorded_at` (Unix seconds), `subcommand`,
+//!   `command`, `crate_version`, `git_hash`, `git_dirty`, `seed`, `setup`
+//!   (the resolved `SimulationSetup` as JSON)
+//! - `runs`: `campaign_id`, `cell` (scenario, preset or price model),
+//!   `mechanism`, `keeper_share`, `run`, `seed`, `bad_debt`,
+//!   `price_drop_pct`, `liquidations` and `participation_rate`. Cascades
+//!   also fill `cascade_depth`, `profit_concentration`, `protocol_revenue`
+//!   and `blocks_to_stability`.
+//!
+//! Seeds are stored as decimal text because they are `u64` and SQLite
+//! integers are signed.
+
+use std::path::Path;
+
+use rusqlite::{params, Connection, Transaction};
+
+use crate::cascade::LiquidationMechanism;
+use crate::error::Result;
+use crate::manifest::RunManifest;
+use crate::monte_carlo::MonteCarloResult;
+use crate::results::CascadeCell;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS campaigns (
+    id INTEGER PRIMARY KEY,
+    recorded_at INTEGER NOT NULL,
+    subcommand TEXT NOT NULL,
+    command TEXT NOT NULL,
+    crate_version TEXT NOT NULL,
+    git_hash TEXT,
+    git_dirty INTEGER NOT NULL,
+    seed TEXT,
+    setup TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS runs (
+    campaign_id INTEGER NOT NULL REFERENCES campaigns(id),
+    cell TEXT NOT NULL,
+    mechanism TEXT NOT NULL,
+    keeper_share REAL NOT NULL,
+    run INTEGER NOT NULL,
+    seed TEXT,
+    bad_debt REAL NOT NULL,
+    price_drop_pct REAL NOT NULL,
+    liquidations INTEGER NOT NULL,
+    participation_rate REAL NOT NULL,
+    cascade_depth INTEGER,
+    profit_concentration REAL,
+    protocol_revenue REAL,
+    blocks_to_stability INTEGER
+);
+CREATE INDEX IF NOT EXISTS runs_by_campaign ON runs(campaign_id);
+";
+
+const INSERT_RUN: &str = "INSERT INTO runs (campaign_id, cell, mechanism, keeper_share, run, seed, bad_debt, \
+    price_drop_pct, liquidations, participation_rate, cascade_depth, profit_concentration, protocol_revenue, \
+    blocks_to_stability) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)";
+
+/// An open results database; every `record_*` call appends one campaign.
+pub struct ResultsDb {
+    conn: Connection,
+}
+
+/// Adds the campaign row and returns its id.
+fn insert_campaign(tx: &Transaction<'_>, manifest: &RunManifest) -> Result<i64> {
+    tx.execute(
+        "INSERT INTO campaigns (recorded_at, subcommand, command, crate_version, git_hash, git_dirty, seed, setup) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            manifest.created_at as i64,
+            manifest.command.get(1).map_or("", String::as_str),
+            manifest.command.join(" "),
+            manifest.crate_version,
+            manifest.git_hash,
+            manifest.git_dirty,
+            manifest.seed.map(|seed| seed.to_string()),
+            serde_json::to_string(&manifest.setup)?,
+        ],
+    )?;
+    Ok(tx.last_insert_rowid())
+}
+
+fn mechanism_columns(mechanism: LiquidationMechanism) -> (String, f64) {
+    (mechanism.name(), mechanism.keeper_share())
+}
+
+impl ResultsDb {
+    /// Opens (or creates) the database at `path`, creating missing tables.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let conn = Connection::open(path)?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { conn })
+    }
+
+    /// The underlying connection, for ad hoc queries.
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+
+    /// Appends a cascade campaign: a run row per result of every cell.
+    /// Returns the campaign id.
+    pub fn record_cascade(&mut self, manifest: &RunManifest, cells: &[CascadeCell]) -> Result<i64> {
+        let tx = self.conn.transaction()?;
+        let campaign = insert_campaign(&tx, manifest)?;
+        {
+            let mut insert = tx.prepare(INSERT_RUN)?;
+            for cell in cells {
+                for (run, result) in cell.results.iter().enumerate() {
+                    let (name, share) = mechanism_columns(result.mechanism);
+                    insert.execute(params![
+                        campaign,
+                        cell.label,
+                        name,
+                        share,
+                        run as i64,
+                        result.seed.map(|seed| seed.to_string()),
+                        result.bad_debt,
+                        result.price_drop_pct,
+                        result.total_liquidations as i64,
+                        result.participation_rate,
+                        result.cascade_depth as i64,
+                        result.profit_concentration,
+                        result.protocol_revenue,
+                        result.blocks_to_stability as i64,
+                    ])?;
+                }
+            }
+        }
+        tx.commit()?;
+        Ok(campaign)
+    }
+
+    /// Appends a Monte Carlo campaign: a run row per path of every result
+    /// set. Returns the campaign id.
+    pub fn record_monte_carlo(&mut self, manifest: &RunManifest, results: &[MonteCarloResult]) -> Result<i64> {
+        let tx = self.conn.transaction()?;
+        let campaign = insert_campaign(&tx, manifest)?;
+        {
+            let mut insert = tx.prepare(INSERT_RUN)?;
+            for result in results {
+                let (name, share) = mechanism_columns(result.mechanism);
+                for run in 0..result.bad_debts.len() {
+                    insert.execute(params![
+                        campaign,
+                        result.model.name(),
+                        name,
+                        share,
+                        run as i64,
+                        result.run_seeds.get(run).map(|seed| seed.to_string()),
+                        result.bad_debts[run],
+                        result.price_drops[run],
+                        result.liquidation_counts[run] as i64,
+                        result.participation_rates[run],
+                        None::<i64>,
+                        None::<f64>,
+                        None::<f64>,
+                        None::<i64>,
+                    ])?;
+                }
+            }
+        }
+        tx.commit()?;
+        Ok(campaign)
+    }
+
+    /// Runs recorded under `campaign`.
+    pub fn run_count(&self, campaign: i64) -> Result<usize> {
+        let count: i64 =
+            self.conn.query_row("SELECT count(*) FROM runs WHERE campaign_id = ?1", [campaign], |row| row.get(0))?;
+        Ok(count as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cascade::{aggregate_results, run_cascade_simulation, PriceScenario, SimulationConfig};
+    use crate::config::SimulationSetup;
+    use crate::monte_carlo::{run_monte_carlo_with_config, PriceModel};
+
+    fn temp_db(name: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("fair-sim-db-{}-{}.sqlite", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    fn manifest(seed: u64) -> RunManifest {
+        let mut setup = SimulationSetup::default();
+        setup.simulation.seed = Some(seed);
+        RunManifest::capture(&setup, vec!["fair-sim".to_string(), "cascade".to_string()])
+    }
+
+    #[test]
+    fn test_campaigns_append_across_sessions() {
+        let path = temp_db("append");
+        let config = SimulationConfig { seed: Some(u64::MAX), ..Default::default() };
+        let results = run_cascade_simulation(LiquidationMechanism::keeper_pool(), PriceScenario::FlashCrash, 3, &config);
+        let cells = [CascadeCell { label: "Flash".to_string(), aggregate: aggregate_results(&results), results }];
+
+        let first = ResultsDb::open(&path).unwrap().record_cascade(&manifest(u64::MAX), &cells).unwrap();
+        let db = &mut ResultsDb::open(&path).unwrap();
+        let second = db.record_cascade(&manifest(1), &cells).unwrap();
+
+        assert_ne!(first, second);
+        assert_eq!(db.run_count(first).unwrap(), 3);
+        assert_eq!(db.run_count(second).unwrap(), 3);
+        let (subcommand, seed): (String, String) = db
+            .connection()
+            .query_row("SELECT subcommand, seed FROM campaigns WHERE id = ?1", [first], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert_eq!((subcommand.as_str(), seed), ("cascade", u64::MAX.to_string()));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_monte_carlo_rows_match_the_paths() {
+        let path = temp_db("monte-carlo");
+        let config = SimulationConfig { seed: Some(2), ..Default::default() };
+        let result = run_monte_carlo_with_config(PriceModel::GBM, LiquidationMechanism::Traditional, 4, &config);
+        let mut db = ResultsDb::open(&path).unwrap();
+        let campaign = db.record_monte_carlo(&manifest(2), std::slice::from_ref(&result)).unwrap();
+
+        let (total, depth): (f64, Option<i64>) = db
+            .connection()
+            .query_row("SELECT sum(bad_debt), max(cascade_depth) FROM runs WHERE campaign_id = ?1", [campaign], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert_eq!(db.run_count(campaign).unwrap(), 4);
+        assert!((total - result.bad_debts.iter().sum::<f64>()).abs() < 1e-6);
+        assert_eq!(depth, None);
+        let setup: String = db.connection().query_row("SELECT setup FROM campaigns", [], |row| row.get(0)).unwrap();
+        assert_eq!(serde_json::from_str::<SimulationSetup>(&setup).unwrap().simulation.seed, Some(2));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
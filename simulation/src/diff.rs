@@ -0,0 +1,302 @@
+//! Campaign Result Diff
+//!
+//! Compares two saved Monte Carlo or cascade result sets (e.g. before/after
+//! a parameter change) cell by cell and reports metric deltas with
+//! significance flags.
+//!
+//! ## Significance
+//! - Means (bad debt, price drop, participation, ...): Welch test
+//! - Probabilities (bad debt, insolvency): two-proportion z-test
+//! - `**` p < 0.01, `*` p < 0.05, blank otherwise
+//! - Tail quantiles (VaR/CVaR) are reported without a test
+//! - Tested metrics also carry a 95% interval on their relative change
+//!   (delta method), so a delta reads as e.g. `-34.0% ± 5.1%`
+
+use crate::cascade::LiquidationMechanism;
+use crate::monte_carlo::{MonteCarloResult, INSOLVENCY_THRESHOLD};
+use crate::results::CascadeCell;
+use crate::stats;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Significance {
+    Strong,       // p < 0.01
+    Weak,         // p < 0.05
+    None,
+    NotTested,
+}
+
+impl Significance {
+    fn from_p_value(p: f64) -> Self {
+        if p < 0.01 {
+            Self::Strong
+        } else if p < 0.05 {
+            Self::Weak
+        } else {
+            Self::None
+        }
+    }
+
+    pub fn flag(&self) -> &'static str {
+        match self {
+            Self::Strong => "**",
+            Self::Weak => "*",
+            Self::None => "",
+            Self::NotTested => "n/a",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MetricDelta {
+    pub metric: &'static str,
+    pub before: f64,
+    pub after: f64,
+    pub p_value: Option<f64>,
+    pub significance: Significance,
+    pub relative_ci: Option<f64>, // 95% half-width of the relative change, as a fraction
+}
+
+impl MetricDelta {
+    fn new(metric: &'static str, before: f64, after: f64, p_value: Option<f64>) -> Self {
+        let significance = p_value.map_or(Significance::NotTested, Significance::from_p_value);
+        Self {
+            metric,
+            before,
+            after,
+            p_value,
+            significance,
+            relative_ci: None,
+        }
+    }
+
+    /// Difference in means of two samples: Welch test plus interval.
+    fn means(metric: &'static str, before: &[f64], after: &[f64]) -> Self {
+        let mut delta = Self::new(metric, stats::mean(before), stats::mean(after), Some(stats::welch_p_value(before, after)));
+        delta.relative_ci = stats::relative_change(before, after).map(|(_, half_width)| half_width);
+        delta
+    }
+
+    /// Difference in the share of samples where `event` holds: two-proportion
+    /// test plus interval on the indicator means.
+    fn share(metric: &'static str, before: &[f64], after: &[f64], event: impl Fn(f64) -> bool) -> Self {
+        let indicator = |xs: &[f64]| xs.iter().map(|&x| if event(x) { 1.0 } else { 0.0 }).collect::<Vec<f64>>();
+        let (a, b) = (indicator(before), indicator(after));
+        let p_value = stats::proportion_p_value(
+            count_where(before, &event),
+            before.len(),
+            count_where(after, &event),
+            after.len(),
+        );
+        let mut delta = Self::new(metric, stats::mean(&a), stats::mean(&b), Some(p_value));
+        delta.relative_ci = stats::relative_change(&a, &b).map(|(_, half_width)| half_width);
+        delta
+    }
+
+    pub fn delta(&self) -> f64 {
+        self.after - self.before
+    }
+
+    pub fn delta_pct(&self) -> Option<f64> {
+        (self.before != 0.0).then(|| self.delta() / self.before.abs() * 100.0)
+    }
+
+    /// One line for a summary, e.g. `Mean bad debt: -34.0% ± 5.1% (p = 0.0002)`.
+    pub fn describe(&self) -> String {
+        let mut line = format!("{}: {}", self.metric, format_delta(self));
+        if let Some(p) = self.p_value {
+            line.push_str(&format!(" (p = {:.4})", p));
+        }
+        line
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CellDiff {
+    pub label: String, // Price model, scenario or preset
+    pub mechanism: LiquidationMechanism,
+    pub deltas: Vec<MetricDelta>,
+}
+
+fn count_where(xs: &[f64], pred: &impl Fn(f64) -> bool) -> usize {
+    xs.iter().filter(|&&x| pred(x)).count()
+}
+
+pub fn diff_cell(before: &MonteCarloResult, after: &MonteCarloResult) -> CellDiff {
+    let (a, b) = (&before.bad_debts, &after.bad_debts);
+
+    let deltas = vec![
+        MetricDelta {
+            before: before.mean_bad_debt,
+            after: after.mean_bad_debt,
+            ..MetricDelta::means("Mean bad debt", a, b)
+        },
+        MetricDelta {
+            before: before.bad_debt_probability,
+            after: after.bad_debt_probability,
+            ..MetricDelta::share("P(bad debt)", a, b, |d| d > 0.0)
+        },
+        MetricDelta {
+            before: before.insolvency_probability,
+            after: after.insolvency_probability,
+            ..MetricDelta::share("P(insolvency)", a, b, |d| d > INSOLVENCY_THRESHOLD)
+        },
+        MetricDelta::new("VaR 99%", before.var_99, after.var_99, None),
+        MetricDelta::new("CVaR 99%", before.cvar_99, after.cvar_99, None),
+        MetricDelta::means("Price drop %", &before.price_drops, &after.price_drops),
+        MetricDelta::means("Participation", &before.participation_rates, &after.participation_rates),
+    ];
+
+    CellDiff {
+        label: before.model.name().to_string(),
+        mechanism: before.mechanism,
+        deltas,
+    }
+}
+
+/// Matches cells by (model, mechanism); cells present in only one set are skipped.
+pub fn diff_results(before: &[MonteCarloResult], after: &[MonteCarloResult]) -> Vec<CellDiff> {
+    before
+        .iter()
+        .filter_map(|b| {
+            after
+                .iter()
+                .find(|a| a.model == b.model && a.mechanism == b.mechanism)
+                .map(|a| diff_cell(b, a))
+        })
+        .collect()
+}
+
+/// Diffs two cascade cells over their per-run results.
+pub fn diff_cascade_cell(before: &CascadeCell, after: &CascadeCell) -> CellDiff {
+    let metric = |f: fn(&crate::cascade::CascadeResult) -> f64| {
+        (before.results.iter().map(f).collect::<Vec<_>>(), after.results.iter().map(f).collect::<Vec<_>>())
+    };
+    let (bad_a, bad_b) = metric(|r| r.bad_debt);
+    let means = |name, f| {
+        let (a, b) = metric(f);
+        MetricDelta::means(name, &a, &b)
+    };
+
+    let deltas = vec![
+        MetricDelta::means("Mean bad debt", &bad_a, &bad_b),
+        MetricDelta::share("P(bad debt)", &bad_a, &bad_b, |d| d > 0.0),
+        means("Liquidations", |r| r.total_liquidations as f64),
+        means("Cascade depth", |r| r.cascade_depth as f64),
+        means("Price drop %", |r| r.price_drop_pct),
+        means("Participation", |r| r.participation_rate),
+        means("Profit concentration", |r| r.profit_concentration),
+        means("Protocol revenue", |r| r.protocol_revenue),
+    ];
+
+    CellDiff {
+        label: before.label.clone(),
+        mechanism: before.aggregate.mechanism,
+        deltas,
+    }
+}
+
+/// Matches cells by (label, mechanism); cells present in only one set, or
+/// saved without their runs (split sweeps), are skipped.
+pub fn diff_cascades(before: &[CascadeCell], after: &[CascadeCell]) -> Vec<CellDiff> {
+    before
+        .iter()
+        .filter(|b| !b.results.is_empty())
+        .filter_map(|b| {
+            after
+                .iter()
+                .find(|a| a.label == b.label && a.aggregate.mechanism == b.aggregate.mechanism && !a.results.is_empty())
+                .map(|a| diff_cascade_cell(b, a))
+        })
+        .collect()
+}
+
+fn format_value(x: f64) -> String {
+    if x.abs() >= 100.0 {
+        format!("{:.0}", x)
+    } else {
+        format!("{:.4}", x)
+    }
+}
+
+fn format_delta(d: &MetricDelta) -> String {
+    match (d.delta_pct(), d.relative_ci) {
+        (Some(pct), Some(ci)) => format!("{:+.1}% ± {:.1}%", pct, ci * 100.0),
+        (Some(pct), None) => format!("{:+.1}%", pct),
+        (None, _) if d.delta() >= 0.0 => format!("+{}", format_value(d.delta())),
+        (None, _) => format_value(d.delta()),
+    }
+}
+
+/// Renders the diff as a Markdown section per cell, ready for a forum post.
+pub fn to_markdown(diffs: &[CellDiff]) -> String {
+    let mut out = String::new();
+
+    for cell in diffs {
+        out.push_str(&format!("### {} / {}\n\n", cell.label, cell.mechanism.name()));
+        out.push_str("| Metric | Before | After | Delta | Sig. |\n");
+        out.push_str("|--------|-------:|------:|------:|:----:|\n");
+        for d in &cell.deltas {
+            out.push_str(&format!(
+                "| {} | {} | {} | {} | {} |\n",
+                d.metric,
+                format_value(d.before),
+                format_value(d.after),
+                format_delta(d),
+                d.significance.flag()
+            ));
+        }
+        out.push('\n');
+    }
+
+    out.push_str("_Significance: `**` p < 0.01, `*` p < 0.05 (Welch / two-proportion z-test)._\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cascade::{aggregate_results, run_cascade_simulation, PriceScenario, SimulationConfig};
+    use crate::monte_carlo::{run_monte_carlo, PriceModel};
+
+    #[test]
+    fn test_identical_results_not_significant() {
+        let result = run_monte_carlo(PriceModel::GBM, LiquidationMechanism::keeper_pool(), 50);
+        let diffs = diff_results(std::slice::from_ref(&result), std::slice::from_ref(&result));
+
+        assert_eq!(diffs.len(), 1);
+        for d in &diffs[0].deltas {
+            assert!(d.delta().abs() < 1e-9);
+            assert_ne!(d.significance, Significance::Strong);
+            assert_ne!(d.significance, Significance::Weak);
+        }
+    }
+
+    #[test]
+    fn test_markdown_output() {
+        let before = run_monte_carlo(PriceModel::GBM, LiquidationMechanism::Traditional, 20);
+        let mut after = before.clone();
+        after.mean_bad_debt += 1000.0;
+
+        let md = to_markdown(&diff_results(&[before], &[after]));
+        assert!(md.contains("| Metric | Before | After | Delta | Sig. |"));
+        assert!(md.contains("Mean bad debt"));
+    }
+
+    #[test]
+    fn test_cascade_cells_carry_intervals() {
+        let cell = |penalty: f64| {
+            let config = SimulationConfig { liquidation_penalty: penalty, seed: Some(8), ..Default::default() };
+            let results = run_cascade_simulation(LiquidationMechanism::Traditional, PriceScenario::FlashCrash, 30, &config);
+            CascadeCell { label: "Flash Crash".to_string(), aggregate: aggregate_results(&results), results }
+        };
+        let (before, after) = (cell(0.13), cell(0.05));
+        let diffs = diff_cascades(std::slice::from_ref(&before), &[after, CascadeCell { results: vec![], ..before.clone() }]);
+
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(diffs[0].label, "Flash Crash");
+        let liquidations = diffs[0].deltas.iter().find(|d| d.metric == "Liquidations").unwrap();
+        assert!(liquidations.relative_ci.is_some_and(|ci| ci > 0.0));
+        assert!(liquidations.describe().starts_with("Liquidations: "));
+        assert!(diff_cascades(&[CascadeCell { results: vec![], ..before.clone() }], &[before]).is_empty());
+    }
+}
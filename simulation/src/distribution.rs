@@ -0,0 +1,223 @@
+//! Metric Distributions
+//!
+//! `aggregate_results` keeps averages, which hide the tails. This summarizes
+//! every cell's runs as empirical distributions instead: quantiles and an
+//! equal-width histogram of bad debt, cascade depth and participation.
+//!
+//! ```bash
+//! fair-sim cascade --seed 1 --distributions distributions.json
+//! fair-sim cascade --seed 1 --distributions distributions.csv --bins 50 --quantiles 0.5,0.9,0.99,0.999
+//! ```
+//!
+//! ## CSV
+//! Long format, one row per statistic: `label,mechanism,metric,statistic,
+//! lower,upper,value`. Quantile rows (`statistic` = `q0.99`) leave the
+//! bounds empty; histogram rows (`bin`) carry their bin's bounds and count.
+
+use std::io::Write;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::cascade::{CascadeResult, LiquidationMechanism};
+use crate::error::{Error, Result};
+use crate::monte_carlo::percentile;
+use crate::results::{save_json, CascadeCell};
+
+/// Quantiles reported unless configured otherwise.
+pub const DEFAULT_QUANTILES: [f64; 7] = [0.01, 0.05, 0.25, 0.5, 0.75, 0.95, 0.99];
+
+pub const DEFAULT_BINS: usize = 20;
+
+/// Metrics summarized per cell.
+pub const DISTRIBUTION_METRICS: [&str; 3] = ["bad_debt", "cascade_depth", "participation_rate"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DistributionConfig {
+    pub quantiles: Vec<f64>,
+    pub bins: usize,
+}
+
+impl Default for DistributionConfig {
+    fn default() -> Self {
+        Self { quantiles: DEFAULT_QUANTILES.to_vec(), bins: DEFAULT_BINS }
+    }
+}
+
+impl DistributionConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.bins == 0 {
+            return Err(Error::Invalid("histograms need at least one bin".to_string()));
+        }
+        if let Some(q) = self.quantiles.iter().find(|q| !(0.0..=1.0).contains(*q)) {
+            return Err(Error::Invalid(format!("quantile {} is outside [0, 1]", q)));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bin {
+    pub lower: f64,
+    pub upper: f64, // Inclusive for the last bin
+    pub count: usize,
+}
+
+/// An empirical distribution of one metric over a cell's runs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Distribution {
+    pub runs: usize,
+    pub mean: f64,
+    pub min: f64,
+    pub max: f64,
+    pub quantiles: Vec<(f64, f64)>, // (probability, value)
+    pub histogram: Vec<Bin>,        // Equal-width bins over [min, max]
+}
+
+impl Distribution {
+    pub fn from_values(values: &[f64], config: &DistributionConfig) -> Self {
+        let mut sorted = values.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let (min, max) = (sorted.first().copied().unwrap_or(0.0), sorted.last().copied().unwrap_or(0.0));
+
+        let width = (max - min) / config.bins as f64;
+        let mut counts = vec![0; config.bins];
+        for &value in &sorted {
+            let bin = if width > 0.0 { ((value - min) / width) as usize } else { 0 };
+            counts[bin.min(config.bins - 1)] += 1;
+        }
+        let histogram = counts
+            .into_iter()
+            .enumerate()
+            .map(|(i, count)| Bin { lower: min + i as f64 * width, upper: min + (i + 1) as f64 * width, count })
+            .collect();
+
+        Self {
+            runs: values.len(),
+            mean: crate::stats::mean(values),
+            min,
+            max,
+            quantiles: config.quantiles.iter().map(|&q| (q, percentile(&sorted, q))).collect(),
+            histogram,
+        }
+    }
+
+    pub fn quantile(&self, q: f64) -> Option<f64> {
+        self.quantiles.iter().find(|(p, _)| *p == q).map(|&(_, value)| value)
+    }
+}
+
+/// Distributions of every `DISTRIBUTION_METRICS` metric for one cell.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CellDistributions {
+    pub label: String,
+    pub mechanism: LiquidationMechanism,
+    pub bad_debt: Distribution,
+    pub cascade_depth: Distribution,
+    pub participation_rate: Distribution,
+}
+
+impl CellDistributions {
+    pub fn from_cell(cell: &CascadeCell, config: &DistributionConfig) -> Self {
+        let of = |f: fn(&CascadeResult) -> f64| {
+            Distribution::from_values(&cell.results.iter().map(f).collect::<Vec<_>>(), config)
+        };
+        Self {
+            label: cell.label.clone(),
+            mechanism: cell.aggregate.mechanism,
+            bad_debt: of(|r| r.bad_debt),
+            cascade_depth: of(|r| r.cascade_depth as f64),
+            participation_rate: of(|r| r.participation_rate),
+        }
+    }
+
+    /// `(metric, distribution)` in `DISTRIBUTION_METRICS` order.
+    pub fn metrics(&self) -> [(&'static str, &Distribution); 3] {
+        [
+            (DISTRIBUTION_METRICS[0], &self.bad_debt),
+            (DISTRIBUTION_METRICS[1], &self.cascade_depth),
+            (DISTRIBUTION_METRICS[2], &self.participation_rate),
+        ]
+    }
+}
+
+/// Summarizes every cell that kept its runs.
+pub fn summarize_cells(cells: &[CascadeCell], config: &DistributionConfig) -> Result<Vec<CellDistributions>> {
+    config.validate()?;
+    Ok(cells
+        .iter()
+        .filter(|cell| !cell.results.is_empty())
+        .map(|cell| CellDistributions::from_cell(cell, config))
+        .collect())
+}
+
+pub fn write_csv(distributions: &[CellDistributions], mut out: impl Write) -> Result<()> {
+    writeln!(out, "label,mechanism,metric,statistic,lower,upper,value")?;
+    for cell in distributions {
+        for (metric, distribution) in cell.metrics() {
+            let prefix = format!("\"{}\",\"{}\",{}", cell.label, cell.mechanism.name(), metric);
+            for (q, value) in &distribution.quantiles {
+                writeln!(out, "{},q{},,,{}", prefix, q, value)?;
+            }
+            for bin in &distribution.histogram {
+                writeln!(out, "{},bin,{},{},{}", prefix, bin.lower, bin.upper, bin.count)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Saves as CSV for a `.csv` path, JSON otherwise.
+pub fn save(path: impl AsRef<Path>, distributions: &[CellDistributions]) -> Result<()> {
+    let path = path.as_ref();
+    if path.extension().is_some_and(|ext| ext == "csv") {
+        let mut out = std::io::BufWriter::new(std::fs::File::create(path)?);
+        write_csv(distributions, &mut out)?;
+        out.flush()?;
+        Ok(())
+    } else {
+        save_json(path, distributions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cascade::{aggregate_results, run_cascade_simulation, PriceScenario, SimulationConfig};
+
+    #[test]
+    fn test_quantiles_and_bins() {
+        let values: Vec<f64> = (0..=100).map(f64::from).collect();
+        let config = DistributionConfig { quantiles: vec![0.01, 0.5, 0.99], bins: 4 };
+        let distribution = Distribution::from_values(&values, &config);
+
+        assert_eq!(distribution.quantile(0.5), Some(50.0));
+        assert_eq!(distribution.quantile(0.99), Some(99.0));
+        assert_eq!(distribution.histogram.iter().map(|b| b.count).collect::<Vec<_>>(), vec![25, 25, 25, 26]);
+        assert_eq!((distribution.histogram[3].upper, distribution.max), (100.0, 100.0));
+
+        let flat = Distribution::from_values(&[3.0; 5], &config);
+        assert_eq!(flat.histogram[0].count, 5);
+        assert!(DistributionConfig { bins: 0, ..config.clone() }.validate().is_err());
+        assert!(DistributionConfig { quantiles: vec![1.5], ..config }.validate().is_err());
+    }
+
+    #[test]
+    fn test_cells_export_every_metric() {
+        let config = SimulationConfig { seed: Some(9), ..Default::default() };
+        let results = run_cascade_simulation(LiquidationMechanism::Traditional, PriceScenario::FlashCrash, 20, &config);
+        let cell = CascadeCell { label: "Flash".to_string(), aggregate: aggregate_results(&results), results };
+        let summaries = summarize_cells(std::slice::from_ref(&cell), &DistributionConfig::default()).unwrap();
+
+        let bad_debt = &summaries[0].bad_debt;
+        assert_eq!(bad_debt.runs, 20);
+        assert!((bad_debt.mean - cell.aggregate.avg_bad_debt).abs() < 1e-6);
+        assert_eq!(bad_debt.max, cell.aggregate.max_bad_debt);
+        assert_eq!(bad_debt.histogram.iter().map(|b| b.count).sum::<usize>(), 20);
+
+        let mut csv = Vec::new();
+        write_csv(&summaries, &mut csv).unwrap();
+        let rows = String::from_utf8(csv).unwrap().lines().count();
+        assert_eq!(rows, 1 + 3 * (DEFAULT_QUANTILES.len() + DEFAULT_BINS));
+    }
+}
@@ -0,0 +1,215 @@
+//! Reward Token Emissions
+//!
+//! Whether paying keeper pool participants partly in a protocol token keeps
+//! them liquidating when that token collapses. The token trades on system
+//! health (see `cascade::RewardToken`), so its price falls in exactly the
+//! cascades where Fair most needs keepers; a keeper paid in it sees its
+//! payout shrink with the system it is meant to protect.
+//!
+//! ## Method
+//! - Sweeps the token's share of the keeper payout under one price
+//!   scenario; every share sees the same seeds when `config.seed` is set,
+//!   and the token model draws nothing from the RNG
+//! - A run counts as a collapse when the token ends below
+//!   `COLLAPSED_TOKEN_PRICE` of par; participation and bad debt are also
+//!   reported over collapse runs alone, which is where the question lives
+
+use crate::cascade::{
+    run_cascade_simulation, CascadeResult, LiquidationMechanism, PriceScenario, RewardToken, SimulationConfig,
+};
+use crate::error::{Error, Result};
+
+/// Token shares of the keeper payout swept by default, stablecoin-only first.
+pub const EMISSION_SWEEP: [f64; 5] = [0.0, 0.25, 0.5, 0.75, 1.0];
+
+/// Token price (relative to par) below which a run counts as a collapse.
+pub const COLLAPSED_TOKEN_PRICE: f64 = 0.5;
+
+#[derive(Debug, Clone)]
+pub struct EmissionsPoint {
+    pub emission_share: f64,
+    pub avg_liquidations: f64,
+    pub avg_bad_debt: f64,
+    pub avg_participation_rate: f64,
+    pub avg_final_token_price: f64,
+    pub avg_protocol_revenue: f64,
+    pub avg_tokens_emitted: f64,       // USD at par
+    pub collapse_runs: usize,
+    pub collapse_participation: Option<f64>, // None when no run collapsed
+    pub collapse_bad_debt: Option<f64>,
+}
+
+impl EmissionsPoint {
+    fn from_results(emission_share: f64, results: &[CascadeResult]) -> Self {
+        let mean = |runs: &[&CascadeResult], f: fn(&CascadeResult) -> f64| {
+            if runs.is_empty() {
+                None
+            } else {
+                Some(runs.iter().map(|r| f(r)).sum::<f64>() / runs.len() as f64)
+            }
+        };
+        let all: Vec<&CascadeResult> = results.iter().collect();
+        let collapsed: Vec<&CascadeResult> = results
+            .iter()
+            .filter(|r| r.final_token_price.is_some_and(|p| p < COLLAPSED_TOKEN_PRICE))
+            .collect();
+        let avg = |f: fn(&CascadeResult) -> f64| mean(&all, f).unwrap_or(0.0);
+        Self {
+            emission_share,
+            avg_liquidations: avg(|r| r.total_liquidations as f64),
+            avg_bad_debt: avg(|r| r.bad_debt),
+            avg_participation_rate: avg(|r| r.participation_rate),
+            avg_final_token_price: avg(|r| r.final_token_price.unwrap_or(1.0)),
+            avg_protocol_revenue: avg(|r| r.protocol_revenue),
+            avg_tokens_emitted: avg(|r| r.tokens_emitted),
+            collapse_runs: collapsed.len(),
+            collapse_participation: mean(&collapsed, |r| r.participation_rate),
+            collapse_bad_debt: mean(&collapsed, |r| r.bad_debt),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EmissionsReport {
+    pub mechanism: LiquidationMechanism,
+    pub scenario: PriceScenario,
+    pub runs: usize,
+    pub points: Vec<EmissionsPoint>, // In sweep order
+}
+
+impl EmissionsReport {
+    /// Participation lost in collapse runs between the lowest and highest
+    /// swept token share; positive means token pay drove keepers away.
+    pub fn collapse_participation_drop(&self) -> Option<f64> {
+        let lowest = self.points.iter().min_by(|a, b| a.emission_share.total_cmp(&b.emission_share))?;
+        let highest = self.points.iter().max_by(|a, b| a.emission_share.total_cmp(&b.emission_share))?;
+        Some(lowest.collapse_participation? - highest.collapse_participation?)
+    }
+
+    pub fn print(&self) {
+        println!("| Token Share | Liquidations | Bad Debt | Participation | Token Price | Collapses | Collapse Part. |");
+        println!("|-------------|--------------|----------|---------------|-------------|-----------|----------------|");
+        for point in &self.points {
+            let collapse = match point.collapse_participation {
+                Some(rate) => format!("{:.1}%", rate * 100.0),
+                None => "-".to_string(),
+            };
+            println!(
+                "| {:10.0}% | {:12.1} | ${:7.0} | {:12.1}% | {:10.1}% | {:9} | {:>14} |",
+                point.emission_share * 100.0,
+                point.avg_liquidations,
+                point.avg_bad_debt,
+                point.avg_participation_rate * 100.0,
+                point.avg_final_token_price * 100.0,
+                point.collapse_runs,
+                collapse,
+            );
+        }
+        match self.collapse_participation_drop() {
+            Some(drop) if drop > 0.0 => {
+                println!("  Token pay costs {:.1} pts of participation when the token collapses", drop * 100.0)
+            }
+            Some(_) => println!("  Token incentives hold up participation through token collapses"),
+            None => println!("  No run collapsed the token below {:.0}% of par", COLLAPSED_TOKEN_PRICE * 100.0),
+        }
+    }
+}
+
+/// Runs the keeper pool paying each token share in `shares` of the keeper
+/// payout, with the token model otherwise taken from `config.reward_token`
+/// (or its defaults).
+pub fn sweep_emission_share(
+    mechanism: LiquidationMechanism,
+    scenario: PriceScenario,
+    shares: &[f64],
+    runs: usize,
+    config: &SimulationConfig,
+) -> Result<EmissionsReport> {
+    mechanism.validate()?;
+    if !mechanism.is_keeper_pool() {
+        return Err(Error::Invalid("reward token emissions apply to the keeper pool only".to_string()));
+    }
+    config.validate()?;
+    let base = config.reward_token.clone().unwrap_or_default();
+
+    let mut points = Vec::with_capacity(shares.len());
+    for &emission_share in shares {
+        let emitting = SimulationConfig {
+            reward_token: Some(RewardToken { emission_share, ..base.clone() }),
+            ..config.clone()
+        };
+        emitting.validate()?;
+        points.push(EmissionsPoint::from_results(
+            emission_share,
+            &run_cascade_simulation(mechanism, scenario, runs, &emitting),
+        ));
+    }
+
+    Ok(EmissionsReport { mechanism, scenario, runs, points })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cascade::{CascadeSimulationBuilder, CDP};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_stablecoin_only_matches_no_token() {
+        let plain = SimulationConfig { seed: Some(3), ..Default::default() };
+        let token = SimulationConfig {
+            reward_token: Some(RewardToken { emission_share: 0.0, ..Default::default() }),
+            ..plain.clone()
+        };
+        let mechanism = LiquidationMechanism::keeper_pool();
+        let a = run_cascade_simulation(mechanism, PriceScenario::FlashCrash, 5, &plain);
+        let b = run_cascade_simulation(mechanism, PriceScenario::FlashCrash, 5, &token);
+
+        for (a, b) in a.iter().zip(&b) {
+            assert_eq!(a.total_liquidations, b.total_liquidations);
+            assert_eq!(a.bad_debt, b.bad_debt);
+            assert_eq!(a.protocol_revenue, b.protocol_revenue);
+            assert_eq!(b.tokens_emitted, 0.0);
+        }
+    }
+
+    #[test]
+    fn test_collapsed_token_stalls_marginal_liquidation() {
+        // ETH halves: a $26 penalty pays keepers $18.20 in stablecoin, but
+        // only 0.5^3 of that when paid entirely in the collapsed token
+        let run = |reward_token: Option<RewardToken>| {
+            let config = SimulationConfig { reward_token, max_blocks: 3, ..Default::default() };
+            let mut rng = StdRng::seed_from_u64(0);
+            CascadeSimulationBuilder::new()
+                .config(config)
+                .cdps(vec![CDP::from_position(0, 1.0, 800.0)])
+                .price_path(vec![2000.0, 1000.0])
+                .build(&mut rng)
+                .unwrap()
+                .run(&mut rng)
+        };
+        let stable = run(None);
+        let token = run(Some(RewardToken { emission_share: 1.0, health_beta: 3.0 }));
+
+        assert_eq!(stable.total_liquidations, 1);
+        assert_eq!(token.total_liquidations, 0);
+        assert_eq!(token.final_token_price, Some(0.125));
+    }
+
+    #[test]
+    fn test_sweep_reports_emissions() {
+        let config = SimulationConfig { seed: Some(8), ..Default::default() };
+        let report =
+            sweep_emission_share(LiquidationMechanism::keeper_pool(), PriceScenario::FlashCrash, &[0.0, 1.0], 10, &config)
+                .unwrap();
+        let (stable, token) = (&report.points[0], &report.points[1]);
+
+        assert_eq!(stable.avg_tokens_emitted, 0.0);
+        assert!(token.avg_tokens_emitted > 0.0);
+        assert!(token.avg_final_token_price < COLLAPSED_TOKEN_PRICE);
+        assert!(token.avg_liquidations < stable.avg_liquidations);
+        assert!(sweep_emission_share(LiquidationMechanism::Traditional, PriceScenario::BlackSwan, &[0.5], 1, &config)
+            .is_err());
+    }
+}
@@ -0,0 +1,107 @@
+//! Error types for loading external inputs (snapshots, configs, saved results).
+
+use std::fmt;
+
+#[derive(Debug)]
+pub enum Error {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    Toml(toml::de::Error),
+    Yaml(serde_yaml::Error),
+    #[cfg(feature = "arrow")]
+    Parquet(parquet::errors::ParquetError),
+    #[cfg(feature = "plots")]
+    Plot(String),
+    #[cfg(feature = "sqlite")]
+    Sqlite(rusqlite::Error),
+    #[cfg(feature = "gpu")]
+    Gpu(String),
+    Invalid(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "I/O error: {}", e),
+            Self::Json(e) => write!(f, "JSON error: {}", e),
+            Self::Toml(e) => write!(f, "TOML error: {}", e),
+            Self::Yaml(e) => write!(f, "YAML error: {}", e),
+            #[cfg(feature = "arrow")]
+            Self::Parquet(e) => write!(f, "Parquet error: {}", e),
+            #[cfg(feature = "plots")]
+            Self::Plot(msg) => write!(f, "plot error: {}", msg),
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(e) => write!(f, "SQLite error: {}", e),
+            #[cfg(feature = "gpu")]
+            Self::Gpu(msg) => write!(f, "GPU error: {}", msg),
+            Self::Invalid(msg) => write!(f, "invalid input: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Json(e) => Some(e),
+            Self::Toml(e) => Some(e),
+            Self::Yaml(e) => Some(e),
+            #[cfg(feature = "arrow")]
+            Self::Parquet(e) => Some(e),
+            #[cfg(feature = "plots")]
+            Self::Plot(_) => None,
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(e) => Some(e),
+            #[cfg(feature = "gpu")]
+            Self::Gpu(_) => None,
+            Self::Invalid(_) => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Json(e)
+    }
+}
+
+impl From<toml::de::Error> for Error {
+    fn from(e: toml::de::Error) -> Self {
+        Self::Toml(e)
+    }
+}
+
+impl From<serde_yaml::Error> for Error {
+    fn from(e: serde_yaml::Error) -> Self {
+        Self::Yaml(e)
+    }
+}
+
+#[cfg(feature = "arrow")]
+impl From<parquet::errors::ParquetError> for Error {
+    fn from(e: parquet::errors::ParquetError) -> Self {
+        Self::Parquet(e)
+    }
+}
+
+#[cfg(feature = "arrow")]
+impl From<arrow::error::ArrowError> for Error {
+    fn from(e: arrow::error::ArrowError) -> Self {
+        Self::Parquet(e.into())
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl From<rusqlite::Error> for Error {
+    fn from(e: rusqlite::Error) -> Self {
+        Self::Sqlite(e)
+    }
+}
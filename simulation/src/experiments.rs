@@ -0,0 +1,243 @@
+//! Named Experiment Registry
+//!
+//! Campaigns are registered under a unique name with a description and tags.
+//! Results are persisted alongside an index so the body of simulation
+//! evidence stays navigable.
+//!
+//! ## Layout
+//! ```text
+//! experiments/
+//!   registry.json        # index of all experiments
+//!   <name>.json          # Monte Carlo result set for each experiment
+//!   <name>.manifest.json # its run manifest, when registered with one
+//! ```
+//!
+//! Names are checked against this layout: `registry` and names ending in
+//! `.manifest` are rejected, since their results would overwrite the index
+//! or another experiment's manifest.
+
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::manifest::{sidecar_path, RunManifest};
+use crate::monte_carlo::MonteCarloResult;
+use crate::results::{load_monte_carlo, save_monte_carlo};
+
+pub const DEFAULT_REGISTRY_DIR: &str = "experiments";
+const INDEX_FILE: &str = "registry.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Experiment {
+    pub name: String,
+    pub description: String,
+    pub tags: Vec<String>,
+    pub created_at: u64, // Unix seconds
+    pub results_file: String,
+    #[serde(default)]
+    pub manifest_file: Option<String>,
+}
+
+impl Experiment {
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+}
+
+pub struct ExperimentRegistry {
+    root: PathBuf,
+    experiments: Vec<Experiment>,
+}
+
+fn validate_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+        && !name.starts_with('.');
+    if !valid {
+        return Err(Error::Invalid(format!(
+            "experiment name '{}' must be non-empty and use only [A-Za-z0-9._-]",
+            name
+        )));
+    }
+    // Results files share the root with the index and manifest sidecars
+    if Some(name) == INDEX_FILE.strip_suffix(".json") || name.ends_with(".manifest") {
+        return Err(Error::Invalid(format!(
+            "experiment name '{}' would collide with the registry index or a manifest",
+            name
+        )));
+    }
+    Ok(())
+}
+
+impl ExperimentRegistry {
+    /// Opens the registry at `root`, creating the directory if needed.
+    pub fn open(root: impl AsRef<Path>) -> Result<Self> {
+        let root = root.as_ref().to_path_buf();
+        std::fs::create_dir_all(&root)?;
+
+        let index = root.join(INDEX_FILE);
+        let experiments = if index.exists() {
+            serde_json::from_str(&std::fs::read_to_string(&index)?)?
+        } else {
+            Vec::new()
+        };
+
+        Ok(Self { root, experiments })
+    }
+
+    pub fn list(&self) -> &[Experiment] {
+        &self.experiments
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Experiment> {
+        self.experiments.iter().find(|e| e.name == name)
+    }
+
+    pub fn find_by_tag(&self, tag: &str) -> Vec<&Experiment> {
+        self.experiments.iter().filter(|e| e.has_tag(tag)).collect()
+    }
+
+    pub fn register(
+        &mut self,
+        name: &str,
+        description: &str,
+        tags: &[String],
+        results: &[MonteCarloResult],
+        manifest: Option<&RunManifest>,
+    ) -> Result<&Experiment> {
+        validate_name(name)?;
+        if self.get(name).is_some() {
+            return Err(Error::Invalid(format!("experiment '{}' already exists", name)));
+        }
+
+        let results_file = format!("{}.json", name);
+        save_monte_carlo(self.root.join(&results_file), results)?;
+        let manifest_file = match manifest {
+            Some(manifest) => {
+                let path = sidecar_path(&results_file);
+                manifest.save(self.root.join(&path))?;
+                Some(path.to_string_lossy().into_owned())
+            }
+            None => None,
+        };
+
+        let created_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        self.experiments.push(Experiment {
+            name: name.to_string(),
+            description: description.to_string(),
+            tags: tags.to_vec(),
+            created_at,
+            results_file,
+            manifest_file,
+        });
+        self.save_index()?;
+
+        Ok(self.experiments.last().unwrap())
+    }
+
+    pub fn load_results(&self, name: &str) -> Result<Vec<MonteCarloResult>> {
+        let experiment = self
+            .get(name)
+            .ok_or_else(|| Error::Invalid(format!("unknown experiment '{}'", name)))?;
+        load_monte_carlo(self.root.join(&experiment.results_file))
+    }
+
+    /// The manifest `name` was registered with, if any.
+    pub fn load_manifest(&self, name: &str) -> Result<Option<RunManifest>> {
+        let experiment = self
+            .get(name)
+            .ok_or_else(|| Error::Invalid(format!("unknown experiment '{}'", name)))?;
+        experiment.manifest_file.as_ref().map(|file| RunManifest::load(self.root.join(file))).transpose()
+    }
+
+    fn save_index(&self) -> Result<()> {
+        let json = serde_json::to_string_pretty(&self.experiments)?;
+        std::fs::write(self.root.join(INDEX_FILE), json)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cascade::LiquidationMechanism;
+    use crate::config::SimulationSetup;
+    use crate::monte_carlo::{run_monte_carlo, PriceModel};
+
+    fn temp_registry(label: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "fair-sim-registry-{}-{}",
+            label,
+            std::process::id()
+        ));
+        std::fs::remove_dir_all(&dir).ok();
+        dir
+    }
+
+    #[test]
+    fn test_register_and_reload() {
+        let dir = temp_registry("reload");
+        let result = run_monte_carlo(PriceModel::GBM, LiquidationMechanism::keeper_pool(), 10);
+        let manifest = RunManifest::capture(&SimulationSetup::default(), vec!["fair-sim".to_string()]);
+
+        {
+            let mut registry = ExperimentRegistry::open(&dir).unwrap();
+            registry
+                .register(
+                    "baseline-13pct",
+                    "Baseline penalty",
+                    &["baseline".to_string(), "penalty".to_string()],
+                    std::slice::from_ref(&result),
+                    Some(&manifest),
+                )
+                .unwrap();
+        }
+
+        let registry = ExperimentRegistry::open(&dir).unwrap();
+        assert_eq!(registry.list().len(), 1);
+        assert_eq!(registry.find_by_tag("penalty").len(), 1);
+        assert!(registry.find_by_tag("split").is_empty());
+        assert_eq!(registry.load_results("baseline-13pct").unwrap()[0].runs, 10);
+        assert_eq!(registry.load_manifest("baseline-13pct").unwrap().unwrap().command, manifest.command);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_rejects_duplicate_and_invalid_names() {
+        let dir = temp_registry("dupes");
+        let mut registry = ExperimentRegistry::open(&dir).unwrap();
+
+        registry.register("a", "", &[], &[], None).unwrap();
+        assert!(registry.register("a", "", &[], &[], None).is_err());
+        assert!(registry.register("../escape", "", &[], &[], None).is_err());
+        assert!(registry.register("", "", &[], &[], None).is_err());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_rejects_names_colliding_with_registry_files() {
+        let dir = temp_registry("reserved");
+        let mut registry = ExperimentRegistry::open(&dir).unwrap();
+        let manifest = RunManifest::capture(&SimulationSetup::default(), vec!["fair-sim".to_string()]);
+
+        registry.register("foo", "", &[], &[], Some(&manifest)).unwrap();
+        assert!(registry.register("registry", "", &[], &[], None).is_err());
+        assert!(registry.register("foo.manifest", "", &[], &[], None).is_err());
+        // The index and foo's manifest are left as they were
+        let registry = ExperimentRegistry::open(&dir).unwrap();
+        assert_eq!(registry.list().len(), 1);
+        assert_eq!(registry.load_manifest("foo").unwrap().unwrap().command, manifest.command);
+        assert!(registry.load_results("foo").unwrap().is_empty());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
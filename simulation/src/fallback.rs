@@ -0,0 +1,240 @@
+//! Execution Failure Fallbacks
+//!
+//! What it costs the keeper pool when its selected executor fails to land
+//! (out of gas, nonce clashes, stale state), under each fallback policy
+//! (see `cascade::ExecutionFailure`). A backup executor in the same block
+//! loses nothing but gas; re-selecting after a delay leaves the CDP in the
+//! queue while the price keeps moving.
+//!
+//! ## Method
+//! - Every policy runs at the same failure rate on the same seeds when
+//!   `config.seed` is set; failures draw from their own RNG stream, so the
+//!   price paths and populations match across policies
+//! - The baseline is the same setup with the failure rate at zero; coverage
+//!   cost is the liquidations a policy loses against it
+
+use crate::cascade::{
+    run_cascade_simulation, CascadeResult, ExecutionFailure, FallbackPolicy, LiquidationMechanism, PriceScenario,
+    SimulationConfig,
+};
+use crate::error::{Error, Result};
+
+/// Policies compared by default, from an in-block backup to a long timeout.
+pub const FALLBACK_POLICIES: [FallbackPolicy; 4] = [
+    FallbackPolicy::Backup,
+    FallbackPolicy::Reselect { delay: 1 },
+    FallbackPolicy::Reselect { delay: 3 },
+    FallbackPolicy::Reselect { delay: 10 },
+];
+
+#[derive(Debug, Clone)]
+pub struct FallbackPoint {
+    pub policy: FallbackPolicy,
+    pub avg_liquidations: f64,
+    pub avg_bad_debt: f64,
+    pub avg_unliquidated: f64,
+    pub avg_failures: f64,
+    pub avg_fee_burn: f64,
+}
+
+impl FallbackPoint {
+    fn from_results(policy: FallbackPolicy, results: &[CascadeResult]) -> Self {
+        let n = results.len().max(1) as f64;
+        let mean = |f: fn(&CascadeResult) -> f64| results.iter().map(f).sum::<f64>() / n;
+        Self {
+            policy,
+            avg_liquidations: mean(|r| r.total_liquidations as f64),
+            avg_bad_debt: mean(|r| r.bad_debt),
+            avg_unliquidated: mean(|r| r.unliquidated_underwater as f64),
+            avg_failures: mean(|r| r.execution_failures as f64),
+            avg_fee_burn: mean(|r| r.fee_burn),
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self.policy {
+            FallbackPolicy::Backup => "Backup (same block)".to_string(),
+            FallbackPolicy::Reselect { delay } => format!("Reselect after {} block(s)", delay),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FallbackReport {
+    pub mechanism: LiquidationMechanism,
+    pub scenario: PriceScenario,
+    pub runs: usize,
+    pub failure_rate: f64,
+    pub baseline: FallbackPoint, // Failure rate zero
+    pub points: Vec<FallbackPoint>,
+}
+
+impl FallbackReport {
+    /// Liquidations lost to failures against the baseline.
+    pub fn coverage_cost(&self, point: &FallbackPoint) -> f64 {
+        self.baseline.avg_liquidations - point.avg_liquidations
+    }
+
+    /// Bad debt added by failures over the baseline.
+    pub fn bad_debt_cost(&self, point: &FallbackPoint) -> f64 {
+        point.avg_bad_debt - self.baseline.avg_bad_debt
+    }
+
+    /// The policy adding the least bad debt.
+    pub fn cheapest(&self) -> Option<&FallbackPoint> {
+        self.points.iter().min_by(|a, b| a.avg_bad_debt.total_cmp(&b.avg_bad_debt))
+    }
+
+    pub fn print(&self) {
+        let signed = |usd: f64| if usd < 0.0 { format!("-${:.0}", -usd) } else { format!("+${:.0}", usd) };
+        println!("| Policy                     | Failures | Liquidations | Coverage Cost | Bad Debt | Bad Debt Cost |");
+        println!("|----------------------------|----------|--------------|---------------|----------|---------------|");
+        println!(
+            "| {:26} | {:8} | {:12.1} | {:>13} | ${:7.0} | {:>13} |",
+            "No failures",
+            "-",
+            self.baseline.avg_liquidations,
+            "-",
+            self.baseline.avg_bad_debt,
+            "-",
+        );
+        for point in &self.points {
+            println!(
+                "| {:26} | {:8.1} | {:12.1} | {:13.1} | ${:7.0} | {:>13} |",
+                point.label(),
+                point.avg_failures,
+                point.avg_liquidations,
+                self.coverage_cost(point),
+                point.avg_bad_debt,
+                signed(self.bad_debt_cost(point)),
+            );
+        }
+        if let Some(best) = self.cheapest() {
+            println!(
+                "  Cheapest fallback at {:.0}% failures: {} ({} bad debt)",
+                self.failure_rate * 100.0,
+                best.label(),
+                signed(self.bad_debt_cost(best))
+            );
+        }
+    }
+}
+
+/// Runs the keeper pool with its executor failing at `failure_rate` under
+/// each policy in `policies`, against the same setup without failures.
+pub fn compare_fallback_policies(
+    mechanism: LiquidationMechanism,
+    scenario: PriceScenario,
+    failure_rate: f64,
+    policies: &[FallbackPolicy],
+    runs: usize,
+    config: &SimulationConfig,
+) -> Result<FallbackReport> {
+    mechanism.validate()?;
+    if !mechanism.is_keeper_pool() {
+        return Err(Error::Invalid("execution fallbacks apply to the keeper pool only".to_string()));
+    }
+    config.validate()?;
+
+    let run = |failure_rate: f64, fallback: FallbackPolicy| -> Result<FallbackPoint> {
+        let failing = SimulationConfig {
+            execution_failure: Some(ExecutionFailure { failure_rate, fallback }),
+            ..config.clone()
+        };
+        failing.validate()?;
+        Ok(FallbackPoint::from_results(fallback, &run_cascade_simulation(mechanism, scenario, runs, &failing)))
+    };
+    let baseline = run(0.0, FallbackPolicy::Backup)?;
+    let points = policies.iter().map(|&policy| run(failure_rate, policy)).collect::<Result<Vec<_>>>()?;
+
+    Ok(FallbackReport { mechanism, scenario, runs, failure_rate, baseline, points })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cascade::{CascadeSimulationBuilder, CDP};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn single_cdp(fallback: FallbackPolicy) -> CascadeResult {
+        // Every executor fails; the CDP stays liquidatable for four blocks
+        let config = SimulationConfig {
+            execution_failure: Some(ExecutionFailure { failure_rate: 1.0, fallback }),
+            max_blocks: 4,
+            ..Default::default()
+        };
+        let mut rng = StdRng::seed_from_u64(0);
+        CascadeSimulationBuilder::new()
+            .config(config)
+            .cdps(vec![CDP::from_position(0, 5.0, 8_000.0)])
+            .price_path(vec![2000.0; 5])
+            .build(&mut rng)
+            .unwrap()
+            .run(&mut rng)
+    }
+
+    #[test]
+    fn test_policies_bound_failed_attempts() {
+        // A backup tries every willing keeper in the first block, then the
+        // CDP is retried each block; a 3-block delay allows two attempts
+        let backup = single_cdp(FallbackPolicy::Backup);
+        let next_block = single_cdp(FallbackPolicy::Reselect { delay: 1 });
+        let delayed = single_cdp(FallbackPolicy::Reselect { delay: 3 });
+
+        assert_eq!(backup.total_liquidations, 0);
+        assert!(backup.execution_failures >= 50);
+        assert_eq!(next_block.execution_failures, 4);
+        assert_eq!(delayed.execution_failures, 2);
+    }
+
+    #[test]
+    fn test_policies_share_paths_without_failures() {
+        let never = |fallback: FallbackPolicy| SimulationConfig {
+            seed: Some(5),
+            execution_failure: Some(ExecutionFailure { failure_rate: 0.0, fallback }),
+            ..Default::default()
+        };
+        let mechanism = LiquidationMechanism::keeper_pool();
+        let a = run_cascade_simulation(mechanism, PriceScenario::VolatileCrash, 4, &never(FallbackPolicy::Backup));
+        let b = run_cascade_simulation(
+            mechanism,
+            PriceScenario::VolatileCrash,
+            4,
+            &never(FallbackPolicy::Reselect { delay: 10 }),
+        );
+
+        for (a, b) in a.iter().zip(&b) {
+            assert_eq!(a.total_liquidations, b.total_liquidations);
+            assert_eq!(a.bad_debt, b.bad_debt);
+            assert_eq!(b.execution_failures, 0);
+        }
+    }
+
+    #[test]
+    fn test_long_delays_cost_more_coverage() {
+        let config = SimulationConfig { seed: Some(1), ..Default::default() };
+        let report = compare_fallback_policies(
+            LiquidationMechanism::keeper_pool(),
+            PriceScenario::FlashCrash,
+            0.3,
+            &FALLBACK_POLICIES,
+            6,
+            &config,
+        )
+        .unwrap();
+        let (backup, slowest) = (&report.points[0], &report.points[3]);
+
+        assert!(backup.avg_failures > 0.0);
+        assert!(report.coverage_cost(slowest) > report.coverage_cost(backup));
+        assert!(compare_fallback_policies(
+            LiquidationMechanism::Traditional,
+            PriceScenario::FlashCrash,
+            0.3,
+            &FALLBACK_POLICIES,
+            1,
+            &config
+        )
+        .is_err());
+    }
+}
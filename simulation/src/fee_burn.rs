@@ -0,0 +1,205 @@
+//! Priority Fee Burn
+//!
+//! Whether the keeper pool still compresses fee burn once its members race
+//! for the execution slot. Traditional keepers bid priority fees against
+//! the whole penalty; under an executor premium (see
+//! `cascade::ExecutionRace`) pool members bid against that premium alone,
+//! so the 70/30 split should leave far less to burn as long as the premium
+//! stays a slice of the keeper share.
+//!
+//! ## Method
+//! - Runs Traditional once and the keeper pool at each executor share;
+//!   every run sees the same seeds when `config.seed` is set, and the race
+//!   draws nothing extra from the RNG
+//! - Burn is compared per liquidation, since the mechanisms clear
+//!   different numbers of CDPs
+
+use crate::cascade::{
+    run_cascade_simulation, CascadeResult, ExecutionRace, LiquidationMechanism, PriceScenario, SimulationConfig,
+};
+use crate::error::Result;
+
+/// Executor shares of the keeper share swept by default, from no race to
+/// the executor taking the whole keeper share.
+pub const EXECUTOR_SHARE_SWEEP: [f64; 5] = [0.0, 0.1, 0.25, 0.5, 1.0];
+
+#[derive(Debug, Clone)]
+pub struct FeeBurnPoint {
+    pub executor_share: Option<f64>, // None = Traditional
+    pub avg_liquidations: f64,
+    pub avg_fee_burn: f64,
+    pub avg_bad_debt: f64,
+    pub avg_profit_concentration: f64,
+}
+
+impl FeeBurnPoint {
+    fn from_results(executor_share: Option<f64>, results: &[CascadeResult]) -> Self {
+        let n = results.len().max(1) as f64;
+        let mean = |f: fn(&CascadeResult) -> f64| results.iter().map(f).sum::<f64>() / n;
+        Self {
+            executor_share,
+            avg_liquidations: mean(|r| r.total_liquidations as f64),
+            avg_fee_burn: mean(|r| r.fee_burn),
+            avg_bad_debt: mean(|r| r.bad_debt),
+            avg_profit_concentration: mean(|r| r.profit_concentration),
+        }
+    }
+
+    /// Fee burn per CDP cleared; zero when nothing was liquidated.
+    pub fn burn_per_liquidation(&self) -> f64 {
+        if self.avg_liquidations > 0.0 {
+            self.avg_fee_burn / self.avg_liquidations
+        } else {
+            0.0
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FeeBurnReport {
+    pub split: f64,
+    pub scenario: PriceScenario,
+    pub runs: usize,
+    pub traditional: FeeBurnPoint,
+    pub pool: Vec<FeeBurnPoint>, // In sweep order
+}
+
+impl FeeBurnReport {
+    /// Pool burn per liquidation as a fraction of Traditional's.
+    pub fn burn_ratio(&self, point: &FeeBurnPoint) -> Option<f64> {
+        let traditional = self.traditional.burn_per_liquidation();
+        (traditional > 0.0).then(|| point.burn_per_liquidation() / traditional)
+    }
+
+    /// Whether every swept executor share burns less per liquidation than
+    /// Traditional races; None when Traditional cleared nothing to compare.
+    pub fn compresses(&self) -> Option<bool> {
+        let traditional = self.traditional.burn_per_liquidation();
+        (traditional > 0.0).then(|| self.pool.iter().all(|p| p.burn_per_liquidation() < traditional))
+    }
+
+    pub fn print(&self) {
+        println!("| Executor Share | Liquidations | Fee Burn | Burn / Liq. | vs Traditional | Bad Debt | Concentration |");
+        println!("|----------------|--------------|----------|-------------|----------------|----------|---------------|");
+        for point in std::iter::once(&self.traditional).chain(&self.pool) {
+            let label = match point.executor_share {
+                Some(share) => format!("{:.0}%", share * 100.0),
+                None => "Traditional".to_string(),
+            };
+            let ratio = match self.burn_ratio(point) {
+                Some(ratio) => format!("{:.1}%", ratio * 100.0),
+                None => "-".to_string(),
+            };
+            println!(
+                "| {:>14} | {:12.1} | ${:7.0} | ${:10.2} | {:>14} | ${:7.0} | {:12.1}% |",
+                label,
+                point.avg_liquidations,
+                point.avg_fee_burn,
+                point.burn_per_liquidation(),
+                ratio,
+                point.avg_bad_debt,
+                point.avg_profit_concentration * 100.0,
+            );
+        }
+        match self.compresses() {
+            Some(true) => println!(
+                "  The {:.0}/{:.0} pool burns less per liquidation than Traditional at every executor share",
+                self.split * 100.0,
+                (1.0 - self.split) * 100.0
+            ),
+            Some(false) => println!("  Intra-pool races erase the fee compression at high executor shares"),
+            None => println!("  Traditional cleared no CDPs; nothing to compare"),
+        }
+    }
+}
+
+/// Runs Traditional and the keeper pool at `split` with each executor share
+/// in `shares`.
+pub fn compare_fee_burn(
+    split: f64,
+    scenario: PriceScenario,
+    shares: &[f64],
+    runs: usize,
+    config: &SimulationConfig,
+) -> Result<FeeBurnReport> {
+    let mechanism = LiquidationMechanism::KeeperPool { split };
+    mechanism.validate()?;
+    config.validate()?;
+
+    let traditional = FeeBurnPoint::from_results(
+        None,
+        &run_cascade_simulation(LiquidationMechanism::Traditional, scenario, runs, config),
+    );
+    let mut pool = Vec::with_capacity(shares.len());
+    for &executor_share in shares {
+        let racing = SimulationConfig { execution_race: Some(ExecutionRace { executor_share }), ..config.clone() };
+        racing.validate()?;
+        pool.push(FeeBurnPoint::from_results(
+            Some(executor_share),
+            &run_cascade_simulation(mechanism, scenario, runs, &racing),
+        ));
+    }
+
+    Ok(FeeBurnReport { split, scenario, runs, traditional, pool })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cascade::{CascadeSimulationBuilder, Keeper, CDP};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_zero_premium_matches_random_selection() {
+        let plain = SimulationConfig { seed: Some(6), ..Default::default() };
+        let racing = SimulationConfig { execution_race: Some(ExecutionRace { executor_share: 0.0 }), ..plain.clone() };
+        let mechanism = LiquidationMechanism::keeper_pool();
+        let a = run_cascade_simulation(mechanism, PriceScenario::FlashCrash, 4, &plain);
+        let b = run_cascade_simulation(mechanism, PriceScenario::FlashCrash, 4, &racing);
+
+        for (a, b) in a.iter().zip(&b) {
+            assert_eq!(a.total_liquidations, b.total_liquidations);
+            assert_eq!(a.bad_debt, b.bad_debt);
+            assert_eq!(a.fee_burn, b.fee_burn);
+        }
+    }
+
+    #[test]
+    fn test_race_burn_scales_with_prize() {
+        // One $260 penalty, two keepers at priority 0.5 and 1.0
+        let run = |mechanism: LiquidationMechanism, execution_race: Option<ExecutionRace>| {
+            let config = SimulationConfig { execution_race, max_blocks: 2, ..Default::default() };
+            let mut rng = StdRng::seed_from_u64(0);
+            CascadeSimulationBuilder::new()
+                .config(config)
+                .mechanism(mechanism)
+                .cdps(vec![CDP::from_position(0, 5.0, 8_000.0)])
+                .keepers(vec![Keeper::new(0, 50_000.0, 0.5), Keeper::new(1, 50_000.0, 1.0)])
+                .price_path(vec![2000.0, 2000.0])
+                .build(&mut rng)
+                .unwrap()
+                .run(&mut rng)
+        };
+        let traditional = run(LiquidationMechanism::Traditional, None);
+        let random = run(LiquidationMechanism::keeper_pool(), None);
+        let race = run(LiquidationMechanism::keeper_pool(), Some(ExecutionRace { executor_share: 0.1 }));
+
+        assert!((traditional.fee_burn - (2.0 * 20.0 + 1.5 * 260.0)).abs() < 1e-6);
+        assert!((random.fee_burn - (2.0 * 5.0 + 20.0)).abs() < 1e-6);
+        assert!((race.fee_burn - (2.0 * 5.0 + 2.0 * 20.0 + 1.5 * 0.1 * 182.0)).abs() < 1e-6);
+        assert!(race.profit_concentration > random.profit_concentration);
+    }
+
+    #[test]
+    fn test_default_split_compresses_burn() {
+        let config = SimulationConfig { seed: Some(2), ..Default::default() };
+        let report =
+            compare_fee_burn(0.7, PriceScenario::FlashCrash, &EXECUTOR_SHARE_SWEEP, 8, &config).unwrap();
+
+        assert_eq!(report.pool.len(), EXECUTOR_SHARE_SWEEP.len());
+        assert_eq!(report.compresses(), Some(true));
+        assert!(report.pool[4].avg_fee_burn > report.pool[0].avg_fee_burn);
+        assert!(compare_fee_burn(1.5, PriceScenario::FlashCrash, &[0.1], 1, &config).is_err());
+    }
+}
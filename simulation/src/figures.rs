@@ -0,0 +1,219 @@
+//! Whitepaper Figures
+//!
+//! Registry of every chart and table the whitepaper cites, each pinned to a
+//! checked-in config under `simulation/figures/` with a fixed seed. The
+//! `figures` binary regenerates all of them in one command into a directory
+//! named after the crate version and commit, so a figure in the paper can
+//! be traced to the code that produced it.
+//!
+//! ```bash
+//! cargo run --release --bin figures
+//! # With the bad-debt histograms (`plots` feature)
+//! cargo run --release --features plots --bin figures -- --only monte-carlo-tail
+//! ```
+//!
+//! Each table also renders as LaTeX (`booktabs`) for the paper itself; see
+//! `report::table`.
+//!
+//! ## Adding a figure
+//! - Add `figures/<name>.toml` with a `[simulation] seed`
+//! - Register it in `FIGURES` with the kind of table it renders
+
+use std::path::{Path, PathBuf};
+
+use crate::batch::{Batch, Job};
+use crate::cascade::{aggregate_results, sweep_pool_split, SPLIT_SWEEP};
+use crate::config::SimulationSetup;
+use crate::error::{Error, Result};
+use crate::poa::{run_poa_simulation_with_calibration, ObfuscationStrategy};
+use crate::report::table::{cascade_summary, monte_carlo_summary, poa_summary, Table};
+use crate::results::CascadeCell;
+
+/// Checked-in figure configs, relative to the crate root.
+pub const CONFIG_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/figures");
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FigureKind {
+    PriceOfAnarchy,    // Obfuscation strategies in the single-shot game
+    CascadeComparison, // Mechanism per scenario
+    SplitSweep,        // Keeper pool splits per scenario
+    MonteCarloTail,    // Tail metrics per price model and mechanism
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Figure {
+    pub name: &'static str, // Config file stem and output file stem
+    pub caption: &'static str,
+    pub kind: FigureKind,
+}
+
+pub const FIGURES: [Figure; 4] = [
+    Figure {
+        name: "price-of-anarchy",
+        caption: "Price of Anarchy, front-runner share and profit concentration per obfuscation strategy",
+        kind: FigureKind::PriceOfAnarchy,
+    },
+    Figure {
+        name: "cascade-comparison",
+        caption: "Deleveraging cascades under winner-takes-all and the keeper pool",
+        kind: FigureKind::CascadeComparison,
+    },
+    Figure {
+        name: "split-sweep",
+        caption: "Bad debt and keeper participation across keeper pool splits",
+        kind: FigureKind::SplitSweep,
+    },
+    Figure {
+        name: "monte-carlo-tail",
+        caption: "Tail risk of bad debt per price model",
+        kind: FigureKind::MonteCarloTail,
+    },
+];
+
+impl Figure {
+    pub fn find(name: &str) -> Option<Figure> {
+        FIGURES.iter().find(|f| f.name == name).copied()
+    }
+
+    pub fn config_path(&self, dir: impl AsRef<Path>) -> PathBuf {
+        dir.as_ref().join(format!("{}.toml", self.name))
+    }
+
+    /// Loads the figure's config; figures must be seeded to be reproducible.
+    pub fn setup(&self, dir: impl AsRef<Path>) -> Result<SimulationSetup> {
+        let setup = SimulationSetup::load(self.config_path(dir))?;
+        if setup.simulation.seed.is_none() {
+            return Err(Error::Invalid(format!("figure '{}' has no [simulation] seed", self.name)));
+        }
+        Ok(setup)
+    }
+}
+
+/// Output directory for this build under `root`: `v<version>-<commit>`,
+/// with `-dirty` for uncommitted changes and `unknown` outside git.
+pub fn versioned_dir(root: impl AsRef<Path>) -> PathBuf {
+    let commit = option_env!("FAIR_SIM_GIT_HASH").map_or("unknown", |hash| &hash[..hash.len().min(7)]);
+    let dirty = if option_env!("FAIR_SIM_GIT_DIRTY") == Some("true") { "-dirty" } else { "" };
+    root.as_ref().join(format!("v{}-{}{}", env!("CARGO_PKG_VERSION"), commit, dirty))
+}
+
+/// Runs the figure's campaign and renders it as a Markdown table, writing
+/// any charts into `dir`. Returns the Markdown and the chart files.
+pub fn render(figure: &Figure, setup: &SimulationSetup, dir: &Path) -> Result<(String, Vec<PathBuf>)> {
+    let (table, charts) = render_table(figure, setup, dir)?;
+    Ok((markdown(figure, &table), charts))
+}
+
+/// The figure's Markdown section: its caption as a heading over the table.
+pub fn markdown(figure: &Figure, table: &Table) -> String {
+    format!("## {}\n\n{}", figure.caption, table.markdown())
+}
+
+/// Runs the figure's campaign into a table captioned with the figure's
+/// caption (see `report::table` for Markdown and LaTeX), writing any charts
+/// into `dir`.
+#[cfg_attr(not(feature = "plots"), allow(unused_variables, unused_mut))] // Only `plots` draws charts
+pub fn render_table(figure: &Figure, setup: &SimulationSetup, dir: &Path) -> Result<(Table, Vec<PathBuf>)> {
+    let config = &setup.simulation;
+    let mut charts = Vec::new();
+
+    let table = match figure.kind {
+        FigureKind::PriceOfAnarchy => {
+            let runs = setup.runs_or(10_000);
+            let campaigns: Vec<_> = ObfuscationStrategy::all()
+                .into_iter()
+                .map(|strategy| run_poa_simulation_with_calibration(strategy, runs, config.seed, &setup.perception))
+                .collect();
+            poa_summary(&campaigns)
+        }
+        FigureKind::CascadeComparison => {
+            let runs = setup.runs_or(1000);
+            let batch = Batch::new(Job::matrix(&setup.scenarios(), &setup.mechanisms(), runs)).run(config, None)?;
+            let cells: Vec<_> = batch
+                .iter()
+                .map(|(job, results)| CascadeCell {
+                    label: job.scenario.name().to_string(),
+                    aggregate: aggregate_results(results),
+                    results: results.to_vec(),
+                })
+                .collect();
+            cascade_summary(&cells)
+        }
+        FigureKind::SplitSweep => {
+            let runs = setup.runs_or(1000);
+            let mut table = Table::new(&["Scenario", "Split", "Bad Debt", "Participation", "Protocol Revenue"]);
+            for scenario in setup.scenarios() {
+                for agg in sweep_pool_split(scenario, &SPLIT_SWEEP, runs, config)? {
+                    table.push(vec![
+                        scenario.name().to_string(),
+                        format!("{:.0}/{:.0}", agg.mechanism.keeper_share() * 100.0, (1.0 - agg.mechanism.keeper_share()) * 100.0),
+                        format!("${:.0}", agg.avg_bad_debt),
+                        format!("{:.1}%", agg.avg_participation_rate * 100.0),
+                        format!("${:.0}", agg.avg_protocol_revenue),
+                    ]);
+                }
+            }
+            table
+        }
+        FigureKind::MonteCarloTail => {
+            let runs = setup.runs_or(2000);
+            let (models, mechanisms) = (setup.models(), setup.mechanisms());
+            let all_results = Batch::new(Job::model_matrix(&models, &mechanisms, runs)).run(config, None)?.monte_carlo_results();
+            #[cfg(feature = "plots")]
+            for (model, results) in models.iter().zip(all_results.chunks(mechanisms.len())) {
+                let path = dir.join(format!("{}-{:?}.svg", figure.name, model).to_lowercase());
+                let labels: Vec<String> = results.iter().map(|r| r.mechanism.name()).collect();
+                let series: Vec<_> =
+                    labels.iter().zip(results).map(|(l, r)| (l.as_str(), r.bad_debts.as_slice())).collect();
+                crate::plots::bad_debt_histogram(&path, &format!("{}: bad debt", model.name()), &series)?;
+                charts.push(path);
+            }
+            monte_carlo_summary(&all_results)
+        }
+    };
+    Ok((table.with_caption(figure.caption), charts))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_figure_has_a_seeded_config() {
+        for figure in FIGURES {
+            let setup = figure.setup(CONFIG_DIR).unwrap();
+            assert!(setup.simulation.seed.is_some(), "{}", figure.name);
+            assert_eq!(Figure::find(figure.name).unwrap().kind, figure.kind);
+        }
+        assert!(Figure::find("missing").is_none());
+        // The checked-in calibration documents the defaults
+        let poa = Figure::find("price-of-anarchy").unwrap().setup(CONFIG_DIR).unwrap();
+        assert_eq!(poa.perception, crate::poa::PerceptionCalibration::default());
+    }
+
+    #[test]
+    fn test_unseeded_config_is_rejected() {
+        let dir = std::env::temp_dir().join(format!("fair-sim-figures-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("split-sweep.toml"), "runs = 5\n").unwrap();
+        let err = Figure::find("split-sweep").unwrap().setup(&dir).unwrap_err();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(err.to_string().contains("no [simulation] seed"));
+    }
+
+    #[test]
+    fn test_render_is_reproducible() {
+        let figure = Figure::find("cascade-comparison").unwrap();
+        let mut setup = figure.setup(CONFIG_DIR).unwrap();
+        setup.runs = Some(3);
+        let dir = std::env::temp_dir();
+        let (a, _) = render(&figure, &setup, &dir).unwrap();
+        let (b, _) = render(&figure, &setup, &dir).unwrap();
+
+        assert_eq!(a, b);
+        assert!(a.starts_with("## Deleveraging cascades"));
+        assert_eq!(a.lines().filter(|l| l.starts_with("| ")).count(), 1 + setup.scenarios().len() * setup.mechanisms().len());
+        assert!(versioned_dir("out").starts_with("out"));
+    }
+}
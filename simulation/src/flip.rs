@@ -0,0 +1,150 @@
+//! English Auction Apathy
+//!
+//! Maker's flip auctions (see `cascade::FlipAuction`) under keepers that
+//! ignore auctions. Attentive keepers bid each other up to the lot's value
+//! and the tab is covered; as apathy grows, fewer auctions draw a second
+//! bidder before the opening bid expires, and lone bidders win lots for
+//! nothing, as on Black Thursday. The keeper pool on the same scenario is
+//! the reference.
+//!
+//! ## Method
+//! - Runs the English auction at each apathy level with the same bid and
+//!   auction lengths; every run sees the same seeds when `config.seed` is
+//!   set, and apathy is drawn from its own stream so the price paths match
+//! - Recovery is the share of the settled auctions' tabs their winning
+//!   bids raised, pooled over runs; the zero-bid share is the share of
+//!   settled auctions won by a lone bid of zero
+//! - Auctions still open at the end leave their CDPs on the book, so
+//!   their debt shows up in bad debt only if the CDP is underwater
+
+use crate::cascade::{run_cascade_simulation, CascadeResult, FlipAuction, LiquidationMechanism, PriceScenario, SimulationConfig};
+use crate::error::{Error, Result};
+
+/// Apathy levels compared by default. Zero bids need a lone bidder for a
+/// whole `bid_blocks`, so they only appear once most keepers look away.
+pub const FLIP_APATHY: [f64; 4] = [0.0, 0.9, 0.98, 0.995];
+
+#[derive(Debug, Clone)]
+pub struct FlipPoint {
+    pub apathy: f64,
+    pub avg_bad_debt: f64,
+    pub bad_debt_frequency: f64,
+    pub recovery: f64,
+    pub zero_bid_share: f64,
+    pub avg_kicked: f64,
+    pub avg_restarts: f64,
+    pub avg_open: f64,
+}
+
+impl FlipPoint {
+    fn from_results(apathy: f64, results: &[CascadeResult]) -> Self {
+        let n = results.len().max(1) as f64;
+        let flips: Vec<_> = results.iter().filter_map(|r| r.flips).collect();
+        let (raised, tab) = flips.iter().fold((0.0, 0.0), |(raised, tab), f| (raised + f.raised, tab + f.tab));
+        let settled: usize = flips.iter().map(|f| f.settled).sum();
+        Self {
+            apathy,
+            avg_bad_debt: results.iter().map(|r| r.bad_debt).sum::<f64>() / n,
+            bad_debt_frequency: results.iter().filter(|r| r.bad_debt > 0.0).count() as f64 / n,
+            recovery: if tab > 0.0 { raised / tab } else { 1.0 },
+            zero_bid_share: flips.iter().map(|f| f.zero_bids).sum::<usize>() as f64 / settled.max(1) as f64,
+            avg_kicked: flips.iter().map(|f| f.kicked).sum::<usize>() as f64 / n,
+            avg_restarts: flips.iter().map(|f| f.restarts).sum::<usize>() as f64 / n,
+            avg_open: flips.iter().map(|f| f.open).sum::<usize>() as f64 / n,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FlipReport {
+    pub scenario: PriceScenario,
+    pub runs: usize,
+    pub auction: FlipAuction, // Apathy varies per point
+    pub points: Vec<FlipPoint>,
+    pub keeper_pool_bad_debt: f64,
+}
+
+impl FlipReport {
+    pub fn print(&self) {
+        println!("| Apathy | Bad Debt   | P(Bad Debt) | Recovery | Zero Bids | Kicked | Restarts | Open at End |");
+        println!("|--------|------------|-------------|----------|-----------|--------|----------|-------------|");
+        for point in &self.points {
+            println!(
+                "| {:5.1}% | ${:9.0} | {:10.1}% | {:7.1}% | {:8.1}% | {:6.1} | {:8.2} | {:11.2} |",
+                point.apathy * 100.0,
+                point.avg_bad_debt,
+                point.bad_debt_frequency * 100.0,
+                point.recovery * 100.0,
+                point.zero_bid_share * 100.0,
+                point.avg_kicked,
+                point.avg_restarts,
+                point.avg_open,
+            );
+        }
+        println!("  Keeper pool on the same scenario: ${:.0} bad debt", self.keeper_pool_bad_debt);
+        if let Some(first) = self.points.iter().find(|point| point.zero_bid_share > 0.0) {
+            println!(
+                "  Zero-bid auctions appear at {:.1}% apathy ({:.1}% of settled auctions)",
+                first.apathy * 100.0,
+                first.zero_bid_share * 100.0,
+            );
+        } else {
+            println!("  No auction settled on a zero bid");
+        }
+    }
+}
+
+/// Runs the English auction `auction` at each apathy level in `levels`,
+/// and the default keeper pool as the reference.
+pub fn compare_flip_apathy(
+    auction: FlipAuction,
+    scenario: PriceScenario,
+    levels: &[f64],
+    runs: usize,
+    config: &SimulationConfig,
+) -> Result<FlipReport> {
+    if levels.is_empty() {
+        return Err(Error::Invalid("flip sweep needs at least one apathy level".to_string()));
+    }
+    config.validate()?;
+    let points = levels
+        .iter()
+        .map(|&apathy| {
+            let mechanism = LiquidationMechanism::EnglishAuction(FlipAuction { apathy, ..auction });
+            mechanism.validate()?;
+            Ok(FlipPoint::from_results(apathy, &run_cascade_simulation(mechanism, scenario, runs, config)))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let reference = run_cascade_simulation(LiquidationMechanism::keeper_pool(), scenario, runs, config);
+    let keeper_pool_bad_debt = reference.iter().map(|r| r.bad_debt).sum::<f64>() / reference.len().max(1) as f64;
+
+    Ok(FlipReport { scenario, runs, auction, points, keeper_pool_bad_debt })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attentive_keepers_cover_the_tab() {
+        let config = SimulationConfig { seed: Some(3), ..Default::default() };
+        let report = compare_flip_apathy(FlipAuction::default(), PriceScenario::FlashCrash, &[0.0], 10, &config).unwrap();
+
+        let point = &report.points[0];
+        assert!(point.avg_kicked > 0.0);
+        assert_eq!(point.zero_bid_share, 0.0);
+        assert!(point.recovery > 0.95, "recovery {}", point.recovery);
+    }
+
+    #[test]
+    fn test_apathetic_keepers_leave_zero_bids() {
+        let config = SimulationConfig { seed: Some(3), ..Default::default() };
+        let report = compare_flip_apathy(FlipAuction::default(), PriceScenario::FlashCrash, &[0.0, 0.995], 10, &config).unwrap();
+
+        let (attentive, apathetic) = (&report.points[0], &report.points[1]);
+        assert!(apathetic.zero_bid_share > 0.0);
+        assert!(apathetic.recovery < attentive.recovery);
+        assert!(apathetic.avg_restarts >= attentive.avg_restarts);
+        assert!(compare_flip_apathy(FlipAuction::default(), PriceScenario::FlashCrash, &[1.0], 1, &config).is_err());
+    }
+}
@@ -0,0 +1,183 @@
+//! Grace Periods
+//!
+//! A health-factor buffer (see `cascade::GracePeriod`) evaluated against
+//! liquidating CDPs as soon as they fall below the minimum. Owners who top
+//! up within the window keep their CDP and the penalty it would have paid;
+//! the price is paid in bad debt when the owner does not, and the CDP keeps
+//! falling through its window with keepers locked out.
+//!
+//! ## Method
+//! - Runs the mechanism without grace, then at each window length with the
+//!   same top-up rate and target; every run sees the same seeds when
+//!   `config.seed` is set, and top-ups draw from their own stream
+//! - Borrower loss is collateral value seized beyond the debt repaid;
+//!   collateral owners top up is theirs, so it is not a loss
+//! - The tradeoff prices each window in extra bad debt per dollar of
+//!   borrower loss it saves
+
+use crate::cascade::{run_cascade_simulation, CascadeResult, GracePeriod, LiquidationMechanism, PriceScenario, SimulationConfig};
+use crate::error::{Error, Result};
+
+/// Window lengths compared by default, in blocks.
+pub const GRACE_WINDOWS: [usize; 3] = [1, 5, 20];
+
+#[derive(Debug, Clone)]
+pub struct GracePoint {
+    pub grace_blocks: Option<usize>, // None = liquidatable on falling below the minimum
+    pub avg_bad_debt: f64,
+    pub bad_debt_frequency: f64,
+    pub avg_borrower_loss: f64,
+    pub avg_liquidations: f64,
+    pub avg_rescued: f64,   // CDPs topped up in their window
+    pub avg_topped_up: f64, // USD of collateral owners added
+}
+
+impl GracePoint {
+    fn from_results(grace_blocks: Option<usize>, results: &[CascadeResult]) -> Self {
+        let n = results.len().max(1) as f64;
+        let mean = |f: fn(&CascadeResult) -> f64| results.iter().map(f).sum::<f64>() / n;
+        Self {
+            grace_blocks,
+            avg_bad_debt: mean(|r| r.bad_debt),
+            bad_debt_frequency: results.iter().filter(|r| r.bad_debt > 0.0).count() as f64 / n,
+            avg_borrower_loss: mean(|r| r.borrower_loss),
+            avg_liquidations: mean(|r| r.total_liquidations as f64),
+            avg_rescued: mean(|r| r.grace.map_or(0.0, |g| g.rescued as f64)),
+            avg_topped_up: mean(|r| r.grace.map_or(0.0, |g| g.topped_up)),
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self.grace_blocks {
+            None => "No grace".to_string(),
+            Some(blocks) => format!("{} block grace", blocks),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GraceReport {
+    pub mechanism: LiquidationMechanism,
+    pub scenario: PriceScenario,
+    pub runs: usize,
+    pub grace: GracePeriod, // Window length varies per point
+    pub immediate: GracePoint,
+    pub points: Vec<GracePoint>,
+}
+
+impl GraceReport {
+    /// Extra bad debt per dollar of borrower loss `point` saves over
+    /// immediate liquidation; None if it saves nothing.
+    pub fn tradeoff(&self, point: &GracePoint) -> Option<f64> {
+        let saved = self.immediate.avg_borrower_loss - point.avg_borrower_loss;
+        (saved > 0.0).then(|| (point.avg_bad_debt - self.immediate.avg_bad_debt) / saved)
+    }
+
+    pub fn print(&self) {
+        println!("| Window          | Bad Debt   | P(Bad Debt) | Borrower Loss | Liquidations | Rescued | Topped Up   | Bad Debt per $ Saved |");
+        println!("|-----------------|------------|-------------|---------------|--------------|---------|-------------|----------------------|");
+        for point in std::iter::once(&self.immediate).chain(&self.points) {
+            let tradeoff = match (point.grace_blocks, self.tradeoff(point)) {
+                (None, _) => "-".to_string(),
+                (Some(_), Some(cost)) => format!("{:+.3}", cost),
+                (Some(_), None) => "no saving".to_string(),
+            };
+            println!(
+                "| {:15} | ${:9.0} | {:10.1}% | ${:12.0} | {:12.1} | {:7.1} | ${:10.0} | {:>20} |",
+                point.label(),
+                point.avg_bad_debt,
+                point.bad_debt_frequency * 100.0,
+                point.avg_borrower_loss,
+                point.avg_liquidations,
+                point.avg_rescued,
+                point.avg_topped_up,
+                tradeoff,
+            );
+        }
+        if let Some(longest) = self.points.iter().max_by_key(|point| point.grace_blocks) {
+            let saved = self.immediate.avg_borrower_loss - longest.avg_borrower_loss;
+            let extra = longest.avg_bad_debt - self.immediate.avg_bad_debt;
+            let label = longest.label();
+            match (saved > 0.0, extra > 0.0) {
+                (true, true) => println!("  {} saves borrowers ${:.0} for ${:.0} of extra bad debt", label, saved, extra),
+                // Owners topping up take CDPs out of the cascade before they sell
+                (true, false) => println!("  {} saves borrowers ${:.0} and cuts bad debt by ${:.0}", label, saved, -extra),
+                (false, true) => println!("  {} saves borrowers nothing and adds ${:.0} of bad debt", label, extra),
+                (false, false) => println!("  {} saves borrowers nothing but cuts bad debt by ${:.0}", label, -extra),
+            }
+        }
+    }
+}
+
+/// Runs `mechanism` without grace and under `grace` at each window length
+/// in `windows`.
+pub fn compare_grace_periods(
+    mechanism: LiquidationMechanism,
+    grace: GracePeriod,
+    scenario: PriceScenario,
+    windows: &[usize],
+    runs: usize,
+    config: &SimulationConfig,
+) -> Result<GraceReport> {
+    if windows.is_empty() {
+        return Err(Error::Invalid("grace period comparison needs at least one window".to_string()));
+    }
+    mechanism.validate()?;
+    let run = |grace_period: Option<GracePeriod>| -> Result<GracePoint> {
+        let config = SimulationConfig { grace_period, ..config.clone() };
+        config.validate()?;
+        let results = run_cascade_simulation(mechanism, scenario, runs, &config);
+        Ok(GracePoint::from_results(grace_period.map(|grace| grace.grace_blocks), &results))
+    };
+    let immediate = run(None)?;
+    let points = windows
+        .iter()
+        .map(|&grace_blocks| run(Some(GracePeriod { grace_blocks, ..grace })))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(GraceReport { mechanism, scenario, runs, grace, immediate, points })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::ConservationReport;
+
+    #[test]
+    fn test_top_ups_spare_borrowers() {
+        let config = SimulationConfig { seed: Some(3), ..Default::default() };
+        let grace = GracePeriod { top_up_rate: 0.5, ..Default::default() };
+        let report =
+            compare_grace_periods(LiquidationMechanism::keeper_pool(), grace, PriceScenario::GradualDecline, &[5], 10, &config)
+                .unwrap();
+
+        let (immediate, point) = (&report.immediate, &report.points[0]);
+        assert!(point.avg_rescued > 0.0);
+        assert!(point.avg_topped_up > 0.0);
+        assert!(point.avg_liquidations < immediate.avg_liquidations);
+        assert!(point.avg_borrower_loss < immediate.avg_borrower_loss);
+    }
+
+    #[test]
+    fn test_absent_owners_only_delay_liquidation() {
+        let config = SimulationConfig {
+            seed: Some(3),
+            grace_period: Some(GracePeriod { top_up_rate: 0.0, ..Default::default() }),
+            ..Default::default()
+        };
+        let results = run_cascade_simulation(LiquidationMechanism::Traditional, PriceScenario::FlashCrash, 5, &config);
+        let plain = SimulationConfig { grace_period: None, ..config.clone() };
+        let immediate = run_cascade_simulation(LiquidationMechanism::Traditional, PriceScenario::FlashCrash, 5, &plain);
+
+        for (held, immediate) in results.iter().zip(&immediate) {
+            let grace = held.grace.unwrap();
+            assert_eq!((grace.rescued, grace.topped_up), (0, 0.0));
+            assert!(grace.expired <= grace.entered);
+            assert!(held.avg_liquidation_delay >= GracePeriod::default().grace_blocks as f64);
+            assert!(held.avg_liquidation_delay > immediate.avg_liquidation_delay);
+            assert!(ConservationReport::of(held).is_consistent());
+        }
+        let invalid = GracePeriod { top_up_ratio: 1.0, ..Default::default() };
+        assert!(compare_grace_periods(LiquidationMechanism::Traditional, invalid, PriceScenario::FlashCrash, &[5], 1, &plain).is_err());
+    }
+}
@@ -0,0 +1,204 @@
+//! Incentive Cliffs
+//!
+//! What happens when a keeper incentive program ends, or a token vesting
+//! cliff hits, in the middle of market stress. The bonus stops and the
+//! mercenary keepers it attracted leave at the cliff block (see
+//! `cascade::IncentiveProgram`); sweeping that block across a price
+//! scenario shows whether the participation drop costs more when it lands
+//! on the crash than before or after it.
+//!
+//! ## Method
+//! - Every cliff sees the same seeds when `config.seed` is set; the program
+//!   draws nothing from the RNG
+//! - A cliff at block 0 is the world without the program; `None` is the
+//!   program running throughout, the baseline the cliff is charged against
+
+use crate::cascade::{
+    run_cascade_simulation, CascadeResult, IncentiveProgram, LiquidationMechanism, PriceScenario, SimulationConfig,
+};
+use crate::error::Result;
+
+/// Cliff blocks swept by default, from no program to the program outlasting the run.
+pub const CLIFF_SWEEP: [Option<usize>; 7] = [Some(0), Some(1), Some(3), Some(5), Some(10), Some(25), None];
+
+#[derive(Debug, Clone)]
+pub struct CliffPoint {
+    pub cliff_block: Option<usize>,
+    pub avg_liquidations: f64,
+    pub avg_bad_debt: f64,
+    pub avg_participation_rate: f64,
+    pub avg_incentives_paid: f64,
+}
+
+impl CliffPoint {
+    fn from_results(cliff_block: Option<usize>, results: &[CascadeResult]) -> Self {
+        let n = results.len().max(1) as f64;
+        let mean = |f: fn(&CascadeResult) -> f64| results.iter().map(f).sum::<f64>() / n;
+        Self {
+            cliff_block,
+            avg_liquidations: mean(|r| r.total_liquidations as f64),
+            avg_bad_debt: mean(|r| r.bad_debt),
+            avg_participation_rate: mean(|r| r.participation_rate),
+            avg_incentives_paid: mean(|r| r.incentives_paid),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CliffReport {
+    pub mechanism: LiquidationMechanism,
+    pub scenario: PriceScenario,
+    pub runs: usize,
+    pub program: IncentiveProgram,
+    pub points: Vec<CliffPoint>, // In sweep order
+}
+
+impl CliffReport {
+    fn uninterrupted(&self) -> Option<&CliffPoint> {
+        self.points.iter().find(|p| p.cliff_block.is_none())
+    }
+
+    /// Bad debt a cliff adds over the program running throughout, when the
+    /// sweep included that baseline.
+    pub fn cliff_cost(&self, point: &CliffPoint) -> Option<f64> {
+        self.uninterrupted().map(|base| point.avg_bad_debt - base.avg_bad_debt)
+    }
+
+    /// The cliff block adding the most bad debt.
+    pub fn worst_cliff(&self) -> Option<&CliffPoint> {
+        self.points
+            .iter()
+            .filter(|p| p.cliff_block.is_some())
+            .max_by(|a, b| a.avg_bad_debt.total_cmp(&b.avg_bad_debt))
+    }
+
+    pub fn print(&self) {
+        println!("| Cliff Block | Liquidations | Bad Debt | Participation | Bonuses Paid | Cliff Cost |");
+        println!("|-------------|--------------|----------|---------------|--------------|------------|");
+        for point in &self.points {
+            let label = match point.cliff_block {
+                Some(0) => "0 (none)".to_string(),
+                Some(block) => block.to_string(),
+                None => "never".to_string(),
+            };
+            let cost = match self.cliff_cost(point) {
+                Some(cost) => format!("${:.0}", cost),
+                None => "-".to_string(),
+            };
+            println!(
+                "| {:11} | {:12.1} | ${:7.0} | {:12.1}% | ${:11.0} | {:>10} |",
+                label,
+                point.avg_liquidations,
+                point.avg_bad_debt,
+                point.avg_participation_rate * 100.0,
+                point.avg_incentives_paid,
+                cost,
+            );
+        }
+        let Some(worst) = self.worst_cliff() else {
+            return;
+        };
+        match self.cliff_cost(worst) {
+            Some(cost) if cost > 0.0 => {
+                let block = worst.cliff_block.expect("worst_cliff skips the uninterrupted program");
+                println!("  Costliest cliff: block {} (+${:.0} bad debt)", block, cost);
+            }
+            Some(_) => println!("  No cliff adds bad debt over the program running throughout"),
+            None => {}
+        }
+    }
+}
+
+/// Runs `mechanism` with the cliff at each block in `cliffs`, the program
+/// otherwise taken from `config.incentive_program` (or its defaults).
+pub fn sweep_cliff_timing(
+    mechanism: LiquidationMechanism,
+    scenario: PriceScenario,
+    cliffs: &[Option<usize>],
+    runs: usize,
+    config: &SimulationConfig,
+) -> Result<CliffReport> {
+    mechanism.validate()?;
+    config.validate()?;
+    let program = config.incentive_program.clone().unwrap_or_default();
+
+    let points = cliffs
+        .iter()
+        .map(|&cliff_block| {
+            let timed = SimulationConfig {
+                incentive_program: Some(IncentiveProgram { cliff_block, ..program.clone() }),
+                ..config.clone()
+            };
+            CliffPoint::from_results(cliff_block, &run_cascade_simulation(mechanism, scenario, runs, &timed))
+        })
+        .collect();
+
+    Ok(CliffReport { mechanism, scenario, runs, program, points })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cascade::{CascadeSimulationBuilder, CDP};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_empty_program_matches_no_program() {
+        let plain = SimulationConfig { seed: Some(2), ..Default::default() };
+        let empty = SimulationConfig {
+            incentive_program: Some(IncentiveProgram { bonus_per_liquidation: 0.0, mercenary_share: 0.0, ..Default::default() }),
+            ..plain.clone()
+        };
+        for mechanism in LiquidationMechanism::all() {
+            let a = run_cascade_simulation(mechanism, PriceScenario::VolatileCrash, 4, &plain);
+            let b = run_cascade_simulation(mechanism, PriceScenario::VolatileCrash, 4, &empty);
+            for (a, b) in a.iter().zip(&b) {
+                assert_eq!(a.total_liquidations, b.total_liquidations);
+                assert_eq!(a.bad_debt, b.bad_debt);
+                assert_eq!(b.incentives_paid, 0.0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_bonus_carries_marginal_liquidations_until_the_cliff() {
+        // A $6.50 penalty pays pool keepers $4.55, under their $7 bar; a $10
+        // bonus clears it while the program is live
+        let run = |debt: f64, cliff_block: Option<usize>, mercenary_share: f64| {
+            let program = IncentiveProgram { bonus_per_liquidation: 10.0, cliff_block, mercenary_share };
+            let config = SimulationConfig { incentive_program: Some(program), max_blocks: 3, ..Default::default() };
+            let mut rng = StdRng::seed_from_u64(0);
+            CascadeSimulationBuilder::new()
+                .config(config)
+                .cdps(vec![CDP::from_position(0, 1.0, debt)])
+                .price_path(vec![2000.0, 1000.0])
+                .build(&mut rng)
+                .unwrap()
+                .run(&mut rng)
+        };
+
+        let live = run(950.0, None, 0.0);
+        assert_eq!(live.total_liquidations, 1);
+        assert_eq!(live.incentives_paid, 10.0);
+        assert_eq!(run(950.0, Some(0), 0.0).total_liquidations, 0);
+        // A $26 penalty needs no bonus, but with every keeper a mercenary
+        // nobody is left once the cliff passes
+        assert_eq!(run(800.0, Some(0), 0.0).total_liquidations, 1);
+        assert_eq!(run(800.0, Some(0), 1.0).total_liquidations, 0);
+    }
+
+    #[test]
+    fn test_sweep_charges_cliffs_against_uninterrupted_program() {
+        let config = SimulationConfig { seed: Some(4), ..Default::default() };
+        let report =
+            sweep_cliff_timing(LiquidationMechanism::keeper_pool(), PriceScenario::FlashCrash, &CLIFF_SWEEP, 8, &config)
+                .unwrap();
+
+        assert_eq!(report.points.len(), CLIFF_SWEEP.len());
+        assert_eq!(report.points[0].avg_incentives_paid, 0.0);
+        assert!(report.points.last().unwrap().avg_incentives_paid > report.points[1].avg_incentives_paid);
+        assert_eq!(report.cliff_cost(report.points.last().unwrap()), Some(0.0));
+        assert!(report.worst_cliff().is_some_and(|p| p.cliff_block.is_some()));
+    }
+}
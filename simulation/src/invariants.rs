@@ -0,0 +1,274 @@
+//! Invariant Mining
+//!
+//! Runs many keeper pool cascades, records properties that held in every
+//! simulated block, and emits them as candidate Foundry invariant tests with
+//! concrete bounds, so the Solidity suite checks what the model says should
+//! never happen.
+//!
+//! ## Mined Properties
+//! - Largest payout to a single keeper as a share of one liquidation penalty
+//! - Smallest share of penalties retained by the protocol treasury
+//! - Longest liquidation queue while the price is within 30% of its start
+//! - Smallest drawdown at which bad debt was first observed
+//!
+//! ## Output
+//! An abstract `FairSimulatedInvariants` contract. The fuzzing harness
+//! inherits it and implements `IFairInvariantGhosts` with ghost variables
+//! tracked by its handler. Bounds are empirical: review them before adding
+//! the contract to the main suite.
+
+use crate::alerts::Comparison;
+use crate::cascade::{run_single_with_events, BlockEvent, LiquidationMechanism, PriceScenario, SimulationConfig};
+use crate::error::Result;
+
+pub const MODERATE_SHOCK: f64 = 0.30;
+
+#[derive(Debug, Clone)]
+pub struct CandidateInvariant {
+    pub name: &'static str,  // Solidity test name suffix
+    pub description: String,
+    pub ghost: &'static str, // Handler ghost getter the assertion reads
+    pub comparison: Comparison,
+    pub bound: u64,
+    pub observed: f64,
+    pub samples: usize,      // Blocks the property was checked on
+}
+
+impl CandidateInvariant {
+    fn assert_fn(&self) -> &'static str {
+        match self.comparison {
+            Comparison::Less => "assertLt",
+            Comparison::LessOrEqual => "assertLe",
+            Comparison::Greater => "assertGt",
+            Comparison::GreaterOrEqual => "assertGe",
+        }
+    }
+
+    pub fn to_solidity(&self) -> String {
+        format!(
+            "    /// {}\n    /// Observed {:.4} over {} samples.\n    function invariant_{}() public view {{\n        {}(ghosts.{}(), {});\n    }}\n",
+            self.description,
+            self.observed,
+            self.samples,
+            self.name,
+            self.assert_fn(),
+            self.ghost,
+            self.bound
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct InvariantReport {
+    pub runs: usize,
+    pub scenarios: Vec<PriceScenario>,
+    pub invariants: Vec<CandidateInvariant>,
+}
+
+impl InvariantReport {
+    pub fn print(&self) {
+        println!("  Runs per scenario:       {}", self.runs);
+        for inv in &self.invariants {
+            println!(
+                "  {:<33} {} {} (observed {:.4})",
+                format!("{}:", inv.name),
+                inv.comparison.symbol(),
+                inv.bound,
+                inv.observed
+            );
+        }
+    }
+
+    pub fn to_solidity(&self) -> String {
+        let scenarios: Vec<&str> = self.scenarios.iter().map(|s| s.name()).collect();
+        let mut out = String::from("// SPDX-License-Identifier: MIT\npragma solidity ^0.8.20;\n\n");
+        out.push_str("// Candidate invariants mined by `fair-sim invariants`.\n");
+        out.push_str(&format!(
+            "// {} keeper pool runs per scenario: {}.\n",
+            self.runs,
+            scenarios.join(", ")
+        ));
+        out.push_str("// Bounds are empirical; review before promoting to the main suite.\n\n");
+        out.push_str("import {Test} from \"forge-std/Test.sol\";\n\n");
+
+        out.push_str("/// Ghost state the fuzzing handler tracks across calls to Fair.\n");
+        out.push_str("interface IFairInvariantGhosts {\n");
+        for inv in &self.invariants {
+            out.push_str(&format!("    function {}() external view returns (uint256);\n", inv.ghost));
+        }
+        out.push_str("}\n\n");
+
+        out.push_str("abstract contract FairSimulatedInvariants is Test {\n");
+        out.push_str("    IFairInvariantGhosts internal ghosts;\n");
+        for inv in &self.invariants {
+            out.push('\n');
+            out.push_str(&inv.to_solidity());
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+#[derive(Default)]
+struct Observations {
+    blocks: usize,
+    liquidation_blocks: usize,
+    moderate_blocks: usize,
+    max_payout_share: f64,
+    min_protocol_share: f64,
+    max_moderate_queue: usize,
+    max_drawdown: f64,
+    first_bad_debt_drawdown: Option<f64>,
+}
+
+impl Observations {
+    fn record(&mut self, events: &[BlockEvent]) {
+        let Some(initial) = events.first().map(|e| e.price_start) else {
+            return;
+        };
+        for event in events {
+            let drawdown = 1.0 - event.price_end / initial;
+            self.blocks += 1;
+            self.max_drawdown = self.max_drawdown.max(drawdown);
+
+            if event.penalties > 0.0 {
+                let protocol_share = event.protocol_fees / event.penalties;
+                self.min_protocol_share = if self.liquidation_blocks == 0 {
+                    protocol_share
+                } else {
+                    self.min_protocol_share.min(protocol_share)
+                };
+                self.max_payout_share = self.max_payout_share.max(event.max_payout_share);
+                self.liquidation_blocks += 1;
+            }
+            if drawdown <= MODERATE_SHOCK {
+                self.max_moderate_queue = self.max_moderate_queue.max(event.queue_after());
+                self.moderate_blocks += 1;
+            }
+            if event.bad_debt > 0.0 {
+                let first = self.first_bad_debt_drawdown.get_or_insert(drawdown);
+                *first = first.min(drawdown);
+            }
+        }
+    }
+
+    fn into_invariants(self) -> Vec<CandidateInvariant> {
+        let bps = |share: f64| share * 10_000.0;
+        // Tolerate float noise so a 70/30 split yields exactly 7000 / 3000.
+        let ceil_bps = |share: f64| (bps(share) - 1e-6).ceil() as u64;
+        let floor_bps = |share: f64| (bps(share) + 1e-6).floor() as u64;
+        let no_bad_debt_within = self.first_bad_debt_drawdown.unwrap_or(self.max_drawdown);
+
+        vec![
+            CandidateInvariant {
+                name: "perKeeperPayoutBounded",
+                description: "Pool payout to a single keeper never exceeds this share of one penalty (bps).".to_string(),
+                ghost: "ghost_maxPerKeeperPayoutBps",
+                comparison: Comparison::LessOrEqual,
+                bound: ceil_bps(self.max_payout_share),
+                observed: bps(self.max_payout_share),
+                samples: self.liquidation_blocks,
+            },
+            CandidateInvariant {
+                name: "protocolShareRetained",
+                description: "Treasury retains at least this share of every penalty (bps).".to_string(),
+                ghost: "ghost_minProtocolShareBps",
+                comparison: Comparison::GreaterOrEqual,
+                bound: floor_bps(self.min_protocol_share),
+                observed: bps(self.min_protocol_share),
+                samples: self.liquidation_blocks,
+            },
+            CandidateInvariant {
+                name: "queueBoundedUnderModerateShock",
+                description: format!(
+                    "Liquidatable CDPs left unprocessed stay within this count while the price is within {:.0}% of its start.",
+                    MODERATE_SHOCK * 100.0
+                ),
+                ghost: "ghost_maxQueueUnderModerateShock",
+                comparison: Comparison::LessOrEqual,
+                bound: self.max_moderate_queue as u64,
+                observed: self.max_moderate_queue as f64,
+                samples: self.moderate_blocks,
+            },
+            CandidateInvariant {
+                name: "noBadDebtAboveDrawdown",
+                description: format!(
+                    "No bad debt accrues before the price has fallen {:.2}% from its start (bps; the ghost reads type(uint256).max until bad debt appears).",
+                    no_bad_debt_within * 100.0
+                ),
+                ghost: "ghost_minDrawdownAtBadDebtBps",
+                comparison: Comparison::GreaterOrEqual,
+                bound: floor_bps(no_bad_debt_within),
+                observed: bps(no_bad_debt_within),
+                samples: self.blocks,
+            },
+        ]
+    }
+}
+
+/// Mines candidate invariants from `runs` keeper pool cascades per scenario.
+pub fn mine_invariants(
+    scenarios: &[PriceScenario],
+    runs: usize,
+    config: &SimulationConfig,
+) -> Result<InvariantReport> {
+    config.validate()?;
+
+    let mut rng = config.rng();
+    let mut observations = Observations::default();
+    for &scenario in scenarios {
+        for _ in 0..runs {
+            let (_, events) =
+                run_single_with_events(LiquidationMechanism::keeper_pool(), scenario, config, &mut rng);
+            observations.record(&events);
+        }
+    }
+
+    Ok(InvariantReport {
+        runs,
+        scenarios: scenarios.to_vec(),
+        invariants: observations.into_invariants(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find<'a>(report: &'a InvariantReport, name: &str) -> &'a CandidateInvariant {
+        report.invariants.iter().find(|i| i.name == name).unwrap()
+    }
+
+    #[test]
+    fn test_payout_and_protocol_share_match_split() {
+        let config = SimulationConfig { seed: Some(3), ..Default::default() };
+        let report = mine_invariants(&[PriceScenario::FlashCrash], 5, &config).unwrap();
+
+        let payout = find(&report, "perKeeperPayoutBounded");
+        assert!(payout.samples > 0);
+        assert!(payout.bound <= 7_000);
+        assert_eq!(find(&report, "protocolShareRetained").bound, 3_000);
+    }
+
+    #[test]
+    fn test_moderate_shock_queue_respects_block_limit() {
+        let config = SimulationConfig { seed: Some(8), liquidations_per_block: 3, ..Default::default() };
+        let report = mine_invariants(&[PriceScenario::GradualDecline], 5, &config).unwrap();
+
+        let queue = find(&report, "queueBoundedUnderModerateShock");
+        assert!(queue.samples > 0);
+        assert!(queue.bound > 0, "a 3-per-block limit should leave a backlog");
+    }
+
+    #[test]
+    fn test_solidity_output_declares_every_ghost() {
+        let report = mine_invariants(&[PriceScenario::BlackSwan], 2, &SimulationConfig::default()).unwrap();
+        let solidity = report.to_solidity();
+
+        assert!(solidity.contains("abstract contract FairSimulatedInvariants is Test"));
+        for inv in &report.invariants {
+            assert!(solidity.contains(&format!("function {}() external view", inv.ghost)));
+            assert!(solidity.contains(&format!("function invariant_{}()", inv.name)));
+        }
+    }
+}
@@ -0,0 +1,227 @@
+//! Keeper Client Reference
+//!
+//! How an off-chain keeper plugs into the simulator: it consumes a stream of
+//! blocks, each carrying the oracle price and the liquidation opportunities
+//! open in it, hands every opportunity to a `KeeperStrategy`, and submits an
+//! intent for each one the strategy takes. The `keeper-sim-client` binary
+//! wraps this loop around a feed read from a file or recorded live from the
+//! engine.
+//!
+//! ```bash
+//! keeper-sim-client --scenario flash-crash --seed 7 --record feed.jsonl
+//! keeper-sim-client --feed feed.jsonl --min-profit 50 --gas-priority 0.8
+//! ```
+//!
+//! ## Feed
+//! JSON Lines, one `SimulatedBlock` per line in block order. A feed is
+//! recorded with the client watching but never bidding, so it is the run the
+//! simulated keepers would have had on their own; a keeper acting on it
+//! would change the blocks after its first liquidation.
+
+use std::io::{BufRead, Write};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::cascade::{CascadeSimulationBuilder, LiquidationMechanism, PriceScenario, SimulationConfig};
+use crate::error::Result;
+use crate::results::{load_jsonl, save_jsonl};
+use crate::strategy::{attempt_gas_cost, KeeperStrategy, Opportunity};
+
+/// One block as an off-chain keeper sees it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulatedBlock {
+    pub block: usize,
+    pub eth_price: f64, // Oracle price after the block's shock, before liquidations
+    pub opportunities: Vec<Opportunity>,
+}
+
+/// Records the feed of the run on `seed`.
+pub fn record_feed(
+    mechanism: LiquidationMechanism,
+    scenario: PriceScenario,
+    config: &SimulationConfig,
+    seed: u64,
+) -> Result<Vec<SimulatedBlock>> {
+    struct Recorder(Vec<Opportunity>);
+
+    impl KeeperStrategy for Recorder {
+        fn name(&self) -> &str {
+            "Recorder"
+        }
+
+        fn decide(&mut self, opportunity: &Opportunity) -> Option<crate::strategy::KeeperAction> {
+            self.0.push(opportunity.clone());
+            None
+        }
+    }
+
+    let mut rng = config.run_rng(seed);
+    let mut recorder = Recorder(Vec::new());
+    let mut blocks = Vec::new();
+    let mut sim = CascadeSimulationBuilder::new()
+        .config(config.clone())
+        .mechanism(mechanism)
+        .scenario(scenario)
+        .build(&mut rng)?;
+    sim.run_with_strategy_observed(&mut rng, &mut recorder, |state| {
+        blocks.push(SimulatedBlock {
+            block: state.event.block,
+            eth_price: state.event.price_after_shock,
+            opportunities: Vec::new(),
+        })
+    });
+    for opportunity in recorder.0 {
+        if let Some(block) = blocks.iter_mut().find(|b| b.block == opportunity.block) {
+            block.opportunities.push(opportunity);
+        }
+    }
+    Ok(blocks)
+}
+
+pub fn save_feed(path: impl AsRef<Path>, blocks: &[SimulatedBlock]) -> Result<()> {
+    save_jsonl(path, blocks)
+}
+
+pub fn load_feed(path: impl AsRef<Path>) -> Result<Vec<SimulatedBlock>> {
+    load_jsonl(path)
+}
+
+/// Blocks read one line at a time, for feeds that arrive as they are produced.
+pub fn read_feed(reader: impl BufRead) -> impl Iterator<Item = Result<SimulatedBlock>> {
+    reader
+        .lines()
+        .filter(|line| !line.as_ref().is_ok_and(|line| line.trim().is_empty()))
+        .map(|line| Ok(serde_json::from_str(&line?)?))
+}
+
+/// A liquidation the client would submit.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Intent {
+    pub block: usize,
+    pub cdp_id: usize,
+    pub gas_priority: f64,
+    pub expected_profit: f64, // Full penalty, before any pool split
+    pub max_gas_cost: f64,    // Gas paid if the client executes
+}
+
+/// Running totals over the blocks a client has consumed.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ClientSummary {
+    pub blocks: usize,
+    pub opportunities: usize,
+    pub intents: usize,
+    pub expected_profit: f64,
+    pub max_gas_cost: f64,
+}
+
+/// The reference keeper loop around any `KeeperStrategy`.
+pub struct KeeperClient<S> {
+    strategy: S,
+    summary: ClientSummary,
+}
+
+impl<S: KeeperStrategy> KeeperClient<S> {
+    pub fn new(strategy: S) -> Self {
+        Self { strategy, summary: ClientSummary::default() }
+    }
+
+    pub fn strategy(&self) -> &S {
+        &self.strategy
+    }
+
+    pub fn summary(&self) -> &ClientSummary {
+        &self.summary
+    }
+
+    /// Offers every opportunity in `block` to the strategy, in feed order.
+    pub fn on_block(&mut self, block: &SimulatedBlock) -> Vec<Intent> {
+        self.summary.blocks += 1;
+        self.summary.opportunities += block.opportunities.len();
+        let intents: Vec<Intent> = block
+            .opportunities
+            .iter()
+            .filter_map(|opportunity| {
+                let action = self.strategy.decide(opportunity)?;
+                Some(Intent {
+                    block: opportunity.block,
+                    cdp_id: opportunity.cdp_id,
+                    gas_priority: action.gas_priority,
+                    expected_profit: opportunity.expected_profit,
+                    max_gas_cost: attempt_gas_cost(action, true, opportunity.mechanism),
+                })
+            })
+            .collect();
+        self.summary.intents += intents.len();
+        self.summary.expected_profit += intents.iter().map(|i| i.expected_profit).sum::<f64>();
+        self.summary.max_gas_cost += intents.iter().map(|i| i.max_gas_cost).sum::<f64>();
+        intents
+    }
+
+    /// Consumes a whole feed, writing each intent as a JSON line to `out`.
+    pub fn run(
+        &mut self,
+        blocks: impl IntoIterator<Item = Result<SimulatedBlock>>,
+        mut out: impl Write,
+    ) -> Result<ClientSummary> {
+        for block in blocks {
+            for intent in self.on_block(&block?) {
+                serde_json::to_writer(&mut out, &intent)?;
+                out.write_all(b"\n")?;
+            }
+            out.flush()?;
+        }
+        Ok(self.summary.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cascade::run_single_with_strategy;
+    use crate::strategy::{FnStrategy, ThresholdStrategy};
+
+    fn feed() -> Vec<SimulatedBlock> {
+        let config = SimulationConfig { num_cdps: 200, ..Default::default() };
+        record_feed(LiquidationMechanism::Traditional, PriceScenario::FlashCrash, &config, 7).unwrap()
+    }
+
+    #[test]
+    fn test_feed_matches_engine_run() {
+        let config = SimulationConfig { num_cdps: 200, ..Default::default() };
+        let blocks = feed();
+        let mut passive = FnStrategy::new("Passive", |_| None);
+        let (result, _) = run_single_with_strategy(
+            LiquidationMechanism::Traditional,
+            PriceScenario::FlashCrash,
+            &config,
+            &mut config.run_rng(7),
+            &mut passive,
+        );
+
+        assert_eq!(blocks, feed());
+        assert!(blocks.windows(2).all(|w| w[1].block == w[0].block + 1));
+        let offered: usize = blocks.iter().map(|b| b.opportunities.len()).sum();
+        assert!(offered >= result.total_liquidations && result.total_liquidations > 0);
+        assert!(blocks.iter().flat_map(|b| b.opportunities.iter().map(move |o| (b, o))).all(|(b, o)| o.block == b.block));
+    }
+
+    #[test]
+    fn test_client_round_trips_feed() {
+        let blocks = feed();
+        let path = std::env::temp_dir().join(format!("fair-sim-feed-{}.jsonl", std::process::id()));
+        save_feed(&path, &blocks).unwrap();
+        let loaded = load_feed(&path).unwrap();
+        let file = std::io::BufReader::new(std::fs::File::open(&path).unwrap());
+        let mut client = KeeperClient::new(ThresholdStrategy { min_profit: 0.0, gas_priority: 0.5 });
+        let mut out = Vec::new();
+        let summary = client.run(read_feed(file), &mut out).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(loaded, blocks);
+        assert_eq!(summary.blocks, blocks.len());
+        assert!(summary.intents > 0 && summary.intents <= summary.opportunities);
+        assert_eq!(String::from_utf8(out).unwrap().lines().count(), summary.intents);
+        assert!((summary.max_gas_cost - summary.intents as f64 * 70.0).abs() < 1e-6);
+    }
+}
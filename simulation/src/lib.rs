@@ -5,23 +5,173 @@
 //!
 //! ## Modules
 //!
+//! - `core`: CDP, keeper, market and accounting types shared by `poa` and `cascade`
 //! - `poa`: Price of Anarchy simulation (single-shot liquidation game)
+//! - `scoring`: Liquidation-eligibility features, weight vectors and thresholds, serializable as JSON
 //! - `cascade`: Deleveraging cascade simulation (multi-step dynamics)
+//! - `price_path`: `PricePath` trait for user-defined per-block price shocks
+//! - `scenarios`: Named presets of historical episodes (price path plus CDP/keeper populations)
 //! - `monte_carlo`: Monte Carlo stress testing with VaR/CVaR metrics
+//! - `path_backend`: Price paths generated in chunks on the CPU or a GPU (`gpu` feature) and streamed into cascades
+//! - `snapshot`: Live-monitoring adapter running Monte Carlo from on-chain state
+//! - `alerts`: Threshold assertions and exit codes for automated risk gates
+//! - `results`: JSON persistence of Monte Carlo, cascade and game result sets (Parquet with `arrow`)
+//! - `manifest`: Version, commit, seed and resolved config behind a set of results
+//! - `distribution`: Quantiles and histograms of bad debt, cascade depth and participation per cell
+//! - `diff`: Metric deltas with significance flags and intervals between two
+//!   Monte Carlo or cascade result sets
+//! - `report`: Self-contained HTML report of a campaign with Fair-vs-Traditional deltas and inline charts
+//! - `report::table`: Summary comparisons as GitHub Markdown or LaTeX (booktabs) tables
+//! - `experiments`: Named experiment registry with tags and descriptions
+//! - `audit`: End-of-run conservation checks on collateral, debt and penalty flows
+//! - `narrative`: Human-readable timelines from a run's block event log
+//! - `live`: Campaigns streamed block by block as JSON Lines for live notebooks
+//! - `animation`: Per-block cascade frames as compact JSON for a web animation
+//! - `strategy`: Public `KeeperStrategy` trait for external keeper implementations
+//! - `backtest`: PnL backtests of a keeper strategy in simulated cascades
+//! - `keeper_client`: Reference off-chain keeper consuming a block feed through `KeeperStrategy` (`keeper-sim-client` binary)
+//! - `portfolio`: CSV/JSON CDP books replayed in place of the synthetic one
+//! - `position`: Borrower-side liquidation probability for a single CDP
+//! - `recommend`: Penalty/MCR/split proposals that meet governance risk targets
+//! - `invariants`: Simulation-mined bounds emitted as candidate Foundry invariants
+//! - `adversarial`: Fuzzing adversarial keeper mixes against the keeper pool
+//! - `contagion`: Fair bad debt attributable to a neighboring protocol's liquidations
+//! - `shared_liquidity`: Rival stablecoins competing for keeper capital in a market-wide event
+//! - `emissions`: Keeper pool payouts in a protocol token that collapses with system health
+//! - `incentive_cliff`: Keeper incentive programs ending (or vesting cliffs hitting) mid-crisis
+//! - `fee_burn`: Priority fees burned racing for execution, intra-pool versus Traditional
+//! - `fallback`: Coverage cost of fallback policies when the selected pool executor fails
+//! - `mempool`: Coverage and concentration versus the share of keepers watching the mempool
+//! - `switching`: Hybrid policies switching the keeper pool to a backstop under stress
+//! - `cooldown`: Borrower protection versus bad debt under per-borrower liquidation rate limits
+//! - `attribution`: Price declines split into the scenario's and the liquidations', and the amplification per mechanism
+//! - `timelock`: Bad debt accruing while a governance fix for a flagged configuration waits out its timelock
+//! - `treasury`: Keeper pool payouts dripped from a treasury against per-event splits
+//! - `flip`: Maker flip (English) auctions under keeper apathy, and the zero-bid auctions it leaves
+//! - `llamma`: crvUSD soft liquidation bands against the keeper pool: bad debt and borrower loss
+//! - `staking`: Keeper pool stakes and slashing against the flat split, with griefers free riding
+//! - `commit_reveal`: Keeper pool commit-reveal latency per reveal delay, against the MEV it saves
+//! - `grace`: Grace periods for borrowers to top up before liquidation, bad debt against borrower loss
+//! - `batch_auction`: Per-block uniform-price batch auctions against per-CDP races: gas, price impact and fairness
+//! - `penalty_curve`: Penalty curves rising below the minimum ratio, searched for the least bad debt under a borrower-loss cap
+//! - `reputation`: Keeper pool executors drawn by reputation: latency against execution concentration, with griefers
+//! - `pool_split`: Keeper pool shares split alike against pro rata to capital: inequality and small-keeper viability
+//! - `liquidation_queue`: Liquidations in the protocol's published queue, by ratio or IPFE score, against keepers' profit order: price impact and bad debt
+//! - `liquidity`: Mechanism robustness to market makers withdrawing mid-crash (price impact regime switches)
+//! - `rotation`: Keeper pool active sets rotated per epoch, and their liveness risk
+//! - `ruin`: Keeper capital careers net of gas and inventory drawdowns, and how often small keepers are wiped out
+//! - `soak`: Year-long horizons with interest, borrower churn and stress bursts: buffer growth, keeper churn and dust (`soak` binary)
+//! - `budget`: Scenario × mechanism campaigns under a wall-clock, run-count or CI-width budget, runs allocated by variance
+//! - `batch`: Scenario/model × mechanism jobs run as one deduplicated batch on a bounded thread pool
+//! - `checkpoint`: Monte Carlo campaigns checkpointed as they run and resumed after an interruption
+//! - `compute`: Per-run wall time, RNG draws and allocations, summed into campaign summaries
+//! - `sweep`: Cross-product parameter sweeps over any config field, as a long-format table,
+//!   and the canned bad-debt heatmap over shock severity and keeper count
+//! - `plots`: Price path fans, bad-debt histograms and Lorenz curves as SVG/PNG (`plots` feature)
+//! - `database`: Append-only SQLite log of every campaign's setup and per-run metrics (`sqlite` feature)
+//! - `figures`: Whitepaper charts and tables pinned to checked-in configs and seeds (`figures` binary)
+//! - `outliers`: Worst runs of a campaign with the seeds that replay them
+//! - `config`: TOML/YAML simulation setups (`--config` on each subcommand)
+//! - `stats`: Sample moments and significance tests
+//! - `quantiles`: Streaming t-digest quantiles and tail means in bounded memory
 //!
 //! ## Usage
 //!
 //! ```bash
 //! # Run Price of Anarchy simulation
-//! cargo run --bin poa --release
+//! cargo run --release -- poa
 //!
 //! # Run Cascade simulation
-//! cargo run --bin cascade --release
+//! cargo run --release -- cascade
 //!
-//! # Run Monte Carlo stress testing
-//! cargo run --bin monte_carlo --release
+//! # Run Monte Carlo stress testing (reproducibly)
+//! cargo run --release -- monte-carlo --seed 42
+//!
+//! # Run Monte Carlo from an indexer snapshot
+//! cargo run --release -- monitor snapshot.json
+//!
+//! # Compare two saved campaigns
+//! cargo run --release -- diff before.json after.json
+//! cargo run --release -- compare before-cascade.json after-cascade.json
+//!
+//! # Propose protocol parameters meeting risk targets
+//! cargo run --release -- recommend --target "insolvency_prob<0.001"
+//!
+//! # Regenerate every whitepaper figure into figures-out/v<version>-<commit>/
+//! cargo run --release --bin figures
+//!
+//! # Watch a single run live (optional `tui` feature)
+//! cargo run --features tui --release -- dashboard
 //! ```
 
+#![allow(clippy::upper_case_acronyms)]
+
+pub mod core;
 pub mod poa;
+pub mod scoring;
 pub mod cascade;
+pub mod price_path;
+pub mod scenarios;
 pub mod monte_carlo;
+pub mod path_backend;
+pub mod snapshot;
+pub mod alerts;
+pub mod results;
+pub mod manifest;
+pub mod diff;
+pub mod distribution;
+pub mod report;
+pub mod experiments;
+pub mod audit;
+pub mod narrative;
+pub mod animation;
+pub mod strategy;
+pub mod backtest;
+pub mod keeper_client;
+pub mod position;
+pub mod portfolio;
+pub mod recommend;
+pub mod invariants;
+pub mod adversarial;
+pub mod contagion;
+pub mod shared_liquidity;
+pub mod emissions;
+pub mod incentive_cliff;
+pub mod fee_burn;
+pub mod fallback;
+pub mod mempool;
+pub mod switching;
+pub mod cooldown;
+pub mod attribution;
+pub mod timelock;
+pub mod treasury;
+pub mod flip;
+pub mod llamma;
+pub mod staking;
+pub mod commit_reveal;
+pub mod grace;
+pub mod batch_auction;
+pub mod penalty_curve;
+pub mod reputation;
+pub mod pool_split;
+pub mod liquidation_queue;
+pub mod liquidity;
+pub mod live;
+pub mod rotation;
+pub mod ruin;
+pub mod soak;
+pub mod batch;
+pub mod budget;
+pub mod checkpoint;
+pub mod compute;
+pub mod sweep;
+#[cfg(feature = "plots")]
+pub mod plots;
+#[cfg(feature = "sqlite")]
+pub mod database;
+pub mod figures;
+pub mod outliers;
+pub mod stats;
+pub mod quantiles;
+pub mod config;
+pub mod error;
@@ -0,0 +1,163 @@
+//! Liquidity Regime Switches
+//!
+//! Whether a mechanism that survives a crash's price path also survives the
+//! market thinning under it (see `cascade::LiquidityCrisis`). Market makers
+//! withdrawing mid-crash multiply the price impact of every ETH liquidations
+//! sell, so each seizure moves the price further and covers less of the debt
+//! it repays. Each mechanism runs at the configured impact throughout and
+//! again with the crisis; the difference is the bad debt the regime change
+//! alone adds.
+//!
+//! ## Method
+//! - Both campaigns share every seed, drawing one for the comparison when
+//!   `config.seed` is unset, so books, keepers and exogenous moves match
+//!   until the crisis block
+//! - Uses `config.liquidity_crisis`, or `LiquidityCrisis::default()` (10x
+//!   impact from block 5) when unset
+//! - The most robust mechanism is the one whose mean bad debt the crisis
+//!   raises least
+
+use crate::cascade::{
+    run_cascade_simulation, CascadeResult, LiquidationMechanism, LiquidityCrisis, PriceScenario, SimulationConfig,
+};
+use crate::error::Result;
+
+#[derive(Debug, Clone)]
+pub struct LiquidityPoint {
+    pub mechanism: LiquidationMechanism,
+    pub steady_bad_debt: f64, // Mean at the configured impact throughout
+    pub crisis_bad_debt: f64, // Mean with the crisis
+    pub steady_price_drop_pct: f64,
+    pub crisis_price_drop_pct: f64,
+    pub crisis_bad_debt_frequency: f64,
+    pub crisis_unliquidated: f64,
+}
+
+impl LiquidityPoint {
+    fn from_results(mechanism: LiquidationMechanism, steady: &[CascadeResult], crisis: &[CascadeResult]) -> Self {
+        let mean = |results: &[CascadeResult], f: fn(&CascadeResult) -> f64| {
+            results.iter().map(f).sum::<f64>() / results.len().max(1) as f64
+        };
+        Self {
+            mechanism,
+            steady_bad_debt: mean(steady, |r| r.bad_debt),
+            crisis_bad_debt: mean(crisis, |r| r.bad_debt),
+            steady_price_drop_pct: mean(steady, |r| r.price_drop_pct),
+            crisis_price_drop_pct: mean(crisis, |r| r.price_drop_pct),
+            crisis_bad_debt_frequency: mean(crisis, |r| f64::from(u8::from(r.bad_debt > 0.0))),
+            crisis_unliquidated: mean(crisis, |r| r.unliquidated_underwater as f64),
+        }
+    }
+
+    /// Mean bad debt the liquidity crisis adds.
+    pub fn added_bad_debt(&self) -> f64 {
+        self.crisis_bad_debt - self.steady_bad_debt
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LiquidityReport {
+    pub scenario: PriceScenario,
+    pub crisis: LiquidityCrisis,
+    pub runs: usize,
+    pub points: Vec<LiquidityPoint>, // One per mechanism, in the order given
+}
+
+impl LiquidityReport {
+    /// The mechanism whose bad debt the crisis raises least.
+    pub fn most_robust(&self) -> Option<&LiquidityPoint> {
+        self.points.iter().min_by(|a, b| a.added_bad_debt().total_cmp(&b.added_bad_debt()))
+    }
+
+    pub fn print(&self) {
+        println!("| Mechanism   | Steady Bad Debt | Crisis Bad Debt | Added    | P(Bad Debt) | Price Drop      | Unliquidated |");
+        println!("|-------------|-----------------|-----------------|----------|-------------|-----------------|--------------|");
+        for point in &self.points {
+            println!(
+                "| {:11} | ${:14.0} | ${:14.0} | ${:7.0} | {:10.1}% | {:5.1}% -> {:4.1}% | {:12.1} |",
+                point.mechanism.info().short_name,
+                point.steady_bad_debt,
+                point.crisis_bad_debt,
+                point.added_bad_debt(),
+                point.crisis_bad_debt_frequency * 100.0,
+                point.steady_price_drop_pct,
+                point.crisis_price_drop_pct,
+                point.crisis_unliquidated,
+            );
+        }
+        if let Some(best) = self.most_robust() {
+            println!("  Most robust to the regime change: {} (+${:.0})", best.mechanism.name(), best.added_bad_debt());
+        }
+    }
+}
+
+/// Runs each of `mechanisms` through `scenario` at the configured price
+/// impact and again under the liquidity crisis.
+pub fn compare_liquidity_regimes(
+    mechanisms: &[LiquidationMechanism],
+    scenario: PriceScenario,
+    runs: usize,
+    config: &SimulationConfig,
+) -> Result<LiquidityReport> {
+    let crisis = config.liquidity_crisis.unwrap_or_default();
+    let seed = config.seed.unwrap_or_else(rand::random);
+    let steady_config = SimulationConfig { liquidity_crisis: None, seed: Some(seed), ..config.clone() };
+    let crisis_config = SimulationConfig { liquidity_crisis: Some(crisis), ..steady_config.clone() };
+    crisis_config.validate()?;
+
+    let points = mechanisms
+        .iter()
+        .map(|&mechanism| {
+            mechanism.validate()?;
+            let steady = run_cascade_simulation(mechanism, scenario, runs, &steady_config);
+            let thin = run_cascade_simulation(mechanism, scenario, runs, &crisis_config);
+            Ok(LiquidityPoint::from_results(mechanism, &steady, &thin))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(LiquidityReport { scenario, crisis, runs, points })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unit_multiplier_changes_nothing() {
+        let config = SimulationConfig {
+            seed: Some(8),
+            liquidity_crisis: Some(LiquidityCrisis { block: 0, impact_multiplier: 1.0 }),
+            ..Default::default()
+        };
+        let report =
+            compare_liquidity_regimes(&LiquidationMechanism::all(), PriceScenario::VolatileCrash, 5, &config).unwrap();
+
+        assert_eq!(report.points.len(), LiquidationMechanism::all().len());
+        for point in &report.points {
+            assert_eq!(point.added_bad_debt(), 0.0);
+            assert_eq!(point.steady_price_drop_pct, point.crisis_price_drop_pct);
+        }
+    }
+
+    #[test]
+    fn test_thin_market_deepens_the_crash() {
+        let config = SimulationConfig { seed: Some(3), ..Default::default() };
+        let report =
+            compare_liquidity_regimes(&LiquidationMechanism::all(), PriceScenario::FlashCrash, 8, &config).unwrap();
+
+        assert_eq!(report.crisis, LiquidityCrisis::default());
+        for point in &report.points {
+            // Bands the crash gapped through have finished converting by the
+            // crisis, and a batch settles the crash's queue in its first block
+            if let LiquidationMechanism::SoftLiquidation(_) | LiquidationMechanism::BatchAuction(_) = point.mechanism {
+                assert!(point.added_bad_debt() >= 0.0, "{:?}", point);
+                continue;
+            }
+            // Seizures that no longer cover the debt stall keepers as well,
+            // so the crisis shows up in bad debt more than in the price
+            assert!(point.added_bad_debt() > 0.0, "{:?}", point);
+        }
+        let best = report.most_robust().unwrap();
+        assert!(report.points.iter().all(|p| best.added_bad_debt() <= p.added_bad_debt()));
+    }
+}
@@ -0,0 +1,197 @@
+//! Live Block Streams
+//!
+//! A campaign streamed block by block as newline-delimited JSON, so a
+//! Python or Observable notebook can plot runs as the engine produces them
+//! instead of waiting for the campaign to finish. Each line is flushed as
+//! it is written; a reader sees block `n` as soon as the engine is past it.
+//!
+//! ## Format
+//!
+//! A `block` line per block, then a `run` line closing the run:
+//!
+//! ```json
+//! {"kind":"block","scenario":"FlashCrash","mechanism":"keeper-pool","run":0,"seed":42,"block":0,"price":1400.0,"liquidatable":12,"liquidated":9,"queue":3,"eth_sold":31.2,"bad_debt":0.0,"penalties":5120.0,"protocol_revenue":1536.0,"keeper_profit":3584.0,"health":[0,3,41,210,201,42]}
+//! {"kind":"run","scenario":"FlashCrash","mechanism":"keeper-pool","run":0,"seed":42,"bad_debt":0.0,"total_liquidations":57,"participation_rate":0.85,"price_drop_pct":31.4}
+//! ```
+//!
+//! Counts and money in a block line are for that block; `protocol_revenue`
+//! and `keeper_profit` are totals so far. `health` counts open CDPs per
+//! `animation::HEALTH_BANDS` band.
+//!
+//! Runs go one at a time, scenario by scenario, every mechanism replaying
+//! each seed in turn. Seeds are the campaign's, so run `i` is run `i` of
+//! `fair-sim cascade` with the same seed and config. A reader hanging up
+//! ends the campaign with the write error.
+
+use std::io::Write;
+
+use serde::{Deserialize, Serialize};
+
+use crate::animation::{campaign_seeds, health_histogram};
+use crate::cascade::{replay_observed, LiquidationMechanism, LiveState, PriceScenario, SimulationConfig};
+use crate::error::Result;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LiveBlock {
+    pub scenario: PriceScenario,
+    pub mechanism: String, // `MechanismInfo::key`
+    pub run: usize,
+    pub seed: u64,
+    pub block: usize,
+    pub price: f64, // Oracle price at the end of the block
+    pub liquidatable: usize,
+    pub liquidated: usize,
+    pub queue: usize, // Liquidatable but not liquidated this block
+    pub eth_sold: f64,
+    pub bad_debt: f64,
+    pub penalties: f64,
+    pub protocol_revenue: f64,
+    pub keeper_profit: f64,
+    pub health: Vec<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LiveRun {
+    pub scenario: PriceScenario,
+    pub mechanism: String,
+    pub run: usize,
+    pub seed: u64,
+    pub bad_debt: f64,
+    pub total_liquidations: usize,
+    pub participation_rate: f64,
+    pub price_drop_pct: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum LiveLine {
+    Block(LiveBlock),
+    Run(LiveRun),
+}
+
+/// Writes `line` and a newline, flushing so the reader sees it now.
+fn send(out: &mut impl Write, line: &LiveLine) -> Result<()> {
+    serde_json::to_writer(&mut *out, line)?;
+    out.write_all(b"\n")?;
+    out.flush()?;
+    Ok(())
+}
+
+/// Streams `runs` runs of every scenario under each of `mechanisms` to
+/// `out`, returning the number of lines written.
+pub fn stream_campaign(
+    mechanisms: &[LiquidationMechanism],
+    scenarios: &[PriceScenario],
+    runs: usize,
+    config: &SimulationConfig,
+    mut out: impl Write,
+) -> Result<usize> {
+    config.validate()?;
+    for mechanism in mechanisms {
+        mechanism.validate()?;
+    }
+    let seeds = campaign_seeds(runs, config);
+    let mut lines = 0;
+    for &scenario in scenarios {
+        for (run, &seed) in seeds.iter().enumerate() {
+            for &mechanism in mechanisms {
+                let key = mechanism.info().key.to_string();
+                // The observer can't stop the run, so a failed write skips
+                // the rest of its blocks and ends the campaign after it
+                let mut sent = Ok(());
+                let result = replay_observed(mechanism, scenario, config, seed, |state: &LiveState| {
+                    if sent.is_ok() {
+                        let block = LiveBlock {
+                            scenario,
+                            mechanism: key.clone(),
+                            run,
+                            seed,
+                            block: state.event.block,
+                            price: state.event.price_end,
+                            liquidatable: state.event.liquidatable,
+                            liquidated: state.event.liquidated,
+                            queue: state.event.queue_after(),
+                            eth_sold: state.event.eth_sold,
+                            bad_debt: state.event.bad_debt,
+                            penalties: state.event.penalties,
+                            protocol_revenue: state.protocol_revenue,
+                            keeper_profit: state.keeper_profits.iter().sum(),
+                            health: health_histogram(&state.collateral_ratios),
+                        };
+                        sent = send(&mut out, &LiveLine::Block(block));
+                        lines += 1;
+                    }
+                });
+                sent?;
+                let summary = LiveRun {
+                    scenario,
+                    mechanism: key,
+                    run,
+                    seed,
+                    bad_debt: result.bad_debt,
+                    total_liquidations: result.total_liquidations,
+                    participation_rate: result.participation_rate,
+                    price_drop_pct: result.price_drop_pct,
+                };
+                send(&mut out, &LiveLine::Run(summary))?;
+                lines += 1;
+            }
+        }
+    }
+    Ok(lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cascade::run_cascade_simulation;
+
+    #[test]
+    fn test_stream_replays_the_campaign() {
+        let config = SimulationConfig { seed: Some(6), num_cdps: 120, max_blocks: 20, ..Default::default() };
+        let mechanisms = LiquidationMechanism::all();
+        let mut out = Vec::new();
+        let written = stream_campaign(&mechanisms, &[PriceScenario::FlashCrash], 3, &config, &mut out).unwrap();
+
+        let lines: Vec<LiveLine> =
+            String::from_utf8(out).unwrap().lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(lines.len(), written);
+        let runs: Vec<&LiveRun> = lines
+            .iter()
+            .filter_map(|line| match line {
+                LiveLine::Run(run) => Some(run),
+                LiveLine::Block(_) => None,
+            })
+            .collect();
+        assert_eq!(runs.len(), 3 * mechanisms.len());
+        for &mechanism in &mechanisms {
+            let campaign = run_cascade_simulation(mechanism, PriceScenario::FlashCrash, 3, &config);
+            let streamed: Vec<_> = runs.iter().filter(|r| r.mechanism == mechanism.info().key).collect();
+            for (run, result) in streamed.iter().zip(&campaign) {
+                assert_eq!(Some(run.seed), result.seed);
+                assert_eq!(run.bad_debt, result.bad_debt);
+            }
+        }
+        // Every run's blocks come before its summary, in order
+        let LiveLine::Block(first) = &lines[0] else { panic!("stream starts with a block") };
+        assert_eq!((first.run, first.block), (0, 0));
+        assert_eq!(first.queue, first.liquidatable - first.liquidated);
+    }
+
+    #[test]
+    fn test_reader_hanging_up_ends_the_stream() {
+        struct HungUp;
+        impl Write for HungUp {
+            fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+                Err(std::io::ErrorKind::BrokenPipe.into())
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+        let config = SimulationConfig { seed: Some(6), num_cdps: 50, ..Default::default() };
+
+        let streamed = stream_campaign(&LiquidationMechanism::all(), &[PriceScenario::FlashCrash], 2, &config, HungUp);
+        assert!(streamed.is_err());
+    }
+}
@@ -0,0 +1,459 @@
+//! Soft Liquidations
+//!
+//! crvUSD's LLAMMA (`LiquidationMechanism::SoftLiquidation`): the bands a
+//! CDP's collateral converts through, the block of band trades the cascade
+//! engine hands each round to, and the comparison against Fair's keeper
+//! pool on the same scenario. The pool closes a CDP whole and takes its
+//! penalty; the bands trade a CDP's collateral into stablecoin a slice at
+//! a time and back again, so borrowers lose only the arbitrage spread on
+//! what moved, and less collateral reaches the market. Prices that gap
+//! below a CDP's bands convert it at the gapped price, which is where the
+//! bands leave bad debt the pool would not.
+//!
+//! ## Method
+//! - Runs the keeper pool and LLAMMA at each band count with the same
+//!   band width and spread; every run sees the same seeds when
+//!   `config.seed` is set
+//! - Borrower loss is collateral value seized beyond the debt repaid for
+//!   the pool, and the spread paid on every band trade for LLAMMA
+//! - Converted and restored ETH are the collateral sold out of bands and
+//!   bought back into them, averaged over runs
+
+use serde::{Deserialize, Serialize};
+
+use crate::audit::RunLedger;
+use crate::cascade::{
+    run_cascade_simulation, CascadeResult, LiquidationEvent, LiquidationMechanism, PriceScenario, SimulationConfig, DUST_DEBT,
+};
+use crate::core::{Keeper, LiquidationIndex, CDP};
+use crate::error::{Error, Result};
+
+/// Bands of crvUSD's LLAMMA (`LiquidationMechanism::SoftLiquidation`).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Llamma {
+    pub bands: usize,    // Bands each CDP's collateral is spread over (N)
+    pub band_width: f64, // Price fall across one band, as a fraction (1/A)
+    pub spread: f64,     // Arbitrageurs' discount to the market on each conversion
+}
+
+impl Default for Llamma {
+    fn default() -> Self {
+        Self {
+            bands: 10,
+            band_width: 0.01,
+            spread: 0.005,
+        }
+    }
+}
+
+impl Llamma {
+    pub(crate) fn validate(&self) -> Result<()> {
+        if self.bands == 0 {
+            return Err(Error::Invalid("soft liquidation needs at least one band".to_string()));
+        }
+        if !(self.band_width > 0.0 && self.band_width < 1.0) {
+            return Err(Error::Invalid("soft liquidation band_width must be within (0, 1)".to_string()));
+        }
+        if !(0.0..1.0).contains(&self.spread) {
+            return Err(Error::Invalid("soft liquidation spread must be within [0, 1)".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Share of a CDP's deposit converted to stablecoin at `price`, for
+    /// bands starting at `top`: linear within a band, 1 below the last.
+    pub fn converted_share(&self, top: f64, price: f64) -> f64 {
+        if price >= top {
+            return 0.0;
+        }
+        let crossed = (top / price).ln() / -(1.0 - self.band_width).ln();
+        (crossed / self.bands as f64).min(1.0)
+    }
+}
+
+/// Soft liquidation tallies over a run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct SoftOutcome {
+    pub entered: usize,  // CDPs whose bands began converting
+    pub converted: f64,  // ETH sold out of bands
+    pub restored: f64,   // ETH bought back into bands as the price recovered
+    pub unfilled: f64,   // USD of band trades the arbitrageurs lacked capital for, summed over blocks
+    #[serde(default)]
+    pub trades: usize,   // Band trades, each CDP's conversions and buybacks counted per block
+    #[serde(default)]
+    pub spread: f64,     // USD borrowers paid arbitrageurs on band trades
+    #[serde(default)]
+    pub closed: usize,   // CDPs whose bands finished converting, counted as liquidated
+}
+
+/// Where a CDP's LLAMMA bands start and how much collateral they hold,
+/// fixed the first block the CDP is seen.
+#[derive(Debug, Clone, Copy)]
+struct Bands {
+    top: f64,      // Price at which the CDP reaches the minimum ratio
+    deposit: f64,  // ETH spread over the bands
+    entered: bool, // Sold out of its first band
+    closed: bool,  // Converted in full, its one liquidation counted
+}
+
+/// What a block of band trades adds to its round.
+#[derive(Debug, Default)]
+pub(crate) struct SoftRound {
+    pub(crate) eth_sold: f64, // Net of collateral bought back
+    pub(crate) conversions: usize, // Band trades selling collateral, buybacks aside
+    pub(crate) attempts: usize,
+    pub(crate) joined: usize,
+}
+
+/// The engine state a block of band trades reads and moves, lent by the
+/// cascade for the round.
+pub(crate) struct BandMarket<'a> {
+    pub(crate) config: &'a SimulationConfig,
+    pub(crate) mechanism: LiquidationMechanism,
+    pub(crate) block: usize,
+    pub(crate) price: f64,
+    pub(crate) cdps: &'a mut [CDP],
+    pub(crate) index: &'a mut LiquidationIndex,
+    pub(crate) keepers: &'a mut [Keeper],
+    pub(crate) active_keepers: usize,
+    pub(crate) liquidatable_since: &'a [Option<usize>],
+    pub(crate) ledger: &'a mut RunLedger,
+    pub(crate) fee_burn: &'a mut f64,
+    pub(crate) borrower_loss: &'a mut f64,
+    pub(crate) liquidation_delay: &'a mut f64,
+    pub(crate) liquidation_log: Option<&'a mut Vec<LiquidationEvent>>,
+}
+
+/// Every CDP's bands over a run under soft liquidation.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SoftBook {
+    bands: Vec<Option<Bands>>, // Per CDP, once seen
+    pub(crate) outcome: SoftOutcome,
+}
+
+impl SoftBook {
+    /// Moves every CDP's collateral to where its bands say it should be at
+    /// this block's price, as far as the arbitrageurs' capital reaches.
+    pub(crate) fn trade(
+        &mut self,
+        llamma: Llamma,
+        market: BandMarket<'_>,
+        arbitrageurs: &mut Vec<usize>,
+        liquidated: &mut Vec<usize>,
+        inventory: &mut Vec<(usize, f64)>,
+    ) -> SoftRound {
+        let BandMarket {
+            config,
+            mechanism,
+            block,
+            price,
+            cdps,
+            index,
+            keepers,
+            active_keepers,
+            liquidatable_since,
+            ledger,
+            fee_burn,
+            borrower_loss,
+            liquidation_delay,
+            mut liquidation_log,
+        } = market;
+        let min_ratio = config.min_collateral_ratio;
+        self.bands.resize(cdps.len(), None);
+        // ETH each CDP's bands sell (positive) or buy back (negative)
+        let mut trades = Vec::new();
+        for (i, cdp) in cdps.iter().enumerate().filter(|(_, cdp)| !cdp.is_liquidated) {
+            let collateral = cdp.collateral_eth();
+            if collateral <= 0.0 {
+                continue;
+            }
+            let bands = *self.bands[i].get_or_insert(Bands {
+                top: cdp.debt * min_ratio / collateral,
+                deposit: collateral,
+                entered: false,
+                closed: false,
+            });
+            let converted = (bands.deposit - collateral).max(0.0);
+            let target = bands.deposit * llamma.converted_share(bands.top, price);
+            let eth = if target > converted {
+                // Stablecoin beyond the debt has nothing left to repay
+                (target - converted).min(cdp.debt / (price * (1.0 - llamma.spread))).min(collateral)
+            } else {
+                target - converted
+            };
+            // Trades too small to be worth a transaction wait until they add up
+            if eth.abs() * price > DUST_DEBT {
+                trades.push((i, eth));
+            }
+        }
+        let volume: f64 = trades.iter().map(|(_, eth)| eth.abs() * price).sum();
+        let mut round = SoftRound::default();
+        if volume == 0.0 {
+            return round;
+        }
+
+        // Arbitrageurs race for the trades, fastest first, each filling what its capital allows
+        let profit = volume * llamma.spread;
+        let shared = config.shared_liquidity.is_some();
+        arbitrageurs.clear();
+        arbitrageurs.extend((0..active_keepers).filter(|&i| {
+            let keeper = &keepers[i];
+            keeper.willing_to_liquidate(profit, mechanism, &config.keepers)
+                && keeper.can_execute(0)
+                && (!shared || keeper.free_capital > 0.0)
+        }));
+        arbitrageurs.sort_by(|&a, &b| keepers[b].gas_priority.total_cmp(&keepers[a].gas_priority));
+        round.attempts = 1;
+        round.joined = arbitrageurs.len();
+        let base_gas = config.keepers.gas.base_gas;
+        let mut filled = 0.0;
+        for &k in arbitrageurs.iter() {
+            if filled >= volume {
+                break;
+            }
+            let capacity = if shared { keepers[k].free_capital } else { f64::INFINITY };
+            let take = capacity.min(volume - filled);
+            let keeper = &mut keepers[k];
+            keeper.total_profit += take * llamma.spread;
+            keeper.gas_spent += base_gas;
+            *fee_burn += base_gas;
+            // Collateral bought out of the bands is dumped on the market
+            let sold = trades.iter().filter(|(_, eth)| *eth > 0.0).map(|(_, eth)| eth).sum::<f64>() * take / volume;
+            keeper.free_capital -= sold * price;
+            inventory.push((k, sold));
+            filled += take;
+        }
+        let fill = filled / volume;
+        self.outcome.unfilled += volume - filled;
+
+        for (i, eth) in trades {
+            let eth = eth * fill;
+            if eth == 0.0 {
+                continue;
+            }
+            // Selling pays down debt at the discount; buying back re-borrows at the premium
+            let debt = if eth > 0.0 { eth * price * (1.0 - llamma.spread) } else { eth * price * (1.0 + llamma.spread) };
+            let loss = eth.abs() * price * llamma.spread;
+            cdps[i].seize(eth, debt);
+            if cdps[i].debt.abs() < 1e-6 {
+                cdps[i].debt = 0.0; // Repaid in full, bar rounding
+            }
+            index.update(i, &cdps[i]);
+            // Conversions, not liquidations: the debt and spread have their own tallies
+            ledger.seized_collateral += eth;
+            ledger.debt_repaid += debt;
+            ledger.converted_debt += debt;
+            ledger.conversion_spread += loss;
+            *borrower_loss += loss;
+            self.outcome.trades += 1;
+            self.outcome.spread += loss;
+            round.eth_sold += eth;
+            if eth < 0.0 {
+                self.outcome.restored -= eth;
+                continue;
+            }
+            round.conversions += 1;
+            self.outcome.converted += eth;
+            let Some(bands) = self.bands[i].as_mut() else {
+                continue;
+            };
+            if !bands.entered {
+                bands.entered = true;
+                self.outcome.entered += 1;
+            }
+            // A CDP is liquidated once, when its debt is repaid or its collateral runs out
+            let cdp = &cdps[i];
+            if bands.closed || (cdp.debt > 0.0 && cdp.collateral_eth() * price > 1e-6) {
+                continue;
+            }
+            bands.closed = true;
+            self.outcome.closed += 1;
+            if let Some(&k) = arbitrageurs.first() {
+                keepers[k].liquidations += 1;
+            }
+            if let Some(log) = liquidation_log.as_deref_mut() {
+                log.push(LiquidationEvent {
+                    block,
+                    cdp_id: cdps[i].id,
+                    liquidator: arbitrageurs.first().map(|&k| keepers[k].id),
+                    profit: loss,
+                    eth_sold: eth,
+                    price,
+                });
+            }
+            *liquidation_delay += liquidatable_since[i].map_or(0, |since| block - since) as f64;
+            liquidated.push(i);
+        }
+        round
+    }
+}
+
+
+/// Band counts compared by default, from crvUSD's minimum of 4 up.
+pub const LLAMMA_BANDS: [usize; 3] = [4, 10, 30];
+
+#[derive(Debug, Clone)]
+pub struct SoftPoint {
+    pub mechanism: LiquidationMechanism,
+    pub avg_bad_debt: f64,
+    pub bad_debt_frequency: f64,
+    pub avg_borrower_loss: f64,
+    pub avg_price_drop_pct: f64,
+    pub avg_converted: f64, // ETH sold out of bands
+    pub avg_restored: f64,  // ETH bought back into bands
+}
+
+impl SoftPoint {
+    fn from_results(mechanism: LiquidationMechanism, results: &[CascadeResult]) -> Self {
+        let n = results.len().max(1) as f64;
+        let soft: Vec<_> = results.iter().filter_map(|r| r.soft).collect();
+        Self {
+            mechanism,
+            avg_bad_debt: results.iter().map(|r| r.bad_debt).sum::<f64>() / n,
+            bad_debt_frequency: results.iter().filter(|r| r.bad_debt > 0.0).count() as f64 / n,
+            avg_borrower_loss: results.iter().map(|r| r.borrower_loss).sum::<f64>() / n,
+            avg_price_drop_pct: results.iter().map(|r| r.price_drop_pct).sum::<f64>() / n,
+            avg_converted: soft.iter().map(|s| s.converted).sum::<f64>() / n,
+            avg_restored: soft.iter().map(|s| s.restored).sum::<f64>() / n,
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self.mechanism {
+            LiquidationMechanism::SoftLiquidation(llamma) => format!("LLAMMA, {} bands", llamma.bands),
+            mechanism => mechanism.info().short_name.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SoftReport {
+    pub scenario: PriceScenario,
+    pub runs: usize,
+    pub keeper_pool: SoftPoint,
+    pub points: Vec<SoftPoint>,
+}
+
+impl SoftReport {
+    pub fn print(&self) {
+        println!("| Mechanism        | Bad Debt   | P(Bad Debt) | Borrower Loss | Price Drop | Converted ETH | Restored ETH |");
+        println!("|------------------|------------|-------------|---------------|------------|---------------|--------------|");
+        for point in std::iter::once(&self.keeper_pool).chain(&self.points) {
+            let (converted, restored) = match point.mechanism {
+                LiquidationMechanism::SoftLiquidation(_) => {
+                    (format!("{:.1}", point.avg_converted), format!("{:.1}", point.avg_restored))
+                }
+                _ => ("-".to_string(), "-".to_string()),
+            };
+            println!(
+                "| {:16} | ${:9.0} | {:10.1}% | ${:12.0} | {:9.1}% | {:>13} | {:>12} |",
+                point.label(),
+                point.avg_bad_debt,
+                point.bad_debt_frequency * 100.0,
+                point.avg_borrower_loss,
+                point.avg_price_drop_pct,
+                converted,
+                restored,
+            );
+        }
+        let pool = &self.keeper_pool;
+        for point in &self.points {
+            if point.avg_bad_debt > pool.avg_bad_debt {
+                println!(
+                    "  {} leaves ${:.0} more bad debt than the pool",
+                    point.label(),
+                    point.avg_bad_debt - pool.avg_bad_debt,
+                );
+            }
+        }
+        if let Some(cheapest) = self.points.iter().min_by(|a, b| a.avg_borrower_loss.total_cmp(&b.avg_borrower_loss)) {
+            if pool.avg_borrower_loss > 0.0 {
+                println!(
+                    "  Borrowers lose {:.1}% of what the pool takes under {}",
+                    cheapest.avg_borrower_loss / pool.avg_borrower_loss * 100.0,
+                    cheapest.label(),
+                );
+            }
+        }
+    }
+}
+
+/// Runs the default keeper pool and LLAMMA at each band count in `bands`,
+/// the rest of `llamma` held fixed.
+pub fn compare_soft_liquidation(
+    llamma: Llamma,
+    scenario: PriceScenario,
+    bands: &[usize],
+    runs: usize,
+    config: &SimulationConfig,
+) -> Result<SoftReport> {
+    if bands.is_empty() {
+        return Err(Error::Invalid("soft liquidation comparison needs at least one band count".to_string()));
+    }
+    config.validate()?;
+    let run = |mechanism: LiquidationMechanism| -> Result<SoftPoint> {
+        mechanism.validate()?;
+        Ok(SoftPoint::from_results(mechanism, &run_cascade_simulation(mechanism, scenario, runs, config)))
+    };
+    let keeper_pool = run(LiquidationMechanism::keeper_pool())?;
+    let points = bands
+        .iter()
+        .map(|&bands| run(LiquidationMechanism::SoftLiquidation(Llamma { bands, ..llamma })))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(SoftReport { scenario, runs, keeper_pool, points })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bands_spare_borrowers_in_a_gradual_decline() {
+        let config = SimulationConfig { seed: Some(3), ..Default::default() };
+        let report =
+            compare_soft_liquidation(Llamma::default(), PriceScenario::GradualDecline, &[10], 10, &config).unwrap();
+
+        let (pool, soft) = (&report.keeper_pool, &report.points[0]);
+        assert!(soft.avg_converted > 0.0);
+        assert!(soft.avg_borrower_loss < pool.avg_borrower_loss);
+        // Only what repays the debt is sold, so the market falls less
+        assert!(soft.avg_price_drop_pct < pool.avg_price_drop_pct);
+    }
+
+    #[test]
+    fn test_gaps_below_the_bands_leave_bad_debt() {
+        let config = SimulationConfig { seed: Some(3), ..Default::default() };
+        let report = compare_soft_liquidation(Llamma::default(), PriceScenario::BlackSwan, &[4, 30], 10, &config).unwrap();
+
+        assert!(report.points.iter().all(|point| point.avg_bad_debt > 0.0));
+        assert!(compare_soft_liquidation(Llamma::default(), PriceScenario::BlackSwan, &[0], 1, &config).is_err());
+        assert!(compare_soft_liquidation(Llamma::default(), PriceScenario::BlackSwan, &[], 1, &config).is_err());
+    }
+
+    #[test]
+    fn test_a_cdp_crossing_its_bands_is_liquidated_once() {
+        let config = SimulationConfig { seed: Some(3), ..Default::default() };
+        let (results, logs) = crate::cascade::run_cascade_simulation_with_liquidations(
+            LiquidationMechanism::soft_liquidation(),
+            PriceScenario::VolatileCrash,
+            3,
+            &config,
+        );
+        for (result, log) in results.iter().zip(&logs) {
+            let soft = result.soft.expect("a soft liquidation run");
+            // Many band trades, but each CDP's liquidation is counted once
+            assert!(soft.trades > soft.closed);
+            let mut ids: Vec<_> = log.iter().map(|event| event.cdp_id).collect();
+            ids.sort_unstable();
+            ids.dedup();
+            assert_eq!(ids.len(), log.len());
+            assert_eq!(result.total_liquidations, log.len());
+            assert_eq!(soft.closed, log.len());
+            assert_eq!(result.ledger.liquidated_debt, 0.0);
+            assert!(result.ledger.converted_debt > 0.0);
+            assert!(crate::audit::ConservationReport::of(result).is_consistent());
+        }
+    }
+}
@@ -0,0 +1,123 @@
+//! Run Manifests
+//!
+//! A JSON record of exactly what produced a set of results: crate version,
+//! the git commit the binary was built from, the command line, the seed,
+//! and every config parameter after file, environment and flag layering.
+//! Any published chart can be traced back to the invocation that replays it.
+//!
+//! ## Layout
+//! Manifests sit next to the results they describe, named after them:
+//! ```text
+//! before.json            # Monte Carlo result set (`--output`)
+//! before.manifest.json   # its manifest
+//! ```
+//!
+//! ## Git state
+//! The commit is embedded at build time (see `build.rs`); `git_dirty` is
+//! set when the build had uncommitted changes under `simulation/`, in which
+//! case the commit alone does not pin the code.
+
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::SimulationSetup;
+use crate::error::Result;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunManifest {
+    pub crate_version: String,
+    pub git_hash: Option<String>, // None when built outside a git checkout
+    pub git_dirty: bool,
+    pub created_at: u64,      // Unix seconds
+    pub command: Vec<String>, // argv, program name first
+    pub seed: Option<u64>,
+    pub setup: SimulationSetup, // Fully resolved
+}
+
+impl RunManifest {
+    /// Records `setup` as resolved for the run started by `command`.
+    pub fn capture(setup: &SimulationSetup, command: Vec<String>) -> Self {
+        let created_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        Self {
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            git_hash: option_env!("FAIR_SIM_GIT_HASH").map(str::to_string),
+            git_dirty: option_env!("FAIR_SIM_GIT_DIRTY") == Some("true"),
+            created_at,
+            command,
+            seed: setup.simulation.seed,
+            setup: setup.clone(),
+        }
+    }
+
+    /// Whether the manifest pins the run: a seed and a clean, known commit.
+    pub fn is_reproducible(&self) -> bool {
+        self.seed.is_some() && self.git_hash.is_some() && !self.git_dirty
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+    }
+}
+
+/// The manifest path for a results file: `before.json` becomes
+/// `before.manifest.json`.
+pub fn sidecar_path(results: impl AsRef<Path>) -> PathBuf {
+    let results = results.as_ref();
+    let stem = results.file_stem().unwrap_or_default().to_string_lossy();
+    results.with_file_name(format!("{}.manifest.json", stem))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cascade::{LiquidationMechanism, SimulationConfig};
+
+    #[test]
+    fn test_capture_records_setup() {
+        let setup = SimulationSetup {
+            mechanisms: vec![LiquidationMechanism::KeeperPool { split: 0.8 }],
+            runs: Some(50),
+            simulation: SimulationConfig { seed: Some(7), severity: 1.5, ..Default::default() },
+            ..Default::default()
+        };
+        let manifest = RunManifest::capture(&setup, vec!["fair-sim".to_string(), "cascade".to_string()]);
+
+        assert_eq!(manifest.crate_version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(manifest.seed, Some(7));
+        assert_eq!(manifest.setup.runs, Some(50));
+        assert_eq!(manifest.setup.simulation.severity, 1.5);
+        assert_eq!(manifest.is_reproducible(), manifest.git_hash.is_some() && !manifest.git_dirty);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let setup = SimulationSetup { simulation: SimulationConfig { seed: Some(3), ..Default::default() }, ..Default::default() };
+        let manifest = RunManifest::capture(&setup, vec!["fair-sim".to_string()]);
+        let path = std::env::temp_dir().join(format!("fair-sim-manifest-{}.json", std::process::id()));
+
+        manifest.save(&path).unwrap();
+        let loaded = RunManifest::load(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(loaded.git_hash, manifest.git_hash);
+        assert_eq!(loaded.command, manifest.command);
+        assert_eq!(loaded.setup.simulation.num_cdps, setup.simulation.num_cdps);
+        assert!(!RunManifest { seed: None, ..loaded }.is_reproducible());
+    }
+
+    #[test]
+    fn test_sidecar_sits_next_to_results() {
+        assert_eq!(sidecar_path("runs/before.json"), PathBuf::from("runs/before.manifest.json"));
+        assert_eq!(sidecar_path("sweep.csv"), PathBuf::from("sweep.manifest.json"));
+    }
+}
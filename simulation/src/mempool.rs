@@ -0,0 +1,220 @@
+//! Mempool Visibility
+//!
+//! How information asymmetry between keepers shapes coverage and profit
+//! concentration. Keepers watching the public mempool see pending oracle
+//! updates and act on a CDP the block it turns liquidatable; the rest
+//! react to confirmed state later (see `cascade::MempoolVisibility`).
+//! Under Traditional races the first keeper to act takes the whole
+//! penalty, so a small watcher set can capture it; the keeper pool shares
+//! each penalty across everyone who committed in time.
+//!
+//! ## Method
+//! - Sweeps the watcher share from 0 (private mempool) to 1 (fully public)
+//!   at a fixed lead; every share sees the same seeds when `config.seed` is
+//!   set, and visibility draws nothing from the RNG
+//! - Coverage is the share of liquidated CDPs among those liquidated or
+//!   left underwater at the end of a run; weighted coverage also counts
+//!   how far below the minimum ratio each waiting CDP sat, and for how long
+
+use crate::cascade::{
+    run_cascade_simulation, CascadeResult, LiquidationMechanism, MempoolVisibility, PriceScenario, SimulationConfig,
+};
+use crate::error::Result;
+
+/// Watcher shares swept by default, private to fully public.
+pub const VISIBILITY_SWEEP: [f64; 6] = [0.0, 0.1, 0.25, 0.5, 0.75, 1.0];
+
+#[derive(Debug, Clone)]
+pub struct VisibilityPoint {
+    pub visible_share: f64,
+    pub avg_coverage: f64,
+    pub avg_weighted_coverage: f64, // Exposure-weighted; see `CascadeResult::weighted_coverage`
+    pub avg_liquidations: f64,
+    pub avg_liquidation_delay: f64,
+    pub avg_bad_debt: f64,
+    pub avg_profit_concentration: f64,
+    pub avg_participation_rate: f64,
+}
+
+impl VisibilityPoint {
+    fn from_results(visible_share: f64, results: &[CascadeResult]) -> Self {
+        let n = results.len().max(1) as f64;
+        let mean = |f: fn(&CascadeResult) -> f64| results.iter().map(f).sum::<f64>() / n;
+        Self {
+            visible_share,
+            avg_coverage: mean(coverage),
+            avg_weighted_coverage: mean(CascadeResult::weighted_coverage),
+            avg_liquidations: mean(|r| r.total_liquidations as f64),
+            avg_liquidation_delay: mean(|r| r.avg_liquidation_delay),
+            avg_bad_debt: mean(|r| r.bad_debt),
+            avg_profit_concentration: mean(|r| r.profit_concentration),
+            avg_participation_rate: mean(|r| r.participation_rate),
+        }
+    }
+}
+
+/// Liquidated CDPs over those liquidated or left underwater; 1 when neither.
+pub(crate) fn coverage(result: &CascadeResult) -> f64 {
+    let reached = result.total_liquidations + result.unliquidated_underwater;
+    if reached == 0 {
+        1.0
+    } else {
+        result.total_liquidations as f64 / reached as f64
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct VisibilityReport {
+    pub mechanism: LiquidationMechanism,
+    pub scenario: PriceScenario,
+    pub runs: usize,
+    pub lead_blocks: usize,
+    pub points: Vec<VisibilityPoint>, // In sweep order
+}
+
+impl VisibilityReport {
+    /// Widest gap in profit concentration across the sweep.
+    pub fn concentration_spread(&self) -> f64 {
+        let values = self.points.iter().map(|p| p.avg_profit_concentration);
+        let max = values.clone().fold(f64::NEG_INFINITY, f64::max);
+        let min = values.fold(f64::INFINITY, f64::min);
+        if self.points.is_empty() {
+            0.0
+        } else {
+            max - min
+        }
+    }
+
+    /// The watcher share with the most concentrated profits.
+    pub fn most_concentrated(&self) -> Option<&VisibilityPoint> {
+        self.points.iter().max_by(|a, b| a.avg_profit_concentration.total_cmp(&b.avg_profit_concentration))
+    }
+
+    pub fn print(&self) {
+        println!("| Visible | Coverage | Weighted | Liquidations | Delay (blocks) | Bad Debt | Concentration | Participation |");
+        println!("|---------|----------|----------|--------------|----------------|----------|---------------|---------------|");
+        for point in &self.points {
+            println!(
+                "| {:6.0}% | {:7.1}% | {:7.1}% | {:12.1} | {:14.2} | ${:7.0} | {:12.1}% | {:12.1}% |",
+                point.visible_share * 100.0,
+                point.avg_coverage * 100.0,
+                point.avg_weighted_coverage * 100.0,
+                point.avg_liquidations,
+                point.avg_liquidation_delay,
+                point.avg_bad_debt,
+                point.avg_profit_concentration * 100.0,
+                point.avg_participation_rate * 100.0,
+            );
+        }
+        if let Some(peak) = self.most_concentrated() {
+            println!(
+                "  Concentration peaks at {:.0}% visibility ({:.1}%); spread {:.1} pts",
+                peak.visible_share * 100.0,
+                peak.avg_profit_concentration * 100.0,
+                self.concentration_spread() * 100.0
+            );
+        }
+    }
+}
+
+/// Runs `mechanism` with each watcher share in `shares`, the lead taken
+/// from `config.mempool` (or its defaults).
+pub fn sweep_visibility(
+    mechanism: LiquidationMechanism,
+    scenario: PriceScenario,
+    shares: &[f64],
+    runs: usize,
+    config: &SimulationConfig,
+) -> Result<VisibilityReport> {
+    mechanism.validate()?;
+    config.validate()?;
+    let lead_blocks = config.mempool.clone().unwrap_or_default().lead_blocks;
+
+    let mut points = Vec::with_capacity(shares.len());
+    for &visible_share in shares {
+        let visible = SimulationConfig {
+            mempool: Some(MempoolVisibility { visible_share, lead_blocks }),
+            ..config.clone()
+        };
+        visible.validate()?;
+        points.push(VisibilityPoint::from_results(
+            visible_share,
+            &run_cascade_simulation(mechanism, scenario, runs, &visible),
+        ));
+    }
+
+    Ok(VisibilityReport { mechanism, scenario, runs, lead_blocks, points })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cascade::{CascadeSimulationBuilder, Keeper, CDP};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_public_mempool_matches_no_visibility_model() {
+        let plain = SimulationConfig { seed: Some(4), ..Default::default() };
+        let public = SimulationConfig {
+            mempool: Some(MempoolVisibility { visible_share: 1.0, lead_blocks: 3 }),
+            ..plain.clone()
+        };
+        for mechanism in LiquidationMechanism::all() {
+            let a = run_cascade_simulation(mechanism, PriceScenario::VolatileCrash, 3, &plain);
+            let b = run_cascade_simulation(mechanism, PriceScenario::VolatileCrash, 3, &public);
+            for (a, b) in a.iter().zip(&b) {
+                assert_eq!(a.total_liquidations, b.total_liquidations);
+                assert_eq!(a.bad_debt, b.bad_debt);
+                assert_eq!(a.avg_liquidation_delay, b.avg_liquidation_delay);
+            }
+        }
+    }
+
+    #[test]
+    fn test_private_mempool_delays_every_keeper() {
+        let run = |visible_share: f64| {
+            let config = SimulationConfig {
+                mempool: Some(MempoolVisibility { visible_share, lead_blocks: 2 }),
+                max_blocks: 5,
+                ..Default::default()
+            };
+            let mut rng = StdRng::seed_from_u64(0);
+            CascadeSimulationBuilder::new()
+                .config(config)
+                .cdps(vec![CDP::from_position(0, 5.0, 8_000.0)])
+                .keepers(vec![Keeper::new(0, 50_000.0, 0.5), Keeper::new(1, 50_000.0, 0.9)])
+                .price_path(vec![2000.0; 6])
+                .build(&mut rng)
+                .unwrap()
+                .run(&mut rng)
+        };
+        let private = run(0.0);
+        let half = run(0.5);
+
+        assert_eq!(private.total_liquidations, 1);
+        assert_eq!(private.avg_liquidation_delay, 2.0);
+        assert_eq!(half.avg_liquidation_delay, 0.0);
+    }
+
+    #[test]
+    fn test_sweep_traces_visibility_curve() {
+        let config = SimulationConfig { seed: Some(3), ..Default::default() };
+        let report = sweep_visibility(
+            LiquidationMechanism::keeper_pool(),
+            PriceScenario::FlashCrash,
+            &VISIBILITY_SWEEP,
+            6,
+            &config,
+        )
+        .unwrap();
+        let (private, few, public) = (&report.points[0], &report.points[1], &report.points[5]);
+
+        assert_eq!(report.points.len(), VISIBILITY_SWEEP.len());
+        assert_eq!(report.lead_blocks, 1);
+        assert!(private.avg_liquidation_delay > public.avg_liquidation_delay);
+        // A few watchers split the fresh CDPs' penalties among themselves
+        assert!(few.avg_profit_concentration > public.avg_profit_concentration);
+        assert!(report.concentration_spread() > 0.0);
+    }
+}
@@ -0,0 +1,211 @@
+//! Scenario Narrative Generator
+//!
+//! Converts a single run's block event log into a human-readable timeline,
+//! used in reports to explain mechanism behavior to non-quant stakeholders:
+//!
+//! ```text
+//! block 12: price -8.0%, 37 CDPs liquidatable, 10 cleared, queue growing (27)
+//! ```
+//!
+//! Quiet stretches (no liquidation pressure, small price moves) are collapsed
+//! into a single line so the timeline stays readable.
+
+use crate::cascade::{BlockEvent, CascadeResult};
+
+const NOTABLE_MOVE: f64 = 0.01; // 1% block move is always worth mentioning
+
+fn pct_change(from: f64, to: f64) -> f64 {
+    if from == 0.0 {
+        return 0.0;
+    }
+    (to / from - 1.0) * 100.0
+}
+
+fn is_notable(event: &BlockEvent) -> bool {
+    event.liquidatable > 0
+        || (event.price_end / event.price_start - 1.0).abs() >= NOTABLE_MOVE
+}
+
+fn queue_trend(previous: usize, current: usize) -> &'static str {
+    if current == 0 {
+        "queue cleared"
+    } else if current > previous {
+        "queue growing"
+    } else if current < previous {
+        "queue shrinking"
+    } else {
+        "queue steady"
+    }
+}
+
+fn describe_block(event: &BlockEvent, previous_queue: usize) -> String {
+    let mut line = format!(
+        "block {}: price {:+.1}%",
+        event.block,
+        pct_change(event.price_start, event.price_end)
+    );
+
+    let impact = pct_change(event.price_after_shock, event.price_end);
+    if impact.abs() >= 0.05 {
+        line.push_str(&format!(" ({:+.1}% from liquidation sales)", impact));
+    }
+
+    if event.liquidatable > 0 {
+        let queue = event.queue_after();
+        line.push_str(&format!(
+            ", {} CDPs liquidatable, {} cleared, {}",
+            event.liquidatable,
+            event.liquidated,
+            queue_trend(previous_queue, queue)
+        ));
+        if queue > 0 {
+            line.push_str(&format!(" ({})", queue));
+        }
+    } else {
+        line.push_str(", no CDPs liquidatable");
+    }
+
+    if event.bad_debt > 0.0 {
+        line.push_str(&format!(", bad debt ${:.0}", event.bad_debt));
+    }
+
+    line
+}
+
+fn describe_quiet(events: &[BlockEvent]) -> String {
+    let first = &events[0];
+    let last = &events[events.len() - 1];
+    let range = if events.len() == 1 {
+        format!("block {}", first.block)
+    } else {
+        format!("blocks {}-{}", first.block, last.block)
+    };
+    format!(
+        "{}: quiet, price {:+.1}% overall",
+        range,
+        pct_change(first.price_start, last.price_end)
+    )
+}
+
+/// Builds the block-by-block timeline for one run.
+pub fn narrate(events: &[BlockEvent]) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut quiet_start: Option<usize> = None;
+    let mut previous_queue = 0;
+
+    for (i, event) in events.iter().enumerate() {
+        if is_notable(event) {
+            if let Some(start) = quiet_start.take() {
+                lines.push(describe_quiet(&events[start..i]));
+            }
+            lines.push(describe_block(event, previous_queue));
+        } else if quiet_start.is_none() {
+            quiet_start = Some(i);
+        }
+        previous_queue = event.queue_after();
+    }
+
+    if let Some(start) = quiet_start {
+        lines.push(describe_quiet(&events[start..]));
+    }
+
+    lines
+}
+
+/// One-paragraph summary to accompany the timeline.
+pub fn summarize(result: &CascadeResult, events: &[BlockEvent]) -> String {
+    let peak_queue = events.iter().map(|e| e.queue_after()).max().unwrap_or(0);
+    let peak_block = events
+        .iter()
+        .max_by_key(|e| e.queue_after())
+        .map(|e| e.block)
+        .unwrap_or(0);
+
+    let mut summary = format!(
+        "Under {}, the {} scenario moved ETH {:.1}% lower over {} blocks. \
+         Keepers cleared {} CDPs across {} liquidation waves.",
+        result.mechanism.name(),
+        result.scenario.name(),
+        result.price_drop_pct,
+        result.blocks_to_stability,
+        result.total_liquidations,
+        result.cascade_depth,
+    );
+
+    if peak_queue > 0 {
+        summary.push_str(&format!(
+            " The backlog peaked at {} CDPs in block {}.",
+            peak_queue, peak_block
+        ));
+    }
+
+    if result.bad_debt > 0.0 {
+        summary.push_str(&format!(
+            " {} underwater CDPs were left unliquidated, leaving ${:.0} of bad debt.",
+            result.unliquidated_underwater, result.bad_debt
+        ));
+    } else {
+        summary.push_str(" No bad debt was left behind.");
+    }
+
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cascade::{run_single_with_events, LiquidationMechanism, PriceScenario, SimulationConfig};
+
+    fn event(block: usize, start: f64, end: f64, liquidatable: usize, liquidated: usize) -> BlockEvent {
+        BlockEvent {
+            block,
+            price_start: start,
+            price_after_shock: end,
+            price_end: end,
+            liquidatable,
+            liquidated,
+            eth_sold: 0.0,
+            bad_debt: 0.0,
+            penalties: 0.0,
+            protocol_fees: 0.0,
+            max_payout_share: 0.0,
+            neighbor_eth_sold: 0.0,
+            token_price: None,
+            insurance_fund: None,
+        }
+    }
+
+    #[test]
+    fn test_narrate_collapses_quiet_blocks() {
+        let events = vec![
+            event(0, 2000.0, 1840.0, 37, 10),
+            event(1, 1840.0, 1830.0, 40, 10),
+            event(2, 1830.0, 1830.0, 0, 0),
+            event(3, 1830.0, 1831.0, 0, 0),
+        ];
+        let lines = narrate(&events);
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[0],
+            "block 0: price -8.0%, 37 CDPs liquidatable, 10 cleared, queue growing (27)"
+        );
+        assert!(lines[1].contains("queue growing (30)"));
+        assert!(lines[2].starts_with("blocks 2-3: quiet"));
+    }
+
+    #[test]
+    fn test_narrate_simulated_run() {
+        let mut rng = rand::thread_rng();
+        let (result, events) = run_single_with_events(
+            LiquidationMechanism::keeper_pool(),
+            PriceScenario::FlashCrash,
+            &SimulationConfig::default(),
+            &mut rng,
+        );
+
+        assert!(!events.is_empty());
+        assert!(narrate(&events)[0].starts_with("block 0: price -"));
+        assert!(summarize(&result, &events).contains("Flash Crash"));
+    }
+}
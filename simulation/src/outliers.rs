@@ -0,0 +1,80 @@
+//! Outlier Runs
+//!
+//! Picks the runs of a campaign with the most bad debt, each with the
+//! per-run seed that replays it on its own, so a weird tail result can be
+//! re-run and narrated instead of reconstructed.
+//!
+//! ## Reproducing
+//! `fair-sim narrative --run-seed <seed>` with the campaign's `--config`,
+//! mechanism, and scenario replays the run block by block. Campaign
+//! subcommands print the full command next to each outlier.
+
+use crate::cascade::CascadeResult;
+use crate::monte_carlo::MonteCarloResult;
+
+pub const DEFAULT_OUTLIERS: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Outlier {
+    pub run: usize, // Index of the run within its campaign
+    pub seed: u64,
+    pub bad_debt: f64,
+}
+
+/// Up to `count` runs with bad debt, worst first.
+pub fn worst_runs(results: &[CascadeResult], count: usize) -> Vec<Outlier> {
+    rank(results.iter().map(|r| (r.seed, r.bad_debt)), count)
+}
+
+/// Up to `count` Monte Carlo runs with bad debt, worst first. Empty for
+/// result sets saved before per-run seeds were recorded.
+pub fn worst_monte_carlo_runs(result: &MonteCarloResult, count: usize) -> Vec<Outlier> {
+    if result.run_seeds.len() != result.bad_debts.len() {
+        return Vec::new();
+    }
+    rank(result.run_seeds.iter().map(|&s| Some(s)).zip(result.bad_debts.iter().copied()), count)
+}
+
+fn rank(runs: impl Iterator<Item = (Option<u64>, f64)>, count: usize) -> Vec<Outlier> {
+    let mut outliers: Vec<Outlier> = runs
+        .enumerate()
+        .filter_map(|(run, (seed, bad_debt))| Some(Outlier { run, seed: seed?, bad_debt }))
+        .filter(|o| o.bad_debt > 0.0)
+        .collect();
+    outliers.sort_by(|a, b| b.bad_debt.partial_cmp(&a.bad_debt).unwrap().then(a.run.cmp(&b.run)));
+    outliers.truncate(count);
+    outliers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cascade::{replay_with_events, run_cascade_simulation, LiquidationMechanism, PriceScenario, SimulationConfig};
+    use crate::monte_carlo::{run_monte_carlo_with_config, PriceModel};
+
+    #[test]
+    fn test_worst_runs_replay_exactly() {
+        let config = SimulationConfig { seed: Some(5), ..Default::default() };
+        let mechanism = LiquidationMechanism::keeper_pool();
+        let results = run_cascade_simulation(mechanism, PriceScenario::BlackSwan, 10, &config);
+        let outliers = worst_runs(&results, 2);
+
+        assert_eq!(outliers.len(), 2);
+        assert!(outliers[0].bad_debt >= outliers[1].bad_debt);
+        let max = results.iter().map(|r| r.bad_debt).fold(0.0, f64::max);
+        assert_eq!(outliers[0].bad_debt, max);
+
+        let (replayed, _) = replay_with_events(mechanism, PriceScenario::BlackSwan, &config, outliers[0].seed);
+        assert_eq!(replayed.bad_debt, outliers[0].bad_debt);
+    }
+
+    #[test]
+    fn test_monte_carlo_outliers_need_seeds() {
+        let config = SimulationConfig { seed: Some(9), ..Default::default() };
+        let mut result = run_monte_carlo_with_config(PriceModel::HistoricalMar2020, LiquidationMechanism::Traditional, 10, &config);
+
+        assert!(worst_monte_carlo_runs(&result, 3).iter().all(|o| o.seed == result.run_seeds[o.run]));
+        result.run_seeds.clear(); // As loaded from an older result file
+        assert!(worst_monte_carlo_runs(&result, 3).is_empty());
+    }
+}
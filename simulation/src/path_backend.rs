@@ -0,0 +1,294 @@
+//! Path Backends
+//!
+//! Where Monte Carlo price paths come from when the cascade engine is
+//! driven by a price model's paths rather than a scenario's shocks.
+//! `PathBackend` generates paths a chunk at a time: `CpuBackend` wraps
+//! `generate_price_paths`, and `GpuBackend` (`gpu` feature) generates GBM
+//! and jump-diffusion paths in a wgpu compute shader, for 10⁷+ path
+//! experiments in which path generation dominates.
+//!
+//! ## Method
+//! - `run_on_paths` generates the next chunk of `PATH_CHUNK` paths on its
+//!   own thread while the current chunk's cascades run across the rayon
+//!   pool, and folds runs into a `TailSummary`, so memory is bounded by two
+//!   chunks however many paths there are
+//! - Each run follows one path as a `ScriptedPath`, rescaled to open at
+//!   `initial_eth_price`, with liquidation impact on top; the price holds
+//!   once the path runs out
+//! - Chunk and run seeds are drawn from `config.rng()`, so a seeded campaign
+//!   is reproducible on the same backend; the backends draw different
+//!   paths from a seed and agree in distribution
+//!
+//! ## Corpora
+//! A `PathCorpus` holds a campaign's paths and run seeds, generated once
+//! and saved as JSON, for `replay_corpus` to run against any mechanism and
+//! configuration: every replay sees the same paths and the same books and
+//! keepers per path, so differences between replays are the mechanism's or
+//! the parameters', not sampling noise. A corpus generated on a seed holds
+//! exactly the runs `run_on_paths` draws on that seed.
+
+use std::path::Path;
+use std::sync::mpsc;
+use std::thread;
+
+use rand::prelude::*;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::cascade::{campaign_span, CascadeResult, CascadeSimulationBuilder, LiquidationMechanism, SimulationConfig};
+use crate::error::{Error, Result};
+use crate::monte_carlo::{generate_price_paths, scenario_for_model, MonteCarloResult, PricePathConfig, TailSummary};
+use crate::results::{load_json, save_json};
+
+#[cfg(feature = "gpu")]
+mod gpu;
+#[cfg(feature = "gpu")]
+pub use gpu::GpuBackend;
+
+/// Paths generated, and cascades run, at a time.
+pub const PATH_CHUNK: usize = 65_536;
+
+/// A source of price paths.
+pub trait PathBackend: Send {
+    fn name(&self) -> String;
+
+    /// `paths` paths of `config.blocks + 1` prices each, opening at the
+    /// price `generate_price_path` opens at, drawn from `seed`.
+    fn generate(&mut self, config: &PricePathConfig, paths: usize, seed: u64) -> Result<Vec<Vec<f64>>>;
+}
+
+/// Paths from `generate_price_paths`, for every price model.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CpuBackend;
+
+impl PathBackend for CpuBackend {
+    fn name(&self) -> String {
+        "CPU".to_string()
+    }
+
+    fn generate(&mut self, config: &PricePathConfig, paths: usize, seed: u64) -> Result<Vec<Vec<f64>>> {
+        Ok(generate_price_paths(config, paths, &mut StdRng::seed_from_u64(seed)))
+    }
+}
+
+/// A campaign's price paths and run seeds, kept to replay.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PathCorpus {
+    pub path_config: PricePathConfig,
+    pub backend: String,    // `PathBackend::name` of the generator
+    pub seed: Option<u64>,  // `config.seed` it was generated on
+    pub paths: Vec<Vec<f64>>,
+    pub run_seeds: Vec<u64>, // Per path: the seed its run draws its book and keepers from
+}
+
+impl PathCorpus {
+    /// `runs` paths from `backend`, with their run seeds, drawn as
+    /// `run_on_paths` draws them from `config.rng()`.
+    pub fn generate(
+        backend: &mut dyn PathBackend,
+        path_config: &PricePathConfig,
+        runs: usize,
+        config: &SimulationConfig,
+    ) -> Result<Self> {
+        check_paths(path_config)?;
+        let mut rng = config.rng();
+        let mut corpus = Self {
+            path_config: path_config.clone(),
+            backend: backend.name(),
+            seed: config.seed,
+            paths: Vec::with_capacity(runs),
+            run_seeds: Vec::with_capacity(runs),
+        };
+        while corpus.paths.len() < runs {
+            let (paths, seeds) = draw_chunk(backend, path_config, PATH_CHUNK.min(runs - corpus.paths.len()), &mut rng)?;
+            corpus.paths.extend(paths);
+            corpus.run_seeds.extend(seeds);
+        }
+        Ok(corpus)
+    }
+
+    pub fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        save_json(path, self)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let corpus: Self = load_json(path)?;
+        if corpus.paths.len() != corpus.run_seeds.len() {
+            return Err(Error::Invalid("corpus has a different number of paths and run seeds".to_string()));
+        }
+        if !corpus.paths.iter().all(|path| path.first().is_some_and(|&price| price > 0.0)) {
+            return Err(Error::Invalid("corpus paths must open at a positive price".to_string()));
+        }
+        Ok(corpus)
+    }
+}
+
+fn check_paths(path_config: &PricePathConfig) -> Result<()> {
+    if path_config.blocks == 0 {
+        return Err(Error::Invalid("price paths need at least one block".to_string()));
+    }
+    Ok(())
+}
+
+/// The next `paths` paths and their run seeds.
+fn draw_chunk(
+    backend: &mut dyn PathBackend,
+    path_config: &PricePathConfig,
+    paths: usize,
+    rng: &mut StdRng,
+) -> Result<(Vec<Vec<f64>>, Vec<u64>)> {
+    let chunk_seed = rng.gen();
+    let seeds = (0..paths).map(|_| rng.gen()).collect();
+    Ok((backend.generate(path_config, paths, chunk_seed)?, seeds))
+}
+
+/// One cascade of `mechanism` per path, on its run seed, in parallel.
+fn run_chunk(
+    paths: &[Vec<f64>],
+    seeds: &[u64],
+    mechanism: LiquidationMechanism,
+    config: &SimulationConfig,
+) -> Result<Vec<CascadeResult>> {
+    paths
+        .par_iter()
+        .zip(seeds)
+        .map(|(path, &seed)| {
+            let scale = config.initial_eth_price / path[0];
+            let mut rng = config.run_rng(seed);
+            let mut sim = CascadeSimulationBuilder::new()
+                .config(config.clone())
+                .mechanism(mechanism)
+                .price_path(path.iter().map(|p| p * scale).collect())
+                .build(&mut rng)?;
+            Ok(sim.run(&mut rng))
+        })
+        .collect()
+}
+
+/// `runs` cascades of `mechanism`, each driven by one of `backend`'s
+/// `path_config` paths instead of a scenario, summarized as by
+/// `run_monte_carlo_streaming`.
+pub fn run_on_paths(
+    backend: &mut dyn PathBackend,
+    path_config: &PricePathConfig,
+    mechanism: LiquidationMechanism,
+    runs: usize,
+    config: &SimulationConfig,
+) -> Result<MonteCarloResult> {
+    config.validate()?;
+    mechanism.validate()?;
+    check_paths(path_config)?;
+    let _campaign = campaign_span(mechanism, scenario_for_model(path_config.model), runs).entered();
+    let mut summary = TailSummary::default();
+
+    thread::scope(|scope| -> Result<()> {
+        // One chunk waits, held in `send`, while the previous one runs
+        let (sender, chunks) = mpsc::sync_channel(0);
+        let generator = &mut *backend;
+        scope.spawn(move || {
+            let mut rng = config.rng();
+            let mut first = 0;
+            while first < runs {
+                let paths = PATH_CHUNK.min(runs - first);
+                let chunk = draw_chunk(generator, path_config, paths, &mut rng);
+                let failed = chunk.is_err();
+                if sender.send(chunk).is_err() || failed {
+                    return;
+                }
+                first += paths;
+            }
+        });
+
+        for chunk in chunks {
+            let (paths, seeds) = chunk?;
+            for result in &run_chunk(&paths, &seeds, mechanism, config)? {
+                summary.add(result);
+            }
+        }
+        Ok(())
+    })?;
+
+    let result = summary.finish(path_config.model, mechanism);
+    info!(backend = backend.name(), var_99 = result.var_99, cvar_99 = result.cvar_99, "tail risk (estimated)");
+    Ok(result)
+}
+
+/// Every run of `corpus` under `mechanism` and `config`, summarized as by
+/// `run_on_paths`; `config.seed` is ignored, the corpus fixing each run's.
+pub fn replay_corpus(
+    corpus: &PathCorpus,
+    mechanism: LiquidationMechanism,
+    config: &SimulationConfig,
+) -> Result<MonteCarloResult> {
+    config.validate()?;
+    mechanism.validate()?;
+    let _campaign = campaign_span(mechanism, scenario_for_model(corpus.path_config.model), corpus.len()).entered();
+    let mut summary = TailSummary::default();
+    for (paths, seeds) in corpus.paths.chunks(PATH_CHUNK).zip(corpus.run_seeds.chunks(PATH_CHUNK)) {
+        for result in &run_chunk(paths, seeds, mechanism, config)? {
+            summary.add(result);
+        }
+    }
+    Ok(summary.finish(corpus.path_config.model, mechanism))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monte_carlo::PriceModel;
+
+    #[test]
+    fn test_every_run_follows_a_path() {
+        let config = SimulationConfig { seed: Some(4), ..Default::default() };
+        let path_config = PricePathConfig { model: PriceModel::JumpDiffusion, ..Default::default() };
+        let mechanism = LiquidationMechanism::keeper_pool();
+        let run = || run_on_paths(&mut CpuBackend, &path_config, mechanism, 40, &config).unwrap();
+        let (a, b) = (run(), run());
+
+        assert_eq!(a.runs, 40);
+        assert_eq!(a.model, PriceModel::JumpDiffusion);
+        assert_eq!(a.mean_bad_debt, b.mean_bad_debt);
+        assert_eq!(a.var_99, b.var_99);
+    }
+
+    #[test]
+    fn test_corpus_replays_the_campaign() {
+        let config = SimulationConfig { seed: Some(7), ..Default::default() };
+        let path_config = PricePathConfig { model: PriceModel::JumpDiffusion, blocks: 50, ..Default::default() };
+        let corpus = PathCorpus::generate(&mut CpuBackend, &path_config, 30, &config).unwrap();
+        let file = std::env::temp_dir().join(format!("fair-sim-corpus-{}.json", std::process::id()));
+        corpus.save(&file).unwrap();
+        let loaded = PathCorpus::load(&file).unwrap();
+        std::fs::remove_file(&file).unwrap();
+        assert_eq!(loaded, corpus);
+
+        // The corpus holds the campaign's runs, whatever seed replays it
+        let mechanism = LiquidationMechanism::keeper_pool();
+        let campaign = run_on_paths(&mut CpuBackend, &path_config, mechanism, 30, &config).unwrap();
+        let replayed = replay_corpus(&loaded, mechanism, &SimulationConfig { seed: None, ..config }).unwrap();
+        assert_eq!(replayed.runs, 30);
+        assert_eq!(replayed.mean_bad_debt, campaign.mean_bad_debt);
+        assert_eq!(replayed.var_99, campaign.var_99);
+    }
+
+    #[test]
+    fn test_flat_paths_leave_no_bad_debt() {
+        // Without drift or volatility the price only moves with liquidations
+        let config = SimulationConfig { seed: Some(2), ..Default::default() };
+        let path_config = PricePathConfig { drift: 0.0, volatility: 0.0, ..Default::default() };
+        let result =
+            run_on_paths(&mut CpuBackend, &path_config, LiquidationMechanism::Traditional, 20, &config).unwrap();
+
+        assert_eq!(result.runs, 20);
+        assert_eq!(result.max_bad_debt, 0.0);
+    }
+}
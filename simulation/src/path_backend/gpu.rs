@@ -0,0 +1,253 @@
+//! GBM and jump-diffusion paths generated on a GPU through wgpu
+//! (`paths.wgsl`), on whichever adapter wgpu picks: Vulkan, Metal, DX12 or
+//! GL, or a software rasterizer such as llvmpipe when nothing else is
+//! available (`WGPU_BACKEND` restricts the choice).
+//!
+//! Paths are computed in f32: per-block returns round at about 1e-7, far
+//! below block volatility, and prices come back widened to f64. Batches
+//! are sized to the device's storage buffer limit and read back one at a
+//! time.
+
+use std::borrow::Cow;
+use std::sync::mpsc;
+
+use bytemuck::{Pod, Zeroable};
+
+use crate::error::{Error, Result};
+use crate::monte_carlo::{PriceModel, PricePathConfig, INITIAL_PRICE};
+
+use super::PathBackend;
+
+const WORKGROUP_SIZE: u32 = 64; // Matches `@workgroup_size` in paths.wgsl
+
+/// `Params` in paths.wgsl, padded to a multiple of 16 bytes.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+struct Params {
+    first: u32,
+    paths: u32,
+    blocks: u32,
+    seed_lo: u32,
+    seed_hi: u32,
+    drift: f32,
+    diffusion: f32,
+    jump_rate: f32,
+    jump_p0: f32,
+    jump_mean: f32,
+    jump_std: f32,
+    floor: f32,
+    initial_price: f32,
+    _padding: [u32; 3],
+}
+
+impl Params {
+    fn new(config: &PricePathConfig, seed: u64) -> Self {
+        let dt = 1.0 / config.steps_per_year;
+        let jump_rate = match config.model {
+            PriceModel::JumpDiffusion => config.jump_intensity * dt,
+            _ => 0.0,
+        };
+        Self {
+            first: 0,
+            paths: 0,
+            blocks: config.blocks as u32,
+            seed_lo: seed as u32,
+            seed_hi: (seed >> 32) as u32,
+            drift: ((config.drift - 0.5 * config.volatility.powi(2)) * dt) as f32,
+            diffusion: (config.volatility * dt.sqrt()) as f32,
+            jump_rate: jump_rate as f32,
+            jump_p0: (-jump_rate).exp() as f32,
+            jump_mean: config.jump_mean as f32,
+            jump_std: config.jump_std as f32,
+            floor: (50.0 / INITIAL_PRICE).ln() as f32, // `generate_price_path` floors prices at $50
+            initial_price: INITIAL_PRICE as f32,
+            _padding: [0; 3],
+        }
+    }
+}
+
+fn gpu_error(e: impl std::fmt::Display) -> Error {
+    Error::Gpu(e.to_string())
+}
+
+/// Paths from a compute shader. Draws the same models as
+/// `generate_price_paths`, from a different stream.
+pub struct GpuBackend {
+    adapter: wgpu::AdapterInfo,
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    pipeline: wgpu::ComputePipeline,
+}
+
+impl GpuBackend {
+    /// Opens the highest-performance adapter; an error when there is none.
+    pub fn new() -> Result<Self> {
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::new_without_display_handle_from_env());
+        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::HighPerformance,
+            ..Default::default()
+        }))
+        .map_err(|e| Error::Gpu(format!("no GPU adapter available: {}", e)))?;
+        let (device, queue) = pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor {
+            label: Some("fair-sim paths"),
+            required_limits: adapter.limits(),
+            ..Default::default()
+        }))
+        .map_err(gpu_error)?;
+
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("paths.wgsl"),
+            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(include_str!("paths.wgsl"))),
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("price paths"),
+            layout: None,
+            module: &module,
+            entry_point: Some("main"),
+            compilation_options: Default::default(),
+            cache: None,
+        });
+
+        Ok(Self { adapter: adapter.get_info(), device, queue, pipeline })
+    }
+
+    /// Most paths of `blocks` blocks one dispatch can write.
+    fn batch_limit(&self, blocks: usize) -> usize {
+        let limits = self.device.limits();
+        let bytes = limits.max_storage_buffer_binding_size.min(limits.max_buffer_size);
+        let by_buffer = bytes as usize / ((blocks + 1) * std::mem::size_of::<f32>());
+        let by_dispatch = limits.max_compute_workgroups_per_dimension as usize * WORKGROUP_SIZE as usize;
+        by_buffer.min(by_dispatch).max(1)
+    }
+
+    /// Runs one batch and reads its prices back, block-major.
+    fn dispatch(&self, params: Params, output: &wgpu::Buffer, staging: &wgpu::Buffer) -> Result<Vec<f32>> {
+        let bytes = u64::from(params.paths) * (u64::from(params.blocks) + 1) * std::mem::size_of::<f32>() as u64;
+        let uniforms = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("path params"),
+            size: std::mem::size_of::<Params>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        self.queue.write_buffer(&uniforms, 0, bytemuck::bytes_of(&params));
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("price paths"),
+            layout: &self.pipeline.get_bind_group_layout(0),
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: uniforms.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 1, resource: output.as_entire_binding() },
+            ],
+        });
+
+        let mut encoder = self.device.create_command_encoder(&Default::default());
+        {
+            let mut pass = encoder.begin_compute_pass(&Default::default());
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.dispatch_workgroups(params.paths.div_ceil(WORKGROUP_SIZE), 1, 1);
+        }
+        encoder.copy_buffer_to_buffer(output, 0, staging, 0, bytes);
+        let submission = self.queue.submit([encoder.finish()]);
+
+        let (sender, mapped) = mpsc::channel();
+        staging.map_async(wgpu::MapMode::Read, ..bytes, move |result| {
+            let _ = sender.send(result);
+        });
+        self.device
+            .poll(wgpu::PollType::Wait { submission_index: Some(submission), timeout: None })
+            .map_err(gpu_error)?;
+        mapped.recv().map_err(gpu_error)?.map_err(gpu_error)?;
+        let prices = bytemuck::cast_slice(&staging.get_mapped_range(..bytes).map_err(gpu_error)?).to_vec();
+        staging.unmap();
+        Ok(prices)
+    }
+}
+
+impl PathBackend for GpuBackend {
+    fn name(&self) -> String {
+        format!("GPU ({}, {:?})", self.adapter.name, self.adapter.backend)
+    }
+
+    fn generate(&mut self, config: &PricePathConfig, paths: usize, seed: u64) -> Result<Vec<Vec<f64>>> {
+        if !matches!(config.model, PriceModel::GBM | PriceModel::JumpDiffusion) {
+            return Err(Error::Invalid(format!(
+                "the GPU backend generates GBM and jump-diffusion paths, not {}",
+                config.model.name()
+            )));
+        }
+        if u32::try_from(paths).is_err() || u32::try_from(config.blocks).is_err() {
+            return Err(Error::Invalid("too many paths or blocks for the GPU backend".to_string()));
+        }
+
+        let batch = self.batch_limit(config.blocks).min(paths.max(1));
+        let bytes = (batch * (config.blocks + 1) * std::mem::size_of::<f32>()) as u64;
+        let output = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("prices"),
+            size: bytes,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let staging = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("prices readback"),
+            size: bytes,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let mut out = Vec::with_capacity(paths);
+        let base = Params::new(config, seed);
+        for first in (0..paths).step_by(batch) {
+            let lanes = batch.min(paths - first);
+            let params = Params { first: first as u32, paths: lanes as u32, ..base };
+            let prices = self.dispatch(params, &output, &staging)?;
+            out.extend((0..lanes).map(|lane| prices[lane..].iter().step_by(lanes).map(|&p| f64::from(p)).collect()));
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The backend, or None (with a note) on machines without an adapter.
+    fn backend() -> Option<GpuBackend> {
+        GpuBackend::new().map_err(|e| eprintln!("skipping GPU test: {}", e)).ok()
+    }
+
+    #[test]
+    fn test_gpu_paths_match_the_model() {
+        let Some(mut gpu) = backend() else { return };
+        // Daily steps, so a path spans 100 days and sees a few jumps
+        let config = PricePathConfig {
+            model: PriceModel::JumpDiffusion,
+            steps_per_year: 365.0,
+            volatility: 0.5,
+            drift: 0.0,
+            ..Default::default()
+        };
+        let paths = gpu.generate(&config, 20_003, 3).unwrap();
+        assert_eq!(paths.len(), 20_003);
+        assert!(paths.iter().all(|p| p.len() == config.blocks + 1 && p[0] == INITIAL_PRICE));
+        assert_eq!(paths[..9], gpu.generate(&config, 9, 3).unwrap()[..]);
+
+        let n = paths.len() as f64;
+        let log_returns: Vec<f64> = paths.iter().map(|p| (p[config.blocks] / INITIAL_PRICE).ln()).collect();
+        let mean = log_returns.iter().sum::<f64>() / n;
+        let variance = log_returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / n;
+        let t = config.blocks as f64 / config.steps_per_year;
+        let jumps = config.jump_intensity * t;
+        let expected_mean = -0.5 * config.volatility.powi(2) * t + jumps * config.jump_mean;
+        let expected_variance =
+            config.volatility.powi(2) * t + jumps * (config.jump_std.powi(2) + config.jump_mean.powi(2));
+        assert!((mean - expected_mean).abs() < 0.01, "{} vs {}", mean, expected_mean);
+        assert!((variance / expected_variance - 1.0).abs() < 0.05, "{} vs {}", variance, expected_variance);
+    }
+
+    #[test]
+    fn test_gpu_rejects_models_it_cannot_draw() {
+        let Some(mut gpu) = backend() else { return };
+        let config = PricePathConfig { model: PriceModel::GARCH, ..Default::default() };
+        assert!(gpu.generate(&config, 10, 1).is_err());
+    }
+}
@@ -0,0 +1,93 @@
+// GBM and jump-diffusion price paths, one path per invocation.
+//
+// Draws are counter-based: every block hashes (path, block, seed) with
+// pcg4d (Jarzynski & Olano, "Hash Functions for GPU Rendering", 2020), so a
+// path depends only on its index and the seed, not on how it was batched.
+// Box-Muller turns two words into the diffusion and jump normals, and a
+// third word inverts the Poisson jump count. Prices are written
+// block-major, so a workgroup's writes for a block are contiguous.
+
+struct Params {
+    first: u32,         // Index of the batch's first path
+    paths: u32,         // Paths in the batch
+    blocks: u32,
+    seed_lo: u32,
+    seed_hi: u32,
+    drift: f32,         // Log drift per block, compensator included
+    diffusion: f32,     // Volatility per block
+    jump_rate: f32,     // Expected jumps per block; 0 = GBM
+    jump_p0: f32,       // exp(-jump_rate)
+    jump_mean: f32,
+    jump_std: f32,
+    floor: f32,         // Log of the price floor relative to the opening price
+    initial_price: f32,
+}
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read_write> prices: array<f32>;
+
+const MAX_JUMPS: f32 = 32.0;
+const TAU: f32 = 6.283185307;
+
+fn pcg4d(input: vec4<u32>) -> vec4<u32> {
+    var v = input * 1664525u + 1013904223u;
+    v.x += v.y * v.w;
+    v.y += v.z * v.x;
+    v.z += v.x * v.y;
+    v.w += v.y * v.z;
+    v ^= v >> vec4<u32>(16u);
+    v.x += v.y * v.w;
+    v.y += v.z * v.x;
+    v.z += v.x * v.y;
+    v.w += v.y * v.z;
+    return v;
+}
+
+// Uniform on (0, 1], safe to take the log of
+fn open_unit(bits: u32) -> f32 {
+    return (f32(bits >> 8u) + 1.0) / 16777216.0;
+}
+
+// Uniform on [0, 1)
+fn unit(bits: u32) -> f32 {
+    return f32(bits >> 8u) / 16777216.0;
+}
+
+// Poisson(jump_rate) by inversion
+fn jump_count(u: f32) -> f32 {
+    var k = 0.0;
+    var p = params.jump_p0;
+    var cdf = p;
+    while (u > cdf && k < MAX_JUMPS) {
+        k += 1.0;
+        p *= params.jump_rate / k;
+        cdf += p;
+    }
+    return k;
+}
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let lane = id.x;
+    if (lane >= params.paths) {
+        return;
+    }
+    let path = params.first + lane;
+    prices[lane] = params.initial_price;
+
+    var log_price = 0.0;
+    for (var block = 0u; block < params.blocks; block++) {
+        let bits = pcg4d(vec4<u32>(path, block, params.seed_lo, params.seed_hi));
+        let radius = sqrt(-2.0 * log(open_unit(bits.x)));
+        let angle = TAU * unit(bits.y);
+        var ret = params.drift + params.diffusion * radius * cos(angle);
+        if (params.jump_rate > 0.0) {
+            let k = jump_count(unit(bits.z));
+            if (k > 0.0) {
+                ret += k * params.jump_mean + sqrt(k) * params.jump_std * radius * sin(angle);
+            }
+        }
+        log_price = max(log_price + ret, params.floor);
+        prices[(block + 1u) * params.paths + lane] = params.initial_price * exp(log_price);
+    }
+}
@@ -0,0 +1,208 @@
+//! Penalty Curve Sweep
+//!
+//! Searches linear penalty curves (see `cascade::PenaltyCurve`) for the
+//! one that minimizes bad debt without costing borrowers more than a cap.
+//! A higher penalty on deep CDPs draws keepers to them before they go
+//! underwater, while a lower one near the minimum spares owners who only
+//! just crossed it; the flat penalty charges both the same.
+//!
+//! ## Method
+//! - Runs the mechanism at the flat `liquidation_penalty`, as a curve with
+//!   no slope so that it too seizes only the debt plus the penalty, then at
+//!   every pair of starting penalty and slope in the grid, all capped at
+//!   the same max penalty; every run sees the same seeds when
+//!   `config.seed` is set
+//! - Borrower loss is collateral value seized beyond the debt repaid,
+//!   averaged over runs and per liquidation; the cap, on the average over
+//!   runs, defaults to the flat penalty's
+//! - The best curve has the least average bad debt among those within the
+//!   cap, ties going to the lower borrower loss
+
+use crate::cascade::{run_cascade_simulation, CascadeResult, LiquidationMechanism, PenaltyCurve, PriceScenario, SimulationConfig};
+use crate::error::{Error, Result};
+
+/// Penalties at the minimum ratio swept by default.
+pub const MIN_PENALTIES: [f64; 3] = [0.02, 0.05, 0.10];
+
+/// Slopes swept by default, per unit of collateral ratio below the minimum.
+pub const PENALTY_SLOPES: [f64; 4] = [0.0, 0.25, 0.5, 1.0];
+
+#[derive(Debug, Clone)]
+pub struct CurvePoint {
+    pub curve: Option<PenaltyCurve>, // None = the flat penalty
+    pub avg_bad_debt: f64,
+    pub bad_debt_frequency: f64,
+    pub avg_borrower_loss: f64,
+    pub loss_per_liquidation: f64, // Borrower loss over every run's liquidations
+    pub avg_liquidations: f64,
+}
+
+impl CurvePoint {
+    fn from_results(curve: Option<PenaltyCurve>, results: &[CascadeResult]) -> Self {
+        let n = results.len().max(1) as f64;
+        let mean = |f: fn(&CascadeResult) -> f64| results.iter().map(f).sum::<f64>() / n;
+        let liquidations: usize = results.iter().map(|r| r.total_liquidations).sum();
+        Self {
+            curve,
+            avg_bad_debt: mean(|r| r.bad_debt),
+            bad_debt_frequency: results.iter().filter(|r| r.bad_debt > 0.0).count() as f64 / n,
+            avg_borrower_loss: mean(|r| r.borrower_loss),
+            loss_per_liquidation: results.iter().map(|r| r.borrower_loss).sum::<f64>() / liquidations.max(1) as f64,
+            avg_liquidations: mean(|r| r.total_liquidations as f64),
+        }
+    }
+
+    pub fn label(&self, flat_penalty: f64) -> String {
+        match self.curve {
+            Some(curve) => format!("{:.0}% + {:.2}/ratio", curve.min_penalty * 100.0, curve.slope),
+            None => format!("Flat {:.0}%", flat_penalty * 100.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CurveReport {
+    pub mechanism: LiquidationMechanism,
+    pub scenario: PriceScenario,
+    pub runs: usize,
+    pub flat_penalty: f64,
+    pub max_penalty: f64,
+    pub loss_cap: f64, // USD of average borrower loss
+    pub flat: CurvePoint,
+    pub points: Vec<CurvePoint>,
+}
+
+impl CurveReport {
+    /// The curve with the least bad debt within the borrower-loss cap, if
+    /// any stays within it.
+    pub fn best(&self) -> Option<&CurvePoint> {
+        self.points.iter().filter(|point| point.avg_borrower_loss <= self.loss_cap).min_by(|a, b| {
+            a.avg_bad_debt.total_cmp(&b.avg_bad_debt).then(a.avg_borrower_loss.total_cmp(&b.avg_borrower_loss))
+        })
+    }
+
+    pub fn print(&self) {
+        let best = self.best().and_then(|best| best.curve);
+        println!("| Penalty           | Bad Debt   | P(Bad Debt) | Borrower Loss | Loss/Liquidation | Liquidations |");
+        println!("|-------------------|------------|-------------|---------------|------------------|--------------|");
+        for point in std::iter::once(&self.flat).chain(&self.points) {
+            let marker = if point.curve.is_some() && point.curve == best { "*" } else { " " };
+            let over = if point.avg_borrower_loss > self.loss_cap { "!" } else { " " };
+            println!(
+                "| {:16}{} | ${:9.0} | {:10.1}% | ${:11.0}{} | ${:15.0} | {:12.1} |",
+                point.label(self.flat_penalty),
+                marker,
+                point.avg_bad_debt,
+                point.bad_debt_frequency * 100.0,
+                point.avg_borrower_loss,
+                over,
+                point.loss_per_liquidation,
+                point.avg_liquidations,
+            );
+        }
+        println!("  Curves capped at {:.0}%; ! marks borrower loss over the ${:.0} cap", self.max_penalty * 100.0, self.loss_cap);
+        match self.best() {
+            Some(best) => println!(
+                "  Best: {} (*), ${:.0} bad debt against ${:.0} flat",
+                best.label(self.flat_penalty),
+                best.avg_bad_debt,
+                self.flat.avg_bad_debt,
+            ),
+            None => println!("  No curve compared keeps borrower loss within the cap"),
+        }
+    }
+}
+
+/// Runs `mechanism` at the flat penalty and at each curve from
+/// `min_penalties` by `slopes` up to `max_penalty`. `loss_cap` bounds the
+/// average borrower loss of the best curve; None caps it at the flat
+/// penalty's.
+#[allow(clippy::too_many_arguments)]
+pub fn sweep_penalty_curves(
+    mechanism: LiquidationMechanism,
+    scenario: PriceScenario,
+    min_penalties: &[f64],
+    slopes: &[f64],
+    max_penalty: f64,
+    loss_cap: Option<f64>,
+    runs: usize,
+    config: &SimulationConfig,
+) -> Result<CurveReport> {
+    if min_penalties.is_empty() || slopes.is_empty() {
+        return Err(Error::Invalid("penalty curve sweep needs at least one starting penalty and slope".to_string()));
+    }
+    mechanism.validate()?;
+    // The flat penalty as a curve, so every point seizes alike
+    let flat_curve = PenaltyCurve { min_penalty: config.liquidation_penalty, slope: 0.0, max_penalty: config.liquidation_penalty };
+    let run = |curve: Option<PenaltyCurve>| -> Result<CurvePoint> {
+        let config = SimulationConfig { penalty_curve: Some(curve.unwrap_or(flat_curve)), ..config.clone() };
+        config.validate()?;
+        Ok(CurvePoint::from_results(curve, &run_cascade_simulation(mechanism, scenario, runs, &config)))
+    };
+    let flat = run(None)?;
+    let points = min_penalties
+        .iter()
+        .flat_map(|&min_penalty| slopes.iter().map(move |&slope| PenaltyCurve { min_penalty, slope, max_penalty }))
+        .map(|curve| run(Some(curve)))
+        .collect::<Result<Vec<_>>>()?;
+    let loss_cap = loss_cap.unwrap_or(flat.avg_borrower_loss);
+
+    Ok(CurveReport {
+        mechanism,
+        scenario,
+        runs,
+        flat_penalty: config.liquidation_penalty,
+        max_penalty,
+        loss_cap,
+        flat,
+        points,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slopes_draw_keepers_to_deep_cdps() {
+        let config = SimulationConfig { seed: Some(3), ..Default::default() };
+        let report = sweep_penalty_curves(
+            LiquidationMechanism::keeper_pool(),
+            PriceScenario::GradualDecline,
+            &[0.02, 0.05],
+            &[0.0, 1.0],
+            0.20,
+            None,
+            10,
+            &config,
+        )
+        .unwrap();
+
+        let (flat_low, steep_low, flat_mid) = (&report.points[0], &report.points[1], &report.points[2]);
+        // Each liquidation takes the penalty it is charged, not the whole CDP
+        assert!(flat_low.loss_per_liquidation < flat_mid.loss_per_liquidation);
+        assert!(flat_mid.loss_per_liquidation < report.flat.loss_per_liquidation);
+        assert!(steep_low.loss_per_liquidation > flat_low.loss_per_liquidation);
+        // Keepers pass over deep CDPs at 2% but clear them once the slope prices them up
+        assert!(steep_low.avg_liquidations > flat_low.avg_liquidations);
+        let best = report.best().unwrap();
+        assert!(report.points.iter().all(|point| best.avg_bad_debt <= point.avg_bad_debt));
+    }
+
+    #[test]
+    fn test_cap_rules_out_curves() {
+        let config = SimulationConfig { seed: Some(3), ..Default::default() };
+        let sweep = |loss_cap| {
+            sweep_penalty_curves(LiquidationMechanism::Traditional, PriceScenario::FlashCrash, &[0.10], &[0.5], 0.20, loss_cap, 5, &config)
+        };
+
+        assert!(sweep(Some(0.0)).unwrap().best().is_none());
+        let curved = SimulationConfig { penalty_curve: Some(PenaltyCurve::default()), ..config.clone() };
+        for result in run_cascade_simulation(LiquidationMechanism::Traditional, PriceScenario::FlashCrash, 3, &curved) {
+            assert!(crate::audit::ConservationReport::of(&result).is_consistent());
+        }
+        assert!(sweep(Some(f64::INFINITY)).unwrap().best().is_some());
+        assert!(sweep_penalty_curves(LiquidationMechanism::Traditional, PriceScenario::FlashCrash, &[0.3], &[0.5], 0.20, None, 1, &config).is_err());
+        assert!(sweep_penalty_curves(LiquidationMechanism::Traditional, PriceScenario::FlashCrash, &[], &[0.5], 0.20, None, 1, &config).is_err());
+    }
+}
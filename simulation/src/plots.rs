@@ -0,0 +1,282 @@
+//! Plots
+//!
+//! Charts of a campaign's results, rendered with `plotters` behind the
+//! optional `plots` feature: SVG by default, PNG for `.png` paths.
+//!
+//! - Price path fans: the median path per mechanism inside its 5th-95th
+//!   percentile band, block by block
+//! - Bad-debt histograms: the distribution of nonzero bad debt per
+//!   mechanism, with the share of runs ending without any in the legend
+//! - Lorenz curves: cumulative keeper profit against the cumulative share
+//!   of keepers, with each mechanism's Gini coefficient in the legend
+//!
+//! ```bash
+//! cargo run --release --features plots -- monte-carlo --runs 2000 --plot-dir plots
+//! ```
+
+use std::path::Path;
+
+use plotters::coord::types::RangedCoordf64;
+use plotters::coord::Shift;
+use plotters::prelude::*;
+
+pub use crate::core::{gini, lorenz};
+use crate::error::{Error, Result};
+use crate::monte_carlo::percentile;
+
+/// Chart size in pixels.
+pub const SIZE: (u32, u32) = (960, 600);
+
+/// Histogram bins across the largest bad debt.
+pub const HISTOGRAM_BINS: usize = 40;
+
+const BAND: (f64, f64) = (0.05, 0.95);
+
+/// 5th percentile, median and 95th percentile of every block across
+/// `paths`; blocks past the end of a shorter path count the paths still
+/// running.
+pub fn quantile_bands(paths: &[Vec<f64>]) -> Vec<[f64; 3]> {
+    let blocks = paths.iter().map(Vec::len).max().unwrap_or(0);
+    (0..blocks)
+        .map(|block| {
+            let mut prices: Vec<f64> = paths.iter().filter_map(|path| path.get(block).copied()).collect();
+            prices.sort_by(f64::total_cmp);
+            [percentile(&prices, BAND.0), percentile(&prices, 0.5), percentile(&prices, BAND.1)]
+        })
+        .collect()
+}
+
+/// Counts of `values` in `bins` equal-width bins over `[0, max]`.
+pub fn histogram(values: &[f64], bins: usize, max: f64) -> Vec<usize> {
+    let mut counts = vec![0; bins];
+    if bins == 0 || max <= 0.0 {
+        return counts;
+    }
+    for &value in values {
+        let bin = ((value / max) * bins as f64) as usize;
+        counts[bin.min(bins - 1)] += 1;
+    }
+    counts
+}
+
+fn plot_error(e: impl std::fmt::Display) -> Error {
+    Error::Plot(e.to_string())
+}
+
+fn is_png(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("png"))
+}
+
+/// Draws with `$draw(root, args..)` on a PNG or SVG backend for `$path`.
+macro_rules! render {
+    ($path:expr, $draw:ident($($arg:expr),*)) => {
+        if is_png($path) {
+            $draw(BitMapBackend::new($path, SIZE).into_drawing_area(), $($arg),*)
+        } else {
+            $draw(SVGBackend::new($path, SIZE).into_drawing_area(), $($arg),*)
+        }
+    };
+}
+
+/// Price path fan per labelled set of paths.
+pub fn price_fan(path: &Path, title: &str, series: &[(&str, &[Vec<f64>])]) -> Result<()> {
+    render!(path, draw_price_fan(title, series))
+}
+
+/// Histogram of nonzero bad debt per labelled set of runs.
+pub fn bad_debt_histogram(path: &Path, title: &str, series: &[(&str, &[f64])]) -> Result<()> {
+    render!(path, draw_bad_debt_histogram(title, series))
+}
+
+/// Lorenz curve of keeper profits per labelled set.
+pub fn lorenz_curves(path: &Path, title: &str, series: &[(&str, &[f64])]) -> Result<()> {
+    render!(path, draw_lorenz_curves(title, series))
+}
+
+fn draw_price_fan<DB: DrawingBackend>(
+    root: DrawingArea<DB, Shift>,
+    title: &str,
+    series: &[(&str, &[Vec<f64>])],
+) -> Result<()> {
+    let bands: Vec<Vec<[f64; 3]>> = series.iter().map(|(_, paths)| quantile_bands(paths)).collect();
+    let blocks = bands.iter().map(Vec::len).max().unwrap_or(0).max(2) - 1;
+    let low = bands.iter().flatten().map(|q| q[0]).fold(f64::INFINITY, f64::min);
+    let high = bands.iter().flatten().map(|q| q[2]).fold(0.0, f64::max);
+    let low = if low.is_finite() { low * 0.95 } else { 0.0 };
+
+    root.fill(&WHITE).map_err(plot_error)?;
+    let mut chart = ChartBuilder::on(&root)
+        .caption(title, ("sans-serif", 24))
+        .margin(15)
+        .x_label_area_size(40)
+        .y_label_area_size(70)
+        .build_cartesian_2d(0.0..blocks as f64, low..(high * 1.05).max(low + 1.0))
+        .map_err(plot_error)?;
+    chart
+        .configure_mesh()
+        .x_desc("Block")
+        .y_desc("ETH price")
+        .x_label_formatter(&|block| format!("{:.0}", block))
+        .y_label_formatter(&|price| format!("${:.0}", price))
+        .draw()
+        .map_err(plot_error)?;
+
+    for (i, ((label, _), band)) in series.iter().zip(&bands).enumerate() {
+        let color = Palette99::pick(i).to_rgba();
+        let outline = band
+            .iter()
+            .enumerate()
+            .map(|(block, q)| (block as f64, q[0]))
+            .chain(band.iter().enumerate().rev().map(|(block, q)| (block as f64, q[2])));
+        chart.draw_series(std::iter::once(Polygon::new(outline.collect::<Vec<_>>(), color.mix(0.2)))).map_err(plot_error)?;
+        chart
+            .draw_series(LineSeries::new(band.iter().enumerate().map(|(block, q)| (block as f64, q[1])), color.stroke_width(2)))
+            .map_err(plot_error)?
+            .label(format!("{} (median, 5-95%)", label))
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color.stroke_width(2)));
+    }
+    draw_legend(&mut chart, SeriesLabelPosition::UpperRight)?;
+    root.present().map_err(plot_error)
+}
+
+fn draw_bad_debt_histogram<DB: DrawingBackend>(
+    root: DrawingArea<DB, Shift>,
+    title: &str,
+    series: &[(&str, &[f64])],
+) -> Result<()> {
+    let max = series.iter().flat_map(|(_, values)| values.iter()).copied().fold(0.0, f64::max).max(1.0);
+    let width = max / HISTOGRAM_BINS as f64;
+    let counts: Vec<Vec<usize>> = series
+        .iter()
+        .map(|(_, values)| {
+            let nonzero: Vec<f64> = values.iter().copied().filter(|&v| v > 0.0).collect();
+            histogram(&nonzero, HISTOGRAM_BINS, max)
+        })
+        .collect();
+    let tallest = counts.iter().flatten().copied().max().unwrap_or(0).max(1);
+
+    root.fill(&WHITE).map_err(plot_error)?;
+    let mut chart = ChartBuilder::on(&root)
+        .caption(title, ("sans-serif", 24))
+        .margin(15)
+        .x_label_area_size(40)
+        .y_label_area_size(60)
+        .build_cartesian_2d(0.0..max, 0.0..tallest as f64 * 1.1)
+        .map_err(plot_error)?;
+    chart
+        .configure_mesh()
+        .x_desc("Bad debt")
+        .y_desc("Runs")
+        .x_label_formatter(&|debt| format!("${:.0}k", debt / 1000.0))
+        .y_label_formatter(&|runs| format!("{:.0}", runs))
+        .draw()
+        .map_err(plot_error)?;
+
+    for (i, ((label, values), counts)) in series.iter().zip(&counts).enumerate() {
+        let color = Palette99::pick(i).to_rgba();
+        let clean = values.iter().filter(|&&v| v <= 0.0).count() as f64 / values.len().max(1) as f64;
+        chart
+            .draw_series(counts.iter().enumerate().filter(|(_, &count)| count > 0).map(|(bin, &count)| {
+                let x = bin as f64 * width;
+                Rectangle::new([(x, 0.0), (x + width, count as f64)], color.mix(0.5).filled())
+            }))
+            .map_err(plot_error)?
+            .label(format!("{} ({:.0}% of runs without bad debt)", label, clean * 100.0))
+            .legend(move |(x, y)| Rectangle::new([(x, y - 5), (x + 15, y + 5)], color.mix(0.5).filled()));
+    }
+    draw_legend(&mut chart, SeriesLabelPosition::UpperRight)?;
+    root.present().map_err(plot_error)
+}
+
+fn draw_lorenz_curves<DB: DrawingBackend>(
+    root: DrawingArea<DB, Shift>,
+    title: &str,
+    series: &[(&str, &[f64])],
+) -> Result<()> {
+    root.fill(&WHITE).map_err(plot_error)?;
+    let mut chart = ChartBuilder::on(&root)
+        .caption(title, ("sans-serif", 24))
+        .margin(15)
+        .x_label_area_size(40)
+        .y_label_area_size(60)
+        .build_cartesian_2d(0.0..1.0, 0.0..1.0)
+        .map_err(plot_error)?;
+    chart
+        .configure_mesh()
+        .x_desc("Share of keepers")
+        .y_desc("Share of keeper profit")
+        .draw()
+        .map_err(plot_error)?;
+
+    chart
+        .draw_series(LineSeries::new([(0.0, 0.0), (1.0, 1.0)], BLACK.mix(0.4)))
+        .map_err(plot_error)?
+        .label("Equal shares")
+        .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], BLACK.mix(0.4)));
+    for (i, (label, profits)) in series.iter().enumerate() {
+        let color = Palette99::pick(i).to_rgba();
+        chart
+            .draw_series(LineSeries::new(lorenz(profits), color.stroke_width(2)))
+            .map_err(plot_error)?
+            .label(format!("{} (Gini {:.2})", label, gini(profits)))
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color.stroke_width(2)));
+    }
+    draw_legend(&mut chart, SeriesLabelPosition::UpperLeft)?;
+    root.present().map_err(plot_error)
+}
+
+fn draw_legend<'a, DB: DrawingBackend + 'a>(
+    chart: &mut ChartContext<'a, DB, Cartesian2d<RangedCoordf64, RangedCoordf64>>,
+    position: SeriesLabelPosition,
+) -> Result<()> {
+    chart
+        .configure_series_labels()
+        .position(position)
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()
+        .map_err(plot_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lorenz_and_gini() {
+        assert!(gini(&[5.0; 4]).abs() < 1e-12);
+        assert!((gini(&[0.0, 0.0, 0.0, 8.0]) - 0.75).abs() < 1e-12);
+        assert_eq!(lorenz(&[3.0, 1.0]), vec![(0.0, 0.0), (0.5, 0.25), (1.0, 1.0)]);
+        assert!(lorenz(&[0.0, 0.0]).is_empty());
+        assert_eq!(gini(&[]), 0.0);
+    }
+
+    #[test]
+    fn test_bands_and_histogram() {
+        let paths = vec![vec![1.0, 2.0, 3.0], vec![1.0, 4.0], vec![1.0, 6.0, 9.0]];
+        let bands = quantile_bands(&paths);
+
+        assert_eq!(bands.len(), 3);
+        assert_eq!(bands[0], [1.0; 3]);
+        assert_eq!(bands[1], [2.0, 4.0, 6.0]);
+        assert_eq!(bands[2][1], 9.0); // Median of two rounds up
+        assert_eq!(histogram(&[0.5, 1.0, 9.9, 10.0], 10, 10.0), vec![1, 1, 0, 0, 0, 0, 0, 0, 0, 2]);
+    }
+
+    #[test]
+    fn test_renders_svg_and_png() {
+        let dir = std::env::temp_dir();
+        let paths = vec![vec![2000.0, 1900.0, 1700.0], vec![2000.0, 1950.0, 1850.0]];
+        let svg = dir.join(format!("fair-sim-plot-{}.svg", std::process::id()));
+        let png = dir.join(format!("fair-sim-plot-{}.png", std::process::id()));
+
+        price_fan(&svg, "Fan", &[("Keeper pool", &paths)]).unwrap();
+        bad_debt_histogram(&png, "Bad debt", &[("Traditional", &[0.0, 10.0, 250.0])]).unwrap();
+        lorenz_curves(&svg, "Profits", &[("Keeper pool", &[1.0, 2.0, 7.0])]).unwrap();
+
+        assert!(std::fs::read_to_string(&svg).unwrap().contains("Gini 0.40"));
+        assert!(std::fs::read(&png).unwrap().starts_with(b"\x89PNG"));
+        std::fs::remove_file(svg).unwrap();
+        std::fs::remove_file(png).unwrap();
+    }
+}
@@ -7,22 +7,35 @@
 //! 4. Fair variants with profit sharing
 //!
 //! Measures Price of Anarchy = Nash Cost / Social Optimum
+//!
+//! What a keeper can tell about a CDP depends on what the strategy reveals,
+//! so each strategy maps to a `Perception` in a `PerceptionCalibration`.
+//! The calibration is the `[perception]` section of a config file; the
+//! defaults are spelled out in `figures/price-of-anarchy.toml`.
 
 use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+use tracing::info_span;
+
+use crate::cascade::DEFAULT_POOL_SPLIT;
+use crate::core::{penalty_profit, profit_concentration, shocked_price, split_with_losers, Keeper, CDP};
+use crate::error::{Error, Result};
+use crate::scoring::{self, ScoringModel, NUM_FEATURES};
 
 pub const NUM_CDPS: usize = 100;
 pub const NUM_KEEPERS: usize = 20;
 pub const ETH_PRICE: f64 = 2000.0;
 pub const LIQUIDATION_PENALTY: f64 = 0.13;
+pub const LIQUIDATION_GAS_COST: f64 = 50.0; // USD, netted from the equity before the penalty
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum ObfuscationStrategy {
     Transparent,
     NoiseBased,
     IPFE,
     Fair6040,
     Fair5050,
-    KeeperPool,
+    KeeperPool { split: f64 }, // Keeper share of the penalty; the rest goes to the protocol
 }
 
 impl ObfuscationStrategy {
@@ -33,160 +46,213 @@ impl ObfuscationStrategy {
             Self::IPFE,
             Self::Fair6040,
             Self::Fair5050,
-            Self::KeeperPool,
+            Self::KeeperPool { split: DEFAULT_POOL_SPLIT },
         ]
     }
 
-    pub fn name(&self) -> &'static str {
+    pub fn name(&self) -> String {
         match self {
-            Self::Transparent => "Transparent",
-            Self::NoiseBased => "Noise-Based",
-            Self::IPFE => "IPFE Only",
-            Self::Fair6040 => "Fair 60/40",
-            Self::Fair5050 => "Fair 50/50",
-            Self::KeeperPool => "Keeper Pool 70/30",
+            Self::Transparent => "Transparent".to_string(),
+            Self::NoiseBased => "Noise-Based".to_string(),
+            Self::IPFE => "IPFE Only".to_string(),
+            Self::Fair6040 => "Fair 60/40".to_string(),
+            Self::Fair5050 => "Fair 50/50".to_string(),
+            Self::KeeperPool { split } => {
+                format!("Keeper Pool {:.0}/{:.0}", split * 100.0, (1.0 - split) * 100.0)
+            }
+        }
+    }
+}
+
+/// The signal a keeper acts on when deciding whether a CDP is liquidatable.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "signal", rename_all = "snake_case")]
+pub enum Perception {
+    /// Weights and threshold are public: keepers compute the true score.
+    Score,
+    /// Weights are public but the threshold is jittered by up to ±`noise`
+    /// (relative); confidence is `1 - noise`.
+    NoisyThreshold { noise: f64 },
+    /// Nothing about the score is public: keepers fall back on the
+    /// collateral ratio, flagging CDPs below `cutoff`, with a confidence
+    /// drawn uniformly from `[min, max]`.
+    CollateralRatio { cutoff: f64, confidence: [f64; 2] },
+}
+
+impl Perception {
+    pub fn validate(&self) -> Result<()> {
+        match *self {
+            Self::Score => Ok(()),
+            Self::NoisyThreshold { noise } if !(0.0..1.0).contains(&noise) => {
+                Err(Error::Invalid(format!("perception noise must be in [0, 1), got {}", noise)))
+            }
+            Self::NoisyThreshold { .. } => Ok(()),
+            Self::CollateralRatio { cutoff, .. } if cutoff <= 0.0 => {
+                Err(Error::Invalid(format!("perception cutoff must be positive, got {}", cutoff)))
+            }
+            Self::CollateralRatio { confidence: [min, max], .. } if !(0.0 <= min && min <= max && max <= 1.0) => {
+                Err(Error::Invalid(format!(
+                    "perception confidence must satisfy 0 <= min <= max <= 1, got [{}, {}]",
+                    min, max
+                )))
+            }
+            Self::CollateralRatio { .. } => Ok(()),
         }
     }
 }
 
+/// A `Perception` per strategy, loaded from the `[perception]` config
+/// section. Omitted strategies keep their defaults.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PerceptionCalibration {
+    pub transparent: Perception,
+    pub noise_based: Perception,
+    pub ipfe: Perception,
+    pub fair: Perception, // Fair 60/40, Fair 50/50 and the keeper pool
+}
+
+impl Default for PerceptionCalibration {
+    fn default() -> Self {
+        Self {
+            transparent: Perception::Score,
+            noise_based: Perception::NoisyThreshold { noise: 0.29 },
+            ipfe: Perception::CollateralRatio { cutoff: 1.6, confidence: [0.2, 0.6] },
+            fair: Perception::CollateralRatio { cutoff: 1.6, confidence: [0.0, 1.0] },
+        }
+    }
+}
+
+impl PerceptionCalibration {
+    pub fn for_strategy(&self, strategy: ObfuscationStrategy) -> Perception {
+        match strategy {
+            ObfuscationStrategy::Transparent => self.transparent,
+            ObfuscationStrategy::NoiseBased => self.noise_based,
+            ObfuscationStrategy::IPFE => self.ipfe,
+            ObfuscationStrategy::Fair6040
+            | ObfuscationStrategy::Fair5050
+            | ObfuscationStrategy::KeeperPool { .. } => self.fair,
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        for perception in [self.transparent, self.noise_based, self.ipfe, self.fair] {
+            perception.validate()?;
+        }
+        Ok(())
+    }
+}
+
+/// A CDP with the hidden features the liquidation score weighs (see
+/// `scoring`).
 #[derive(Clone)]
-pub struct CDP {
-    pub id: usize,
-    pub collateral: f64,
-    pub debt: f64,
+pub struct ScoredCdp {
+    pub position: CDP,
     pub age_days: f64,
     pub volatility_score: f64,
 }
 
-impl CDP {
+impl ScoredCdp {
     pub fn new(id: usize, rng: &mut impl Rng) -> Self {
         let collateral = 1.0 + rng.gen::<f64>() * 9.0;
         let ratio = 1.3 + rng.gen::<f64>() * 0.5;
         let debt = (collateral * ETH_PRICE) / ratio;
 
         Self {
-            id,
-            collateral,
-            debt,
+            position: CDP::from_position(id, collateral, debt),
             age_days: rng.gen::<f64>() * 365.0,
             volatility_score: rng.gen::<f64>(),
         }
     }
 
     pub fn collateral_ratio(&self, eth_price: f64) -> f64 {
-        (self.collateral * eth_price) / self.debt
+        self.position.collateral_ratio(eth_price)
     }
 
-    pub fn features(&self, eth_price: f64) -> [f64; 5] {
-        [
-            self.collateral_ratio(eth_price),
+    pub fn features(&self, eth_price: f64) -> [f64; NUM_FEATURES] {
+        scoring::features(
+            self.position.collateral_value(eth_price),
+            self.position.debt,
+            self.age_days,
             self.volatility_score,
-            self.debt / (self.collateral * eth_price),
-            (self.age_days / 365.0).min(1.0),
-            (self.collateral * eth_price / 10000.0).min(2.0),
-        ]
+        )
     }
 
     pub fn liquidation_profit(&self, eth_price: f64) -> f64 {
-        let collateral_value = self.collateral * eth_price;
-        let profit = collateral_value - self.debt - 50.0;
-        profit.max(0.0) * LIQUIDATION_PENALTY
-    }
-}
-
-#[derive(Clone)]
-pub struct Keeper {
-    pub id: usize,
-    pub gas_priority: f64,
-    pub total_profit: f64,
-    pub successful_liquidations: usize,
-}
-
-impl Keeper {
-    pub fn new(id: usize, rng: &mut impl Rng) -> Self {
-        Self {
-            id,
-            gas_priority: rng.gen::<f64>(),
-            total_profit: 0.0,
-            successful_liquidations: 0,
-        }
+        penalty_profit(
+            self.position.collateral_value(eth_price),
+            self.position.debt,
+            LIQUIDATION_GAS_COST,
+            LIQUIDATION_PENALTY,
+        )
     }
 }
 
 pub struct LiquidationGame {
-    pub cdps: Vec<CDP>,
+    pub cdps: Vec<ScoredCdp>,
     pub eth_price: f64,
-    pub true_weights: [f64; 5],
-    pub true_threshold: f64,
+    pub scoring: ScoringModel, // The true weights and threshold, hidden unless transparent
     pub strategy: ObfuscationStrategy,
-    pub noise_level: f64,
+    pub perception: Perception,
 }
 
 impl LiquidationGame {
     pub fn new(strategy: ObfuscationStrategy, rng: &mut impl Rng) -> Self {
-        let cdps: Vec<CDP> = (0..NUM_CDPS).map(|i| CDP::new(i, rng)).collect();
-        let true_weights = [2.0, -1.0, -1.5, 0.3, -0.3];
-        let true_threshold = 2.0;
+        Self::with_calibration(strategy, &PerceptionCalibration::default(), rng)
+    }
+
+    pub fn with_calibration(
+        strategy: ObfuscationStrategy,
+        calibration: &PerceptionCalibration,
+        rng: &mut impl Rng,
+    ) -> Self {
+        let cdps: Vec<ScoredCdp> = (0..NUM_CDPS).map(|i| ScoredCdp::new(i, rng)).collect();
 
         Self {
             cdps,
             eth_price: ETH_PRICE,
-            true_weights,
-            true_threshold,
+            scoring: ScoringModel::default(),
             strategy,
-            noise_level: 0.29,
+            perception: calibration.for_strategy(strategy),
         }
     }
 
-    pub fn compute_true_score(&self, cdp: &CDP) -> f64 {
-        let features = cdp.features(self.eth_price);
-        features
-            .iter()
-            .zip(self.true_weights.iter())
-            .map(|(f, w)| f * w)
-            .sum()
+    pub fn compute_true_score(&self, cdp: &ScoredCdp) -> f64 {
+        self.scoring.score(&cdp.features(self.eth_price))
     }
 
-    pub fn is_truly_liquidatable(&self, cdp: &CDP) -> bool {
-        self.compute_true_score(cdp) < self.true_threshold
+    pub fn is_truly_liquidatable(&self, cdp: &ScoredCdp) -> bool {
+        self.scoring.is_liquidatable(&cdp.features(self.eth_price))
     }
 
-    pub fn keeper_perceives_liquidatable(&self, cdp: &CDP, rng: &mut impl Rng) -> (bool, f64) {
-        match self.strategy {
-            ObfuscationStrategy::Transparent => {
+    pub fn keeper_perceives_liquidatable(&self, cdp: &ScoredCdp, rng: &mut impl Rng) -> (bool, f64) {
+        match self.perception {
+            Perception::Score => {
                 let score = self.compute_true_score(cdp);
-                (score < self.true_threshold, 1.0)
+                (score < self.scoring.threshold, 1.0)
             }
-            ObfuscationStrategy::NoiseBased => {
+            Perception::NoisyThreshold { noise } => {
                 let perceived_threshold =
-                    self.true_threshold * (1.0 + (rng.gen::<f64>() - 0.5) * 2.0 * self.noise_level);
+                    self.scoring.threshold * (1.0 + (rng.gen::<f64>() - 0.5) * 2.0 * noise);
                 let score = self.compute_true_score(cdp);
-                let confidence = 1.0 - self.noise_level;
+                let confidence = 1.0 - noise;
                 (score < perceived_threshold, confidence)
             }
-            ObfuscationStrategy::IPFE => {
-                let ratio = cdp.collateral_ratio(self.eth_price);
-                let perceived_liquidatable = ratio < 1.6;
-                let confidence = 0.2 + rng.gen::<f64>() * 0.4;
-                (perceived_liquidatable, confidence)
-            }
-            ObfuscationStrategy::Fair6040
-            | ObfuscationStrategy::Fair5050
-            | ObfuscationStrategy::KeeperPool => {
+            Perception::CollateralRatio { cutoff, confidence: [min, max] } => {
                 let ratio = cdp.collateral_ratio(self.eth_price);
-                let perceived_liquidatable = ratio < 1.6;
-                let confidence = rng.gen::<f64>();
+                let perceived_liquidatable = ratio < cutoff;
+                let confidence = min + rng.gen::<f64>() * (max - min);
                 (perceived_liquidatable, confidence)
             }
         }
     }
 
     pub fn simulate_price_drop(&mut self, pct: f64) {
-        self.eth_price *= 1.0 - pct;
+        self.eth_price = shocked_price(self.eth_price, 1.0 - pct);
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GameResult {
     pub strategy: ObfuscationStrategy,
     pub successful_liquidations: usize,
@@ -200,8 +266,16 @@ pub struct GameResult {
 }
 
 pub fn simulate_game(strategy: ObfuscationStrategy, rng: &mut impl Rng) -> GameResult {
-    let mut game = LiquidationGame::new(strategy, rng);
-    let mut keepers: Vec<Keeper> = (0..NUM_KEEPERS).map(|i| Keeper::new(i, rng)).collect();
+    simulate_game_with_calibration(strategy, &PerceptionCalibration::default(), rng)
+}
+
+pub fn simulate_game_with_calibration(
+    strategy: ObfuscationStrategy,
+    calibration: &PerceptionCalibration,
+    rng: &mut impl Rng,
+) -> GameResult {
+    let mut game = LiquidationGame::with_calibration(strategy, calibration, rng);
+    let mut keepers: Vec<Keeper> = (0..NUM_KEEPERS).map(|i| Keeper::new(i, f64::INFINITY, rng.gen())).collect();
 
     game.simulate_price_drop(0.10);
 
@@ -247,7 +321,7 @@ pub fn simulate_game(strategy: ObfuscationStrategy, rng: &mut impl Rng) -> GameR
             strategy,
             ObfuscationStrategy::Fair6040
                 | ObfuscationStrategy::Fair5050
-                | ObfuscationStrategy::KeeperPool
+                | ObfuscationStrategy::KeeperPool { .. }
         );
 
         let winner_idx = if uses_random {
@@ -261,32 +335,19 @@ pub fn simulate_game(strategy: ObfuscationStrategy, rng: &mut impl Rng) -> GameR
             let profit = cdp.liquidation_profit(game.eth_price);
 
             match strategy {
-                ObfuscationStrategy::Fair6040 if attempts.len() > 1 => {
-                    let winner_share = profit * 0.6;
-                    let pool_share = profit * 0.4;
-                    let per_other = pool_share / (attempts.len() - 1) as f64;
+                ObfuscationStrategy::Fair6040 | ObfuscationStrategy::Fair5050 if attempts.len() > 1 => {
+                    let winner_share = if strategy == ObfuscationStrategy::Fair6040 { 0.6 } else { 0.5 };
+                    let (winner_cut, per_other) = split_with_losers(profit, winner_share, attempts.len() - 1);
 
-                    keepers[winner_id].total_profit += winner_share;
+                    keepers[winner_id].total_profit += winner_cut;
                     for (i, (kid, _, _)) in attempts.iter().enumerate() {
                         if i != winner_idx {
                             keepers[*kid].total_profit += per_other;
                         }
                     }
                 }
-                ObfuscationStrategy::Fair5050 if attempts.len() > 1 => {
-                    let winner_share = profit * 0.5;
-                    let pool_share = profit * 0.5;
-                    let per_other = pool_share / (attempts.len() - 1) as f64;
-
-                    keepers[winner_id].total_profit += winner_share;
-                    for (i, (kid, _, _)) in attempts.iter().enumerate() {
-                        if i != winner_idx {
-                            keepers[*kid].total_profit += per_other;
-                        }
-                    }
-                }
-                ObfuscationStrategy::KeeperPool => {
-                    let keeper_pool = profit * 0.7;
+                ObfuscationStrategy::KeeperPool { split } => {
+                    let keeper_pool = profit * split;
                     let per_keeper = keeper_pool / attempts.len() as f64;
 
                     for (kid, _, _) in attempts.iter() {
@@ -298,7 +359,7 @@ pub fn simulate_game(strategy: ObfuscationStrategy, rng: &mut impl Rng) -> GameR
                 }
             }
 
-            keepers[winner_id].successful_liquidations += 1;
+            keepers[winner_id].liquidations += 1;
             total_profit_extracted += profit;
             successful_liquidations += 1;
 
@@ -310,14 +371,8 @@ pub fn simulate_game(strategy: ObfuscationStrategy, rng: &mut impl Rng) -> GameR
         }
     }
 
-    let profit_concentration = if total_profit_extracted > 0.0 {
-        let mut profits: Vec<f64> = keepers.iter().map(|k| k.total_profit).collect();
-        profits.sort_by(|a, b| b.partial_cmp(a).unwrap());
-        let top_20_pct = profits.iter().take(NUM_KEEPERS / 5).sum::<f64>();
-        top_20_pct / total_profit_extracted
-    } else {
-        0.0
-    };
+    let profits: Vec<f64> = keepers.iter().map(|k| k.total_profit).collect();
+    let profit_concentration = profit_concentration(&profits, total_profit_extracted);
 
     let gas_waste_ratio =
         failed_attempts as f64 / (failed_attempts + successful_liquidations).max(1) as f64;
@@ -349,9 +404,23 @@ pub fn compute_poa(results: &[GameResult]) -> f64 {
     nash_cost / social_optimum.max(0.01)
 }
 
-pub fn run_poa_simulation(strategy: ObfuscationStrategy, runs: usize) -> Vec<GameResult> {
-    let mut rng = rand::thread_rng();
-    (0..runs).map(|_| simulate_game(strategy, &mut rng)).collect()
+/// `seed` fixes the RNG for reproducible runs; `None` seeds from OS entropy.
+pub fn run_poa_simulation(strategy: ObfuscationStrategy, runs: usize, seed: Option<u64>) -> Vec<GameResult> {
+    run_poa_simulation_with_calibration(strategy, runs, seed, &PerceptionCalibration::default())
+}
+
+pub fn run_poa_simulation_with_calibration(
+    strategy: ObfuscationStrategy,
+    runs: usize,
+    seed: Option<u64>,
+    calibration: &PerceptionCalibration,
+) -> Vec<GameResult> {
+    let mut rng = match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+    let _campaign = info_span!("poa", strategy = %strategy.name(), runs).entered();
+    (0..runs).map(|_| simulate_game_with_calibration(strategy, calibration, &mut rng)).collect()
 }
 
 #[cfg(test)]
@@ -361,7 +430,7 @@ mod tests {
     #[test]
     fn test_cdp_features() {
         let mut rng = rand::thread_rng();
-        let cdp = CDP::new(0, &mut rng);
+        let cdp = ScoredCdp::new(0, &mut rng);
         let features = cdp.features(ETH_PRICE);
 
         assert!(features[0] > 1.0);
@@ -375,4 +444,34 @@ mod tests {
 
         assert!(result.successful_liquidations > 0 || result.missed_liquidations == 0);
     }
+
+    #[test]
+    fn test_calibration_maps_strategies() {
+        let calibration = PerceptionCalibration::default();
+        assert_eq!(calibration.for_strategy(ObfuscationStrategy::Transparent), Perception::Score);
+        assert_eq!(calibration.for_strategy(ObfuscationStrategy::KeeperPool { split: 0.5 }), calibration.fair);
+        assert!(calibration.validate().is_ok());
+
+        let bad = PerceptionCalibration {
+            ipfe: Perception::CollateralRatio { cutoff: 1.6, confidence: [0.6, 0.2] },
+            ..calibration
+        };
+        assert!(bad.validate().unwrap_err().to_string().contains("confidence"));
+    }
+
+    #[test]
+    fn test_perfect_information_wastes_no_gas() {
+        // Every strategy seeing the true score never attempts a healthy CDP
+        let calibration = PerceptionCalibration {
+            ipfe: Perception::Score,
+            fair: Perception::Score,
+            ..Default::default()
+        };
+        for strategy in ObfuscationStrategy::all() {
+            let games = run_poa_simulation_with_calibration(strategy, 5, Some(3), &calibration);
+            if strategy != ObfuscationStrategy::NoiseBased {
+                assert!(games.iter().all(|g| g.failed_attempts == 0), "{}", strategy.name());
+            }
+        }
+    }
 }
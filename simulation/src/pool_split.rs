@@ -0,0 +1,167 @@
+//! Pool Split Comparison
+//!
+//! The keeper pool's members' part split alike per member against pro
+//! rata to capital (see `cascade::PoolSplit`). The equal split pays a
+//! small keeper as much as a whale for the same round, which keeps small
+//! keepers in business but invites splitting capital across identities;
+//! the capital split pays what each member brings, as a stake-weighted pool
+//! would, and hands the whales most of the pool.
+//!
+//! ## Method
+//! - Runs the default keeper pool under each split on the same keepers;
+//!   every run sees the same seeds when `config.seed` is set. The keeper
+//!   capital distribution is the config's, so a heavy-tailed one shows the
+//!   split at its most unequal
+//! - Inequality is the Gini coefficient of keepers' profit, and its
+//!   concentration the top fifth of keepers' share of it
+//! - Small keepers are the fifth with the least capital; one is viable if
+//!   its profit covers its gas, and its return is profit net of gas over
+//!   capital
+
+use rand::Rng;
+
+use crate::cascade::{CascadeSimulationBuilder, LiquidationMechanism, PoolSplit, PriceScenario, SimulationConfig};
+use crate::core::{gini, profit_concentration, Keeper};
+use crate::error::{Error, Result};
+
+#[derive(Debug, Clone)]
+pub struct SplitPoint {
+    pub split: PoolSplit,
+    pub avg_bad_debt: f64,
+    pub gini: f64,
+    pub profit_concentration: f64, // Top fifth of keepers' share of profit
+    pub small_viable: f64,         // Share of small keepers whose profit covers their gas
+    pub small_return: f64,         // Small keepers' profit net of gas over their capital
+    pub avg_profit: f64,           // Per keeper
+}
+
+impl SplitPoint {
+    pub fn label(&self) -> &'static str {
+        match self.split {
+            PoolSplit::Equal => "Equal",
+            PoolSplit::Capital => "Pro rata to capital",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SplitReport {
+    pub scenario: PriceScenario,
+    pub runs: usize,
+    pub points: Vec<SplitPoint>,
+}
+
+impl SplitReport {
+    pub fn print(&self) {
+        println!("| Split               | Bad Debt   | Gini  | Top 20% Profit | Small Viable | Small Return | Profit/Keeper |");
+        println!("|---------------------|------------|-------|----------------|--------------|--------------|---------------|");
+        for point in &self.points {
+            println!(
+                "| {:19} | ${:9.0} | {:5.3} | {:13.1}% | {:11.1}% | {:11.2}% | ${:12.0} |",
+                point.label(),
+                point.avg_bad_debt,
+                point.gini,
+                point.profit_concentration * 100.0,
+                point.small_viable * 100.0,
+                point.small_return * 100.0,
+                point.avg_profit,
+            );
+        }
+        if let [equal, capital] = &self.points[..] {
+            println!(
+                "  Splitting by capital moves the Gini by {:+.3} and small keepers' return by {:+.2} points",
+                capital.gini - equal.gini,
+                (capital.small_return - equal.small_return) * 100.0,
+            );
+        }
+    }
+}
+
+/// The fifth of `keepers` with the least capital (at least one).
+fn small_keepers(keepers: &[Keeper]) -> Vec<&Keeper> {
+    let mut by_capital: Vec<&Keeper> = keepers.iter().collect();
+    by_capital.sort_by(|a, b| a.capital.total_cmp(&b.capital));
+    by_capital.truncate((keepers.len() / 5).max(1));
+    by_capital
+}
+
+/// Runs the default keeper pool under the equal and the capital split.
+pub fn compare_pool_splits(scenario: PriceScenario, runs: usize, config: &SimulationConfig) -> Result<SplitReport> {
+    if runs == 0 {
+        return Err(Error::Invalid("pool split comparison needs at least one run".to_string()));
+    }
+    if config.keeper_staking.is_some() {
+        return Err(Error::Invalid("pool split comparison pays staked members by stake under either split".to_string()));
+    }
+    config.validate()?;
+    let seed: u64 = config.rng().gen();
+    let run = |split: PoolSplit| -> Result<SplitPoint> {
+        let config = SimulationConfig { pool_split: split, ..config.clone() };
+        let (mut bad_debt, mut inequality, mut concentration, mut viable, mut small_return, mut profit) =
+            (0.0, 0.0, 0.0, 0.0, 0.0, 0.0);
+        for i in 0..runs {
+            let mut rng = config.run_rng(seed.wrapping_add(i as u64));
+            let mut sim = CascadeSimulationBuilder::new()
+                .config(config.clone())
+                .mechanism(LiquidationMechanism::keeper_pool())
+                .scenario(scenario)
+                .build(&mut rng)?;
+            let result = sim.run(&mut rng);
+            let profits: Vec<f64> = sim.keepers().iter().map(|k| k.total_profit).collect();
+            let total: f64 = profits.iter().sum();
+            let small = small_keepers(sim.keepers());
+            let net = |k: &Keeper| k.total_profit - k.gas_spent;
+            bad_debt += result.bad_debt;
+            inequality += gini(&profits);
+            concentration += profit_concentration(&profits, total);
+            viable += small.iter().filter(|k| net(k) > 0.0).count() as f64 / small.len() as f64;
+            small_return += small.iter().map(|k| net(k) / k.capital).sum::<f64>() / small.len() as f64;
+            profit += total / profits.len().max(1) as f64;
+        }
+        let n = runs as f64;
+        Ok(SplitPoint {
+            split,
+            avg_bad_debt: bad_debt / n,
+            gini: inequality / n,
+            profit_concentration: concentration / n,
+            small_viable: viable / n,
+            small_return: small_return / n,
+            avg_profit: profit / n,
+        })
+    };
+    let points = [PoolSplit::Equal, PoolSplit::Capital].into_iter().map(run).collect::<Result<Vec<_>>>()?;
+
+    Ok(SplitReport { scenario, runs, points })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cascade::CapitalDistribution;
+
+    #[test]
+    fn test_capital_split_favors_whales() {
+        let mut config = SimulationConfig { seed: Some(3), ..Default::default() };
+        config.keepers.capital = CapitalDistribution::PowerLaw { min: 10_000.0, alpha: 1.2 };
+        let report = compare_pool_splits(PriceScenario::GradualDecline, 5, &config).unwrap();
+
+        let (equal, capital) = (&report.points[0], &report.points[1]);
+        assert!(capital.gini > equal.gini);
+        assert!(capital.small_return < equal.small_return);
+        // The split moves profit between members; the pool pays out the same
+        assert!((capital.avg_profit - equal.avg_profit).abs() < 1e-6 * equal.avg_profit);
+    }
+
+    #[test]
+    fn test_equal_capital_splits_alike() {
+        let mut config = SimulationConfig { seed: Some(3), ..Default::default() };
+        config.keepers.capital = CapitalDistribution::Uniform { min: 50_000.0, max: 50_000.0 };
+        let report = compare_pool_splits(PriceScenario::FlashCrash, 3, &config).unwrap();
+
+        let (equal, capital) = (&report.points[0], &report.points[1]);
+        assert!((capital.gini - equal.gini).abs() < 1e-9);
+        assert!((capital.small_return - equal.small_return).abs() < 1e-9);
+        let staked = SimulationConfig { keeper_staking: Some(Default::default()), ..config };
+        assert!(compare_pool_splits(PriceScenario::FlashCrash, 1, &staked).is_err());
+    }
+}
@@ -0,0 +1,260 @@
+//! Borrower Position Risk
+//!
+//! Conditional Monte Carlo for a single CDP: given its collateral and debt,
+//! how likely is it to be liquidated within a day, a week, or a month? Sized
+//! for a front-end "position health" widget, so the report serializes to JSON.
+//!
+//! ## Method
+//! - Price paths are simulated at 10 steps per day (the resolution the
+//!   historical crash models replay at), starting from the current price,
+//!   and generated in chunks with `generate_price_paths`
+//! - A step counts as a liquidation once the position falls below the
+//!   minimum collateral ratio and the keepers' share of the penalty clears
+//!   their profit threshold under the chosen mechanism
+//! - Positions that are liquidatable but too small for keepers to bother
+//!   with are reported separately as "stranded"
+//! - With `accruing_collateral` configured, the position holds the
+//!   yield-bearing token and its backing grows every step
+
+use serde::{Deserialize, Serialize};
+
+use crate::cascade::{keepers_will_act, LiquidationMechanism, SimulationConfig, CDP};
+use crate::error::{Error, Result};
+use crate::monte_carlo::{generate_price_paths, PriceModel, PricePathConfig};
+
+pub const HORIZON_DAYS: [usize; 3] = [1, 7, 30];
+const STEPS_PER_DAY: usize = 10;
+const PATH_CHUNK: usize = 1024; // Paths generated at a time
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Position {
+    pub collateral: f64, // ETH
+    pub debt: f64,       // USD
+    pub eth_price: f64,  // Current oracle price
+}
+
+impl Position {
+    fn validate(&self) -> Result<()> {
+        if !(self.collateral > 0.0 && self.debt >= 0.0 && self.eth_price > 0.0) {
+            return Err(Error::Invalid(
+                "position needs positive collateral and price, and non-negative debt".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn collateral_ratio(&self) -> f64 {
+        CDP::from_position(0, self.collateral, self.debt).collateral_ratio(self.eth_price)
+    }
+
+    /// ETH price at which the position becomes liquidatable.
+    pub fn liquidation_price(&self, min_collateral_ratio: f64) -> f64 {
+        self.debt * min_collateral_ratio / self.collateral
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HorizonRisk {
+    pub days: usize,
+    pub liquidation_probability: f64,
+    pub stranded_probability: f64, // Liquidatable at the horizon but ignored by keepers
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PositionRisk {
+    pub position: Position,
+    pub model: PriceModel,
+    pub mechanism: LiquidationMechanism,
+    pub runs: usize,
+    pub collateral_ratio: f64,
+    pub liquidation_price: f64,
+    pub horizons: Vec<HorizonRisk>,
+}
+
+impl PositionRisk {
+    pub fn print(&self) {
+        println!("  Collateral ratio:        {:.1}%", self.collateral_ratio * 100.0);
+        println!("  Liquidation price:       ${:.0}", self.liquidation_price);
+        for h in &self.horizons {
+            println!(
+                "  P(liquidated, {:>2}d):      {:.2}% (stranded {:.2}%)",
+                h.days,
+                h.liquidation_probability * 100.0,
+                h.stranded_probability * 100.0
+            );
+        }
+    }
+}
+
+/// Returns the step at which keepers liquidate the position (if any) and,
+/// for every earlier step, whether it was liquidatable but ignored.
+fn simulate_path(
+    cdp: &CDP,
+    path: &[f64],
+    config: &SimulationConfig,
+    mechanism: LiquidationMechanism,
+) -> (Option<usize>, Vec<bool>) {
+    let mut stranded = Vec::with_capacity(path.len());
+    let accrual = config
+        .accruing_collateral
+        .as_ref()
+        .map(|token| (token.growth(1.0 / (365.0 * STEPS_PER_DAY as f64)), token.accounting));
+    let mut cdp = cdp.clone();
+
+    for (step, &price) in path.iter().enumerate() {
+        if let (Some((growth, accounting)), true) = (accrual, step > 0) {
+            cdp.accrue(growth, accounting);
+        }
+        let liquidatable = cdp.is_liquidatable(price, config.min_collateral_ratio);
+        let profit = cdp.liquidation_profit(price, config.liquidation_penalty);
+        if liquidatable && keepers_will_act(profit, mechanism, &config.keepers) {
+            return (Some(step), stranded);
+        }
+        stranded.push(liquidatable);
+    }
+
+    (None, stranded)
+}
+
+pub fn assess_position(
+    position: &Position,
+    model: PriceModel,
+    mechanism: LiquidationMechanism,
+    config: &SimulationConfig,
+    runs: usize,
+) -> Result<PositionRisk> {
+    position.validate()?;
+    config.validate()?;
+
+    let max_days = HORIZON_DAYS[HORIZON_DAYS.len() - 1];
+    let path_config = PricePathConfig {
+        model,
+        blocks: max_days * STEPS_PER_DAY,
+        steps_per_year: 365.0 * STEPS_PER_DAY as f64,
+        ..Default::default()
+    };
+    let cdp = match config.accruing_collateral {
+        Some(_) => CDP::from_position(0, position.collateral, position.debt).accruing(),
+        None => CDP::from_position(0, position.collateral, position.debt),
+    };
+
+    let mut liquidated = [0usize; HORIZON_DAYS.len()];
+    let mut stranded = [0usize; HORIZON_DAYS.len()];
+    let mut rng = config.rng();
+
+    let raw_paths = (0..runs).step_by(PATH_CHUNK).flat_map(|first| {
+        generate_price_paths(&path_config, PATH_CHUNK.min(runs - first), &mut rng)
+    });
+    for raw in raw_paths {
+        // Paths start at the model's reference price; rescale to the oracle.
+        let scale = position.eth_price / raw[0];
+        let path: Vec<f64> = raw.iter().map(|p| p * scale).collect();
+
+        let (liquidated_at, stranded_by_step) = simulate_path(&cdp, &path, config, mechanism);
+        for (i, &days) in HORIZON_DAYS.iter().enumerate() {
+            let end = days * STEPS_PER_DAY;
+            match liquidated_at {
+                Some(step) if step <= end => liquidated[i] += 1,
+                _ if stranded_by_step.get(end).copied().unwrap_or(false) => stranded[i] += 1,
+                _ => {}
+            }
+        }
+    }
+
+    let n = runs.max(1) as f64;
+    let horizons = HORIZON_DAYS
+        .iter()
+        .enumerate()
+        .map(|(i, &days)| HorizonRisk {
+            days,
+            liquidation_probability: liquidated[i] as f64 / n,
+            stranded_probability: stranded[i] as f64 / n,
+        })
+        .collect();
+
+    Ok(PositionRisk {
+        position: *position,
+        model,
+        mechanism,
+        runs,
+        collateral_ratio: position.collateral_ratio(),
+        liquidation_price: position.liquidation_price(config.min_collateral_ratio),
+        horizons,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cascade::AccruingCollateral;
+
+    #[test]
+    fn test_probabilities_grow_with_horizon() {
+        let position = Position {
+            collateral: 10.0,
+            debt: 11_000.0,
+            eth_price: 2000.0,
+        };
+        let risk = assess_position(
+            &position,
+            PriceModel::GBM,
+            LiquidationMechanism::keeper_pool(),
+            &SimulationConfig::default(),
+            200,
+        )
+        .unwrap();
+
+        assert!((risk.liquidation_price - 1650.0).abs() < 1e-9);
+        assert_eq!(risk.horizons.len(), 3);
+        for pair in risk.horizons.windows(2) {
+            assert!(pair[1].liquidation_probability >= pair[0].liquidation_probability);
+        }
+    }
+
+    #[test]
+    fn test_already_liquidatable_position() {
+        let position = Position {
+            collateral: 10.0,
+            debt: 15_000.0,
+            eth_price: 2000.0,
+        };
+        let risk = assess_position(
+            &position,
+            PriceModel::GBM,
+            LiquidationMechanism::keeper_pool(),
+            &SimulationConfig::default(),
+            20,
+        )
+        .unwrap();
+
+        assert!((risk.horizons[0].liquidation_probability - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_collateral_yield_lowers_risk() {
+        let position = Position {
+            collateral: 10.0,
+            debt: 12_000.0,
+            eth_price: 2000.0,
+        };
+        let assess = |accruing_collateral| {
+            let config = SimulationConfig { seed: Some(5), accruing_collateral, ..Default::default() };
+            assess_position(&position, PriceModel::GBM, LiquidationMechanism::keeper_pool(), &config, 300).unwrap()
+        };
+        let plain = assess(None);
+        let staked = assess(Some(AccruingCollateral { annual_yield: 3.0, ..Default::default() }));
+
+        assert!(staked.horizons[2].liquidation_probability < plain.horizons[2].liquidation_probability);
+    }
+
+    #[test]
+    fn test_rejects_invalid_position() {
+        let position = Position {
+            collateral: 0.0,
+            debt: 1000.0,
+            eth_price: 2000.0,
+        };
+        let config = SimulationConfig::default();
+        assert!(assess_position(&position, PriceModel::GBM, LiquidationMechanism::Traditional, &config, 10).is_err());
+    }
+}
@@ -0,0 +1,238 @@
+//! Pluggable Price Paths
+//!
+//! The exogenous price move the cascade engine applies at the start of each
+//! block. The built-in `PriceScenario`s implement `PricePath`, as do
+//! pre-computed paths (`ScriptedPath`) and any closure
+//! `FnMut(block, price, rng) -> return`, so users can drive the engine with
+//! their own shocks through `CascadeSimulationBuilder::shocks`.
+//!
+//! ## Contract
+//! - `shock` returns a multiplicative move (`0.7` = 30% drop, `1.0` = flat)
+//!   applied on top of the price left by the previous block's liquidations,
+//!   so liquidation impact persists
+//! - The engine floors the price at $100 after each move
+//!
+//! ## Recorded Shocks
+//! `ShockSequence` holds the shocks a scenario actually drew in one run
+//! (see `cascade::replay_recording_shocks`). Replaying it against another
+//! mechanism or config with `cascade::replay_on_shocks` holds the price
+//! path fixed, separating path luck from the mechanism's effect. Shocks
+//! are recorded before `severity` is applied, so a replay at another
+//! severity rescales the same path.
+
+use std::path::Path;
+
+use rand::{Rng, RngCore};
+use rand_distr::{Distribution, Normal};
+use serde::{Deserialize, Serialize};
+
+use crate::cascade::PriceScenario;
+use crate::error::{Error, Result};
+
+pub trait PricePath {
+    /// Multiplicative price move for `block`, given the current oracle price.
+    fn shock(&mut self, block: usize, price: f64, rng: &mut dyn RngCore) -> f64;
+
+    fn name(&self) -> String {
+        "Custom price path".to_string()
+    }
+}
+
+impl PricePath for PriceScenario {
+    fn shock(&mut self, block: usize, _price: f64, rng: &mut dyn RngCore) -> f64 {
+        match self {
+            PriceScenario::GradualDecline => {
+                if block < 10 { 0.98 } else { 1.0 } // 2% drop per block
+            }
+            PriceScenario::FlashCrash => {
+                if block == 0 { 0.70 } else { 1.0 } // 30% instant drop
+            }
+            PriceScenario::VolatileCrash => {
+                let normal = Normal::new(-0.02, 0.05).unwrap();
+                let return_pct: f64 = normal.sample(rng);
+                let jump = if rng.gen::<f64>() < 0.1 { 0.9 } else { 1.0 }; // 10% chance of 10% jump down
+                (1.0 + return_pct) * jump
+            }
+            PriceScenario::BlackSwan => {
+                if block == 0 {
+                    0.50 // 50% instant drop
+                } else if block < 20 {
+                    0.99 // Continued 1% decline
+                } else {
+                    1.0
+                }
+            }
+        }
+    }
+
+    fn name(&self) -> String {
+        PriceScenario::name(self).to_string()
+    }
+}
+
+/// A pre-computed price per block, applied as block-to-block returns. The
+/// first entry is the opening price; the price holds once the path runs out.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptedPath {
+    prices: Vec<f64>,
+}
+
+impl ScriptedPath {
+    pub fn new(prices: Vec<f64>) -> Result<Self> {
+        if prices.is_empty() || prices.iter().any(|&p| !p.is_finite() || p <= 0.0) {
+            return Err(Error::Invalid("price_path must be non-empty and positive".to_string()));
+        }
+        Ok(Self { prices })
+    }
+
+    pub fn opening_price(&self) -> f64 {
+        self.prices[0]
+    }
+}
+
+impl PricePath for ScriptedPath {
+    fn shock(&mut self, block: usize, _price: f64, _rng: &mut dyn RngCore) -> f64 {
+        match (self.prices.get(block), self.prices.get(block + 1)) {
+            (Some(&from), Some(&to)) => to / from,
+            _ => 1.0,
+        }
+    }
+
+    fn name(&self) -> String {
+        format!("Scripted path ({} prices)", self.prices.len())
+    }
+}
+
+/// The shocks one run drew from its scenario, per block. The price holds
+/// once the sequence runs out.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShockSequence {
+    pub scenario: PriceScenario, // Scenario the shocks were drawn from
+    pub seed: u64,               // Per-run seed of the recorded run
+    pub shocks: Vec<f64>,
+}
+
+impl ShockSequence {
+    pub fn validate(&self) -> Result<()> {
+        if self.shocks.iter().any(|&s| !s.is_finite() || s <= 0.0) {
+            return Err(Error::Invalid("shocks must be positive".to_string()));
+        }
+        Ok(())
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        crate::results::save_json(path, self)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let sequence: Self = crate::results::load_json(path)?;
+        sequence.validate()?;
+        Ok(sequence)
+    }
+}
+
+impl PricePath for ShockSequence {
+    fn shock(&mut self, block: usize, _price: f64, _rng: &mut dyn RngCore) -> f64 {
+        self.shocks.get(block).copied().unwrap_or(1.0)
+    }
+
+    fn name(&self) -> String {
+        format!("Recorded {} (run seed {})", self.scenario.name(), self.seed)
+    }
+}
+
+impl<F> PricePath for F
+where
+    F: FnMut(usize, f64, &mut dyn RngCore) -> f64,
+{
+    fn shock(&mut self, block: usize, price: f64, rng: &mut dyn RngCore) -> f64 {
+        self(block, price, rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cascade::{CascadeSimulationBuilder, SimulationConfig};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_scripted_path_applies_returns() {
+        let mut path = ScriptedPath::new(vec![2000.0, 1800.0, 1800.0, 900.0]).unwrap();
+        let mut rng = StdRng::seed_from_u64(0);
+        let shocks: Vec<f64> = (0..5).map(|b| path.shock(b, 0.0, &mut rng)).collect();
+
+        assert_eq!(shocks, vec![0.9, 1.0, 0.5, 1.0, 1.0]);
+        assert!(ScriptedPath::new(Vec::new()).is_err());
+        assert!(ScriptedPath::new(vec![2000.0, f64::NAN]).is_err());
+    }
+
+    #[test]
+    fn test_severity_scales_log_moves() {
+        let run = |severity: f64, scenario: PriceScenario| {
+            let config = SimulationConfig { severity, price_impact_per_eth: 0.0, max_blocks: 1, ..Default::default() };
+            let mut rng = StdRng::seed_from_u64(2);
+            let mut sim = CascadeSimulationBuilder::new().config(config).scenario(scenario).build(&mut rng).unwrap();
+            sim.run_with_events(&mut rng).1[0].price_after_shock
+        };
+
+        assert!((run(2.0, PriceScenario::FlashCrash) - 2000.0 * 0.49).abs() < 1e-6);
+        assert_eq!(run(0.0, PriceScenario::FlashCrash), 2000.0);
+        let (mild, base) = (run(0.5, PriceScenario::VolatileCrash), run(1.0, PriceScenario::VolatileCrash));
+        assert!(((mild / 2000.0).ln() * 2.0 - (base / 2000.0).ln()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_closure_drives_the_engine() {
+        // A 5% drop per block while the price is above $1500
+        let shocks = |_block: usize, price: f64, _rng: &mut dyn RngCore| if price > 1500.0 { 0.95 } else { 1.0 };
+        let config = SimulationConfig { price_impact_per_eth: 0.0, ..Default::default() };
+        let mut rng = StdRng::seed_from_u64(4);
+        let mut sim = CascadeSimulationBuilder::new().config(config).shocks(shocks).build(&mut rng).unwrap();
+        let (result, events) = sim.run_with_events(&mut rng);
+
+        assert!(events.iter().all(|e| e.price_after_shock >= 1500.0 * 0.95));
+        assert!(result.final_price <= 1500.0);
+    }
+
+    #[test]
+    fn test_scenario_as_path_matches_stock_runs() {
+        let config = SimulationConfig { seed: Some(12), ..Default::default() };
+        let stock = crate::cascade::run_cascade_simulation(
+            crate::cascade::LiquidationMechanism::keeper_pool(),
+            PriceScenario::VolatileCrash,
+            1,
+            &config,
+        );
+        let mut rng = config.run_rng(stock[0].seed.unwrap());
+        let mut sim = CascadeSimulationBuilder::new()
+            .config(config)
+            .shocks(PriceScenario::VolatileCrash)
+            .build(&mut rng)
+            .unwrap();
+
+        assert_eq!(sim.run(&mut rng).bad_debt, stock[0].bad_debt);
+    }
+
+    #[test]
+    fn test_recorded_shocks_fix_the_path() {
+        use crate::cascade::{replay_on_shocks, replay_recording_shocks, LiquidationMechanism};
+
+        let config = SimulationConfig::default();
+        let (recorded, events, shocks) =
+            replay_recording_shocks(LiquidationMechanism::Traditional, PriceScenario::VolatileCrash, &config, 77);
+        assert_eq!(shocks.shocks.len(), events.len());
+
+        // The same mechanism on its own shocks is the same run
+        let (replayed, _) = replay_on_shocks(LiquidationMechanism::Traditional, &shocks, &config, shocks.seed);
+        assert_eq!(replayed.bad_debt, recorded.bad_debt);
+
+        // Another mechanism sees the same exogenous moves, block for block
+        let (_, pool_events) = replay_on_shocks(LiquidationMechanism::keeper_pool(), &shocks, &config, shocks.seed);
+        for (event, &shock) in pool_events.iter().zip(&shocks.shocks) {
+            assert!((event.price_after_shock - (event.price_start * shock).max(100.0)).abs() < 1e-9);
+        }
+        assert!(ShockSequence { shocks: vec![0.9, -1.0], ..shocks }.validate().is_err());
+    }
+}
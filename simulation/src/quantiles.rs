@@ -0,0 +1,209 @@
+//! Streaming Quantiles
+//!
+//! A merging t-digest: quantiles and tail means of a sample seen one value
+//! at a time, in memory bounded by the compression rather than the sample
+//! size. Values are buffered, then sorted and merged into weighted
+//! centroids whose size shrinks toward either tail, so the extremes a VaR
+//! or CVaR reads stay close to exact while the bulk is summarized coarsely.
+//!
+//! ## Accuracy
+//! - Count, mean, min and max are exact
+//! - Centroid sizes follow the arcsine scale function, so at most about
+//!   `compression` centroids are kept and samples of up to about
+//!   `compression / 2` values are kept exactly
+//! - At the default compression (tens of kilobytes), the 99.9th percentile
+//!   and 99% tail mean of a heavy-tailed sample of 200,000 land within 1%
+//!   of the exact ones
+
+/// Default compression: about this many centroids are kept.
+pub const DEFAULT_COMPRESSION: f64 = 1000.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Centroid {
+    mean: f64,
+    weight: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct TDigest {
+    compression: f64,
+    centroids: Vec<Centroid>, // Sorted by mean
+    buffer: Vec<f64>,         // Values not merged yet
+    count: usize,
+    sum: f64,
+    min: f64,
+    max: f64,
+}
+
+impl Default for TDigest {
+    fn default() -> Self {
+        Self::new(DEFAULT_COMPRESSION)
+    }
+}
+
+impl TDigest {
+    pub fn new(compression: f64) -> Self {
+        Self {
+            compression,
+            centroids: Vec::new(),
+            buffer: Vec::new(),
+            count: 0,
+            sum: 0.0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+
+    pub fn add(&mut self, value: f64) {
+        self.buffer.push(value);
+        self.count += 1;
+        self.sum += value;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        if self.buffer.len() as f64 >= 5.0 * self.compression {
+            self.merge();
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn mean(&self) -> f64 {
+        if self.count == 0 { 0.0 } else { self.sum / self.count as f64 }
+    }
+
+    pub fn max(&self) -> f64 {
+        if self.count == 0 { 0.0 } else { self.max }
+    }
+
+    /// Scale function: a centroid may span at most one unit of it.
+    fn scale(&self, q: f64) -> f64 {
+        self.compression / (2.0 * std::f64::consts::PI) * (2.0 * q - 1.0).clamp(-1.0, 1.0).asin()
+    }
+
+    /// Folds the buffer into the centroids, merging neighbors while the
+    /// merged centroid spans at most one unit of `scale`.
+    fn merge(&mut self) {
+        if self.buffer.is_empty() {
+            return;
+        }
+        let mut incoming: Vec<Centroid> =
+            self.buffer.drain(..).map(|mean| Centroid { mean, weight: 1.0 }).collect();
+        incoming.append(&mut self.centroids);
+        incoming.sort_by(|a, b| a.mean.total_cmp(&b.mean));
+
+        let total = self.count as f64;
+        let mut merged: Vec<Centroid> = Vec::with_capacity(self.compression as usize);
+        let mut before = 0.0; // Weight of the centroids already closed
+        for next in incoming {
+            if let Some(last) = merged.last_mut() {
+                let weight = last.weight + next.weight;
+                if self.scale((before + weight) / total) - self.scale(before / total) <= 1.0 {
+                    last.mean += (next.mean - last.mean) * next.weight / weight;
+                    last.weight = weight;
+                    continue;
+                }
+                before += last.weight;
+            }
+            merged.push(next);
+        }
+        self.centroids = merged;
+    }
+
+    /// The `p` quantile, interpolating between centroid centers.
+    pub fn quantile(&mut self, p: f64) -> f64 {
+        self.merge();
+        if self.count == 0 {
+            return 0.0;
+        }
+        let target = p.clamp(0.0, 1.0) * self.count as f64;
+        let mut previous = (0.0, self.min); // (cumulative weight, value) at the last center
+        let mut cumulative = 0.0;
+        for centroid in &self.centroids {
+            let center = cumulative + centroid.weight / 2.0;
+            if target < center {
+                let span = center - previous.0;
+                let t = if span > 0.0 { (target - previous.0) / span } else { 0.0 };
+                return previous.1 + (centroid.mean - previous.1) * t;
+            }
+            previous = (center, centroid.mean);
+            cumulative += centroid.weight;
+        }
+        let span = cumulative - previous.0;
+        let t = if span > 0.0 { (target - previous.0) / span } else { 1.0 };
+        previous.1 + (self.max - previous.1) * t.min(1.0)
+    }
+
+    /// Mean of the values above the `p` quantile (the expected shortfall
+    /// beyond it); the maximum when nothing lies above.
+    pub fn tail_mean(&mut self, p: f64) -> f64 {
+        self.merge();
+        let total = self.count as f64;
+        let cutoff = p.clamp(0.0, 1.0) * total;
+        if total - cutoff <= 0.0 {
+            return self.max();
+        }
+        let mut cumulative = 0.0;
+        let mut tail = 0.0;
+        for centroid in &self.centroids {
+            let above = (cumulative + centroid.weight - cutoff).clamp(0.0, centroid.weight);
+            tail += above * centroid.mean;
+            cumulative += centroid.weight;
+        }
+        tail / (total - cutoff)
+    }
+
+    /// Centroids kept, after merging what is buffered.
+    pub fn size(&mut self) -> usize {
+        self.merge();
+        self.centroids.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn test_small_samples_are_exact() {
+        let mut digest = TDigest::default();
+        for value in [5.0, 1.0, 3.0, 2.0, 4.0] {
+            digest.add(value);
+        }
+
+        assert_eq!(digest.size(), 5);
+        assert_eq!(digest.quantile(0.5), 3.0);
+        assert_eq!(digest.quantile(1.0), 5.0);
+        assert_eq!(digest.tail_mean(0.6), 4.5);
+        assert_eq!(digest.mean(), 3.0);
+    }
+
+    #[test]
+    fn test_heavy_tail_stays_close_in_bounded_memory() {
+        // Mostly zero, with a Pareto tail: the shape of bad debt across runs
+        let mut rng = StdRng::seed_from_u64(11);
+        let mut digest = TDigest::default();
+        let mut exact = Vec::new();
+        for _ in 0..200_000 {
+            let value = if rng.gen::<f64>() < 0.7 { 0.0 } else { 1000.0 / rng.gen::<f64>().powf(1.0 / 1.5) };
+            digest.add(value);
+            exact.push(value);
+        }
+        exact.sort_by(f64::total_cmp);
+        let n = exact.len();
+
+        assert!(digest.size() <= DEFAULT_COMPRESSION as usize);
+        assert_eq!(digest.quantile(0.5), 0.0);
+        for p in [0.9, 0.95, 0.99, 0.999] {
+            let truth = exact[((n - 1) as f64 * p).round() as usize];
+            let estimate = digest.quantile(p);
+            assert!((estimate - truth).abs() / truth < 0.01, "p{}: {} vs {}", p, estimate, truth);
+        }
+        let tail = &exact[(n as f64 * 0.99).ceil() as usize..];
+        let truth = tail.iter().sum::<f64>() / tail.len() as f64;
+        assert!((digest.tail_mean(0.99) - truth).abs() / truth < 0.01);
+    }
+}
@@ -0,0 +1,335 @@
+//! Protocol Parameter Recommendations
+//!
+//! Sweeps liquidation penalty, minimum collateral ratio, and keeper pool
+//! split, checks every combination against governance risk targets, and
+//! proposes the cheapest combination that meets them, with the evidence
+//! tables a parameter-change proposal needs.
+//!
+//! ## Method
+//! - Targets use the `alerts` syntax (e.g. `insolvency_prob<0.001`) and must
+//!   hold under every price model for a candidate to be feasible
+//! - The synthetic CDP book is opened relative to each candidate MCR, so a
+//!   stricter MCR does not start the run with the whole book underwater
+//! - Among feasible candidates the recommendation prefers the lowest MCR,
+//!   then the lowest penalty, then the lowest keeper share (capital
+//!   efficiency for borrowers first, protocol revenue last)
+//! - Sensitivity tables vary one parameter around the recommendation
+//! - Capital requirement is the worst 99% VaR of bad debt across models
+
+use crate::alerts::{self, Assertion, MetricSource, Violation, MONTE_CARLO_METRICS};
+use crate::cascade::{LiquidationMechanism, SimulationConfig};
+use crate::error::Result;
+use crate::monte_carlo::{run_monte_carlo_with_config, MonteCarloResult, PriceModel};
+
+const REPORT_METRICS: [&str; 4] = ["insolvency_prob", "bad_debt_prob", "mean_bad_debt", "var_99"];
+
+#[derive(Debug, Clone)]
+pub struct ParameterGrid {
+    pub penalties: Vec<f64>,
+    pub min_collateral_ratios: Vec<f64>,
+    pub keeper_shares: Vec<f64>,
+}
+
+impl Default for ParameterGrid {
+    fn default() -> Self {
+        Self {
+            penalties: vec![0.05, 0.08, 0.10, 0.13, 0.15],
+            min_collateral_ratios: vec![1.3, 1.5, 1.75, 2.0],
+            keeper_shares: vec![0.6, 0.7, 0.8, 0.9],
+        }
+    }
+}
+
+impl ParameterGrid {
+    pub fn len(&self) -> usize {
+        self.penalties.len() * self.min_collateral_ratios.len() * self.keeper_shares.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Parameter {
+    Penalty,
+    MinCollateralRatio,
+    KeeperShare,
+}
+
+impl Parameter {
+    pub fn all() -> Vec<Self> {
+        vec![Self::Penalty, Self::MinCollateralRatio, Self::KeeperShare]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Penalty => "Liquidation penalty",
+            Self::MinCollateralRatio => "Minimum collateral ratio",
+            Self::KeeperShare => "Keeper pool share",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub penalty: f64,
+    pub min_collateral_ratio: f64,
+    pub keeper_share: f64,
+    pub results: Vec<MonteCarloResult>, // One per price model
+    pub violations: Vec<Violation>,
+}
+
+impl Candidate {
+    pub fn feasible(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    pub fn value(&self, parameter: Parameter) -> f64 {
+        match parameter {
+            Parameter::Penalty => self.penalty,
+            Parameter::MinCollateralRatio => self.min_collateral_ratio,
+            Parameter::KeeperShare => self.keeper_share,
+        }
+    }
+
+    /// Worst (largest) value of a metric across price models.
+    pub fn worst(&self, metric: &str) -> f64 {
+        self.results
+            .iter()
+            .filter_map(|r| r.metric(metric))
+            .fold(0.0, f64::max)
+    }
+
+    fn label(&self) -> String {
+        format!(
+            "penalty {:.0}% / MCR {:.0}% / split {:.0}/{:.0}",
+            self.penalty * 100.0,
+            self.min_collateral_ratio * 100.0,
+            self.keeper_share * 100.0,
+            (1.0 - self.keeper_share) * 100.0
+        )
+    }
+}
+
+fn candidate_config(base: &SimulationConfig, penalty: f64, mcr: f64) -> SimulationConfig {
+    let mut config = base.clone();
+    let dist = &mut config.cdp_distribution;
+    let spread = dist.max_ratio - dist.min_ratio;
+    let margin = dist.min_ratio - base.min_collateral_ratio;
+    dist.min_ratio = mcr + margin;
+    dist.max_ratio = mcr + margin + spread;
+
+    config.liquidation_penalty = penalty;
+    config.min_collateral_ratio = mcr;
+    config
+}
+
+#[derive(Debug, Clone)]
+pub struct Recommendation {
+    pub targets: Vec<Assertion>,
+    pub models: Vec<PriceModel>,
+    pub runs: usize,
+    pub candidates: Vec<Candidate>,
+    recommended: Option<usize>,
+}
+
+impl Recommendation {
+    pub fn recommended(&self) -> Option<&Candidate> {
+        self.recommended.map(|i| &self.candidates[i])
+    }
+
+    /// Surplus buffer covering bad debt in 99% of runs under every model.
+    pub fn capital_requirement(&self) -> Option<f64> {
+        self.recommended().map(|c| c.worst("var_99"))
+    }
+
+    /// Candidates that differ from the recommendation only in `parameter`.
+    pub fn sensitivity(&self, parameter: Parameter) -> Vec<&Candidate> {
+        let Some(best) = self.recommended() else {
+            return Vec::new();
+        };
+        let mut rows: Vec<&Candidate> = self
+            .candidates
+            .iter()
+            .filter(|c| {
+                Parameter::all()
+                    .into_iter()
+                    .filter(|&p| p != parameter)
+                    .all(|p| c.value(p) == best.value(p))
+            })
+            .collect();
+        rows.sort_by(|a, b| a.value(parameter).partial_cmp(&b.value(parameter)).unwrap());
+        rows
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::from("## Parameter Recommendation\n\n");
+
+        let targets: Vec<String> = self.targets.iter().map(|t| format!("`{}`", t)).collect();
+        out.push_str(&format!("- Targets: {}\n", targets.join(", ")));
+        let models: Vec<&str> = self.models.iter().map(|m| m.name()).collect();
+        out.push_str(&format!("- Price models: {}\n", models.join(", ")));
+        out.push_str(&format!(
+            "- Candidates: {} ({} feasible), {} runs per model\n\n",
+            self.candidates.len(),
+            self.candidates.iter().filter(|c| c.feasible()).count(),
+            self.runs
+        ));
+
+        match self.recommended() {
+            Some(best) => {
+                out.push_str(&format!("**Recommended:** {}\n\n", best.label()));
+                out.push_str(&format!(
+                    "**Capital requirement:** ${:.0} surplus buffer (worst-model VaR 99%)\n\n",
+                    self.capital_requirement().unwrap_or(0.0)
+                ));
+                for parameter in Parameter::all() {
+                    out.push_str(&format!("### Sensitivity: {}\n\n", parameter.name()));
+                    push_table(&mut out, &self.sensitivity(parameter));
+                }
+            }
+            None => {
+                out.push_str("**No candidate meets every target.** Closest misses:\n\n");
+                let mut misses: Vec<&Candidate> = self.candidates.iter().collect();
+                misses.sort_by_key(|c| c.violations.len());
+                for c in misses.iter().take(3) {
+                    out.push_str(&format!("- {}: {} violation(s)\n", c.label(), c.violations.len()));
+                    for v in &c.violations {
+                        out.push_str(&format!("  - {}\n", v));
+                    }
+                }
+                out.push('\n');
+            }
+        }
+
+        out.push_str("### All Candidates\n\n");
+        let all: Vec<&Candidate> = self.candidates.iter().collect();
+        push_table(&mut out, &all);
+        out.push_str("_Metrics are the worst value across price models._\n");
+        out
+    }
+}
+
+fn push_table(out: &mut String, rows: &[&Candidate]) {
+    out.push_str("| Penalty | MCR | Split | P(insolvency) | P(bad debt) | Mean bad debt | VaR 99% | Meets targets |\n");
+    out.push_str("|--------:|----:|------:|--------------:|------------:|--------------:|--------:|:-------------:|\n");
+    for c in rows {
+        let [insolvency, bad_debt, mean, var] = REPORT_METRICS.map(|m| c.worst(m));
+        out.push_str(&format!(
+            "| {:.0}% | {:.0}% | {:.0}/{:.0} | {:.2}% | {:.2}% | ${:.0} | ${:.0} | {} |\n",
+            c.penalty * 100.0,
+            c.min_collateral_ratio * 100.0,
+            c.keeper_share * 100.0,
+            (1.0 - c.keeper_share) * 100.0,
+            insolvency * 100.0,
+            bad_debt * 100.0,
+            mean,
+            var,
+            if c.feasible() { "yes" } else { "no" }
+        ));
+    }
+    out.push('\n');
+}
+
+/// Evaluates every grid point under the keeper pool mechanism.
+pub fn recommend(
+    targets: &[Assertion],
+    grid: &ParameterGrid,
+    models: &[PriceModel],
+    runs: usize,
+    base: &SimulationConfig,
+) -> Result<Recommendation> {
+    alerts::validate_metrics(targets, &[MONTE_CARLO_METRICS])?;
+
+    let mut candidates = Vec::with_capacity(grid.len());
+    for &mcr in &grid.min_collateral_ratios {
+        for &penalty in &grid.penalties {
+            for &keeper_share in &grid.keeper_shares {
+                let config = candidate_config(base, penalty, mcr);
+                config.validate()?;
+                let mechanism = LiquidationMechanism::KeeperPool { split: keeper_share };
+                mechanism.validate()?;
+
+                let mut candidate = Candidate {
+                    penalty,
+                    min_collateral_ratio: mcr,
+                    keeper_share,
+                    results: Vec::with_capacity(models.len()),
+                    violations: Vec::new(),
+                };
+                for &model in models {
+                    let result = run_monte_carlo_with_config(model, mechanism, runs, &config);
+                    let context = format!("{} under {}", candidate.label(), model.name());
+                    candidate.violations.extend(alerts::check_all(targets, &result, &context)?);
+                    candidate.results.push(result);
+                }
+                candidates.push(candidate);
+            }
+        }
+    }
+
+    let recommended = candidates
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| c.feasible())
+        .min_by(|(_, a), (_, b)| {
+            Parameter::all()
+                .into_iter()
+                .map(|p| a.value(p).partial_cmp(&b.value(p)).unwrap())
+                .find(|o| o.is_ne())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })
+        .map(|(i, _)| i);
+
+    Ok(Recommendation {
+        targets: targets.to_vec(),
+        models: models.to_vec(),
+        runs,
+        candidates,
+        recommended,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn small_grid() -> ParameterGrid {
+        ParameterGrid {
+            penalties: vec![0.13, 0.08],
+            min_collateral_ratios: vec![1.5],
+            keeper_shares: vec![0.8, 0.7],
+        }
+    }
+
+    #[test]
+    fn test_picks_cheapest_feasible_candidate() {
+        let targets = vec![Assertion::parse("insolvency_prob<=1").unwrap()];
+        let rec = recommend(&targets, &small_grid(), &[PriceModel::GBM], 3, &SimulationConfig::default()).unwrap();
+
+        let best = rec.recommended().unwrap();
+        assert_eq!(rec.candidates.len(), 4);
+        assert_eq!((best.penalty, best.keeper_share), (0.08, 0.7));
+        assert_eq!(rec.sensitivity(Parameter::Penalty).len(), 2);
+        assert_eq!(rec.sensitivity(Parameter::MinCollateralRatio).len(), 1);
+        assert!(rec.to_markdown().contains("**Recommended:** penalty 8% / MCR 150% / split 70/30"));
+    }
+
+    #[test]
+    fn test_unreachable_target_has_no_recommendation() {
+        let targets = vec![Assertion::parse("insolvency_prob<0").unwrap()];
+        let rec = recommend(&targets, &small_grid(), &[PriceModel::GBM], 2, &SimulationConfig::default()).unwrap();
+
+        assert!(rec.recommended().is_none());
+        assert!(rec.capital_requirement().is_none());
+        assert!(rec.to_markdown().contains("No candidate meets every target"));
+    }
+
+    #[test]
+    fn test_candidate_book_tracks_mcr() {
+        let config = candidate_config(&SimulationConfig::default(), 0.1, 2.0);
+        assert!((config.cdp_distribution.min_ratio - 2.0).abs() < 1e-9);
+        assert!((config.cdp_distribution.max_ratio - 3.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,288 @@
+//! HTML Reports
+//!
+//! A single self-contained HTML file per Monte Carlo campaign, to attach to
+//! a governance forum post as is: the run's provenance, a summary table,
+//! Fair-vs-Traditional deltas with significance flags (see `diff`), and
+//! bar charts drawn as inline SVG. No scripts, stylesheets or images are
+//! loaded from anywhere else.
+//!
+//! ```bash
+//! fair-sim monte-carlo --runs 2000 --seed 42 --html report.html
+//! ```
+//!
+//! `table` renders the same summaries as Markdown or LaTeX tables.
+
+pub mod table;
+
+use std::fmt::Write as _;
+use std::path::Path;
+
+use crate::diff::{diff_cell, MetricDelta, Significance};
+use crate::error::Result;
+use crate::manifest::RunManifest;
+use crate::monte_carlo::{MonteCarloResult, PriceModel};
+
+const STYLE: &str = "body{font-family:system-ui,sans-serif;max-width:960px;margin:2em auto;color:#222}\
+table{border-collapse:collapse;margin:1em 0}th,td{border:1px solid #ccc;padding:4px 10px;text-align:right}\
+th:first-child,td:first-child{text-align:left}.better{color:#1a7f37}.worse{color:#c62828}\
+.meta{color:#666;font-size:0.9em}";
+
+const PALETTE: [&str; 4] = ["#d1495b", "#2e86ab", "#edae49", "#66a182"];
+
+/// Escapes text for HTML element content and attribute values.
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn money(x: f64) -> String {
+    format!("${:.0}", x)
+}
+
+/// Grouped bar chart as inline SVG: a group per category, a bar per series.
+pub fn svg_bar_chart(title: &str, categories: &[&str], series: &[(String, Vec<f64>)]) -> String {
+    let (width, height, left, bottom, top) = (900.0, 320.0, 80.0, 60.0, 40.0);
+    let max = series.iter().flat_map(|(_, values)| values).copied().fold(0.0, f64::max).max(1.0);
+    let plot_height = height - bottom - top;
+    let group = (width - left) / categories.len().max(1) as f64;
+    let bar = group * 0.8 / series.len().max(1) as f64;
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" font-size=\"12\">\
+         <text x=\"{x}\" y=\"20\" text-anchor=\"middle\" font-size=\"15\">{t}</text>\
+         <line x1=\"{left}\" y1=\"{top}\" x2=\"{left}\" y2=\"{b}\" stroke=\"#444\"/>\
+         <line x1=\"{left}\" y1=\"{b}\" x2=\"{w}\" y2=\"{b}\" stroke=\"#444\"/>\
+         <text x=\"{tick}\" y=\"{ty}\" text-anchor=\"end\">{max}</text>",
+        w = width,
+        h = height,
+        x = width / 2.0,
+        t = escape(title),
+        b = height - bottom,
+        tick = left - 5.0,
+        ty = top + 4.0,
+        max = money(max),
+    );
+    for (c, category) in categories.iter().enumerate() {
+        let x0 = left + group * c as f64 + group * 0.1;
+        let _ = write!(
+            svg,
+            "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\">{}</text>",
+            x0 + group * 0.4,
+            height - bottom + 16.0,
+            escape(category)
+        );
+        for (s, (label, values)) in series.iter().enumerate() {
+            let value = values.get(c).copied().unwrap_or(0.0);
+            let bar_height = value / max * plot_height;
+            let _ = write!(
+                svg,
+                "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"{}\"><title>{}: {}</title></rect>",
+                x0 + bar * s as f64,
+                height - bottom - bar_height,
+                bar,
+                bar_height,
+                PALETTE[s % PALETTE.len()],
+                escape(label),
+                money(value)
+            );
+        }
+    }
+    for (s, (label, _)) in series.iter().enumerate() {
+        let x = left + 10.0 + 260.0 * s as f64;
+        let _ = write!(
+            svg,
+            "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"12\" height=\"12\" fill=\"{}\"/><text x=\"{:.1}\" y=\"{:.1}\">{}</text>",
+            x,
+            height - 22.0,
+            PALETTE[s % PALETTE.len()],
+            x + 16.0,
+            height - 12.0,
+            escape(label)
+        );
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+fn table(out: &mut String, headers: &[&str], rows: impl IntoIterator<Item = Vec<String>>) {
+    out.push_str("<table><tr>");
+    for header in headers {
+        let _ = write!(out, "<th>{}</th>", escape(header));
+    }
+    out.push_str("</tr>");
+    for row in rows {
+        out.push_str("<tr>");
+        for cell in row {
+            let _ = write!(out, "<td>{}</td>", cell);
+        }
+        out.push_str("</tr>");
+    }
+    out.push_str("</table>");
+}
+
+/// A delta cell, green when Fair improves on Traditional. Lower is better
+/// for every metric but participation.
+fn delta_cell(delta: &MetricDelta) -> String {
+    let change = match delta.delta_pct() {
+        Some(pct) => format!("{:+.1}%", pct),
+        None => format!("{:+.4}", delta.delta()),
+    };
+    let higher_is_better = delta.metric == "Participation";
+    let class = match delta.delta().partial_cmp(&0.0) {
+        Some(std::cmp::Ordering::Equal) | None => "",
+        Some(std::cmp::Ordering::Greater) if higher_is_better => "better",
+        Some(std::cmp::Ordering::Less) if !higher_is_better => "better",
+        _ => "worse",
+    };
+    let flag = match delta.significance {
+        Significance::NotTested => "",
+        significance => significance.flag(),
+    };
+    format!("<span class=\"{}\">{}</span> {}", class, change, flag)
+}
+
+fn is_fair(result: &MonteCarloResult) -> bool {
+    result.mechanism.is_keeper_pool()
+}
+
+/// Renders a campaign's results as a self-contained HTML page.
+pub fn monte_carlo_html(title: &str, results: &[MonteCarloResult], manifest: Option<&RunManifest>) -> String {
+    let mut models: Vec<PriceModel> = Vec::new();
+    for result in results {
+        if !models.contains(&result.model) {
+            models.push(result.model);
+        }
+    }
+
+    let mut out = format!(
+        "<!DOCTYPE html><html lang=\"en\"><head><meta charset=\"utf-8\"><title>{t}</title><style>{STYLE}</style></head><body><h1>{t}</h1>",
+        t = escape(title)
+    );
+    if let Some(manifest) = manifest {
+        let _ = write!(
+            out,
+            "<p class=\"meta\">fair-simulation {} ({}{}), seed {}, {} runs per cell.<br>Reproduce: <code>{}</code></p>",
+            escape(&manifest.crate_version),
+            escape(manifest.git_hash.as_deref().map_or("unknown commit", |hash| &hash[..hash.len().min(7)])),
+            if manifest.git_dirty { ", uncommitted changes" } else { "" },
+            manifest.seed.map_or("unseeded".to_string(), |seed| seed.to_string()),
+            manifest.setup.runs.unwrap_or_default(),
+            escape(&manifest.command.join(" ")),
+        );
+    }
+
+    out.push_str("<h2>Summary</h2>");
+    table(
+        &mut out,
+        &["Model", "Mechanism", "Mean bad debt", "P(bad debt)", "VaR 99%", "CVaR 99%", "P(insolvency)"],
+        results.iter().map(|r| {
+            vec![
+                escape(r.model.name()),
+                escape(&r.mechanism.name()),
+                money(r.mean_bad_debt),
+                format!("{:.1}%", r.bad_debt_probability * 100.0),
+                money(r.var_99),
+                money(r.cvar_99),
+                format!("{:.2}%", r.insolvency_probability * 100.0),
+            ]
+        }),
+    );
+
+    let pairs: Vec<(&MonteCarloResult, &MonteCarloResult)> = models
+        .iter()
+        .filter_map(|&model| {
+            let find = |fair: bool| results.iter().find(|r| r.model == model && is_fair(r) == fair);
+            find(false).zip(find(true))
+        })
+        .collect();
+    if !pairs.is_empty() {
+        out.push_str("<h2>Fair vs Traditional</h2>");
+        let diffs: Vec<_> = pairs.iter().map(|(trad, fair)| diff_cell(trad, fair)).collect();
+        let metrics: Vec<&str> = diffs[0].deltas.iter().map(|d| d.metric).collect();
+        let mut headers = vec!["Model"];
+        headers.extend(&metrics);
+        table(
+            &mut out,
+            &headers,
+            diffs.iter().map(|cell| {
+                std::iter::once(escape(&cell.label)).chain(cell.deltas.iter().map(delta_cell)).collect()
+            }),
+        );
+        out.push_str(
+            "<p class=\"meta\">Change from Traditional to Fair. <code>**</code> p &lt; 0.01, <code>*</code> p &lt; 0.05 \
+             (Welch / two-proportion z-test); tail quantiles are not tested.</p>",
+        );
+    }
+
+    out.push_str("<h2>Charts</h2>");
+    let categories: Vec<&str> = models.iter().map(|m| m.name()).collect();
+    let mut mechanisms: Vec<String> = Vec::new();
+    for result in results {
+        if !mechanisms.contains(&result.mechanism.name()) {
+            mechanisms.push(result.mechanism.name());
+        }
+    }
+    for (title, metric) in [
+        ("Mean bad debt", (|r: &MonteCarloResult| r.mean_bad_debt) as fn(&MonteCarloResult) -> f64),
+        ("VaR 99%", |r| r.var_99),
+    ] {
+        let series: Vec<(String, Vec<f64>)> = mechanisms
+            .iter()
+            .map(|name| {
+                let values = models
+                    .iter()
+                    .map(|&model| {
+                        results.iter().find(|r| r.model == model && &r.mechanism.name() == name).map_or(0.0, metric)
+                    })
+                    .collect();
+                (name.clone(), values)
+            })
+            .collect();
+        out.push_str(&svg_bar_chart(title, &categories, &series));
+    }
+
+    out.push_str("</body></html>\n");
+    out
+}
+
+pub fn save_html(path: impl AsRef<Path>, html: &str) -> Result<()> {
+    std::fs::write(path, html)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cascade::{LiquidationMechanism, SimulationConfig};
+    use crate::monte_carlo::run_monte_carlo_with_config;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("<a href=\"x\">&</a>"), "&lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;");
+    }
+
+    #[test]
+    fn test_report_is_self_contained() {
+        let config = SimulationConfig { seed: Some(1), ..Default::default() };
+        let results: Vec<_> = LiquidationMechanism::all()
+            .into_iter()
+            .map(|mechanism| run_monte_carlo_with_config(PriceModel::JumpDiffusion, mechanism, 20, &config))
+            .collect();
+        let html = monte_carlo_html("Penalty <13%>", &results, None);
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<title>Penalty &lt;13%&gt;</title>"));
+        assert!(html.contains("<h2>Fair vs Traditional</h2>"));
+        assert_eq!(html.matches("<svg").count(), 2);
+        assert!(!html.contains("<script") && !html.contains("src=") && !html.contains("href="));
+    }
+
+    #[test]
+    fn test_single_mechanism_skips_deltas() {
+        let config = SimulationConfig { seed: Some(1), ..Default::default() };
+        let results = vec![run_monte_carlo_with_config(PriceModel::GBM, LiquidationMechanism::keeper_pool(), 10, &config)];
+        let html = monte_carlo_html("Pool only", &results, None);
+
+        assert!(!html.contains("Fair vs Traditional"));
+        assert_eq!(html.matches("<rect").count(), 2 * 2); // A bar and a legend swatch per chart
+    }
+}
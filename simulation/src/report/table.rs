@@ -0,0 +1,276 @@
+//! Markdown and LaTeX Tables
+//!
+//! The summary comparisons as GitHub Markdown for forum posts and LaTeX
+//! (`booktabs`) for the paper, so a table can be pasted in as is instead
+//! of retyped from terminal output.
+//!
+//! ```bash
+//! fair-sim monte-carlo --runs 2000 --seed 42 --table tail-risk.tex
+//! fair-sim cascade --seed 1 --table cascades.md
+//! ```
+//!
+//! Text columns are left-aligned and numeric ones (including `$` amounts
+//! and percentages) right-aligned. The LaTeX output needs
+//! `\usepackage{booktabs}` in the preamble.
+
+use std::fmt::Write as _;
+use std::path::Path;
+
+use crate::error::{Error, Result};
+use crate::monte_carlo::MonteCarloResult;
+use crate::poa::{compute_poa, GameResult};
+use crate::results::CascadeCell;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TableFormat {
+    Markdown,
+    Latex,
+}
+
+impl TableFormat {
+    /// Format for a file extension: `.md` is Markdown, `.tex` LaTeX.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("md" | "markdown") => Ok(Self::Markdown),
+            Some("tex") => Ok(Self::Latex),
+            _ => Err(Error::Invalid(format!("{}: tables are written as .md or .tex", path.display()))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Table {
+    pub caption: Option<String>, // LaTeX caption; Markdown leaves headings to the caller
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+fn is_numeric(cell: &str) -> bool {
+    let digits = cell.trim_start_matches(['-', '+', '$']);
+    digits.starts_with(|c: char| c.is_ascii_digit())
+}
+
+/// Escapes LaTeX special characters in text mode.
+pub fn escape_latex(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '~' => out.push_str("\\textasciitilde{}"),
+            '^' => out.push_str("\\textasciicircum{}"),
+            '\\' => out.push_str("\\textbackslash{}"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+impl Table {
+    pub fn new(headers: &[&str]) -> Self {
+        Self { headers: headers.iter().map(|h| h.to_string()).collect(), ..Default::default() }
+    }
+
+    pub fn with_caption(mut self, caption: impl Into<String>) -> Self {
+        self.caption = Some(caption.into());
+        self
+    }
+
+    pub fn push(&mut self, row: Vec<String>) {
+        self.rows.push(row);
+    }
+
+    /// True for columns whose every cell is a number.
+    fn right_aligned(&self) -> Vec<bool> {
+        (0..self.headers.len())
+            .map(|col| {
+                !self.rows.is_empty() && self.rows.iter().all(|row| row.get(col).is_some_and(|cell| is_numeric(cell)))
+            })
+            .collect()
+    }
+
+    pub fn markdown(&self) -> String {
+        let right = self.right_aligned();
+        let cell = |row: &[String], col: usize| row.get(col).map_or(String::new(), |c| c.replace('|', "\\|"));
+        let widths: Vec<usize> = (0..self.headers.len())
+            .map(|col| {
+                self.rows.iter().map(|row| cell(row, col).chars().count()).chain([self.headers[col].chars().count()]).max().unwrap_or(0)
+            })
+            .collect();
+        let line = |cells: Vec<String>| {
+            let padded: Vec<String> = cells
+                .iter()
+                .enumerate()
+                .map(|(col, c)| if right[col] { format!("{:>w$}", c, w = widths[col]) } else { format!("{:w$}", c, w = widths[col]) })
+                .collect();
+            format!("| {} |\n", padded.join(" | "))
+        };
+
+        let mut out = line((0..self.headers.len()).map(|col| cell(&self.headers, col)).collect());
+        out.push('|');
+        for (col, width) in widths.iter().enumerate() {
+            let dashes = "-".repeat(width + 1);
+            let _ = write!(out, "{}|", if right[col] { format!("{}:", dashes) } else { format!("{}-", dashes) });
+        }
+        out.push('\n');
+        for row in &self.rows {
+            out.push_str(&line((0..self.headers.len()).map(|col| cell(row, col)).collect()));
+        }
+        out
+    }
+
+    /// A `table` float with a `booktabs` tabular.
+    pub fn latex(&self) -> String {
+        let spec: String = self.right_aligned().iter().map(|&right| if right { 'r' } else { 'l' }).collect();
+        let line = |cells: &[String]| {
+            let escaped: Vec<String> = (0..self.headers.len())
+                .map(|col| cells.get(col).map_or(String::new(), |c| escape_latex(c)))
+                .collect();
+            format!("    {} \\\\\n", escaped.join(" & "))
+        };
+
+        let mut out = String::from("\\begin{table}[htbp]\n  \\centering\n");
+        if let Some(caption) = &self.caption {
+            let _ = writeln!(out, "  \\caption{{{}}}", escape_latex(caption));
+        }
+        let _ = writeln!(out, "  \\begin{{tabular}}{{{}}}", spec);
+        out.push_str("    \\toprule\n");
+        out.push_str(&line(&self.headers));
+        out.push_str("    \\midrule\n");
+        for row in &self.rows {
+            out.push_str(&line(row));
+        }
+        out.push_str("    \\bottomrule\n  \\end{tabular}\n\\end{table}\n");
+        out
+    }
+
+    pub fn render(&self, format: TableFormat) -> String {
+        match format {
+            TableFormat::Markdown => self.markdown(),
+            TableFormat::Latex => self.latex(),
+        }
+    }
+
+    /// Writes the table in the format `path`'s extension names.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let format = TableFormat::from_path(&path)?;
+        std::fs::write(path, self.render(format))?;
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Summary comparisons
+// ---------------------------------------------------------------------------
+
+/// A row per cascade cell (scenario, preset or split) and mechanism, with
+/// p5/p50/p95 bands over the cell's runs.
+pub fn cascade_summary(cells: &[CascadeCell]) -> Table {
+    let mut table = Table::new(&[
+        "Scenario",
+        "Mechanism",
+        "Bad Debt (p5 / p50 / p95)",
+        "P(Bad Debt)",
+        "Liquidations (p5 / p50 / p95)",
+        "Participation (p5 / p50 / p95)",
+        "Concentration (p5 / p50 / p95)",
+    ]);
+    let dollars = |v: f64| format!("${:.0}", v);
+    let percent = |v: f64| format!("{:.1}%", v * 100.0);
+    for cell in cells {
+        let agg = &cell.aggregate;
+        table.push(vec![
+            cell.label.clone(),
+            agg.mechanism.name(),
+            agg.bands.bad_debt.format(dollars),
+            format!("{:.1}%", agg.bad_debt_frequency * 100.0),
+            agg.bands.liquidations.format(|v| format!("{:.0}", v)),
+            agg.bands.participation_rate.format(percent),
+            agg.bands.profit_concentration.format(percent),
+        ]);
+    }
+    table.with_caption("Deleveraging cascades per scenario and mechanism")
+}
+
+/// A row per price model and mechanism.
+pub fn monte_carlo_summary(results: &[MonteCarloResult]) -> Table {
+    let mut table = Table::new(&["Model", "Mechanism", "Mean Bad Debt", "VaR 99%", "CVaR 99%", "P(Insolvency)"]);
+    for result in results {
+        table.push(vec![
+            result.model.name().to_string(),
+            result.mechanism.name(),
+            format!("${:.0}", result.mean_bad_debt),
+            format!("${:.0}", result.var_99),
+            format!("${:.0}", result.cvar_99),
+            format!("{:.2}%", result.insolvency_probability * 100.0),
+        ]);
+    }
+    table.with_caption("Tail risk of bad debt per price model and mechanism")
+}
+
+/// A row per obfuscation strategy, from each strategy's games.
+pub fn poa_summary(campaigns: &[Vec<GameResult>]) -> Table {
+    let mut table = Table::new(&["Strategy", "PoA", "Front-runner", "Concentration"]);
+    for games in campaigns.iter().filter(|games| !games.is_empty()) {
+        let n = games.len() as f64;
+        let front_runner: f64 = games
+            .iter()
+            .map(|g| if g.total_profit > 0.0 { g.front_runner_profit / g.total_profit } else { 0.0 })
+            .sum::<f64>()
+            / n;
+        let concentration = games.iter().map(|g| g.profit_concentration).sum::<f64>() / n;
+        table.push(vec![
+            games[0].strategy.name(),
+            format!("{:.2}", compute_poa(games)),
+            format!("{:.1}%", front_runner * 100.0),
+            format!("{:.1}%", concentration * 100.0),
+        ]);
+    }
+    table.with_caption("Price of Anarchy, front-runner share and profit concentration per obfuscation strategy")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Table {
+        let mut table = Table::new(&["Model", "Bad Debt", "P(Insolvency)"]).with_caption("Tail_risk & 99%");
+        table.push(vec!["GBM".to_string(), "$1200".to_string(), "0.50%".to_string()]);
+        table.push(vec!["Mar 2020 | hist".to_string(), "$35".to_string(), "12.25%".to_string()]);
+        table
+    }
+
+    #[test]
+    fn test_markdown_aligns_numeric_columns() {
+        let markdown = sample().markdown();
+        let lines: Vec<&str> = markdown.lines().collect();
+
+        assert_eq!(lines[0], "| Model            | Bad Debt | P(Insolvency) |");
+        assert_eq!(lines[1], "|------------------|---------:|--------------:|");
+        assert_eq!(lines[3], "| Mar 2020 \\| hist |      $35 |        12.25% |");
+        assert!(lines.iter().all(|l| l.chars().count() == lines[0].chars().count()));
+    }
+
+    #[test]
+    fn test_latex_is_booktabs_and_escaped() {
+        let latex = sample().latex();
+
+        assert!(latex.contains("\\caption{Tail\\_risk \\& 99\\%}"));
+        assert!(latex.contains("\\begin{tabular}{lrr}"));
+        assert!(latex.contains("    GBM & \\$1200 & 0.50\\% \\\\\n"));
+        let rules: Vec<usize> = ["\\toprule", "\\midrule", "\\bottomrule"].iter().map(|r| latex.find(r).unwrap()).collect();
+        assert!(rules.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(escape_latex("a~b^c\\"), "a\\textasciitilde{}b\\textasciicircum{}c\\textbackslash{}");
+    }
+
+    #[test]
+    fn test_format_from_extension() {
+        assert_eq!(TableFormat::from_path("out/summary.md").unwrap(), TableFormat::Markdown);
+        assert_eq!(TableFormat::from_path("paper/tail.tex").unwrap(), TableFormat::Latex);
+        assert!(TableFormat::from_path("summary.csv").is_err());
+        assert!(sample().save("summary").is_err());
+    }
+}
@@ -0,0 +1,189 @@
+//! Keeper Reputation
+//!
+//! Whether drawing the keeper pool's executor by reputation (see
+//! `cascade::KeeperReputation`) cuts liquidation latency without bringing
+//! back the concentration of winner-takes-all races. Drawn uniformly, a
+//! griefer that joins every round is selected as often as anyone and each
+//! round it is selected for lapses. Weighted by record, it is soon passed
+//! over; but the more weight the best records carry, the more executions
+//! pile onto the few keepers that join most rounds.
+//!
+//! ## Method
+//! - Adds `griefers` griefers to the keeper set, drawn from their own
+//!   stream as in `adversarial`, and runs the uniform draw and reputation
+//!   at each exponent; every run sees the same seeds
+//! - Latency is blocks from a CDP becoming liquidatable to its
+//!   liquidation, averaged over liquidations
+//! - Execution concentration is the top fifth of keepers' share of
+//!   executions; profit concentration the same for profit. Traditional,
+//!   with the same keepers, is the winner-takes-all reference
+
+use rand::Rng;
+
+use crate::adversarial::AdversaryMix;
+use crate::cascade::{CascadeSimulationBuilder, KeeperReputation, LiquidationMechanism, PriceScenario, SimulationConfig};
+use crate::core::profit_concentration;
+use crate::error::{Error, Result};
+
+/// Score exponents compared by default, from proportional to record up to
+/// strongly favoring the best.
+pub const REPUTATION_EXPONENTS: [f64; 3] = [1.0, 2.0, 8.0];
+
+#[derive(Debug, Clone)]
+pub struct ReputationPoint {
+    pub mechanism: LiquidationMechanism,
+    pub exponent: Option<f64>, // None = executors drawn uniformly
+    pub avg_bad_debt: f64,
+    pub bad_debt_frequency: f64,
+    pub avg_delay: f64,
+    pub execution_concentration: f64, // Top fifth of keepers' share of executions
+    pub profit_concentration: f64,    // Top fifth of keepers' share of profit
+    pub avg_discredited: f64,         // Keepers ending a run with a score below one half
+}
+
+impl ReputationPoint {
+    pub fn label(&self) -> String {
+        match self.exponent {
+            Some(exponent) => format!("Reputation, ^{}", exponent),
+            None if self.mechanism.is_keeper_pool() => "Pool, uniform".to_string(),
+            None => self.mechanism.info().short_name.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ReputationReport {
+    pub scenario: PriceScenario,
+    pub runs: usize,
+    pub griefers: usize,
+    pub reputation: KeeperReputation, // Exponent varies per point
+    pub traditional: ReputationPoint,
+    pub uniform: ReputationPoint,
+    pub points: Vec<ReputationPoint>,
+}
+
+impl ReputationReport {
+    pub fn print(&self) {
+        println!("| Selection         | Bad Debt   | P(Bad Debt) | Delay  | Top 20% Executions | Top 20% Profit | Discredited |");
+        println!("|-------------------|------------|-------------|--------|--------------------|----------------|-------------|");
+        for point in [&self.traditional, &self.uniform].into_iter().chain(&self.points) {
+            let discredited = match point.exponent {
+                Some(_) => format!("{:.1}", point.avg_discredited),
+                None => "-".to_string(),
+            };
+            println!(
+                "| {:17} | ${:9.0} | {:10.1}% | {:6.2} | {:17.1}% | {:13.1}% | {:>11} |",
+                point.label(),
+                point.avg_bad_debt,
+                point.bad_debt_frequency * 100.0,
+                point.avg_delay,
+                point.execution_concentration * 100.0,
+                point.profit_concentration * 100.0,
+                discredited,
+            );
+        }
+        for point in &self.points {
+            println!(
+                "  {}: {:+.2} blocks of delay against the uniform draw, top fifth executing {:.1}% (Traditional {:.1}%)",
+                point.label(),
+                point.avg_delay - self.uniform.avg_delay,
+                point.execution_concentration * 100.0,
+                self.traditional.execution_concentration * 100.0,
+            );
+        }
+    }
+}
+
+/// Runs Traditional and the default keeper pool with `griefers` griefers
+/// added, drawing executors uniformly and under `reputation` at each
+/// exponent in `exponents`.
+pub fn compare_reputation(
+    reputation: KeeperReputation,
+    scenario: PriceScenario,
+    exponents: &[f64],
+    griefers: usize,
+    runs: usize,
+    config: &SimulationConfig,
+) -> Result<ReputationReport> {
+    if runs == 0 {
+        return Err(Error::Invalid("reputation comparison needs at least one run".to_string()));
+    }
+    config.validate()?;
+    let seed: u64 = config.rng().gen();
+    let mix = AdversaryMix { griefers, ..Default::default() };
+    let run = |mechanism: LiquidationMechanism, exponent: Option<f64>| -> Result<ReputationPoint> {
+        let config = SimulationConfig {
+            keeper_reputation: exponent.map(|exponent| KeeperReputation { exponent, ..reputation }),
+            ..config.clone()
+        };
+        config.validate()?;
+        let (mut bad_debt, mut bad_debt_runs, mut delay, mut executions, mut profits) = (0.0, 0, 0.0, 0.0, 0.0);
+        let mut discredited = 0;
+        for i in 0..runs {
+            let run_seed = seed.wrapping_add(i as u64);
+            let mut rng = config.run_rng(run_seed);
+            let mut adversary_rng = config.run_rng(!run_seed);
+            let keepers = mix.keepers(&config, &mut rng, &mut adversary_rng);
+            let mut sim = CascadeSimulationBuilder::new()
+                .config(config.clone())
+                .mechanism(mechanism)
+                .scenario(scenario)
+                .keepers(keepers)
+                .build(&mut rng)?;
+            let result = sim.run(&mut rng);
+            let counts: Vec<f64> = sim.keepers().iter().map(|k| k.liquidations as f64).collect();
+            bad_debt += result.bad_debt;
+            bad_debt_runs += usize::from(result.bad_debt > 0.0);
+            delay += result.avg_liquidation_delay;
+            executions += profit_concentration(&counts, counts.iter().sum());
+            profits += result.profit_concentration;
+            discredited += result.reputation.map_or(0, |r| r.discredited);
+        }
+        let n = runs as f64;
+        Ok(ReputationPoint {
+            mechanism,
+            exponent,
+            avg_bad_debt: bad_debt / n,
+            bad_debt_frequency: bad_debt_runs as f64 / n,
+            avg_delay: delay / n,
+            execution_concentration: executions / n,
+            profit_concentration: profits / n,
+            avg_discredited: discredited as f64 / n,
+        })
+    };
+    let pool = LiquidationMechanism::keeper_pool();
+    let traditional = run(LiquidationMechanism::Traditional, None)?;
+    let uniform = run(pool, None)?;
+    let points = exponents.iter().map(|&exponent| run(pool, Some(exponent))).collect::<Result<Vec<_>>>()?;
+
+    Ok(ReputationReport { scenario, runs, griefers, reputation, traditional, uniform, points })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reputation_passes_over_griefers() {
+        let config = SimulationConfig { seed: Some(3), ..Default::default() };
+        let report = compare_reputation(KeeperReputation::default(), PriceScenario::FlashCrash, &[2.0], 10, 5, &config).unwrap();
+
+        let (uniform, weighted) = (&report.uniform, &report.points[0]);
+        assert!(weighted.avg_delay < uniform.avg_delay);
+        assert!(weighted.avg_discredited >= 10.0);
+        // Executions spread well short of a winner-takes-all race
+        assert!(weighted.execution_concentration < report.traditional.execution_concentration);
+    }
+
+    #[test]
+    fn test_steeper_weights_concentrate_executions() {
+        let config = SimulationConfig { seed: Some(3), ..Default::default() };
+        let report = compare_reputation(KeeperReputation::default(), PriceScenario::GradualDecline, &[1.0, 8.0], 0, 5, &config).unwrap();
+
+        let (mild, steep) = (&report.points[0], &report.points[1]);
+        assert!(steep.execution_concentration >= mild.execution_concentration);
+        assert_eq!(report.uniform.avg_discredited, 0.0);
+        let invalid = KeeperReputation { memory: 0.0, ..Default::default() };
+        assert!(compare_reputation(invalid, PriceScenario::GradualDecline, &[1.0], 0, 1, &config).is_err());
+    }
+}
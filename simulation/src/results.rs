@@ -0,0 +1,402 @@
+//! Result Persistence
+//!
+//! Saves and loads result sets as JSON so campaigns can be compared after
+//! the fact (e.g. before/after a parameter change) or consumed by
+//! downstream tooling.
+//!
+//! ## Formats
+//! - `monte-carlo --output`: `[MonteCarloResult]`
+//! - `cascade --output`: `[CascadeCell]`, one per scenario (or preset) and
+//!   mechanism
+//! - `poa --output`: `[GameResult]`, every game of every strategy
+//! - `corpus --output`: `PathCorpus`, a price model's paths and run seeds
+//!   (see `path_backend`)
+//! - `reaggregate --output`: `[TailRisk]`, a `monte-carlo --output` set's
+//!   tail metrics at new thresholds
+//! - `cascade --distributions`: `[CellDistributions]`, quantiles and
+//!   histograms per cell (see `distribution`)
+//! - `cascade --liquidation-log`: JSON Lines, one `LoggedLiquidation` per
+//!   liquidation of every run, in run and block order:
+//!   ```json
+//!   {"scenario":"FlashCrash","mechanism":"Traditional","seed":1797164333,"block":0,"cdp_id":347,"liquidator":14,"profit":78.2,"eth_sold":9.0,"price":1400.0}
+//!   ```
+//! - `cascade --stream`: JSON Lines written as the campaign runs, one
+//!   `StreamedLine::Run` per run the moment it completes, then one
+//!   `StreamedLine::Aggregate` per cell once every run is in. Nothing is
+//!   buffered, so campaigns of any size run in flat memory; a stream cut
+//!   short keeps every run that finished:
+//!   ```json
+//!   {"kind":"run","label":"Flash Crash (-30% instant)","result":{"mechanism":"Traditional",...}}
+//!   {"kind":"aggregate","label":"Flash Crash (-30% instant)","aggregate":{"mechanism":"Traditional",...}}
+//!   ```
+//!
+//! ## Parquet
+//! With the optional `arrow` feature, `monte-carlo --output paths.parquet`
+//! writes one row per simulated path instead (model, mechanism, keeper
+//! share, run, seed, bad debt, price drop, liquidations, participation),
+//! Snappy-compressed, for direct Polars/DuckDB consumption:
+//! ```bash
+//! cargo run --release --features arrow -- monte-carlo --runs 100000 --output paths.parquet
+//! duckdb -c "SELECT mechanism, quantile_cont(bad_debt, 0.99) FROM 'paths.parquet' GROUP BY 1"
+//! ```
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::cascade::{
+    AggregatedCascadeResult, CascadeResult, LiquidationEvent, LiquidationMechanism, PriceScenario, RunningAggregate,
+};
+use crate::error::Result;
+use crate::monte_carlo::MonteCarloResult;
+
+/// One cell of a cascade campaign: its runs and their aggregate.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CascadeCell {
+    pub label: String, // Scenario, preset or split
+    pub aggregate: AggregatedCascadeResult,
+    pub results: Vec<CascadeResult>, // Empty when only the aggregate is kept (split sweeps)
+}
+
+/// One line of a liquidation log: a liquidation and the run it belongs to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoggedLiquidation {
+    pub scenario: PriceScenario,
+    pub mechanism: LiquidationMechanism,
+    pub seed: Option<u64>, // Per-run seed; replays the run with `--run-seed`
+    #[serde(flatten)]
+    pub event: LiquidationEvent,
+}
+
+impl LoggedLiquidation {
+    /// Tags each run's liquidations with the run's cell and seed.
+    pub fn from_runs<'a>(
+        scenario: PriceScenario,
+        mechanism: LiquidationMechanism,
+        results: &'a [CascadeResult],
+        logs: &'a [Vec<LiquidationEvent>],
+    ) -> impl Iterator<Item = Self> + 'a {
+        results.iter().zip(logs).flat_map(move |(result, log)| {
+            log.iter().map(move |event| Self { scenario, mechanism, seed: result.seed, event: event.clone() })
+        })
+    }
+}
+
+/// One line of a streamed cascade campaign.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum StreamedLine {
+    Run { label: String, result: Box<CascadeResult> },
+    Aggregate { label: String, aggregate: Box<AggregatedCascadeResult> },
+}
+
+/// Streaming JSON Lines writer for cascade campaigns: `write_run` emits a
+/// run as soon as it completes and `finish` emits the cell aggregates.
+/// Only running sums are kept in memory.
+pub struct CascadeStream<W: Write> {
+    writer: W,
+    cells: Vec<(String, LiquidationMechanism, RunningAggregate)>, // In first-seen order
+}
+
+impl CascadeStream<BufWriter<File>> {
+    pub fn create(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self::new(BufWriter::new(File::create(path)?)))
+    }
+}
+
+impl<W: Write> CascadeStream<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer, cells: Vec::new() }
+    }
+
+    /// Writes and flushes `result` as a run of the cell named `label`.
+    pub fn write_run(&mut self, label: &str, result: &CascadeResult) -> Result<()> {
+        let position = self.cells.iter().position(|(cell, mechanism, _)| cell == label && *mechanism == result.mechanism);
+        let i = position.unwrap_or_else(|| {
+            self.cells.push((label.to_string(), result.mechanism, RunningAggregate::default()));
+            self.cells.len() - 1
+        });
+        self.cells[i].2.push(result);
+
+        #[derive(Serialize)]
+        #[serde(tag = "kind", rename = "run")]
+        struct Run<'a> {
+            label: &'a str,
+            result: &'a CascadeResult,
+        }
+        serde_json::to_writer(&mut self.writer, &Run { label, result })?;
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    /// The aggregate of the cell so far, if it has any runs.
+    pub fn aggregate(&self, label: &str, mechanism: LiquidationMechanism) -> Option<AggregatedCascadeResult> {
+        self.cells
+            .iter()
+            .find(|(cell, cell_mechanism, _)| cell == label && *cell_mechanism == mechanism)
+            .and_then(|(_, _, running)| running.finish())
+    }
+
+    /// Writes every cell's aggregate and returns the cells, without their runs.
+    pub fn finish(mut self) -> Result<Vec<CascadeCell>> {
+        let mut cells = Vec::with_capacity(self.cells.len());
+        for (label, _, running) in &self.cells {
+            let aggregate = running.finish().expect("cells are opened by a run");
+            serde_json::to_writer(&mut self.writer, &StreamedLine::Aggregate { label: label.clone(), aggregate: Box::new(aggregate.clone()) })?;
+            self.writer.write_all(b"\n")?;
+            cells.push(CascadeCell { label: label.clone(), aggregate, results: Vec::new() });
+        }
+        self.writer.flush()?;
+        Ok(cells)
+    }
+}
+
+/// Writes one JSON value per line.
+pub fn save_jsonl<T: Serialize>(path: impl AsRef<Path>, values: impl IntoIterator<Item = T>) -> Result<()> {
+    let mut writer = BufWriter::new(std::fs::File::create(path)?);
+    for value in values {
+        serde_json::to_writer(&mut writer, &value)?;
+        writer.write_all(b"\n")?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+pub fn load_jsonl<T: DeserializeOwned>(path: impl AsRef<Path>) -> Result<Vec<T>> {
+    let reader = BufReader::new(std::fs::File::open(path)?);
+    let mut values = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        if !line.trim().is_empty() {
+            values.push(serde_json::from_str(&line)?);
+        }
+    }
+    Ok(values)
+}
+
+pub fn save_json<T: Serialize + ?Sized>(path: impl AsRef<Path>, value: &T) -> Result<()> {
+    let json = serde_json::to_string(value)?;
+    std::fs::write(path, json)?;
+    Ok(())
+}
+
+pub fn load_json<T: DeserializeOwned>(path: impl AsRef<Path>) -> Result<T> {
+    let json = std::fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+pub fn save_monte_carlo(path: impl AsRef<Path>, results: &[MonteCarloResult]) -> Result<()> {
+    save_json(path, results)
+}
+
+pub fn load_monte_carlo(path: impl AsRef<Path>) -> Result<Vec<MonteCarloResult>> {
+    load_json(path)
+}
+
+/// Saves Monte Carlo result sets in the format `path`'s extension names:
+/// Parquet for `.parquet` (with the `arrow` feature), JSON otherwise.
+pub fn save_monte_carlo_as(path: impl AsRef<Path>, results: &[MonteCarloResult]) -> Result<()> {
+    let path = path.as_ref();
+    if path.extension().is_some_and(|ext| ext == "parquet") {
+        #[cfg(feature = "arrow")]
+        return save_monte_carlo_parquet(path, results);
+        #[cfg(not(feature = "arrow"))]
+        return Err(crate::error::Error::Invalid("Parquet output needs the `arrow` feature (--features arrow)".to_string()));
+    }
+    save_monte_carlo(path, results)
+}
+
+/// Saves every path of every result set as one Parquet row.
+#[cfg(feature = "arrow")]
+pub fn save_monte_carlo_parquet(path: impl AsRef<Path>, results: &[MonteCarloResult]) -> Result<()> {
+    use std::sync::Arc;
+
+    use arrow::array::{ArrayRef, Float64Array, StringArray, UInt64Array};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+    use parquet::basic::Compression;
+    use parquet::file::properties::WriterProperties;
+
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("model", DataType::Utf8, false),
+        Field::new("mechanism", DataType::Utf8, false),
+        Field::new("keeper_share", DataType::Float64, false),
+        Field::new("run", DataType::UInt64, false),
+        Field::new("seed", DataType::UInt64, true), // Null in result sets saved before seeds were recorded
+        Field::new("bad_debt", DataType::Float64, false),
+        Field::new("price_drop", DataType::Float64, false),
+        Field::new("liquidations", DataType::UInt64, false),
+        Field::new("participation_rate", DataType::Float64, false),
+    ]));
+    let properties = WriterProperties::builder().set_compression(Compression::SNAPPY).build();
+    let mut writer = ArrowWriter::try_new(std::fs::File::create(path)?, schema.clone(), Some(properties))?;
+
+    // One batch per result set, so memory holds one set's columns at a time
+    for result in results {
+        let n = result.bad_debts.len();
+        let mechanism = result.mechanism.info().variant;
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(StringArray::from(vec![format!("{:?}", result.model); n])),
+            Arc::new(StringArray::from(vec![mechanism; n])),
+            Arc::new(Float64Array::from(vec![result.mechanism.keeper_share(); n])),
+            Arc::new(UInt64Array::from_iter_values(0..n as u64)),
+            Arc::new(UInt64Array::from((0..n).map(|i| result.run_seeds.get(i).copied()).collect::<Vec<_>>())),
+            Arc::new(Float64Array::from(result.bad_debts.clone())),
+            Arc::new(Float64Array::from(result.price_drops.clone())),
+            Arc::new(UInt64Array::from_iter_values(result.liquidation_counts.iter().map(|&c| c as u64))),
+            Arc::new(Float64Array::from(result.participation_rates.clone())),
+        ];
+        writer.write(&RecordBatch::try_new(schema.clone(), columns)?)?;
+    }
+    writer.close()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cascade::{aggregate_results, run_cascade_simulation, LiquidationMechanism, PriceScenario, SimulationConfig};
+    use crate::monte_carlo::{run_monte_carlo, PriceModel};
+    use crate::poa::{run_poa_simulation, GameResult, ObfuscationStrategy};
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("fair-sim-{}-{}.json", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let result = run_monte_carlo(PriceModel::GBM, LiquidationMechanism::keeper_pool(), 10);
+        let path = temp_path("results");
+
+        save_monte_carlo(&path, std::slice::from_ref(&result)).unwrap();
+        let loaded = load_monte_carlo(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].model, result.model);
+        assert_eq!(loaded[0].bad_debts, result.bad_debts);
+    }
+
+    #[test]
+    fn test_cascade_cell_round_trip() {
+        let config = SimulationConfig { seed: Some(5), ..Default::default() };
+        let results = run_cascade_simulation(LiquidationMechanism::keeper_pool(), PriceScenario::FlashCrash, 3, &config);
+        let cell = CascadeCell { label: "Flash Crash".to_string(), aggregate: aggregate_results(&results), results };
+        let path = temp_path("cascade");
+
+        save_json(&path, std::slice::from_ref(&cell)).unwrap();
+        let loaded: Vec<CascadeCell> = load_json(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(loaded[0].label, cell.label);
+        assert_eq!(loaded[0].aggregate.mechanism, cell.aggregate.mechanism);
+        assert_eq!(loaded[0].aggregate.avg_bad_debt, cell.aggregate.avg_bad_debt);
+        assert_eq!(loaded[0].results[2].seed, cell.results[2].seed);
+        assert_eq!(loaded[0].results[2].ledger, cell.results[2].ledger);
+    }
+
+    #[test]
+    fn test_game_results_round_trip() {
+        let results = run_poa_simulation(ObfuscationStrategy::KeeperPool { split: 0.8 }, 5, Some(1));
+        let path = temp_path("poa");
+
+        save_json(&path, &results).unwrap();
+        let loaded: Vec<GameResult> = load_json(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(loaded.len(), 5);
+        assert_eq!(loaded[0].strategy, ObfuscationStrategy::KeeperPool { split: 0.8 });
+        assert_eq!(loaded[4].total_profit, results[4].total_profit);
+    }
+
+    #[test]
+    fn test_liquidation_log_round_trip() {
+        use crate::cascade::run_cascade_simulation_with_liquidations;
+
+        let config = SimulationConfig { seed: Some(8), ..Default::default() };
+        let mechanism = LiquidationMechanism::keeper_pool();
+        let (results, logs) = run_cascade_simulation_with_liquidations(mechanism, PriceScenario::FlashCrash, 2, &config);
+        let lines: Vec<_> = LoggedLiquidation::from_runs(PriceScenario::FlashCrash, mechanism, &results, &logs).collect();
+        let path = temp_path("liquidations").with_extension("jsonl");
+
+        save_jsonl(&path, &lines).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        let loaded: Vec<LoggedLiquidation> = load_jsonl(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(text.lines().count(), results.iter().map(|r| r.total_liquidations).sum::<usize>());
+        assert_eq!(loaded, lines);
+        assert_eq!(loaded[0].seed, results[0].seed);
+    }
+
+    #[test]
+    #[cfg(not(feature = "arrow"))]
+    fn test_parquet_needs_feature() {
+        let path = temp_path("paths").with_extension("parquet");
+        assert!(matches!(save_monte_carlo_as(&path, &[]), Err(crate::error::Error::Invalid(_))));
+        assert!(!path.exists());
+    }
+
+    #[test]
+    #[cfg(feature = "arrow")]
+    fn test_parquet_has_a_row_per_path() {
+        use arrow::array::{Float64Array, StringArray};
+        use arrow::compute::concat_batches;
+        use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+        let results = [
+            run_monte_carlo(PriceModel::GBM, LiquidationMechanism::Traditional, 4),
+            run_monte_carlo(PriceModel::JumpDiffusion, LiquidationMechanism::keeper_pool(), 6),
+        ];
+        let path = temp_path("paths").with_extension("parquet");
+
+        save_monte_carlo_as(&path, &results).unwrap();
+        let reader = ParquetRecordBatchReaderBuilder::try_new(std::fs::File::open(&path).unwrap()).unwrap().build().unwrap();
+        let batches: Vec<_> = reader.map(|batch| batch.unwrap()).collect();
+        let table = concat_batches(&batches[0].schema(), &batches).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(table.num_rows(), 10);
+        let column = |name: &str| table.column(table.schema().index_of(name).unwrap()).clone();
+        let mechanisms = column("mechanism");
+        let mechanisms = mechanisms.as_any().downcast_ref::<StringArray>().unwrap();
+        let bad_debts = column("bad_debt");
+        let bad_debts = bad_debts.as_any().downcast_ref::<Float64Array>().unwrap();
+        assert_eq!(mechanisms.value(0), "Traditional");
+        assert_eq!(mechanisms.value(4), "KeeperPool");
+        assert_eq!(bad_debts.values()[4..].to_vec(), results[1].bad_debts);
+    }
+
+    #[test]
+    fn test_stream_matches_buffered_campaign() {
+        let config = SimulationConfig { seed: Some(6), ..Default::default() };
+        let path = temp_path("stream");
+        let mut stream = CascadeStream::create(&path).unwrap();
+        for mechanism in LiquidationMechanism::all() {
+            crate::cascade::run_cascade_streaming(mechanism, PriceScenario::FlashCrash, 4, &config, |result| {
+                stream.write_run("Flash", &result)
+            })
+            .unwrap();
+        }
+        // Every run is on disk before the aggregates are
+        let mechanisms = LiquidationMechanism::all().len();
+        assert_eq!(load_jsonl::<StreamedLine>(&path).unwrap().len(), 4 * mechanisms);
+        let cells = stream.finish().unwrap();
+        let lines: Vec<StreamedLine> = load_jsonl(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        let buffered = run_cascade_simulation(LiquidationMechanism::Traditional, PriceScenario::FlashCrash, 4, &config);
+        assert_eq!(lines.len(), 5 * mechanisms);
+        assert!(matches!(&lines[0], StreamedLine::Run { result, .. } if result.seed == buffered[0].seed));
+        assert!(matches!(&lines[4 * mechanisms], StreamedLine::Aggregate { label, .. } if label == "Flash"));
+        assert_eq!(cells.len(), mechanisms);
+        assert!(cells[0].results.is_empty());
+        assert_eq!(cells[0].aggregate.avg_bad_debt, aggregate_results(&buffered).avg_bad_debt);
+        assert_eq!(cells[1].aggregate.mechanism, LiquidationMechanism::keeper_pool());
+    }
+}
@@ -0,0 +1,220 @@
+//! Keeper Rotation
+//!
+//! Keeper pools that admit an active set per epoch instead of every keeper
+//! (see `cascade::KeeperRotation`). A small set concentrates rewards among
+//! fewer members and cuts commit overhead, but leans on fewer operators
+//! being online: if every member of the epoch's set is down when a crash
+//! hits, nobody liquidates until the next rotation.
+//!
+//! ## Method
+//! - Sweeps set size against epoch length at a fixed offline rate, next to
+//!   the open pool every keeper joins; every cell sees the same seeds when
+//!   `config.seed` is set, and rotation draws from its own stream (seeding
+//!   that stream shifts the rotating cells' paths off the open pool's, so
+//!   compare against it in aggregate)
+//! - Outage blocks count blocks with CDPs liquidatable and the whole active
+//!   set offline; outage frequency is the share of runs with any
+
+use crate::cascade::{
+    run_cascade_simulation, CascadeResult, KeeperRotation, LiquidationMechanism, PriceScenario, SimulationConfig,
+};
+use crate::error::Result;
+use crate::mempool::coverage;
+
+/// Active set sizes swept by default.
+pub const SET_SIZES: [usize; 4] = [3, 5, 10, 20];
+
+/// Epoch lengths (blocks) swept by default.
+pub const EPOCH_LENGTHS: [usize; 2] = [10, 50];
+
+#[derive(Debug, Clone)]
+pub struct RotationPoint {
+    pub rotation: Option<KeeperRotation>, // None = open pool
+    pub avg_bad_debt: f64,
+    pub bad_debt_frequency: f64,
+    pub avg_coverage: f64,
+    pub avg_weighted_coverage: f64, // Exposure-weighted; see `CascadeResult::weighted_coverage`
+    pub avg_outage_blocks: f64,
+    pub outage_frequency: f64,
+    pub avg_profit_concentration: f64,
+}
+
+impl RotationPoint {
+    fn from_results(rotation: Option<KeeperRotation>, results: &[CascadeResult]) -> Self {
+        let n = results.len().max(1) as f64;
+        let mean = |f: fn(&CascadeResult) -> f64| results.iter().map(f).sum::<f64>() / n;
+        Self {
+            rotation,
+            avg_bad_debt: mean(|r| r.bad_debt),
+            bad_debt_frequency: results.iter().filter(|r| r.bad_debt > 0.0).count() as f64 / n,
+            avg_coverage: mean(coverage),
+            avg_weighted_coverage: mean(CascadeResult::weighted_coverage),
+            avg_outage_blocks: mean(|r| r.rotation_outages as f64),
+            outage_frequency: results.iter().filter(|r| r.rotation_outages > 0).count() as f64 / n,
+            avg_profit_concentration: mean(|r| r.profit_concentration),
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match &self.rotation {
+            None => "Open pool".to_string(),
+            Some(rotation) => format!("{} per {} blocks", rotation.set_size, rotation.epoch_blocks),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RotationReport {
+    pub split: f64,
+    pub scenario: PriceScenario,
+    pub runs: usize,
+    pub offline_rate: f64,
+    pub open: RotationPoint,
+    pub points: Vec<RotationPoint>, // Set sizes, each across the epoch lengths
+}
+
+impl RotationReport {
+    /// The smallest set size that saw no outage at any epoch length.
+    pub fn smallest_live_set(&self) -> Option<usize> {
+        self.points
+            .iter()
+            .filter_map(|p| p.rotation.map(|r| r.set_size))
+            .filter(|&size| {
+                self.points
+                    .iter()
+                    .filter(|p| p.rotation.is_some_and(|r| r.set_size == size))
+                    .all(|p| p.outage_frequency == 0.0)
+            })
+            .min()
+    }
+
+    pub fn print(&self) {
+        println!("| Active Set          | Bad Debt | P(Bad Debt) | Coverage | Weighted | Outage Blocks | P(Outage) | Concentration |");
+        println!("|---------------------|----------|-------------|----------|----------|---------------|-----------|---------------|");
+        for point in std::iter::once(&self.open).chain(&self.points) {
+            println!(
+                "| {:19} | ${:7.0} | {:10.1}% | {:7.1}% | {:7.1}% | {:13.2} | {:8.1}% | {:12.1}% |",
+                point.label(),
+                point.avg_bad_debt,
+                point.bad_debt_frequency * 100.0,
+                point.avg_coverage * 100.0,
+                point.avg_weighted_coverage * 100.0,
+                point.avg_outage_blocks,
+                point.outage_frequency * 100.0,
+                point.avg_profit_concentration * 100.0,
+            );
+        }
+        match self.smallest_live_set() {
+            Some(size) => println!(
+                "  Smallest set with no outage at {:.0}% offline: {} keepers",
+                self.offline_rate * 100.0,
+                size
+            ),
+            None => println!("  Every set size saw an outage at {:.0}% offline", self.offline_rate * 100.0),
+        }
+    }
+}
+
+/// Runs the keeper pool at `split` open to every keeper and under each
+/// combination of `set_sizes` and `epochs`, the offline rate taken from
+/// `config.keeper_rotation` (or its defaults).
+pub fn sweep_rotation(
+    split: f64,
+    scenario: PriceScenario,
+    set_sizes: &[usize],
+    epochs: &[usize],
+    runs: usize,
+    config: &SimulationConfig,
+) -> Result<RotationReport> {
+    let mechanism = LiquidationMechanism::KeeperPool { split };
+    mechanism.validate()?;
+    let offline_rate = config.keeper_rotation.unwrap_or_default().offline_rate;
+
+    let run = |rotation: Option<KeeperRotation>| -> Result<RotationPoint> {
+        let rotating = SimulationConfig { keeper_rotation: rotation, ..config.clone() };
+        rotating.validate()?;
+        Ok(RotationPoint::from_results(rotation, &run_cascade_simulation(mechanism, scenario, runs, &rotating)))
+    };
+    let open = run(None)?;
+    let mut points = Vec::with_capacity(set_sizes.len() * epochs.len());
+    for &set_size in set_sizes {
+        for &epoch_blocks in epochs {
+            points.push(run(Some(KeeperRotation { set_size, epoch_blocks, offline_rate }))?);
+        }
+    }
+
+    Ok(RotationReport { split, scenario, runs, offline_rate, open, points })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cascade::{CascadeSimulationBuilder, Keeper, CDP};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_everyone_online_ignores_epoch_length() {
+        // With every keeper in the set and online, rotation changes nothing
+        let rotating = |epoch_blocks: usize| SimulationConfig {
+            seed: Some(2),
+            keeper_rotation: Some(KeeperRotation { set_size: usize::MAX, epoch_blocks, offline_rate: 0.0 }),
+            ..Default::default()
+        };
+        let mechanism = LiquidationMechanism::keeper_pool();
+        let a = run_cascade_simulation(mechanism, PriceScenario::VolatileCrash, 4, &rotating(1));
+        let b = run_cascade_simulation(mechanism, PriceScenario::VolatileCrash, 4, &rotating(50));
+
+        for (a, b) in a.iter().zip(&b) {
+            assert_eq!(a.total_liquidations, b.total_liquidations);
+            assert_eq!(a.bad_debt, b.bad_debt);
+            assert_eq!(a.participation_rate, b.participation_rate);
+            assert_eq!(b.rotation_outages, 0);
+        }
+    }
+
+    #[test]
+    fn test_offline_set_stalls_pool_not_traditional() {
+        let run = |mechanism: LiquidationMechanism| {
+            let config = SimulationConfig {
+                keeper_rotation: Some(KeeperRotation { set_size: 1, epoch_blocks: 10, offline_rate: 1.0 }),
+                max_blocks: 5,
+                ..Default::default()
+            };
+            let mut rng = StdRng::seed_from_u64(0);
+            CascadeSimulationBuilder::new()
+                .config(config)
+                .mechanism(mechanism)
+                .cdps(vec![CDP::from_position(0, 5.0, 8_000.0)])
+                .keepers(vec![Keeper::new(0, 50_000.0, 0.5), Keeper::new(1, 50_000.0, 0.9)])
+                .price_path(vec![2000.0; 6])
+                .build(&mut rng)
+                .unwrap()
+                .run(&mut rng)
+        };
+        let pool = run(LiquidationMechanism::keeper_pool());
+        let traditional = run(LiquidationMechanism::Traditional);
+
+        assert_eq!(pool.total_liquidations, 0);
+        assert_eq!(pool.rotation_outages, 5);
+        assert_eq!(traditional.total_liquidations, 1);
+        assert_eq!(traditional.rotation_outages, 0);
+    }
+
+    #[test]
+    fn test_small_sets_risk_outages() {
+        let config = SimulationConfig {
+            seed: Some(5),
+            keeper_rotation: Some(KeeperRotation { offline_rate: 0.5, ..Default::default() }),
+            ..Default::default()
+        };
+        let report = sweep_rotation(0.7, PriceScenario::VolatileCrash, &[2, 20], &[10], 10, &config).unwrap();
+        let (small, large) = (&report.points[0], &report.points[1]);
+
+        assert_eq!(report.offline_rate, 0.5);
+        assert_eq!(report.open.avg_outage_blocks, 0.0);
+        assert!(small.outage_frequency > large.outage_frequency);
+        assert!(small.avg_coverage <= report.open.avg_coverage);
+        assert!(sweep_rotation(0.7, PriceScenario::FlashCrash, &[0], &[10], 1, &config).is_err());
+    }
+}
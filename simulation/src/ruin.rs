@@ -0,0 +1,276 @@
+//! Keeper Risk of Ruin
+//!
+//! Whether Fair keeps small keepers viable. A keeper's capital moves with
+//! every penalty it earns, and with every gas fee it pays, including
+//! Traditional races it loses, whose transactions revert but still pay.
+//! It also moves with drawdowns on the collateral it seizes, which it
+//! sells at the block's closing price after the cascade's own impact (see
+//! `Keeper::net_worth`). A small keeper racing whales mostly buys reverted
+//! transactions; the question is whether sharing penalties in the pool
+//! turns that bleed into a living.
+//!
+//! ## Method
+//! - A career is `campaigns` consecutive cascades against one keeper
+//!   population, each keeper's capital carried from one cascade to the next
+//! - A keeper is ruined once its capital, checked every block, falls to
+//!   `ruin_level` of where its career started. Ruined keepers stay in the
+//!   population, so competition does not thin as keepers fail
+//! - Small keepers are those starting at or below the `small_quantile`
+//!   capital quantile of their population
+//! - Every mechanism sees the same career seeds: the same populations,
+//!   books and price shocks, up to mechanism-driven draws
+
+use rand::Rng;
+
+use crate::cascade::{
+    CascadeSimulationBuilder, Keeper, LiquidationMechanism, PriceScenario, SimulationConfig,
+};
+use crate::error::{Error, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RuinConfig {
+    pub campaigns: usize,    // Cascades per career
+    pub ruin_level: f64,     // Share of starting capital at which a keeper is wiped out
+    pub small_quantile: f64, // Keepers at or below this capital quantile count as small
+}
+
+impl Default for RuinConfig {
+    fn default() -> Self {
+        Self {
+            campaigns: 20,
+            ruin_level: 0.5,
+            small_quantile: 0.25,
+        }
+    }
+}
+
+impl RuinConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.campaigns == 0 {
+            return Err(Error::Invalid("a career needs at least one campaign".to_string()));
+        }
+        if !(0.0..1.0).contains(&self.ruin_level) {
+            return Err(Error::Invalid("ruin level must be within [0, 1)".to_string()));
+        }
+        if !(0.0..=1.0).contains(&self.small_quantile) {
+            return Err(Error::Invalid("small keeper quantile must be within [0, 1]".to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// One keeper's capital over a career.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KeeperCareer {
+    pub start: f64,
+    pub low: f64, // Lowest capital seen at any block
+    pub end: f64,
+    pub small: bool,
+}
+
+impl KeeperCareer {
+    pub fn is_ruined(&self, ruin_level: f64) -> bool {
+        self.low <= self.start * ruin_level
+    }
+
+    /// Deepest fall below the starting capital, as a share of it.
+    pub fn max_drawdown(&self) -> f64 {
+        if self.start > 0.0 {
+            (1.0 - self.low / self.start).max(0.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Runs one career for every keeper of a population drawn from `seed`.
+pub fn run_career(
+    mechanism: LiquidationMechanism,
+    scenario: PriceScenario,
+    ruin: &RuinConfig,
+    config: &SimulationConfig,
+    seed: u64,
+) -> Result<Vec<KeeperCareer>> {
+    let mut rng = config.run_rng(seed);
+    let mut keepers = config.keepers.population(&mut rng);
+    let start: Vec<f64> = keepers.iter().map(|k| k.capital).collect();
+    let mut low = start.clone();
+
+    for _ in 0..ruin.campaigns {
+        let mut capital: Vec<f64> = keepers.iter().map(|k| k.capital).collect();
+        let mut sim = CascadeSimulationBuilder::new()
+            .config(config.clone())
+            .mechanism(mechanism)
+            .scenario(scenario)
+            .keepers(keepers.clone())
+            .build(&mut rng)?;
+        sim.run_observed(&mut rng, |state| {
+            capital.clone_from(&state.keeper_capital);
+            for (low, &now) in low.iter_mut().zip(&state.keeper_capital) {
+                *low = low.min(now);
+            }
+        });
+        keepers = keepers
+            .iter()
+            .zip(&capital)
+            .map(|(k, &capital)| Keeper::new(k.id, capital.max(0.0), k.gas_priority).with_behavior(k.behavior))
+            .collect();
+    }
+
+    let mut sorted = start.clone();
+    sorted.sort_by(f64::total_cmp);
+    let cutoff = sorted[((sorted.len() - 1) as f64 * ruin.small_quantile).floor() as usize];
+    Ok(start
+        .iter()
+        .zip(&low)
+        .zip(&keepers)
+        .map(|((&start, &low), keeper)| KeeperCareer { start, low, end: keeper.capital, small: start <= cutoff })
+        .collect())
+}
+
+#[derive(Debug, Clone)]
+pub struct RuinPoint {
+    pub mechanism: LiquidationMechanism,
+    pub small_ruin_probability: f64, // Share of small keeper careers ending in ruin
+    pub ruin_probability: f64,       // Across every keeper
+    pub small_median_growth: f64,    // Median end over start capital of small keepers
+    pub small_avg_drawdown: f64,
+}
+
+impl RuinPoint {
+    fn from_careers(mechanism: LiquidationMechanism, careers: &[KeeperCareer], ruin_level: f64) -> Self {
+        let share_ruined = |careers: &[&KeeperCareer]| {
+            careers.iter().filter(|c| c.is_ruined(ruin_level)).count() as f64 / careers.len().max(1) as f64
+        };
+        let small: Vec<&KeeperCareer> = careers.iter().filter(|c| c.small).collect();
+        let mut growth: Vec<f64> = small.iter().filter(|c| c.start > 0.0).map(|c| c.end / c.start).collect();
+        growth.sort_by(f64::total_cmp);
+        Self {
+            mechanism,
+            small_ruin_probability: share_ruined(&small),
+            ruin_probability: share_ruined(&careers.iter().collect::<Vec<_>>()),
+            small_median_growth: growth.get(growth.len() / 2).copied().unwrap_or(1.0),
+            small_avg_drawdown: small.iter().map(|c| c.max_drawdown()).sum::<f64>() / small.len().max(1) as f64,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RuinReport {
+    pub scenario: PriceScenario,
+    pub careers: usize,
+    pub ruin: RuinConfig,
+    pub points: Vec<RuinPoint>,
+}
+
+impl RuinReport {
+    pub fn point(&self, mechanism: LiquidationMechanism) -> Option<&RuinPoint> {
+        self.points.iter().find(|p| p.mechanism == mechanism)
+    }
+
+    pub fn print(&self) {
+        println!("| {:30} | P(Ruin), Small | P(Ruin), All | Small Median Growth | Small Avg Drawdown |", "Mechanism");
+        println!("|{}|----------------|--------------|---------------------|--------------------|", "-".repeat(32));
+        for point in &self.points {
+            println!(
+                "| {:30} | {:13.1}% | {:11.1}% | {:18.2}x | {:17.1}% |",
+                point.mechanism.name(),
+                point.small_ruin_probability * 100.0,
+                point.ruin_probability * 100.0,
+                point.small_median_growth,
+                point.small_avg_drawdown * 100.0,
+            );
+        }
+        let pool = self.points.iter().find(|p| p.mechanism.is_keeper_pool());
+        if let (Some(pool), Some(traditional)) = (pool, self.point(LiquidationMechanism::Traditional)) {
+            println!(
+                "  Small keepers ruined: {:.1}% under {} vs {:.1}% under Traditional",
+                pool.small_ruin_probability * 100.0,
+                pool.mechanism.name(),
+                traditional.small_ruin_probability * 100.0,
+            );
+        }
+    }
+}
+
+/// Runs `careers` keeper careers under each mechanism and estimates how
+/// often small keepers are wiped out.
+pub fn analyze_ruin(
+    mechanisms: &[LiquidationMechanism],
+    scenario: PriceScenario,
+    careers: usize,
+    ruin: &RuinConfig,
+    config: &SimulationConfig,
+) -> Result<RuinReport> {
+    ruin.validate()?;
+    config.validate()?;
+    let mut seeds = config.rng();
+    let seeds: Vec<u64> = (0..careers).map(|_| seeds.gen()).collect();
+
+    let points = mechanisms
+        .iter()
+        .map(|&mechanism| {
+            let mut all = Vec::new();
+            for &seed in &seeds {
+                all.extend(run_career(mechanism, scenario, ruin, config, seed)?);
+            }
+            Ok(RuinPoint::from_careers(mechanism, &all, ruin.ruin_level))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(RuinReport { scenario, careers, ruin: *ruin, points })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cascade::CapitalDistribution;
+
+    fn small_book() -> SimulationConfig {
+        SimulationConfig { num_cdps: 100, seed: Some(21), ..Default::default() }
+    }
+
+    #[test]
+    fn test_careers_replay_from_their_seed() {
+        let ruin = RuinConfig { campaigns: 3, ..Default::default() };
+        let config = small_book();
+        let a = run_career(LiquidationMechanism::Traditional, PriceScenario::FlashCrash, &ruin, &config, 5).unwrap();
+        let b = run_career(LiquidationMechanism::Traditional, PriceScenario::FlashCrash, &ruin, &config, 5).unwrap();
+
+        assert_eq!(a, b);
+        assert_eq!(a.len(), config.keepers.count);
+        assert!(a.iter().all(|c| c.low <= c.start && c.low <= c.end));
+        let small = a.iter().filter(|c| c.small).count();
+        assert!(small > 0 && small < a.len());
+    }
+
+    #[test]
+    fn test_reverted_races_drain_small_keepers() {
+        // Uniform priority draws leave the racing mostly to losers paying gas
+        let config = SimulationConfig {
+            keepers: crate::cascade::KeeperConfig {
+                capital: CapitalDistribution::Uniform { min: 200.0, max: 20_000.0 },
+                ..Default::default()
+            },
+            ..small_book()
+        };
+        let ruin = RuinConfig { campaigns: 5, ruin_level: 0.9, ..Default::default() };
+        let mechanisms = [LiquidationMechanism::Traditional, LiquidationMechanism::keeper_pool()];
+        let report = analyze_ruin(&mechanisms, PriceScenario::VolatileCrash, 2, &ruin, &config).unwrap();
+        let (traditional, pool) = (&report.points[0], &report.points[1]);
+
+        assert!(traditional.small_avg_drawdown > 0.0);
+        assert!(pool.small_ruin_probability <= traditional.small_ruin_probability);
+        assert!(pool.small_median_growth >= traditional.small_median_growth);
+    }
+
+    #[test]
+    fn test_invalid_ruin_config() {
+        let config = small_book();
+        let bad = |ruin: RuinConfig| analyze_ruin(&LiquidationMechanism::all(), PriceScenario::FlashCrash, 1, &ruin, &config).is_err();
+
+        assert!(bad(RuinConfig { campaigns: 0, ..Default::default() }));
+        assert!(bad(RuinConfig { ruin_level: 1.0, ..Default::default() }));
+        assert!(bad(RuinConfig { small_quantile: -0.1, ..Default::default() }));
+    }
+}
@@ -0,0 +1,296 @@
+//! Named Scenario Presets
+//!
+//! Documented stress presets modeled on historical episodes. Each preset
+//! sets both the exogenous price path and the CDP/keeper populations the
+//! episode hit, so "Mar2020" means thin keeper coverage under a gas spike,
+//! not just the price drop. Select one by name from the library
+//! (`"Mar2020".parse::<ScenarioPreset>()`) or the CLI (`--preset Mar2020`).
+//!
+//! ## Presets
+//! - `Mar2020`: Black Thursday. An 8% gap then ~3% per block for 14 blocks
+//!   (about -40%), a partial rebound; a gas spike raises every keeper's
+//!   bar and most keeper bots are offline; borrowers run thin buffers
+//! - `LunaCollapse`: an accelerating death spiral, -1% per block growing to
+//!   -6% over 40 blocks (about -75%); a neighboring protocol liquidates
+//!   into the same market and keeper capital is whale-concentrated
+//! - `SlowBleed2022`: a grinding -0.3% per block drift over 200 blocks
+//!   (about -45%) with no jumps; conservative borrowers
+//! - `FlashCrashRecovery`: a 35% wick at block 0 that recovers 3.5% per
+//!   block for 10 blocks; default populations
+//! - `LiquidityCrunch`: a 10% gap then ~1% per block for 20 blocks (about
+//!   -26%), with market makers withdrawing at block 5 so every ETH sold
+//!   moves the price 10x as far (`liquidity_crisis`, unless the base sets one)
+//!
+//! ## Overrides
+//! A preset replaces the population and horizon fields it documents on
+//! top of the base config; the base's seed, penalty, MCR, and any optional
+//! add-ons not named above are kept.
+
+use std::fmt;
+use std::str::FromStr;
+
+use rand::{Rng, RngCore};
+use rand_distr::{Distribution, Normal};
+use serde::{Deserialize, Serialize};
+use tracing::{debug_span, info_span};
+
+use crate::cascade::{
+    run_seeded, BlockEvent, CapitalDistribution, CascadeResult, CascadeSimulation,
+    CascadeSimulationBuilder, CdpDistribution, KeeperConfig, LiquidationMechanism,
+    ParticipationThresholds, PriceScenario, SimulationConfig,
+};
+use crate::error::{Error, Result};
+use crate::price_path::PricePath;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScenarioPreset {
+    Mar2020,
+    LunaCollapse,
+    SlowBleed2022,
+    FlashCrashRecovery,
+    LiquidityCrunch,
+}
+
+impl ScenarioPreset {
+    pub fn all() -> Vec<Self> {
+        vec![Self::Mar2020, Self::LunaCollapse, Self::SlowBleed2022, Self::FlashCrashRecovery, Self::LiquidityCrunch]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Mar2020 => "Mar2020",
+            Self::LunaCollapse => "LunaCollapse",
+            Self::SlowBleed2022 => "SlowBleed2022",
+            Self::FlashCrashRecovery => "FlashCrashRecovery",
+            Self::LiquidityCrunch => "LiquidityCrunch",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Mar2020 => "Black Thursday: -40% under a gas spike with most keepers offline",
+            Self::LunaCollapse => "Accelerating death spiral (-75%) with a neighbor dumping collateral",
+            Self::SlowBleed2022 => "Grinding -45% drift over 200 blocks, no jumps",
+            Self::FlashCrashRecovery => "35% wick that recovers within 10 blocks",
+            Self::LiquidityCrunch => "-26% crash with market makers withdrawing at block 5 (10x impact)",
+        }
+    }
+
+    /// Closest built-in scenario, recorded as `CascadeResult::scenario`.
+    pub fn label(&self) -> PriceScenario {
+        match self {
+            Self::Mar2020 => PriceScenario::VolatileCrash,
+            Self::LunaCollapse => PriceScenario::BlackSwan,
+            Self::SlowBleed2022 => PriceScenario::GradualDecline,
+            Self::FlashCrashRecovery => PriceScenario::FlashCrash,
+            Self::LiquidityCrunch => PriceScenario::VolatileCrash,
+        }
+    }
+
+    /// `base` with the preset's populations and horizon applied.
+    pub fn config(&self, base: &SimulationConfig) -> SimulationConfig {
+        match self {
+            Self::Mar2020 => SimulationConfig {
+                max_blocks: 100,
+                cdp_distribution: CdpDistribution { min_ratio: 1.5, max_ratio: 2.2, ..base.cdp_distribution.clone() },
+                keepers: KeeperConfig {
+                    count: 15,
+                    // ~200 gwei; fixed-spread keepers race like Traditional ones,
+                    // and every auction bid and band trade pays the congested price too
+                    thresholds: ParticipationThresholds {
+                        traditional: Some(150.0),
+                        keeper_pool: Some(20.0),
+                        fixed_spread: Some(150.0),
+                        english_auction: Some(150.0),
+                        soft_liquidation: Some(150.0),
+                        batch_auction: Some(150.0),
+                    },
+                    ..base.keepers.clone()
+                },
+                ..base.clone()
+            },
+            Self::LunaCollapse => SimulationConfig {
+                max_blocks: 150,
+                neighbor: Some(base.neighbor.clone().unwrap_or_default()),
+                keepers: KeeperConfig {
+                    capital: CapitalDistribution::PowerLaw { min: 5_000.0, alpha: 1.3 },
+                    ..base.keepers.clone()
+                },
+                ..base.clone()
+            },
+            Self::SlowBleed2022 => SimulationConfig {
+                max_blocks: 200,
+                cdp_distribution: CdpDistribution { min_ratio: 1.6, max_ratio: 3.0, ..base.cdp_distribution.clone() },
+                ..base.clone()
+            },
+            Self::FlashCrashRecovery => SimulationConfig { max_blocks: 60, ..base.clone() },
+            Self::LiquidityCrunch => SimulationConfig {
+                max_blocks: 100,
+                liquidity_crisis: Some(base.liquidity_crisis.unwrap_or_default()),
+                ..base.clone()
+            },
+        }
+    }
+
+    pub fn price_path(&self) -> PresetPath {
+        PresetPath { preset: *self }
+    }
+
+    /// A single run of the preset on `base`, drawing the initial state from `rng`.
+    pub fn simulation(
+        &self,
+        mechanism: LiquidationMechanism,
+        base: &SimulationConfig,
+        rng: &mut impl Rng,
+    ) -> Result<CascadeSimulation> {
+        CascadeSimulationBuilder::new()
+            .config(self.config(base))
+            .mechanism(mechanism)
+            .scenario(self.label())
+            .shocks(self.price_path())
+            .build(rng)
+    }
+
+    /// Runs `runs` cascades of the preset, each on its own seed.
+    pub fn run(&self, mechanism: LiquidationMechanism, runs: usize, base: &SimulationConfig) -> Result<Vec<CascadeResult>> {
+        let config = self.config(base);
+        config.validate()?;
+        mechanism.validate()?;
+        let _campaign = info_span!("campaign", preset = self.name(), mechanism = %mechanism.name(), runs).entered();
+        Ok(run_seeded(runs, &config, |rng| {
+            self.simulation(mechanism, base, rng).expect("config validated above").run(rng)
+        }))
+    }
+
+    /// Replays one run of `run` from its seed, with events.
+    pub fn replay(
+        &self,
+        mechanism: LiquidationMechanism,
+        base: &SimulationConfig,
+        seed: u64,
+    ) -> Result<(CascadeResult, Vec<BlockEvent>)> {
+        let _run = debug_span!("run", preset = self.name(), seed).entered();
+        let mut rng = base.run_rng(seed);
+        let (mut result, events) = self.simulation(mechanism, base, &mut rng)?.run_with_events(&mut rng);
+        result.seed = Some(seed);
+        Ok((result, events))
+    }
+}
+
+impl fmt::Display for ScenarioPreset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for ScenarioPreset {
+    type Err = Error;
+
+    /// Case-insensitive; `-` and `_` are ignored ("luna-collapse" works).
+    fn from_str(name: &str) -> Result<Self> {
+        let key = |s: &str| s.chars().filter(|c| *c != '-' && *c != '_').collect::<String>().to_ascii_lowercase();
+        Self::all().into_iter().find(|p| key(p.name()) == key(name)).ok_or_else(|| {
+            let names: Vec<&str> = Self::all().iter().map(|p| p.name()).collect();
+            Error::Invalid(format!("unknown preset '{}' (expected one of: {})", name, names.join(", ")))
+        })
+    }
+}
+
+/// The exogenous moves of a preset; see the module docs for their shapes.
+#[derive(Debug, Clone, Copy)]
+pub struct PresetPath {
+    preset: ScenarioPreset,
+}
+
+impl PricePath for PresetPath {
+    fn shock(&mut self, block: usize, _price: f64, rng: &mut dyn RngCore) -> f64 {
+        let mut noisy = |drift: f64, sigma: f64| 1.0 + drift + Normal::new(0.0, sigma).unwrap().sample(rng);
+        match self.preset {
+            ScenarioPreset::Mar2020 => match block {
+                0 => 0.92,
+                1..=14 => noisy(-0.03, 0.01),
+                15..=29 => noisy(0.005, 0.01),
+                _ => 1.0,
+            },
+            ScenarioPreset::LunaCollapse => {
+                if block < 40 {
+                    noisy(-0.01 * (1.0 + block as f64 / 8.0), 0.015)
+                } else {
+                    1.0
+                }
+            }
+            ScenarioPreset::SlowBleed2022 => noisy(-0.003, 0.008),
+            ScenarioPreset::FlashCrashRecovery => match block {
+                0 => 0.65,
+                1..=10 => 1.035,
+                _ => 1.0,
+            },
+            ScenarioPreset::LiquidityCrunch => match block {
+                0 => 0.90,
+                1..=20 => noisy(-0.01, 0.01),
+                _ => 1.0,
+            },
+        }
+    }
+
+    fn name(&self) -> String {
+        self.preset.name().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cascade::LiquidityCrisis;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_presets_parse_by_name() {
+        for preset in ScenarioPreset::all() {
+            assert_eq!(preset.name().parse::<ScenarioPreset>().unwrap(), preset);
+        }
+        assert_eq!("luna-collapse".parse::<ScenarioPreset>().unwrap(), ScenarioPreset::LunaCollapse);
+        assert_eq!("MAR2020".parse::<ScenarioPreset>().unwrap(), ScenarioPreset::Mar2020);
+        assert!("Mar2021".parse::<ScenarioPreset>().is_err());
+    }
+
+    #[test]
+    fn test_paths_match_documented_drawdowns() {
+        // Median-path drawdown per preset, from the module docs
+        let expected = [
+            (ScenarioPreset::Mar2020, 14, 0.40),
+            (ScenarioPreset::LunaCollapse, 39, 0.75),
+            (ScenarioPreset::SlowBleed2022, 199, 0.45),
+            (ScenarioPreset::FlashCrashRecovery, 0, 0.35),
+            (ScenarioPreset::LiquidityCrunch, 20, 0.26),
+        ];
+        for (preset, trough, drawdown) in expected {
+            let mut rng = StdRng::seed_from_u64(0);
+            let mut path = preset.price_path();
+            let price = (0..=trough).map(|b| path.shock(b, 0.0, &mut rng)).product::<f64>();
+            assert!((1.0 - price - drawdown).abs() < 0.1, "{}: {:.2}", preset, 1.0 - price);
+        }
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut wick = ScenarioPreset::FlashCrashRecovery.price_path();
+        let recovered = (0..=10).map(|b| wick.shock(b, 0.0, &mut rng)).product::<f64>();
+        assert!(recovered > 0.9);
+    }
+
+    #[test]
+    fn test_preset_runs_replay_from_seed() {
+        let base = SimulationConfig { seed: Some(3), num_cdps: 100, ..Default::default() };
+        let preset = ScenarioPreset::Mar2020;
+        let results = preset.run(LiquidationMechanism::Traditional, 3, &base).unwrap();
+        let (replayed, events) = preset.replay(LiquidationMechanism::Traditional, &base, results[1].seed.unwrap()).unwrap();
+
+        assert_eq!(replayed.bad_debt, results[1].bad_debt);
+        assert_eq!(replayed.scenario, PriceScenario::VolatileCrash);
+        assert!(!events.is_empty());
+        assert_eq!(preset.config(&base).keepers.count, 15);
+        assert_eq!(preset.config(&base).seed, Some(3));
+        let crunch = ScenarioPreset::LiquidityCrunch.config(&base);
+        assert_eq!(crunch.liquidity_crisis, Some(LiquidityCrisis::default()));
+    }
+}
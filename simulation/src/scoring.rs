@@ -0,0 +1,122 @@
+//! Liquidation-Eligibility Scoring
+//!
+//! The linear score behind the Price of Anarchy game: a CDP's features
+//! weighed by a hidden weight vector, liquidatable once the score falls
+//! below a threshold. It is kept apart from `poa` so the cascade engine,
+//! the calibration tools and an off-chain keeper can all score a position
+//! with the same code, and exchange weight vectors as JSON:
+//!
+//! ```json
+//! {"weights":[2.0,-1.0,-1.5,0.3,-0.3],"threshold":2.0}
+//! ```
+//!
+//! ## Features
+//! In `FEATURES` order:
+//! - `collateral_ratio`: collateral value over debt
+//! - `volatility`: the position's volatility score, in [0, 1]
+//! - `debt_to_collateral`: debt over collateral value
+//! - `age`: position age in years, capped at 1
+//! - `size`: collateral value in units of $10,000, capped at 2
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::results::{load_json, save_json};
+
+pub const NUM_FEATURES: usize = 5;
+
+pub const FEATURES: [&str; NUM_FEATURES] = ["collateral_ratio", "volatility", "debt_to_collateral", "age", "size"];
+
+/// The feature vector of a position worth `collateral_value` against `debt`.
+pub fn features(collateral_value: f64, debt: f64, age_days: f64, volatility_score: f64) -> [f64; NUM_FEATURES] {
+    let collateral_ratio = if debt == 0.0 { f64::INFINITY } else { collateral_value / debt };
+    [
+        collateral_ratio,
+        volatility_score,
+        debt / collateral_value,
+        (age_days / 365.0).min(1.0),
+        (collateral_value / 10000.0).min(2.0),
+    ]
+}
+
+/// A weight vector and the threshold the weighted score is compared with.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScoringModel {
+    pub weights: [f64; NUM_FEATURES],
+    pub threshold: f64, // Liquidatable strictly below
+}
+
+impl Default for ScoringModel {
+    fn default() -> Self {
+        Self {
+            weights: [2.0, -1.0, -1.5, 0.3, -0.3],
+            threshold: 2.0,
+        }
+    }
+}
+
+impl ScoringModel {
+    pub fn validate(&self) -> Result<()> {
+        if self.weights.iter().chain([&self.threshold]).any(|x| !x.is_finite()) {
+            return Err(Error::Invalid("scoring weights and threshold must be finite".to_string()));
+        }
+        Ok(())
+    }
+
+    pub fn score(&self, features: &[f64; NUM_FEATURES]) -> f64 {
+        features.iter().zip(self.weights.iter()).map(|(f, w)| f * w).sum()
+    }
+
+    pub fn is_liquidatable(&self, features: &[f64; NUM_FEATURES]) -> bool {
+        self.score(features) < self.threshold
+    }
+
+    /// Loads and validates a model saved with `save`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let model: Self = load_json(path)?;
+        model.validate()?;
+        Ok(model)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        save_json(path, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_model_scores() {
+        let model = ScoringModel::default();
+        // Deep in collateral: ratio 3 dominates the score
+        let safe = features(30_000.0, 10_000.0, 100.0, 0.5);
+        // Barely collateralized, volatile and old
+        let risky = features(11_000.0, 10_000.0, 400.0, 0.9);
+
+        assert!((safe[2] - 1.0 / 3.0).abs() < 1e-12);
+        assert_eq!(risky[3], 1.0);
+        assert!(!model.is_liquidatable(&safe));
+        assert!(model.is_liquidatable(&risky));
+    }
+
+    #[test]
+    fn test_weight_vectors_round_trip() {
+        let model = ScoringModel { weights: [1.5, -0.5, -2.0, 0.0, 0.1], threshold: 1.2 };
+        let path = std::env::temp_dir().join(format!("fair-sim-scoring-{}.json", std::process::id()));
+
+        model.save(&path).unwrap();
+        let loaded = ScoringModel::load(&path).unwrap();
+        std::fs::write(&path, r#"{"weights":[1.0,1.0,1.0,1.0],"threshold":1.0}"#).unwrap();
+        let short = ScoringModel::load(&path);
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(loaded, model);
+        assert!(short.is_err());
+        assert!(ScoringModel { threshold: f64::NAN, ..model }.validate().is_err());
+    }
+}
@@ -0,0 +1,176 @@
+//! Shared-Liquidity Stress
+//!
+//! During a market-wide event several stablecoins liquidate at once and
+//! compete for the same keepers' capital. This sweeps the bonus rival
+//! protocols pay and measures whether Fair's rewards still win keeper
+//! attention, against a baseline where keeper capital binds but no rival
+//! bids for it.
+//!
+//! ## Method
+//! - Every bonus sees the same seeds when `config.seed` is set; capital
+//!   allocation draws nothing from the RNG, so differences are the rivals'
+//! - A keeper's return on a Fair CDP is its share of the penalty per USD of
+//!   debt repaid; keepers fill rival liquidations first when the rival
+//!   bonus beats the best Fair return that block
+
+use crate::cascade::{
+    run_cascade_simulation, CascadeResult, LiquidationMechanism, PriceScenario, SharedLiquidity, SimulationConfig,
+};
+use crate::error::Result;
+
+pub const BONUS_SWEEP: [f64; 6] = [0.01, 0.02, 0.03, 0.05, 0.08, 0.10];
+
+#[derive(Debug, Clone)]
+pub struct SharedLiquidityPoint {
+    pub competitor_bonus: Option<f64>, // None = baseline without rival volume
+    pub avg_liquidations: f64,
+    pub avg_bad_debt: f64,
+    pub avg_participation_rate: f64,
+    pub avg_capital_diverted: f64,
+}
+
+impl SharedLiquidityPoint {
+    fn from_results(competitor_bonus: Option<f64>, results: &[CascadeResult]) -> Self {
+        let n = results.len().max(1) as f64;
+        let mean = |f: fn(&CascadeResult) -> f64| results.iter().map(f).sum::<f64>() / n;
+        Self {
+            competitor_bonus,
+            avg_liquidations: mean(|r| r.total_liquidations as f64),
+            avg_bad_debt: mean(|r| r.bad_debt),
+            avg_participation_rate: mean(|r| r.participation_rate),
+            avg_capital_diverted: mean(|r| r.capital_diverted),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SharedLiquidityReport {
+    pub mechanism: LiquidationMechanism,
+    pub scenario: PriceScenario,
+    pub runs: usize,
+    pub baseline: SharedLiquidityPoint,
+    pub points: Vec<SharedLiquidityPoint>,
+}
+
+impl SharedLiquidityReport {
+    /// Lowest swept bonus at which keepers commit capital to rivals while
+    /// Fair has CDPs to liquidate, i.e. where Fair stops winning their
+    /// attention.
+    pub fn losing_bonus(&self) -> Option<f64> {
+        self.points
+            .iter()
+            .find(|p| p.avg_capital_diverted > 0.0)
+            .and_then(|p| p.competitor_bonus)
+    }
+
+    pub fn print(&self) {
+        println!("| Rival Bonus | Liquidations | Bad Debt | Participation | Capital Diverted |");
+        println!("|-------------|--------------|----------|---------------|------------------|");
+        for point in std::iter::once(&self.baseline).chain(&self.points) {
+            let label = match point.competitor_bonus {
+                Some(bonus) => format!("{:.0}%", bonus * 100.0),
+                None => "none".to_string(),
+            };
+            println!(
+                "| {:11} | {:12.1} | ${:7.0} | {:12.1}% | {:15.1}% |",
+                label,
+                point.avg_liquidations,
+                point.avg_bad_debt,
+                point.avg_participation_rate * 100.0,
+                point.avg_capital_diverted * 100.0,
+            );
+        }
+        match self.losing_bonus() {
+            Some(bonus) => println!("  Keepers defect to rivals from a {:.0}% bonus", bonus * 100.0),
+            None => println!("  Fair holds keeper attention across the sweep"),
+        }
+    }
+}
+
+/// Runs `mechanism` against rivals paying each bonus in `bonuses`, with
+/// rival volume and any other setting taken from `config.shared_liquidity`
+/// (or its defaults).
+pub fn sweep_competitor_bonus(
+    mechanism: LiquidationMechanism,
+    scenario: PriceScenario,
+    bonuses: &[f64],
+    runs: usize,
+    config: &SimulationConfig,
+) -> Result<SharedLiquidityReport> {
+    mechanism.validate()?;
+    config.validate()?;
+    let base = config.shared_liquidity.clone().unwrap_or_default();
+
+    let isolated = SimulationConfig {
+        shared_liquidity: Some(SharedLiquidity { competitor_debt_per_block: 0.0, ..base.clone() }),
+        ..config.clone()
+    };
+    let baseline = SharedLiquidityPoint::from_results(
+        None,
+        &run_cascade_simulation(mechanism, scenario, runs, &isolated),
+    );
+
+    let mut points = Vec::with_capacity(bonuses.len());
+    for &competitor_bonus in bonuses {
+        let shared = SimulationConfig {
+            shared_liquidity: Some(SharedLiquidity { competitor_bonus, ..base.clone() }),
+            ..config.clone()
+        };
+        shared.validate()?;
+        points.push(SharedLiquidityPoint::from_results(
+            Some(competitor_bonus),
+            &run_cascade_simulation(mechanism, scenario, runs, &shared),
+        ));
+    }
+
+    Ok(SharedLiquidityReport { mechanism, scenario, runs, baseline, points })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sweep(mechanism: LiquidationMechanism, bonuses: &[f64]) -> SharedLiquidityReport {
+        sweep_with(mechanism, bonuses, SharedLiquidity::default())
+    }
+
+    fn sweep_with(mechanism: LiquidationMechanism, bonuses: &[f64], rivals: SharedLiquidity) -> SharedLiquidityReport {
+        let config = SimulationConfig { seed: Some(11), shared_liquidity: Some(rivals), ..Default::default() };
+        sweep_competitor_bonus(mechanism, PriceScenario::FlashCrash, bonuses, 10, &config).unwrap()
+    }
+
+    #[test]
+    fn test_free_bonus_matches_baseline() {
+        // Rivals paying nothing never win capital, so runs are unchanged
+        let report = sweep(LiquidationMechanism::keeper_pool(), &[0.0]);
+        let point = &report.points[0];
+
+        assert_eq!(point.avg_capital_diverted, 0.0);
+        assert_eq!(point.avg_liquidations, report.baseline.avg_liquidations);
+        assert_eq!(point.avg_bad_debt, report.baseline.avg_bad_debt);
+        assert_eq!(report.losing_bonus(), None);
+    }
+
+    #[test]
+    fn test_rich_rivals_drain_keepers() {
+        // Rival volume beyond every keeper's capital leaves Fair unserviced
+        let rivals = SharedLiquidity { competitor_debt_per_block: 1e12, ..Default::default() };
+        let report = sweep_with(LiquidationMechanism::keeper_pool(), &[1.0], rivals);
+        let point = &report.points[0];
+
+        assert!(point.avg_capital_diverted > 0.0);
+        assert!(point.avg_liquidations < report.baseline.avg_liquidations);
+        assert_eq!(report.losing_bonus(), Some(1.0));
+    }
+
+    #[test]
+    fn test_pool_split_loses_keepers_first() {
+        // The keeper pool hands keepers a smaller share, so rivals win them
+        // at bonuses winner-takes-all still beats
+        let pool = sweep(LiquidationMechanism::keeper_pool(), &BONUS_SWEEP);
+        let traditional = sweep(LiquidationMechanism::Traditional, &BONUS_SWEEP);
+
+        let diverted = |r: &SharedLiquidityReport| r.points.iter().map(|p| p.avg_capital_diverted).sum::<f64>();
+        assert!(diverted(&pool) >= diverted(&traditional));
+    }
+}
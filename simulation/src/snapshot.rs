@@ -0,0 +1,219 @@
+//! Live-Monitoring Adapter
+//!
+//! Runs the Monte Carlo forward from the current on-chain state instead of a
+//! synthetic CDP book, turning the simulation into an operational risk monitor.
+//!
+//! ## Snapshot Format
+//! The adapter consumes the JSON produced by an indexer:
+//!
+//! ```json
+//! {
+//!   "block_number": 19000000,
+//!   "oracle_price": 2150.0,
+//!   "surplus_buffer": 250000.0,
+//!   "cdps": [
+//!     { "id": 1, "collateral": 12.5, "debt": 15000.0 },
+//!     { "id": 2, "collateral": 3.0, "debt": 4100.0 }
+//!   ]
+//! }
+//! ```
+//!
+//! ## What We Report
+//! - Current system state (collateral ratio, liquidatable CDPs)
+//! - Monte Carlo tail metrics per price model, starting from the snapshot
+//! - Probability the surplus buffer is exhausted by bad debt
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::cascade::{run_cascade_from_state, LiquidationMechanism, SimulationConfig, CDP};
+use crate::error::{Error, Result};
+use crate::monte_carlo::{scenario_for_model, summarize, MonteCarloResult, PriceModel};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CdpSnapshot {
+    pub id: usize,
+    pub collateral: f64, // ETH
+    pub debt: f64,       // USD
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChainSnapshot {
+    #[serde(default)]
+    pub block_number: Option<u64>,
+    pub oracle_price: f64,
+    pub surplus_buffer: f64,
+    pub cdps: Vec<CdpSnapshot>,
+}
+
+impl ChainSnapshot {
+    pub fn from_json(json: &str) -> Result<Self> {
+        let snapshot: Self = serde_json::from_str(json)?;
+        snapshot.validate()?;
+        Ok(snapshot)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.oracle_price.is_nan() || self.oracle_price <= 0.0 {
+            return Err(Error::Invalid(format!(
+                "oracle_price must be positive, got {}",
+                self.oracle_price
+            )));
+        }
+        if self.surplus_buffer < 0.0 {
+            return Err(Error::Invalid(format!(
+                "surplus_buffer must be non-negative, got {}",
+                self.surplus_buffer
+            )));
+        }
+        if self.cdps.is_empty() {
+            return Err(Error::Invalid("snapshot contains no CDPs".to_string()));
+        }
+        for cdp in &self.cdps {
+            if cdp.collateral < 0.0 || cdp.debt < 0.0 {
+                return Err(Error::Invalid(format!(
+                    "CDP {} has negative collateral or debt",
+                    cdp.id
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn total_collateral(&self) -> f64 {
+        self.cdps.iter().map(|c| c.collateral).sum()
+    }
+
+    pub fn total_debt(&self) -> f64 {
+        self.cdps.iter().map(|c| c.debt).sum()
+    }
+
+    pub fn system_collateral_ratio(&self) -> f64 {
+        let debt = self.total_debt();
+        if debt == 0.0 {
+            return f64::INFINITY;
+        }
+        self.total_collateral() * self.oracle_price / debt
+    }
+
+    fn to_cdps(&self) -> Vec<CDP> {
+        self.cdps
+            .iter()
+            .map(|c| CDP::from_position(c.id, c.collateral, c.debt))
+            .collect()
+    }
+
+    /// CDPs below `min_collateral_ratio` at the oracle price.
+    pub fn currently_liquidatable(&self, min_collateral_ratio: f64) -> usize {
+        self.to_cdps()
+            .iter()
+            .filter(|cdp| cdp.is_liquidatable(self.oracle_price, min_collateral_ratio))
+            .count()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SnapshotRiskReport {
+    pub monte_carlo: MonteCarloResult,
+    pub surplus_buffer: f64,
+    pub buffer_exhaustion_probability: f64,
+    pub mean_uncovered_bad_debt: f64,
+}
+
+impl SnapshotRiskReport {
+    pub fn print(&self) {
+        self.monte_carlo.print();
+        println!("  Surplus buffer:          ${:.0}", self.surplus_buffer);
+        println!(
+            "  Buffer exhaustion prob:  {:.2}%",
+            self.buffer_exhaustion_probability * 100.0
+        );
+        println!("  Mean uncovered debt:     ${:.0}", self.mean_uncovered_bad_debt);
+    }
+}
+
+pub fn run_monte_carlo_from_snapshot(
+    snapshot: &ChainSnapshot,
+    model: PriceModel,
+    mechanism: LiquidationMechanism,
+    runs: usize,
+    config: &SimulationConfig,
+) -> SnapshotRiskReport {
+    let results = run_cascade_from_state(
+        mechanism,
+        scenario_for_model(model),
+        &snapshot.to_cdps(),
+        snapshot.oracle_price,
+        runs,
+        config,
+    );
+    let monte_carlo = summarize(model, mechanism, &results);
+
+    let buffer = snapshot.surplus_buffer;
+    let exhausted = monte_carlo.bad_debts.iter().filter(|&&d| d > buffer).count();
+    let uncovered: f64 = monte_carlo
+        .bad_debts
+        .iter()
+        .map(|&d| (d - buffer).max(0.0))
+        .sum();
+
+    SnapshotRiskReport {
+        surplus_buffer: buffer,
+        buffer_exhaustion_probability: exhausted as f64 / runs as f64,
+        mean_uncovered_bad_debt: uncovered / runs as f64,
+        monte_carlo,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SNAPSHOT: &str = r#"{
+        "block_number": 19000000,
+        "oracle_price": 2000.0,
+        "surplus_buffer": 50000.0,
+        "cdps": [
+            { "id": 0, "collateral": 10.0, "debt": 12000.0 },
+            { "id": 1, "collateral": 5.0, "debt": 6000.0 },
+            { "id": 2, "collateral": 20.0, "debt": 15000.0 }
+        ]
+    }"#;
+
+    #[test]
+    fn test_snapshot_parsing() {
+        let snapshot = ChainSnapshot::from_json(SNAPSHOT).unwrap();
+
+        assert_eq!(snapshot.cdps.len(), 3);
+        assert_eq!(snapshot.block_number, Some(19_000_000));
+        assert!((snapshot.total_debt() - 33000.0).abs() < 0.001);
+        assert_eq!(snapshot.currently_liquidatable(SimulationConfig::default().min_collateral_ratio), 0);
+        assert_eq!(snapshot.currently_liquidatable(2.0), 2);
+    }
+
+    #[test]
+    fn test_snapshot_rejects_invalid_price() {
+        let json = SNAPSHOT.replace("2000.0", "0.0");
+        assert!(matches!(ChainSnapshot::from_json(&json), Err(Error::Invalid(_))));
+    }
+
+    #[test]
+    fn test_monte_carlo_from_snapshot() {
+        let snapshot = ChainSnapshot::from_json(SNAPSHOT).unwrap();
+        let report = run_monte_carlo_from_snapshot(
+            &snapshot,
+            PriceModel::HistoricalMar2020,
+            LiquidationMechanism::keeper_pool(),
+            20,
+            &SimulationConfig::default(),
+        );
+
+        assert_eq!(report.monte_carlo.runs, 20);
+        assert!(report.buffer_exhaustion_probability <= report.monte_carlo.bad_debt_probability);
+    }
+}
@@ -0,0 +1,343 @@
+//! Long-Horizon Soak
+//!
+//! Crash scenarios last minutes of chain time; some failure modes take a
+//! year to show. A soak runs one book and one keeper set through a
+//! continuous horizon of days, carrying every position, keeper balance and
+//! the protocol's buffer from each day to the next:
+//!
+//! - Interest: open debt accrues the stability fee daily, paid into the buffer
+//! - Borrower churn: positions are repaid at random and the book refilled
+//!   with new borrowers at the day's price
+//! - Calm days: one mean-reverting log-normal price move, liquidated like
+//!   any cascade block
+//! - Stress bursts: every `stress_every` days a full cascade of
+//!   `stress_scenario`, at `stress_severity`, runs from where the market
+//!   stands
+//!
+//! What it reports accumulates too slowly for a crash run to see: growth of
+//! the buffer (interest and retained penalties, less written-off bad debt),
+//! keeper churn (keepers whose capital falls to `keeper_exit` of their
+//! start leave and are replaced) and dust, open positions whose whole
+//! penalty would not pay a liquidation's base gas. Partial liquidations
+//! leave dust behind; no keeper will ever clear it.
+
+use std::io::Write;
+use std::path::Path;
+
+use rand::Rng;
+use rand_distr::{Distribution, Normal};
+use serde::{Deserialize, Serialize};
+
+use crate::cascade::{
+    CascadeSimulationBuilder, Keeper, LiquidationMechanism, PriceScenario, SimulationConfig, CDP,
+};
+use crate::error::{Error, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SoakConfig {
+    pub days: usize,
+    pub stability_fee: f64,    // Annual interest on debt
+    pub borrower_churn: f64,   // Share of positions repaid per year
+    pub daily_volatility: f64, // Of the calm-day log price
+    pub mean_reversion: f64,   // Daily pull of the log price back to the initial price
+    pub stress_every: usize,   // Days between stress bursts; 0 = none
+    pub stress_scenario: PriceScenario,
+    pub stress_severity: f64,  // Replaces the config's severity during bursts
+    pub keeper_exit: f64,      // Share of starting capital at which a keeper leaves
+}
+
+impl Default for SoakConfig {
+    fn default() -> Self {
+        Self {
+            days: 365,
+            stability_fee: 0.05,
+            borrower_churn: 0.5,
+            daily_volatility: 0.03,
+            mean_reversion: 0.05,
+            stress_every: 30,
+            stress_scenario: PriceScenario::VolatileCrash,
+            stress_severity: 0.5,
+            keeper_exit: 0.5,
+        }
+    }
+}
+
+impl SoakConfig {
+    pub fn validate(&self) -> Result<()> {
+        let check = |ok: bool, msg: &str| if ok { Ok(()) } else { Err(Error::Invalid(msg.to_string())) };
+
+        check(self.days > 0, "a soak needs at least one day")?;
+        check(self.stability_fee >= 0.0, "stability fee must be non-negative")?;
+        check((0.0..=365.0).contains(&self.borrower_churn), "borrower churn must be within [0, 365] per year")?;
+        check(self.daily_volatility >= 0.0, "daily volatility must be non-negative")?;
+        check((0.0..=1.0).contains(&self.mean_reversion), "mean reversion must be within [0, 1]")?;
+        check(self.stress_severity >= 0.0, "stress severity must be non-negative")?;
+        check((0.0..1.0).contains(&self.keeper_exit), "keeper exit level must be within [0, 1)")
+    }
+}
+
+/// The state of the system at the end of one day.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SoakDay {
+    pub day: usize,
+    pub stress: bool,
+    pub eth_price: f64,
+    pub open_cdps: usize,
+    pub total_debt: f64,
+    pub interest: f64,          // Accrued today
+    pub protocol_revenue: f64,  // Penalty share retained today
+    pub bad_debt: f64,          // Written off against the buffer today
+    pub buffer: f64,            // Cumulative
+    pub liquidations: usize,
+    pub repaid: usize,          // Positions closed by churn
+    pub keepers_exited: usize,
+    pub dust_cdps: usize,       // Open positions too small to pay a liquidation's gas
+    pub dust_debt: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SoakReport {
+    pub mechanism: LiquidationMechanism,
+    pub soak: SoakConfig,
+    pub keepers: usize,
+    pub days: Vec<SoakDay>,
+}
+
+impl SoakReport {
+    fn last(&self) -> &SoakDay {
+        self.days.last().expect("a soak runs at least one day")
+    }
+
+    pub fn keepers_exited(&self) -> usize {
+        self.days.iter().map(|d| d.keepers_exited).sum()
+    }
+
+    /// Keepers replaced per year, as a share of the keeper set.
+    pub fn annual_keeper_churn(&self) -> f64 {
+        self.keepers_exited() as f64 / self.keepers as f64 * 365.0 / self.days.len() as f64
+    }
+
+    pub fn total_bad_debt(&self) -> f64 {
+        self.days.iter().map(|d| d.bad_debt).sum()
+    }
+
+    /// End-of-horizon buffer as a share of outstanding debt.
+    pub fn buffer_ratio(&self) -> f64 {
+        let last = self.last();
+        if last.total_debt > 0.0 { last.buffer / last.total_debt } else { 0.0 }
+    }
+
+    pub fn print(&self) {
+        let last = self.last();
+        let stress: Vec<&SoakDay> = self.days.iter().filter(|d| d.stress).collect();
+        println!("  Days:                    {} ({} stress bursts)", self.days.len(), stress.len());
+        println!("  Final ETH price:         ${:.0}", last.eth_price);
+        println!("  Buffer:                  ${:.0} ({:.2}% of ${:.0} debt)", last.buffer, self.buffer_ratio() * 100.0, last.total_debt);
+        println!("    Interest:              ${:.0}", self.days.iter().map(|d| d.interest).sum::<f64>());
+        println!("    Retained penalties:    ${:.0}", self.days.iter().map(|d| d.protocol_revenue).sum::<f64>());
+        println!("    Bad debt written off:  ${:.0}", self.total_bad_debt());
+        println!(
+            "  Liquidations:            {} ({} in stress bursts)",
+            self.days.iter().map(|d| d.liquidations).sum::<usize>(),
+            stress.iter().map(|d| d.liquidations).sum::<usize>(),
+        );
+        println!("  Positions repaid:        {}", self.days.iter().map(|d| d.repaid).sum::<usize>());
+        println!("  Keepers exited:          {} ({:.1}% of the set per year)", self.keepers_exited(), self.annual_keeper_churn() * 100.0);
+        println!(
+            "  Dust:                    {} positions, ${:.0} debt (peak {})",
+            last.dust_cdps,
+            last.dust_debt,
+            self.days.iter().map(|d| d.dust_cdps).max().unwrap_or(0),
+        );
+    }
+}
+
+/// Runs one soak of `soak.days` days under `mechanism` from `seed`.
+pub fn run_soak(
+    mechanism: LiquidationMechanism,
+    soak: &SoakConfig,
+    config: &SimulationConfig,
+    seed: u64,
+) -> Result<SoakReport> {
+    soak.validate()?;
+    config.validate()?;
+    let mut rng = config.run_rng(seed);
+    let mut market = config.run_rng(rng.gen()); // Own stream, so calm days match across mechanisms
+    let calm = Normal::new(0.0, soak.daily_volatility).map_err(|e| Error::Invalid(e.to_string()))?;
+    let daily_interest = (1.0 + soak.stability_fee).powf(1.0 / 365.0) - 1.0;
+    let repay_chance = soak.borrower_churn / 365.0;
+
+    let mut eth_price = config.initial_eth_price;
+    let mut cdps: Vec<CDP> = (0..config.num_cdps)
+        .map(|id| CDP::new(id, eth_price, &config.cdp_distribution, &mut rng))
+        .collect();
+    let mut next_id = cdps.len();
+    let mut keepers = config.keepers.population(&mut rng);
+    let mut start: Vec<f64> = keepers.iter().map(|k| k.capital).collect();
+    let mut buffer = 0.0;
+    let mut days = Vec::with_capacity(soak.days);
+
+    let stress_config = SimulationConfig { severity: soak.stress_severity, ..config.clone() };
+
+    for day in 1..=soak.days {
+        let before = cdps.len();
+        cdps.retain(|_| rng.gen::<f64>() >= repay_chance);
+        let repaid = before - cdps.len();
+        while cdps.len() < config.num_cdps {
+            cdps.push(CDP::new(next_id, eth_price, &config.cdp_distribution, &mut rng));
+            next_id += 1;
+        }
+        let mut interest = 0.0;
+        for cdp in &mut cdps {
+            interest += cdp.debt * daily_interest;
+            cdp.debt *= 1.0 + daily_interest;
+        }
+
+        let stress = soak.stress_every > 0 && day % soak.stress_every == 0;
+        let builder = CascadeSimulationBuilder::new()
+            .config(if stress { stress_config.clone() } else { config.clone() })
+            .mechanism(mechanism)
+            .cdps(std::mem::take(&mut cdps))
+            .keepers(keepers.clone());
+        let builder = if stress {
+            builder.scenario(soak.stress_scenario).eth_price(eth_price)
+        } else {
+            let pull = soak.mean_reversion * (config.initial_eth_price / eth_price).ln();
+            builder.price_path(vec![eth_price, eth_price * (pull + calm.sample(&mut market)).exp()])
+        };
+        let mut sim = builder.build(&mut rng)?;
+        let mut capital: Vec<f64> = keepers.iter().map(|k| k.capital).collect();
+        let result = sim.run_observed(&mut rng, |state| capital.clone_from(&state.keeper_capital));
+        eth_price = result.final_price;
+
+        // Underwater positions nobody liquidated are written off against the buffer
+        let mut bad_debt = 0.0;
+        cdps = sim
+            .cdps()
+            .iter()
+            .filter(|cdp| !cdp.is_liquidated)
+            .filter(|cdp| {
+                let shortfall = cdp.bad_debt(eth_price);
+                bad_debt += shortfall;
+                shortfall == 0.0
+            })
+            .cloned()
+            .collect();
+        buffer += interest + result.protocol_revenue - bad_debt;
+
+        let mut keepers_exited = 0;
+        for (i, keeper) in keepers.iter_mut().enumerate() {
+            if capital[i] <= start[i] * soak.keeper_exit {
+                *keeper = config.keepers.keeper(keeper.id, &mut rng);
+                start[i] = keeper.capital;
+                keepers_exited += 1;
+            } else {
+                *keeper = Keeper::new(keeper.id, capital[i].max(0.0), keeper.gas_priority).with_behavior(keeper.behavior);
+            }
+        }
+
+        let dust: Vec<&CDP> = cdps
+            .iter()
+            .filter(|cdp| cdp.liquidation_profit(eth_price, config.liquidation_penalty) < config.keepers.gas.base_gas)
+            .collect();
+        days.push(SoakDay {
+            day,
+            stress,
+            eth_price,
+            open_cdps: cdps.len(),
+            total_debt: cdps.iter().fold(0.0, |sum, cdp| sum + cdp.debt),
+            interest,
+            protocol_revenue: result.protocol_revenue,
+            bad_debt,
+            buffer,
+            liquidations: result.total_liquidations,
+            repaid,
+            keepers_exited,
+            dust_cdps: dust.len(),
+            dust_debt: dust.iter().fold(0.0, |sum, cdp| sum + cdp.debt),
+        });
+    }
+
+    Ok(SoakReport { mechanism, soak: *soak, keepers: keepers.len(), days })
+}
+
+pub fn write_csv(report: &SoakReport, mut out: impl Write) -> Result<()> {
+    writeln!(
+        out,
+        "day,stress,eth_price,open_cdps,total_debt,interest,protocol_revenue,bad_debt,buffer,liquidations,repaid,keepers_exited,dust_cdps,dust_debt"
+    )?;
+    for d in &report.days {
+        writeln!(
+            out,
+            "{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+            d.day,
+            d.stress,
+            d.eth_price,
+            d.open_cdps,
+            d.total_debt,
+            d.interest,
+            d.protocol_revenue,
+            d.bad_debt,
+            d.buffer,
+            d.liquidations,
+            d.repaid,
+            d.keepers_exited,
+            d.dust_cdps,
+            d.dust_debt,
+        )?;
+    }
+    Ok(())
+}
+
+/// Saves the daily series as CSV for a `.csv` path, the whole report as JSON otherwise.
+pub fn save(path: impl AsRef<Path>, report: &SoakReport) -> Result<()> {
+    let path = path.as_ref();
+    if path.extension().is_some_and(|ext| ext == "csv") {
+        let mut out = std::io::BufWriter::new(std::fs::File::create(path)?);
+        write_csv(report, &mut out)?;
+        out.flush()?;
+        Ok(())
+    } else {
+        crate::results::save_json(path, report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cascade::CdpDistribution;
+
+    fn small_book() -> SimulationConfig {
+        SimulationConfig { num_cdps: 100, ..Default::default() }
+    }
+
+    #[test]
+    fn test_soak_carries_state_across_days() {
+        let soak = SoakConfig { days: 60, ..Default::default() };
+        let a = run_soak(LiquidationMechanism::keeper_pool(), &soak, &small_book(), 4).unwrap();
+        let b = run_soak(LiquidationMechanism::keeper_pool(), &soak, &small_book(), 4).unwrap();
+
+        assert_eq!(a.days, b.days);
+        assert_eq!(a.days.len(), 60);
+        assert_eq!(a.days.iter().filter(|d| d.stress).map(|d| d.day).collect::<Vec<_>>(), vec![30, 60]);
+        assert!(a.days.iter().all(|d| d.interest > 0.0 && d.open_cdps <= 100));
+        let buffer: f64 = a.days.iter().map(|d| d.interest + d.protocol_revenue - d.bad_debt).sum();
+        assert!((a.last().buffer - buffer).abs() < 1e-6);
+        assert!(a.days.iter().map(|d| d.repaid).sum::<usize>() > 0);
+    }
+
+    #[test]
+    fn test_small_positions_are_left_as_dust() {
+        let soak = SoakConfig { days: 30, stress_every: 10, ..Default::default() };
+        let tiny = SimulationConfig {
+            cdp_distribution: CdpDistribution { min_collateral: 0.05, max_collateral: 0.5, ..Default::default() },
+            ..small_book()
+        };
+        let report = run_soak(LiquidationMechanism::Traditional, &soak, &tiny, 2).unwrap();
+        let plain = run_soak(LiquidationMechanism::Traditional, &soak, &small_book(), 2).unwrap();
+
+        assert!(report.last().dust_cdps > plain.last().dust_cdps);
+        assert!(SoakConfig { keeper_exit: 1.0, ..soak }.validate().is_err());
+    }
+}
@@ -0,0 +1,188 @@
+//! Keeper Staking
+//!
+//! Whether bonding keeper pool membership with stake (see
+//! `cascade::KeeperStaking`) fixes the free riding the flat split invites.
+//! Under the flat split a griefer that joins every round and never
+//! executes takes a full member's share, and each round it is selected for
+//! lapses. Staked, it is slashed each time it is selected and ejected once
+//! its stake falls below the minimum, while rewards follow stake, so the
+//! best-capitalized keepers take more of the pool.
+//!
+//! ## Method
+//! - Adds `griefers` griefers to the keeper set, drawn from their own
+//!   stream as in `adversarial`, and runs the flat split and staking at
+//!   each slash rate; every run sees the same seeds
+//! - Griefer P&L is a griefer's profit net of slashing, averaged over
+//!   griefers and runs; griefing stops paying once it turns negative
+//! - Honest profit and its concentration (the top fifth's share) cover the
+//!   honest keepers alone
+
+use rand::Rng;
+
+use crate::adversarial::AdversaryMix;
+use crate::cascade::{
+    CascadeSimulationBuilder, KeeperBehavior, KeeperStaking, LiquidationMechanism, PriceScenario, SimulationConfig,
+};
+use crate::core::profit_concentration;
+use crate::error::{Error, Result};
+
+/// Slash rates compared by default; zero bonds and weights by stake
+/// without slashing.
+pub const SLASH_RATES: [f64; 3] = [0.0, 0.3, 1.0];
+
+#[derive(Debug, Clone)]
+pub struct StakingPoint {
+    pub slash_rate: Option<f64>, // None = the flat split
+    pub avg_bad_debt: f64,
+    pub bad_debt_frequency: f64,
+    pub griefer_pnl: f64,          // Per griefer, net of slashing
+    pub honest_profit: f64,        // Per honest keeper
+    pub profit_concentration: f64, // Top fifth of honest keepers' share of their profit
+    pub avg_slashed: f64,
+    pub avg_ejected: f64,
+}
+
+impl StakingPoint {
+    pub fn label(&self) -> String {
+        match self.slash_rate {
+            Some(rate) => format!("Staked, {:.0}% slash", rate * 100.0),
+            None => "Flat split".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StakingReport {
+    pub scenario: PriceScenario,
+    pub runs: usize,
+    pub griefers: usize,
+    pub staking: KeeperStaking, // Slash rate varies per point
+    pub points: Vec<StakingPoint>,
+}
+
+impl StakingReport {
+    pub fn print(&self) {
+        println!("| Pool               | Bad Debt   | P(Bad Debt) | Griefer P&L | Honest Profit | Top 20% Honest | Slashed   | Ejected |");
+        println!("|--------------------|------------|-------------|-------------|---------------|----------------|-----------|---------|");
+        for point in &self.points {
+            println!(
+                "| {:18} | ${:9.0} | {:10.1}% | ${:10.0} | ${:12.0} | {:13.1}% | ${:8.0} | {:7.1} |",
+                point.label(),
+                point.avg_bad_debt,
+                point.bad_debt_frequency * 100.0,
+                point.griefer_pnl,
+                point.honest_profit,
+                point.profit_concentration * 100.0,
+                point.avg_slashed,
+                point.avg_ejected,
+            );
+        }
+        if self.griefers == 0 {
+            return;
+        }
+        match self.points.iter().find(|point| point.slash_rate.is_some() && point.griefer_pnl < 0.0) {
+            Some(point) => println!("  Griefing stops paying at {}", point.label()),
+            None => println!("  Griefers profit at every slash rate compared"),
+        }
+    }
+}
+
+/// Runs the default keeper pool with `griefers` griefers added, under the
+/// flat split and under `staking` at each rate in `slash_rates`.
+pub fn compare_staking(
+    staking: KeeperStaking,
+    scenario: PriceScenario,
+    slash_rates: &[f64],
+    griefers: usize,
+    runs: usize,
+    config: &SimulationConfig,
+) -> Result<StakingReport> {
+    if runs == 0 {
+        return Err(Error::Invalid("staking comparison needs at least one run".to_string()));
+    }
+    config.validate()?;
+    let seed: u64 = config.rng().gen();
+    let mix = AdversaryMix { griefers, ..Default::default() };
+    let run = |slash_rate: Option<f64>| -> Result<StakingPoint> {
+        let config = SimulationConfig {
+            keeper_staking: slash_rate.map(|slash_rate| KeeperStaking { slash_rate, ..staking }),
+            ..config.clone()
+        };
+        config.validate()?;
+        let (mut bad_debt, mut bad_debt_runs, mut griefer_pnl, mut honest_profit, mut concentration) = (0.0, 0, 0.0, 0.0, 0.0);
+        let (mut slashed, mut ejected) = (0.0, 0);
+        for i in 0..runs {
+            let run_seed = seed.wrapping_add(i as u64);
+            let mut rng = config.run_rng(run_seed);
+            let mut adversary_rng = config.run_rng(!run_seed);
+            let keepers = mix.keepers(&config, &mut rng, &mut adversary_rng);
+            let mut sim = CascadeSimulationBuilder::new()
+                .config(config.clone())
+                .mechanism(LiquidationMechanism::keeper_pool())
+                .scenario(scenario)
+                .keepers(keepers)
+                .build(&mut rng)?;
+            let result = sim.run(&mut rng);
+            let (griefers, honest): (Vec<_>, Vec<_>) =
+                sim.keepers().iter().partition(|k| k.behavior == KeeperBehavior::Griefer);
+            let profits: Vec<f64> = honest.iter().map(|k| k.total_profit).collect();
+            let total: f64 = profits.iter().sum();
+            bad_debt += result.bad_debt;
+            bad_debt_runs += usize::from(result.bad_debt > 0.0);
+            griefer_pnl += griefers.iter().map(|k| k.total_profit).sum::<f64>() / griefers.len().max(1) as f64;
+            honest_profit += total / profits.len().max(1) as f64;
+            concentration += profit_concentration(&profits, total);
+            if let Some(outcome) = result.staking {
+                slashed += outcome.slashed;
+                ejected += outcome.ejected;
+            }
+        }
+        let n = runs as f64;
+        Ok(StakingPoint {
+            slash_rate,
+            avg_bad_debt: bad_debt / n,
+            bad_debt_frequency: bad_debt_runs as f64 / n,
+            griefer_pnl: griefer_pnl / n,
+            honest_profit: honest_profit / n,
+            profit_concentration: concentration / n,
+            avg_slashed: slashed / n,
+            avg_ejected: ejected as f64 / n,
+        })
+    };
+    let points = std::iter::once(None)
+        .chain(slash_rates.iter().map(|&rate| Some(rate)))
+        .map(run)
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(StakingReport { scenario, runs, griefers, staking, points })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slashing_ejects_griefers() {
+        let config = SimulationConfig { seed: Some(3), ..Default::default() };
+        let report = compare_staking(KeeperStaking::default(), PriceScenario::FlashCrash, &[0.0, 1.0], 10, 5, &config).unwrap();
+
+        let (flat, unslashed, slashed) = (&report.points[0], &report.points[1], &report.points[2]);
+        assert!(flat.griefer_pnl > 0.0);
+        assert_eq!((unslashed.avg_slashed, unslashed.avg_ejected), (0.0, 0.0));
+        assert_eq!(slashed.avg_ejected, 10.0);
+        assert!(slashed.griefer_pnl < 0.0);
+    }
+
+    #[test]
+    fn test_stake_weighting_concentrates_profit() {
+        let config = SimulationConfig { seed: Some(3), ..Default::default() };
+        let report = compare_staking(KeeperStaking::default(), PriceScenario::GradualDecline, &[0.0], 0, 5, &config).unwrap();
+
+        let (flat, staked) = (&report.points[0], &report.points[1]);
+        assert_eq!(flat.griefer_pnl, 0.0);
+        assert!((staked.honest_profit - flat.honest_profit).abs() < 1e-6 * flat.honest_profit);
+        assert!(staked.profit_concentration > flat.profit_concentration);
+        let invalid = KeeperStaking { stake_share: 0.0, ..Default::default() };
+        assert!(compare_staking(invalid, PriceScenario::GradualDecline, &[0.3], 0, 1, &config).is_err());
+    }
+}
@@ -0,0 +1,169 @@
+//! Statistical Helpers
+//!
+//! Sample moments, percentile bands and large-sample significance tests
+//! shared by the result comparison tools. Monte Carlo campaigns have
+//! hundreds to thousands of runs, so normal approximations are used
+//! throughout.
+
+use serde::{Deserialize, Serialize};
+
+use crate::quantiles::TDigest;
+
+pub fn mean(xs: &[f64]) -> f64 {
+    if xs.is_empty() {
+        return 0.0;
+    }
+    xs.iter().sum::<f64>() / xs.len() as f64
+}
+
+/// Unbiased sample variance.
+pub fn variance(xs: &[f64]) -> f64 {
+    if xs.len() < 2 {
+        return 0.0;
+    }
+    let m = mean(xs);
+    xs.iter().map(|x| (x - m).powi(2)).sum::<f64>() / (xs.len() - 1) as f64
+}
+
+pub fn std_error(xs: &[f64]) -> f64 {
+    if xs.is_empty() {
+        return 0.0;
+    }
+    (variance(xs) / xs.len() as f64).sqrt()
+}
+
+/// The 5th, 50th and 95th percentiles of a metric over a campaign's runs:
+/// the spread a single run can land anywhere in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Band {
+    pub p5: f64,
+    pub p50: f64,
+    pub p95: f64,
+}
+
+impl Band {
+    pub fn of(digest: &mut TDigest) -> Self {
+        Self { p5: digest.quantile(0.05), p50: digest.quantile(0.5), p95: digest.quantile(0.95) }
+    }
+
+    /// Whether the two bands share any value: a difference in means between
+    /// overlapping bands is within run-to-run spread.
+    pub fn overlaps(&self, other: &Band) -> bool {
+        self.p5 <= other.p95 && other.p5 <= self.p95
+    }
+
+    /// `p5 / p50 / p95`, each formatted by `value`.
+    pub fn format(&self, value: impl Fn(f64) -> String) -> String {
+        format!("{} / {} / {}", value(self.p5), value(self.p50), value(self.p95))
+    }
+}
+
+/// Abramowitz & Stegun 7.1.26, max error 1.5e-7.
+fn erf(x: f64) -> f64 {
+    let sign = if x < 0.0 { -1.0 } else { 1.0 };
+    let x = x.abs();
+    let t = 1.0 / (1.0 + 0.3275911 * x);
+    let poly = t
+        * (0.254829592
+            + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    sign * (1.0 - poly * (-x * x).exp())
+}
+
+pub fn normal_cdf(z: f64) -> f64 {
+    0.5 * (1.0 + erf(z / std::f64::consts::SQRT_2))
+}
+
+fn two_sided_p(z: f64) -> f64 {
+    if z.is_nan() {
+        return 1.0;
+    }
+    2.0 * (1.0 - normal_cdf(z.abs()))
+}
+
+/// Two-sided p-value for a difference in means (Welch, normal approximation).
+pub fn welch_p_value(a: &[f64], b: &[f64]) -> f64 {
+    if a.len() < 2 || b.len() < 2 {
+        return 1.0;
+    }
+    let se = (variance(a) / a.len() as f64 + variance(b) / b.len() as f64).sqrt();
+    let diff = mean(b) - mean(a);
+    if se == 0.0 {
+        return if diff == 0.0 { 1.0 } else { 0.0 };
+    }
+    two_sided_p(diff / se)
+}
+
+/// Two-sided p-value for a difference in proportions (pooled z-test).
+pub fn proportion_p_value(successes_a: usize, n_a: usize, successes_b: usize, n_b: usize) -> f64 {
+    if n_a == 0 || n_b == 0 {
+        return 1.0;
+    }
+    let p_a = successes_a as f64 / n_a as f64;
+    let p_b = successes_b as f64 / n_b as f64;
+    let pooled = (successes_a + successes_b) as f64 / (n_a + n_b) as f64;
+    let se = (pooled * (1.0 - pooled) * (1.0 / n_a as f64 + 1.0 / n_b as f64)).sqrt();
+    if se == 0.0 {
+        return 1.0;
+    }
+    two_sided_p((p_b - p_a) / se)
+}
+
+/// Relative change from `a`'s mean to `b`'s, with its 95% half-width by
+/// the delta method for independent samples. None when `a`'s mean is zero.
+pub fn relative_change(a: &[f64], b: &[f64]) -> Option<(f64, f64)> {
+    let (mean_a, mean_b) = (mean(a), mean(b));
+    if mean_a == 0.0 {
+        return None;
+    }
+    let ratio = mean_b / mean_a;
+    let se = (std_error(b).powi(2) + ratio.powi(2) * std_error(a).powi(2)).sqrt() / mean_a.abs();
+    Some((ratio - 1.0, 1.96 * se))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_band_of_a_sample() {
+        let mut digest = TDigest::default();
+        (0..=100).for_each(|i| digest.add(i as f64));
+        let band = Band::of(&mut digest);
+        assert!((band.p5 - 5.0).abs() < 1.0 && (band.p50 - 50.0).abs() < 1.0 && (band.p95 - 95.0).abs() < 1.0);
+        assert_eq!(band.format(|v| format!("{:.0}", v)), "5 / 50 / 95");
+
+        let shifted = Band { p5: 96.0, p50: 120.0, p95: 150.0 };
+        assert!(!band.overlaps(&shifted));
+        assert!(band.overlaps(&Band { p5: 90.0, ..shifted }));
+    }
+
+    #[test]
+    fn test_normal_cdf() {
+        assert!((normal_cdf(0.0) - 0.5).abs() < 1e-6);
+        assert!((normal_cdf(1.96) - 0.975).abs() < 1e-3);
+        assert!((normal_cdf(-1.96) - 0.025).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_significance_tests() {
+        let a: Vec<f64> = (0..500).map(|i| (i % 10) as f64).collect();
+        let b: Vec<f64> = a.iter().map(|x| x + 3.0).collect();
+
+        assert!(welch_p_value(&a, &a) > 0.99);
+        assert!(welch_p_value(&a, &b) < 0.001);
+        assert!(proportion_p_value(50, 1000, 50, 1000) > 0.99);
+        assert!(proportion_p_value(50, 1000, 150, 1000) < 0.001);
+    }
+
+    #[test]
+    fn test_relative_change() {
+        let a: Vec<f64> = (0..400).map(|i| 100.0 + (i % 5) as f64).collect();
+        let b: Vec<f64> = a.iter().map(|x| x * 0.66).collect();
+        let (change, half_width) = relative_change(&a, &b).unwrap();
+
+        assert!((change + 0.34).abs() < 1e-9);
+        assert!(half_width > 0.0 && half_width < 0.01);
+        assert_eq!(relative_change(&a, &a).unwrap().0, 0.0);
+        assert!(relative_change(&[0.0, 0.0], &b).is_none());
+    }
+}
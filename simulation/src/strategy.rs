@@ -0,0 +1,189 @@
+//! Keeper Strategy Interface
+//!
+//! The public extension point for plugging an external keeper implementation
+//! into the cascade engine. The strategy competes against the simulated keeper
+//! population under the chosen mechanism and pays gas for every attempt.
+//!
+//! ## Gas Model
+//! - Traditional: every attempt costs base gas plus a priority premium;
+//!   losing the race still burns gas (reverted transaction)
+//! - Keeper pool: joining the pool costs commit gas; only the selected
+//!   executor pays base gas plus priority premium
+//! - English auction, soft liquidation and batch auction: strategies are
+//!   not offered auction lots, band trades or batches; only the simulated
+//!   keepers take them
+
+use serde::{Deserialize, Serialize};
+
+use crate::cascade::LiquidationMechanism;
+
+pub const BASE_GAS_COST: f64 = 20.0;       // USD per liquidation transaction
+pub const PRIORITY_FEE_SCALE: f64 = 100.0; // USD premium at gas_priority = 1.0
+pub const POOL_COMMIT_COST: f64 = 5.0;     // USD to register for a pool liquidation
+
+/// What the external keeper sees about a single liquidation opportunity.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Opportunity {
+    pub block: usize,
+    pub cdp_id: usize,
+    pub collateral: f64,
+    pub debt: f64,
+    pub collateral_ratio: f64,
+    pub eth_price: f64,
+    pub expected_profit: f64, // Full liquidation penalty, before any split
+    pub mechanism: LiquidationMechanism,
+    pub competitors: usize,   // Simulated keepers willing to act on it
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KeeperAction {
+    pub gas_priority: f64, // 0-1, higher = faster execution, more gas paid
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum AttemptResult {
+    Won,           // Executed the liquidation
+    Lost,          // Outbid or not selected
+    PoolShare,     // Received a pool share without executing
+}
+
+#[derive(Debug, Clone)]
+pub struct AttemptOutcome {
+    pub block: usize,
+    pub cdp_id: usize,
+    pub result: AttemptResult,
+    pub revenue: f64,
+    pub gas_cost: f64,
+}
+
+pub trait KeeperStrategy {
+    fn name(&self) -> &str;
+
+    /// Return `Some(action)` to attempt the liquidation, `None` to pass.
+    fn decide(&mut self, opportunity: &Opportunity) -> Option<KeeperAction>;
+
+    /// Called after every attempt so stateful strategies can adapt.
+    fn on_outcome(&mut self, _outcome: &AttemptOutcome) {}
+}
+
+pub fn attempt_gas_cost(action: KeeperAction, executed: bool, mechanism: LiquidationMechanism) -> f64 {
+    let execution = BASE_GAS_COST + action.gas_priority.clamp(0.0, 1.0) * PRIORITY_FEE_SCALE;
+    match mechanism {
+        LiquidationMechanism::Traditional
+        | LiquidationMechanism::FixedSpread { .. }
+        | LiquidationMechanism::EnglishAuction(_)
+        | LiquidationMechanism::SoftLiquidation(_)
+        | LiquidationMechanism::BatchAuction(_) => execution,
+        LiquidationMechanism::KeeperPool { .. } => {
+            POOL_COMMIT_COST + if executed { execution } else { 0.0 }
+        }
+    }
+}
+
+/// Per-run ledger of the external keeper's activity.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct KeeperLedger {
+    pub attempts: usize,
+    pub wins: usize,
+    pub pool_shares: usize,
+    pub revenue: f64,
+    pub gas_spent: f64,
+    pub pnl_by_block: Vec<f64>, // Cumulative PnL at the end of each block
+}
+
+impl KeeperLedger {
+    pub fn net_pnl(&self) -> f64 {
+        self.revenue - self.gas_spent
+    }
+
+    pub fn record(&mut self, outcome: &AttemptOutcome) {
+        self.attempts += 1;
+        match outcome.result {
+            AttemptResult::Won => self.wins += 1,
+            AttemptResult::PoolShare => self.pool_shares += 1,
+            AttemptResult::Lost => {}
+        }
+        self.revenue += outcome.revenue;
+        self.gas_spent += outcome.gas_cost;
+    }
+
+    pub fn max_drawdown(&self) -> f64 {
+        let mut peak = 0.0_f64;
+        let mut drawdown = 0.0_f64;
+        for &pnl in &self.pnl_by_block {
+            peak = peak.max(pnl);
+            drawdown = drawdown.max(peak - pnl);
+        }
+        drawdown
+    }
+}
+
+/// Reference strategy: attempt whenever expected profit after gas clears a
+/// margin, bidding a fixed gas priority.
+#[derive(Debug, Clone)]
+pub struct ThresholdStrategy {
+    pub min_profit: f64,
+    pub gas_priority: f64,
+}
+
+impl KeeperStrategy for ThresholdStrategy {
+    fn name(&self) -> &str {
+        "Threshold"
+    }
+
+    fn decide(&mut self, opportunity: &Opportunity) -> Option<KeeperAction> {
+        let action = KeeperAction {
+            gas_priority: self.gas_priority,
+        };
+        let cost = attempt_gas_cost(action, true, opportunity.mechanism);
+        (opportunity.expected_profit - cost > self.min_profit).then_some(action)
+    }
+}
+
+/// Scripting hook: wraps a closure as a strategy for quick experiments.
+pub struct FnStrategy<F> {
+    name: String,
+    decide: F,
+}
+
+impl<F: FnMut(&Opportunity) -> Option<KeeperAction>> FnStrategy<F> {
+    pub fn new(name: impl Into<String>, decide: F) -> Self {
+        Self {
+            name: name.into(),
+            decide,
+        }
+    }
+}
+
+impl<F: FnMut(&Opportunity) -> Option<KeeperAction>> KeeperStrategy for FnStrategy<F> {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn decide(&mut self, opportunity: &Opportunity) -> Option<KeeperAction> {
+        (self.decide)(opportunity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gas_cost_by_mechanism() {
+        let action = KeeperAction { gas_priority: 0.5 };
+
+        assert!((attempt_gas_cost(action, false, LiquidationMechanism::Traditional) - 70.0).abs() < 1e-9);
+        assert!((attempt_gas_cost(action, false, LiquidationMechanism::keeper_pool()) - 5.0).abs() < 1e-9);
+        assert!((attempt_gas_cost(action, true, LiquidationMechanism::keeper_pool()) - 75.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_ledger_drawdown() {
+        let ledger = KeeperLedger {
+            pnl_by_block: vec![0.0, 100.0, 40.0, 120.0, 60.0],
+            ..Default::default()
+        };
+        assert!((ledger.max_drawdown() - 60.0).abs() < 1e-9);
+    }
+}
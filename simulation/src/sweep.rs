@@ -0,0 +1,414 @@
+//! Parameter Sweeps
+//!
+//! Runs the cascade engine over the full cross-product of ranges for any
+//! `SimulationConfig` field, so a sweep is one invocation rather than a
+//! bespoke driver. Results come back in long format: one row per grid
+//! point, mechanism, scenario, and metric, ready for a dataframe.
+//!
+//! ## Specs
+//! `field=start:end:step` for an inclusive range, `field=a,b,c` for a grid.
+//! Fields are dotted paths into the config as it appears in a scenario
+//! file; optional sections (e.g. `reward_token`) must be enabled in the
+//! base config before their fields can be swept.
+//!
+//! ```text
+//! liquidation_penalty=0.05:0.20:0.05
+//! min_collateral_ratio=1.1,1.5,2.0
+//! keepers.count=10,50,100,200
+//! severity=0.25:3:0.25
+//! ```
+//!
+//! Every grid point sees the same seeds when `config.seed` is set, so
+//! differences between points are the parameters'.
+//!
+//! ## Bad-Debt Heatmap
+//! The standard shock × keeper figure is canned: `bad_debt_heatmaps` sweeps
+//! `severity` against `keepers.count` and reshapes the rows into one
+//! severity-by-keeper-count grid per mechanism and scenario
+//! (`fair-sim heatmap`).
+
+use std::io::Write;
+
+use serde_json::Value;
+
+use crate::alerts::MetricSource;
+use crate::cascade::{
+    aggregate_results, run_cascade_simulation, AggregatedCascadeResult, LiquidationMechanism, PriceScenario,
+    SimulationConfig,
+};
+use crate::error::{Error, Result};
+
+pub const SWEEP_METRICS: &[&str] = &[
+    "avg_bad_debt",
+    "max_bad_debt",
+    "bad_debt_frequency",
+    "avg_liquidations",
+    "avg_cascade_depth",
+    "avg_participation_rate",
+    "avg_price_drop_pct",
+    "avg_protocol_revenue",
+    "avg_fee_burn",
+];
+
+const MAX_AXIS_VALUES: usize = 10_000;
+
+impl MetricSource for AggregatedCascadeResult {
+    fn metric(&self, name: &str) -> Option<f64> {
+        match name {
+            "avg_bad_debt" => Some(self.avg_bad_debt),
+            "max_bad_debt" => Some(self.max_bad_debt),
+            "bad_debt_frequency" => Some(self.bad_debt_frequency),
+            "avg_liquidations" => Some(self.avg_liquidations),
+            "avg_cascade_depth" => Some(self.avg_cascade_depth),
+            "avg_participation_rate" => Some(self.avg_participation_rate),
+            "avg_price_drop_pct" => Some(self.avg_price_drop_pct),
+            "avg_protocol_revenue" => Some(self.avg_protocol_revenue),
+            "avg_fee_burn" => Some(self.avg_fee_burn),
+            _ => None,
+        }
+    }
+}
+
+/// One swept field and the values it takes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SweepAxis {
+    pub field: String,
+    pub values: Vec<f64>,
+}
+
+impl SweepAxis {
+    pub fn parse(spec: &str) -> Result<Self> {
+        let (field, values) = spec
+            .split_once('=')
+            .ok_or_else(|| Error::Invalid(format!("sweep spec '{}' must look like field=values", spec)))?;
+        let field = field.trim();
+        if field.is_empty() {
+            return Err(Error::Invalid(format!("sweep spec '{}' names no field", spec)));
+        }
+        let number = |s: &str| {
+            s.trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|v| v.is_finite())
+                .ok_or_else(|| Error::Invalid(format!("'{}' in sweep spec '{}' is not a number", s.trim(), spec)))
+        };
+
+        let values = if values.contains(':') {
+            let bounds: Vec<&str> = values.split(':').collect();
+            let [start, end, step] = bounds[..] else {
+                return Err(Error::Invalid(format!("sweep range '{}' must be start:end:step", values)));
+            };
+            let (start, end, step) = (number(start)?, number(end)?, number(step)?);
+            if step <= 0.0 || end < start {
+                return Err(Error::Invalid(format!("sweep range '{}' needs start <= end and a positive step", values)));
+            }
+            let count = ((end - start) / step + 1e-9).floor() as usize + 1;
+            if count > MAX_AXIS_VALUES {
+                return Err(Error::Invalid(format!("sweep range '{}' has more than {} values", values, MAX_AXIS_VALUES)));
+            }
+            // Round away accumulated float error so 0.05:0.20:0.05 yields 0.15, not 0.15000000000000002
+            (0..count).map(|i| ((start + i as f64 * step) * 1e9).round() / 1e9).collect()
+        } else {
+            values.split(',').map(number).collect::<Result<Vec<f64>>>()?
+        };
+
+        Ok(Self { field: field.to_string(), values })
+    }
+}
+
+/// Sets each `(field, value)` on a copy of `config` and validates the result.
+pub fn apply_point(config: &SimulationConfig, point: &[(String, f64)]) -> Result<SimulationConfig> {
+    let mut tree = serde_json::to_value(config)?;
+    for (field, value) in point {
+        let mut node = &mut tree;
+        for key in field.split('.') {
+            node = match node {
+                Value::Object(map) => map
+                    .get_mut(key)
+                    .ok_or_else(|| Error::Invalid(format!("unknown config field '{}'", field)))?,
+                Value::Null => {
+                    return Err(Error::Invalid(format!(
+                        "'{}' is in an optional section; enable it in the base config to sweep it",
+                        field
+                    )))
+                }
+                _ => return Err(Error::Invalid(format!("config field '{}' is not a section", field))),
+            };
+        }
+        *node = match node {
+            Value::Number(n) if n.is_u64() || n.is_i64() => {
+                if value.fract() != 0.0 || *value < 0.0 {
+                    return Err(Error::Invalid(format!("'{}' takes whole numbers, got {}", field, value)));
+                }
+                Value::from(*value as u64)
+            }
+            Value::Number(_) => Value::from(*value),
+            _ => return Err(Error::Invalid(format!("config field '{}' is not numeric", field))),
+        };
+    }
+    let swept: SimulationConfig = serde_json::from_value(tree)?;
+    swept.validate()?;
+    Ok(swept)
+}
+
+/// Full cross-product of the axes' values, first axis outermost.
+pub fn grid(axes: &[SweepAxis]) -> Vec<Vec<(String, f64)>> {
+    axes.iter().fold(vec![Vec::new()], |points, axis| {
+        points
+            .iter()
+            .flat_map(|point| {
+                axis.values.iter().map(move |&value| {
+                    let mut next = point.clone();
+                    next.push((axis.field.clone(), value));
+                    next
+                })
+            })
+            .collect()
+    })
+}
+
+/// One long-format result row.
+#[derive(Debug, Clone)]
+pub struct SweepRow {
+    pub point: Vec<(String, f64)>,
+    pub mechanism: LiquidationMechanism,
+    pub scenario: PriceScenario,
+    pub metric: &'static str,
+    pub value: f64,
+}
+
+/// Runs every grid point under each mechanism and scenario. The grid is
+/// checked up front, so a bad spec fails before any simulation runs.
+pub fn run_sweep(
+    axes: &[SweepAxis],
+    mechanisms: &[LiquidationMechanism],
+    scenarios: &[PriceScenario],
+    runs: usize,
+    config: &SimulationConfig,
+) -> Result<Vec<SweepRow>> {
+    for mechanism in mechanisms {
+        mechanism.validate()?;
+    }
+    let points = grid(axes);
+    let configs = points.iter().map(|point| apply_point(config, point)).collect::<Result<Vec<_>>>()?;
+
+    let mut rows = Vec::with_capacity(points.len() * mechanisms.len() * scenarios.len() * SWEEP_METRICS.len());
+    for (point, swept) in points.iter().zip(&configs) {
+        for &mechanism in mechanisms {
+            for &scenario in scenarios {
+                let result = aggregate_results(&run_cascade_simulation(mechanism, scenario, runs, swept));
+                for &metric in SWEEP_METRICS {
+                    rows.push(SweepRow {
+                        point: point.clone(),
+                        mechanism,
+                        scenario,
+                        metric,
+                        value: result.metric(metric).expect("SWEEP_METRICS are all exposed"),
+                    });
+                }
+            }
+        }
+    }
+    Ok(rows)
+}
+
+/// Writes rows as CSV: one column per swept field, then mechanism,
+/// scenario, metric, value.
+pub fn write_csv(rows: &[SweepRow], mut out: impl Write) -> Result<()> {
+    let Some(first) = rows.first() else {
+        return Ok(());
+    };
+    let fields: Vec<&str> = first.point.iter().map(|(field, _)| field.as_str()).collect();
+    let header: Vec<&str> = fields.into_iter().chain(["mechanism", "scenario", "metric", "value"]).collect();
+    writeln!(out, "{}", header.join(","))?;
+    for row in rows {
+        let values: Vec<String> = row.point.iter().map(|(_, v)| v.to_string()).collect();
+        writeln!(
+            out,
+            "{},\"{}\",\"{}\",{},{}",
+            values.join(","),
+            row.mechanism.name(),
+            row.scenario.name(),
+            row.metric,
+            row.value
+        )?;
+    }
+    Ok(())
+}
+
+/// Default heatmap rows: shock severity, from a quarter to twice the
+/// scenario's log drop.
+pub const HEATMAP_SEVERITIES: &[f64] = &[0.25, 0.5, 0.75, 1.0, 1.25, 1.5, 1.75, 2.0];
+
+/// Default heatmap columns: keeper population sizes.
+pub const HEATMAP_KEEPER_COUNTS: &[f64] = &[5.0, 10.0, 20.0, 50.0, 100.0, 200.0];
+
+/// Bad debt over shock severity (rows) and keeper count (columns) for one
+/// mechanism and scenario.
+#[derive(Debug, Clone)]
+pub struct Heatmap {
+    pub mechanism: LiquidationMechanism,
+    pub scenario: PriceScenario,
+    pub severities: Vec<f64>,
+    pub keeper_counts: Vec<f64>,
+    pub avg_bad_debt: Vec<Vec<f64>>,       // [severity][keeper count]
+    pub bad_debt_frequency: Vec<Vec<f64>>, // Share of runs ending with any bad debt
+}
+
+impl Heatmap {
+    pub fn print(&self) {
+        println!("### {} / {}: average bad debt", self.scenario.name(), self.mechanism.name());
+        println!();
+        let header: String = self.keeper_counts.iter().map(|k| format!(" {:>11} |", format!("{} keepers", k))).collect();
+        let rule: String = self.keeper_counts.iter().map(|_| "-------------|").collect();
+        println!("| Severity |{}", header);
+        println!("|---------:|{}", rule);
+        for (severity, row) in self.severities.iter().zip(&self.avg_bad_debt) {
+            let cells: String = row.iter().map(|v| format!(" {:>11.0} |", v)).collect();
+            println!("| {:>8.2} |{}", severity, cells);
+        }
+        println!();
+    }
+}
+
+/// Sweeps `severity` against `keepers.count` and returns one heatmap per
+/// mechanism and scenario, in that order.
+pub fn bad_debt_heatmaps(
+    severities: &[f64],
+    keeper_counts: &[f64],
+    mechanisms: &[LiquidationMechanism],
+    scenarios: &[PriceScenario],
+    runs: usize,
+    config: &SimulationConfig,
+) -> Result<Vec<Heatmap>> {
+    let axes = [
+        SweepAxis { field: "severity".to_string(), values: severities.to_vec() },
+        SweepAxis { field: "keepers.count".to_string(), values: keeper_counts.to_vec() },
+    ];
+    let rows = run_sweep(&axes, mechanisms, scenarios, runs, config)?;
+    let cell = |mechanism: LiquidationMechanism, scenario: PriceScenario, metric: &str, severity: f64, keepers: f64| {
+        rows.iter()
+            .find(|r| {
+                r.mechanism == mechanism
+                    && r.scenario == scenario
+                    && r.metric == metric
+                    && r.point == [("severity".to_string(), severity), ("keepers.count".to_string(), keepers)]
+            })
+            .map_or(f64::NAN, |r| r.value)
+    };
+    let grid_of = |mechanism, scenario, metric: &str| -> Vec<Vec<f64>> {
+        severities
+            .iter()
+            .map(|&severity| keeper_counts.iter().map(|&keepers| cell(mechanism, scenario, metric, severity, keepers)).collect())
+            .collect()
+    };
+
+    Ok(mechanisms
+        .iter()
+        .flat_map(|&mechanism| scenarios.iter().map(move |&scenario| (mechanism, scenario)))
+        .map(|(mechanism, scenario)| Heatmap {
+            mechanism,
+            scenario,
+            severities: severities.to_vec(),
+            keeper_counts: keeper_counts.to_vec(),
+            avg_bad_debt: grid_of(mechanism, scenario, "avg_bad_debt"),
+            bad_debt_frequency: grid_of(mechanism, scenario, "bad_debt_frequency"),
+        })
+        .collect())
+}
+
+/// Writes heatmaps as tidy CSV, one row per cell: mechanism, scenario,
+/// severity, keepers, avg_bad_debt, bad_debt_frequency.
+pub fn write_heatmap_csv(heatmaps: &[Heatmap], mut out: impl Write) -> Result<()> {
+    writeln!(out, "mechanism,scenario,severity,keepers,avg_bad_debt,bad_debt_frequency")?;
+    for map in heatmaps {
+        for (i, severity) in map.severities.iter().enumerate() {
+            for (j, keepers) in map.keeper_counts.iter().enumerate() {
+                writeln!(
+                    out,
+                    "\"{}\",\"{}\",{},{},{},{}",
+                    map.mechanism.name(),
+                    map.scenario.name(),
+                    severity,
+                    keepers,
+                    map.avg_bad_debt[i][j],
+                    map.bad_debt_frequency[i][j]
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_specs() {
+        let range = SweepAxis::parse("liquidation_penalty=0.05:0.20:0.05").unwrap();
+        assert_eq!(range.values, vec![0.05, 0.1, 0.15, 0.2]);
+
+        let list = SweepAxis::parse("keepers.count=10, 50,200").unwrap();
+        assert_eq!(list.field, "keepers.count");
+        assert_eq!(list.values, vec![10.0, 50.0, 200.0]);
+
+        assert!(SweepAxis::parse("liquidation_penalty").is_err());
+        assert!(SweepAxis::parse("liquidation_penalty=0.2:0.1:0.05").is_err());
+        assert!(SweepAxis::parse("liquidation_penalty=0.1:0.2").is_err());
+        assert!(SweepAxis::parse("liquidation_penalty=abc").is_err());
+    }
+
+    #[test]
+    fn test_apply_point_sets_nested_fields() {
+        let config = SimulationConfig::default();
+        let point = vec![("keepers.count".to_string(), 12.0), ("min_collateral_ratio".to_string(), 1.1)];
+        let swept = apply_point(&config, &point).unwrap();
+
+        assert_eq!(swept.keepers.count, 12);
+        assert_eq!(swept.min_collateral_ratio, 1.1);
+        assert!(apply_point(&config, &[("keepers.count".to_string(), 2.5)]).is_err());
+        assert!(apply_point(&config, &[("no_such_field".to_string(), 1.0)]).is_err());
+        assert!(apply_point(&config, &[("reward_token.emission_share".to_string(), 0.5)]).is_err());
+        assert!(apply_point(&config, &[("liquidation_penalty".to_string(), 2.0)]).is_err());
+    }
+
+    #[test]
+    fn test_sweep_covers_cross_product() {
+        let axes = vec![
+            SweepAxis::parse("liquidation_penalty=0.05,0.13").unwrap(),
+            SweepAxis::parse("keepers.count=5:15:5").unwrap(),
+        ];
+        let config = SimulationConfig { num_cdps: 50, seed: Some(1), ..Default::default() };
+        let rows = run_sweep(&axes, &LiquidationMechanism::all(), &[PriceScenario::FlashCrash], 2, &config).unwrap();
+
+        assert_eq!(rows.len(), 2 * 3 * LiquidationMechanism::all().len() * SWEEP_METRICS.len());
+        assert_eq!(rows[0].point, vec![("liquidation_penalty".to_string(), 0.05), ("keepers.count".to_string(), 5.0)]);
+
+        let mut csv = Vec::new();
+        write_csv(&rows, &mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert!(csv.starts_with("liquidation_penalty,keepers.count,mechanism,scenario,metric,value\n"));
+        assert_eq!(csv.lines().count(), rows.len() + 1);
+    }
+
+    #[test]
+    fn test_heatmap_grid_shape() {
+        let config = SimulationConfig { num_cdps: 50, seed: Some(4), ..Default::default() };
+        let (severities, keepers) = ([0.0, 2.0], [5.0, 20.0, 40.0]);
+        let maps =
+            bad_debt_heatmaps(&severities, &keepers, &LiquidationMechanism::all(), &[PriceScenario::FlashCrash], 2, &config)
+                .unwrap();
+
+        assert_eq!(maps.len(), LiquidationMechanism::all().len());
+        assert_eq!(maps[1].mechanism, LiquidationMechanism::keeper_pool());
+        assert_eq!(maps[0].avg_bad_debt.len(), 2);
+        assert!(maps.iter().flat_map(|m| m.avg_bad_debt.iter().flatten()).all(|v| v.is_finite()));
+        // No shock, no bad debt
+        assert!(maps.iter().all(|m| m.avg_bad_debt[0].iter().all(|&v| v == 0.0)));
+
+        let mut csv = Vec::new();
+        write_heatmap_csv(&maps, &mut csv).unwrap();
+        assert_eq!(String::from_utf8(csv).unwrap().lines().count(), 1 + maps.len() * 2 * 3);
+    }
+}
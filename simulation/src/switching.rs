@@ -0,0 +1,260 @@
+//! Mechanism Switching
+//!
+//! Hybrid policies that run the keeper pool normally and hand liquidations
+//! to a backstop under stress (see `cascade::MechanismSwitch`), evaluated
+//! against the pure mechanisms. The question is whether a backstop clears
+//! the queue faster than either mechanism alone when the cascade outruns
+//! the pool, and what it costs in protocol revenue and fee burn.
+//!
+//! ## Method
+//! - Runs pure Traditional, the pure keeper pool, and the keeper pool under
+//!   each policy; every run sees the same seeds when `config.seed` is set,
+//!   and switching draws nothing from the RNG
+//! - Backstop blocks count the blocks each run spent under the backstop
+
+use crate::cascade::{
+    run_cascade_simulation, Backstop, CascadeResult, LiquidationMechanism, MechanismSwitch, PriceScenario,
+    SimulationConfig,
+};
+use crate::error::Result;
+
+const AUCTION: Backstop = Backstop::DutchAuction { start_penalty: 0.05, step: 0.02, max_penalty: 0.30 };
+
+/// Policies compared by default: a Traditional backstop and a Dutch
+/// auction, each engaging at two and five blocks of queued liquidations.
+pub const SWITCH_POLICIES: [MechanismSwitch; 4] = [
+    MechanismSwitch { queue_threshold: 20, backstop: Backstop::Traditional },
+    MechanismSwitch { queue_threshold: 50, backstop: Backstop::Traditional },
+    MechanismSwitch { queue_threshold: 20, backstop: AUCTION },
+    MechanismSwitch { queue_threshold: 50, backstop: AUCTION },
+];
+
+#[derive(Debug, Clone)]
+pub struct SwitchingPoint {
+    pub mechanism: LiquidationMechanism,
+    pub policy: Option<MechanismSwitch>, // None = pure mechanism
+    pub avg_bad_debt: f64,
+    pub bad_debt_frequency: f64,
+    pub avg_liquidations: f64,
+    pub avg_protocol_revenue: f64,
+    pub avg_fee_burn: f64,
+    pub avg_backstop_blocks: f64,
+}
+
+impl SwitchingPoint {
+    fn from_results(mechanism: LiquidationMechanism, policy: Option<MechanismSwitch>, results: &[CascadeResult]) -> Self {
+        let n = results.len().max(1) as f64;
+        let mean = |f: fn(&CascadeResult) -> f64| results.iter().map(f).sum::<f64>() / n;
+        Self {
+            mechanism,
+            policy,
+            avg_bad_debt: mean(|r| r.bad_debt),
+            bad_debt_frequency: results.iter().filter(|r| r.bad_debt > 0.0).count() as f64 / n,
+            avg_liquidations: mean(|r| r.total_liquidations as f64),
+            avg_protocol_revenue: mean(|r| r.protocol_revenue),
+            avg_fee_burn: mean(|r| r.fee_burn),
+            avg_backstop_blocks: mean(|r| r.backstop_blocks as f64),
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match (&self.policy, self.mechanism) {
+            (None, LiquidationMechanism::Traditional) => "Traditional".to_string(),
+            (None, LiquidationMechanism::KeeperPool { .. }) => "Keeper pool".to_string(),
+            (None, LiquidationMechanism::FixedSpread { .. }) => "Fixed spread".to_string(),
+            (None, LiquidationMechanism::EnglishAuction(_)) => "English auction".to_string(),
+            (None, LiquidationMechanism::SoftLiquidation(_)) => "Soft liquidation".to_string(),
+            (None, LiquidationMechanism::BatchAuction(_)) => "Batch auction".to_string(),
+            (Some(policy), _) => format!("Pool -> {} at {}", policy.backstop.name(), policy.queue_threshold),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SwitchingReport {
+    pub split: f64,
+    pub scenario: PriceScenario,
+    pub runs: usize,
+    pub traditional: SwitchingPoint,
+    pub keeper_pool: SwitchingPoint,
+    pub hybrids: Vec<SwitchingPoint>,
+}
+
+impl SwitchingReport {
+    /// The pure mechanism with less bad debt.
+    pub fn best_pure(&self) -> &SwitchingPoint {
+        if self.traditional.avg_bad_debt < self.keeper_pool.avg_bad_debt {
+            &self.traditional
+        } else {
+            &self.keeper_pool
+        }
+    }
+
+    /// Hybrids with less bad debt than both pure mechanisms.
+    pub fn dominating_hybrids(&self) -> Vec<&SwitchingPoint> {
+        let best = self.best_pure().avg_bad_debt;
+        self.hybrids.iter().filter(|h| h.avg_bad_debt < best).collect()
+    }
+
+    pub fn print(&self) {
+        println!("| Policy                                            | Bad Debt | P(Bad Debt) | Liquidations | Protocol Revenue | Fee Burn | Backstop Blocks |");
+        println!("|---------------------------------------------------|----------|-------------|--------------|------------------|----------|-----------------|");
+        for point in [&self.traditional, &self.keeper_pool].into_iter().chain(&self.hybrids) {
+            println!(
+                "| {:49} | ${:7.0} | {:10.1}% | {:12.1} | ${:15.0} | ${:7.0} | {:15.1} |",
+                point.label(),
+                point.avg_bad_debt,
+                point.bad_debt_frequency * 100.0,
+                point.avg_liquidations,
+                point.avg_protocol_revenue,
+                point.avg_fee_burn,
+                point.avg_backstop_blocks,
+            );
+        }
+        match self.dominating_hybrids().first() {
+            Some(best) => println!(
+                "  {} of {} hybrids beat both pure mechanisms; e.g. {} (${:.0} vs ${:.0})",
+                self.dominating_hybrids().len(),
+                self.hybrids.len(),
+                best.label(),
+                best.avg_bad_debt,
+                self.best_pure().avg_bad_debt
+            ),
+            None => println!("  No hybrid beats the best pure mechanism ({})", self.best_pure().label()),
+        }
+    }
+}
+
+/// Runs both pure mechanisms and the keeper pool at `split` under each
+/// policy in `policies`.
+pub fn compare_switching(
+    split: f64,
+    scenario: PriceScenario,
+    policies: &[MechanismSwitch],
+    runs: usize,
+    config: &SimulationConfig,
+) -> Result<SwitchingReport> {
+    let pool = LiquidationMechanism::KeeperPool { split };
+    pool.validate()?;
+    let pure = SimulationConfig { mechanism_switch: None, ..config.clone() };
+    pure.validate()?;
+
+    let run = |mechanism: LiquidationMechanism, policy: Option<MechanismSwitch>| -> Result<SwitchingPoint> {
+        let switching = SimulationConfig { mechanism_switch: policy, ..pure.clone() };
+        switching.validate()?;
+        Ok(SwitchingPoint::from_results(mechanism, policy, &run_cascade_simulation(mechanism, scenario, runs, &switching)))
+    };
+    let traditional = run(LiquidationMechanism::Traditional, None)?;
+    let keeper_pool = run(pool, None)?;
+    let hybrids = policies.iter().map(|&policy| run(pool, Some(policy))).collect::<Result<Vec<_>>>()?;
+
+    Ok(SwitchingReport { split, scenario, runs, traditional, keeper_pool, hybrids })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::ConservationReport;
+    use crate::cascade::{AuctionDiscount, CascadeSimulationBuilder, Keeper, CDP};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_unreachable_threshold_matches_pure_pool() {
+        let plain = SimulationConfig { seed: Some(8), ..Default::default() };
+        let never = SimulationConfig {
+            mechanism_switch: Some(MechanismSwitch { queue_threshold: usize::MAX, backstop: AUCTION }),
+            ..plain.clone()
+        };
+        let mechanism = LiquidationMechanism::keeper_pool();
+        let a = run_cascade_simulation(mechanism, PriceScenario::VolatileCrash, 4, &plain);
+        let b = run_cascade_simulation(mechanism, PriceScenario::VolatileCrash, 4, &never);
+
+        for (a, b) in a.iter().zip(&b) {
+            assert_eq!(a.total_liquidations, b.total_liquidations);
+            assert_eq!(a.bad_debt, b.bad_debt);
+            assert_eq!(b.backstop_blocks, 0);
+        }
+    }
+
+    #[test]
+    fn test_auction_clears_thin_equity() {
+        // $300 of equity: $39 at 13% misses the $50 Traditional threshold;
+        // the auction's 5% + 2%/block reaches 17% ($51) after six blocks
+        let run = |mechanism_switch: Option<MechanismSwitch>| {
+            let config = SimulationConfig { mechanism_switch, max_blocks: 10, ..Default::default() };
+            let mut rng = StdRng::seed_from_u64(0);
+            CascadeSimulationBuilder::new()
+                .config(config)
+                .mechanism(LiquidationMechanism::Traditional)
+                .cdps(vec![CDP::from_position(0, 5.0, 9_700.0)])
+                .keepers(vec![Keeper::new(0, 50_000.0, 0.5)])
+                .price_path(vec![2000.0; 11])
+                .build(&mut rng)
+                .unwrap()
+                .run(&mut rng)
+        };
+        let pure = run(None);
+        let auction = run(Some(MechanismSwitch { queue_threshold: 0, backstop: AUCTION }));
+
+        assert_eq!(pure.total_liquidations, 0);
+        assert_eq!(auction.total_liquidations, 1);
+        assert_eq!(auction.backstop_liquidations, 1);
+        assert_eq!(auction.avg_liquidation_delay, 6.0);
+        assert!((auction.ledger.penalties - 300.0 * 0.17).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_auction_discount_returns_the_surplus() {
+        // Same thin-equity CDP: the auction clears at 17% after six blocks
+        let run = |auction_discount: Option<AuctionDiscount>, keepers: usize, seed: u64| {
+            let config = SimulationConfig {
+                mechanism_switch: Some(MechanismSwitch { queue_threshold: 0, backstop: AUCTION }),
+                auction_discount,
+                max_blocks: 10,
+                ..Default::default()
+            };
+            let mut rng = StdRng::seed_from_u64(seed);
+            CascadeSimulationBuilder::new()
+                .config(config)
+                .mechanism(LiquidationMechanism::Traditional)
+                .cdps(vec![CDP::from_position(0, 5.0, 9_700.0)])
+                .keepers((0..keepers).map(|i| Keeper::new(i, 50_000.0, 0.5)).collect())
+                .price_path(vec![2000.0; 11])
+                .build(&mut rng)
+                .unwrap()
+                .run(&mut rng)
+        };
+        let fixed = run(None, 1, 0);
+        let offered = run(Some(AuctionDiscount { dispersion: 0.0, protocol_share: 0.5 }), 1, 0);
+
+        // Without the discount model the whole $300 of equity is seized
+        assert!((fixed.borrower_loss - 300.0).abs() < 1e-6);
+        assert!((offered.borrower_loss - 51.0).abs() < 1e-6);
+        assert!((offered.protocol_revenue - 25.5).abs() < 1e-6);
+        assert!(ConservationReport::of(&offered).is_consistent());
+
+        // More bidders clear at thinner discounts
+        let mean_loss = |keepers: usize| {
+            let auction = AuctionDiscount { dispersion: 0.5, protocol_share: 0.3 };
+            (0..20).map(|seed| run(Some(auction), keepers, seed).borrower_loss).sum::<f64>() / 20.0
+        };
+        let (alone, crowded) = (mean_loss(1), mean_loss(8));
+        assert!(crowded < alone && alone < 51.0, "{} vs {}", crowded, alone);
+    }
+
+    #[test]
+    fn test_report_covers_pure_and_hybrids() {
+        let config = SimulationConfig { seed: Some(4), ..Default::default() };
+        let report = compare_switching(0.7, PriceScenario::GradualDecline, &SWITCH_POLICIES, 6, &config).unwrap();
+        let (shallow, deep) = (&report.hybrids[2], &report.hybrids[3]);
+
+        assert_eq!(report.hybrids.len(), SWITCH_POLICIES.len());
+        assert_eq!(report.traditional.avg_backstop_blocks, 0.0);
+        // A lower threshold engages the backstop sooner, and the backstop retains no protocol share
+        assert!(shallow.avg_backstop_blocks > deep.avg_backstop_blocks);
+        assert!(shallow.avg_protocol_revenue < deep.avg_protocol_revenue);
+        assert!(deep.avg_protocol_revenue < report.keeper_pool.avg_protocol_revenue);
+        assert!(compare_switching(1.5, PriceScenario::FlashCrash, &SWITCH_POLICIES, 1, &config).is_err());
+    }
+}
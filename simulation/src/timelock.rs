@@ -0,0 +1,198 @@
+//! Governance Timelocks
+//!
+//! The cost of the delay between detecting a dangerous configuration and
+//! governance being able to change it (see `cascade::GovernanceTimelock`).
+//! A fix that would have contained the cascade executes only once the
+//! timelock runs out, and the bad debt that piles up in between is the
+//! price of the timelock's protection against hostile proposals.
+//!
+//! ## Method
+//! - Runs the mechanism with its parameters fixed all run, with the change
+//!   executing the block it is detected, and behind each timelock length;
+//!   every run sees the same seeds when `config.seed` is set, and the
+//!   timelock draws nothing from the RNG
+//! - Detection is the queue-depth early warning: more than
+//!   `queue_threshold` CDPs liquidatable at the start of a block
+//! - Window bad debt is what accrued from detection to execution (to the
+//!   end of the run when the change never executes); the extra bad debt
+//!   compares each run's end state against executing immediately
+
+use crate::cascade::{
+    run_cascade_simulation, CascadeResult, GovernanceTimelock, LiquidationMechanism, PriceScenario, SimulationConfig,
+};
+use crate::error::Result;
+
+/// Timelock lengths compared by default, in blocks.
+pub const TIMELOCK_DELAYS: [usize; 5] = [5, 10, 25, 50, 100];
+
+#[derive(Debug, Clone)]
+pub struct TimelockPoint {
+    pub delay_blocks: Option<usize>, // None = parameters fixed all run
+    pub avg_bad_debt: f64,
+    pub bad_debt_frequency: f64,
+    pub detection_rate: f64,      // Runs in which the configuration was flagged
+    pub avg_detected_at: f64,     // Mean detection block over flagged runs
+    pub avg_window_bad_debt: f64, // Mean over all runs
+}
+
+impl TimelockPoint {
+    fn from_results(delay_blocks: Option<usize>, results: &[CascadeResult]) -> Self {
+        let n = results.len().max(1) as f64;
+        let detected: Vec<usize> = results.iter().filter_map(|r| r.governance_detected_at).collect();
+        Self {
+            delay_blocks,
+            avg_bad_debt: results.iter().map(|r| r.bad_debt).sum::<f64>() / n,
+            bad_debt_frequency: results.iter().filter(|r| r.bad_debt > 0.0).count() as f64 / n,
+            detection_rate: detected.len() as f64 / n,
+            avg_detected_at: detected.iter().sum::<usize>() as f64 / detected.len().max(1) as f64,
+            avg_window_bad_debt: results.iter().map(|r| r.governance_window_bad_debt).sum::<f64>() / n,
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self.delay_blocks {
+            None => "No change".to_string(),
+            Some(0) => "Immediate".to_string(),
+            Some(delay) => format!("{} block timelock", delay),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TimelockReport {
+    pub mechanism: LiquidationMechanism,
+    pub scenario: PriceScenario,
+    pub runs: usize,
+    pub change: GovernanceTimelock, // Detection threshold and parameters; `delay_blocks` varies
+    pub unchanged: TimelockPoint,
+    pub immediate: TimelockPoint,
+    pub delays: Vec<TimelockPoint>,
+}
+
+impl TimelockReport {
+    /// Bad debt `point` leaves beyond executing the change immediately.
+    pub fn extra_bad_debt(&self, point: &TimelockPoint) -> f64 {
+        point.avg_bad_debt - self.immediate.avg_bad_debt
+    }
+
+    /// Extra bad debt per block of timelock, fitted through the immediate
+    /// change; None without delays.
+    pub fn cost_per_block(&self) -> Option<f64> {
+        let (xy, xx) = self.delays.iter().fold((0.0, 0.0), |(xy, xx), point| {
+            let delay = point.delay_blocks.unwrap_or(0) as f64;
+            (xy + delay * self.extra_bad_debt(point), xx + delay * delay)
+        });
+        (xx > 0.0).then(|| xy / xx)
+    }
+
+    pub fn print(&self) {
+        println!("| Policy                  | Bad Debt | P(Bad Debt) | Detected | At Block | Window Bad Debt | Extra vs Immediate |");
+        println!("|-------------------------|----------|-------------|----------|----------|-----------------|--------------------|");
+        for point in [&self.unchanged, &self.immediate].into_iter().chain(&self.delays) {
+            let (detected, at_block, window) = match point.delay_blocks {
+                None => ("-".to_string(), "-".to_string(), "-".to_string()),
+                Some(_) => (
+                    format!("{:.1}%", point.detection_rate * 100.0),
+                    format!("{:.1}", point.avg_detected_at),
+                    format!("${:.0}", point.avg_window_bad_debt),
+                ),
+            };
+            let extra = match point.delay_blocks {
+                Some(delay) if delay > 0 => format!("${:+.0}", self.extra_bad_debt(point)),
+                _ => "-".to_string(),
+            };
+            println!(
+                "| {:23} | ${:7.0} | {:10.1}% | {:>8} | {:>8} | {:>15} | {:>18} |",
+                point.label(),
+                point.avg_bad_debt,
+                point.bad_debt_frequency * 100.0,
+                detected,
+                at_block,
+                window,
+                extra,
+            );
+        }
+        if self.immediate.detection_rate == 0.0 {
+            println!("  The queue never exceeded {} CDPs; nothing was flagged", self.change.queue_threshold);
+            return;
+        }
+        if let (Some(shortest), Some(longest)) = (self.delays.first(), self.delays.last()) {
+            println!(
+                "  Bad debt accrued while waiting: ${:.0} at {} blocks, ${:.0} at {}",
+                shortest.avg_window_bad_debt,
+                shortest.delay_blocks.unwrap_or(0),
+                longest.avg_window_bad_debt,
+                longest.delay_blocks.unwrap_or(0),
+            );
+        }
+        if self.immediate.avg_bad_debt >= self.unchanged.avg_bad_debt {
+            println!("  The change does not reduce bad debt even executed immediately");
+        } else if let Some(cost) = self.cost_per_block() {
+            println!("  Each block of timelock costs ${:.0} of bad debt on average", cost);
+        }
+    }
+}
+
+/// Runs `mechanism` with its parameters fixed, with `change` executing
+/// immediately, and behind each timelock length in `delays`.
+pub fn compare_timelocks(
+    mechanism: LiquidationMechanism,
+    scenario: PriceScenario,
+    change: GovernanceTimelock,
+    delays: &[usize],
+    runs: usize,
+    config: &SimulationConfig,
+) -> Result<TimelockReport> {
+    mechanism.validate()?;
+    let run = |delay_blocks: Option<usize>| -> Result<TimelockPoint> {
+        let governance_timelock = delay_blocks.map(|delay_blocks| GovernanceTimelock { delay_blocks, ..change });
+        let config = SimulationConfig { governance_timelock, ..config.clone() };
+        config.validate()?;
+        Ok(TimelockPoint::from_results(delay_blocks, &run_cascade_simulation(mechanism, scenario, runs, &config)))
+    };
+    let unchanged = run(None)?;
+    let immediate = run(Some(0))?;
+    let delays = delays.iter().filter(|&&delay| delay > 0).map(|&delay| run(Some(delay))).collect::<Result<Vec<_>>>()?;
+
+    Ok(TimelockReport { mechanism, scenario, runs, change, unchanged, immediate, delays })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unreachable_threshold_changes_nothing() {
+        let plain = SimulationConfig { seed: Some(8), ..Default::default() };
+        let never = SimulationConfig {
+            governance_timelock: Some(GovernanceTimelock { queue_threshold: usize::MAX, ..Default::default() }),
+            ..plain.clone()
+        };
+        let mechanism = LiquidationMechanism::Traditional;
+        let a = run_cascade_simulation(mechanism, PriceScenario::FlashCrash, 4, &plain);
+        let b = run_cascade_simulation(mechanism, PriceScenario::FlashCrash, 4, &never);
+
+        for (a, b) in a.iter().zip(&b) {
+            assert_eq!(a.bad_debt, b.bad_debt);
+            assert_eq!(b.governance_detected_at, None);
+            assert_eq!(b.governance_window_bad_debt, 0.0);
+        }
+    }
+
+    #[test]
+    fn test_longer_timelocks_leave_the_change_later() {
+        // Raising the penalty to 100% makes every CDP worth liquidating
+        let config = SimulationConfig { seed: Some(3), ..Default::default() };
+        let change = GovernanceTimelock { queue_threshold: 0, liquidation_penalty: Some(1.0), ..Default::default() };
+        let report =
+            compare_timelocks(LiquidationMechanism::Traditional, PriceScenario::BlackSwan, change, &[10, 1000], 20, &config)
+                .unwrap();
+
+        assert_eq!(report.immediate.detection_rate, 1.0);
+        assert_eq!(report.immediate.avg_window_bad_debt, 0.0);
+        // A timelock outlasting every run leaves the parameters as configured
+        assert_eq!(report.delays[1].avg_bad_debt, report.unchanged.avg_bad_debt);
+        assert!(report.delays[1].avg_window_bad_debt >= report.delays[0].avg_window_bad_debt);
+        assert!(report.extra_bad_debt(&report.delays[1]) >= 0.0);
+    }
+}
@@ -0,0 +1,82 @@
+//! Golden outputs of the exporters downstream dashboards and papers parse:
+//! campaign JSON, distribution and sweep CSV, and the Markdown and LaTeX
+//! summary tables. A format change fails here until its snapshot is
+//! reviewed and accepted (`cargo insta review`, or `INSTA_UPDATE=always`).
+//!
+//! Every fixture is a seeded campaign on a small book, so the snapshots pin
+//! the numbers as well as the layout. Wall times are redacted.
+
+use fair_simulation::cascade::{
+    aggregate_results, run_cascade_simulation, LiquidationMechanism, PriceScenario, SimulationConfig,
+};
+use fair_simulation::distribution::{self, summarize_cells, DistributionConfig};
+use fair_simulation::monte_carlo::{run_monte_carlo_with_config, PriceModel};
+use fair_simulation::report::table::{cascade_summary, monte_carlo_summary};
+use fair_simulation::results::CascadeCell;
+use fair_simulation::soak::{self, run_soak, SoakConfig};
+use fair_simulation::sweep::{self, run_sweep, SweepAxis};
+
+fn config() -> SimulationConfig {
+    SimulationConfig { num_cdps: 60, seed: Some(17), ..Default::default() }
+}
+
+fn cells() -> Vec<CascadeCell> {
+    let config = config();
+    [PriceScenario::FlashCrash, PriceScenario::BlackSwan]
+        .into_iter()
+        .flat_map(|scenario| LiquidationMechanism::all().into_iter().map(move |mechanism| (scenario, mechanism)))
+        .map(|(scenario, mechanism)| {
+            let results = run_cascade_simulation(mechanism, scenario, 4, &config);
+            CascadeCell { label: scenario.name().to_string(), aggregate: aggregate_results(&results), results }
+        })
+        .collect()
+}
+
+fn csv(write: impl FnOnce(&mut Vec<u8>) -> fair_simulation::error::Result<()>) -> String {
+    let mut out = Vec::new();
+    write(&mut out).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn test_cascade_cells_json() {
+    insta::assert_json_snapshot!(cells(), {
+        "[].aggregate.compute.wall_secs" => "[wall]",
+        "[].aggregate.compute.max_wall_secs" => "[wall]",
+        "[].results[].compute.wall_secs" => "[wall]",
+    });
+}
+
+#[test]
+fn test_summary_tables() {
+    let cells = cells();
+    insta::assert_snapshot!("cascade_summary_markdown", cascade_summary(&cells).markdown());
+    insta::assert_snapshot!("cascade_summary_latex", cascade_summary(&cells).latex());
+
+    let tail: Vec<_> = LiquidationMechanism::all()
+        .into_iter()
+        .map(|mechanism| run_monte_carlo_with_config(PriceModel::GBM, mechanism, 8, &config()))
+        .collect();
+    insta::assert_snapshot!("monte_carlo_summary_markdown", monte_carlo_summary(&tail).markdown());
+    insta::assert_snapshot!("monte_carlo_summary_latex", monte_carlo_summary(&tail).latex());
+}
+
+#[test]
+fn test_distributions_json_and_csv() {
+    let config = DistributionConfig { quantiles: vec![0.05, 0.5, 0.95], bins: 4 };
+    let distributions = summarize_cells(&cells(), &config).unwrap();
+
+    insta::assert_json_snapshot!("distributions_json", distributions);
+    insta::assert_snapshot!("distributions_csv", csv(|out| distribution::write_csv(&distributions, out)));
+}
+
+#[test]
+fn test_sweep_and_soak_csv() {
+    let axes = [SweepAxis { field: "liquidation_penalty".to_string(), values: vec![0.05, 0.15] }];
+    let rows = run_sweep(&axes, &LiquidationMechanism::all(), &[PriceScenario::VolatileCrash], 3, &config()).unwrap();
+    insta::assert_snapshot!("sweep_csv", csv(|out| sweep::write_csv(&rows, out)));
+
+    let days = SoakConfig { days: 6, stress_every: 3, ..Default::default() };
+    let report = run_soak(LiquidationMechanism::keeper_pool(), &days, &config(), 5).unwrap();
+    insta::assert_snapshot!("soak_csv", csv(|out| soak::write_csv(&report, out)));
+}
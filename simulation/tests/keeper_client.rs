@@ -0,0 +1,63 @@
+//! End-to-end: the `keeper-sim-client` binary against a feed from the engine,
+//! live and replayed from a file.
+
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+use fair_simulation::cascade::{LiquidationMechanism, PriceScenario, SimulationConfig};
+use fair_simulation::keeper_client::{load_feed, record_feed, Intent, KeeperClient};
+use fair_simulation::strategy::ThresholdStrategy;
+
+fn client(args: &[&str], stdin: Option<&[u8]>) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_keeper-sim-client"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(stdin.unwrap_or_default()).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn intents(output: &Output) -> Vec<Intent> {
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8_lossy(&output.stdout).lines().map(|line| serde_json::from_str(line).unwrap()).collect()
+}
+
+#[test]
+fn test_live_and_replayed_feeds_agree() {
+    let path = std::env::temp_dir().join(format!("keeper-sim-client-{}.jsonl", std::process::id()));
+    let path = path.to_str().unwrap();
+    let strategy = ["--min-profit", "0", "--gas-priority", "0.5"];
+
+    let live = client(&[&["--mechanism", "traditional", "--seed", "7", "--record", path], &strategy[..]].concat(), None);
+    let replayed = client(&[&["--feed", path], &strategy[..]].concat(), None);
+    let piped = client(&[&["--feed", "-"], &strategy[..]].concat(), Some(&std::fs::read(path).unwrap()));
+    let feed = load_feed(path).unwrap();
+    std::fs::remove_file(path).ok();
+
+    let live = intents(&live);
+    assert!(!live.is_empty());
+    assert_eq!(intents(&replayed), live);
+    assert_eq!(intents(&piped), live);
+    assert_eq!(
+        feed,
+        record_feed(LiquidationMechanism::Traditional, PriceScenario::FlashCrash, &SimulationConfig::default(), 7).unwrap(),
+    );
+}
+
+#[test]
+fn test_binary_matches_library_client() {
+    let feed = record_feed(LiquidationMechanism::keeper_pool(), PriceScenario::VolatileCrash, &SimulationConfig::default(), 3).unwrap();
+    let mut library = KeeperClient::new(ThresholdStrategy { min_profit: 100.0, gas_priority: 0.9 });
+    let expected: Vec<Intent> = feed.iter().flat_map(|block| library.on_block(block)).collect();
+
+    let output = client(&["--scenario", "volatile", "--seed", "3", "--min-profit", "100"], None);
+    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+    assert_eq!(intents(&output), expected);
+    assert!(stderr.contains(&format!("{} intents", library.summary().intents)));
+
+    let rejected = client(&["--feed", "feed.jsonl", "--seed", "3"], None);
+    assert!(!rejected.status.success());
+}